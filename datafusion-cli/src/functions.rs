@@ -21,7 +21,7 @@ use datafusion_common::instant::Instant;
 use std::fmt;
 use std::fs::File;
 use std::str::FromStr;
-use std::sync::Arc;
+use std::sync::{Arc, Weak};
 
 use arrow::array::{
     DurationMillisecondArray, GenericListArray, Int64Array, StringArray, StructArray,
@@ -36,12 +36,15 @@ use datafusion::common::{Column, plan_err};
 use datafusion::datasource::TableProvider;
 use datafusion::datasource::memory::MemorySourceConfig;
 use datafusion::error::Result;
-use datafusion::execution::cache::cache_manager::CacheManager;
+use datafusion::execution::cache::CacheAccessor;
+use datafusion::execution::cache::cache_manager::{ByteRangeCache, CacheManager};
+use datafusion::execution::context::SessionState;
 use datafusion::logical_expr::Expr;
 use datafusion::physical_plan::ExecutionPlan;
 use datafusion::scalar::ScalarValue;
 
 use async_trait::async_trait;
+use parking_lot::RwLock;
 use parquet::basic::ConvertedType;
 use parquet::data_type::{ByteArray, FixedLenByteArray};
 use parquet::file::reader::FileReader;
@@ -869,3 +872,177 @@ impl TableFunctionImpl for ListFilesCacheFunc {
         Ok(Arc::new(list_files_cache))
     }
 }
+
+/// BYTE_RANGE_CACHE and BYTE_RANGE_CACHE_STATS table functions
+#[derive(Debug)]
+struct ByteRangeCacheTable {
+    schema: SchemaRef,
+    batch: RecordBatch,
+}
+
+#[async_trait]
+impl TableProvider for ByteRangeCacheTable {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn table_type(&self) -> datafusion::logical_expr::TableType {
+        datafusion::logical_expr::TableType::Base
+    }
+
+    async fn scan(
+        &self,
+        _state: &dyn Session,
+        projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        _limit: Option<usize>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(MemorySourceConfig::try_new_exec(
+            &[vec![self.batch.clone()]],
+            TableProvider::schema(self),
+            projection.cloned(),
+        )?)
+    }
+}
+
+/// Returns the byte range cache of the current runtime environment of the
+/// session, which changes when the cache is enabled with `SET`.
+fn current_byte_range_cache(
+    state: &Weak<RwLock<SessionState>>,
+) -> Option<Arc<dyn ByteRangeCache>> {
+    let state = state.upgrade()?;
+    let state = state.read();
+    state.runtime_env().cache_manager.get_byte_range_cache()
+}
+
+/// Implementation of the `byte_range_cache` table function in datafusion-cli.
+///
+/// This function returns one row per byte range cached from an object store,
+/// along with the tier holding it and the number of times it was read.
+#[derive(Debug)]
+pub struct ByteRangeCacheFunc {
+    state: Weak<RwLock<SessionState>>,
+}
+
+impl ByteRangeCacheFunc {
+    pub fn new(state: Weak<RwLock<SessionState>>) -> Self {
+        Self { state }
+    }
+}
+
+impl TableFunctionImpl for ByteRangeCacheFunc {
+    fn call_with_args(&self, args: TableFunctionArgs) -> Result<Arc<dyn TableProvider>> {
+        let exprs = args.exprs();
+        if !exprs.is_empty() {
+            return plan_err!("byte_range_cache should have no arguments");
+        }
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("store_url", DataType::Utf8, false),
+            Field::new("path", DataType::Utf8, false),
+            Field::new("version", DataType::Utf8, false),
+            Field::new("range_start", DataType::UInt64, false),
+            Field::new("range_end", DataType::UInt64, false),
+            Field::new("size_bytes", DataType::UInt64, false),
+            Field::new("tier", DataType::Utf8, false),
+            Field::new("hits", DataType::UInt64, false),
+        ]));
+
+        let mut store_url_arr = vec![];
+        let mut path_arr = vec![];
+        let mut version_arr = vec![];
+        let mut range_start_arr = vec![];
+        let mut range_end_arr = vec![];
+        let mut size_bytes_arr = vec![];
+        let mut tier_arr = vec![];
+        let mut hits_arr = vec![];
+
+        if let Some(byte_range_cache) = current_byte_range_cache(&self.state) {
+            for (key, entry) in byte_range_cache.list_entries() {
+                store_url_arr.push(key.store_url);
+                path_arr.push(key.location.to_string());
+                version_arr.push(key.version);
+                range_start_arr.push(key.range.start);
+                range_end_arr.push(key.range.end);
+                size_bytes_arr.push(entry.size_bytes as u64);
+                tier_arr.push(entry.tier.to_string());
+                hits_arr.push(entry.hits as u64);
+            }
+        }
+
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(StringArray::from(store_url_arr)),
+                Arc::new(StringArray::from(path_arr)),
+                Arc::new(StringArray::from(version_arr)),
+                Arc::new(UInt64Array::from(range_start_arr)),
+                Arc::new(UInt64Array::from(range_end_arr)),
+                Arc::new(UInt64Array::from(size_bytes_arr)),
+                Arc::new(StringArray::from(tier_arr)),
+                Arc::new(UInt64Array::from(hits_arr)),
+            ],
+        )?;
+
+        Ok(Arc::new(ByteRangeCacheTable { schema, batch }))
+    }
+}
+
+/// Implementation of the `byte_range_cache_stats` table function in datafusion-cli.
+///
+/// This function returns a single row with the hit and miss counts and the size
+/// of the byte range cache, or no rows if the cache is disabled.
+#[derive(Debug)]
+pub struct ByteRangeCacheStatsFunc {
+    state: Weak<RwLock<SessionState>>,
+}
+
+impl ByteRangeCacheStatsFunc {
+    pub fn new(state: Weak<RwLock<SessionState>>) -> Self {
+        Self { state }
+    }
+}
+
+impl TableFunctionImpl for ByteRangeCacheStatsFunc {
+    fn call_with_args(&self, args: TableFunctionArgs) -> Result<Arc<dyn TableProvider>> {
+        let exprs = args.exprs();
+        if !exprs.is_empty() {
+            return plan_err!("byte_range_cache_stats should have no arguments");
+        }
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("entries", DataType::UInt64, false),
+            Field::new("hits", DataType::UInt64, false),
+            Field::new("misses", DataType::UInt64, false),
+            Field::new("memory_limit_bytes", DataType::UInt64, false),
+            Field::new("memory_used_bytes", DataType::UInt64, false),
+            Field::new("disk_used_bytes", DataType::UInt64, false),
+        ]));
+
+        let mut columns = vec![vec![]; schema.fields().len()];
+        if let Some(byte_range_cache) = current_byte_range_cache(&self.state) {
+            let metrics = byte_range_cache.metrics();
+            let values = [
+                byte_range_cache.len(),
+                metrics.hits,
+                metrics.misses,
+                byte_range_cache.cache_limit(),
+                metrics.memory_used,
+                metrics.disk_used,
+            ];
+            for (column, value) in columns.iter_mut().zip(values) {
+                column.push(value as u64);
+            }
+        }
+
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            columns
+                .into_iter()
+                .map(|column| Arc::new(UInt64Array::from(column)) as _)
+                .collect(),
+        )?;
+
+        Ok(Arc::new(ByteRangeCacheTable { schema, batch }))
+    }
+}
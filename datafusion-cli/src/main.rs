@@ -32,7 +32,8 @@ use datafusion::logical_expr::ExplainFormat;
 use datafusion::prelude::SessionContext;
 use datafusion_cli::catalog::DynamicObjectStoreCatalog;
 use datafusion_cli::functions::{
-    ListFilesCacheFunc, MetadataCacheFunc, ParquetMetadataFunc, StatisticsCacheFunc,
+    ByteRangeCacheFunc, ByteRangeCacheStatsFunc, ListFilesCacheFunc, MetadataCacheFunc,
+    ParquetMetadataFunc, StatisticsCacheFunc,
 };
 use datafusion_cli::object_storage::instrumented::{
     InstrumentedObjectStoreMode, InstrumentedObjectStoreRegistry,
@@ -260,6 +261,17 @@ async fn main_inner() -> Result<()> {
         )),
     );
 
+    // register `byte_range_cache` and `byte_range_cache_stats` table functions to get the
+    // contents and the hit rate of the byte range cache
+    ctx.register_udtf(
+        "byte_range_cache",
+        Arc::new(ByteRangeCacheFunc::new(ctx.state_weak_ref())),
+    );
+    ctx.register_udtf(
+        "byte_range_cache_stats",
+        Arc::new(ByteRangeCacheStatsFunc::new(ctx.state_weak_ref())),
+    );
+
     let mut print_options = PrintOptions {
         format: args.format,
         quiet: args.quiet,
@@ -848,4 +860,66 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_byte_range_cache() -> Result<(), DataFusionError> {
+        let ctx = SessionContext::new();
+        ctx.register_udtf(
+            "byte_range_cache",
+            Arc::new(ByteRangeCacheFunc::new(ctx.state_weak_ref())),
+        );
+        ctx.register_udtf(
+            "byte_range_cache_stats",
+            Arc::new(ByteRangeCacheStatsFunc::new(ctx.state_weak_ref())),
+        );
+
+        // the cache is disabled by default
+        let sql = "SELECT * FROM byte_range_cache_stats()";
+        let rbs = ctx.sql(sql).await?.collect().await?;
+        assert_snapshot!(batches_to_string(&rbs),@r"
+        +---------+------+--------+--------------------+-------------------+-----------------+
+        | entries | hits | misses | memory_limit_bytes | memory_used_bytes | disk_used_bytes |
+        +---------+------+--------+--------------------+-------------------+-----------------+
+        +---------+------+--------+--------------------+-------------------+-----------------+
+        ");
+
+        ctx.sql("SET datafusion.runtime.byte_range_cache_limit = '1M'")
+            .await?
+            .collect()
+            .await?;
+        ctx.register_parquet(
+            "alltypes_plain",
+            "../parquet-testing/data/alltypes_plain.parquet",
+            ParquetReadOptions::new(),
+        )
+        .await?;
+        for _ in 0..2 {
+            ctx.sql("select * from alltypes_plain")
+                .await?
+                .collect()
+                .await?;
+        }
+
+        let sql = "SELECT entries > 0 AS has_entries, hits > 0 AS has_hits, misses > 0 AS has_misses, memory_limit_bytes, disk_used_bytes FROM byte_range_cache_stats()";
+        let rbs = ctx.sql(sql).await?.collect().await?;
+        assert_snapshot!(batches_to_string(&rbs),@r"
+        +-------------+----------+------------+--------------------+-----------------+
+        | has_entries | has_hits | has_misses | memory_limit_bytes | disk_used_bytes |
+        +-------------+----------+------------+--------------------+-----------------+
+        | true        | true     | true       | 1048576            | 0               |
+        +-------------+----------+------------+--------------------+-----------------+
+        ");
+
+        let sql = "SELECT DISTINCT split_part(path, '/', -1) as filename, tier FROM byte_range_cache()";
+        let rbs = ctx.sql(sql).await?.collect().await?;
+        assert_snapshot!(batches_to_string(&rbs),@r"
+        +------------------------+--------+
+        | filename               | tier   |
+        +------------------------+--------+
+        | alltypes_plain.parquet | memory |
+        +------------------------+--------+
+        ");
+
+        Ok(())
+    }
 }
@@ -20,7 +20,11 @@
 //! This example demonstrates implementing SQL `PIVOT` and `UNPIVOT` operations
 //! using a custom [`RelationPlanner`]. Unlike the other examples that create
 //! custom logical/physical nodes, this example shows how to **rewrite** SQL
-//! constructs into equivalent standard SQL operations:
+//! constructs into equivalent standard SQL operations.
+//!
+//! DataFusion also plans `PIVOT` and `UNPIVOT` natively; registered relation
+//! planners run first, so this planner takes precedence over the built-in
+//! planning.
 //!
 //! ## Supported Syntax
 //!
@@ -20,6 +20,9 @@
 //! This example demonstrates implementing SQL `TABLESAMPLE` support using
 //! DataFusion's extensibility APIs.
 //!
+//! DataFusion also plans `TABLESAMPLE` natively; registered relation planners
+//! run first, so this planner takes precedence over the built-in planning.
+//!
 //! This is a working `TABLESAMPLE` implementation that can serve as a starting
 //! point for your own projects. It also works as a template for adding other
 //! custom SQL operators, covering the full pipeline from parsing to execution.
@@ -20,6 +20,8 @@
 use std::mem;
 use std::sync::Arc;
 
+use crate::partition_path::{HivePartitionPathParser, PartitionPathParser};
+
 use datafusion_catalog::Session;
 use datafusion_common::{HashMap, Result, ScalarValue, assert_or_internal_err};
 use datafusion_datasource::ListingTableUrl;
@@ -286,40 +288,45 @@ fn populate_partition_values<'a>(
     }
 }
 
+/// Returns the common prefix of the [Hive Partitioning] paths of the
+/// partitions selected by `filters`, if any
+///
+/// [Hive Partitioning]: HivePartitionPathParser
 pub fn evaluate_partition_prefix<'a>(
     partition_cols: &'a [(String, DataType)],
     filters: &'a [Expr],
+) -> Option<Path> {
+    partition_prefix(partition_cols, filters, &HivePartitionPathParser::new())
+}
+
+fn partition_prefix(
+    partition_cols: &[(String, DataType)],
+    filters: &[Expr],
+    partition_path_parser: &dyn PartitionPathParser,
 ) -> Option<Path> {
     let mut partition_values = HashMap::new();
     for filter in filters {
         populate_partition_values(&mut partition_values, filter);
     }
 
-    if partition_values.is_empty() {
+    // if a partition only has a single literal value, then it can be added to the
+    // prefix
+    let single_values = partition_values
+        .into_iter()
+        .filter_map(|(col, value)| match value {
+            PartitionValue::Single(val) => Some((col, val)),
+            PartitionValue::Multi => None,
+        })
+        .collect::<std::collections::HashMap<_, _>>();
+    if single_values.is_empty() {
         return None;
     }
 
-    let mut parts = vec![];
-    for (p, _) in partition_cols {
-        match partition_values.get(p.as_str()) {
-            Some(PartitionValue::Single(val)) => {
-                // if a partition only has a single literal value, then it can be added to the
-                // prefix
-                parts.push(format!("{p}={val}"));
-            }
-            _ => {
-                // break on the first unconstrainted partition to create a common prefix
-                // for all covered partitions.
-                break;
-            }
-        }
-    }
-
-    if parts.is_empty() {
-        None
-    } else {
-        Some(Path::from_iter(parts))
-    }
+    let partition_cols = partition_cols
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<_>>();
+    partition_path_parser.prefix(&partition_cols, &single_values)
 }
 
 fn filter_partitions(
@@ -357,26 +364,43 @@ fn filter_partitions(
 
 /// Returns `Ok(None)` when the file is not inside a valid partition path
 /// (e.g. a stale file in the table root directory). Such files are skipped
-/// because hive-style partition values are never null and there is no valid
-/// value to assign for non-partitioned files.
+/// because there is no valid value to assign for non-partitioned files.
+///
+/// Partition values missing from the path are null, if allowed by
+/// `partition_path_parser`.
 fn try_into_partitioned_file(
     object_meta: ObjectMeta,
     partition_cols: &[(String, DataType)],
     table_path: &ListingTableUrl,
+    partition_path_parser: &dyn PartitionPathParser,
 ) -> Result<Option<PartitionedFile>> {
-    let cols = partition_cols.iter().map(|(name, _)| name.as_str());
-    let parsed = parse_partitions_for_path(table_path, &object_meta.location, cols);
+    let Some(mut segments) = table_path
+        .strip_prefix(&object_meta.location)
+        .map(|segments| segments.collect::<Vec<_>>())
+    else {
+        return Ok(None);
+    };
+    // the last segment is the file itself
+    segments.pop();
+    let cols = partition_cols
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<_>>();
 
-    let Some(parsed) = parsed else {
-        // parse_partitions_for_path already logs a debug message
+    let Some(parsed) = partition_path_parser.parse(&segments, &cols) else {
+        debug!(
+            "Ignoring file: file_path='{}', table_path='{table_path}', partition_cols={cols:?}",
+            object_meta.location
+        );
         return Ok(None);
     };
 
     let partition_values = parsed
         .into_iter()
         .zip(partition_cols)
-        .map(|(parsed, (_, datatype))| {
-            ScalarValue::try_from_string(parsed.to_string(), datatype)
+        .map(|(parsed, (_, datatype))| match parsed {
+            Some(parsed) => ScalarValue::try_from_string(parsed.to_string(), datatype),
+            None => ScalarValue::try_from(datatype),
         })
         .collect::<Result<Vec<_>>>()?;
 
@@ -390,6 +414,9 @@ fn try_into_partitioned_file(
 /// that belong to irrelevant partitions using `filters` expressions.
 /// `filters` should only contain expressions that can be evaluated
 /// using only the partition columns.
+///
+/// The partition values of the files are parsed from their paths by
+/// `partition_path_parser`.
 pub async fn pruned_partition_list<'a>(
     ctx: &'a dyn Session,
     store: &'a dyn ObjectStore,
@@ -397,9 +424,10 @@ pub async fn pruned_partition_list<'a>(
     filters: &'a [Expr],
     file_extension: &'a str,
     partition_cols: &'a [(String, DataType)],
+    partition_path_parser: &'a dyn PartitionPathParser,
 ) -> Result<BoxStream<'a, Result<PartitionedFile>>> {
     let prefix = if !partition_cols.is_empty() {
-        evaluate_partition_prefix(partition_cols, filters)
+        partition_prefix(partition_cols, filters, partition_path_parser)
     } else {
         None
     };
@@ -433,6 +461,7 @@ pub async fn pruned_partition_list<'a>(
                     object_meta,
                     partition_cols,
                     table_path,
+                    partition_path_parser,
                 ))
             })
             .try_filter_map(move |pf| {
@@ -487,8 +516,13 @@ mod tests {
     use std::ops::Not;
 
     use super::*;
+    use crate::partition_path::TemplatePartitionPathParser;
     use datafusion_expr::{case, col};
 
+    fn parser() -> HivePartitionPathParser {
+        HivePartitionPathParser::new()
+    }
+
     #[test]
     fn test_split_files() {
         let new_partitioned_file = |path: &str| PartitionedFile::new(path.to_owned(), 10);
@@ -612,7 +646,8 @@ mod tests {
         };
 
         let result =
-            try_into_partitioned_file(meta, &partition_cols, &table_path).unwrap();
+            try_into_partitioned_file(meta, &partition_cols, &table_path, &parser())
+                .unwrap();
         assert!(result.is_some());
         let pf = result.unwrap();
         assert_eq!(pf.partition_values.len(), 1);
@@ -638,7 +673,8 @@ mod tests {
         };
 
         let result =
-            try_into_partitioned_file(meta, &partition_cols, &table_path).unwrap();
+            try_into_partitioned_file(meta, &partition_cols, &table_path, &parser())
+                .unwrap();
         assert!(
             result.is_none(),
             "Files outside partition structure should be skipped"
@@ -659,7 +695,8 @@ mod tests {
         };
 
         let result =
-            try_into_partitioned_file(meta, &partition_cols, &table_path).unwrap();
+            try_into_partitioned_file(meta, &partition_cols, &table_path, &parser())
+                .unwrap();
         assert!(
             result.is_none(),
             "Files with wrong partition column name should be skipped"
@@ -682,7 +719,8 @@ mod tests {
         };
 
         let result =
-            try_into_partitioned_file(meta, &partition_cols, &table_path).unwrap();
+            try_into_partitioned_file(meta, &partition_cols, &table_path, &parser())
+                .unwrap();
         assert!(result.is_some());
         let pf = result.unwrap();
         assert_eq!(pf.partition_values.len(), 2);
@@ -713,16 +751,83 @@ mod tests {
         };
 
         let result =
-            try_into_partitioned_file(meta, &partition_cols, &table_path).unwrap();
-        // File has year=2024 but no month= directory — parse_partitions_for_path
-        // returns None because the path component "data.parquet" doesn't match
-        // the expected "month=..." pattern.
+            try_into_partitioned_file(meta, &partition_cols, &table_path, &parser())
+                .unwrap();
+        // File has year=2024 but no month= directory — the parser returns None
+        // because missing partition values are not read as nulls by default.
         assert!(
             result.is_none(),
             "Files with incomplete partition structure should be skipped"
         );
     }
 
+    #[test]
+    fn test_try_into_partitioned_file_missing_partition_as_null() {
+        let table_path = ListingTableUrl::parse("file:///bucket/mytable").unwrap();
+        let partition_cols = vec![
+            ("year".to_string(), DataType::Utf8),
+            ("month".to_string(), DataType::Utf8),
+        ];
+        let parser = HivePartitionPathParser::new().with_missing_values_as_null(true);
+        let meta = |location: &str| ObjectMeta {
+            location: Path::from(location),
+            last_modified: chrono::Utc::now(),
+            size: 100,
+            e_tag: None,
+            version: None,
+        };
+
+        let pf = try_into_partitioned_file(
+            meta("bucket/mytable/year=2024/data.parquet"),
+            &partition_cols,
+            &table_path,
+            &parser,
+        )
+        .unwrap()
+        .expect("file with the first partition should be read");
+        assert_eq!(
+            pf.partition_values,
+            vec![ScalarValue::from("2024"), ScalarValue::Utf8(None)]
+        );
+
+        let result = try_into_partitioned_file(
+            meta("bucket/mytable/data.parquet"),
+            &partition_cols,
+            &table_path,
+            &parser,
+        )
+        .unwrap();
+        assert!(
+            result.is_none(),
+            "Files outside partition structure should be skipped"
+        );
+    }
+
+    #[test]
+    fn test_try_into_partitioned_file_template() {
+        let table_path = ListingTableUrl::parse("file:///bucket/mytable").unwrap();
+        let partition_cols = vec![
+            ("year".to_string(), DataType::Int32),
+            ("month".to_string(), DataType::Int32),
+        ];
+        let parser = TemplatePartitionPathParser::try_new("{year}/{month}").unwrap();
+        let meta = ObjectMeta {
+            location: Path::from("bucket/mytable/2024/06/data.parquet"),
+            last_modified: chrono::Utc::now(),
+            size: 100,
+            e_tag: None,
+            version: None,
+        };
+
+        let pf = try_into_partitioned_file(meta, &partition_cols, &table_path, &parser)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            pf.partition_values,
+            vec![ScalarValue::Int32(Some(2024)), ScalarValue::Int32(Some(6))]
+        );
+    }
+
     #[test]
     fn test_expr_applicable_for_cols() {
         assert!(expr_applicable_for_cols(
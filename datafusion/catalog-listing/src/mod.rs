@@ -28,8 +28,12 @@
 mod config;
 pub mod helpers;
 mod options;
+mod partition_path;
 mod table;
 
 pub use config::{ListingTableConfig, SchemaSource};
 pub use options::ListingOptions;
+pub use partition_path::{
+    HivePartitionPathParser, PartitionPathParser, TemplatePartitionPathParser,
+};
 pub use table::{ListFilesResult, ListingTable};
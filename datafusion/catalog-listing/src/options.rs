@@ -15,6 +15,7 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use crate::partition_path::{HivePartitionPathParser, PartitionPathParser};
 use arrow::datatypes::{DataType, SchemaRef};
 use datafusion_catalog::Session;
 use datafusion_common::plan_err;
@@ -61,6 +62,9 @@ pub struct ListingOptions {
     ///       multiple equivalent orderings, the outer `Vec` will have a
     ///       single element.
     pub file_sort_order: Vec<Vec<SortExpr>>,
+    /// Parses the values of the partition columns from the paths of the
+    /// files. See [Self::with_partition_path_parser] for details
+    pub partition_path_parser: Arc<dyn PartitionPathParser>,
 }
 
 impl ListingOptions {
@@ -78,6 +82,7 @@ impl ListingOptions {
             collect_stat: false,
             target_partitions: 1,
             file_sort_order: vec![],
+            partition_path_parser: Arc::new(HivePartitionPathParser::new()),
         }
     }
 
@@ -260,6 +265,45 @@ impl ListingOptions {
         self
     }
 
+    /// Set the parser of the partition values in the file paths on
+    /// [`ListingOptions`] and returns self.
+    ///
+    /// By default, the partition columns are read from [Hive Partitioning]
+    /// paths, see [Self::with_table_partition_cols].
+    /// [`TemplatePartitionPathParser`] reads other layouts, and both parsers
+    /// can read files missing trailing partition columns, for example when
+    /// the partitioning of a table changed over time.
+    ///
+    /// ```
+    /// # use std::sync::Arc;
+    /// # use arrow::datatypes::DataType;
+    /// # use datafusion_catalog_listing::{ListingOptions, TemplatePartitionPathParser};
+    /// # use datafusion_datasource_parquet::file_format::ParquetFormat;
+    ///
+    /// // listing options for files with paths such as `/mnt/data/2024/06/data.parquet`
+    /// // where files written before the table was partitioned by `month` are
+    /// // stored in `/mnt/data/2023/data.parquet`
+    /// let parser = TemplatePartitionPathParser::try_new("{year}/{month}")?
+    ///     .with_missing_values_as_null(true);
+    /// let listing_options = ListingOptions::new(Arc::new(ParquetFormat::default()))
+    ///     .with_table_partition_cols(vec![
+    ///         ("year".to_string(), DataType::Int32),
+    ///         ("month".to_string(), DataType::Int32),
+    ///     ])
+    ///     .with_partition_path_parser(Arc::new(parser));
+    /// # Ok::<(), datafusion_common::DataFusionError>(())
+    /// ```
+    ///
+    /// [Hive Partitioning]: crate::HivePartitionPathParser
+    /// [`TemplatePartitionPathParser`]: crate::TemplatePartitionPathParser
+    pub fn with_partition_path_parser(
+        mut self,
+        partition_path_parser: Arc<dyn PartitionPathParser>,
+    ) -> Self {
+        self.partition_path_parser = partition_path_parser;
+        self
+    }
+
     /// Infer the schema of the files at the given path on the provided object store.
     ///
     /// If the table_path contains one or more files (i.e. it is a directory /
@@ -0,0 +1,395 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Parsers for the partition values encoded in the paths of the files of a
+//! [`ListingTable`](crate::ListingTable)
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+use datafusion_common::{Result, plan_err};
+use object_store::path::Path;
+
+/// Extracts the values of the partition columns of a
+/// [`ListingTable`](crate::ListingTable) from the directories of its files.
+///
+/// [`HivePartitionPathParser`] is used by default, and
+/// [`TemplatePartitionPathParser`] supports layouts such as
+/// `2024/06/01/`. See [`ListingOptions::with_partition_path_parser`].
+///
+/// [`ListingOptions::with_partition_path_parser`]: crate::ListingOptions::with_partition_path_parser
+pub trait PartitionPathParser: Any + Debug + Send + Sync {
+    /// Returns the values of `partition_cols`, in order, encoded in `dirs`,
+    /// the directories of a file below the table path.
+    ///
+    /// The value of a partition column missing from `dirs` is `None`, which
+    /// is only allowed if [`Self::missing_values_as_null`] returns true.
+    /// Returns `None` if the file is not in a partition and must be ignored.
+    fn parse<'a>(
+        &self,
+        dirs: &[&'a str],
+        partition_cols: &[&str],
+    ) -> Option<Vec<Option<&'a str>>>;
+
+    /// Returns the directory below the table path containing all files
+    /// whose partition columns have the given `values`, so that only this
+    /// directory needs to be listed.
+    ///
+    /// `values` contains the columns filtered to a single value. Returns
+    /// `None` if all files need to be listed.
+    fn prefix(
+        &self,
+        _partition_cols: &[&str],
+        _values: &HashMap<&str, String>,
+    ) -> Option<Path> {
+        None
+    }
+
+    /// Returns true if partition columns missing from the path of a file are
+    /// read as nulls, making the partition columns nullable
+    fn missing_values_as_null(&self) -> bool {
+        false
+    }
+}
+
+impl dyn PartitionPathParser {
+    /// Returns `true` if the parser is of type `T`.
+    pub fn is<T: PartitionPathParser>(&self) -> bool {
+        (self as &dyn Any).is::<T>()
+    }
+}
+
+/// Parses [Hive Partitioning] paths such as `/year=2024/month=06/`, where
+/// the directories are named `column=value` in the order of the partition
+/// columns.
+///
+/// [Hive Partitioning]: https://docs.cloudera.com/HDPDocuments/HDP2/HDP-2.1.3/bk_system-admin-guide/content/hive_partitioned_tables.html
+#[derive(Debug, Clone, Default)]
+pub struct HivePartitionPathParser {
+    missing_values_as_null: bool,
+}
+
+impl HivePartitionPathParser {
+    /// Create a parser ignoring files that are not in a directory for each
+    /// partition column
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// If set, files missing the directories of trailing partition columns,
+    /// for example after partition columns were added to a table, are read
+    /// with nulls for these columns. Files missing the first partition
+    /// column are still ignored.
+    pub fn with_missing_values_as_null(mut self, missing_values_as_null: bool) -> Self {
+        self.missing_values_as_null = missing_values_as_null;
+        self
+    }
+}
+
+impl PartitionPathParser for HivePartitionPathParser {
+    fn parse<'a>(
+        &self,
+        dirs: &[&'a str],
+        partition_cols: &[&str],
+    ) -> Option<Vec<Option<&'a str>>> {
+        let mut values = Vec::with_capacity(partition_cols.len());
+        for (dir, col) in dirs.iter().copied().zip(partition_cols) {
+            match dir.split_once('=') {
+                Some((name, value)) if name == *col => values.push(Some(value)),
+                _ => break,
+            }
+        }
+        if values.is_empty()
+            || (values.len() < partition_cols.len() && !self.missing_values_as_null)
+        {
+            return None;
+        }
+        values.resize(partition_cols.len(), None);
+        Some(values)
+    }
+
+    fn prefix(
+        &self,
+        partition_cols: &[&str],
+        values: &HashMap<&str, String>,
+    ) -> Option<Path> {
+        // Stop at the first column with multiple values to create a common
+        // prefix for all selected partitions
+        let parts = partition_cols
+            .iter()
+            .map_while(|col| Some(format!("{col}={}", values.get(col)?)))
+            .collect::<Vec<_>>();
+        (!parts.is_empty()).then(|| Path::from_iter(parts))
+    }
+
+    fn missing_values_as_null(&self) -> bool {
+        self.missing_values_as_null
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TemplatePart {
+    Literal(String),
+    Column(String),
+}
+
+/// Parses paths following a template such as `{year}/{month}/{day}`, where
+/// each directory of the template contains the values of partition columns
+/// named in braces, optionally surrounded by literal text such as in
+/// `dt={date}` or `{year}-{month}`.
+///
+/// All partition columns of the table must be named in the template.
+/// Values are parsed as the types of the partition columns, so `06` is read
+/// as `6` by an integer column. As values may be formatted differently, for
+/// example padded with zeros, the partitions of a table are not listed
+/// selectively, but its files are still pruned by filters on the partition
+/// columns.
+#[derive(Debug, Clone)]
+pub struct TemplatePartitionPathParser {
+    dirs: Vec<Vec<TemplatePart>>,
+    missing_values_as_null: bool,
+}
+
+impl TemplatePartitionPathParser {
+    /// Create a parser for paths following `template`
+    pub fn try_new(template: &str) -> Result<Self> {
+        let template = template.trim_matches('/');
+        let mut names = vec![];
+        let mut dirs = vec![];
+        for dir in template.split('/') {
+            let mut parts = vec![];
+            let mut rest = dir;
+            while !rest.is_empty() {
+                let Some(start) = rest.find('{') else {
+                    parts.push(TemplatePart::Literal(rest.to_string()));
+                    break;
+                };
+                if start > 0 {
+                    parts.push(TemplatePart::Literal(rest[..start].to_string()));
+                }
+                let Some(end) = rest[start..].find('}') else {
+                    return plan_err!("Unclosed '{{' in partition template '{template}'");
+                };
+                let name = &rest[start + 1..start + end];
+                if name.is_empty() || name.contains('{') {
+                    return plan_err!(
+                        "Invalid column name '{name}' in partition template '{template}'"
+                    );
+                }
+                if matches!(parts.last(), Some(TemplatePart::Column(_))) {
+                    return plan_err!(
+                        "Columns must be separated by text in partition template '{template}'"
+                    );
+                }
+                if names.contains(&name) {
+                    return plan_err!(
+                        "Column '{name}' appears more than once in partition template '{template}'"
+                    );
+                }
+                names.push(name);
+                parts.push(TemplatePart::Column(name.to_string()));
+                rest = &rest[start + end + 1..];
+            }
+            if !parts
+                .iter()
+                .any(|part| matches!(part, TemplatePart::Column(_)))
+            {
+                return plan_err!(
+                    "Directory '{dir}' of partition template '{template}' has no columns"
+                );
+            }
+            dirs.push(parts);
+        }
+        Ok(Self {
+            dirs,
+            missing_values_as_null: false,
+        })
+    }
+
+    /// If set, files missing the trailing directories of the template, for
+    /// example after partition columns were added to a table, are read with
+    /// nulls for the columns of these directories. Files not matching the
+    /// first directory of the template are still ignored.
+    pub fn with_missing_values_as_null(mut self, missing_values_as_null: bool) -> Self {
+        self.missing_values_as_null = missing_values_as_null;
+        self
+    }
+
+    /// Returns the values of the columns in `dir`, or `None` if `dir` does
+    /// not match `parts`
+    fn parse_dir<'a, 'b>(
+        parts: &'b [TemplatePart],
+        dir: &'a str,
+    ) -> Option<Vec<(&'b str, &'a str)>> {
+        let mut values = vec![];
+        let mut rest = dir;
+        for (i, part) in parts.iter().enumerate() {
+            match part {
+                TemplatePart::Literal(literal) => {
+                    rest = rest.strip_prefix(literal.as_str())?;
+                }
+                TemplatePart::Column(name) => {
+                    // A column extends up to the following text
+                    let end = match parts.get(i + 1) {
+                        Some(TemplatePart::Literal(next)) => rest.find(next.as_str())?,
+                        _ => rest.len(),
+                    };
+                    if end == 0 {
+                        return None;
+                    }
+                    values.push((name.as_str(), &rest[..end]));
+                    rest = &rest[end..];
+                }
+            }
+        }
+        rest.is_empty().then_some(values)
+    }
+}
+
+impl PartitionPathParser for TemplatePartitionPathParser {
+    fn parse<'a>(
+        &self,
+        dirs: &[&'a str],
+        partition_cols: &[&str],
+    ) -> Option<Vec<Option<&'a str>>> {
+        let mut values = vec![None; partition_cols.len()];
+        let mut matched = 0;
+        for (parts, dir) in self.dirs.iter().zip(dirs) {
+            let Some(dir_values) = Self::parse_dir(parts, *dir) else {
+                break;
+            };
+            for (name, value) in dir_values {
+                if let Some(i) = partition_cols.iter().position(|col| *col == name) {
+                    values[i] = Some(value);
+                }
+            }
+            matched += 1;
+        }
+        if matched == 0
+            || (values.iter().any(Option::is_none) && !self.missing_values_as_null)
+        {
+            return None;
+        }
+        Some(values)
+    }
+
+    fn missing_values_as_null(&self) -> bool {
+        self.missing_values_as_null
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hive_parse() {
+        let parser = HivePartitionPathParser::new();
+        let cols = ["year", "month"];
+        assert_eq!(
+            parser.parse(&["year=2024", "month=06"], &cols),
+            Some(vec![Some("2024"), Some("06")])
+        );
+        // directories below the partitions are ignored
+        assert_eq!(
+            parser.parse(&["year=2024", "month=06", "other"], &cols),
+            Some(vec![Some("2024"), Some("06")])
+        );
+        assert_eq!(parser.parse(&["year=2024"], &cols), None);
+        assert_eq!(parser.parse(&["month=06", "year=2024"], &cols), None);
+        assert_eq!(parser.parse(&[], &cols), None);
+
+        let parser = parser.with_missing_values_as_null(true);
+        assert_eq!(
+            parser.parse(&["year=2024"], &cols),
+            Some(vec![Some("2024"), None])
+        );
+        assert_eq!(
+            parser.parse(&["year=2024", "other"], &cols),
+            Some(vec![Some("2024"), None])
+        );
+        assert_eq!(parser.parse(&["month=06"], &cols), None);
+        assert_eq!(parser.parse(&[], &cols), None);
+    }
+
+    #[test]
+    fn test_hive_prefix() {
+        let parser = HivePartitionPathParser::new();
+        let cols = ["year", "month", "day"];
+        let values =
+            HashMap::from([("year", "2024".to_string()), ("day", "1".to_string())]);
+        assert_eq!(parser.prefix(&cols, &values), Some(Path::from("year=2024")));
+        let values = HashMap::from([("month", "6".to_string())]);
+        assert_eq!(parser.prefix(&cols, &values), None);
+    }
+
+    #[test]
+    fn test_template_parse() -> Result<()> {
+        let parser = TemplatePartitionPathParser::try_new("{year}/{month}/{day}")?;
+        let cols = ["year", "month", "day"];
+        assert_eq!(
+            parser.parse(&["2024", "06", "01"], &cols),
+            Some(vec![Some("2024"), Some("06"), Some("01")])
+        );
+        assert_eq!(parser.parse(&["2024", "06"], &cols), None);
+
+        let parser = parser.with_missing_values_as_null(true);
+        assert_eq!(
+            parser.parse(&["2024", "06"], &cols),
+            Some(vec![Some("2024"), Some("06"), None])
+        );
+        assert_eq!(parser.parse(&[], &cols), None);
+
+        // columns are returned in the order of the partition columns
+        let parser = TemplatePartitionPathParser::try_new("/dt={year}-{month}/h{hour}/")?;
+        assert_eq!(
+            parser.parse(&["dt=2024-06", "h13"], &["hour", "year", "month"]),
+            Some(vec![Some("13"), Some("2024"), Some("06")])
+        );
+        assert_eq!(parser.parse(&["dt=2024", "h13"], &["year", "month"]), None);
+        assert_eq!(parser.parse(&["2024-06", "h13"], &["year", "month"]), None);
+        assert_eq!(parser.parse(&["dt=-06"], &["year", "month"]), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_template_invalid() {
+        for (template, error) in [
+            ("{year", "Unclosed '{' in partition template '{year'"),
+            ("{}", "Invalid column name '' in partition template '{}'"),
+            (
+                "{year}{month}",
+                "Columns must be separated by text in partition template '{year}{month}'",
+            ),
+            (
+                "{year}/{year}",
+                "Column 'year' appears more than once in partition template '{year}/{year}'",
+            ),
+            (
+                "{year}/data",
+                "Directory 'data' of partition template '{year}/data' has no columns",
+            ),
+        ] {
+            let err = TemplatePartitionPathParser::try_new(template).unwrap_err();
+            assert_eq!(
+                err.strip_backtrace(),
+                format!("Error during planning: {error}")
+            );
+        }
+    }
+}
@@ -17,13 +17,14 @@
 
 use crate::config::SchemaSource;
 use crate::helpers::{expr_applicable_for_cols, pruned_partition_list};
-use crate::{ListingOptions, ListingTableConfig};
+use crate::{HivePartitionPathParser, ListingOptions, ListingTableConfig};
 use arrow::datatypes::{Field, Schema, SchemaBuilder, SchemaRef};
 use async_trait::async_trait;
 use datafusion_catalog::{ScanArgs, ScanResult, Session, TableProvider};
 use datafusion_common::stats::Precision;
 use datafusion_common::{
-    Constraints, SchemaExt, Statistics, internal_datafusion_err, plan_err, project_schema,
+    ColumnStatistics, Constraints, SchemaExt, Statistics, TableReference,
+    internal_datafusion_err, not_impl_err, plan_err, project_schema,
 };
 use datafusion_datasource::file::FileSource;
 use datafusion_datasource::file_groups::FileGroup;
@@ -35,7 +36,9 @@ use datafusion_datasource::{
     ListingTableUrl, PartitionedFile, TableSchema, compute_all_files_statistics,
 };
 use datafusion_execution::cache::TableScopedPath;
-use datafusion_execution::cache::cache_manager::FileStatisticsCache;
+use datafusion_execution::cache::cache_manager::{
+    FileStatisticsCache, TableStatistics, TableStatisticsStore,
+};
 use datafusion_execution::cache::cache_unit::DefaultFileStatisticsCache;
 use datafusion_expr::dml::InsertOp;
 use datafusion_expr::execution_props::ExecutionProps;
@@ -45,9 +48,11 @@ use datafusion_physical_expr_adapter::PhysicalExprAdapterFactory;
 use datafusion_physical_expr_common::sort_expr::LexOrdering;
 use datafusion_physical_plan::ExecutionPlan;
 use datafusion_physical_plan::empty::EmptyExec;
+use datafusion_physical_plan::operator_statistics::ColumnDistributions;
 use futures::{Stream, StreamExt, TryStreamExt, future, stream};
 use object_store::ObjectStore;
 use std::collections::HashMap;
+use std::fmt::Debug;
 use std::sync::Arc;
 
 /// Result of a file listing operation from [`ListingTable::list_files_for_scan`].
@@ -188,6 +193,12 @@ pub struct ListingTable {
     definition: Option<String>,
     /// Cache for collected file statistics
     collected_statistics: Arc<dyn FileStatisticsCache>,
+    /// Store of table statistics collected by `ANALYZE TABLE`, along with the
+    /// name this table's statistics are stored under
+    table_statistics: Option<(TableReference, Arc<dyn TableStatisticsStore>)>,
+    /// Value distributions (histograms and most common values) of the table
+    /// columns, if known
+    column_distributions: Option<Arc<ColumnDistributions>>,
     /// Constraints applied to this table
     constraints: Constraints,
     /// Column default expressions for columns that are not physically present in the data files
@@ -214,8 +225,14 @@ impl ListingTable {
 
         // Add the partition columns to the file schema
         let mut builder = SchemaBuilder::from(file_schema.as_ref().to_owned());
+        let partition_cols_nullable =
+            options.partition_path_parser.missing_values_as_null();
         for (part_col_name, part_col_type) in &options.table_partition_cols {
-            builder.push(Field::new(part_col_name, part_col_type.clone(), false));
+            builder.push(Field::new(
+                part_col_name,
+                part_col_type.clone(),
+                partition_cols_nullable,
+            ));
         }
 
         let table_schema = Arc::new(
@@ -232,6 +249,8 @@ impl ListingTable {
             options,
             definition: None,
             collected_statistics: Arc::new(DefaultFileStatisticsCache::default()),
+            table_statistics: None,
+            column_distributions: None,
             constraints: Constraints::default(),
             column_defaults: HashMap::new(),
             expr_adapter_factory: config.expr_adapter_factory,
@@ -267,6 +286,51 @@ impl ListingTable {
         self
     }
 
+    /// Set the [`TableStatisticsStore`] holding the statistics collected for
+    /// this table by `ANALYZE TABLE`, along with the fully qualified name they
+    /// are stored under.
+    ///
+    /// The stored statistics are returned by [`TableProvider::statistics`] as
+    /// inexact statistics, since the files may have changed after they were
+    /// collected, and used as estimates for statistics that can not be derived
+    /// from the files when scanning the table. The stored value distributions
+    /// are attached to the scans, unless set with
+    /// [`Self::with_column_distributions`].
+    pub fn with_table_statistics_store(
+        mut self,
+        table_ref: TableReference,
+        store: Arc<dyn TableStatisticsStore>,
+    ) -> Self {
+        self.table_statistics = Some((table_ref, store));
+        self
+    }
+
+    /// Set the value distributions (histograms and most common values) of the
+    /// table columns, in table schema order (file schema + partition columns).
+    ///
+    /// They are attached to the scans of this table, see
+    /// [`FileScanConfigBuilder::with_column_distributions`].
+    pub fn with_column_distributions(
+        mut self,
+        column_distributions: Arc<ColumnDistributions>,
+    ) -> Self {
+        self.column_distributions = Some(column_distributions);
+        self
+    }
+
+    /// Returns the statistics collected for this table by `ANALYZE TABLE`,
+    /// if any
+    fn collected_statistics(&self) -> Option<Arc<TableStatistics>> {
+        let (table_ref, store) = self.table_statistics.as_ref()?;
+        store
+            .get(table_ref)
+            // ignore statistics collected for a different schema
+            .filter(|collected| {
+                collected.statistics.column_statistics.len()
+                    == self.table_schema.fields().len()
+            })
+    }
+
     /// Specify the SQL definition for this table, if any
     pub fn with_definition(mut self, definition: Option<String>) -> Self {
         self.definition = definition;
@@ -436,6 +500,48 @@ fn derive_common_ordering_from_files(file_groups: &[FileGroup]) -> Option<LexOrd
     }
 }
 
+/// Fills in the statistics that could not be derived from the files with the
+/// statistics collected by `ANALYZE TABLE`.
+///
+/// The collected statistics are only used as estimates, since the table may
+/// have changed after they were collected.
+fn fill_absent_statistics(statistics: Statistics, collected: Statistics) -> Statistics {
+    fn or_collected<T: Debug + Clone + PartialEq + Eq + PartialOrd>(
+        value: Precision<T>,
+        collected: Precision<T>,
+    ) -> Precision<T> {
+        match value {
+            Precision::Absent => collected,
+            value => value,
+        }
+    }
+
+    let collected = collected.to_inexact();
+    Statistics {
+        num_rows: or_collected(statistics.num_rows, collected.num_rows),
+        total_byte_size: or_collected(
+            statistics.total_byte_size,
+            collected.total_byte_size,
+        ),
+        column_statistics: statistics
+            .column_statistics
+            .into_iter()
+            .zip(collected.column_statistics)
+            .map(|(column, collected)| ColumnStatistics {
+                null_count: or_collected(column.null_count, collected.null_count),
+                max_value: or_collected(column.max_value, collected.max_value),
+                min_value: or_collected(column.min_value, collected.min_value),
+                sum_value: or_collected(column.sum_value, collected.sum_value),
+                distinct_count: or_collected(
+                    column.distinct_count,
+                    collected.distinct_count,
+                ),
+                byte_size: or_collected(column.byte_size, collected.byte_size),
+            })
+            .collect(),
+    }
+}
+
 // Expressions can be used for partition pruning if they can be evaluated using
 // only the partition columns and there are partition columns.
 fn can_be_evaluated_for_partition_pruning(
@@ -514,6 +620,19 @@ impl TableProvider for ListingTable {
         } = self
             .list_files_for_scan(state, &partition_filters, statistic_file_limit)
             .await?;
+        let collected = self.collected_statistics();
+        let statistics = match &collected {
+            // The files may have changed since the statistics were collected
+            Some(collected) => fill_absent_statistics(
+                statistics,
+                collected.statistics.clone().to_inexact(),
+            ),
+            None => statistics,
+        };
+        let column_distributions = self
+            .column_distributions
+            .clone()
+            .or_else(|| collected?.column_distributions::<ColumnDistributions>());
 
         // if no files need to be read, return an `EmptyExec`
         if partitioned_file_lists.is_empty() {
@@ -574,6 +693,7 @@ impl TableProvider for ListingTable {
                     .with_file_groups(partitioned_file_lists)
                     .with_constraints(self.constraints.clone())
                     .with_statistics(statistics)
+                    .with_column_distributions(column_distributions)
                     .with_projection_indices(projection)?
                     .with_limit(limit)
                     .with_output_ordering(output_ordering)
@@ -614,6 +734,12 @@ impl TableProvider for ListingTable {
         self.definition.as_deref()
     }
 
+    fn statistics(&self) -> Option<Statistics> {
+        // The files may have changed since the statistics were collected
+        self.collected_statistics()
+            .map(|collected| collected.statistics.clone().to_inexact())
+    }
+
     async fn insert_into(
         &self,
         state: &dyn Session,
@@ -632,6 +758,19 @@ impl TableProvider for ListingTable {
             );
         }
 
+        // Partitioned files are always written to Hive Partitioning paths
+        if !self.options.table_partition_cols.is_empty()
+            && !self
+                .options
+                .partition_path_parser
+                .is::<HivePartitionPathParser>()
+        {
+            return not_impl_err!(
+                "Inserting into a ListingTable with partition path parser {:?} is not supported",
+                self.options.partition_path_parser
+            );
+        }
+
         // Get the object store for the table path.
         let store = state.runtime_env().object_store(table_path)?;
 
@@ -642,6 +781,7 @@ impl TableProvider for ListingTable {
             &[],
             &self.options.file_extension,
             &self.options.table_partition_cols,
+            self.options.partition_path_parser.as_ref(),
         )
         .await?;
 
@@ -716,6 +856,7 @@ impl ListingTable {
                 filters,
                 &self.options.file_extension,
                 &self.options.table_partition_cols,
+                self.options.partition_path_parser.as_ref(),
             )
         }))
         .await?;
@@ -1049,4 +1190,46 @@ mod tests {
         let result = derive_common_ordering_from_files(&file_groups);
         assert_eq!(result, Some(ordering));
     }
+
+    #[test]
+    fn test_fill_absent_statistics() {
+        use datafusion_common::ScalarValue;
+
+        let from_files = Statistics {
+            num_rows: Precision::Exact(100),
+            total_byte_size: Precision::Absent,
+            column_statistics: vec![
+                ColumnStatistics::new_unknown().with_null_count(Precision::Exact(0)),
+            ],
+        };
+        let collected = Statistics {
+            num_rows: Precision::Exact(90),
+            total_byte_size: Precision::Exact(1024),
+            column_statistics: vec![
+                ColumnStatistics::new_unknown()
+                    .with_null_count(Precision::Exact(5))
+                    .with_distinct_count(Precision::Exact(42))
+                    .with_min_value(Precision::Exact(ScalarValue::Int32(Some(1)))),
+            ],
+        };
+
+        let statistics = fill_absent_statistics(from_files, collected);
+        // statistics derived from the files take precedence
+        assert_eq!(statistics.num_rows, Precision::Exact(100));
+        assert_eq!(
+            statistics.column_statistics[0].null_count,
+            Precision::Exact(0)
+        );
+        // collected statistics are only estimates
+        assert_eq!(statistics.total_byte_size, Precision::Inexact(1024));
+        assert_eq!(
+            statistics.column_statistics[0].distinct_count,
+            Precision::Inexact(42)
+        );
+        assert_eq!(
+            statistics.column_statistics[0].min_value,
+            Precision::Inexact(ScalarValue::Int32(Some(1)))
+        );
+        assert_eq!(statistics.column_statistics[0].max_value, Precision::Absent);
+    }
 }
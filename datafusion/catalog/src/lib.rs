@@ -37,6 +37,7 @@ pub mod default_table_source;
 pub mod empty;
 pub mod information_schema;
 pub mod listing_schema;
+pub mod materialized_view;
 pub mod memory;
 pub mod stream;
 pub mod streaming;
@@ -0,0 +1,171 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Materialized view data source which stores the results of a LogicalPlan.
+
+use std::sync::Arc;
+
+use crate::Session;
+use crate::TableProvider;
+
+use arrow::datatypes::SchemaRef;
+use async_trait::async_trait;
+use datafusion_common::error::Result;
+use datafusion_common::{SchemaExt, Statistics};
+use datafusion_expr::dml::InsertOp;
+use datafusion_expr::{Expr, LogicalPlan, TableProviderFilterPushDown, TableType};
+use datafusion_physical_plan::{ExecutionPlan, collect};
+use parking_lot::Mutex;
+
+/// An implementation of `TableProvider` that stores the results of a logical
+/// plan in another `TableProvider`, such as a [`MemTable`].
+///
+/// Scans read the stored results, which reflect the tables read by the plan
+/// as of the last [`MaterializedView::refresh`]. The view is fresh after a
+/// refresh, until [`MaterializedView::mark_stale`] is called, e.g. when one of
+/// these tables is modified.
+///
+/// [`MemTable`]: crate::MemTable
+#[derive(Debug)]
+pub struct MaterializedView {
+    /// LogicalPlan of the view
+    logical_plan: LogicalPlan,
+    /// SQL used to create the view, if available
+    definition: Option<String>,
+    /// Stores the results of the view
+    storage: Arc<dyn TableProvider>,
+    freshness: Mutex<Freshness>,
+}
+
+#[derive(Debug, Default)]
+struct Freshness {
+    fresh: bool,
+    /// Incremented whenever the view becomes stale, so that a refresh running
+    /// concurrently with a modification does not mark the view fresh
+    generation: u64,
+}
+
+impl MaterializedView {
+    /// Create a new materialized view storing its results in `storage`.
+    ///
+    /// The schema of `storage` must match the schema of `logical_plan`, and
+    /// `storage` must support deleting all rows with
+    /// [`TableProvider::delete_from`] and appending rows with
+    /// [`TableProvider::insert_into`]. The view is stale until it is refreshed.
+    pub fn try_new(
+        logical_plan: LogicalPlan,
+        definition: Option<String>,
+        storage: Arc<dyn TableProvider>,
+    ) -> Result<Self> {
+        storage
+            .schema()
+            .logically_equivalent_names_and_types(logical_plan.schema().inner())?;
+        Ok(Self {
+            logical_plan,
+            definition,
+            storage,
+            freshness: Mutex::default(),
+        })
+    }
+
+    /// Get definition ref
+    pub fn definition(&self) -> Option<&String> {
+        self.definition.as_ref()
+    }
+
+    /// Get logical_plan ref
+    pub fn logical_plan(&self) -> &LogicalPlan {
+        &self.logical_plan
+    }
+
+    /// Get the table storing the results of the view
+    pub fn storage(&self) -> &Arc<dyn TableProvider> {
+        &self.storage
+    }
+
+    /// Returns true if the stored results reflect the current contents of the
+    /// tables read by the view
+    pub fn is_fresh(&self) -> bool {
+        self.freshness.lock().fresh
+    }
+
+    /// Marks the stored results as outdated, until the next refresh
+    pub fn mark_stale(&self) {
+        let mut freshness = self.freshness.lock();
+        freshness.fresh = false;
+        freshness.generation += 1;
+    }
+
+    /// Recomputes the results of the view, replacing the stored results
+    pub async fn refresh(&self, state: &dyn Session) -> Result<()> {
+        // Mark the view stale first, so the refresh does not read from it
+        self.mark_stale();
+        let generation = self.freshness.lock().generation;
+
+        // Replace the stored results: delete all rows, then append the new ones
+        let input = state.create_physical_plan(&self.logical_plan).await?;
+        let delete = self.storage.delete_from(state, vec![]).await?;
+        collect(delete, state.task_ctx()).await?;
+        let insert = self
+            .storage
+            .insert_into(state, input, InsertOp::Append)
+            .await?;
+        collect(insert, state.task_ctx()).await?;
+
+        let mut freshness = self.freshness.lock();
+        if freshness.generation == generation {
+            freshness.fresh = true;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl TableProvider for MaterializedView {
+    fn schema(&self) -> SchemaRef {
+        self.storage.schema()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::View
+    }
+
+    fn get_table_definition(&self) -> Option<&str> {
+        self.definition.as_deref()
+    }
+
+    fn supports_filters_pushdown(
+        &self,
+        filters: &[&Expr],
+    ) -> Result<Vec<TableProviderFilterPushDown>> {
+        self.storage.supports_filters_pushdown(filters)
+    }
+
+    fn statistics(&self) -> Option<Statistics> {
+        self.storage.statistics()
+    }
+
+    async fn scan(
+        &self,
+        state: &dyn Session,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        limit: Option<usize>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        self.storage.scan(state, projection, filters, limit).await
+    }
+}
@@ -24,20 +24,27 @@ use std::sync::Arc;
 use crate::TableProvider;
 
 use arrow::array::{
-    Array, ArrayRef, BooleanArray, RecordBatch as ArrowRecordBatch, UInt64Array,
+    Array, ArrayRef, BooleanArray, RecordBatch as ArrowRecordBatch, UInt32Array,
+    UInt64Array, new_null_array,
 };
 use arrow::compute::kernels::zip::zip;
-use arrow::compute::{and, filter_record_batch};
+use arrow::compute::{
+    and, concat_batches, filter_record_batch, take_arrays, take_record_batch,
+};
 use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
 use arrow::record_batch::RecordBatch;
+use datafusion_common::cast::as_boolean_array;
 use datafusion_common::error::Result;
 use datafusion_common::tree_node::TreeNodeRecursion;
-use datafusion_common::{Constraints, DFSchema, SchemaExt, not_impl_err, plan_err};
+use datafusion_common::{
+    Constraints, DFSchema, DFSchemaRef, SchemaExt, exec_err, internal_err, not_impl_err,
+    plan_err,
+};
 use datafusion_common_runtime::JoinSet;
 use datafusion_datasource::memory::{MemSink, MemorySourceConfig};
 use datafusion_datasource::sink::DataSinkExec;
 use datafusion_datasource::source::DataSourceExec;
-use datafusion_expr::dml::InsertOp;
+use datafusion_expr::dml::{InsertOp, MergeAction, MergeClauseKind, MergeInto};
 use datafusion_expr::{Expr, SortExpr, TableType};
 use datafusion_physical_expr::{
     LexOrdering, create_physical_expr, create_physical_sort_exprs,
@@ -46,7 +53,7 @@ use datafusion_physical_plan::repartition::RepartitionExec;
 use datafusion_physical_plan::stream::RecordBatchStreamAdapter;
 use datafusion_physical_plan::{
     DisplayAs, DisplayFormatType, ExecutionPlan, ExecutionPlanProperties, Partitioning,
-    PhysicalExpr, PlanProperties, common,
+    PhysicalExpr, PlanProperties, collect, common,
 };
 use datafusion_session::Session;
 
@@ -495,6 +502,309 @@ impl TableProvider for MemTable {
 
         Ok(Arc::new(DmlResultExec::new(total_updated)))
     }
+
+    /// Merges `source` into the table by comparing every target row with every
+    /// source row, which is only suitable for small tables.
+    async fn merge_into(
+        &self,
+        state: &dyn Session,
+        source: Arc<dyn ExecutionPlan>,
+        schema: DFSchemaRef,
+        merge: MergeInto,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let source_schema = source.schema();
+        let source_batches = collect(source, state.task_ctx()).await?;
+        let source_batch = concat_batches(&source_schema, &source_batches)?;
+
+        // The batches the clauses are evaluated on hold the target columns
+        // followed by the source columns, either of which may be NULL
+        let joined_schema = Arc::new(Schema::new(
+            schema
+                .fields()
+                .iter()
+                .map(|field| field.as_ref().clone().with_nullable(true))
+                .collect::<Vec<_>>(),
+        ));
+        let props = state.execution_props();
+        let on = create_physical_expr(&merge.on, &schema, props)?;
+        let clauses = merge
+            .clauses
+            .iter()
+            .map(|clause| {
+                let predicate = clause
+                    .predicate
+                    .as_ref()
+                    .map(|predicate| create_physical_expr(predicate, &schema, props))
+                    .transpose()?;
+                let action = match &clause.action {
+                    MergeAction::Update(assignments) => PhysicalMergeAction::Update(
+                        assignments
+                            .iter()
+                            .map(|(column_name, value)| {
+                                let Ok(index) = self.schema.index_of(column_name) else {
+                                    return plan_err!(
+                                        "MERGE failed: column '{column_name}' does not exist"
+                                    );
+                                };
+                                Ok((index, create_physical_expr(value, &schema, props)?))
+                            })
+                            .collect::<Result<_>>()?,
+                    ),
+                    MergeAction::Insert(values) => PhysicalMergeAction::Insert(
+                        values
+                            .iter()
+                            .map(|value| create_physical_expr(value, &schema, props))
+                            .collect::<Result<_>>()?,
+                    ),
+                    MergeAction::Delete => PhysicalMergeAction::Delete,
+                };
+                Ok((clause.kind, predicate, action))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // Hold all partitions until the merge succeeded, so that a failing
+        // merge leaves the table unchanged
+        let mut partitions = Vec::with_capacity(self.batches.len());
+        for partition_data in &self.batches {
+            partitions.push(partition_data.write().await);
+        }
+
+        let mut total_merged: u64 = 0;
+        let mut source_matched = vec![false; source_batch.num_rows()];
+        let mut new_partitions = Vec::with_capacity(partitions.len());
+
+        for partition in &partitions {
+            let mut new_batches = Vec::with_capacity(partition.len());
+
+            for batch in partition.iter() {
+                if batch.num_rows() == 0 {
+                    continue;
+                }
+
+                let matches =
+                    match_target_rows(batch, &source_batch, &on, &joined_schema)?;
+                for source_index in matches.iter().flatten() {
+                    source_matched[*source_index as usize] = true;
+                }
+                let joined = joined_batch(
+                    &joined_schema,
+                    batch.columns().to_vec(),
+                    &source_batch,
+                    &UInt32Array::from(matches.clone()),
+                )?;
+
+                let mut handled = vec![false; batch.num_rows()];
+                let mut keep = vec![true; batch.num_rows()];
+                let mut columns = batch.columns().to_vec();
+
+                for (kind, predicate, action) in &clauses {
+                    let matched = match kind {
+                        MergeClauseKind::Matched => true,
+                        MergeClauseKind::NotMatchedBySource => false,
+                        MergeClauseKind::NotMatchedByTarget => continue,
+                    };
+                    let candidates = matches
+                        .iter()
+                        .zip(&handled)
+                        .map(|(m, handled)| Some(!handled && m.is_some() == matched))
+                        .collect();
+                    let mask = clause_mask(candidates, predicate.as_ref(), &joined)?;
+
+                    for (row, selected) in mask.values().iter().enumerate() {
+                        if selected {
+                            handled[row] = true;
+                            total_merged += 1;
+                            if matches!(action, PhysicalMergeAction::Delete) {
+                                keep[row] = false;
+                            }
+                        }
+                    }
+
+                    match action {
+                        PhysicalMergeAction::Update(assignments) => {
+                            for (index, value) in assignments {
+                                // Only evaluate the new value on the updated rows
+                                let new_values = value
+                                    .evaluate_selection(&joined, &mask)?
+                                    .into_array(joined.num_rows())?;
+                                let new_arr: &dyn Array = new_values.as_ref();
+                                let orig_arr: &dyn Array = columns[*index].as_ref();
+                                columns[*index] = zip(&mask, &new_arr, &orig_arr)?;
+                            }
+                        }
+                        PhysicalMergeAction::Delete => {}
+                        PhysicalMergeAction::Insert(_) => {
+                            return internal_err!(
+                                "MERGE INSERT is only valid for unmatched source rows"
+                            );
+                        }
+                    }
+                }
+
+                let merged_batch =
+                    ArrowRecordBatch::try_new(Arc::clone(&self.schema), columns)?;
+                let merged_batch =
+                    filter_record_batch(&merged_batch, &BooleanArray::from(keep))?;
+                if merged_batch.num_rows() > 0 {
+                    new_batches.push(merged_batch);
+                }
+            }
+
+            new_partitions.push(new_batches);
+        }
+
+        // Source rows not matching any target row, with NULL target columns
+        let unmatched = source_matched
+            .iter()
+            .enumerate()
+            .filter(|(_, matched)| !**matched)
+            .map(|(row, _)| row as u32)
+            .collect::<UInt32Array>();
+        let unmatched_source = take_record_batch(&source_batch, &unmatched)?;
+        let mut columns = self
+            .schema
+            .fields()
+            .iter()
+            .map(|field| new_null_array(field.data_type(), unmatched.len()))
+            .collect::<Vec<_>>();
+        columns.extend(unmatched_source.columns().iter().cloned());
+        let joined = ArrowRecordBatch::try_new(Arc::clone(&joined_schema), columns)?;
+
+        let mut handled = vec![false; joined.num_rows()];
+        let mut inserted = vec![];
+        for (kind, predicate, action) in &clauses {
+            if *kind != MergeClauseKind::NotMatchedByTarget {
+                continue;
+            }
+            let candidates = handled.iter().map(|handled| Some(!handled)).collect();
+            let mask = clause_mask(candidates, predicate.as_ref(), &joined)?;
+            for (row, selected) in mask.values().iter().enumerate() {
+                handled[row] |= selected;
+            }
+
+            let PhysicalMergeAction::Insert(values) = action else {
+                return internal_err!(
+                    "MERGE only supports INSERT for unmatched source rows"
+                );
+            };
+            let rows = filter_record_batch(&joined, &mask)?;
+            if rows.num_rows() == 0 {
+                continue;
+            }
+            let columns = values
+                .iter()
+                .map(|value| value.evaluate(&rows)?.into_array(rows.num_rows()))
+                .collect::<Result<Vec<_>>>()?;
+            total_merged += rows.num_rows() as u64;
+            inserted.push(ArrowRecordBatch::try_new(
+                Arc::clone(&self.schema),
+                columns,
+            )?);
+        }
+
+        if !inserted.is_empty() {
+            let Some(first_partition) = new_partitions.first_mut() else {
+                return exec_err!(
+                    "MERGE failed: cannot insert into a table without partitions"
+                );
+            };
+            first_partition.extend(inserted);
+        }
+
+        *self.sort_order.lock() = vec![];
+        for (partition, new_batches) in partitions.iter_mut().zip(new_partitions) {
+            **partition = new_batches;
+        }
+
+        Ok(Arc::new(DmlResultExec::new(total_merged)))
+    }
+}
+
+/// A [`MergeAction`] with physical expressions.
+enum PhysicalMergeAction {
+    /// The index and the new value of each assigned column
+    Update(Vec<(usize, Arc<dyn PhysicalExpr>)>),
+    Insert(Vec<Arc<dyn PhysicalExpr>>),
+    Delete,
+}
+
+/// Returns, for each row of `target`, the index of the row of `source` it
+/// matches according to `on`, or `None` if there is no such row.
+fn match_target_rows(
+    target: &RecordBatch,
+    source: &RecordBatch,
+    on: &Arc<dyn PhysicalExpr>,
+    joined_schema: &SchemaRef,
+) -> Result<Vec<Option<u32>>> {
+    let num_source_rows = source.num_rows() as u32;
+    let target_indices = (0..target.num_rows() as u32)
+        .flat_map(|row| std::iter::repeat_n(row, num_source_rows as usize))
+        .collect::<UInt32Array>();
+    let source_indices = (0..target.num_rows())
+        .flat_map(|_| 0..num_source_rows)
+        .collect::<UInt32Array>();
+
+    // Evaluate `on` for every pair of target and source rows
+    let pairs = joined_batch(
+        joined_schema,
+        take_arrays(target.columns(), &target_indices, None)?,
+        source,
+        &source_indices,
+    )?;
+    let on_result = on.evaluate(&pairs)?.into_array(pairs.num_rows())?;
+    let on_result = as_boolean_array(&on_result)?;
+
+    let mut matches = vec![None; target.num_rows()];
+    for pair in 0..pairs.num_rows() {
+        if on_result.is_valid(pair) && on_result.value(pair) {
+            let target_row = target_indices.value(pair) as usize;
+            if matches[target_row]
+                .replace(source_indices.value(pair))
+                .is_some()
+            {
+                return exec_err!(
+                    "MERGE failed: a row of the target table matched more than one row of the source"
+                );
+            }
+        }
+    }
+    Ok(matches)
+}
+
+/// Appends the rows of `source` at `source_indices` to `target_columns`.
+/// NULL indices produce NULL source columns.
+fn joined_batch(
+    joined_schema: &SchemaRef,
+    mut target_columns: Vec<ArrayRef>,
+    source: &RecordBatch,
+    source_indices: &UInt32Array,
+) -> Result<RecordBatch> {
+    target_columns.extend(take_arrays(source.columns(), source_indices, None)?);
+    Ok(ArrowRecordBatch::try_new(
+        Arc::clone(joined_schema),
+        target_columns,
+    )?)
+}
+
+/// Returns the `candidates` rows of `batch` that satisfy `predicate`, treating
+/// NULL as false.
+fn clause_mask(
+    candidates: BooleanArray,
+    predicate: Option<&Arc<dyn PhysicalExpr>>,
+    batch: &RecordBatch,
+) -> Result<BooleanArray> {
+    let Some(predicate) = predicate else {
+        return Ok(candidates);
+    };
+    let result = predicate
+        .evaluate_selection(batch, &candidates)?
+        .into_array(batch.num_rows())?;
+    let result = as_boolean_array(&result)?;
+    Ok(candidates
+        .iter()
+        .zip(result.iter())
+        .map(|(candidate, result)| Some(candidate == Some(true) && result == Some(true)))
+        .collect())
 }
 
 /// Evaluate filter expressions against a batch and return a combined boolean mask.
@@ -23,11 +23,11 @@ use std::sync::Arc;
 use crate::session::Session;
 use arrow::datatypes::SchemaRef;
 use async_trait::async_trait;
-use datafusion_common::{Constraints, Statistics, not_impl_err};
+use datafusion_common::{Constraints, DFSchemaRef, Statistics, not_impl_err};
 use datafusion_common::{Result, internal_err};
 use datafusion_expr::Expr;
 
-use datafusion_expr::dml::InsertOp;
+use datafusion_expr::dml::{InsertOp, MergeInto};
 use datafusion_expr::{
     CreateExternalTable, LogicalPlan, TableProviderFilterPushDown, TableType,
 };
@@ -310,9 +310,12 @@ pub trait TableProvider: Any + Debug + Sync + Send {
     }
 
     /// Get statistics for this table, if available
-    /// Although not presently used in mainline DataFusion, this allows implementation specific
-    /// behavior for downstream repositories, in conjunction with specialized optimizer rules to
-    /// perform operations such as re-ordering of joins.
+    ///
+    /// The built in `ListingTable` returns the statistics collected for it by
+    /// `ANALYZE TABLE`. Beyond that this is not used in mainline DataFusion, but it
+    /// allows implementation specific behavior for downstream repositories, in
+    /// conjunction with specialized optimizer rules to perform operations such as
+    /// re-ordering of joins.
     fn statistics(&self) -> Option<Statistics> {
         None
     }
@@ -378,6 +381,25 @@ pub trait TableProvider: Any + Debug + Sync + Send {
     async fn truncate(&self, _state: &dyn Session) -> Result<Arc<dyn ExecutionPlan>> {
         not_impl_err!("TRUNCATE not supported for {} table", self.table_type())
     }
+
+    /// Merge the rows produced by `source` into this table (`MERGE INTO`).
+    ///
+    /// `merge` holds the `ON` condition and the `WHEN` clauses of the
+    /// statement. Their expressions are resolved against `schema`: the columns
+    /// of this table, qualified with [`MergeInto::target_alias`], followed by
+    /// the columns of `source`.
+    ///
+    /// Returns an [`ExecutionPlan`] producing a single row with `count` (UInt64),
+    /// the number of rows inserted, updated or deleted.
+    async fn merge_into(
+        &self,
+        _state: &dyn Session,
+        _source: Arc<dyn ExecutionPlan>,
+        _schema: DFSchemaRef,
+        _merge: MergeInto,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        not_impl_err!("MERGE not supported for {} table", self.table_type())
+    }
 }
 
 impl dyn TableProvider {
@@ -599,6 +599,13 @@ config_namespace! {
         /// Number of files to read in parallel when inferring schema and statistics
         pub meta_fetch_concurrency: usize, default = 32
 
+        /// Size in bytes of the byte ranges that files of formats which can be split
+        /// at newline boundaries (CSV and newline-delimited JSON) are divided into
+        /// while scanning. Idle partitions steal these ranges from each other, so
+        /// that a single large file is read by all partitions. Set to 0 to disable
+        /// splitting.
+        pub file_split_size: usize, default = 64 * 1024 * 1024
+
         /// Guarantees a minimum level of output files running in parallel.
         /// RecordBatches will be distributed in round robin fashion to each
         /// parallel writer. Each writer is closed and a new file opened once
@@ -706,6 +713,23 @@ config_namespace! {
         ///
         /// Disabled by default, set to a number greater than 0 for enabling it.
         pub hash_join_buffering_capacity: usize, default = 0
+
+        /// Should hash joins fall back to a partitioned (grace) hash join that
+        /// spills both inputs to disk when the build side does not fit in memory.
+        ///
+        /// When enabled, a `Partitioned` mode hash join whose build side exceeds the
+        /// memory limit hash-partitions both inputs into spill files and joins them one
+        /// partition at a time. Requires a `DiskManager` that supports temporary files.
+        ///
+        /// Hash joins in `CollectLeft` mode, null-aware anti joins and joins that must
+        /// preserve the order of the probe side never spill. A partition that spills
+        /// does not narrow the dynamic filter pushed into the probe side.
+        pub enable_hash_join_spill: bool, default = false
+
+        /// Number of partitions each input of a hash join is split into when it
+        /// falls back to spilling (see `enable_hash_join_spill`). Partitions that still
+        /// do not fit in memory are split again, up to a fixed recursion depth.
+        pub hash_join_spill_partitions: usize, default = 16
     }
 }
 
@@ -1268,6 +1292,18 @@ config_namespace! {
         /// operator's built-in `partition_statistics`.
         pub use_statistics_registry: bool, default = false
 
+        /// When set to true, the physical plan optimizer reorders trees of inner
+        /// equi-joins and cross joins to minimize the estimated size of the
+        /// intermediate results, using row count and distinct count estimates
+        /// from the `StatisticsRegistry`. Join trees are only reordered when all
+        /// their inputs have a row count estimate.
+        pub enable_cost_based_join_reorder: bool, default = false
+
+        /// The maximum number of join inputs for which cost-based join reordering
+        /// (see `enable_cost_based_join_reorder`) considers all bushy join orders.
+        /// Larger join trees are ordered greedily. Values above 16 are treated as 16.
+        pub cost_based_join_reorder_max_relations: usize, default = 10
+
         /// When set to true, the physical plan optimizer will prefer HashJoin over SortMergeJoin.
         /// HashJoin can work more efficiently than SortMergeJoin but consumes more memory
         pub prefer_hash_join: bool, default = true
@@ -1348,6 +1384,31 @@ config_namespace! {
         /// closer to the leaf table scans, and push those projections down
         /// towards the leaf nodes.
         pub enable_leaf_expression_pushdown: bool, default = true
+
+        /// When set to true, the optimizer will rewrite queries to read from
+        /// materialized views whose stored results are fresh, if they can answer
+        /// the query exactly or by filtering and re-aggregating the stored results.
+        /// Views are only marked stale by `INSERT`, `UPDATE` and `DELETE`
+        /// statements planned in the same session, so other changes to the
+        /// tables they read, such as new files, are not detected
+        pub enable_materialized_view_rewrite: bool, default = false
+
+        /// When set to true, the optimizer will pre-aggregate the input of an
+        /// inner join on its join keys, if the aggregate above the join only
+        /// reads that input and the other input is unique on its join keys.
+        /// Eager aggregation is currently experimental. It computes `avg` from
+        /// a `Float64` sum and a count, which may change the rounding of the result
+        pub enable_eager_aggregation: bool, default = false
+
+        /// Aggregates are only pushed below joins if the estimated number of
+        /// groups of the pre-aggregation, based on the statistics of the table,
+        /// is at most this fraction of the estimated number of input rows
+        pub eager_aggregation_max_group_ratio: f64, default = 0.5
+
+        /// When set to true, `first_value` and `last_value` aggregates ordered
+        /// by a single non-nullable expression are rewritten to `min_by` and
+        /// `max_by`, which do not require their input to be sorted
+        pub enable_first_last_to_min_max_by: bool, default = false
     }
 }
 
@@ -2231,6 +2292,7 @@ pub enum ConfigFileType {
     #[cfg(feature = "parquet")]
     PARQUET,
     JSON,
+    AVRO,
 }
 
 /// Represents the configuration options available for handling different table formats within a data processing application.
@@ -2249,6 +2311,10 @@ pub struct TableOptions {
     /// Configuration options for JSON file handling.
     pub json: JsonOptions,
 
+    /// Configuration options for Avro file handling, such as the compression
+    /// codec of written files.
+    pub avro: AvroOptions,
+
     /// The current file format that the table operations should assume. This option allows
     /// for dynamic switching between the supported file types (e.g., CSV, Parquet, JSON).
     pub current_format: Option<ConfigFileType>,
@@ -2272,11 +2338,13 @@ impl ConfigField for TableOptions {
                 ConfigFileType::PARQUET => self.parquet.visit(v, "format", ""),
                 ConfigFileType::CSV => self.csv.visit(v, "format", ""),
                 ConfigFileType::JSON => self.json.visit(v, "format", ""),
+                ConfigFileType::AVRO => self.avro.visit(v, "format", ""),
             }
         } else {
             self.csv.visit(v, "csv", "");
             self.parquet.visit(v, "parquet", "");
             self.json.visit(v, "json", "");
+            self.avro.visit(v, "avro", "");
         }
     }
 
@@ -2308,6 +2376,7 @@ impl ConfigField for TableOptions {
                     ConfigFileType::PARQUET => self.parquet.set(rem, value),
                     ConfigFileType::CSV => self.csv.set(rem, value),
                     ConfigFileType::JSON => self.json.set(rem, value),
+                    ConfigFileType::AVRO => self.avro.set(rem, value),
                 }
             }
             _ => _config_err!("Config value \"{key}\" not found on TableOptions"),
@@ -3358,6 +3427,22 @@ config_namespace! {
     }
 }
 
+config_namespace! {
+    /// Options controlling Avro format
+    pub struct AvroOptions {
+        /// Compression codec of the data blocks of written Avro files. Valid
+        /// values are: null, deflate, snappy, zstd, bzip2 and xz. If not
+        /// specified, data blocks are not compressed.
+        pub codec: Option<String>, default = None
+        /// Name of the top-level record of the schema of written Avro files.
+        /// If not specified, the name derived from the Arrow schema is used.
+        pub schema_name: Option<String>, default = None
+        /// Namespace of the top-level record of the schema of written Avro
+        /// files. If not specified, the record has no namespace.
+        pub schema_namespace: Option<String>, default = None
+    }
+}
+
 pub trait OutputFormatExt: Display {}
 
 #[derive(Debug, Clone, PartialEq)]
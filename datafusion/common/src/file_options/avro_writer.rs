@@ -17,5 +17,87 @@
 
 //! Options related to how avro files should be written
 
-#[derive(Clone, Debug)]
-pub struct AvroWriterOptions {}
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::config::AvroOptions;
+use crate::error::{_config_err, DataFusionError, Result};
+
+/// Options for writing Avro object container files
+#[derive(Clone, Debug, Default)]
+pub struct AvroWriterOptions {
+    /// Compression codec of the data blocks, `None` if they are not compressed
+    pub codec: Option<AvroCodec>,
+    /// Name of the top-level record of the written schema
+    pub schema_name: Option<String>,
+    /// Namespace of the top-level record of the written schema
+    pub schema_namespace: Option<String>,
+}
+
+impl AvroWriterOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the compression codec of the data blocks
+    pub fn with_codec(mut self, codec: Option<AvroCodec>) -> Self {
+        self.codec = codec;
+        self
+    }
+}
+
+impl TryFrom<&AvroOptions> for AvroWriterOptions {
+    type Error = DataFusionError;
+
+    fn try_from(value: &AvroOptions) -> Result<Self> {
+        let codec = match value.codec.as_deref() {
+            Some(codec) if !codec.eq_ignore_ascii_case("null") => Some(codec.parse()?),
+            _ => None,
+        };
+        Ok(Self {
+            codec,
+            schema_name: value.schema_name.clone(),
+            schema_namespace: value.schema_namespace.clone(),
+        })
+    }
+}
+
+/// Compression codec of the data blocks of an Avro object container file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AvroCodec {
+    Deflate,
+    Snappy,
+    Zstd,
+    Bzip2,
+    Xz,
+}
+
+impl FromStr for AvroCodec {
+    type Err = DataFusionError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "deflate" => Ok(Self::Deflate),
+            "snappy" => Ok(Self::Snappy),
+            "zstd" | "zstandard" => Ok(Self::Zstd),
+            "bzip2" => Ok(Self::Bzip2),
+            "xz" => Ok(Self::Xz),
+            _ => _config_err!(
+                "Unknown Avro codec: {s}. Valid values are: null, deflate, snappy, zstd, bzip2 and xz"
+            ),
+        }
+    }
+}
+
+impl Display for AvroCodec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let codec = match self {
+            Self::Deflate => "deflate",
+            Self::Snappy => "snappy",
+            Self::Zstd => "zstandard",
+            Self::Bzip2 => "bzip2",
+            Self::Xz => "xz",
+        };
+        write!(f, "{codec}")
+    }
+}
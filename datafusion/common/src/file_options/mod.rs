@@ -33,7 +33,11 @@ mod tests {
     use crate::{
         Result,
         config::{ConfigFileType, TableOptions},
-        file_options::{csv_writer::CsvWriterOptions, json_writer::JsonWriterOptions},
+        file_options::{
+            avro_writer::{AvroCodec, AvroWriterOptions},
+            csv_writer::CsvWriterOptions,
+            json_writer::JsonWriterOptions,
+        },
         parsers::CompressionTypeVariant,
     };
 
@@ -317,4 +321,31 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    // for StatementOptions
+    fn test_writeroptions_avro_from_statement_options() -> Result<()> {
+        let mut option_map: HashMap<String, String> = HashMap::new();
+        option_map.insert("format.codec".to_owned(), "ZSTD".to_owned());
+        option_map.insert("format.schema_name".to_owned(), "event".to_owned());
+        option_map.insert("format.schema_namespace".to_owned(), "org".to_owned());
+
+        let mut table_config = TableOptions::new();
+        table_config.set_config_format(ConfigFileType::AVRO);
+        table_config.alter_with_string_hash_map(&option_map)?;
+
+        let avro_options = AvroWriterOptions::try_from(&table_config.avro)?;
+        assert_eq!(avro_options.codec, Some(AvroCodec::Zstd));
+        assert_eq!(avro_options.schema_name.as_deref(), Some("event"));
+        assert_eq!(avro_options.schema_namespace.as_deref(), Some("org"));
+
+        table_config.avro.codec = Some("null".to_owned());
+        let avro_options = AvroWriterOptions::try_from(&table_config.avro)?;
+        assert_eq!(avro_options.codec, None);
+
+        table_config.avro.codec = Some("lz4".to_owned());
+        assert!(AvroWriterOptions::try_from(&table_config.avro).is_err());
+
+        Ok(())
+    }
 }
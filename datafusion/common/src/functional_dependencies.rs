@@ -368,6 +368,17 @@ impl FunctionalDependencies {
                 // These joins preserve functional dependencies of the right side:
                 right_func_dependencies
             }
+            JoinType::LeftAsOf => {
+                // Each left row appears exactly once, so the functional
+                // dependencies of the left side are preserved. A right row may
+                // appear multiple times, or be replaced by NULL values:
+                right_func_dependencies.add_offset(left_cols_len);
+                right_func_dependencies =
+                    right_func_dependencies.with_dependency(Dependency::Multi);
+                right_func_dependencies.downgrade_dependencies();
+                left_func_dependencies.extend(right_func_dependencies);
+                left_func_dependencies
+            }
             JoinType::Full => {
                 // All of the functional dependencies are lost in a FULL join:
                 FunctionalDependencies::empty()
@@ -22,7 +22,7 @@ use std::{
     str::FromStr,
 };
 
-use crate::error::_not_impl_err;
+use crate::error::{_not_impl_err, _plan_err};
 use crate::{DataFusionError, Result};
 
 /// Join type
@@ -72,6 +72,21 @@ pub enum JoinType {
     /// Same logic as the LeftMark Join above, however it returns a record for each record from the
     /// right input.
     RightMark,
+    /// Left As-Of Join - Returns all rows from the left table, each joined with at most one row
+    /// from the right table.
+    ///
+    /// Among the right rows with equal join keys, the matching row is the one closest to the left
+    /// row that satisfies the match condition, a single comparison (`<`, `<=`, `>` or `>=`)
+    /// between a left and a right expression. For example, with the match condition
+    /// `left.ts >= right.ts`, the right row with the greatest `ts` not after `left.ts` is
+    /// returned. If no right row matches, NULL values are returned for the columns from the
+    /// right table.
+    ///
+    /// The join filter of a `LeftAsOf` join is exactly its match condition, the equality
+    /// predicates are the equijoin keys.
+    ///
+    /// This is the `ASOF JOIN ... MATCH_CONDITION (...)` of Snowflake and DuckDB.
+    LeftAsOf,
 }
 
 impl JoinType {
@@ -79,9 +94,15 @@ impl JoinType {
         self == JoinType::Left || self == JoinType::Right || self == JoinType::Full
     }
 
+    /// Returns true if this is an as-of join
+    pub fn is_asof(self) -> bool {
+        self == JoinType::LeftAsOf
+    }
+
     /// Returns the `JoinType` if the (2) inputs were swapped
     ///
-    /// Panics if [`Self::supports_swap`] returns false
+    /// Panics if [`Self::supports_swap`] returns false, use [`Self::try_swap`]
+    /// for a fallible version
     pub fn swap(&self) -> JoinType {
         match self {
             JoinType::Inner => JoinType::Inner,
@@ -94,9 +115,19 @@ impl JoinType {
             JoinType::RightAnti => JoinType::LeftAnti,
             JoinType::LeftMark => JoinType::RightMark,
             JoinType::RightMark => JoinType::LeftMark,
+            JoinType::LeftAsOf => panic!("{self} join does not support swapping inputs"),
         }
     }
 
+    /// Returns the `JoinType` if the (2) inputs were swapped, or an error if
+    /// [`Self::supports_swap`] returns false
+    pub fn try_swap(&self) -> Result<JoinType> {
+        if !self.supports_swap() {
+            return _plan_err!("{self} join does not support swapping inputs");
+        }
+        Ok(self.swap())
+    }
+
     /// Whether each side of the join is preserved for ON-clause filter pushdown.
     ///
     /// It is only correct to push ON-clause filters below a join for preserved
@@ -123,6 +154,7 @@ impl JoinType {
             JoinType::RightAnti => (true, false),
             JoinType::LeftMark => (false, true),
             JoinType::RightMark => (true, false),
+            JoinType::LeftAsOf => (false, true),
         }
     }
 
@@ -154,6 +186,7 @@ impl JoinType {
                 | JoinType::LeftAnti
                 | JoinType::LeftMark
                 | JoinType::RightSemi
+                | JoinType::LeftAsOf
         )
     }
 }
@@ -171,6 +204,7 @@ impl Display for JoinType {
             JoinType::RightAnti => "RightAnti",
             JoinType::LeftMark => "LeftMark",
             JoinType::RightMark => "RightMark",
+            JoinType::LeftAsOf => "LeftAsOf",
         };
         write!(f, "{join_type}")
     }
@@ -192,6 +226,7 @@ impl FromStr for JoinType {
             "RIGHTANTI" => Ok(JoinType::RightAnti),
             "LEFTMARK" => Ok(JoinType::LeftMark),
             "RIGHTMARK" => Ok(JoinType::RightMark),
+            "LEFTASOF" => Ok(JoinType::LeftAsOf),
             _ => _not_impl_err!("The join type {s} does not exist or is not implemented"),
         }
     }
@@ -239,3 +274,22 @@ impl JoinSide {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn try_swap() -> Result<()> {
+        assert_eq!(JoinType::Left.try_swap()?, JoinType::Right);
+        assert_eq!(JoinType::LeftMark.try_swap()?, JoinType::RightMark);
+
+        let err = JoinType::LeftAsOf.try_swap().unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("LeftAsOf join does not support swapping inputs"),
+            "{err}"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,41 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+use std::fmt::{self, Display, Formatter};
+
+/// How a `TABLESAMPLE` clause selects the rows of a relation.
+///
+/// Both methods keep every row with the same probability, but differ in the
+/// granularity of the decision.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Hash)]
+pub enum SampleMethod {
+    /// Every row is selected independently (also known as `ROW` sampling).
+    Bernoulli,
+    /// Whole blocks of rows, such as batches or Parquet row groups, are
+    /// selected together (also known as `BLOCK` sampling). Cheaper than
+    /// [`SampleMethod::Bernoulli`] as unselected blocks do not need to be
+    /// read, but the sample is less random.
+    System,
+}
+
+impl Display for SampleMethod {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            SampleMethod::Bernoulli => write!(f, "BERNOULLI"),
+            SampleMethod::System => write!(f, "SYSTEM"),
+        }
+    }
+}
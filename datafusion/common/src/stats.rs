@@ -460,6 +460,28 @@ impl Statistics {
         self
     }
 
+    /// Estimates the statistics of a random sample that keeps `fraction` of
+    /// the rows, such as the output of a `TABLESAMPLE` clause.
+    ///
+    /// Row counts and sizes are scaled by `fraction` and all other values
+    /// become inexact, except for sums, which are no longer known.
+    pub fn with_sample_fraction(mut self, fraction: f64) -> Self {
+        self.num_rows = self.num_rows.with_estimated_selectivity(fraction);
+        self.total_byte_size = self.total_byte_size.with_estimated_selectivity(fraction);
+        self.column_statistics = self
+            .column_statistics
+            .into_iter()
+            .map(|s| {
+                let mut s = s.to_inexact();
+                s.null_count = s.null_count.with_estimated_selectivity(fraction);
+                s.byte_size = s.byte_size.with_estimated_selectivity(fraction);
+                s.sum_value = Precision::Absent;
+                s
+            })
+            .collect();
+        self
+    }
+
     /// Project the statistics to the given column indices.
     ///
     /// For example, if we had statistics for columns `{"a", "b", "c"}`,
@@ -2252,6 +2274,39 @@ mod tests {
         assert_eq!(result.total_byte_size, Precision::Inexact(1200));
     }
 
+    #[test]
+    fn test_with_sample_fraction() {
+        let original_stats = Statistics {
+            num_rows: Precision::Exact(1000),
+            total_byte_size: Precision::Exact(8000),
+            column_statistics: vec![ColumnStatistics {
+                null_count: Precision::Exact(10),
+                max_value: Precision::Exact(ScalarValue::Int64(Some(100))),
+                min_value: Precision::Exact(ScalarValue::Int64(Some(1))),
+                sum_value: Precision::Exact(ScalarValue::Int64(Some(5000))),
+                distinct_count: Precision::Exact(100),
+                byte_size: Precision::Exact(8000),
+            }],
+        };
+
+        let result = original_stats.with_sample_fraction(0.25);
+        assert_eq!(result.num_rows, Precision::Inexact(250));
+        assert_eq!(result.total_byte_size, Precision::Inexact(2000));
+        let column = &result.column_statistics[0];
+        assert_eq!(column.null_count, Precision::Inexact(3));
+        assert_eq!(column.byte_size, Precision::Inexact(2000));
+        assert_eq!(
+            column.max_value,
+            Precision::Inexact(ScalarValue::Int64(Some(100)))
+        );
+        assert_eq!(
+            column.min_value,
+            Precision::Inexact(ScalarValue::Int64(Some(1)))
+        );
+        assert_eq!(column.sum_value, Precision::Absent);
+        assert_eq!(column.distinct_count, Precision::Inexact(100));
+    }
+
     #[test]
     fn test_with_fetch_total_byte_size_fallback() {
         // Test that total_byte_size falls back to scaling when not all columns have byte_size
@@ -385,7 +385,7 @@ mod tests {
     ) -> Result<Arc<dyn ExecutionPlan>> {
         let testdata = test_util::arrow_test_data();
         let store_root = format!("{testdata}/avro");
-        let format = AvroFormat {};
+        let format = AvroFormat::default();
         scan_format(
             state,
             &format,
@@ -20,7 +20,10 @@
 
 mod table;
 pub use datafusion_catalog_listing::helpers;
-pub use datafusion_catalog_listing::{ListingOptions, ListingTable, ListingTableConfig};
+pub use datafusion_catalog_listing::{
+    HivePartitionPathParser, ListingOptions, ListingTable, ListingTableConfig,
+    PartitionPathParser, TemplatePartitionPathParser,
+};
 // Keep for backwards compatibility until removed
 #[expect(deprecated)]
 pub use datafusion_datasource::PartitionedFileStream;
@@ -193,8 +193,13 @@ impl TableProviderFactory for ListingTableFactory {
         let config = ListingTableConfig::new(table_path)
             .with_listing_options(options.with_file_sort_order(cmd.order_exprs.clone()))
             .with_schema(resolved_schema);
+        let cache_manager = &state.runtime_env().cache_manager;
         let provider = ListingTable::try_new(config)?
-            .with_cache(state.runtime_env().cache_manager.get_file_statistic_cache());
+            .with_cache(cache_manager.get_file_statistic_cache())
+            .with_table_statistics_store(
+                session_state.resolve_table_ref(cmd.name.clone()).into(),
+                cache_manager.get_table_statistics_store(),
+            );
         let table = provider
             .with_definition(cmd.definition.clone())
             .with_constraints(cmd.constraints.clone())
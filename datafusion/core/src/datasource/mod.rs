@@ -32,6 +32,7 @@ mod view_test;
 pub use self::default_table_source::{
     DefaultTableSource, provider_as_source, source_as_provider,
 };
+pub use self::materialized_view::MaterializedView;
 pub use self::memory::MemTable;
 pub use self::view::ViewTable;
 pub use crate::catalog::TableProvider;
@@ -39,6 +40,7 @@ pub use crate::logical_expr::TableType;
 pub use datafusion_catalog::cte_worktable;
 pub use datafusion_catalog::default_table_source;
 pub use datafusion_catalog::empty;
+pub use datafusion_catalog::materialized_view;
 pub use datafusion_catalog::memory;
 pub use datafusion_catalog::stream;
 pub use datafusion_catalog::view;
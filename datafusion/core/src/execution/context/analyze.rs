@@ -0,0 +1,362 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use super::{DataFrame, Result, SessionContext};
+use crate::functions_aggregate::count::count_all;
+use crate::functions_aggregate::expr_fn::{approx_distinct, count, max, min};
+use arrow::array::{ArrayRef, new_empty_array};
+use arrow::compute::concat;
+use arrow::datatypes::{DataType, SchemaRef, TimeUnit};
+use arrow::record_batch::RecordBatch;
+use datafusion_catalog::TableProvider;
+use datafusion_common::stats::Precision;
+use datafusion_common::{
+    ColumnStatistics, SampleMethod, ScalarValue, Statistics, TableReference, internal_err,
+};
+use datafusion_execution::cache::cache_manager::TableStatistics;
+use datafusion_expr::{AnalyzeTable, Expr, LogicalPlanBuilder, cast, ident};
+use datafusion_physical_plan::operator_statistics::{
+    ColumnDistribution, ColumnDistributions, Histogram, HistogramBucket,
+};
+use std::sync::Arc;
+
+/// Maximum number of rows sampled to build the histograms and most common
+/// values of the analyzed columns
+const DISTRIBUTION_SAMPLE_SIZE: usize = 30_000;
+/// Maximum number of buckets of the histograms
+const HISTOGRAM_BUCKETS: usize = 100;
+/// Maximum number of most common values kept per column
+const MAX_MOST_COMMON_VALUES: usize = 100;
+/// Seed of the sample, so that analyzing the same data gives the same
+/// histograms
+const DISTRIBUTION_SAMPLE_SEED: u64 = 0;
+
+/// The aggregates computed for a single column by `ANALYZE TABLE`, as indices
+/// into the aggregate output
+struct ColumnAggregates {
+    non_null_count: usize,
+    min_max: Option<(usize, usize)>,
+    distinct_count: Option<usize>,
+}
+
+impl SessionContext {
+    /// Collects the statistics of a table and stores them in the
+    /// [`TableStatisticsStore`] of the session.
+    ///
+    /// A single scan of the table computes the row count and, for each
+    /// analyzed column, the null count, the min and max values and an
+    /// estimate of the number of distinct values using HyperLogLog.
+    ///
+    /// A second scan samples at most about [`DISTRIBUTION_SAMPLE_SIZE`] rows
+    /// to build an equi-depth histogram and the most common values of the
+    /// analyzed columns whose values can be ordered.
+    ///
+    /// [`TableStatisticsStore`]: datafusion_execution::cache::cache_manager::TableStatisticsStore
+    pub(super) async fn analyze_table(&self, cmd: AnalyzeTable) -> Result<DataFrame> {
+        let AnalyzeTable { name, columns, .. } = cmd;
+        let provider = self.table_provider(name.clone()).await?;
+        let table_ref = TableReference::from(self.state.read().resolve_table_ref(name));
+        let schema = provider.schema();
+
+        let mut aggregates = vec![count_all()];
+        let mut column_aggregates = Vec::with_capacity(schema.fields().len());
+        for field in schema.fields() {
+            let analyzed = columns
+                .as_ref()
+                .is_none_or(|columns| columns.contains(field.name()));
+            if !analyzed {
+                column_aggregates.push(None);
+                continue;
+            }
+
+            let column = ident(field.name());
+            let mut push = |aggregate: Expr| {
+                aggregates.push(aggregate);
+                aggregates.len() - 1
+            };
+            let non_null_count = push(count(column.clone()));
+            let min_max = supports_min_max(field.data_type())
+                .then(|| (push(min(column.clone())), push(max(column.clone()))));
+            let distinct_count = distinct_count_input(column, field.data_type())
+                .map(|input| push(approx_distinct(input)));
+            column_aggregates.push(Some(ColumnAggregates {
+                non_null_count,
+                min_max,
+                distinct_count,
+            }));
+        }
+
+        let batches = self
+            .read_table(Arc::clone(&provider))?
+            .aggregate(vec![], aggregates)?
+            .collect()
+            .await?;
+        let Some(batch) = batches.iter().find(|batch| batch.num_rows() == 1) else {
+            return internal_err!("Expected a single row of statistics for {table_ref}");
+        };
+
+        let num_rows = value_as_usize(batch, 0)?;
+        let column_statistics = column_aggregates
+            .iter()
+            .map(|aggregates| {
+                let Some(aggregates) = aggregates else {
+                    return Ok(ColumnStatistics::new_unknown());
+                };
+                let non_null_count = value_as_usize(batch, aggregates.non_null_count)?;
+                let mut statistics = ColumnStatistics::new_unknown()
+                    .with_null_count(Precision::Exact(num_rows - non_null_count));
+                if let Some((min_index, max_index)) = aggregates.min_max {
+                    statistics = statistics
+                        .with_min_value(non_null_value(batch, min_index)?)
+                        .with_max_value(non_null_value(batch, max_index)?);
+                }
+                if let Some(index) = aggregates.distinct_count {
+                    // HyperLogLog only estimates the number of distinct values
+                    statistics = statistics.with_distinct_count(Precision::Inexact(
+                        value_as_usize(batch, index)?,
+                    ));
+                }
+                Ok(statistics)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let statistics = Statistics {
+            num_rows: Precision::Exact(num_rows),
+            total_byte_size: Precision::Absent,
+            column_statistics,
+        };
+
+        let distribution_columns = schema
+            .fields()
+            .iter()
+            .zip(&column_aggregates)
+            .enumerate()
+            .filter(|(_, (field, aggregates))| {
+                aggregates.is_some() && supports_min_max(field.data_type())
+            })
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+        let mut table_statistics = TableStatistics::new(statistics);
+        if num_rows > 0 && !distribution_columns.is_empty() {
+            let column_distributions = self
+                .sample_column_distributions(
+                    provider,
+                    &distribution_columns,
+                    &table_statistics.statistics,
+                )
+                .await?;
+            table_statistics = table_statistics
+                .with_column_distributions(Arc::new(column_distributions));
+        }
+
+        self.runtime_env()
+            .cache_manager
+            .get_table_statistics_store()
+            .put(&table_ref, Arc::new(table_statistics));
+        self.return_empty_dataframe()
+    }
+
+    /// Builds the [`ColumnDistributions`] of the table, with the distributions
+    /// of the given columns computed from a sample of the table.
+    ///
+    /// The distributions of the sample are scaled to the row count, null count
+    /// and distinct count of the whole table in `statistics`.
+    async fn sample_column_distributions(
+        &self,
+        provider: Arc<dyn TableProvider>,
+        columns: &[usize],
+        statistics: &Statistics,
+    ) -> Result<ColumnDistributions> {
+        let schema: SchemaRef = provider.schema();
+        let num_rows = *statistics.num_rows.get_value().unwrap_or(&0);
+        let (state, plan) = self
+            .read_table(provider)?
+            .select(
+                columns
+                    .iter()
+                    .map(|index| ident(schema.field(*index).name())),
+            )?
+            .into_parts();
+        let fraction = DISTRIBUTION_SAMPLE_SIZE as f64 / num_rows as f64;
+        let plan = if fraction < 1.0 {
+            LogicalPlanBuilder::from(plan)
+                .sample(
+                    SampleMethod::Bernoulli,
+                    fraction,
+                    Some(DISTRIBUTION_SAMPLE_SEED),
+                )?
+                .build()?
+        } else {
+            plan
+        };
+        let batches = DataFrame::new(state, plan).collect().await?;
+
+        let mut distributions = vec![None; schema.fields().len()];
+        for (sample_index, &index) in columns.iter().enumerate() {
+            let arrays = batches
+                .iter()
+                .map(|batch| batch.column(sample_index).as_ref())
+                .collect::<Vec<_>>();
+            let values: ArrayRef = if arrays.is_empty() {
+                new_empty_array(schema.field(index).data_type())
+            } else {
+                concat(&arrays)?
+            };
+            let sample = ColumnDistribution::try_from_array(
+                &values,
+                HISTOGRAM_BUCKETS,
+                MAX_MOST_COMMON_VALUES,
+            )?;
+            distributions[index] = scale_distribution(
+                &sample,
+                num_rows,
+                &statistics.column_statistics[index],
+            )?
+            .map(Arc::new);
+        }
+        Ok(ColumnDistributions::new(distributions))
+    }
+}
+
+/// Scales the distribution of a sample of a column to the whole column,
+/// described by its row count and column statistics.
+///
+/// Returns `None` if the sample has no non-null values.
+fn scale_distribution(
+    sample: &ColumnDistribution,
+    num_rows: usize,
+    statistics: &ColumnStatistics,
+) -> Result<Option<ColumnDistribution>> {
+    let sample_non_null = sample.num_rows() - sample.null_count();
+    if sample_non_null == 0 {
+        return Ok(None);
+    }
+    let null_count = *statistics.null_count.get_value().unwrap_or(&0);
+    let non_null = num_rows.saturating_sub(null_count);
+    let sample_distinct = sample.distinct_count().unwrap_or(1).max(1);
+    let distinct_count = statistics
+        .distinct_count
+        .get_value()
+        .copied()
+        .unwrap_or(sample_distinct);
+
+    // Rounding down keeps the scaled rows within the rows of the column
+    let row_factor = non_null as f64 / sample_non_null as f64;
+    let scale_rows = |rows: usize| (rows as f64 * row_factor) as usize;
+    // Values that are rare in the column may be missing from the sample
+    let distinct_factor = (distinct_count as f64 / sample_distinct as f64).max(1.0);
+
+    let histogram = sample
+        .histogram()
+        .map(|histogram| {
+            let buckets = histogram
+                .buckets()
+                .iter()
+                .map(|bucket| {
+                    let num_rows = scale_rows(bucket.num_rows);
+                    let distinct_count =
+                        (bucket.distinct_count as f64 * distinct_factor).round() as usize;
+                    HistogramBucket {
+                        lower: bucket.lower.clone(),
+                        upper: bucket.upper.clone(),
+                        num_rows,
+                        distinct_count: distinct_count.clamp(1, num_rows.max(1)),
+                    }
+                })
+                .collect();
+            Histogram::try_new(buckets)
+        })
+        .transpose()?;
+    let most_common_values = sample
+        .most_common_values()
+        .iter()
+        .map(|(value, rows)| (value.clone(), scale_rows(*rows)))
+        .collect();
+
+    ColumnDistribution::try_new(
+        num_rows,
+        null_count,
+        Some(distinct_count),
+        histogram,
+        most_common_values,
+    )
+    .map(Some)
+}
+
+/// Returns true if the min and max values of columns of the given type are
+/// collected
+fn supports_min_max(data_type: &DataType) -> bool {
+    (data_type.is_primitive() && !matches!(data_type, DataType::Interval(_)))
+        || matches!(
+            data_type,
+            DataType::Boolean
+                | DataType::Utf8
+                | DataType::LargeUtf8
+                | DataType::Utf8View
+                | DataType::Binary
+                | DataType::LargeBinary
+                | DataType::BinaryView
+        )
+}
+
+/// Returns the input of `approx_distinct` used to estimate the number of
+/// distinct values of a column, casting types `approx_distinct` does not
+/// support to a type it does
+fn distinct_count_input(column: Expr, data_type: &DataType) -> Option<Expr> {
+    match data_type {
+        DataType::Int8
+        | DataType::Int16
+        | DataType::Int32
+        | DataType::Int64
+        | DataType::UInt8
+        | DataType::UInt16
+        | DataType::UInt32
+        | DataType::UInt64
+        | DataType::Date32
+        | DataType::Date64
+        | DataType::Time32(TimeUnit::Second | TimeUnit::Millisecond)
+        | DataType::Time64(TimeUnit::Microsecond | TimeUnit::Nanosecond)
+        | DataType::Timestamp(_, _)
+        | DataType::Utf8
+        | DataType::LargeUtf8
+        | DataType::Utf8View
+        | DataType::Binary
+        | DataType::LargeBinary => Some(column),
+        DataType::BinaryView => Some(cast(column, DataType::Binary)),
+        data_type if supports_min_max(data_type) => Some(cast(column, DataType::Utf8)),
+        _ => None,
+    }
+}
+
+fn value_as_usize(batch: &RecordBatch, index: usize) -> Result<usize> {
+    match ScalarValue::try_from_array(batch.column(index), 0)? {
+        ScalarValue::Int64(Some(value)) => Ok(value as usize),
+        ScalarValue::UInt64(Some(value)) => Ok(value as usize),
+        other => internal_err!("Expected a count, got {other:?}"),
+    }
+}
+
+/// Returns the value of an aggregate, which is null if the column only
+/// contains nulls
+fn non_null_value(batch: &RecordBatch, index: usize) -> Result<Precision<ScalarValue>> {
+    let value = ScalarValue::try_from_array(batch.column(index), 0)?;
+    Ok(if value.is_null() {
+        Precision::Absent
+    } else {
+        Precision::Exact(value)
+    })
+}
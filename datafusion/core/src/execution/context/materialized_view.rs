@@ -0,0 +1,90 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use super::{DataFrame, Result, SessionContext};
+use crate::datasource::{MaterializedView, MemTable};
+use datafusion_common::{exec_err, not_impl_err, plan_err};
+use datafusion_expr::{CreateView, RefreshMaterializedView};
+use std::sync::Arc;
+
+impl SessionContext {
+    /// Creates a materialized view and stores its results.
+    ///
+    /// The results are stored in the table created by the
+    /// [`MaterializedViewStorageFactory`] of the session, or in memory if
+    /// none is configured. While the view is fresh, the optimizer may read
+    /// the stored results to answer queries.
+    ///
+    /// [`MaterializedViewStorageFactory`]: crate::execution::session_state::MaterializedViewStorageFactory
+    pub(super) async fn create_materialized_view(
+        &self,
+        cmd: CreateView,
+    ) -> Result<DataFrame> {
+        let CreateView {
+            name,
+            input,
+            or_replace,
+            definition,
+            temporary,
+            ..
+        } = cmd;
+
+        if temporary {
+            return not_impl_err!("Temporary materialized views not supported");
+        }
+        if !or_replace && self.table_exist(name.clone())? {
+            return exec_err!("Table '{name}' already exists");
+        }
+
+        // Store the analyzed plan, which the optimizer compares to the
+        // analyzed plans of queries
+        let state = self.state();
+        let plan = state.analyzer().execute_and_check(
+            Arc::unwrap_or_clone(input),
+            state.config_options(),
+            |_, _| {},
+        )?;
+        let schema = Arc::clone(plan.schema().inner());
+        let storage = match state.materialized_view_storage_factory() {
+            Some(factory) => factory.create(&name, schema, &state)?,
+            None => Arc::new(MemTable::try_new(schema, vec![vec![]])?),
+        };
+        let view = Arc::new(MaterializedView::try_new(plan, definition, storage)?);
+        view.refresh(&state).await?;
+
+        if or_replace {
+            self.deregister_table(name.clone())?;
+        }
+        self.register_table(name.clone(), Arc::clone(&view) as _)?;
+        self.state.write().register_materialized_view(name, view);
+        self.return_empty_dataframe()
+    }
+
+    /// Recomputes and stores the results of a materialized view
+    pub(super) async fn refresh_materialized_view(
+        &self,
+        cmd: RefreshMaterializedView,
+    ) -> Result<DataFrame> {
+        let RefreshMaterializedView { name, .. } = cmd;
+        let provider = self.table_provider(name.clone()).await?;
+        let Some(view) = provider.downcast_ref::<MaterializedView>() else {
+            return plan_err!("'{name}' is not a materialized view");
+        };
+        view.refresh(&self.state()).await?;
+        self.return_empty_dataframe()
+    }
+}
@@ -75,8 +75,8 @@ use datafusion_common::{
 };
 pub use datafusion_execution::TaskContext;
 use datafusion_execution::cache::cache_manager::{
-    DEFAULT_LIST_FILES_CACHE_MEMORY_LIMIT, DEFAULT_LIST_FILES_CACHE_TTL,
-    DEFAULT_METADATA_CACHE_LIMIT,
+    DEFAULT_BYTE_RANGE_CACHE_LIMIT, DEFAULT_LIST_FILES_CACHE_MEMORY_LIMIT,
+    DEFAULT_LIST_FILES_CACHE_TTL, DEFAULT_METADATA_CACHE_LIMIT,
 };
 pub use datafusion_execution::config::SessionConfig;
 use datafusion_execution::disk_manager::{
@@ -106,8 +106,10 @@ use object_store::ObjectStore;
 use parking_lot::RwLock;
 use url::Url;
 
+mod analyze;
 mod csv;
 mod json;
+mod materialized_view;
 #[cfg(feature = "parquet")]
 mod parquet;
 
@@ -717,6 +719,12 @@ impl SessionContext {
                     DdlStatement::DropFunction(cmd) => {
                         Box::pin(self.drop_function(cmd)).await
                     }
+                    DdlStatement::AnalyzeTable(cmd) => {
+                        Box::pin(self.analyze_table(cmd)).await
+                    }
+                    DdlStatement::RefreshMaterializedView(cmd) => {
+                        Box::pin(self.refresh_materialized_view(cmd)).await
+                    }
                     ddl => Ok(DataFrame::new(self.state(), LogicalPlan::Ddl(ddl))),
                 }
             }
@@ -934,12 +942,16 @@ impl SessionContext {
     }
 
     async fn create_view(&self, cmd: CreateView) -> Result<DataFrame> {
+        if cmd.materialized {
+            return self.create_materialized_view(cmd).await;
+        }
         let CreateView {
             name,
             input,
             or_replace,
             definition,
             temporary,
+            ..
         } = cmd;
 
         let view = self.table(name.clone()).await;
@@ -1183,6 +1195,10 @@ impl SessionContext {
                 let duration = Self::parse_duration(variable, value)?;
                 builder.with_object_list_cache_ttl(Some(duration))
             }
+            "byte_range_cache_limit" => {
+                let limit = Self::parse_capacity_limit(variable, value)?;
+                builder.with_byte_range_cache_limit(limit)
+            }
             _ => return plan_err!("Unknown runtime configuration: {variable}"),
             // Remember to update `reset_runtime_variable()` when adding new options
         };
@@ -1222,6 +1238,10 @@ impl SessionContext {
                 builder =
                     builder.with_object_list_cache_ttl(DEFAULT_LIST_FILES_CACHE_TTL);
             }
+            "byte_range_cache_limit" => {
+                builder =
+                    builder.with_byte_range_cache_limit(DEFAULT_BYTE_RANGE_CACHE_LIMIT);
+            }
             _ => return plan_err!("Unknown runtime configuration: {variable}"),
         };
 
@@ -1403,13 +1423,14 @@ impl SessionContext {
     ) -> Result<bool> {
         let table_ref = table_ref.into();
         let table = table_ref.table().to_owned();
-        let maybe_schema = {
+        let (resolved, maybe_schema) = {
             let state = self.state.read();
             let resolved = state.resolve_table_ref(table_ref.clone());
-            state
+            let maybe_schema = state
                 .catalog_list()
                 .catalog(&resolved.catalog)
-                .and_then(|c| c.schema(&resolved.schema))
+                .and_then(|c| c.schema(&resolved.schema));
+            (resolved, maybe_schema)
         };
 
         if let Some(schema) = maybe_schema
@@ -1417,10 +1438,19 @@ impl SessionContext {
             && table_provider.table_type() == table_type
         {
             schema.deregister_table(&table)?;
-            if table_type == TableType::Base
-                && let Some(lfc) = self.runtime_env().cache_manager.get_list_files_cache()
-            {
-                lfc.drop_table_entries(&Some(table_ref))?;
+            if table_type == TableType::View {
+                self.state
+                    .write()
+                    .deregister_materialized_view(resolved.clone());
+            }
+            if table_type == TableType::Base {
+                let cache_manager = &self.runtime_env().cache_manager;
+                if let Some(lfc) = cache_manager.get_list_files_cache() {
+                    lfc.drop_table_entries(&Some(table_ref))?;
+                }
+                cache_manager
+                    .get_table_statistics_store()
+                    .remove(&resolved.into());
             }
             return Ok(true);
         }
@@ -1736,6 +1766,9 @@ impl SessionContext {
     ///
     /// This method is `async` because it might need to resolve the schema.
     ///
+    /// The table uses the statistics collected by `ANALYZE TABLE` as
+    /// estimates.
+    ///
     /// [`ObjectStore`]: object_store::ObjectStore
     pub async fn register_listing_table(
         &self,
@@ -1753,7 +1786,16 @@ impl SessionContext {
         let config = ListingTableConfig::new(table_path)
             .with_listing_options(options)
             .with_schema(resolved_schema);
-        let table = ListingTable::try_new(config)?.with_definition(sql_definition);
+        let table_ref: TableReference = table_ref.into();
+        let resolved = self.state.read().resolve_table_ref(table_ref.clone());
+        let table = ListingTable::try_new(config)?
+            .with_definition(sql_definition)
+            .with_table_statistics_store(
+                resolved.into(),
+                self.runtime_env()
+                    .cache_manager
+                    .get_table_statistics_store(),
+            );
         self.register_table(table_ref, Arc::new(table))?;
         Ok(())
     }
@@ -1857,10 +1899,15 @@ impl SessionContext {
     ) -> Result<Option<Arc<dyn TableProvider>>> {
         let table_ref = table_ref.into();
         let table = table_ref.table().to_owned();
-        self.state
+        let provider = self
+            .state
             .read()
-            .schema_for_ref(table_ref)?
-            .deregister_table(&table)
+            .schema_for_ref(table_ref.clone())?
+            .deregister_table(&table)?;
+        if provider.is_some() {
+            self.state.write().deregister_materialized_view(table_ref);
+        }
+        Ok(provider)
     }
 
     /// Return `true` if the specified table exists in the schema provider.
@@ -2088,9 +2135,11 @@ pub trait QueryPlanner: Debug {
 /// DataFusion will parse `CREATE FUNCTION` statements into [`CreateFunction`]
 /// structs and pass them to the [`create`](Self::create) method.
 ///
-/// Note there is no default implementation of this trait provided in DataFusion,
-/// because the implementation and requirements vary widely. Please see
-/// [function_factory example] for a reference implementation.
+/// By default, [`SqlFunctionFactory`] creates functions defined by SQL
+/// expressions and queries. Please see [function_factory example] for an
+/// example of a custom implementation.
+///
+/// [`SqlFunctionFactory`]: crate::execution::SqlFunctionFactory
 ///
 /// [function_factory example]: https://github.com/apache/datafusion/blob/main/datafusion-examples/examples/builtin_functions/function_factory.rs
 ///
@@ -2262,15 +2311,19 @@ mod tests {
     use arrow_schema::FieldRef;
     use datafusion_common::DataFusionError;
     use datafusion_common::datatype::DataTypeExt;
+    use datafusion_common::stats::Precision;
+    use datafusion_physical_plan::operator_statistics::ColumnDistributions;
     use std::error::Error;
     use std::path::PathBuf;
 
-    use datafusion_common::test_util::batches_to_string;
+    use datafusion_common::test_util::{batches_to_sort_string, batches_to_string};
     use datafusion_common_runtime::SpawnedTask;
     use insta::{allow_duplicates, assert_snapshot};
 
     use crate::catalog::SchemaProvider;
-    use crate::execution::session_state::SessionStateBuilder;
+    use crate::execution::session_state::{
+        MaterializedViewStorageFactory, SessionStateBuilder,
+    };
     use crate::physical_planner::PhysicalPlanner;
     use async_trait::async_trait;
     use datafusion_expr::planner::TypePlanner;
@@ -2368,6 +2421,34 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn register_csv_uses_analyzed_statistics() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let ctx = create_ctx(&tmp_dir, 4).await?;
+        let provider = ctx.table_provider("test").await?;
+        assert_eq!(provider.statistics(), None);
+
+        ctx.sql("ANALYZE TABLE test").await?.collect().await?;
+        let num_rows = ctx.table("test").await?.count().await?;
+        let statistics = provider.statistics().unwrap();
+        assert_eq!(statistics.num_rows, Precision::Inexact(num_rows));
+
+        let collected = ctx
+            .runtime_env()
+            .cache_manager
+            .get_table_statistics_store()
+            .get(&TableReference::full("datafusion", "public", "test"))
+            .unwrap();
+        let distributions = collected
+            .column_distributions::<ColumnDistributions>()
+            .unwrap();
+        let c2 = distributions.column(1).unwrap();
+        assert_eq!(c2.num_rows(), num_rows);
+        assert!(c2.histogram().is_some());
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn send_context_to_threads() -> Result<()> {
         // ensure SessionContexts can be used in a multi-threaded
@@ -2997,4 +3078,90 @@ mod tests {
             assert!(have.unwrap_err().to_string().contains(MEMORY_LIMIT));
         }
     }
+
+    #[derive(Debug, Default)]
+    struct RecordingStorageFactory {
+        tables: parking_lot::Mutex<Vec<Arc<MemTable>>>,
+    }
+
+    impl MaterializedViewStorageFactory for RecordingStorageFactory {
+        fn create(
+            &self,
+            _name: &TableReference,
+            schema: SchemaRef,
+            _session_state: &SessionState,
+        ) -> Result<Arc<dyn TableProvider>> {
+            let table = Arc::new(MemTable::try_new(schema, vec![vec![]])?);
+            self.tables.lock().push(Arc::clone(&table));
+            Ok(table)
+        }
+    }
+
+    #[tokio::test]
+    async fn materialized_view_storage_factory() -> Result<()> {
+        let factory = Arc::new(RecordingStorageFactory::default());
+        let state = SessionStateBuilder::new()
+            .with_default_features()
+            .with_materialized_view_storage_factory(Some(
+                Arc::clone(&factory) as Arc<dyn MaterializedViewStorageFactory>
+            ))
+            .build();
+        let ctx = SessionContext::new_with_state(state);
+        ctx.sql("CREATE TABLE t AS VALUES (1), (2)")
+            .await?
+            .collect()
+            .await?;
+        ctx.sql("CREATE MATERIALIZED VIEW mv AS SELECT column1 * 10 AS x FROM t")
+            .await?
+            .collect()
+            .await?;
+
+        let storage = factory.tables.lock().clone();
+        assert_eq!(storage.len(), 1);
+        let read_storage = async || {
+            let batches = ctx
+                .read_table(Arc::clone(&storage[0]) as Arc<dyn TableProvider>)?
+                .collect()
+                .await?;
+            Ok::<_, DataFusionError>(batches_to_sort_string(&batches))
+        };
+        assert_snapshot!(read_storage().await?, @r"
+        +----+
+        | x  |
+        +----+
+        | 10 |
+        | 20 |
+        +----+
+        ");
+
+        let state = ctx.state();
+        let view = state.materialized_views().values().next().unwrap();
+        assert!(view.is_fresh());
+
+        // Planning the insert keeps the view fresh until it is executed
+        let insert = ctx.sql("INSERT INTO t VALUES (3)").await?;
+        let plan = insert.create_physical_plan().await?;
+        assert!(view.is_fresh());
+        crate::physical_plan::collect(plan, ctx.task_ctx()).await?;
+        assert!(!view.is_fresh());
+
+        ctx.sql("REFRESH MATERIALIZED VIEW mv")
+            .await?
+            .collect()
+            .await?;
+        assert!(view.is_fresh());
+        assert_snapshot!(read_storage().await?, @r"
+        +----+
+        | x  |
+        +----+
+        | 10 |
+        | 20 |
+        | 30 |
+        +----+
+        ");
+
+        ctx.sql("DROP VIEW mv").await?.collect().await?;
+        assert!(ctx.state().materialized_views().is_empty());
+        Ok(())
+    }
 }
@@ -0,0 +1,147 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`MarkStaleExec`] to invalidate materialized views once a DML statement
+//! modified the tables they read from
+
+use std::fmt;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use arrow::array::RecordBatch;
+use arrow_schema::SchemaRef;
+use datafusion_common::tree_node::TreeNodeRecursion;
+use datafusion_common::{Result, Statistics, assert_eq_or_internal_err};
+use datafusion_execution::TaskContext;
+use datafusion_physical_expr_common::physical_expr::PhysicalExpr;
+use datafusion_physical_plan::{
+    DisplayAs, DisplayFormatType, ExecutionPlan, PlanProperties, RecordBatchStream,
+    SendableRecordBatchStream,
+};
+use futures::{Stream, StreamExt};
+
+use crate::datasource::MaterializedView;
+
+/// Passes through the results of a DML statement, and marks the
+/// materialized views reading from its target as stale once the statement
+/// finished executing.
+///
+/// Views are also marked stale if the statement fails, as the target may
+/// have been partially modified.
+#[derive(Debug)]
+pub(crate) struct MarkStaleExec {
+    input: Arc<dyn ExecutionPlan>,
+    views: Vec<Arc<MaterializedView>>,
+}
+
+impl MarkStaleExec {
+    pub(crate) fn new(
+        input: Arc<dyn ExecutionPlan>,
+        views: Vec<Arc<MaterializedView>>,
+    ) -> Self {
+        Self { input, views }
+    }
+}
+
+impl DisplayAs for MarkStaleExec {
+    fn fmt_as(&self, _t: DisplayFormatType, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "MarkStaleExec: views={}", self.views.len())
+    }
+}
+
+impl ExecutionPlan for MarkStaleExec {
+    fn name(&self) -> &str {
+        "MarkStaleExec"
+    }
+
+    fn properties(&self) -> &Arc<PlanProperties> {
+        self.input.properties()
+    }
+
+    fn maintains_input_order(&self) -> Vec<bool> {
+        vec![true]
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
+        vec![&self.input]
+    }
+
+    fn apply_expressions(
+        &self,
+        _f: &mut dyn FnMut(&dyn PhysicalExpr) -> Result<TreeNodeRecursion>,
+    ) -> Result<TreeNodeRecursion> {
+        Ok(TreeNodeRecursion::Continue)
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        mut children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        assert_eq_or_internal_err!(
+            children.len(),
+            1,
+            "MarkStaleExec requires exactly one child"
+        );
+        Ok(Arc::new(Self::new(
+            children.swap_remove(0),
+            self.views.clone(),
+        )))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let input = self.input.execute(partition, context)?;
+        Ok(Box::pin(MarkStaleStream {
+            input,
+            views: self.views.clone(),
+        }))
+    }
+
+    fn partition_statistics(&self, partition: Option<usize>) -> Result<Arc<Statistics>> {
+        self.input.partition_statistics(partition)
+    }
+}
+
+struct MarkStaleStream {
+    input: SendableRecordBatchStream,
+    views: Vec<Arc<MaterializedView>>,
+}
+
+impl Stream for MarkStaleStream {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let poll = self.input.poll_next_unpin(cx);
+        if matches!(poll, Poll::Ready(None | Some(Err(_)))) {
+            self.views.iter().for_each(|view| view.mark_stale());
+        }
+        poll
+    }
+}
+
+impl RecordBatchStream for MarkStaleStream {
+    fn schema(&self) -> SchemaRef {
+        self.input.schema()
+    }
+}
@@ -18,9 +18,16 @@
 //! Shared state for query planning and execution.
 
 pub mod context;
+pub mod result_cache;
 pub mod session_state;
+#[cfg(feature = "sql")]
+pub mod sql_macro;
+pub use result_cache::ResultCache;
 pub use session_state::{SessionState, SessionStateBuilder};
+#[cfg(feature = "sql")]
+pub use sql_macro::SqlFunctionFactory;
 
+mod materialized_view;
 mod session_state_defaults;
 
 pub use session_state_defaults::SessionStateDefaults;
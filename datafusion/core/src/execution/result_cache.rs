@@ -0,0 +1,799 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`ResultCache`] to reuse the results of repeated queries
+
+use std::collections::hash_map::DefaultHasher;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::pin::Pin;
+use std::sync::{Arc, Weak};
+use std::task::{Context, Poll};
+
+use arrow::array::RecordBatch;
+use arrow_schema::SchemaRef;
+use datafusion_common::tree_node::TreeNodeRecursion;
+use datafusion_common::{Result, Statistics, assert_eq_or_internal_err};
+use datafusion_execution::TaskContext;
+use datafusion_execution::cache::TableScopedPath;
+use datafusion_execution::cache::lru_queue::LruQueue;
+use datafusion_expr::{Expr, LogicalPlan};
+use datafusion_optimizer::LogicalPlanSignature;
+use datafusion_physical_expr_common::physical_expr::PhysicalExpr;
+use datafusion_physical_plan::{
+    DisplayAs, DisplayFormatType, ExecutionPlan, PlanProperties, RecordBatchStream,
+    SendableRecordBatchStream,
+};
+use futures::{Stream, StreamExt};
+use parking_lot::Mutex;
+
+use crate::catalog::TableProvider;
+use crate::datasource::listing::ListingTable;
+use crate::datasource::memory::MemorySourceConfig;
+use crate::datasource::{MemTable, source_as_provider};
+use crate::execution::SessionState;
+
+/// Caches the results of queries, so that repeated executions of the same
+/// query are answered without reading their inputs again.
+///
+/// Results are keyed by the [`LogicalPlanSignature`] of the optimized
+/// [`LogicalPlan`], and are only reused if the tables the query reads from
+/// have not changed since:
+///
+/// * A [`ListingTable`] is unchanged while the versions (e-tags, or last
+///   modified times) of its files, as recorded by the list files cache of the
+///   [`CacheManager`], are unchanged. Queries over tables whose listing is not
+///   cached, e.g. tables over a single file or when the list files cache is
+///   disabled, are not cached.
+/// * A [`MemTable`] can only be changed by DML statements.
+///
+/// Planning a DML statement (`INSERT`, `DELETE`, `UPDATE`, ...) invalidates
+/// all results read from the target table, as does its completion.
+///
+/// Queries that read from any other [`TableProvider`], or that contain
+/// volatile expressions such as `random()`, are never cached.
+///
+/// Results are kept within a memory budget and are evicted in least recently
+/// used order. The cache is opt-in, see
+/// [`SessionStateBuilder::with_result_cache`].
+///
+/// [`CacheManager`]: datafusion_execution::cache::cache_manager::CacheManager
+/// [`SessionStateBuilder::with_result_cache`]: crate::execution::SessionStateBuilder::with_result_cache
+pub struct ResultCache {
+    memory_limit: usize,
+    state: Mutex<ResultCacheState>,
+}
+
+struct ResultCacheState {
+    lru_queue: LruQueue<LogicalPlanSignature, CachedResult>,
+    memory_used: usize,
+    /// Incremented on every invalidation, used to discard results computed
+    /// concurrently with a DML statement
+    generation: u64,
+    hits: usize,
+    misses: usize,
+}
+
+#[derive(Debug)]
+struct CachedResult {
+    plan: LogicalPlan,
+    tables: Vec<ScannedTable>,
+    schema: SchemaRef,
+    partitions: Vec<Vec<RecordBatch>>,
+    size: usize,
+}
+
+/// A table read by a query
+#[derive(Debug, Clone)]
+struct ScannedTable {
+    provider: Weak<dyn TableProvider>,
+    version: TableVersion,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TableVersion {
+    /// The table can only be changed by DML statements
+    Unversioned,
+    /// Fingerprint of the versions of the table's files
+    Files(u64),
+    /// The table's files are not in the list files cache
+    Unknown,
+}
+
+impl ScannedTable {
+    /// Returns `None` if results read from `provider` can not be cached
+    fn try_new(provider: &Arc<dyn TableProvider>, state: &SessionState) -> Option<Self> {
+        let version = if let Some(table) = provider.downcast_ref::<ListingTable>() {
+            listing_table_version(table, state)
+        } else if provider.downcast_ref::<MemTable>().is_some() {
+            TableVersion::Unversioned
+        } else {
+            return None;
+        };
+
+        Some(Self {
+            provider: Arc::downgrade(provider),
+            version,
+        })
+    }
+
+    fn is(&self, provider: &Arc<dyn TableProvider>) -> bool {
+        Weak::ptr_eq(&self.provider, &Arc::downgrade(provider))
+    }
+
+    fn is_dropped(&self) -> bool {
+        self.provider.strong_count() == 0
+    }
+}
+
+impl PartialEq for ScannedTable {
+    fn eq(&self, other: &Self) -> bool {
+        Weak::ptr_eq(&self.provider, &other.provider) && self.version == other.version
+    }
+}
+
+fn listing_table_version(table: &ListingTable, state: &SessionState) -> TableVersion {
+    let Some(list_files_cache) = state.runtime_env().cache_manager.get_list_files_cache()
+    else {
+        return TableVersion::Unknown;
+    };
+
+    let mut hasher = DefaultHasher::new();
+    for table_path in table.table_paths() {
+        let key = TableScopedPath {
+            table: table_path.get_table_ref().clone(),
+            path: table_path.prefix().clone(),
+        };
+        let Some(cached) = list_files_cache.get(&key) else {
+            return TableVersion::Unknown;
+        };
+        for meta in cached.files.iter() {
+            meta.location.hash(&mut hasher);
+            meta.e_tag.hash(&mut hasher);
+            meta.last_modified.hash(&mut hasher);
+            meta.size.hash(&mut hasher);
+        }
+    }
+    TableVersion::Files(hasher.finish())
+}
+
+/// Returns the tables read by `plan`, or `None` if its results can not be
+/// cached
+fn scanned_tables(
+    plan: &LogicalPlan,
+    state: &SessionState,
+) -> Result<Option<Vec<ScannedTable>>> {
+    let mut tables = vec![];
+    let mut cacheable = true;
+    plan.apply_with_subqueries(|node| {
+        let supported = match node {
+            LogicalPlan::TableScan(scan) => match source_as_provider(&scan.source)
+                .ok()
+                .and_then(|provider| ScannedTable::try_new(&provider, state))
+            {
+                Some(table) => {
+                    tables.push(table);
+                    true
+                }
+                None => false,
+            },
+            LogicalPlan::Dml(_)
+            | LogicalPlan::Ddl(_)
+            | LogicalPlan::Copy(_)
+            | LogicalPlan::Statement(_)
+            | LogicalPlan::Explain(_)
+            | LogicalPlan::Analyze(_)
+            | LogicalPlan::DescribeTable(_)
+            | LogicalPlan::Extension(_) => false,
+            _ => true,
+        };
+        if supported && !node.expressions().iter().any(Expr::is_volatile) {
+            Ok(TreeNodeRecursion::Continue)
+        } else {
+            cacheable = false;
+            Ok(TreeNodeRecursion::Stop)
+        }
+    })?;
+
+    Ok((cacheable && !tables.is_empty()).then_some(tables))
+}
+
+impl ResultCache {
+    /// Create a new cache that keeps at most `memory_limit` bytes of results
+    pub fn new(memory_limit: usize) -> Self {
+        Self {
+            memory_limit,
+            state: Mutex::new(ResultCacheState {
+                lru_queue: LruQueue::new(),
+                memory_used: 0,
+                generation: 0,
+                hits: 0,
+                misses: 0,
+            }),
+        }
+    }
+
+    /// Returns the maximum number of bytes of results kept by the cache
+    pub fn memory_limit(&self) -> usize {
+        self.memory_limit
+    }
+
+    /// Returns the number of bytes of results currently kept by the cache
+    pub fn memory_used(&self) -> usize {
+        self.state.lock().memory_used
+    }
+
+    /// Returns the number of cached query results
+    pub fn len(&self) -> usize {
+        self.state.lock().lru_queue.len()
+    }
+
+    /// Returns true if no query results are cached
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of queries answered from the cache
+    pub fn hits(&self) -> usize {
+        self.state.lock().hits
+    }
+
+    /// Returns the number of cacheable queries that were not answered from
+    /// the cache
+    pub fn misses(&self) -> usize {
+        self.state.lock().misses
+    }
+
+    /// Removes all cached results
+    pub fn clear(&self) {
+        let mut state = self.state.lock();
+        state.generation += 1;
+        state.lru_queue.clear();
+        state.memory_used = 0;
+    }
+
+    /// Removes all cached results read from `provider`
+    pub fn invalidate_table(&self, provider: &Arc<dyn TableProvider>) {
+        let mut state = self.state.lock();
+        state.generation += 1;
+        let invalidated: Vec<_> = state
+            .lru_queue
+            .list_entries()
+            .into_iter()
+            .filter(|(_, result)| {
+                result
+                    .tables
+                    .iter()
+                    .any(|table| table.is(provider) || table.is_dropped())
+            })
+            .map(|(signature, _)| *signature)
+            .collect();
+        for signature in invalidated {
+            if let Some(result) = state.lru_queue.remove(&signature) {
+                state.memory_used -= result.size;
+            }
+        }
+    }
+
+    /// Creates a physical plan for the optimized `logical_plan`, either
+    /// returning the cached results or recording the results for later use.
+    pub(crate) async fn create_physical_plan(
+        self: &Arc<Self>,
+        logical_plan: &LogicalPlan,
+        session_state: &SessionState,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let query_planner = session_state.query_planner();
+
+        if let LogicalPlan::Dml(dml) = logical_plan
+            && let Ok(target) = source_as_provider(&dml.target)
+        {
+            self.invalidate_table(&target);
+            let plan = query_planner
+                .create_physical_plan(logical_plan, session_state)
+                .await?;
+            return Ok(Arc::new(ResultCacheExec::new(
+                plan,
+                Arc::clone(self),
+                OnCompletion::Invalidate(target),
+            )));
+        }
+
+        let Some(tables) = scanned_tables(logical_plan, session_state)? else {
+            return query_planner
+                .create_physical_plan(logical_plan, session_state)
+                .await;
+        };
+
+        let signature = LogicalPlanSignature::new(logical_plan);
+        let generation = {
+            let mut state = self.state.lock();
+            let hit = state
+                .lru_queue
+                .get(&signature)
+                .filter(|result| result.plan == *logical_plan && result.tables == tables)
+                .map(|result| (result.partitions.clone(), Arc::clone(&result.schema)));
+            if let Some((partitions, schema)) = hit {
+                state.hits += 1;
+                drop(state);
+                return Ok(MemorySourceConfig::try_new_exec(&partitions, schema, None)?);
+            }
+            state.misses += 1;
+            state.generation
+        };
+
+        let plan = query_planner
+            .create_physical_plan(logical_plan, session_state)
+            .await?;
+
+        // Planning lists the files of the scanned tables if they were not
+        // listed before
+        let Some(tables) = scanned_tables(logical_plan, session_state)? else {
+            return Ok(plan);
+        };
+        if tables
+            .iter()
+            .any(|table| table.version == TableVersion::Unknown)
+        {
+            return Ok(plan);
+        }
+
+        let pending = PendingResult::new(
+            signature,
+            logical_plan.clone(),
+            tables,
+            generation,
+            plan.schema(),
+            plan.output_partitioning().partition_count(),
+        );
+        Ok(Arc::new(ResultCacheExec::new(
+            plan,
+            Arc::clone(self),
+            OnCompletion::Store(Arc::new(Mutex::new(pending))),
+        )))
+    }
+
+    fn insert(
+        &self,
+        signature: LogicalPlanSignature,
+        result: CachedResult,
+        generation: u64,
+    ) {
+        let size = result.size;
+        if size > self.memory_limit {
+            return;
+        }
+
+        let mut state = self.state.lock();
+        if state.generation != generation {
+            return;
+        }
+        if let Some(previous) = state.lru_queue.put(signature, result) {
+            state.memory_used -= previous.size;
+        }
+        state.memory_used += size;
+        while state.memory_used > self.memory_limit {
+            let Some((_, evicted)) = state.lru_queue.pop() else {
+                break;
+            };
+            state.memory_used -= evicted.size;
+        }
+    }
+}
+
+impl fmt::Debug for ResultCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.state.lock();
+        f.debug_struct("ResultCache")
+            .field("memory_limit", &self.memory_limit)
+            .field("memory_used", &state.memory_used)
+            .field("entries", &state.lru_queue.len())
+            .finish()
+    }
+}
+
+/// Results of a query being collected by [`ResultCacheExec`]
+#[derive(Debug)]
+struct PendingResult {
+    signature: LogicalPlanSignature,
+    plan: LogicalPlan,
+    tables: Vec<ScannedTable>,
+    generation: u64,
+    schema: SchemaRef,
+    partitions: Vec<Vec<RecordBatch>>,
+    started: Vec<bool>,
+    completed: Vec<bool>,
+    size: usize,
+    /// Set once the results can not (or no longer) be cached, e.g. because
+    /// they exceed the memory limit
+    abandoned: bool,
+}
+
+impl PendingResult {
+    fn new(
+        signature: LogicalPlanSignature,
+        plan: LogicalPlan,
+        tables: Vec<ScannedTable>,
+        generation: u64,
+        schema: SchemaRef,
+        partition_count: usize,
+    ) -> Self {
+        Self {
+            signature,
+            plan,
+            tables,
+            generation,
+            schema,
+            partitions: vec![vec![]; partition_count],
+            started: vec![false; partition_count],
+            completed: vec![false; partition_count],
+            size: 0,
+            abandoned: false,
+        }
+    }
+
+    fn abandon(&mut self) {
+        self.abandoned = true;
+        self.partitions.clear();
+    }
+
+    fn start(&mut self, partition: usize) {
+        // Results of a re-executed partition would be duplicated
+        if std::mem::replace(&mut self.started[partition], true) {
+            self.abandon();
+        }
+    }
+
+    fn push(&mut self, partition: usize, batch: &RecordBatch, memory_limit: usize) {
+        if self.abandoned {
+            return;
+        }
+        self.size += batch.get_array_memory_size();
+        if self.size > memory_limit {
+            self.abandon();
+        } else {
+            self.partitions[partition].push(batch.clone());
+        }
+    }
+
+    /// Returns the results once all partitions completed
+    fn finish(&mut self, partition: usize) -> Option<(u64, CachedResult)> {
+        self.completed[partition] = true;
+        if self.abandoned || !self.completed.iter().all(|completed| *completed) {
+            return None;
+        }
+        self.abandoned = true;
+        Some((
+            self.generation,
+            CachedResult {
+                plan: self.plan.clone(),
+                tables: std::mem::take(&mut self.tables),
+                schema: Arc::clone(&self.schema),
+                partitions: std::mem::take(&mut self.partitions),
+                size: self.size,
+            },
+        ))
+    }
+}
+
+#[derive(Debug, Clone)]
+enum OnCompletion {
+    /// Store the results of the query in the cache
+    Store(Arc<Mutex<PendingResult>>),
+    /// Invalidate the results read from the target of a DML statement
+    Invalidate(Arc<dyn TableProvider>),
+}
+
+/// Passes through the results of its input, and updates the [`ResultCache`]
+/// once they are complete.
+#[derive(Debug)]
+struct ResultCacheExec {
+    input: Arc<dyn ExecutionPlan>,
+    cache: Arc<ResultCache>,
+    on_completion: OnCompletion,
+}
+
+impl ResultCacheExec {
+    fn new(
+        input: Arc<dyn ExecutionPlan>,
+        cache: Arc<ResultCache>,
+        on_completion: OnCompletion,
+    ) -> Self {
+        Self {
+            input,
+            cache,
+            on_completion,
+        }
+    }
+}
+
+impl DisplayAs for ResultCacheExec {
+    fn fmt_as(&self, _t: DisplayFormatType, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.on_completion {
+            OnCompletion::Store(_) => write!(f, "ResultCacheExec: mode=store"),
+            OnCompletion::Invalidate(_) => write!(f, "ResultCacheExec: mode=invalidate"),
+        }
+    }
+}
+
+impl ExecutionPlan for ResultCacheExec {
+    fn name(&self) -> &str {
+        "ResultCacheExec"
+    }
+
+    fn properties(&self) -> &Arc<PlanProperties> {
+        self.input.properties()
+    }
+
+    fn maintains_input_order(&self) -> Vec<bool> {
+        vec![true]
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
+        vec![&self.input]
+    }
+
+    fn apply_expressions(
+        &self,
+        _f: &mut dyn FnMut(&dyn PhysicalExpr) -> Result<TreeNodeRecursion>,
+    ) -> Result<TreeNodeRecursion> {
+        Ok(TreeNodeRecursion::Continue)
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        mut children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        assert_eq_or_internal_err!(
+            children.len(),
+            1,
+            "ResultCacheExec requires exactly one child"
+        );
+        let input = children.swap_remove(0);
+        match &self.on_completion {
+            // The results of a rewritten plan can not be attributed to the
+            // original query
+            OnCompletion::Store(_) => Ok(input),
+            OnCompletion::Invalidate(_) => Ok(Arc::new(Self::new(
+                input,
+                Arc::clone(&self.cache),
+                self.on_completion.clone(),
+            ))),
+        }
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        if let OnCompletion::Store(pending) = &self.on_completion {
+            pending.lock().start(partition);
+        }
+        let input = self.input.execute(partition, context)?;
+        Ok(Box::pin(ResultCacheStream {
+            input,
+            partition,
+            cache: Arc::clone(&self.cache),
+            on_completion: self.on_completion.clone(),
+        }))
+    }
+
+    fn partition_statistics(&self, partition: Option<usize>) -> Result<Arc<Statistics>> {
+        self.input.partition_statistics(partition)
+    }
+}
+
+struct ResultCacheStream {
+    input: SendableRecordBatchStream,
+    partition: usize,
+    cache: Arc<ResultCache>,
+    on_completion: OnCompletion,
+}
+
+impl ResultCacheStream {
+    fn on_batch(&self, batch: &RecordBatch) {
+        if let OnCompletion::Store(pending) = &self.on_completion {
+            pending
+                .lock()
+                .push(self.partition, batch, self.cache.memory_limit);
+        }
+    }
+
+    fn on_error(&self) {
+        if let OnCompletion::Store(pending) = &self.on_completion {
+            pending.lock().abandon();
+        }
+    }
+
+    fn on_finish(&self) {
+        match &self.on_completion {
+            OnCompletion::Store(pending) => {
+                let mut pending = pending.lock();
+                let signature = pending.signature;
+                if let Some((generation, result)) = pending.finish(self.partition) {
+                    drop(pending);
+                    self.cache.insert(signature, result, generation);
+                }
+            }
+            OnCompletion::Invalidate(target) => self.cache.invalidate_table(target),
+        }
+    }
+}
+
+impl Stream for ResultCacheStream {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let poll = self.input.poll_next_unpin(cx);
+        match &poll {
+            Poll::Ready(Some(Ok(batch))) => self.on_batch(batch),
+            Poll::Ready(Some(Err(_))) => self.on_error(),
+            Poll::Ready(None) => self.on_finish(),
+            Poll::Pending => {}
+        }
+        poll
+    }
+}
+
+impl RecordBatchStream for ResultCacheStream {
+    fn schema(&self) -> SchemaRef {
+        self.input.schema()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::execution::SessionStateBuilder;
+    use crate::prelude::SessionContext;
+    use arrow::util::pretty::pretty_format_batches;
+    use insta::assert_snapshot;
+
+    fn context_with_result_cache(memory_limit: usize) -> SessionContext {
+        let state = SessionStateBuilder::new()
+            .with_default_features()
+            .with_result_cache(Arc::new(ResultCache::new(memory_limit)))
+            .build();
+        SessionContext::new_with_state(state)
+    }
+
+    fn result_cache(ctx: &SessionContext) -> Arc<ResultCache> {
+        Arc::clone(ctx.state().result_cache().unwrap())
+    }
+
+    async fn query(ctx: &SessionContext, sql: &str) -> Result<String> {
+        let batches = ctx.sql(sql).await?.collect().await?;
+        Ok(pretty_format_batches(&batches)?.to_string())
+    }
+
+    #[tokio::test]
+    async fn test_memory_table() -> Result<()> {
+        let ctx = context_with_result_cache(1024 * 1024);
+        let cache = result_cache(&ctx);
+        ctx.sql("CREATE TABLE t AS VALUES (1), (2), (3)")
+            .await?
+            .collect()
+            .await?;
+
+        let sql = "SELECT sum(column1) AS total FROM t";
+        let first = query(&ctx, sql).await?;
+        assert_eq!((cache.hits(), cache.misses(), cache.len()), (0, 1, 1));
+        assert_eq!(query(&ctx, sql).await?, first);
+        assert_eq!((cache.hits(), cache.misses()), (1, 1));
+        assert!(cache.memory_used() > 0);
+
+        // DML on the table invalidates the cached results
+        ctx.sql("INSERT INTO t VALUES (4)").await?.collect().await?;
+        assert!(cache.is_empty());
+        assert_snapshot!(query(&ctx, sql).await?, @r"
+        +-------+
+        | total |
+        +-------+
+        | 10    |
+        +-------+
+        ");
+        assert_eq!((cache.hits(), cache.misses()), (1, 2));
+
+        // Replacing the table does not reuse results of the old table
+        ctx.sql("CREATE OR REPLACE TABLE t AS VALUES (5)")
+            .await?
+            .collect()
+            .await?;
+        assert_snapshot!(query(&ctx, sql).await?, @r"
+        +-------+
+        | total |
+        +-------+
+        | 5     |
+        +-------+
+        ");
+        assert_eq!((cache.hits(), cache.misses()), (1, 3));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_listing_table() -> Result<()> {
+        let ctx = context_with_result_cache(1024 * 1024);
+        let cache = result_cache(&ctx);
+        let dir = tempfile::tempdir()?;
+        std::fs::write(dir.path().join("1.csv"), "a\n1\n2\n")?;
+        ctx.sql(&format!(
+            "CREATE EXTERNAL TABLE t (a INT) STORED AS CSV LOCATION '{}/' \
+             OPTIONS ('format.has_header' 'true')",
+            dir.path().display()
+        ))
+        .await?
+        .collect()
+        .await?;
+
+        let sql = "SELECT count(*) AS n, sum(a) AS total FROM t";
+        let first = query(&ctx, sql).await?;
+        assert_eq!(query(&ctx, sql).await?, first);
+        assert_eq!((cache.hits(), cache.misses()), (1, 1));
+
+        ctx.sql("INSERT INTO t VALUES (3)").await?.collect().await?;
+        assert_snapshot!(query(&ctx, sql).await?, @r"
+        +---+-------+
+        | n | total |
+        +---+-------+
+        | 3 | 6     |
+        +---+-------+
+        ");
+        assert_eq!((cache.hits(), cache.misses()), (1, 2));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_uncacheable_queries() -> Result<()> {
+        let ctx = context_with_result_cache(1024 * 1024);
+        let cache = result_cache(&ctx);
+        ctx.sql("CREATE TABLE t AS VALUES (1), (2), (3)")
+            .await?
+            .collect()
+            .await?;
+
+        // Volatile expressions
+        for _ in 0..2 {
+            query(&ctx, "SELECT column1, random() FROM t").await?;
+        }
+        // No table scans
+        for _ in 0..2 {
+            query(&ctx, "SELECT 1").await?;
+        }
+        assert_eq!((cache.hits(), cache.misses(), cache.len()), (0, 0, 0));
+
+        // Results larger than the memory limit are not kept
+        let ctx = context_with_result_cache(1);
+        let cache = result_cache(&ctx);
+        ctx.sql("CREATE TABLE t AS VALUES (1), (2), (3)")
+            .await?
+            .collect()
+            .await?;
+        for _ in 0..2 {
+            query(&ctx, "SELECT * FROM t").await?;
+        }
+        assert_eq!((cache.hits(), cache.misses(), cache.len()), (0, 2, 0));
+        assert_eq!(cache.memory_used(), 0);
+
+        Ok(())
+    }
+}
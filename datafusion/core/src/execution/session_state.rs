@@ -23,14 +23,17 @@ use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
 use std::sync::Arc;
 
-use crate::catalog::{CatalogProviderList, SchemaProvider, TableProviderFactory};
+use crate::catalog::{
+    CatalogProviderList, SchemaProvider, TableProvider, TableProviderFactory,
+};
 use crate::datasource::file_format::FileFormatFactory;
-#[cfg(feature = "sql")]
-use crate::datasource::provider_as_source;
+use crate::datasource::{MaterializedView, provider_as_source, source_as_provider};
 use crate::execution::SessionStateDefaults;
 use crate::execution::context::{EmptySerializerRegistry, FunctionFactory, QueryPlanner};
+use crate::execution::materialized_view::MarkStaleExec;
+use crate::execution::result_cache::ResultCache;
 use crate::physical_planner::{DefaultPhysicalPlanner, PhysicalPlanner};
-use arrow_schema::{DataType, FieldRef};
+use arrow_schema::{DataType, FieldRef, SchemaRef};
 use datafusion_catalog::MemoryCatalogProviderList;
 use datafusion_catalog::information_schema::{
     INFORMATION_SCHEMA, InformationSchemaProvider,
@@ -41,7 +44,7 @@ use datafusion_common::alias::AliasGenerator;
 use datafusion_common::config::Dialect;
 use datafusion_common::config::{ConfigExtension, ConfigOptions, TableOptions};
 use datafusion_common::display::{PlanType, StringifiedPlan, ToStringifiedPlan};
-use datafusion_common::tree_node::TreeNode;
+use datafusion_common::tree_node::{TreeNode, TreeNodeRecursion};
 use datafusion_common::{
     DFSchema, DataFusionError, ResolvedTableReference, TableReference, config_err,
     exec_err, plan_datafusion_err,
@@ -64,6 +67,7 @@ use datafusion_expr::simplify::SimplifyContext;
 use datafusion_expr::{
     AggregateUDF, Explain, Expr, HigherOrderUDF, LogicalPlan, ScalarUDF, WindowUDF,
 };
+use datafusion_optimizer::materialized_view_rewrite::MaterializedViewCandidate;
 use datafusion_optimizer::simplify_expressions::ExprSimplifier;
 use datafusion_optimizer::{
     Analyzer, AnalyzerRule, Optimizer, OptimizerConfig, OptimizerRule,
@@ -203,6 +207,15 @@ pub struct SessionState {
     /// enhanced statistics (e.g., NDV overrides, histograms) beyond what
     /// is available from `ExecutionPlan::partition_statistics()`.
     statistics_registry: Option<StatisticsRegistry>,
+    /// Optional cache for the results of queries, see [`ResultCache`].
+    result_cache: Option<Arc<ResultCache>>,
+    /// [`MaterializedViewStorageFactory`] creating the tables which store the
+    /// results of materialized views.
+    ///
+    /// If not set, the results are stored in memory.
+    materialized_view_storage_factory: Option<Arc<dyn MaterializedViewStorageFactory>>,
+    /// Materialized views created in this session, by resolved name
+    materialized_views: HashMap<TableReference, Arc<MaterializedView>>,
     /// Cache logical plans of prepared statements for later execution.
     /// Key is the prepared statement name.
     prepared_plans: HashMap<String, Arc<PreparedPlan>>,
@@ -235,6 +248,11 @@ impl Debug for SessionState {
             .field("table_factories", &self.table_factories)
             .field("function_factory", &self.function_factory)
             .field("cache_factory", &self.cache_factory)
+            .field("result_cache", &self.result_cache)
+            .field(
+                "materialized_view_storage_factory",
+                &self.materialized_view_storage_factory,
+            )
             .field("expr_planners", &self.expr_planners);
 
         #[cfg(feature = "sql")]
@@ -347,9 +365,10 @@ impl SessionState {
         let resolved_ref = self.resolve_table_ref(table_ref);
         if self.config.information_schema() && *resolved_ref.schema == *INFORMATION_SCHEMA
         {
-            return Ok(Arc::new(InformationSchemaProvider::new(Arc::clone(
-                &self.catalog_list,
-            ))));
+            return Ok(Arc::new(
+                InformationSchemaProvider::new(Arc::clone(&self.catalog_list))
+                    .with_table_functions(self.table_functions.clone()),
+            ));
         }
 
         self.catalog_list
@@ -748,15 +767,41 @@ impl SessionState {
     /// `CREATE TABLE`, which do not have corresponding physical plans and must
     /// be handled by another layer, typically [`SessionContext`].
     ///
+    /// If a [`ResultCache`] is configured, the results of a previous
+    /// execution of the same query are returned when still valid.
+    ///
+    /// The materialized views reading from the target of a DML statement are
+    /// marked stale once the returned plan finished executing.
+    ///
     /// [`SessionContext`]: crate::execution::context::SessionContext
     pub async fn create_physical_plan(
         &self,
         logical_plan: &LogicalPlan,
     ) -> datafusion_common::Result<Arc<dyn ExecutionPlan>> {
+        let stale_views = match logical_plan {
+            LogicalPlan::Dml(dml) => match source_as_provider(&dml.target) {
+                Ok(target) => self.materialized_views_reading(&target),
+                Err(_) => vec![],
+            },
+            _ => vec![],
+        };
         let logical_plan = self.optimize(logical_plan)?;
-        self.query_planner
-            .create_physical_plan(&logical_plan, self)
-            .await
+        let plan = match &self.result_cache {
+            Some(result_cache) => {
+                result_cache
+                    .create_physical_plan(&logical_plan, self)
+                    .await?
+            }
+            None => {
+                self.query_planner
+                    .create_physical_plan(&logical_plan, self)
+                    .await?
+            }
+        };
+        if stale_views.is_empty() {
+            return Ok(plan);
+        }
+        Ok(Arc::new(MarkStaleExec::new(plan, stale_views)))
     }
 
     /// Create a [`PhysicalExpr`] from an [`Expr`] after applying type
@@ -852,6 +897,60 @@ impl SessionState {
         self.statistics_registry.as_ref()
     }
 
+    /// Returns the [`ResultCache`] if one is configured.
+    pub fn result_cache(&self) -> Option<&Arc<ResultCache>> {
+        self.result_cache.as_ref()
+    }
+
+    /// Returns the [`MaterializedViewStorageFactory`] if one is configured.
+    pub fn materialized_view_storage_factory(
+        &self,
+    ) -> Option<&Arc<dyn MaterializedViewStorageFactory>> {
+        self.materialized_view_storage_factory.as_ref()
+    }
+
+    /// Returns the materialized views which the optimizer may use to answer
+    /// queries, by resolved name
+    pub fn materialized_views(&self) -> &HashMap<TableReference, Arc<MaterializedView>> {
+        &self.materialized_views
+    }
+
+    /// Registers a materialized view, so that the optimizer may use it to
+    /// answer queries while it is fresh, returning the view previously
+    /// registered under the same name, if any.
+    ///
+    /// Note this does not register the view in the catalog.
+    pub fn register_materialized_view(
+        &mut self,
+        name: impl Into<TableReference>,
+        view: Arc<MaterializedView>,
+    ) -> Option<Arc<MaterializedView>> {
+        let name = TableReference::from(self.resolve_table_ref(name));
+        self.materialized_views.insert(name, view)
+    }
+
+    /// Deregisters a materialized view, returning the view if it was
+    /// registered
+    pub fn deregister_materialized_view(
+        &mut self,
+        name: impl Into<TableReference>,
+    ) -> Option<Arc<MaterializedView>> {
+        let name = TableReference::from(self.resolve_table_ref(name));
+        self.materialized_views.remove(&name)
+    }
+
+    /// Returns the materialized views reading from `table`
+    fn materialized_views_reading(
+        &self,
+        table: &Arc<dyn TableProvider>,
+    ) -> Vec<Arc<MaterializedView>> {
+        self.materialized_views
+            .values()
+            .filter(|view| reads_table(view.logical_plan(), table))
+            .cloned()
+            .collect()
+    }
+
     /// Mark the start of the execution
     pub fn mark_start_execution(&mut self) {
         let config = Arc::clone(self.config.options());
@@ -1048,6 +1147,8 @@ pub struct SessionStateBuilder {
     function_factory: Option<Arc<dyn FunctionFactory>>,
     cache_factory: Option<Arc<dyn CacheFactory>>,
     statistics_registry: Option<StatisticsRegistry>,
+    result_cache: Option<Arc<ResultCache>>,
+    materialized_view_storage_factory: Option<Arc<dyn MaterializedViewStorageFactory>>,
     // fields to support convenience functions
     analyzer_rules: Option<Vec<Arc<dyn AnalyzerRule + Send + Sync>>>,
     optimizer_rules: Option<Vec<Arc<dyn OptimizerRule + Send + Sync>>>,
@@ -1091,6 +1192,8 @@ impl SessionStateBuilder {
             function_factory: None,
             cache_factory: None,
             statistics_registry: None,
+            result_cache: None,
+            materialized_view_storage_factory: None,
             // fields to support convenience functions
             analyzer_rules: None,
             optimizer_rules: None,
@@ -1151,6 +1254,8 @@ impl SessionStateBuilder {
             function_factory: existing.function_factory,
             cache_factory: existing.cache_factory,
             statistics_registry: existing.statistics_registry,
+            result_cache: existing.result_cache,
+            materialized_view_storage_factory: existing.materialized_view_storage_factory,
             // fields to support convenience functions
             analyzer_rules: None,
             optimizer_rules: None,
@@ -1161,7 +1266,13 @@ impl SessionStateBuilder {
     /// Adds defaults for table_factories, file formats, expr_planners and builtin
     /// scalar, aggregate and windows functions.
     ///
+    /// Also sets the [`SqlFunctionFactory`] as function factory and
+    /// [`SessionStateDefaults::default_statistics_registry`] as statistics
+    /// registry if none is set.
+    ///
     /// Note overwrites any previously registered items with the same name.
+    ///
+    /// [`SqlFunctionFactory`]: crate::execution::SqlFunctionFactory
     pub fn with_default_features(mut self) -> Self {
         self.table_factories
             .get_or_insert_with(HashMap::new)
@@ -1204,6 +1315,13 @@ impl SessionStateBuilder {
                     .map(|f| (f.name().to_string(), f)),
             );
 
+        #[cfg(feature = "sql")]
+        self.function_factory
+            .get_or_insert_with(SessionStateDefaults::default_function_factory);
+
+        self.statistics_registry
+            .get_or_insert_with(SessionStateDefaults::default_statistics_registry);
+
         self
     }
 
@@ -1495,6 +1613,24 @@ impl SessionStateBuilder {
         self
     }
 
+    /// Set a [`ResultCache`] to reuse the results of repeated queries.
+    ///
+    /// The cache is shared by all clones of the resulting [`SessionState`].
+    pub fn with_result_cache(mut self, result_cache: Arc<ResultCache>) -> Self {
+        self.result_cache = Some(result_cache);
+        self
+    }
+
+    /// Set a [`MaterializedViewStorageFactory`] to create the tables storing
+    /// the results of materialized views
+    pub fn with_materialized_view_storage_factory(
+        mut self,
+        factory: Option<Arc<dyn MaterializedViewStorageFactory>>,
+    ) -> Self {
+        self.materialized_view_storage_factory = factory;
+        self
+    }
+
     /// Register an `ObjectStore` to the [`RuntimeEnv`]. See [`RuntimeEnv::register_object_store`]
     /// for more details.
     ///
@@ -1564,6 +1700,8 @@ impl SessionStateBuilder {
             function_factory,
             cache_factory,
             statistics_registry,
+            result_cache,
+            materialized_view_storage_factory,
             analyzer_rules,
             optimizer_rules,
             physical_optimizer_rules,
@@ -1606,6 +1744,9 @@ impl SessionStateBuilder {
             function_factory,
             cache_factory,
             statistics_registry,
+            result_cache,
+            materialized_view_storage_factory,
+            materialized_views: HashMap::new(),
             prepared_plans: HashMap::new(),
         };
 
@@ -1854,6 +1995,18 @@ impl SessionStateBuilder {
         &mut self.cache_factory
     }
 
+    /// Returns the result cache
+    pub fn result_cache(&mut self) -> &mut Option<Arc<ResultCache>> {
+        &mut self.result_cache
+    }
+
+    /// Returns the materialized view storage factory
+    pub fn materialized_view_storage_factory(
+        &mut self,
+    ) -> &mut Option<Arc<dyn MaterializedViewStorageFactory>> {
+        &mut self.materialized_view_storage_factory
+    }
+
     /// Returns the current analyzer_rules value
     pub fn analyzer_rules(
         &mut self,
@@ -1893,6 +2046,11 @@ impl Debug for SessionStateBuilder {
             .field("table_factories", &self.table_factories)
             .field("function_factory", &self.function_factory)
             .field("cache_factory", &self.cache_factory)
+            .field("result_cache", &self.result_cache)
+            .field(
+                "materialized_view_storage_factory",
+                &self.materialized_view_storage_factory,
+            )
             .field("expr_planners", &self.expr_planners);
         #[cfg(feature = "sql")]
         let ret = ret.field("type_planner", &self.type_planner);
@@ -2282,6 +2440,37 @@ impl OptimizerConfig for SessionState {
     fn function_registry(&self) -> Option<&dyn FunctionRegistry> {
         Some(self)
     }
+
+    fn materialized_view_candidates(&self) -> Vec<MaterializedViewCandidate> {
+        self.materialized_views
+            .iter()
+            .filter(|(_, view)| view.is_fresh())
+            .map(|(name, view)| MaterializedViewCandidate {
+                name: name.clone(),
+                plan: Arc::new(view.logical_plan().clone()),
+                source: provider_as_source(Arc::clone(view) as _),
+            })
+            .collect()
+    }
+}
+
+/// Returns true if `plan` scans `table`
+fn reads_table(plan: &LogicalPlan, table: &Arc<dyn TableProvider>) -> bool {
+    let mut found = false;
+    // The closure never returns an error
+    let _ = plan.apply_with_subqueries(|node| {
+        if let LogicalPlan::TableScan(scan) = node
+            && let Ok(provider) = source_as_provider(&scan.source)
+        {
+            found = Arc::ptr_eq(&provider, table);
+        }
+        Ok(if found {
+            TreeNodeRecursion::Stop
+        } else {
+            TreeNodeRecursion::Continue
+        })
+    });
+    found
 }
 
 /// Create a new task context instance from SessionState
@@ -2341,6 +2530,25 @@ pub trait CacheFactory: Debug + Send + Sync {
     ) -> datafusion_common::Result<LogicalPlan>;
 }
 
+/// A [`MaterializedViewStorageFactory`] can be registered via
+/// [`SessionStateBuilder`] to store the results of materialized views in a
+/// custom [`TableProvider`], instead of in memory.
+pub trait MaterializedViewStorageFactory: Debug + Send + Sync {
+    /// Create an empty table with the given schema storing the results of
+    /// the materialized view `name`.
+    ///
+    /// The table must support [`TableProvider::delete_from`] without filters
+    /// and [`TableProvider::insert_into`] with [`InsertOp::Append`].
+    ///
+    /// [`InsertOp::Append`]: datafusion_expr::dml::InsertOp::Append
+    fn create(
+        &self,
+        name: &TableReference,
+        schema: SchemaRef,
+        session_state: &SessionState,
+    ) -> datafusion_common::Result<Arc<dyn TableProvider>>;
+}
+
 #[cfg(test)]
 mod tests {
     use super::{SessionContextProvider, SessionStateBuilder};
@@ -26,7 +26,12 @@ use crate::datasource::file_format::json::JsonFormatFactory;
 #[cfg(feature = "parquet")]
 use crate::datasource::file_format::parquet::ParquetFormatFactory;
 use crate::datasource::provider::DefaultTableFactory;
+use crate::datasource::source::DataSourceStatisticsProvider;
+#[cfg(feature = "sql")]
+use crate::execution::context::FunctionFactory;
 use crate::execution::context::SessionState;
+#[cfg(feature = "sql")]
+use crate::execution::sql_macro::SqlFunctionFactory;
 #[cfg(feature = "nested_expressions")]
 use crate::functions_nested;
 use crate::{functions, functions_aggregate, functions_table, functions_window};
@@ -38,6 +43,7 @@ use datafusion_execution::runtime_env::RuntimeEnv;
 use datafusion_expr::planner::ExprPlanner;
 use datafusion_expr::registry::ExtensionTypeRegistrationRef;
 use datafusion_expr::{AggregateUDF, HigherOrderUDF, ScalarUDF, WindowUDF};
+use datafusion_physical_plan::operator_statistics::StatisticsRegistry;
 use std::collections::HashMap;
 use std::sync::Arc;
 use url::Url;
@@ -104,9 +110,10 @@ impl SessionStateDefaults {
 
     /// returns the list of default [`ScalarUDF`]s
     pub fn default_scalar_functions() -> Vec<Arc<ScalarUDF>> {
-        #[cfg_attr(not(feature = "nested_expressions"), expect(unused_mut))]
         let mut functions: Vec<Arc<ScalarUDF>> = functions::all_default_functions();
 
+        functions.append(&mut functions_aggregate::all_default_scalar_functions());
+
         #[cfg(feature = "nested_expressions")]
         functions.append(&mut functions_nested::all_default_nested_functions());
 
@@ -144,6 +151,12 @@ impl SessionStateDefaults {
         functions_table::all_default_table_functions()
     }
 
+    /// returns the default [`FunctionFactory`], which creates SQL functions
+    #[cfg(feature = "sql")]
+    pub fn default_function_factory() -> Arc<dyn FunctionFactory> {
+        Arc::new(SqlFunctionFactory::new())
+    }
+
     /// returns the list of default [`FileFormatFactory`]s
     pub fn default_file_formats() -> Vec<Arc<dyn FileFormatFactory>> {
         let file_formats: Vec<Arc<dyn FileFormatFactory>> = vec![
@@ -159,6 +172,16 @@ impl SessionStateDefaults {
         file_formats
     }
 
+    /// returns the default [`StatisticsRegistry`]: the built-in providers of
+    /// [`StatisticsRegistry::default_with_builtin_providers`] and the
+    /// [`DataSourceStatisticsProvider`], so that histograms and most common
+    /// values supplied by data sources are used
+    pub fn default_statistics_registry() -> StatisticsRegistry {
+        let mut registry = StatisticsRegistry::default_with_builtin_providers();
+        registry.register(Arc::new(DataSourceStatisticsProvider));
+        registry
+    }
+
     /// registers all builtin functions - scalar, array and aggregate
     pub fn register_builtin_functions(state: &mut SessionState) {
         Self::register_scalar_functions(state);
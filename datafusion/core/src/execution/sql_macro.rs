@@ -0,0 +1,520 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`SqlFunctionFactory`] to create functions defined in SQL
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use arrow::array::{RecordBatch, RecordBatchOptions};
+use arrow::compute::can_cast_types;
+use arrow::datatypes::{DataType, Field};
+use async_trait::async_trait;
+use datafusion_catalog::{TableFunctionArgs, TableFunctionImpl, TableProvider};
+use datafusion_common::tree_node::{
+    Transformed, TransformedResult, TreeNode, TreeNodeRecursion,
+};
+use datafusion_common::{
+    Column, DFSchema, Result, ScalarValue, internal_datafusion_err, internal_err,
+    not_impl_err, plan_datafusion_err, plan_err,
+};
+use datafusion_expr::execution_props::ExecutionProps;
+use datafusion_expr::expr::Placeholder;
+use datafusion_expr::simplify::{ExprSimplifyResult, SimplifyContext};
+use datafusion_expr::{
+    ColumnarValue, CreateFunction, Expr, ExprSchemable, LogicalPlan, OperateFunctionArg,
+    ScalarFunctionArgs, ScalarUDF, ScalarUDFImpl, Signature, Volatility,
+};
+use datafusion_optimizer::analyzer::type_coercion::TypeCoercionRewriter;
+use datafusion_physical_expr::create_physical_expr;
+use datafusion_sql::parser::Statement;
+use datafusion_sql::planner::IdentNormalizer;
+use sqlparser::ast;
+
+use crate::datasource::ViewTable;
+use crate::execution::SessionState;
+use crate::execution::context::{FunctionFactory, RegisterFunction};
+
+/// The default [`FunctionFactory`], which creates functions defined in SQL.
+///
+/// Scalar functions are defined by an expression, which references the
+/// parameters by name or position (`$1`). Calls are replaced by the
+/// expression during planning:
+///
+/// ```sql
+/// CREATE FUNCTION add_tax(x DOUBLE) RETURNS DOUBLE RETURN x * 1.2;
+/// ```
+///
+/// Table functions are defined by a query, which references the parameters
+/// as placeholders (`$n` or `$1`), and are read like a view:
+///
+/// ```sql
+/// CREATE FUNCTION recent(n INT) RETURNS TABLE
+/// AS 'SELECT * FROM events WHERE day > current_date - $n';
+/// ```
+///
+/// Arguments are coerced to the types of the parameters, and parameters with
+/// a default value may be omitted.
+#[derive(Debug, Default)]
+pub struct SqlFunctionFactory {}
+
+impl SqlFunctionFactory {
+    /// Creates a new [`SqlFunctionFactory`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl FunctionFactory for SqlFunctionFactory {
+    async fn create(
+        &self,
+        state: &SessionState,
+        statement: CreateFunction,
+    ) -> Result<RegisterFunction> {
+        if let Some(language) = &statement.params.language
+            && !language.value.eq_ignore_ascii_case("sql")
+        {
+            return not_impl_err!(
+                "Functions in language {language} are not supported, only SQL functions are"
+            );
+        }
+
+        if statement.returns_table {
+            let function = SqlTableMacro::try_new(state, statement).await?;
+            Ok(RegisterFunction::Table(
+                function.name.clone(),
+                Arc::new(function),
+            ))
+        } else {
+            let function = SqlScalarMacro::try_new(state, statement)?;
+            Ok(RegisterFunction::Scalar(Arc::new(
+                ScalarUDF::new_from_impl(function),
+            )))
+        }
+    }
+}
+
+/// Returns the names of the parameters, or `None` for positional parameters
+fn parameter_names(
+    state: &SessionState,
+    args: &[OperateFunctionArg],
+) -> Vec<Option<String>> {
+    let normalizer = IdentNormalizer::new(
+        state.config_options().sql_parser.enable_ident_normalization,
+    );
+    args.iter()
+        .map(|arg| arg.name.clone().map(|name| normalizer.normalize(name)))
+        .collect()
+}
+
+/// Returns the index of the parameter referenced by a placeholder such as
+/// `$1` or `$name`
+fn parameter_index(
+    function: &str,
+    names: &[Option<String>],
+    placeholder: &str,
+) -> Result<usize> {
+    let name = placeholder.strip_prefix('$').unwrap_or(placeholder);
+    let index = match name.parse::<usize>() {
+        Ok(position) => position.checked_sub(1).filter(|i| *i < names.len()),
+        Err(_) => names.iter().position(|n| n.as_deref() == Some(name)),
+    };
+    index.ok_or_else(|| {
+        plan_datafusion_err!("Unknown parameter {placeholder} in function '{function}'")
+    })
+}
+
+/// Returns the number of parameters without a default value, which are the
+/// leading parameters
+fn required_parameters<T>(defaults: &[Option<T>]) -> usize {
+    defaults.iter().take_while(|d| d.is_none()).count()
+}
+
+/// Checks the number of arguments of a call, of which the parameters with a
+/// default value may be omitted
+fn check_argument_count(
+    function: &str,
+    required: usize,
+    total: usize,
+    provided: usize,
+) -> Result<()> {
+    if provided < required || provided > total {
+        if required == total {
+            return plan_err!(
+                "Function '{function}' expects {total} arguments, got {provided}"
+            );
+        }
+        return plan_err!(
+            "Function '{function}' expects {required} to {total} arguments, got {provided}"
+        );
+    }
+    Ok(())
+}
+
+/// A scalar function defined by a SQL expression, which is inlined into the
+/// calling expression by [`ScalarUDFImpl::simplify`].
+///
+/// Calls are not inlined if a volatile argument, such as `random()`, is
+/// passed for a parameter that is referenced more than once, as it would be
+/// evaluated once per reference. These calls evaluate `body` on the arguments
+/// instead.
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct SqlScalarMacro {
+    name: String,
+    /// The names of the columns referencing the parameters in `body`
+    parameters: Vec<String>,
+    types: Vec<DataType>,
+    /// The default values of the parameters
+    defaults: Vec<Option<Expr>>,
+    /// The type coerced body, cast to `return_type`
+    body: Expr,
+    /// The number of times each parameter is referenced in `body`
+    references: Vec<usize>,
+    /// The schema with a column for each parameter, which `body` references
+    schema: DFSchema,
+    signature: Signature,
+    return_type: DataType,
+}
+
+impl SqlScalarMacro {
+    fn try_new(state: &SessionState, statement: CreateFunction) -> Result<Self> {
+        let CreateFunction {
+            name,
+            args,
+            return_type,
+            params,
+            ..
+        } = statement;
+        let Some(body) = params.function_body else {
+            return plan_err!("Function '{name}' has no body");
+        };
+        let args = args.unwrap_or_default();
+        let names = parameter_names(state, &args);
+
+        // The body references the parameters as columns of this schema
+        let parameters = names
+            .iter()
+            .enumerate()
+            .map(|(i, n)| n.clone().unwrap_or_else(|| format!("${}", i + 1)))
+            .collect::<Vec<_>>();
+        let fields = parameters
+            .iter()
+            .zip(&args)
+            .map(|(n, arg)| Field::new(n, arg.data_type.clone(), true))
+            .collect::<Vec<_>>();
+        let schema = DFSchema::from_unqualified_fields(fields.into(), HashMap::new())?;
+
+        let body = body
+            .transform(|expr| match expr {
+                Expr::Placeholder(Placeholder { id, .. }) => {
+                    let index = parameter_index(&name, &names, &id)?;
+                    Ok(Transformed::yes(Expr::Column(Column::from_name(
+                        &parameters[index],
+                    ))))
+                }
+                Expr::Column(column) => {
+                    if column.relation.is_some() || !parameters.contains(&column.name) {
+                        return plan_err!(
+                            "Unknown parameter {column} in function '{name}'"
+                        );
+                    }
+                    Ok(Transformed::no(Expr::Column(column)))
+                }
+                _ => Ok(Transformed::no(expr)),
+            })
+            .data()?
+            .rewrite(&mut TypeCoercionRewriter::new(&schema))
+            .data()?;
+        let body_type = body.get_type(&schema)?;
+        let (body, return_type) = match return_type {
+            Some(return_type) if return_type != body_type => {
+                (body.cast_to(&return_type, &schema)?, return_type)
+            }
+            _ => (body, body_type),
+        };
+
+        let empty_schema = DFSchema::empty();
+        let defaults = args
+            .iter()
+            .map(|arg| {
+                arg.default_expr
+                    .clone()
+                    .map(|expr| expr.cast_to(&arg.data_type, &empty_schema))
+                    .transpose()
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let volatility = params.behavior.unwrap_or(if body.is_volatile() {
+            Volatility::Volatile
+        } else {
+            Volatility::Immutable
+        });
+
+        let mut references = vec![0; parameters.len()];
+        body.apply(|expr| {
+            if let Expr::Column(column) = expr
+                && let Some(index) = parameters.iter().position(|p| *p == column.name)
+            {
+                references[index] += 1;
+            }
+            Ok(TreeNodeRecursion::Continue)
+        })?;
+
+        Ok(Self {
+            name,
+            parameters,
+            types: args.into_iter().map(|arg| arg.data_type).collect(),
+            defaults,
+            body,
+            references,
+            schema,
+            signature: Signature::user_defined(volatility),
+            return_type,
+        })
+    }
+}
+
+impl ScalarUDFImpl for SqlScalarMacro {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(self.return_type.clone())
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        check_argument_count(
+            &self.name,
+            required_parameters(&self.defaults),
+            self.types.len(),
+            arg_types.len(),
+        )?;
+        arg_types
+            .iter()
+            .zip(&self.types)
+            .map(|(arg_type, data_type)| {
+                if can_cast_types(arg_type, data_type) {
+                    Ok(data_type.clone())
+                } else {
+                    plan_err!(
+                        "Function '{}' expects an argument of type {data_type}, got {arg_type}",
+                        self.name
+                    )
+                }
+            })
+            .collect()
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        // `simplify` adds the default values of omitted arguments to calls
+        // that are not inlined
+        if args.args.len() != self.parameters.len() {
+            return internal_err!(
+                "Function '{}' expects {} arguments, got {}",
+                self.name,
+                self.parameters.len(),
+                args.args.len()
+            );
+        }
+        let columns = args
+            .args
+            .iter()
+            .map(|arg| arg.to_array(args.number_rows))
+            .collect::<Result<Vec<_>>>()?;
+        let batch = RecordBatch::try_new_with_options(
+            Arc::clone(self.schema.inner()),
+            columns,
+            &RecordBatchOptions::new().with_row_count(Some(args.number_rows)),
+        )?;
+        create_physical_expr(&self.body, &self.schema, &ExecutionProps::new())?
+            .evaluate(&batch)
+    }
+
+    fn simplify(
+        &self,
+        args: Vec<Expr>,
+        _info: &SimplifyContext,
+    ) -> Result<ExprSimplifyResult> {
+        let provided = args.len();
+        let args = args
+            .into_iter()
+            .map(Some)
+            .chain(self.defaults.iter().skip(provided).cloned())
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| {
+                internal_datafusion_err!(
+                    "Missing arguments in call to function '{}'",
+                    self.name
+                )
+            })?;
+
+        // Inlining would evaluate the argument once per reference
+        if args
+            .iter()
+            .zip(&self.references)
+            .any(|(arg, references)| *references > 1 && arg.is_volatile())
+        {
+            return Ok(ExprSimplifyResult::Original(args));
+        }
+
+        let expr = self
+            .body
+            .clone()
+            .transform(|expr| match expr {
+                Expr::Column(column) => {
+                    let index = self
+                        .parameters
+                        .iter()
+                        .position(|p| *p == column.name)
+                        .ok_or_else(|| {
+                            internal_datafusion_err!(
+                                "Unknown parameter {column} in function '{}'",
+                                self.name
+                            )
+                        })?;
+                    Ok(Transformed::yes(args[index].clone()))
+                }
+                _ => Ok(Transformed::no(expr)),
+            })
+            .data()?;
+        Ok(ExprSimplifyResult::Simplified(expr))
+    }
+}
+
+/// A table function defined by a SQL query, which is read like a view with
+/// the placeholders replaced by the arguments
+#[derive(Debug)]
+struct SqlTableMacro {
+    name: String,
+    /// The names of the parameters, or `None` for positional parameters
+    names: Vec<Option<String>>,
+    types: Vec<DataType>,
+    /// The default values of the parameters
+    defaults: Vec<Option<ScalarValue>>,
+    plan: LogicalPlan,
+    /// The SQL text of the query
+    definition: String,
+}
+
+impl SqlTableMacro {
+    async fn try_new(state: &SessionState, statement: CreateFunction) -> Result<Self> {
+        let CreateFunction {
+            name, args, params, ..
+        } = statement;
+        let Some(definition) = params
+            .function_body
+            .as_ref()
+            .and_then(|body| match body {
+                Expr::Literal(value, _) => value.try_as_str().flatten(),
+                _ => None,
+            })
+            .map(str::to_string)
+        else {
+            return plan_err!(
+                "Table function '{name}' must be defined by a query string, such as AS 'SELECT ...'"
+            );
+        };
+
+        let dialect = state.config_options().sql_parser.dialect;
+        let statement = state.sql_to_statement(&definition, &dialect)?;
+        if !matches!(
+            &statement,
+            Statement::Statement(s) if matches!(s.as_ref(), ast::Statement::Query(_))
+        ) {
+            return plan_err!("The body of table function '{name}' must be a query");
+        }
+        let plan = state.statement_to_plan(statement).await?;
+
+        let args = args.unwrap_or_default();
+        let names = parameter_names(state, &args);
+        plan.apply_with_subqueries(|plan| {
+            plan.apply_expressions(|expr| {
+                expr.apply(|expr| {
+                    if let Expr::Placeholder(Placeholder { id, .. }) = expr {
+                        parameter_index(&name, &names, id)?;
+                    }
+                    Ok(TreeNodeRecursion::Continue)
+                })
+            })
+        })?;
+
+        let defaults = args
+            .iter()
+            .map(|arg| match &arg.default_expr {
+                None => Ok(None),
+                Some(Expr::Literal(value, _)) => value.cast_to(&arg.data_type).map(Some),
+                Some(_) => plan_err!(
+                    "Default values of table function '{name}' must be literals"
+                ),
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            name,
+            names,
+            types: args.into_iter().map(|arg| arg.data_type).collect(),
+            defaults,
+            plan,
+            definition,
+        })
+    }
+}
+
+impl TableFunctionImpl for SqlTableMacro {
+    fn call_with_args(&self, args: TableFunctionArgs) -> Result<Arc<dyn TableProvider>> {
+        let exprs = args.exprs();
+        check_argument_count(
+            &self.name,
+            required_parameters(&self.defaults),
+            self.types.len(),
+            exprs.len(),
+        )?;
+
+        // Placeholders reference the parameters by position or name
+        let mut values = HashMap::new();
+        for (i, data_type) in self.types.iter().enumerate() {
+            let value = match exprs.get(i) {
+                Some(Expr::Literal(value, _)) => value.cast_to(data_type)?,
+                Some(_) => {
+                    return plan_err!(
+                        "Arguments of table function '{}' must be literals",
+                        self.name
+                    );
+                }
+                None => self.defaults[i]
+                    .clone()
+                    .ok_or_else(|| internal_datafusion_err!("Missing default value"))?,
+            };
+            if let Some(name) = &self.names[i] {
+                values.insert(name.clone(), value.clone());
+            }
+            values.insert((i + 1).to_string(), value);
+        }
+
+        let plan = self.plan.clone().with_param_values(values)?;
+        Ok(Arc::new(ViewTable::new(
+            plan,
+            Some(self.definition.clone()),
+        )))
+    }
+}
@@ -47,6 +47,7 @@ use crate::physical_plan::joins::{
 use crate::physical_plan::limit::{GlobalLimitExec, LocalLimitExec};
 use crate::physical_plan::projection::{ProjectionExec, ProjectionExpr};
 use crate::physical_plan::repartition::RepartitionExec;
+use crate::physical_plan::sample::SampleExec;
 use crate::physical_plan::sorts::sort::SortExec;
 use crate::physical_plan::union::UnionExec;
 use crate::physical_plan::unnest::UnnestExec;
@@ -59,7 +60,7 @@ use crate::schema_equivalence::schema_satisfied_by;
 
 use arrow::array::{RecordBatch, builder::StringBuilder};
 use arrow::compute::SortOptions;
-use arrow::datatypes::Schema;
+use arrow::datatypes::{DataType, Schema};
 use arrow_schema::Field;
 use datafusion_catalog::ScanArgs;
 use datafusion_common::Column;
@@ -67,18 +68,19 @@ use datafusion_common::HashMap as DFHashMap;
 use datafusion_common::display::ToStringifiedPlan;
 use datafusion_common::format::ExplainAnalyzeCategories;
 use datafusion_common::tree_node::{
-    Transformed, TreeNode, TreeNodeRecursion, TreeNodeVisitor,
+    Transformed, TransformedResult, TreeNode, TreeNodeRecursion, TreeNodeVisitor,
 };
 use datafusion_common::{
     DFSchema, DFSchemaRef, ScalarValue, exec_err, internal_datafusion_err, internal_err,
     not_impl_err, plan_err,
 };
 use datafusion_common::{
-    TableReference, assert_eq_or_internal_err, assert_or_internal_err,
+    SampleMethod, TableReference, assert_eq_or_internal_err, assert_or_internal_err,
 };
 use datafusion_datasource::file_groups::FileGroup;
 use datafusion_datasource::memory::MemorySourceConfig;
-use datafusion_expr::dml::{CopyTo, InsertOp};
+use datafusion_datasource::source::DataSourceExec;
+use datafusion_expr::dml::{CopyTo, InsertOp, MergeAction, MergeClause, MergeInto};
 use datafusion_expr::execution_props::{ScalarSubqueryResults, SubqueryIndex};
 use datafusion_expr::expr::{
     AggregateFunction, AggregateFunctionParams, Alias, GroupingSet, NullTreatment,
@@ -89,10 +91,11 @@ use datafusion_expr::logical_plan::Subquery;
 use datafusion_expr::logical_plan::builder::wrap_projection_for_join_if_necessary;
 use datafusion_expr::utils::{expr_to_columns, split_conjunction};
 use datafusion_expr::{
-    Analyze, BinaryExpr, DescribeTable, DmlStatement, Explain, ExplainFormat, Extension,
-    FetchType, Filter, JoinType, Operator, RecursiveQuery, SkipType, StringifiedPlan,
-    WindowFrame, WindowFrameBound, WriteOp,
+    Analyze, BinaryExpr, DescribeTable, DmlStatement, Explain, ExplainFormat,
+    ExprSchemable, Extension, FetchType, Filter, JoinType, Operator, RecursiveQuery,
+    Sample, SkipType, StringifiedPlan, WindowFrame, WindowFrameBound, WriteOp,
 };
+use datafusion_optimizer::analyzer::type_coercion::TypeCoercionRewriter;
 use datafusion_physical_expr::aggregate::{AggregateExprBuilder, AggregateFunctionExpr};
 use datafusion_physical_expr::expressions::Literal;
 use datafusion_physical_expr::{
@@ -101,7 +104,7 @@ use datafusion_physical_expr::{
 use datafusion_physical_optimizer::PhysicalOptimizerRule;
 use datafusion_physical_plan::empty::EmptyExec;
 use datafusion_physical_plan::execution_plan::InvariantLevel;
-use datafusion_physical_plan::joins::PiecewiseMergeJoinExec;
+use datafusion_physical_plan::joins::{AsOfJoinExec, PiecewiseMergeJoinExec};
 use datafusion_physical_plan::placeholder_row::PlaceholderRowExec;
 use datafusion_physical_plan::recursive_query::RecursiveQueryExec;
 use datafusion_physical_plan::scalar_subquery::{ScalarSubqueryExec, ScalarSubqueryLink};
@@ -737,7 +740,7 @@ impl DefaultPhysicalPlanner {
                 // the column name rather than column name + explicit data type.
                 let table_partition_cols = partition_by
                     .iter()
-                    .map(|s| (s.to_string(), arrow::datatypes::DataType::Null))
+                    .map(|s| (s.to_string(), DataType::Null))
                     .collect::<Vec<_>>();
 
                 let keep_partition_by_columns = match source_option_tuples
@@ -883,6 +886,31 @@ impl DefaultPhysicalPlanner {
                     );
                 }
             }
+            LogicalPlan::Dml(DmlStatement {
+                table_name,
+                target,
+                op: WriteOp::Merge(merge),
+                input,
+                ..
+            }) => {
+                if let Some(provider) = target.downcast_ref::<DefaultTableSource>() {
+                    let source = children.one()?;
+                    let schema =
+                        Arc::new(merge.schema(&target.schema(), input.schema())?);
+                    let merge = coerce_merge_into(merge, &schema)?;
+                    provider
+                        .table_provider
+                        .merge_into(session_state, source, schema, merge)
+                        .await
+                        .map_err(|e| {
+                            e.context(format!("MERGE operation on table '{table_name}'"))
+                        })?
+                } else {
+                    return exec_err!(
+                        "Table source can't be downcasted to DefaultTableSource"
+                    );
+                }
+            }
             LogicalPlan::Dml(DmlStatement {
                 table_name,
                 target,
@@ -1224,6 +1252,31 @@ impl DefaultPhysicalPlanner {
                     Arc::new(filter.with_default_selectivity(selectivity)?);
                 filter_exec
             }
+            LogicalPlan::Sample(Sample {
+                method,
+                fraction,
+                seed,
+                ..
+            }) => {
+                let physical_input = children.one()?;
+                // Block-level samples can avoid reading unselected blocks of files
+                let pushed_down = match physical_input.downcast_ref::<DataSourceExec>() {
+                    Some(scan) if *method == SampleMethod::System => scan
+                        .data_source()
+                        .try_pushdown_sample(*fraction, *seed)?
+                        .map(|data_source| scan.clone().with_data_source(data_source)),
+                    _ => None,
+                };
+                match pushed_down {
+                    Some(scan) => Arc::new(scan),
+                    None => Arc::new(SampleExec::try_new(
+                        physical_input,
+                        *method,
+                        *fraction,
+                        *seed,
+                    )?),
+                }
+            }
             LogicalPlan::Repartition(Repartition {
                 input,
                 partitioning_scheme,
@@ -1476,7 +1529,6 @@ impl DefaultPhysicalPlanner {
                     })
                     .collect::<Result<join_utils::JoinOn>>()?;
 
-                // TODO: `num_range_filters` can be used later on for ASOF joins (`num_range_filters > 1`)
                 let mut num_range_filters = 0;
                 let mut range_filters: Vec<Expr> = Vec::new();
                 let mut total_filters = 0;
@@ -1591,7 +1643,40 @@ impl DefaultPhysicalPlanner {
                     session_state.config_options().optimizer.prefer_hash_join;
 
                 // TODO: Allow PWMJ to deal with residual equijoin conditions
-                let join: Arc<dyn ExecutionPlan> = if join_on.is_empty() {
+                let join: Arc<dyn ExecutionPlan> = if *join_type == JoinType::LeftAsOf {
+                    // The join filter of an ASOF join is its match condition
+                    let Some(Expr::BinaryExpr(be)) = filter else {
+                        return plan_err!(
+                            "ASOF join requires its join filter to be a single match condition comparing both inputs"
+                        );
+                    };
+                    let Some((lhs_logical, op, rhs_logical)) =
+                        split_join_range_filter(be, left_df_schema, right_df_schema)?
+                    else {
+                        return plan_err!(
+                            "ASOF join match condition must compare an expression of the left input with an expression of the right input, got {be}"
+                        );
+                    };
+                    let match_left = create_physical_expr(
+                        lhs_logical,
+                        left_df_schema,
+                        execution_props,
+                    )?;
+                    let match_right = create_physical_expr(
+                        rhs_logical,
+                        right_df_schema,
+                        execution_props,
+                    )?;
+
+                    Arc::new(AsOfJoinExec::try_new(
+                        physical_left,
+                        physical_right,
+                        join_on,
+                        (match_left, match_right),
+                        op,
+                        *null_equality,
+                    )?)
+                } else if join_on.is_empty() {
                     if join_filter.is_none() && *join_type == JoinType::Inner {
                         // cross join if there is no join conditions and no join filter set
                         Arc::new(CrossJoinExec::new(physical_left, physical_right))
@@ -1617,57 +1702,9 @@ impl DefaultPhysicalPlanner {
                             );
                         };
 
-                        let mut op = be.op;
-                        if !matches!(
-                            op,
-                            Operator::Lt | Operator::LtEq | Operator::Gt | Operator::GtEq
-                        ) {
-                            return plan_err!(
-                                "Unsupported operator for PWMJ: {:?}. Expected one of <, <=, >, >=",
-                                op
-                            );
-                        }
-
-                        fn reverse_ineq(op: Operator) -> Operator {
-                            match op {
-                                Operator::Lt => Operator::Gt,
-                                Operator::LtEq => Operator::GtEq,
-                                Operator::Gt => Operator::Lt,
-                                Operator::GtEq => Operator::LtEq,
-                                _ => op,
-                            }
-                        }
-
-                        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-                        enum Side {
-                            Left,
-                            Right,
-                            Both,
-                        }
-
-                        let side_of = |e: &Expr| -> Result<Side> {
-                            let cols = e.column_refs();
-                            let any_left = cols
-                                .iter()
-                                .any(|c| left_df_schema.index_of_column(c).is_ok());
-                            let any_right = cols
-                                .iter()
-                                .any(|c| right_df_schema.index_of_column(c).is_ok());
-
-                            Ok(match (any_left, any_right) {
-                                (true, false) => Side::Left,
-                                (false, true) => Side::Right,
-                                (true, true) => Side::Both,
-                                _ => unreachable!(),
-                            })
-                        };
-
-                        let mut lhs_logical = &be.left;
-                        let mut rhs_logical = &be.right;
-
-                        let left_side = side_of(lhs_logical)?;
-                        let right_side = side_of(rhs_logical)?;
-                        if left_side == Side::Both || right_side == Side::Both {
+                        let Some((lhs_logical, op, rhs_logical)) =
+                            split_join_range_filter(be, left_df_schema, right_df_schema)?
+                        else {
                             return Ok(Arc::new(NestedLoopJoinExec::try_new(
                                 physical_left,
                                 physical_right,
@@ -1675,18 +1712,7 @@ impl DefaultPhysicalPlanner {
                                 join_type,
                                 None,
                             )?));
-                        }
-
-                        if left_side == Side::Right && right_side == Side::Left {
-                            std::mem::swap(&mut lhs_logical, &mut rhs_logical);
-                            op = reverse_ineq(op);
-                        } else if !(left_side == Side::Left && right_side == Side::Right)
-                        {
-                            return plan_err!(
-                                "Unsupported operator for PWMJ: {:?}. Expected one of <, <=, >, >=",
-                                op
-                            );
-                        }
+                        };
 
                         let on_left = create_physical_expr(
                             lhs_logical,
@@ -2143,6 +2169,61 @@ fn qualify_join_schema_sides(
     join_schema.with_field_specific_qualified_schema(qualifiers)
 }
 
+/// Splits a range comparison `lhs <op> rhs` in a join filter into
+/// `(left_expr, op, right_expr)`, where `left_expr` only references the left
+/// input and `right_expr` only references the right input. The operator is
+/// reversed if the operands have to be swapped.
+///
+/// Returns `None` if one of the operands references both inputs.
+fn split_join_range_filter<'a>(
+    filter: &'a BinaryExpr,
+    left_schema: &DFSchema,
+    right_schema: &DFSchema,
+) -> Result<Option<(&'a Expr, Operator, &'a Expr)>> {
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    enum Side {
+        Left,
+        Right,
+        Both,
+        Neither,
+    }
+
+    let side_of = |e: &Expr| {
+        let cols = e.column_refs();
+        let any_left = cols.iter().any(|c| left_schema.index_of_column(c).is_ok());
+        let any_right = cols.iter().any(|c| right_schema.index_of_column(c).is_ok());
+        match (any_left, any_right) {
+            (true, false) => Side::Left,
+            (false, true) => Side::Right,
+            (true, true) => Side::Both,
+            (false, false) => Side::Neither,
+        }
+    };
+
+    let op = filter.op;
+    let reversed_op = match op {
+        Operator::Lt => Operator::Gt,
+        Operator::LtEq => Operator::GtEq,
+        Operator::Gt => Operator::Lt,
+        Operator::GtEq => Operator::LtEq,
+        _ => {
+            return plan_err!(
+                "Unsupported operator for range join filter: {:?}. Expected one of <, <=, >, >=",
+                op
+            );
+        }
+    };
+
+    match (side_of(&filter.left), side_of(&filter.right)) {
+        (Side::Both, _) | (_, Side::Both) => Ok(None),
+        (Side::Left, Side::Right) => Ok(Some((&filter.left, op, &filter.right))),
+        (Side::Right, Side::Left) => Ok(Some((&filter.right, reversed_op, &filter.left))),
+        _ => plan_err!(
+            "Unsupported range join filter: {filter}. Expected an expression of each join input"
+        ),
+    }
+}
+
 fn get_physical_expr_pair(
     expr: &Expr,
     input_dfschema: &DFSchema,
@@ -2242,6 +2323,7 @@ fn extract_dml_filters(
             | LogicalPlan::Union(_)
             | LogicalPlan::Join(_)
             | LogicalPlan::Repartition(_)
+            | LogicalPlan::Sample(_)
             | LogicalPlan::Aggregate(_)
             | LogicalPlan::Window(_)
             | LogicalPlan::Subquery(_) => {
@@ -2380,6 +2462,53 @@ fn is_identity_assignment(expr: &Expr, column_name: &str) -> bool {
     }
 }
 
+/// Coerce the types of the `ON` condition and the `WHEN` clauses of a `MERGE`
+/// statement. Unlike the expressions of other DML statements, these are not
+/// part of the input plan and therefore not coerced by the analyzer.
+fn coerce_merge_into(merge: &MergeInto, schema: &DFSchema) -> Result<MergeInto> {
+    let coerce = |expr: &Expr| {
+        expr.clone()
+            .rewrite(&mut TypeCoercionRewriter::new(schema))
+            .data()
+    };
+    let coerce_predicate =
+        |expr: &Expr| coerce(expr)?.cast_to(&DataType::Boolean, schema);
+
+    let clauses = merge
+        .clauses
+        .iter()
+        .map(|clause| {
+            let action = match &clause.action {
+                MergeAction::Update(assignments) => MergeAction::Update(
+                    assignments
+                        .iter()
+                        .map(|(column, value)| Ok((column.clone(), coerce(value)?)))
+                        .collect::<Result<_>>()?,
+                ),
+                MergeAction::Insert(values) => {
+                    MergeAction::Insert(values.iter().map(coerce).collect::<Result<_>>()?)
+                }
+                MergeAction::Delete => MergeAction::Delete,
+            };
+            Ok(MergeClause {
+                kind: clause.kind,
+                predicate: clause
+                    .predicate
+                    .as_ref()
+                    .map(coerce_predicate)
+                    .transpose()?,
+                action,
+            })
+        })
+        .collect::<Result<_>>()?;
+
+    Ok(MergeInto {
+        target_alias: merge.target_alias.clone(),
+        on: coerce_predicate(&merge.on)?,
+        clauses,
+    })
+}
+
 /// Check if window bounds are valid after schema information is available, and
 /// window_frame bounds are casted to the corresponding column type.
 /// queries like:
@@ -25,6 +25,7 @@ use datafusion::execution::SessionStateBuilder;
 use datafusion_catalog_listing::helpers::{
     describe_partition, list_partitions, pruned_partition_list,
 };
+use datafusion_catalog_listing::{HivePartitionPathParser, TemplatePartitionPathParser};
 use datafusion_common::ScalarValue;
 use datafusion_datasource::ListingTableUrl;
 use datafusion_expr::{Expr, col, lit};
@@ -47,6 +48,7 @@ async fn test_pruned_partition_list_empty() {
         &[filter],
         ".parquet",
         &[(String::from("mypartition"), DataType::Utf8)],
+        &HivePartitionPathParser::new(),
     )
     .await
     .expect("partition pruning failed")
@@ -74,6 +76,7 @@ async fn test_pruned_partition_list() {
         &[filter],
         ".parquet",
         &[(String::from("mypartition"), DataType::Utf8)],
+        &HivePartitionPathParser::new(),
     )
     .await
     .expect("partition pruning failed")
@@ -117,6 +120,7 @@ async fn test_pruned_partition_list_multi() {
             (String::from("part1"), DataType::Utf8),
             (String::from("part2"), DataType::Utf8),
         ],
+        &HivePartitionPathParser::new(),
     )
     .await
     .expect("partition pruning failed")
@@ -145,6 +149,61 @@ async fn test_pruned_partition_list_multi() {
     );
 }
 
+#[tokio::test]
+async fn test_pruned_partition_list_template() {
+    let (store, state) = make_test_store_and_state(&[
+        ("tablepath/2024/01/file1.parquet", 100),
+        ("tablepath/2024/02/file2.parquet", 100),
+        ("tablepath/2025/01/file3.parquet", 100),
+        ("tablepath/2024/file4.parquet", 100),
+        ("tablepath/file5.parquet", 100),
+    ]);
+    let parser = TemplatePartitionPathParser::try_new("{year}/{month}")
+        .unwrap()
+        .with_missing_values_as_null(true);
+    let filter = Expr::eq(col("year"), lit(2024));
+    let pruned = pruned_partition_list(
+        state.as_ref(),
+        store.as_ref(),
+        &ListingTableUrl::parse("file:///tablepath/").unwrap(),
+        &[filter],
+        ".parquet",
+        &[
+            (String::from("year"), DataType::Int32),
+            (String::from("month"), DataType::Int32),
+        ],
+        &parser,
+    )
+    .await
+    .expect("partition pruning failed")
+    .try_collect::<Vec<_>>()
+    .await
+    .unwrap();
+
+    let mut pruned = pruned
+        .into_iter()
+        .map(|f| (f.object_meta.location.to_string(), f.partition_values))
+        .collect::<Vec<_>>();
+    pruned.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(
+        pruned,
+        vec![
+            (
+                "tablepath/2024/01/file1.parquet".to_string(),
+                vec![ScalarValue::Int32(Some(2024)), ScalarValue::Int32(Some(1))]
+            ),
+            (
+                "tablepath/2024/02/file2.parquet".to_string(),
+                vec![ScalarValue::Int32(Some(2024)), ScalarValue::Int32(Some(2))]
+            ),
+            (
+                "tablepath/2024/file4.parquet".to_string(),
+                vec![ScalarValue::Int32(Some(2024)), ScalarValue::Int32(None)]
+            ),
+        ]
+    );
+}
+
 #[tokio::test]
 async fn test_list_partition() {
     let (store, _) = make_test_store_and_state(&[
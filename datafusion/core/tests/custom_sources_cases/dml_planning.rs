@@ -15,7 +15,7 @@
 // specific language governing permissions and limitations
 // under the License.
 
-//! Tests for DELETE, UPDATE, and TRUNCATE planning to verify filter and assignment extraction.
+//! Tests for DELETE, UPDATE, TRUNCATE and MERGE planning to verify filter and assignment extraction.
 
 use std::sync::{Arc, Mutex};
 
@@ -24,12 +24,13 @@ use async_trait::async_trait;
 use datafusion::datasource::{TableProvider, TableType};
 use datafusion::error::Result;
 use datafusion::execution::context::{SessionConfig, SessionContext};
+use datafusion::logical_expr::dml::{MergeAction, MergeClauseKind, MergeInto};
 use datafusion::logical_expr::{
     Expr, LogicalPlan, TableProviderFilterPushDown, TableScan,
 };
 use datafusion_catalog::Session;
-use datafusion_common::ScalarValue;
 use datafusion_common::tree_node::{TreeNode, TreeNodeRecursion};
+use datafusion_common::{DFSchemaRef, ScalarValue};
 use datafusion_physical_plan::ExecutionPlan;
 use datafusion_physical_plan::empty::EmptyExec;
 
@@ -287,6 +288,67 @@ impl TableProvider for CaptureTruncateProvider {
     }
 }
 
+/// A TableProvider that captures the schema and clauses passed to merge_into().
+struct CaptureMergeProvider {
+    schema: SchemaRef,
+    received_merge: Arc<Mutex<Option<(DFSchemaRef, MergeInto)>>>,
+}
+
+impl CaptureMergeProvider {
+    fn new(schema: SchemaRef) -> Self {
+        Self {
+            schema,
+            received_merge: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    fn captured_merge(&self) -> Option<(DFSchemaRef, MergeInto)> {
+        self.received_merge.lock().unwrap().clone()
+    }
+}
+
+impl std::fmt::Debug for CaptureMergeProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CaptureMergeProvider")
+            .field("schema", &self.schema)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl TableProvider for CaptureMergeProvider {
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.schema)
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Base
+    }
+
+    async fn scan(
+        &self,
+        _state: &dyn Session,
+        _projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        _limit: Option<usize>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(EmptyExec::new(Arc::clone(&self.schema))))
+    }
+
+    async fn merge_into(
+        &self,
+        _state: &dyn Session,
+        _source: Arc<dyn ExecutionPlan>,
+        schema: DFSchemaRef,
+        merge: MergeInto,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        *self.received_merge.lock().unwrap() = Some((schema, merge));
+        Ok(Arc::new(EmptyExec::new(Arc::new(Schema::new(vec![
+            Field::new("count", DataType::UInt64, false),
+        ])))))
+    }
+}
+
 fn test_schema() -> SchemaRef {
     Arc::new(Schema::new(vec![
         Field::new("id", DataType::Int32, false),
@@ -804,3 +866,68 @@ async fn test_unsupported_table_truncate() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_merge_into_clauses() -> Result<()> {
+    let provider = Arc::new(CaptureMergeProvider::new(test_schema()));
+    let ctx = SessionContext::new();
+    ctx.register_table("t", Arc::clone(&provider) as Arc<dyn TableProvider>)?;
+    ctx.sql("CREATE TABLE s (id BIGINT, value INT) AS VALUES (1, 10)")
+        .await?
+        .collect()
+        .await?;
+
+    ctx.sql(
+        "MERGE INTO t USING s ON t.id = s.id \
+         WHEN MATCHED AND s.value > 5 THEN UPDATE SET value = s.value \
+         WHEN NOT MATCHED THEN INSERT (id, value) VALUES (s.id, s.value) \
+         WHEN NOT MATCHED BY SOURCE THEN DELETE",
+    )
+    .await?
+    .collect()
+    .await?;
+
+    let (schema, merge) = provider.captured_merge().expect("merge should be captured");
+    let fields = schema
+        .iter()
+        .map(|(qualifier, field)| format!("{}.{}", qualifier.unwrap(), field.name()))
+        .collect::<Vec<_>>();
+    assert_eq!(fields, ["t.id", "t.status", "t.value", "s.id", "s.value"]);
+
+    // The types of the ON condition have been coerced
+    assert_eq!(merge.on.to_string(), "CAST(t.id AS Int64) = s.id");
+    assert_eq!(
+        merge
+            .clauses
+            .iter()
+            .map(|clause| (clause.kind, clause.predicate.is_some()))
+            .collect::<Vec<_>>(),
+        [
+            (MergeClauseKind::Matched, true),
+            (MergeClauseKind::NotMatchedByTarget, false),
+            (MergeClauseKind::NotMatchedBySource, false),
+        ]
+    );
+    let MergeAction::Insert(values) = &merge.clauses[1].action else {
+        panic!("expected INSERT, got {}", merge.clauses[1].action);
+    };
+    assert_eq!(values.len(), 3, "should have a value for every column");
+    assert_eq!(merge.clauses[2].action, MergeAction::Delete);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_unsupported_table_merge() -> Result<()> {
+    let schema = test_schema();
+    let ctx = SessionContext::new();
+
+    let empty_table = datafusion::datasource::empty::EmptyTable::new(schema);
+    ctx.register_table("empty_t", Arc::new(empty_table))?;
+
+    let result = ctx
+        .sql("MERGE INTO empty_t t USING (SELECT 1 AS id) s ON t.id = s.id WHEN MATCHED THEN DELETE")
+        .await;
+
+    assert!(result.is_err() || result.unwrap().collect().await.is_err());
+    Ok(())
+}
@@ -2622,6 +2622,7 @@ async fn verify_join_output_partitioning() -> Result<()> {
                         out_partitioning,
                     &Partitioning::UnknownPartitioning(partition_count) if partition_count == default_partition_count));
             }
+            JoinType::LeftAsOf => unreachable!("ASOF joins require a match condition"),
         }
     }
 
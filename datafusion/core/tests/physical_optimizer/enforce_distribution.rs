@@ -829,7 +829,10 @@ fn multi_hash_joins() -> Result<()> {
                 let plan_sort = test_config.to_plan(top_join, &SORT_DISTRIB_DISTRIB);
                 assert_plan!(plan_distrib, plan_sort);
             }
-            JoinType::RightSemi | JoinType::RightAnti | JoinType::RightMark => {}
+            JoinType::RightSemi
+            | JoinType::RightAnti
+            | JoinType::RightMark
+            | JoinType::LeftAsOf => {}
         }
 
 
@@ -898,7 +901,10 @@ fn multi_hash_joins() -> Result<()> {
                 let plan_sort = test_config.to_plan(top_join, &SORT_DISTRIB_DISTRIB);
                         assert_plan!(plan_distrib, plan_sort);
             }
-            JoinType::LeftSemi | JoinType::LeftAnti | JoinType::LeftMark => {}
+            JoinType::LeftSemi
+            | JoinType::LeftAnti
+            | JoinType::LeftMark
+            | JoinType::LeftAsOf => {}
         }
 
                 });
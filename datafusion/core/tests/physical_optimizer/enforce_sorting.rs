@@ -33,7 +33,7 @@ use arrow::compute::{SortOptions};
 use arrow::datatypes::{DataType, SchemaRef};
 use datafusion_common::config::{ConfigOptions, CsvOptions};
 use datafusion_common::tree_node::{TreeNode, TransformedResult};
-use datafusion_common::{create_array, Result, TableReference};
+use datafusion_common::{create_array, NullEquality, Result, TableReference};
 use datafusion_datasource::file_scan_config::FileScanConfigBuilder;
 use datafusion_datasource::source::DataSourceExec;
 use datafusion_expr_common::operator::Operator;
@@ -44,6 +44,7 @@ use datafusion_physical_expr_common::sort_expr::{
 };
 use datafusion_physical_expr::{Distribution, Partitioning};
 use datafusion_physical_expr::expressions::{col, BinaryExpr, Column, NotExpr};
+use datafusion_physical_plan::joins::AsOfJoinExec;
 use datafusion_physical_plan::limit::{GlobalLimitExec, LocalLimitExec};
 use datafusion_physical_plan::repartition::RepartitionExec;
 use datafusion_physical_plan::sorts::sort_preserving_merge::SortPreservingMergeExec;
@@ -2850,3 +2851,92 @@ async fn test_sort_with_streaming_table() -> Result<()> {
 
     Ok(())
 }
+
+/// Runs only the `ensure_sorting` pass of [`EnforceSorting`], as sort pushdown
+/// adds back the sorts required by operators
+fn ensure_sorting_plan(plan: Arc<dyn ExecutionPlan>) -> Result<String> {
+    let adjusted = PlanWithCorrespondingSort::new_default(plan)
+        .transform_up(ensure_sorting)
+        .data()
+        .and_then(check_integrity)?;
+    Ok(displayable(adjusted.plan.as_ref()).indent(true).to_string())
+}
+
+/// Creates an as-of join on `nullable_col = col_a` with the match condition
+/// `non_nullable_col >= col_b`
+fn asof_join_exec(
+    left: Arc<dyn ExecutionPlan>,
+    right: Arc<dyn ExecutionPlan>,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    let on = vec![(
+        col("nullable_col", &left.schema())?,
+        col("col_a", &right.schema())?,
+    )];
+    let match_on = (
+        col("non_nullable_col", &left.schema())?,
+        col("col_b", &right.schema())?,
+    );
+    Ok(Arc::new(AsOfJoinExec::try_new(
+        left,
+        right,
+        on,
+        match_on,
+        Operator::GtEq,
+        NullEquality::NullEqualsNothing,
+    )?))
+}
+
+#[test]
+fn test_sort_above_asof_join_keeps_sort_required_by_join() -> Result<()> {
+    let left = parquet_exec(create_test_schema()?);
+    let right_schema = create_test_schema2()?;
+    let right_ordering = [
+        sort_expr("col_a", &right_schema),
+        sort_expr("col_b", &right_schema),
+    ]
+    .into();
+    let right = sort_exec(right_ordering, parquet_exec(right_schema));
+    let join = asof_join_exec(left, right)?;
+    // The join does not propagate the ordering of its right input, so the
+    // sort below it is not connected to the sort above it
+    let physical_plan = sort_exec([sort_expr("col_b", &join.schema())].into(), join);
+
+    assert_snapshot!(ensure_sorting_plan(physical_plan)?, @r"
+    SortExec: expr=[col_b@3 ASC], preserve_partitioning=[false]
+      AsOfJoinExec: match_condition=(non_nullable_col >= col_b), on=[(nullable_col@0, col_a@0)]
+        DataSourceExec: file_groups={1 group: [[x]]}, projection=[nullable_col, non_nullable_col], file_type=parquet
+        SortExec: expr=[col_a@0 ASC, col_b@1 ASC], preserve_partitioning=[false]
+          DataSourceExec: file_groups={1 group: [[x]]}, projection=[col_a, col_b], file_type=parquet
+    ");
+    Ok(())
+}
+
+#[test]
+fn test_sort_above_asof_join_removes_only_sort_of_left_input() -> Result<()> {
+    let left_schema = create_test_schema()?;
+    let left = sort_exec(
+        [sort_expr("nullable_col", &left_schema)].into(),
+        parquet_exec(left_schema),
+    );
+    let right_schema = create_test_schema2()?;
+    let right_ordering = [
+        sort_expr("col_a", &right_schema),
+        sort_expr("col_b", &right_schema),
+    ]
+    .into();
+    let right = sort_exec(right_ordering, parquet_exec(right_schema));
+    let join = asof_join_exec(left, right)?;
+    // The sort of the left input is overridden by the sort above the join,
+    // while the sort of the right input is required by the join
+    let physical_plan =
+        sort_exec([sort_expr("non_nullable_col", &join.schema())].into(), join);
+
+    assert_snapshot!(ensure_sorting_plan(physical_plan)?, @r"
+    SortExec: expr=[non_nullable_col@1 ASC], preserve_partitioning=[false]
+      AsOfJoinExec: match_condition=(non_nullable_col >= col_b), on=[(nullable_col@0, col_a@0)]
+        DataSourceExec: file_groups={1 group: [[x]]}, projection=[nullable_col, non_nullable_col], file_type=parquet
+        SortExec: expr=[col_a@0 ASC, col_b@1 ASC], preserve_partitioning=[false]
+          DataSourceExec: file_groups={1 group: [[x]]}, projection=[col_a, col_b], file_type=parquet
+    ");
+    Ok(())
+}
@@ -0,0 +1,70 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use super::*;
+use datafusion::physical_plan::filter::FilterExec;
+use datafusion_common::stats::Precision;
+
+/// Returns the estimated number of rows of the first [`FilterExec`] of the plan
+fn filter_rows(
+    ctx: &SessionContext,
+    plan: &Arc<dyn ExecutionPlan>,
+) -> Result<Precision<usize>> {
+    let mut plan = Arc::clone(plan);
+    while plan.downcast_ref::<FilterExec>().is_none() {
+        plan = Arc::clone(plan.children()[0]);
+    }
+    let state = ctx.state();
+    let registry = state.statistics_registry().unwrap();
+    Ok(registry.compute(plan.as_ref())?.base().num_rows)
+}
+
+#[tokio::test]
+async fn analyze_table_histograms_change_filter_estimate() -> Result<()> {
+    let tmp_dir = TempDir::new()?;
+    let mut file = File::create(tmp_dir.path().join("skewed.csv"))?;
+    // 90 rows with a = 1 and one row with each of a = 2..=11
+    writeln!(file, "a")?;
+    for _ in 0..90 {
+        writeln!(file, "1")?;
+    }
+    for a in 2..=11 {
+        writeln!(file, "{a}")?;
+    }
+
+    let ctx =
+        SessionContext::new_with_config(SessionConfig::new().with_target_partitions(1));
+    let schema = Schema::new(vec![Field::new("a", DataType::Int32, false)]);
+    ctx.register_csv(
+        "t",
+        tmp_dir.path().to_str().unwrap(),
+        CsvReadOptions::new().schema(&schema),
+    )
+    .await?;
+
+    let sql = "SELECT a FROM t WHERE a = 1";
+    let plan = ctx.sql(sql).await?.create_physical_plan().await?;
+    assert_eq!(filter_rows(&ctx, &plan)?, Precision::Absent);
+
+    plan_and_collect(&ctx, "ANALYZE TABLE t").await?;
+
+    // The most common values collected by ANALYZE TABLE estimate the filter
+    let plan = ctx.sql(sql).await?.create_physical_plan().await?;
+    assert_eq!(filter_rows(&ctx, &plan)?, Precision::Inexact(90));
+
+    Ok(())
+}
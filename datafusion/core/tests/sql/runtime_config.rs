@@ -344,6 +344,68 @@ async fn test_list_files_cache_ttl() {
     assert_eq!(get_limit(&ctx), Duration::from_secs(90));
 }
 
+#[tokio::test]
+async fn test_byte_range_cache() {
+    let ctx = SessionContext::new();
+    assert!(
+        ctx.task_ctx()
+            .runtime_env()
+            .cache_manager
+            .get_byte_range_cache()
+            .is_none()
+    );
+
+    ctx.sql("SET datafusion.runtime.byte_range_cache_limit = '10M'")
+        .await
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+
+    let cache = ctx
+        .task_ctx()
+        .runtime_env()
+        .cache_manager
+        .get_byte_range_cache()
+        .unwrap();
+    assert_eq!(cache.cache_limit(), 10 * 1024 * 1024);
+
+    let testdata = datafusion::test_util::parquet_test_data();
+    ctx.register_parquet(
+        "alltypes_plain",
+        format!("{testdata}/alltypes_plain.parquet"),
+        Default::default(),
+    )
+    .await
+    .unwrap();
+
+    let query = "SELECT id, string_col FROM alltypes_plain";
+    let expected = ctx.sql(query).await.unwrap().collect().await.unwrap();
+    let metrics = cache.metrics();
+    assert_eq!(metrics.hits, 0);
+    assert!(metrics.misses > 0);
+    assert!(metrics.memory_used > 0);
+
+    // the column chunks are served from the cache the second time
+    let actual = ctx.sql(query).await.unwrap().collect().await.unwrap();
+    assert_eq!(actual, expected);
+    assert!(cache.metrics().hits > 0);
+
+    ctx.sql("SET datafusion.runtime.byte_range_cache_limit = '0K'")
+        .await
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    assert!(
+        ctx.task_ctx()
+            .runtime_env()
+            .cache_manager
+            .get_byte_range_cache()
+            .is_none()
+    );
+}
+
 #[tokio::test]
 async fn test_unknown_runtime_config() {
     let ctx = SessionContext::new();
@@ -1369,6 +1369,7 @@ async fn create_scalar_function_from_sql_statement_postgres_syntax() -> Result<(
             default_expr: None,
         }]),
         return_type: Some(DataType::Int32),
+        returns_table: false,
         params: CreateFunctionBody {
             language: Some(Ident {
                 value: "plrust".into(),
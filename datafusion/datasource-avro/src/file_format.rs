@@ -17,30 +17,65 @@
 
 //! Apache Avro [`FileFormat`] abstractions
 use std::collections::HashMap;
-use std::fmt;
+use std::fmt::{self, Debug};
 use std::sync::Arc;
 
 use crate::read_avro_schema_from_reader;
 use crate::source::AvroSource;
 
+use arrow::array::RecordBatch;
 use arrow::datatypes::Schema;
 use arrow::datatypes::SchemaRef;
+use arrow_avro::compression::CompressionCodec;
+use arrow_avro::writer::{AvroWriter, WriterBuilder};
+use bytes::Bytes;
 use datafusion_common::DEFAULT_AVRO_EXTENSION;
 use datafusion_common::GetExt;
+use datafusion_common::config::{AvroOptions, ConfigFileType};
+use datafusion_common::file_options::avro_writer::{AvroCodec, AvroWriterOptions};
 use datafusion_common::internal_err;
+use datafusion_common::not_impl_err;
 use datafusion_common::parsers::CompressionTypeVariant;
 use datafusion_common::{Result, Statistics};
+use datafusion_common_runtime::SpawnedTask;
+use datafusion_datasource::display::FileGroupDisplay;
 use datafusion_datasource::file::FileSource;
 use datafusion_datasource::file_compression_type::FileCompressionType;
 use datafusion_datasource::file_format::{FileFormat, FileFormatFactory};
 use datafusion_datasource::file_scan_config::FileScanConfig;
+use datafusion_datasource::file_sink_config::{FileSink, FileSinkConfig};
+use datafusion_datasource::sink::{DataSink, DataSinkExec};
 use datafusion_datasource::source::DataSourceExec;
-use datafusion_physical_plan::ExecutionPlan;
+use datafusion_datasource::write::demux::DemuxedStreamReceiver;
+use datafusion_datasource::write::orchestration::spawn_stateful_writer_tasks_and_join;
+use datafusion_datasource::write::{
+    SharedBuffer, StatefulBatchSerializer, get_writer_schema,
+};
+use datafusion_execution::{SendableRecordBatchStream, TaskContext};
+use datafusion_expr::dml::InsertOp;
+use datafusion_physical_expr_common::sort_expr::LexRequirement;
+use datafusion_physical_plan::{DisplayAs, DisplayFormatType, ExecutionPlan};
 use datafusion_session::Session;
 
 use async_trait::async_trait;
 use object_store::{GetResultPayload, ObjectMeta, ObjectStore, ObjectStoreExt};
 
+/// Initial writing buffer size. Note this is just a size hint for efficiency. It
+/// will grow beyond the set value if needed.
+const INITIAL_BUFFER_BYTES: usize = 1048576;
+
+/// Schema metadata key of the name of the top-level Avro record, read by the
+/// Avro writer
+const AVRO_NAME_METADATA_KEY: &str = "avro.name";
+
+/// Schema metadata key of the namespace of the top-level Avro record, read by
+/// the Avro writer
+const AVRO_NAMESPACE_METADATA_KEY: &str = "avro.namespace";
+
+/// Schema metadata key of a verbatim Avro schema, which takes precedence over
+/// the schema derived from the Arrow schema in the Avro writer
+const AVRO_SCHEMA_METADATA_KEY: &str = "avro.schema";
+
 #[derive(Default)]
 /// Factory struct used to create [`AvroFormat`]
 pub struct AvroFormatFactory;
@@ -55,14 +90,20 @@ impl AvroFormatFactory {
 impl FileFormatFactory for AvroFormatFactory {
     fn create(
         &self,
-        _state: &dyn Session,
-        _format_options: &HashMap<String, String>,
+        state: &dyn Session,
+        format_options: &HashMap<String, String>,
     ) -> Result<Arc<dyn FileFormat>> {
-        Ok(Arc::new(AvroFormat))
+        let mut table_options = state.default_table_options();
+        table_options.set_config_format(ConfigFileType::AVRO);
+        table_options.alter_with_string_hash_map(format_options)?;
+
+        Ok(Arc::new(
+            AvroFormat::default().with_options(table_options.avro),
+        ))
     }
 
     fn default(&self) -> Arc<dyn FileFormat> {
-        Arc::new(AvroFormat)
+        Arc::new(AvroFormat::default())
     }
 }
 
@@ -81,7 +122,22 @@ impl GetExt for AvroFormatFactory {
 
 /// Avro [`FileFormat`] implementation.
 #[derive(Default, Debug)]
-pub struct AvroFormat;
+pub struct AvroFormat {
+    options: AvroOptions,
+}
+
+impl AvroFormat {
+    /// Set Avro options
+    pub fn with_options(mut self, options: AvroOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Retrieve Avro options
+    pub fn options(&self) -> &AvroOptions {
+        &self.options
+    }
+}
 
 #[async_trait]
 impl FileFormat for AvroFormat {
@@ -147,6 +203,23 @@ impl FileFormat for AvroFormat {
         Ok(DataSourceExec::from_data_source(conf))
     }
 
+    async fn create_writer_physical_plan(
+        &self,
+        input: Arc<dyn ExecutionPlan>,
+        _state: &dyn Session,
+        conf: FileSinkConfig,
+        order_requirements: Option<LexRequirement>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        if conf.insert_op != InsertOp::Append {
+            return not_impl_err!("Overwrites are not implemented yet for Avro format");
+        }
+
+        let writer_options = AvroWriterOptions::try_from(&self.options)?;
+        let sink = Arc::new(AvroSink::new(conf, writer_options));
+
+        Ok(Arc::new(DataSinkExec::new(input, sink, order_requirements)) as _)
+    }
+
     fn file_source(
         &self,
         table_schema: datafusion_datasource::TableSchema,
@@ -154,3 +227,149 @@ impl FileFormat for AvroFormat {
         Arc::new(AvroSource::new(table_schema))
     }
 }
+
+/// Implements [`DataSink`] for writing Avro object container files
+pub struct AvroSink {
+    /// Config options for writing data
+    config: FileSinkConfig,
+    /// Writer options for the underlying Avro writer
+    writer_options: AvroWriterOptions,
+}
+
+impl Debug for AvroSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AvroSink").finish()
+    }
+}
+
+impl DisplayAs for AvroSink {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                write!(f, "AvroSink(file_groups=",)?;
+                FileGroupDisplay(&self.config.file_group).fmt_as(t, f)?;
+                write!(f, ")")
+            }
+            DisplayFormatType::TreeRender => {
+                writeln!(f, "format: avro")?;
+                write!(f, "file={}", &self.config.original_url)
+            }
+        }
+    }
+}
+
+impl AvroSink {
+    /// Create from config.
+    pub fn new(config: FileSinkConfig, writer_options: AvroWriterOptions) -> Self {
+        Self {
+            config,
+            writer_options,
+        }
+    }
+
+    /// Retrieve the writer options
+    pub fn writer_options(&self) -> &AvroWriterOptions {
+        &self.writer_options
+    }
+
+    /// The schema of the written files, named as configured in the writer
+    /// options
+    fn writer_schema(&self) -> Schema {
+        let schema = get_writer_schema(&self.config);
+        let mut metadata = schema.metadata().clone();
+        // The schema of the input is not necessarily the schema of the output
+        metadata.remove(AVRO_SCHEMA_METADATA_KEY);
+        if let Some(name) = &self.writer_options.schema_name {
+            metadata.insert(AVRO_NAME_METADATA_KEY.to_string(), name.clone());
+        }
+        if let Some(namespace) = &self.writer_options.schema_namespace {
+            metadata.insert(AVRO_NAMESPACE_METADATA_KEY.to_string(), namespace.clone());
+        }
+        Schema::new_with_metadata(schema.fields().clone(), metadata)
+    }
+}
+
+#[async_trait]
+impl FileSink for AvroSink {
+    fn config(&self) -> &FileSinkConfig {
+        &self.config
+    }
+
+    async fn spawn_writer_tasks_and_join(
+        &self,
+        context: &Arc<TaskContext>,
+        demux_task: SpawnedTask<Result<()>>,
+        file_stream_rx: DemuxedStreamReceiver,
+        object_store: Arc<dyn ObjectStore>,
+    ) -> Result<u64> {
+        let schema = self.writer_schema();
+        let codec = self.writer_options.codec.map(|codec| match codec {
+            AvroCodec::Deflate => CompressionCodec::Deflate,
+            AvroCodec::Snappy => CompressionCodec::Snappy,
+            AvroCodec::Zstd => CompressionCodec::ZStandard,
+            AvroCodec::Bzip2 => CompressionCodec::Bzip2,
+            AvroCodec::Xz => CompressionCodec::Xz,
+        });
+        spawn_stateful_writer_tasks_and_join(
+            context,
+            || AvroSerializer::try_new(schema.clone(), codec),
+            object_store,
+            demux_task,
+            file_stream_rx,
+        )
+        .await
+    }
+}
+
+#[async_trait]
+impl DataSink for AvroSink {
+    fn schema(&self) -> &SchemaRef {
+        self.config.output_schema()
+    }
+
+    async fn write_all(
+        &self,
+        data: SendableRecordBatchStream,
+        context: &Arc<TaskContext>,
+    ) -> Result<u64> {
+        FileSink::write_all(self, data, context).await
+    }
+}
+
+/// Serializes the [`RecordBatch`]es of an Avro object container file, writing
+/// each batch as a data block.
+struct AvroSerializer {
+    writer: AvroWriter<SharedBuffer>,
+    buffer: SharedBuffer,
+}
+
+impl AvroSerializer {
+    fn try_new(
+        schema: Schema,
+        codec: Option<CompressionCodec>,
+    ) -> Result<Box<dyn StatefulBatchSerializer>> {
+        let buffer = SharedBuffer::new(INITIAL_BUFFER_BYTES);
+        let writer = WriterBuilder::new(schema)
+            .with_compression(codec)
+            .build(buffer.clone())?;
+        Ok(Box::new(Self { writer, buffer }))
+    }
+
+    /// Takes the bytes written so far
+    fn take_buffer(&self) -> Bytes {
+        let mut buffer = self.buffer.buffer.try_lock().unwrap();
+        Bytes::from(std::mem::take(&mut *buffer))
+    }
+}
+
+impl StatefulBatchSerializer for AvroSerializer {
+    fn serialize(&mut self, batch: &RecordBatch) -> Result<Bytes> {
+        self.writer.write(batch)?;
+        Ok(self.take_buffer())
+    }
+
+    fn finish(&mut self) -> Result<Bytes> {
+        self.writer.finish()?;
+        Ok(self.take_buffer())
+    }
+}
@@ -27,11 +27,16 @@ use std::task::Poll;
 use datafusion_datasource::decoder::{DecoderDeserializer, deserialize_stream};
 use datafusion_datasource::file_compression_type::FileCompressionType;
 use datafusion_datasource::file_stream::{FileOpenFuture, FileOpener};
+use datafusion_datasource::morsel::{
+    FileOpenerMorselizer, Morselizer, NewlineDelimitedDecoder,
+    NewlineDelimitedMorselizer, ProjectionMorselizer,
+};
 use datafusion_datasource::{
     FileRange, ListingTableUrl, PartitionedFile, RangeCalculation, TableSchema,
     as_file_source, calculate_range,
 };
 
+use arrow::array::RecordBatch;
 use arrow::csv;
 use datafusion_common::config::CsvOptions;
 use datafusion_common::tree_node::TreeNodeRecursion;
@@ -46,6 +51,8 @@ use datafusion_physical_plan::{
 };
 
 use crate::file_format::CsvDecoder;
+use bytes::Bytes;
+use futures::stream::BoxStream;
 use futures::{StreamExt, TryStreamExt};
 use object_store::buffered::BufWriter;
 use object_store::{GetOptions, GetResultPayload, ObjectStore};
@@ -235,6 +242,32 @@ impl CsvOpener {
     }
 }
 
+/// Decodes the morsels of CSV files without newlines in values
+#[derive(Debug)]
+struct CsvMorselDecoder {
+    config: Arc<CsvSource>,
+}
+
+impl NewlineDelimitedDecoder for CsvMorselDecoder {
+    fn decode(
+        &self,
+        morsel: Bytes,
+        at_file_start: bool,
+    ) -> Result<BoxStream<'static, Result<RecordBatch>>> {
+        // Only the morsel at the start of the file contains the header
+        let mut config = (*self.config).clone();
+        config.options.has_header = Some(config.has_header() && at_file_start);
+        config.options.truncated_rows = Some(config.truncate_rows());
+
+        let decoder = config.builder().build_decoder();
+        let stream = deserialize_stream(
+            futures::stream::iter([Ok(morsel)]),
+            DecoderDeserializer::new(CsvDecoder::new(decoder)),
+        );
+        Ok(stream.map_err(Into::into).boxed())
+    }
+}
+
 impl From<CsvSource> for Arc<dyn FileSource> {
     fn from(source: CsvSource) -> Self {
         as_file_source(source)
@@ -262,6 +295,44 @@ impl FileSource for CsvSource {
         Ok(opener)
     }
 
+    fn create_morselizer(
+        &self,
+        object_store: Arc<dyn ObjectStore>,
+        base_config: &FileScanConfig,
+        partition_index: usize,
+    ) -> Result<Box<dyn Morselizer>> {
+        // Compressed files can only be read as a stream and lines of files
+        // with newlines in values cannot be found by byte offset
+        if base_config.file_compression_type.is_compressed()
+            || !self.supports_repartitioning()
+        {
+            let opener =
+                self.create_file_opener(object_store, base_config, partition_index)?;
+            return Ok(Box::new(FileOpenerMorselizer::new(opener)));
+        }
+
+        let config = Arc::new(self.clone());
+        let fallback = FileOpenerMorselizer::new(Arc::new(CsvOpener {
+            config: Arc::clone(&config),
+            file_compression_type: base_config.file_compression_type,
+            object_store: Arc::clone(&object_store),
+            partition_index,
+        }));
+        let mut morselizer = NewlineDelimitedMorselizer::new(
+            object_store,
+            Arc::new(CsvMorselDecoder { config }),
+            fallback,
+        );
+        if let Some(terminator) = self.terminator() {
+            morselizer = morselizer.with_newline(terminator);
+        }
+        Ok(Box::new(ProjectionMorselizer::try_new(
+            self.projection.clone(),
+            Box::new(morselizer),
+            self.table_schema.file_schema(),
+        )?))
+    }
+
     fn table_schema(&self) -> &TableSchema {
         &self.table_schema
     }
@@ -34,6 +34,10 @@ use datafusion_common_runtime::{JoinSet, SpawnedTask};
 use datafusion_datasource::decoder::{DecoderDeserializer, deserialize_stream};
 use datafusion_datasource::file_compression_type::FileCompressionType;
 use datafusion_datasource::file_stream::{FileOpenFuture, FileOpener};
+use datafusion_datasource::morsel::{
+    FileOpenerMorselizer, Morselizer, NewlineDelimitedDecoder,
+    NewlineDelimitedMorselizer, ProjectionMorselizer,
+};
 use datafusion_datasource::projection::{ProjectionOpener, SplitProjection};
 use datafusion_datasource::{ListingTableUrl, PartitionedFile, as_file_source};
 use datafusion_physical_plan::projection::ProjectionExprs;
@@ -47,6 +51,8 @@ use datafusion_datasource::file_scan_config::FileScanConfig;
 use datafusion_execution::TaskContext;
 use datafusion_physical_plan::metrics::ExecutionPlanMetricsSet;
 
+use bytes::Bytes;
+use futures::stream::BoxStream;
 use futures::{Stream, StreamExt, TryStreamExt};
 use object_store::buffered::BufWriter;
 use object_store::{GetOptions, GetResultPayload, ObjectStore};
@@ -160,6 +166,35 @@ impl JsonSource {
         self.newline_delimited = newline_delimited;
         self
     }
+
+    /// true if the files are read as newline-delimited JSON
+    pub fn newline_delimited(&self) -> bool {
+        self.newline_delimited
+    }
+}
+
+/// Decodes the morsels of newline-delimited JSON files
+#[derive(Debug)]
+struct NdJsonMorselDecoder {
+    batch_size: usize,
+    projected_schema: SchemaRef,
+}
+
+impl NewlineDelimitedDecoder for NdJsonMorselDecoder {
+    fn decode(
+        &self,
+        morsel: Bytes,
+        _at_file_start: bool,
+    ) -> Result<BoxStream<'static, Result<RecordBatch>>> {
+        let decoder = ReaderBuilder::new(Arc::clone(&self.projected_schema))
+            .with_batch_size(self.batch_size)
+            .build_decoder()?;
+        let stream = deserialize_stream(
+            futures::stream::iter([Ok(morsel)]),
+            DecoderDeserializer::new(JsonDecoder::new(decoder)),
+        );
+        Ok(stream.map_err(Into::into).boxed())
+    }
 }
 
 impl From<JsonSource> for Arc<dyn FileSource> {
@@ -200,6 +235,47 @@ impl FileSource for JsonSource {
         Ok(opener)
     }
 
+    fn create_morselizer(
+        &self,
+        object_store: Arc<dyn ObjectStore>,
+        base_config: &FileScanConfig,
+        partition: usize,
+    ) -> Result<Box<dyn Morselizer>> {
+        // JSON arrays and compressed files have to be read as a whole
+        if !self.newline_delimited || base_config.file_compression_type.is_compressed() {
+            let opener = self.create_file_opener(object_store, base_config, partition)?;
+            return Ok(Box::new(FileOpenerMorselizer::new(opener)));
+        }
+
+        let file_schema = self.table_schema.file_schema();
+        let projected_schema =
+            Arc::new(file_schema.project(&self.projection.file_indices)?);
+        let batch_size = self
+            .batch_size
+            .expect("Batch size must set before creating morselizer");
+
+        let fallback = FileOpenerMorselizer::new(Arc::new(JsonOpener {
+            batch_size,
+            projected_schema: Arc::clone(&projected_schema),
+            file_compression_type: base_config.file_compression_type,
+            object_store: Arc::clone(&object_store),
+            newline_delimited: true,
+        }));
+        let morselizer = NewlineDelimitedMorselizer::new(
+            object_store,
+            Arc::new(NdJsonMorselDecoder {
+                batch_size,
+                projected_schema,
+            }),
+            fallback,
+        );
+        Ok(Box::new(ProjectionMorselizer::try_new(
+            self.projection.clone(),
+            Box::new(morselizer),
+            file_schema,
+        )?))
+    }
+
     fn table_schema(&self) -> &datafusion_datasource::TableSchema {
         &self.table_schema
     }
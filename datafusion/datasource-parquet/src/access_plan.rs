@@ -19,6 +19,8 @@ use crate::sort::reverse_row_selection;
 use datafusion_common::{Result, assert_eq_or_internal_err};
 use parquet::arrow::arrow_reader::{RowSelection, RowSelector};
 use parquet::file::metadata::{ParquetMetaData, RowGroupMetaData};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
 /// A selection of rows and row groups within a ParquetFile to decode.
 ///
@@ -93,6 +95,42 @@ pub struct ParquetAccessPlan {
     row_groups: Vec<RowGroupAccess>,
 }
 
+/// A block-level `TABLESAMPLE SYSTEM` sample pushed into a `ParquetSource`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct RowGroupSample {
+    /// Probability of each row group to be read
+    pub(crate) fraction: f64,
+    /// Seed of the random number generator, `None` for a random sample
+    pub(crate) seed: Option<u64>,
+}
+
+impl RowGroupSample {
+    /// Skip the row groups of `file_name` that are not part of the sample
+    pub(crate) fn apply(&self, access_plan: &mut ParquetAccessPlan, file_name: &str) {
+        // derive a seed per file so that files with the same number of row
+        // groups don't all keep the same row groups
+        let seed = match self.seed {
+            Some(seed) => file_seed(seed, file_name),
+            None => rand::random(),
+        };
+        access_plan.sample_row_groups(self.fraction, seed);
+    }
+}
+
+/// Derives the seed of the sample of a file from the seed of the sample and
+/// the file name, using FNV-1a so that the seed does not change across Rust
+/// releases or platforms
+fn file_seed(seed: u64, file_name: &str) -> u64 {
+    const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const FNV_PRIME: u64 = 0x100000001b3;
+    seed.to_le_bytes()
+        .iter()
+        .chain(file_name.as_bytes())
+        .fold(FNV_OFFSET_BASIS, |hash, &byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+        })
+}
+
 /// Describes how the parquet reader will access a row group
 #[derive(Debug, Clone, PartialEq)]
 pub enum RowGroupAccess {
@@ -171,6 +209,21 @@ impl ParquetAccessPlan {
         }
     }
 
+    /// Randomly skip row groups, keeping each scanned row group with
+    /// probability `fraction`. Used for block-level `TABLESAMPLE SYSTEM`.
+    ///
+    /// The same `seed` always keeps the same row groups.
+    pub fn sample_row_groups(&mut self, fraction: f64, seed: u64) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        for access in self.row_groups.iter_mut() {
+            // draw for every row group so the decision only depends on its index
+            let keep = rng.random::<f64>() < fraction;
+            if !keep {
+                *access = RowGroupAccess::Skip;
+            }
+        }
+    }
+
     /// Return an overall `RowSelection`, if needed
     ///
     /// This is used to compute the row selection for the parquet reader. See
@@ -444,6 +497,42 @@ mod test {
         assert_eq!(row_group_indexes, vec![] as Vec<usize>);
         assert_eq!(row_selection, None);
     }
+    #[test]
+    fn test_sample_row_groups() {
+        let mut access_plan = ParquetAccessPlan::new_all(100);
+        access_plan.skip(0);
+        access_plan.sample_row_groups(0.5, 42);
+
+        let row_group_indexes = access_plan.row_group_indexes();
+        // skipped row groups stay skipped, roughly half of the others are kept
+        assert!(!row_group_indexes.contains(&0));
+        assert!((30..70).contains(&row_group_indexes.len()));
+
+        // the same seed keeps the same row groups
+        let mut same_seed = ParquetAccessPlan::new_all(100);
+        same_seed.skip(0);
+        same_seed.sample_row_groups(0.5, 42);
+        assert_eq!(access_plan, same_seed);
+
+        let mut all = ParquetAccessPlan::new_all(4);
+        all.sample_row_groups(1.0, 1);
+        assert_eq!(all.row_group_indexes(), vec![0, 1, 2, 3]);
+        let mut none = ParquetAccessPlan::new_all(4);
+        none.sample_row_groups(0.0, 1);
+        assert!(none.row_group_indexes().is_empty());
+    }
+
+    #[test]
+    fn test_file_seed() {
+        // the seed of a file must not change across releases
+        assert_eq!(file_seed(42, "file.parquet"), 0x9ca550c86d4652d3);
+        assert_ne!(
+            file_seed(42, "file.parquet"),
+            file_seed(42, "other.parquet")
+        );
+        assert_ne!(file_seed(42, "file.parquet"), file_seed(43, "file.parquet"));
+    }
+
     #[test]
     fn test_mixed_1() {
         let access_plan = ParquetAccessPlan::new(vec![
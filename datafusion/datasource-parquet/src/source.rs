@@ -22,6 +22,7 @@ use std::sync::Arc;
 
 use crate::DefaultParquetFileReaderFactory;
 use crate::ParquetFileReaderFactory;
+use crate::access_plan::RowGroupSample;
 use crate::opener::ParquetMorselizer;
 use crate::opener::build_pruning_predicates;
 use crate::row_filter::can_expr_be_pushed_down_with_schemas;
@@ -294,6 +295,9 @@ pub struct ParquetSource {
     /// so we still need to sort them after reading, so the reverse scan is inexact.
     /// Used to optimize ORDER BY ... DESC on sorted data.
     reverse_row_groups: bool,
+    /// Optional block-level sample, reading only some of the row groups.
+    /// See [`FileSource::try_pushdown_sample`].
+    row_group_sample: Option<RowGroupSample>,
 }
 
 impl ParquetSource {
@@ -319,6 +323,7 @@ impl ParquetSource {
             #[cfg(feature = "parquet_encryption")]
             encryption_factory: None,
             reverse_row_groups: false,
+            row_group_sample: None,
         }
     }
 
@@ -580,6 +585,7 @@ impl FileSource for ParquetSource {
             encryption_factory: self.get_encryption_factory_with_config(),
             max_predicate_cache_size: self.max_predicate_cache_size(),
             reverse_row_groups: self.reverse_row_groups,
+            row_group_sample: self.row_group_sample,
         }))
     }
 
@@ -610,6 +616,20 @@ impl FileSource for ParquetSource {
         Some(&self.projection)
     }
 
+    /// Samples whole row groups, skipping the others without reading them.
+    fn try_pushdown_sample(
+        &self,
+        fraction: f64,
+        seed: Option<u64>,
+    ) -> datafusion_common::Result<Option<Arc<dyn FileSource>>> {
+        if self.row_group_sample.is_some() {
+            return Ok(None);
+        }
+        let mut source = self.clone();
+        source.row_group_sample = Some(RowGroupSample { fraction, seed });
+        Ok(Some(Arc::new(source)))
+    }
+
     fn metrics(&self) -> &ExecutionPlanMetricsSet {
         &self.metrics
     }
@@ -633,6 +653,13 @@ impl FileSource for ParquetSource {
                     write!(f, ", reverse_row_groups=true")?;
                 }
 
+                if let Some(sample) = &self.row_group_sample {
+                    write!(f, ", row_group_sample={}", sample.fraction)?;
+                    if let Some(seed) = sample.seed {
+                        write!(f, ", row_group_sample_seed={seed}")?;
+                    }
+                }
+
                 // Try to build the pruning predicates.
                 // These are only generated here because it's useful to have *some*
                 // idea of what pushdown is happening when viewing plans.
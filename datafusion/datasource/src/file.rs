@@ -309,6 +309,23 @@ pub trait FileSource: Any + Send + Sync {
         Ok(None)
     }
 
+    /// Try to push a block-level `TABLESAMPLE SYSTEM` sample into this FileSource.
+    ///
+    /// `FileSource` implementations that can skip whole blocks of a file, such
+    /// as Parquet row groups, should override this method and return a new
+    /// `FileSource` that reads each block with probability `fraction`. The same
+    /// `seed` should select the same blocks, `None` selects random blocks.
+    ///
+    /// Returns `Ok(None)` if sampling is not supported, in which case the rows
+    /// are sampled after they have been read.
+    fn try_pushdown_sample(
+        &self,
+        _fraction: f64,
+        _seed: Option<u64>,
+    ) -> Result<Option<Arc<dyn FileSource>>> {
+        Ok(None)
+    }
+
     /// Deprecated: Set optional schema adapter factory.
     ///
     /// `SchemaAdapterFactory` has been removed. Use `PhysicalExprAdapterFactory` instead.
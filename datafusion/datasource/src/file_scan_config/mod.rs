@@ -50,6 +50,7 @@ use datafusion_physical_expr_common::sort_expr::{LexOrdering, PhysicalSortExpr};
 use datafusion_physical_plan::SortOrderPushdownResult;
 use datafusion_physical_plan::coop::cooperative;
 use datafusion_physical_plan::execution_plan::SchedulingType;
+use datafusion_physical_plan::operator_statistics::ColumnDistributions;
 use datafusion_physical_plan::{
     DisplayAs, DisplayFormatType,
     display::{ProjectSchemaDisplay, display_orderings},
@@ -202,6 +203,10 @@ pub struct FileScanConfig {
     /// would be incorrect if there are filters being applied, thus this should be accessed
     /// via [`FileScanConfig::statistics`].
     pub(crate) statistics: Statistics,
+    /// Value distributions (histograms and most common values) of the table
+    /// columns (file schema + partition columns).
+    /// See [`FileScanConfigBuilder::with_column_distributions`] for more details.
+    pub(crate) column_distributions: Option<Arc<ColumnDistributions>>,
     /// When true, file_groups are organized by partition column values
     /// and output_partitioning will return Hash partitioning on partition columns.
     /// This allows the optimizer to skip hash repartitioning for aggregates and joins
@@ -275,6 +280,7 @@ pub struct FileScanConfigBuilder {
     constraints: Option<Constraints>,
     file_groups: Vec<FileGroup>,
     statistics: Option<Statistics>,
+    column_distributions: Option<Arc<ColumnDistributions>>,
     output_ordering: Vec<LexOrdering>,
     file_compression_type: Option<FileCompressionType>,
     batch_size: Option<usize>,
@@ -298,6 +304,7 @@ impl FileScanConfigBuilder {
             file_source,
             file_groups: vec![],
             statistics: None,
+            column_distributions: None,
             output_ordering: vec![],
             file_compression_type: None,
             limit: None,
@@ -423,6 +430,23 @@ impl FileScanConfigBuilder {
         self
     }
 
+    /// Set the value distributions of the table columns (file schema +
+    /// partition columns), such as histograms collected from Parquet files or
+    /// supplied by a table provider.
+    ///
+    /// Like [`Self::with_statistics`], the distributions describe the entire
+    /// table and are projected when retrieved via
+    /// [`DataSource::column_distributions`], which the
+    /// [`DataSourceStatisticsProvider`](crate::source::DataSourceStatisticsProvider)
+    /// uses to estimate the selectivity of filters and joins above the scan.
+    pub fn with_column_distributions(
+        mut self,
+        column_distributions: Option<Arc<ColumnDistributions>>,
+    ) -> Self {
+        self.column_distributions = column_distributions;
+        self
+    }
+
     /// Set the list of files to be processed, grouped into partitions.
     ///
     /// Each file must have a schema of `file_schema` or a subset. If
@@ -522,6 +546,7 @@ impl FileScanConfigBuilder {
             constraints,
             file_groups,
             statistics,
+            column_distributions,
             output_ordering,
             file_compression_type,
             batch_size,
@@ -551,6 +576,7 @@ impl FileScanConfigBuilder {
             batch_size,
             expr_adapter_factory: expr_adapter,
             statistics,
+            column_distributions,
             partitioned_by_file_group,
         }
     }
@@ -563,6 +589,7 @@ impl From<FileScanConfig> for FileScanConfigBuilder {
             file_source: Arc::<dyn FileSource>::clone(&config.file_source),
             file_groups: config.file_groups,
             statistics: Some(config.statistics),
+            column_distributions: config.column_distributions,
             output_ordering: config.output_ordering,
             file_compression_type: Some(config.file_compression_type),
             limit: config.limit,
@@ -610,6 +637,9 @@ impl DataSource for FileScanConfig {
         let stream = FileStreamBuilder::new(self)
             .with_partition(partition)
             .with_shared_work_source(shared_work_source)
+            .with_file_split_size(Some(
+                context.session_config().options().execution.file_split_size as u64,
+            ))
             .with_morselizer(morselizer)
             .with_metrics(source.metrics())
             .build()?;
@@ -838,6 +868,16 @@ impl DataSource for FileScanConfig {
         }
     }
 
+    fn column_distributions(&self) -> Option<Arc<ColumnDistributions>> {
+        let column_distributions = self.column_distributions.as_ref()?;
+        Some(match self.file_source.projection() {
+            Some(projection) => Arc::new(
+                column_distributions.project(projection.iter().map(|expr| &expr.expr)),
+            ),
+            None => Arc::clone(column_distributions),
+        })
+    }
+
     fn with_fetch(&self, limit: Option<usize>) -> Option<Arc<dyn DataSource>> {
         let source = FileScanConfigBuilder::from(self.clone())
             .with_limit(limit)
@@ -987,6 +1027,27 @@ impl DataSource for FileScanConfig {
         }
     }
 
+    fn try_pushdown_sample(
+        &self,
+        fraction: f64,
+        seed: Option<u64>,
+    ) -> Result<Option<Arc<dyn DataSource>>> {
+        let Some(file_source) = self.file_source.try_pushdown_sample(fraction, seed)?
+        else {
+            return Ok(None);
+        };
+        let mut config = self.clone();
+        config.file_source = file_source;
+        // Only the expected size of the sample is known
+        config.statistics = config.statistics.with_sample_fraction(fraction);
+        for group in &mut config.file_groups {
+            if let Some(statistics) = group.statistics_mut() {
+                *statistics = statistics.clone().with_sample_fraction(fraction);
+            }
+        }
+        Ok(Some(Arc::new(config)))
+    }
+
     fn with_preserve_order(&self, preserve_order: bool) -> Option<Arc<dyn DataSource>> {
         if self.preserve_order == preserve_order {
             return Some(Arc::new(self.clone()));
@@ -1404,7 +1465,7 @@ mod tests {
         verify_sort_integrity,
     };
 
-    use arrow::array::{Int32Array, RecordBatch};
+    use arrow::array::{ArrayRef, Int32Array, RecordBatch};
     use arrow::datatypes::Field;
     use datafusion_common::ColumnStatistics;
     use datafusion_common::stats::Precision;
@@ -1419,6 +1480,7 @@ mod tests {
     use datafusion_physical_expr::projection::ProjectionExprs;
     use datafusion_physical_plan::ExecutionPlan;
     use datafusion_physical_plan::execution_plan::collect;
+    use datafusion_physical_plan::operator_statistics::ColumnDistribution;
     use futures::FutureExt as _;
     use futures::StreamExt as _;
     use futures::stream;
@@ -1513,6 +1575,44 @@ mod tests {
         );
     }
 
+    #[test]
+    fn column_distributions_are_projected() -> Result<()> {
+        let file_schema = aggr_test_schema();
+        let distribution = |values: Vec<i32>| -> Result<_> {
+            let array: ArrayRef = Arc::new(Int32Array::from(values));
+            Ok(Some(Arc::new(ColumnDistribution::try_from_array(
+                &array, 2, 1,
+            )?)))
+        };
+        let mut columns = vec![None; file_schema.fields().len()];
+        columns[0] = distribution(vec![1, 1, 2])?;
+        columns[2] = distribution(vec![3, 4, 5])?;
+        let column_distributions = Arc::new(ColumnDistributions::new(columns.clone()));
+
+        let conf = FileScanConfigBuilder::from(config_for_projection(
+            Arc::clone(&file_schema),
+            Some(vec![2, 1]),
+            Statistics::new_unknown(&file_schema),
+            vec![],
+        ))
+        .with_column_distributions(Some(Arc::clone(&column_distributions)))
+        .build();
+        let projected = conf.column_distributions().unwrap();
+        assert_eq!(projected.columns(), &[columns[2].clone(), None]);
+
+        // without a projection the distributions are returned as is
+        let conf = FileScanConfigBuilder::from(config_for_projection(
+            Arc::clone(&file_schema),
+            None,
+            Statistics::new_unknown(&file_schema),
+            vec![],
+        ))
+        .with_column_distributions(Some(Arc::clone(&column_distributions)))
+        .build();
+        assert_eq!(conf.column_distributions(), Some(column_distributions));
+        Ok(())
+    }
+
     #[test]
     fn test_split_groups_by_statistics() -> Result<()> {
         use chrono::TimeZone;
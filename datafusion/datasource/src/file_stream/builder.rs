@@ -35,6 +35,7 @@ pub struct FileStreamBuilder<'a> {
     metrics: Option<&'a ExecutionPlanMetricsSet>,
     on_error: OnError,
     shared_work_source: Option<SharedWorkSource>,
+    file_split_size: Option<u64>,
 }
 
 impl<'a> FileStreamBuilder<'a> {
@@ -47,6 +48,7 @@ impl<'a> FileStreamBuilder<'a> {
             metrics: None,
             on_error: OnError::Fail,
             shared_work_source: None,
+            file_split_size: None,
         }
     }
 
@@ -93,6 +95,16 @@ impl<'a> FileStreamBuilder<'a> {
         self
     }
 
+    /// Configure the size in bytes of the byte ranges that files are split
+    /// into when they are shared with sibling streams.
+    ///
+    /// Files are only split if the morselizer
+    /// [supports it](Morselizer::supports_file_splitting).
+    pub fn with_file_split_size(mut self, file_split_size: Option<u64>) -> Self {
+        self.file_split_size = file_split_size;
+        self
+    }
+
     /// Build the configured [`FileStream`].
     pub fn build(self) -> Result<FileStream> {
         let Self {
@@ -102,6 +114,7 @@ impl<'a> FileStreamBuilder<'a> {
             metrics,
             on_error,
             shared_work_source,
+            file_split_size,
         } = self;
 
         let Some(partition) = partition else {
@@ -120,7 +133,11 @@ impl<'a> FileStreamBuilder<'a> {
             );
         };
         let work_source = match shared_work_source {
-            Some(shared) => WorkSource::Shared(shared),
+            Some(shared) => {
+                let split_size =
+                    file_split_size.filter(|_| morselizer.supports_file_splitting());
+                WorkSource::Shared(shared.with_split_size(split_size))
+            }
             None => WorkSource::Local(file_group.into_inner().into()),
         };
 
@@ -63,9 +63,19 @@ impl WorkSource {
 ///
 /// It uses a [`Mutex`] internally to provide thread-safe access
 /// to the shared file queue.
+///
+/// Streams whose morselizer supports splitting files (see
+/// [`Morselizer::supports_file_splitting`]) configure a split size, in which
+/// case large files are taken from the queue one byte range at a time and the
+/// rest of the file stays at the front of the queue for any sibling to take.
+///
+/// [`Morselizer::supports_file_splitting`]: crate::morsel::Morselizer::supports_file_splitting
 #[derive(Debug, Clone)]
 pub(crate) struct SharedWorkSource {
     inner: Arc<SharedWorkSourceInner>,
+    /// Maximum size in bytes of the file ranges taken by this stream, if
+    /// files may be split
+    split_size: Option<u64>,
 }
 
 #[derive(Debug, Default)]
@@ -81,9 +91,17 @@ impl SharedWorkSource {
             inner: Arc::new(SharedWorkSourceInner {
                 files: Mutex::new(files),
             }),
+            split_size: None,
         }
     }
 
+    /// Split files larger than `split_size` bytes into byte ranges of at most
+    /// `split_size` bytes when taking them from the queue.
+    pub(crate) fn with_split_size(mut self, split_size: Option<u64>) -> Self {
+        self.split_size = split_size.filter(|&split_size| split_size > 0);
+        self
+    }
+
     /// Create a shared work source for the unopened files in `config`.
     pub(crate) fn from_config(config: &FileScanConfig) -> Self {
         Self::new(config.file_groups.iter().flat_map(FileGroup::iter).cloned())
@@ -91,8 +109,69 @@ impl SharedWorkSource {
 
     /// Pop the next file from the shared work queue.
     ///
+    /// If a split size is configured and the file is larger than it, only its
+    /// first byte range is returned and the remainder is put back at the
+    /// front of the queue.
+    ///
     /// Returns `None` if the queue is empty
     fn pop_front(&self) -> Option<PartitionedFile> {
-        self.inner.files.lock().pop_front()
+        let mut files = self.inner.files.lock();
+        let file = files.pop_front()?;
+        let Some(split_size) = self.split_size else {
+            return Some(file);
+        };
+
+        let (start, end) = match &file.range {
+            Some(range) => (range.start, range.end),
+            None => (0, i64::try_from(file.object_meta.size).unwrap_or(i64::MAX)),
+        };
+        let split_size = i64::try_from(split_size).unwrap_or(i64::MAX);
+        if end.saturating_sub(start) <= split_size {
+            return Some(file);
+        }
+        let split = start + split_size;
+        files.push_front(file.clone().with_range(split, end));
+        Some(file.with_range(start, split))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ranges(source: &SharedWorkSource) -> Vec<(String, Option<(i64, i64)>)> {
+        std::iter::from_fn(|| source.pop_front())
+            .map(|file| {
+                let range = file.range.map(|range| (range.start, range.end));
+                (file.object_meta.location.to_string(), range)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn shared_work_source_splits_large_files() {
+        let files = [PartitionedFile::new("a", 25), PartitionedFile::new("b", 10)];
+        let source = SharedWorkSource::new(files).with_split_size(Some(10));
+        let expected = [
+            ("a", Some((0, 10))),
+            ("a", Some((10, 20))),
+            ("a", Some((20, 25))),
+            ("b", None),
+        ]
+        .map(|(file, range)| (file.to_string(), range));
+        assert_eq!(ranges(&source), expected);
+    }
+
+    #[test]
+    fn shared_work_source_splits_ranges() {
+        let files = [PartitionedFile::new("a", 100).with_range(30, 50)];
+        let source = SharedWorkSource::new(files.clone()).with_split_size(Some(15));
+        let expected = [("a", Some((30, 45))), ("a", Some((45, 50)))]
+            .map(|(file, range)| (file.to_string(), range));
+        assert_eq!(ranges(&source), expected);
+
+        // A split size of 0 disables splitting
+        let source = SharedWorkSource::new(files).with_split_size(Some(0));
+        assert_eq!(ranges(&source), [("a".to_string(), Some((30, 50)))]);
     }
 }
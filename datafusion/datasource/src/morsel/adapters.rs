@@ -42,6 +42,7 @@ impl Debug for FileOpenerMorselizer {
 }
 
 impl FileOpenerMorselizer {
+    /// Create a new morselizer that opens each file with `file_opener`.
     pub fn new(file_opener: Arc<dyn FileOpener>) -> Self {
         Self { file_opener }
     }
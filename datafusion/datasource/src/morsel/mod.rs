@@ -29,14 +29,18 @@
 mod adapters;
 #[cfg(test)]
 pub(crate) mod mocks;
+mod newline_delimited;
+mod projection;
 
 use crate::PartitionedFile;
-pub(crate) use adapters::FileOpenerMorselizer;
+pub use adapters::FileOpenerMorselizer;
 use arrow::array::RecordBatch;
 use datafusion_common::Result;
 use futures::FutureExt;
 use futures::future::BoxFuture;
 use futures::stream::BoxStream;
+pub use newline_delimited::{NewlineDelimitedDecoder, NewlineDelimitedMorselizer};
+pub use projection::ProjectionMorselizer;
 use std::fmt::Debug;
 use std::pin::Pin;
 use std::task::{Context, Poll};
@@ -66,6 +70,17 @@ pub trait Morselizer: Send + Sync + Debug {
     /// work, such as reading from the file. Any needed I/O should be done using
     /// [`MorselPlan::with_pending_planner`].
     fn plan_file(&self, file: PartitionedFile) -> Result<Box<dyn MorselPlanner>>;
+
+    /// Returns `true` if files may be split into arbitrary byte ranges
+    /// (via [`PartitionedFile::range`]) before being passed to
+    /// [`Self::plan_file`].
+    ///
+    /// When `true`, large files are divided into byte ranges of
+    /// `datafusion.execution.file_split_size` bytes that idle sibling streams
+    /// can steal from each other. Defaults to `false`.
+    fn supports_file_splitting(&self) -> bool {
+        false
+    }
 }
 
 /// A Morsel Planner is responsible for creating morsels for a given scan.
@@ -0,0 +1,437 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`NewlineDelimitedMorselizer`] for formats whose records never span lines,
+//! such as CSV and newline-delimited JSON.
+
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use crate::PartitionedFile;
+use crate::morsel::{
+    FileOpenerMorselizer, Morsel, MorselPlan, MorselPlanner, Morselizer,
+};
+use arrow::array::RecordBatch;
+use bytes::{Bytes, BytesMut};
+use datafusion_common::{Result, exec_datafusion_err};
+use futures::stream::BoxStream;
+use object_store::path::Path;
+use object_store::{GetOptions, GetRange, ObjectStore};
+
+/// Default number of bytes read from the object store per morsel
+const DEFAULT_MORSEL_SIZE: u64 = 8 * 1024 * 1024;
+
+/// Decodes the morsels of a [`NewlineDelimitedMorselizer`] into
+/// [`RecordBatch`]es.
+pub trait NewlineDelimitedDecoder: Send + Sync + Debug {
+    /// Decode a morsel of complete lines.
+    ///
+    /// `at_file_start` is `true` if the morsel starts at the beginning of the
+    /// file, e.g. to skip a header line.
+    ///
+    /// This is called by [`Morsel::into_stream`] and must not do any I/O.
+    fn decode(
+        &self,
+        morsel: Bytes,
+        at_file_start: bool,
+    ) -> Result<BoxStream<'static, Result<RecordBatch>>>;
+}
+
+/// A [`Morselizer`] for formats whose records are terminated by a newline
+/// character and never contain it, such as CSV without newlines in values and
+/// newline-delimited JSON.
+///
+/// Files with a byte range (see [`PartitionedFile::range`]) are read in
+/// morsels of about `morsel_size` bytes that are aligned to line boundaries:
+/// the range contains the lines whose first byte lies within it. The next
+/// morsel is fetched while the previous one is decoded, so that I/O and
+/// decoding overlap.
+///
+/// Files without a range are opened by a fallback morselizer, which reads
+/// them as a stream (e.g. to support FIFO files). Large files are split into
+/// ranges before being planned, see [`Morselizer::supports_file_splitting`].
+#[derive(Debug)]
+pub struct NewlineDelimitedMorselizer {
+    object_store: Arc<dyn ObjectStore>,
+    decoder: Arc<dyn NewlineDelimitedDecoder>,
+    fallback: FileOpenerMorselizer,
+    newline: u8,
+    morsel_size: u64,
+}
+
+impl NewlineDelimitedMorselizer {
+    /// Create a new morselizer decoding morsels with `decoder` and opening
+    /// files without a byte range with `fallback`.
+    pub fn new(
+        object_store: Arc<dyn ObjectStore>,
+        decoder: Arc<dyn NewlineDelimitedDecoder>,
+        fallback: FileOpenerMorselizer,
+    ) -> Self {
+        Self {
+            object_store,
+            decoder,
+            fallback,
+            newline: b'\n',
+            morsel_size: DEFAULT_MORSEL_SIZE,
+        }
+    }
+
+    /// Set the character terminating lines, defaults to `\n`.
+    pub fn with_newline(mut self, newline: u8) -> Self {
+        self.newline = newline;
+        self
+    }
+
+    /// Set the number of bytes read from the object store per morsel,
+    /// defaults to 8 MiB.
+    pub fn with_morsel_size(mut self, morsel_size: u64) -> Self {
+        self.morsel_size = morsel_size.max(1);
+        self
+    }
+}
+
+impl Morselizer for NewlineDelimitedMorselizer {
+    fn plan_file(&self, file: PartitionedFile) -> Result<Box<dyn MorselPlanner>> {
+        let Some(range) = file.range.as_ref() else {
+            return self.fallback.plan_file(file);
+        };
+        let start = u64::try_from(range.start).map_err(|_| {
+            exec_datafusion_err!(
+                "Expected start range to fit in u64, got {}",
+                range.start
+            )
+        })?;
+        let end = u64::try_from(range.end).map_err(|_| {
+            exec_datafusion_err!("Expected end range to fit in u64, got {}", range.end)
+        })?;
+        let file_size = file.object_meta.size;
+        // Read from the byte before the range to find out whether the range
+        // starts at a line boundary
+        let first_fetch = start.saturating_sub(1).min(file_size);
+
+        Ok(Box::new(NewlineDelimitedPlanner {
+            object_store: Arc::clone(&self.object_store),
+            decoder: Arc::clone(&self.decoder),
+            newline: self.newline,
+            morsel_size: self.morsel_size,
+            location: file.object_meta.location,
+            file_size,
+            next_fetch: first_fetch,
+            end: end.min(file_size),
+            buffer_start: first_fetch,
+            buffer: Bytes::new(),
+            skip_partial_line: start > 0,
+            fetched: None,
+            produced_morsel: false,
+        }))
+    }
+
+    fn supports_file_splitting(&self) -> bool {
+        true
+    }
+}
+
+/// Plans the morsels of a byte range of a file.
+///
+/// Alternates between fetching the next `morsel_size` bytes (I/O) and
+/// cutting the fetched bytes at the last complete line into a morsel (CPU).
+struct NewlineDelimitedPlanner {
+    object_store: Arc<dyn ObjectStore>,
+    decoder: Arc<dyn NewlineDelimitedDecoder>,
+    newline: u8,
+    morsel_size: u64,
+    location: Path,
+    file_size: u64,
+    /// Offset of the next byte to fetch
+    next_fetch: u64,
+    /// Lines starting before this offset belong to the range
+    end: u64,
+    /// File offset of the first byte of `buffer`
+    buffer_start: u64,
+    /// Bytes fetched before the last fetch that are not part of a morsel yet,
+    /// i.e. the beginning of an incomplete line
+    buffer: Bytes,
+    /// `true` until the end of the line containing the byte before the range
+    /// was found, as that line belongs to the previous range
+    skip_partial_line: bool,
+    /// Bytes returned by the last fetch, not yet planned
+    fetched: Option<Bytes>,
+    /// `true` once a morsel was returned
+    produced_morsel: bool,
+}
+
+impl Debug for NewlineDelimitedPlanner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NewlineDelimitedPlanner")
+            .field("location", &self.location)
+            .field("next_fetch", &self.next_fetch)
+            .field("end", &self.end)
+            .finish_non_exhaustive()
+    }
+}
+
+impl NewlineDelimitedPlanner {
+    /// Returns a plan that fetches the next bytes of the file
+    fn fetch_next(mut self: Box<Self>, mut plan: MorselPlan) -> MorselPlan {
+        let range =
+            self.next_fetch..(self.next_fetch + self.morsel_size).min(self.file_size);
+        self.next_fetch = range.end;
+        let store = Arc::clone(&self.object_store);
+        let location = self.location.clone();
+        plan.set_pending_planner(async move {
+            let options = GetOptions {
+                range: Some(GetRange::Bounded(range)),
+                ..Default::default()
+            };
+            let bytes = store.get_opts(&location, options).await?.bytes().await?;
+            self.fetched = Some(bytes);
+            Ok(self as Box<dyn MorselPlanner>)
+        });
+        plan
+    }
+
+    /// The plan once no more morsels remain
+    fn finish(&self) -> Option<MorselPlan> {
+        // An empty plan, unless the range contained no lines at all
+        self.produced_morsel.then(MorselPlan::new)
+    }
+
+    /// Appends the fetched bytes to the buffer
+    fn take_buffered(&mut self, fetched: Bytes) -> Bytes {
+        if self.buffer.is_empty() {
+            fetched
+        } else {
+            let mut buffer = BytesMut::with_capacity(self.buffer.len() + fetched.len());
+            buffer.extend_from_slice(&self.buffer);
+            buffer.extend_from_slice(&fetched);
+            self.buffer = Bytes::new();
+            buffer.freeze()
+        }
+    }
+}
+
+impl MorselPlanner for NewlineDelimitedPlanner {
+    fn plan(mut self: Box<Self>) -> Result<Option<MorselPlan>> {
+        let Some(fetched) = self.fetched.take() else {
+            if self.next_fetch >= self.file_size || self.buffer_start >= self.end {
+                return Ok(self.finish());
+            }
+            return Ok(Some(self.fetch_next(MorselPlan::new())));
+        };
+        let at_eof = self.next_fetch >= self.file_size || fetched.is_empty();
+        let newline = self.newline;
+        let mut data = self.take_buffered(fetched);
+
+        if self.skip_partial_line {
+            match data.iter().position(|&byte| byte == newline) {
+                Some(position) => {
+                    self.skip_partial_line = false;
+                    self.buffer_start += position as u64 + 1;
+                    data = data.slice(position + 1..);
+                }
+                None => {
+                    // The whole chunk belongs to a line of the previous range
+                    self.buffer_start += data.len() as u64;
+                    return if at_eof || self.buffer_start >= self.end {
+                        Ok(self.finish())
+                    } else {
+                        Ok(Some(self.fetch_next(MorselPlan::new())))
+                    };
+                }
+            }
+        }
+        if self.buffer_start >= self.end || data.is_empty() {
+            return if at_eof || self.buffer_start >= self.end {
+                Ok(self.finish())
+            } else {
+                Ok(Some(self.fetch_next(MorselPlan::new())))
+            };
+        }
+
+        // The range ends with the line containing its last byte
+        let last_owned = (self.end - self.buffer_start - 1) as usize;
+        let range_end = data
+            .get(last_owned..)
+            .and_then(|tail| tail.iter().position(|&byte| byte == newline))
+            .map(|position| last_owned + position + 1);
+        let (morsel_len, finished) = match range_end {
+            Some(range_end) => (range_end, true),
+            None if at_eof => (data.len(), true),
+            None => {
+                let complete_lines = data
+                    .iter()
+                    .rposition(|&byte| byte == newline)
+                    .map_or(0, |position| position + 1);
+                (complete_lines, false)
+            }
+        };
+
+        let mut plan = MorselPlan::new();
+        if morsel_len > 0 {
+            plan = plan.with_morsels(vec![Box::new(NewlineDelimitedMorsel {
+                decoder: Arc::clone(&self.decoder),
+                bytes: data.slice(..morsel_len),
+                at_file_start: self.buffer_start == 0,
+            })]);
+            self.produced_morsel = true;
+        }
+        if finished {
+            return Ok(Some(plan));
+        }
+        self.buffer = data.slice(morsel_len..);
+        self.buffer_start += morsel_len as u64;
+        Ok(Some(self.fetch_next(plan)))
+    }
+}
+
+/// A morsel of complete lines, decoded by a [`NewlineDelimitedDecoder`]
+struct NewlineDelimitedMorsel {
+    decoder: Arc<dyn NewlineDelimitedDecoder>,
+    bytes: Bytes,
+    at_file_start: bool,
+}
+
+impl Debug for NewlineDelimitedMorsel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NewlineDelimitedMorsel")
+            .field("len", &self.bytes.len())
+            .field("at_file_start", &self.at_file_start)
+            .finish()
+    }
+}
+
+impl Morsel for NewlineDelimitedMorsel {
+    fn into_stream(self: Box<Self>) -> BoxStream<'static, Result<RecordBatch>> {
+        match self.decoder.decode(self.bytes, self.at_file_start) {
+            Ok(stream) => stream,
+            Err(e) => Box::pin(futures::stream::once(async { Err(e) })),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_stream::{FileOpenFuture, FileOpener};
+    use arrow::array::{ArrayRef, StringArray};
+    use datafusion_common::internal_err;
+    use futures::{StreamExt, TryStreamExt};
+    use object_store::ObjectStoreExt;
+    use object_store::memory::InMemory;
+
+    /// Decodes each line into a row of a single string column, skipping the
+    /// first line of the file as a header
+    #[derive(Debug)]
+    struct LineDecoder;
+
+    impl NewlineDelimitedDecoder for LineDecoder {
+        fn decode(
+            &self,
+            morsel: Bytes,
+            at_file_start: bool,
+        ) -> Result<BoxStream<'static, Result<RecordBatch>>> {
+            let text = String::from_utf8(morsel.to_vec()).unwrap();
+            let lines = text
+                .lines()
+                .skip(usize::from(at_file_start))
+                .map(str::to_string)
+                .collect::<Vec<_>>();
+            let array: ArrayRef = Arc::new(StringArray::from(lines));
+            let batch = RecordBatch::try_from_iter([("line", array)])?;
+            Ok(futures::stream::iter([Ok(batch)]).boxed())
+        }
+    }
+
+    struct UnreachableOpener;
+
+    impl FileOpener for UnreachableOpener {
+        fn open(&self, _file: PartitionedFile) -> Result<FileOpenFuture> {
+            internal_err!("files with a range are not opened by the fallback")
+        }
+    }
+
+    const FILE: &str = "header\naa\nbbbb\nc\n\ndddddd\neee";
+
+    async fn read_range(morsel_size: u64, start: i64, end: i64) -> Result<Vec<String>> {
+        let store = Arc::new(InMemory::new());
+        let location = Path::from("file.csv");
+        store.put(&location, FILE.into()).await?;
+        let morselizer = NewlineDelimitedMorselizer::new(
+            store,
+            Arc::new(LineDecoder),
+            FileOpenerMorselizer::new(Arc::new(UnreachableOpener)),
+        )
+        .with_morsel_size(morsel_size);
+
+        let file =
+            PartitionedFile::new("file.csv", FILE.len() as u64).with_range(start, end);
+        let mut planners = vec![morselizer.plan_file(file)?];
+        let mut lines = vec![];
+        while let Some(planner) = planners.pop() {
+            let Some(mut plan) = planner.plan()? else {
+                continue;
+            };
+            for morsel in plan.take_morsels() {
+                let batches: Vec<_> = morsel.into_stream().try_collect().await?;
+                for batch in batches {
+                    let column = batch.column(0).as_any();
+                    let array = column.downcast_ref::<StringArray>().unwrap();
+                    lines.extend(array.iter().flatten().map(str::to_string));
+                }
+            }
+            planners.extend(plan.take_ready_planners());
+            if let Some(pending) = plan.take_pending_planner() {
+                planners.push(pending.await?);
+            }
+        }
+        Ok(lines)
+    }
+
+    #[tokio::test]
+    async fn whole_file_range() -> Result<()> {
+        for morsel_size in [1, 3, 7, 1024] {
+            let lines = read_range(morsel_size, 0, FILE.len() as i64).await?;
+            assert_eq!(lines, ["aa", "bbbb", "c", "", "dddddd", "eee"]);
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn ranges_own_lines_starting_in_them() -> Result<()> {
+        let len = FILE.len() as i64;
+        for morsel_size in [1, 2, 5, 1024] {
+            for split in 1..len {
+                let mut lines = read_range(morsel_size, 0, split).await?;
+                lines.extend(read_range(morsel_size, split, len).await?);
+                assert_eq!(
+                    lines,
+                    ["aa", "bbbb", "c", "", "dddddd", "eee"],
+                    "morsel_size {morsel_size}, split at {split}"
+                );
+            }
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn range_within_a_line() -> Result<()> {
+        // "dddddd" starts at offset 18, the range [19, 21) is within it
+        assert!(read_range(4, 19, 21).await?.is_empty());
+        assert_eq!(read_range(4, 18, 19).await?, ["dddddd"]);
+        Ok(())
+    }
+}
@@ -0,0 +1,164 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`ProjectionMorselizer`], the morsel counterpart of [`ProjectionOpener`]
+//!
+//! [`ProjectionOpener`]: crate::projection::ProjectionOpener
+
+use std::sync::Arc;
+
+use crate::PartitionedFile;
+use crate::morsel::{Morsel, MorselPlan, MorselPlanner, Morselizer};
+use crate::projection::{
+    PartitionColumnIndex, SplitProjection, inject_partition_columns_into_projection,
+};
+use arrow::array::RecordBatch;
+use arrow::datatypes::{Schema, SchemaRef};
+use datafusion_common::Result;
+use datafusion_physical_expr::projection::{ProjectionExprs, Projector};
+use futures::StreamExt;
+use futures::stream::BoxStream;
+
+/// A [`Morselizer`] that applies a projection on top of the morsels of an
+/// inner morselizer.
+///
+/// This includes handling partition columns, in the same way as
+/// [`ProjectionOpener`] does for [`FileOpener`]s.
+///
+/// [`ProjectionOpener`]: crate::projection::ProjectionOpener
+/// [`FileOpener`]: crate::file_stream::FileOpener
+#[derive(Debug)]
+pub struct ProjectionMorselizer {
+    inner: Box<dyn Morselizer>,
+    projection: ProjectionExprs,
+    input_schema: SchemaRef,
+    partition_columns: Vec<PartitionColumnIndex>,
+}
+
+impl ProjectionMorselizer {
+    /// Create a new morselizer applying the remainder of `projection` to the
+    /// morsels of `inner`, which reads the file columns of `projection`.
+    pub fn try_new(
+        projection: SplitProjection,
+        inner: Box<dyn Morselizer>,
+        file_schema: &Schema,
+    ) -> Result<Self> {
+        Ok(Self {
+            inner,
+            projection: projection.remapped_projection,
+            input_schema: Arc::new(file_schema.project(&projection.file_indices)?),
+            partition_columns: projection.partition_columns,
+        })
+    }
+}
+
+impl Morselizer for ProjectionMorselizer {
+    fn plan_file(&self, file: PartitionedFile) -> Result<Box<dyn MorselPlanner>> {
+        // Substitute references to partition columns with the literal values
+        // of this file
+        let projection = if self.partition_columns.is_empty() {
+            self.projection.clone()
+        } else {
+            inject_partition_columns_into_projection(
+                &self.projection,
+                &self.partition_columns,
+                file.partition_values.clone(),
+            )
+        };
+        let projector = projection.make_projector(&self.input_schema)?;
+
+        Ok(Box::new(ProjectionPlanner {
+            inner: self.inner.plan_file(file)?,
+            projector,
+        }))
+    }
+
+    fn supports_file_splitting(&self) -> bool {
+        self.inner.supports_file_splitting()
+    }
+}
+
+/// Wraps the morsels and planners produced by an inner planner
+#[derive(Debug)]
+struct ProjectionPlanner {
+    inner: Box<dyn MorselPlanner>,
+    projector: Projector,
+}
+
+impl ProjectionPlanner {
+    fn wrap(
+        inner: Box<dyn MorselPlanner>,
+        projector: &Projector,
+    ) -> Box<dyn MorselPlanner> {
+        Box::new(Self {
+            inner,
+            projector: projector.clone(),
+        })
+    }
+}
+
+impl MorselPlanner for ProjectionPlanner {
+    fn plan(self: Box<Self>) -> Result<Option<MorselPlan>> {
+        let Self { inner, projector } = *self;
+        let Some(mut inner_plan) = inner.plan()? else {
+            return Ok(None);
+        };
+
+        let morsels = inner_plan
+            .take_morsels()
+            .into_iter()
+            .map(|inner| {
+                Box::new(ProjectionMorsel {
+                    inner,
+                    projector: projector.clone(),
+                }) as Box<dyn Morsel>
+            })
+            .collect();
+        let planners = inner_plan
+            .take_ready_planners()
+            .into_iter()
+            .map(|inner| Self::wrap(inner, &projector))
+            .collect();
+        let mut plan = MorselPlan::new()
+            .with_morsels(morsels)
+            .with_planners(planners);
+        if let Some(pending) = inner_plan.take_pending_planner() {
+            plan.set_pending_planner(async move {
+                let inner = pending.await?;
+                Ok(Self::wrap(inner, &projector))
+            });
+        }
+        Ok(Some(plan))
+    }
+}
+
+/// Applies a projection to the batches of an inner morsel
+#[derive(Debug)]
+struct ProjectionMorsel {
+    inner: Box<dyn Morsel>,
+    projector: Projector,
+}
+
+impl Morsel for ProjectionMorsel {
+    fn into_stream(self: Box<Self>) -> BoxStream<'static, Result<RecordBatch>> {
+        let Self { inner, projector } = *self;
+        inner
+            .into_stream()
+            .map(move |batch| projector.project_batch(&batch?))
+            .boxed()
+    }
+}
@@ -105,7 +105,7 @@ pub struct PartitionColumnIndex {
     pub in_partition_values: usize,
 }
 
-fn inject_partition_columns_into_projection(
+pub(crate) fn inject_partition_columns_into_projection(
     projection: &ProjectionExprs,
     partition_columns: &[PartitionColumnIndex],
     partition_values: Vec<ScalarValue>,
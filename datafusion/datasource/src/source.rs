@@ -30,6 +30,9 @@ use datafusion_physical_plan::metrics::SplitMetrics;
 use datafusion_physical_plan::metrics::{
     BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet,
 };
+use datafusion_physical_plan::operator_statistics::{
+    ColumnDistributions, ExtendedStatistics, StatisticsProvider, StatisticsResult,
+};
 use datafusion_physical_plan::projection::ProjectionExec;
 use datafusion_physical_plan::stream::BatchSplitStream;
 use datafusion_physical_plan::{
@@ -171,6 +174,16 @@ pub trait DataSource: Any + Send + Sync + Debug {
     /// across all partitions if `partition` is `None`.
     fn partition_statistics(&self, partition: Option<usize>) -> Result<Arc<Statistics>>;
 
+    /// Returns the value distributions (histograms and most common values) of
+    /// the output columns, if known.
+    ///
+    /// These are attached to the statistics of the scan by the
+    /// [`DataSourceStatisticsProvider`] and used to estimate the selectivity of
+    /// filters and joins above it.
+    fn column_distributions(&self) -> Option<Arc<ColumnDistributions>> {
+        None
+    }
+
     /// Return a copy of this DataSource with a new fetch limit
     fn with_fetch(&self, _limit: Option<usize>) -> Option<Arc<dyn DataSource>>;
     fn fetch(&self) -> Option<usize>;
@@ -225,6 +238,20 @@ pub trait DataSource: Any + Send + Sync + Debug {
         None
     }
 
+    /// Try to create a new `DataSource` that only produces a block-level
+    /// sample of `fraction` of its data, as requested by `TABLESAMPLE SYSTEM`.
+    ///
+    /// See [`FileSource::try_pushdown_sample`] for more details.
+    ///
+    /// [`FileSource::try_pushdown_sample`]: crate::file::FileSource::try_pushdown_sample
+    fn try_pushdown_sample(
+        &self,
+        _fraction: f64,
+        _seed: Option<u64>,
+    ) -> Result<Option<Arc<dyn DataSource>>> {
+        Ok(None)
+    }
+
     /// Apply a closure to each expression used by this data source.
     ///
     /// This includes filter predicates (which may contain dynamic filters) and any
@@ -633,6 +660,43 @@ impl DataSourceExec {
     }
 }
 
+/// Statistics provider for [`DataSourceExec`] that attaches the
+/// [`DataSource::column_distributions`] of the scan to its statistics.
+///
+/// Sessions built with default features register it, so that
+/// [`HistogramStatisticsProvider`] can use the histograms and most common
+/// values supplied by data sources. Custom registries need to register it
+/// themselves:
+///
+/// ```ignore
+/// let mut registry = StatisticsRegistry::default_with_builtin_providers();
+/// registry.register(Arc::new(DataSourceStatisticsProvider));
+/// ```
+///
+/// [`StatisticsRegistry`]: datafusion_physical_plan::operator_statistics::StatisticsRegistry
+/// [`HistogramStatisticsProvider`]: datafusion_physical_plan::operator_statistics::HistogramStatisticsProvider
+#[derive(Debug, Default)]
+pub struct DataSourceStatisticsProvider;
+
+impl StatisticsProvider for DataSourceStatisticsProvider {
+    fn compute_statistics(
+        &self,
+        plan: &dyn ExecutionPlan,
+        _child_stats: &[ExtendedStatistics],
+    ) -> Result<StatisticsResult> {
+        let Some(exec) = plan.downcast_ref::<DataSourceExec>() else {
+            return Ok(StatisticsResult::Delegate);
+        };
+        let Some(column_distributions) = exec.data_source().column_distributions() else {
+            return Ok(StatisticsResult::Delegate);
+        };
+
+        let mut stats = ExtendedStatistics::new_arc(exec.partition_statistics(None)?);
+        stats.set_extension(Arc::unwrap_or_clone(column_distributions));
+        Ok(StatisticsResult::Computed(stats))
+    }
+}
+
 /// Create a new `DataSourceExec` from a `DataSource`
 impl<S> From<S> for DataSourceExec
 where
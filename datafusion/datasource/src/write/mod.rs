@@ -75,6 +75,21 @@ pub trait BatchSerializer: Sync + Send {
     fn serialize(&self, batch: RecordBatch, initial: bool) -> Result<Bytes>;
 }
 
+/// A serializer for file types whose [`RecordBatch`]es can not be serialized
+/// independently of each other, e.g. because the encoding of a batch refers to
+/// a header that is written once per file.
+///
+/// A new serializer is created for each output file, and sees the batches of
+/// that file in order.
+pub trait StatefulBatchSerializer: Send {
+    /// Serializes a `RecordBatch` and returns the bytes that are ready to be
+    /// written to the file.
+    fn serialize(&mut self, batch: &RecordBatch) -> Result<Bytes>;
+
+    /// Finishes the file and returns the remaining bytes, e.g. a footer.
+    fn finish(&mut self) -> Result<Bytes>;
+}
+
 /// Returns an [`AsyncWrite`] which writes to the given object store location
 /// with the specified compression.
 ///
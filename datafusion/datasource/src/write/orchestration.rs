@@ -22,7 +22,7 @@
 use std::sync::Arc;
 
 use super::demux::DemuxedStreamReceiver;
-use super::{BatchSerializer, ObjectWriterBuilder};
+use super::{BatchSerializer, ObjectWriterBuilder, StatefulBatchSerializer};
 use crate::file_compression_type::FileCompressionType;
 use datafusion_common::error::Result;
 
@@ -42,7 +42,8 @@ use tokio::sync::mpsc::{self, Receiver};
 type WriterType = Box<dyn AsyncWrite + Send + Unpin>;
 type SerializerType = Arc<dyn BatchSerializer>;
 
-/// Result of calling [`serialize_rb_stream_to_object_store`]
+/// Result of calling [`serialize_rb_stream_to_object_store`] or
+/// [`serialize_stateful_stream_to_object_store`]
 pub(crate) enum SerializedRecordBatchResult {
     Success {
         /// the writer
@@ -154,6 +155,44 @@ pub(crate) async fn serialize_rb_stream_to_object_store(
     SerializedRecordBatchResult::success(writer, row_count)
 }
 
+/// Serializes a single data stream in order with a [`StatefulBatchSerializer`]
+/// and writes it to an ObjectStore.
+///
+/// As for [`serialize_rb_stream_to_object_store`], the writer is returned to
+/// the caller unless an IO error involving the writer occurred.
+async fn serialize_stateful_stream_to_object_store(
+    mut data_rx: Receiver<RecordBatch>,
+    mut serializer: Box<dyn StatefulBatchSerializer>,
+    mut writer: WriterType,
+) -> SerializedRecordBatchResult {
+    let mut row_count = 0;
+    let mut finished = false;
+    while !finished {
+        let bytes = match data_rx.recv().await {
+            Some(batch) => {
+                row_count += batch.num_rows();
+                serializer.serialize(&batch)
+            }
+            None => {
+                finished = true;
+                serializer.finish()
+            }
+        };
+        let bytes = match bytes {
+            Ok(bytes) => bytes,
+            // Return the writer along with the error
+            Err(e) => return SerializedRecordBatchResult::failure(Some(writer), e),
+        };
+        if let Err(e) = writer.write_all(&bytes).await {
+            return SerializedRecordBatchResult::failure(
+                None,
+                exec_datafusion_err!("Error writing to object store: {e}"),
+            );
+        }
+    }
+    SerializedRecordBatchResult::success(writer, row_count)
+}
+
 type FileWriteBundle = (Receiver<RecordBatch>, SerializerType, WriterType);
 /// Contains the common logic for serializing RecordBatches and
 /// writing the resulting bytes to an ObjectStore.
@@ -164,6 +203,30 @@ pub(crate) async fn stateless_serialize_and_write_files(
     mut rx: Receiver<FileWriteBundle>,
     tx: tokio::sync::oneshot::Sender<u64>,
 ) -> Result<()> {
+    let mut join_set = JoinSet::new();
+    while let Some((data_rx, serializer, writer)) = rx.recv().await {
+        join_set.spawn(async move {
+            serialize_rb_stream_to_object_store(data_rx, serializer, writer).await
+        });
+    }
+    let row_count = join_and_finalize_writers(join_set).await?;
+
+    tx.send(row_count as u64).map_err(|_| {
+        internal_datafusion_err!(
+            "Error encountered while sending row count back to file sink!"
+        )
+    })?;
+    Ok(())
+}
+
+/// Waits for all file writing tasks, then finalizes the writers that were
+/// returned by the tasks, whether they succeeded or not.
+///
+/// Returns the total row count, or the error that triggered aborting the
+/// writes.
+async fn join_and_finalize_writers(
+    mut join_set: JoinSet<SerializedRecordBatchResult>,
+) -> Result<usize> {
     let mut row_count = 0;
     // tracks if any writers encountered an error triggering the need to abort
     let mut any_errors = false;
@@ -172,12 +235,6 @@ pub(crate) async fn stateless_serialize_and_write_files(
     // tracks if any errors were encountered in the process of aborting writers.
     // if true, we may not have a guarantee that all written data was cleaned up.
     let mut any_abort_errors = false;
-    let mut join_set = JoinSet::new();
-    while let Some((data_rx, serializer, writer)) = rx.recv().await {
-        join_set.spawn(async move {
-            serialize_rb_stream_to_object_store(data_rx, serializer, writer).await
-        });
-    }
     let mut finished_writers = Vec::new();
     while let Some(result) = join_set.join_next().await {
         match result {
@@ -233,12 +290,7 @@ pub(crate) async fn stateless_serialize_and_write_files(
         }
     }
 
-    tx.send(row_count as u64).map_err(|_| {
-        internal_datafusion_err!(
-            "Error encountered while sending row count back to file sink!"
-        )
-    })?;
-    Ok(())
+    Ok(row_count)
 }
 
 /// Orchestrates multipart put of a dynamic number of output files from a single input stream
@@ -303,3 +355,46 @@ pub async fn spawn_writer_tasks_and_join(
         internal_datafusion_err!("Did not receive row count from write coordinator")
     })
 }
+
+/// Orchestrates writing a dynamic number of output files from a single input
+/// stream for any statefully serialized file type, i.e. any file type that is
+/// serialized by a [`StatefulBatchSerializer`] per file.
+///
+/// Files are written concurrently, while the [`RecordBatch`]es of each file
+/// are serialized in order by the serializer of that file.
+pub async fn spawn_stateful_writer_tasks_and_join(
+    context: &Arc<TaskContext>,
+    create_serializer: impl Fn() -> Result<Box<dyn StatefulBatchSerializer>>,
+    object_store: Arc<dyn ObjectStore>,
+    demux_task: SpawnedTask<Result<()>>,
+    mut file_stream_rx: DemuxedStreamReceiver,
+) -> Result<u64> {
+    let mut file_write_tasks = JoinSet::new();
+    while let Some((location, rx)) = file_stream_rx.recv().await {
+        let serializer = create_serializer()?;
+        let writer = ObjectWriterBuilder::new(
+            FileCompressionType::UNCOMPRESSED,
+            &location,
+            Arc::clone(&object_store),
+        )
+        .with_buffer_size(Some(
+            context
+                .session_config()
+                .options()
+                .execution
+                .objectstore_writer_buffer_size,
+        ))
+        .build()?;
+
+        file_write_tasks.spawn(async move {
+            serialize_stateful_stream_to_object_store(rx, serializer, writer).await
+        });
+    }
+    let row_count = join_and_finalize_writers(file_write_tasks).await?;
+
+    demux_task
+        .join_unwind()
+        .await
+        .map_err(|e| DataFusionError::ExecutionJoin(Box::new(e)))??;
+    Ok(row_count as u64)
+}
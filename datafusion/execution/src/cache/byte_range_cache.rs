@@ -0,0 +1,647 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use bytes::Bytes;
+use datafusion_common::{DataFusionError, Result};
+use log::debug;
+use object_store::ObjectMeta;
+use object_store::path::Path;
+use parking_lot::Mutex;
+use tempfile::TempDir;
+
+use crate::cache::CacheAccessor;
+use crate::cache::cache_manager::{
+    ByteRangeCache, ByteRangeCacheEntry, ByteRangeCacheKey, ByteRangeCacheMetrics,
+    ByteRangeCacheTier,
+};
+use crate::cache::lru_queue::LruQueue;
+
+/// A byte range spilled to the disk tier of the [`DefaultByteRangeCache`].
+struct DiskEntry {
+    file: PathBuf,
+    size: usize,
+}
+
+/// The disk tier of the [`DefaultByteRangeCache`], holding the ranges evicted
+/// from memory.
+struct DiskTier {
+    /// Directory holding one file per cached range, removed on drop.
+    directory: TempDir,
+    lru_queue: LruQueue<ByteRangeCacheKey, DiskEntry>,
+    limit: usize,
+    used: usize,
+    next_file_id: u64,
+}
+
+impl DiskTier {
+    fn try_new(directory: &std::path::Path, limit: usize) -> Result<Self> {
+        let directory = tempfile::Builder::new()
+            .prefix("datafusion-byte-range-cache")
+            .tempdir_in(directory)
+            .map_err(DataFusionError::IoError)?;
+        debug!(
+            "Created directory '{:?}' for the byte range cache",
+            directory.path()
+        );
+        Ok(Self {
+            directory,
+            lru_queue: LruQueue::new(),
+            limit,
+            used: 0,
+            next_file_id: 0,
+        })
+    }
+
+    /// Writes `value` to disk, evicting the least recently used entries of the
+    /// tier if required. Returns the keys that are no longer cached, which
+    /// includes `key` if the range could not be written.
+    fn put(&mut self, key: ByteRangeCacheKey, value: &Bytes) -> Vec<ByteRangeCacheKey> {
+        if value.len() > self.limit {
+            return vec![key];
+        }
+
+        let file = self
+            .directory
+            .path()
+            .join(format!("{}.bin", self.next_file_id));
+        self.next_file_id += 1;
+        if let Err(e) = std::fs::write(&file, value) {
+            debug!("Failed to spill byte range to {file:?}: {e}");
+            return vec![key];
+        }
+
+        let size = value.len();
+        self.used += size;
+        if let Some(old_entry) = self.lru_queue.put(key, DiskEntry { file, size }) {
+            self.delete(old_entry);
+        }
+
+        let mut evicted = vec![];
+        while self.used > self.limit {
+            match self.lru_queue.pop() {
+                Some((key, removed)) => {
+                    self.delete(removed);
+                    evicted.push(key);
+                }
+                None => break,
+            }
+        }
+        evicted
+    }
+
+    /// Reads and removes an entry from the tier, as it is about to be promoted
+    /// to memory.
+    fn take(&mut self, key: &ByteRangeCacheKey) -> Option<Bytes> {
+        let entry = self.lru_queue.remove(key)?;
+        let value = std::fs::read(&entry.file);
+        self.delete(entry);
+        match value {
+            Ok(value) => Some(Bytes::from(value)),
+            Err(e) => {
+                debug!("Failed to read spilled byte range: {e}");
+                None
+            }
+        }
+    }
+
+    fn remove(&mut self, key: &ByteRangeCacheKey) -> bool {
+        match self.lru_queue.remove(key) {
+            Some(entry) => {
+                self.delete(entry);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn delete(&mut self, entry: DiskEntry) {
+        self.used -= entry.size;
+        if let Err(e) = std::fs::remove_file(&entry.file) {
+            debug!("Failed to remove spilled byte range {:?}: {e}", entry.file);
+        }
+    }
+
+    fn clear(&mut self) {
+        while let Some((_, entry)) = self.lru_queue.pop() {
+            self.delete(entry);
+        }
+    }
+}
+
+/// Handles the inner state of the [`DefaultByteRangeCache`] struct.
+struct DefaultByteRangeCacheState {
+    lru_queue: LruQueue<ByteRangeCacheKey, Bytes>,
+    memory_limit: usize,
+    memory_used: usize,
+    disk: Option<DiskTier>,
+    /// The most recent metadata observed for each object with cached ranges,
+    /// keyed by the object store url and the location of the object.
+    objects: HashMap<(String, Path), ObjectMeta>,
+    cache_hits: HashMap<ByteRangeCacheKey, usize>,
+    hits: usize,
+    misses: usize,
+}
+
+impl DefaultByteRangeCacheState {
+    fn new(memory_limit: usize) -> Self {
+        Self {
+            lru_queue: LruQueue::new(),
+            memory_limit,
+            memory_used: 0,
+            disk: None,
+            objects: HashMap::new(),
+            cache_hits: HashMap::new(),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Returns the cached range, looking in memory first and then on disk.
+    /// Ranges found on disk are moved back to memory.
+    fn get(&mut self, k: &ByteRangeCacheKey) -> Option<Bytes> {
+        let hits = self.cache_hits.get(k).copied().unwrap_or_default();
+        let value = match self.lru_queue.get(k) {
+            Some(value) => Some(value.clone()),
+            None => {
+                let value = self.disk.as_mut().and_then(|disk| disk.take(k));
+                if let Some(value) = &value {
+                    self.put(k.clone(), value.clone());
+                }
+                value
+            }
+        };
+
+        match &value {
+            Some(_) => self.hits += 1,
+            None => self.misses += 1,
+        }
+        if self.contains_key(k) {
+            self.cache_hits.insert(k.clone(), hits + 1);
+        } else {
+            self.cache_hits.remove(k);
+        }
+        value
+    }
+
+    fn contains_key(&self, k: &ByteRangeCacheKey) -> bool {
+        self.lru_queue.peek(k).is_some()
+            || self
+                .disk
+                .as_ref()
+                .is_some_and(|disk| disk.lru_queue.peek(k).is_some())
+    }
+
+    /// Adds a new range to the memory tier, evicting the least recently used
+    /// ranges to disk (or dropping them) if required.
+    fn put(&mut self, key: ByteRangeCacheKey, value: Bytes) -> Option<Bytes> {
+        let value_size = value.len();
+
+        // no point in trying to add this value to the cache if it cannot fit entirely
+        if value_size > self.memory_limit {
+            return None;
+        }
+
+        if let Some(disk) = self.disk.as_mut() {
+            disk.remove(&key);
+        }
+        self.cache_hits.insert(key.clone(), 0);
+        let old_value = self.lru_queue.put(key, value);
+        self.memory_used += value_size;
+        if let Some(ref old_value) = old_value {
+            self.memory_used -= old_value.len();
+        }
+
+        self.evict_entries();
+
+        old_value
+    }
+
+    /// Evicts entries from memory until `memory_used` is lower than `memory_limit`.
+    fn evict_entries(&mut self) {
+        while self.memory_used > self.memory_limit {
+            let Some((key, value)) = self.lru_queue.pop() else {
+                debug_assert!(
+                    false,
+                    "cache is empty while memory_used > memory_limit, cannot happen"
+                );
+                return;
+            };
+            self.memory_used -= value.len();
+            let evicted = match self.disk.as_mut() {
+                Some(disk) => disk.put(key, &value),
+                None => vec![key],
+            };
+            for key in evicted {
+                self.cache_hits.remove(&key);
+            }
+        }
+    }
+
+    fn remove(&mut self, k: &ByteRangeCacheKey) -> Option<Bytes> {
+        let value = match self.lru_queue.remove(k) {
+            Some(value) => {
+                self.memory_used -= value.len();
+                Some(value)
+            }
+            None => self.disk.as_mut().and_then(|disk| disk.take(k)),
+        };
+        self.cache_hits.remove(k);
+        value
+    }
+
+    fn update_object_meta(&mut self, store_url: &str, meta: &ObjectMeta) {
+        let object = (store_url.to_string(), meta.location.clone());
+        let changed = match self.objects.get(&object) {
+            Some(cached) => {
+                ByteRangeCacheKey::version_of(cached)
+                    != ByteRangeCacheKey::version_of(meta)
+            }
+            None => false,
+        };
+        if changed {
+            self.invalidate(store_url, &meta.location);
+        }
+        self.objects.insert(object, meta.clone());
+    }
+
+    /// Removes the metadata and all the cached ranges of an object.
+    fn invalidate(&mut self, store_url: &str, location: &Path) {
+        self.objects
+            .remove(&(store_url.to_string(), location.clone()));
+
+        let is_stale = |key: &ByteRangeCacheKey| {
+            key.store_url == store_url && &key.location == location
+        };
+        let mut stale: Vec<_> = self
+            .lru_queue
+            .list_entries()
+            .into_keys()
+            .filter(|key| is_stale(key))
+            .cloned()
+            .collect();
+        if let Some(disk) = &self.disk {
+            stale.extend(
+                disk.lru_queue
+                    .list_entries()
+                    .into_keys()
+                    .filter(|key| is_stale(key))
+                    .cloned(),
+            );
+        }
+        for key in stale {
+            if let Some(value) = self.lru_queue.remove(&key) {
+                self.memory_used -= value.len();
+            } else if let Some(disk) = self.disk.as_mut() {
+                disk.remove(&key);
+            }
+            self.cache_hits.remove(&key);
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.lru_queue.len() + self.disk.as_ref().map_or(0, |d| d.lru_queue.len())
+    }
+
+    fn clear(&mut self) {
+        self.lru_queue.clear();
+        self.memory_used = 0;
+        if let Some(disk) = self.disk.as_mut() {
+            disk.clear();
+        }
+        self.objects.clear();
+        self.cache_hits.clear();
+    }
+
+    fn list_entries(&self) -> HashMap<ByteRangeCacheKey, ByteRangeCacheEntry> {
+        let entry = |key: &ByteRangeCacheKey, size_bytes, tier| {
+            let entry = ByteRangeCacheEntry {
+                size_bytes,
+                hits: self.cache_hits.get(key).copied().unwrap_or_default(),
+                tier,
+            };
+            (key.clone(), entry)
+        };
+
+        let mut entries: HashMap<_, _> = self
+            .lru_queue
+            .list_entries()
+            .into_iter()
+            .map(|(key, value)| entry(key, value.len(), ByteRangeCacheTier::Memory))
+            .collect();
+        if let Some(disk) = &self.disk {
+            entries.extend(
+                disk.lru_queue
+                    .list_entries()
+                    .into_iter()
+                    .map(|(key, value)| entry(key, value.size, ByteRangeCacheTier::Disk)),
+            );
+        }
+        entries
+    }
+}
+
+/// Default implementation of [`ByteRangeCache`]
+///
+/// Caches byte ranges read from object stores in memory, and optionally on
+/// local disk.
+///
+/// # Internal details
+///
+/// The `memory_limit` controls the maximum size of the ranges held in memory,
+/// which uses a Least Recently Used eviction algorithm. When adding a new
+/// range, if the total size of the cached ranges exceeds `memory_limit`, the
+/// least recently used ranges are evicted until the total size is lower than
+/// `memory_limit`.
+///
+/// If a disk tier is configured with [`Self::try_with_disk_tier`], evicted
+/// ranges are written to files in a temporary directory instead of being
+/// dropped, with a separate LRU bounded by the disk limit. Ranges read from
+/// disk are moved back to memory.
+pub struct DefaultByteRangeCache {
+    // the state is wrapped in a Mutex to ensure the operations are atomic
+    state: Mutex<DefaultByteRangeCacheState>,
+}
+
+impl DefaultByteRangeCache {
+    /// Create a new instance of [`DefaultByteRangeCache`].
+    ///
+    /// # Arguments
+    /// `memory_limit`:  the maximum size of the ranges held in memory, in bytes
+    pub fn new(memory_limit: usize) -> Self {
+        Self {
+            state: Mutex::new(DefaultByteRangeCacheState::new(memory_limit)),
+        }
+    }
+
+    /// Spill the ranges evicted from memory to a new temporary directory
+    /// created in `directory`, holding at most `disk_limit` bytes.
+    pub fn try_with_disk_tier(
+        self,
+        directory: impl AsRef<std::path::Path>,
+        disk_limit: usize,
+    ) -> Result<Self> {
+        let disk = DiskTier::try_new(directory.as_ref(), disk_limit)?;
+        let mut state = self.state.into_inner();
+        state.disk = Some(disk);
+        Ok(Self {
+            state: Mutex::new(state),
+        })
+    }
+
+    /// Returns the size of the ranges cached in memory, in bytes.
+    pub fn memory_used(&self) -> usize {
+        let state = self.state.lock();
+        state.memory_used
+    }
+
+    /// Returns the size of the ranges cached on disk, in bytes.
+    pub fn disk_used(&self) -> usize {
+        let state = self.state.lock();
+        state.disk.as_ref().map_or(0, |disk| disk.used)
+    }
+}
+
+impl CacheAccessor<ByteRangeCacheKey, Bytes> for DefaultByteRangeCache {
+    fn get(&self, key: &ByteRangeCacheKey) -> Option<Bytes> {
+        let mut state = self.state.lock();
+        state.get(key)
+    }
+
+    fn put(&self, key: &ByteRangeCacheKey, value: Bytes) -> Option<Bytes> {
+        let mut state = self.state.lock();
+        state.put(key.clone(), value)
+    }
+
+    fn remove(&self, k: &ByteRangeCacheKey) -> Option<Bytes> {
+        let mut state = self.state.lock();
+        state.remove(k)
+    }
+
+    fn contains_key(&self, k: &ByteRangeCacheKey) -> bool {
+        let state = self.state.lock();
+        state.contains_key(k)
+    }
+
+    fn len(&self) -> usize {
+        let state = self.state.lock();
+        state.len()
+    }
+
+    fn clear(&self) {
+        let mut state = self.state.lock();
+        state.clear();
+    }
+
+    fn name(&self) -> String {
+        "DefaultByteRangeCache".to_string()
+    }
+}
+
+impl ByteRangeCache for DefaultByteRangeCache {
+    fn cache_limit(&self) -> usize {
+        let state = self.state.lock();
+        state.memory_limit
+    }
+
+    fn update_cache_limit(&self, limit: usize) {
+        let mut state = self.state.lock();
+        state.memory_limit = limit;
+        state.evict_entries();
+    }
+
+    fn object_meta(&self, store_url: &str, location: &Path) -> Option<ObjectMeta> {
+        let state = self.state.lock();
+        state
+            .objects
+            .get(&(store_url.to_string(), location.clone()))
+            .cloned()
+    }
+
+    fn update_object_meta(&self, store_url: &str, meta: &ObjectMeta) {
+        let mut state = self.state.lock();
+        state.update_object_meta(store_url, meta);
+    }
+
+    fn invalidate(&self, store_url: &str, location: &Path) {
+        let mut state = self.state.lock();
+        state.invalidate(store_url, location);
+    }
+
+    fn list_entries(&self) -> HashMap<ByteRangeCacheKey, ByteRangeCacheEntry> {
+        let state = self.state.lock();
+        state.list_entries()
+    }
+
+    fn metrics(&self) -> ByteRangeCacheMetrics {
+        let state = self.state.lock();
+        ByteRangeCacheMetrics {
+            hits: state.hits,
+            misses: state.misses,
+            memory_used: state.memory_used,
+            disk_used: state.disk.as_ref().map_or(0, |disk| disk.used),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::DateTime;
+
+    fn meta(location: &str, e_tag: Option<&str>) -> ObjectMeta {
+        ObjectMeta {
+            location: Path::from(location),
+            last_modified: DateTime::parse_from_rfc3339("2025-07-29T12:12:12+00:00")
+                .unwrap()
+                .into(),
+            size: 1024,
+            e_tag: e_tag.map(String::from),
+            version: None,
+        }
+    }
+
+    fn key(meta: &ObjectMeta, start: u64, end: u64) -> ByteRangeCacheKey {
+        ByteRangeCacheKey::new("s3://bucket", meta, start..end)
+    }
+
+    #[test]
+    fn test_byte_range_cache() {
+        let cache = DefaultByteRangeCache::new(10);
+        let meta = meta("a.parquet", Some("1"));
+
+        assert_eq!(cache.get(&key(&meta, 0, 4)), None);
+        cache.put(&key(&meta, 0, 4), Bytes::from_static(b"abcd"));
+        cache.put(&key(&meta, 4, 8), Bytes::from_static(b"efgh"));
+        assert_eq!(cache.memory_used(), 8);
+        assert_eq!(
+            cache.get(&key(&meta, 0, 4)),
+            Some(Bytes::from_static(b"abcd"))
+        );
+
+        // evicts the least recently used range 4..8
+        cache.put(&key(&meta, 8, 12), Bytes::from_static(b"ijkl"));
+        assert!(cache.contains_key(&key(&meta, 0, 4)));
+        assert!(!cache.contains_key(&key(&meta, 4, 8)));
+        assert!(cache.contains_key(&key(&meta, 8, 12)));
+        assert_eq!(cache.memory_used(), 8);
+
+        // ranges larger than the limit are not cached
+        cache.put(&key(&meta, 0, 11), Bytes::from_static(b"abcdefghijk"));
+        assert!(!cache.contains_key(&key(&meta, 0, 11)));
+
+        // lowering the limit evicts entries
+        cache.update_cache_limit(4);
+        assert_eq!(cache.len(), 1);
+        assert!(cache.contains_key(&key(&meta, 8, 12)));
+
+        assert_eq!(
+            cache.metrics(),
+            ByteRangeCacheMetrics {
+                hits: 1,
+                misses: 1,
+                memory_used: 4,
+                disk_used: 0,
+            }
+        );
+
+        cache.clear();
+        assert!(cache.is_empty());
+        assert_eq!(cache.memory_used(), 0);
+    }
+
+    #[test]
+    fn test_byte_range_cache_disk_tier() {
+        let directory = tempfile::tempdir().unwrap();
+        let cache = DefaultByteRangeCache::new(4)
+            .try_with_disk_tier(directory.path(), 8)
+            .unwrap();
+        let meta = meta("a.parquet", None);
+
+        cache.put(&key(&meta, 0, 4), Bytes::from_static(b"abcd"));
+        cache.put(&key(&meta, 4, 8), Bytes::from_static(b"efgh"));
+        cache.put(&key(&meta, 8, 12), Bytes::from_static(b"ijkl"));
+        assert_eq!(cache.memory_used(), 4);
+        assert_eq!(cache.disk_used(), 8);
+        assert_eq!(cache.len(), 3);
+
+        let entries = cache.list_entries();
+        assert_eq!(entries[&key(&meta, 0, 4)].tier, ByteRangeCacheTier::Disk);
+        assert_eq!(entries[&key(&meta, 8, 12)].tier, ByteRangeCacheTier::Memory);
+
+        // reading a spilled range moves it back to memory
+        assert_eq!(
+            cache.get(&key(&meta, 0, 4)),
+            Some(Bytes::from_static(b"abcd"))
+        );
+        let entries = cache.list_entries();
+        assert_eq!(entries[&key(&meta, 0, 4)].tier, ByteRangeCacheTier::Memory);
+        assert_eq!(entries[&key(&meta, 0, 4)].hits, 1);
+        assert_eq!(entries[&key(&meta, 8, 12)].tier, ByteRangeCacheTier::Disk);
+        assert_eq!(cache.disk_used(), 8);
+
+        // the disk tier is bounded as well
+        cache.put(&key(&meta, 12, 16), Bytes::from_static(b"mnop"));
+        assert_eq!(cache.len(), 3);
+        assert!(!cache.contains_key(&key(&meta, 4, 8)));
+
+        // spilled files are removed along with their entries
+        cache.clear();
+        assert_eq!(cache.disk_used(), 0);
+        let spill_dir = std::fs::read_dir(directory.path())
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap();
+        assert_eq!(std::fs::read_dir(spill_dir.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_byte_range_cache_invalidation() {
+        let cache = DefaultByteRangeCache::new(1024);
+        let old = meta("a.parquet", Some("1"));
+        let other = meta("b.parquet", Some("1"));
+
+        cache.update_object_meta("s3://bucket", &old);
+        cache.update_object_meta("s3://bucket", &other);
+        cache.put(&key(&old, 0, 4), Bytes::from_static(b"abcd"));
+        cache.put(&key(&other, 0, 4), Bytes::from_static(b"abcd"));
+        assert_eq!(
+            cache.object_meta("s3://bucket", &old.location),
+            Some(old.clone())
+        );
+        assert_eq!(cache.object_meta("s3://other", &old.location), None);
+
+        // observing the same version keeps the ranges
+        cache.update_object_meta("s3://bucket", &old);
+        assert_eq!(cache.len(), 2);
+
+        // a new version drops the ranges of the previous one
+        let new = meta("a.parquet", Some("2"));
+        cache.update_object_meta("s3://bucket", &new);
+        assert_eq!(cache.object_meta("s3://bucket", &new.location), Some(new));
+        assert!(!cache.contains_key(&key(&old, 0, 4)));
+        assert!(cache.contains_key(&key(&other, 0, 4)));
+
+        cache.invalidate("s3://bucket", &other.location);
+        assert!(cache.is_empty());
+        assert_eq!(cache.object_meta("s3://bucket", &other.location), None);
+    }
+}
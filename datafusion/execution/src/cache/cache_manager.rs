@@ -16,10 +16,12 @@
 // under the License.
 
 use crate::cache::CacheAccessor;
+use crate::cache::DefaultByteRangeCache;
 use crate::cache::DefaultListFilesCache;
-use crate::cache::cache_unit::DefaultFilesMetadataCache;
+use crate::cache::cache_unit::{DefaultFilesMetadataCache, DefaultTableStatisticsStore};
 use crate::cache::list_files_cache::ListFilesEntry;
 use crate::cache::list_files_cache::TableScopedPath;
+use bytes::Bytes;
 use datafusion_common::TableReference;
 use datafusion_common::stats::Precision;
 use datafusion_common::{Result, Statistics};
@@ -28,8 +30,8 @@ use object_store::ObjectMeta;
 use object_store::path::Path;
 use std::any::Any;
 use std::collections::HashMap;
-use std::fmt::{Debug, Formatter};
-use std::ops::Deref;
+use std::fmt::{Debug, Display, Formatter};
+use std::ops::{Deref, Range};
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -296,6 +298,197 @@ pub struct FileMetadataCacheEntry {
     pub extra: HashMap<String, String>,
 }
 
+/// Identifies a byte range of a specific version of an object, used as the key
+/// of the [`ByteRangeCache`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ByteRangeCacheKey {
+    /// Url of the object store holding the object, e.g. `s3://bucket`.
+    pub store_url: String,
+    /// Location of the object within the object store.
+    pub location: Path,
+    /// Version of the object: its e_tag if known, or its last modified time.
+    pub version: String,
+    /// The cached range of bytes.
+    pub range: Range<u64>,
+}
+
+impl ByteRangeCacheKey {
+    /// Create the key of `range` in the version of the object described by `meta`.
+    pub fn new(
+        store_url: impl Into<String>,
+        meta: &ObjectMeta,
+        range: Range<u64>,
+    ) -> Self {
+        Self {
+            store_url: store_url.into(),
+            location: meta.location.clone(),
+            version: Self::version_of(meta),
+            range,
+        }
+    }
+
+    /// Returns the version of the object described by `meta`, used to tell
+    /// apart ranges of an object that was overwritten.
+    pub fn version_of(meta: &ObjectMeta) -> String {
+        meta.e_tag
+            .clone()
+            .unwrap_or_else(|| meta.last_modified.to_rfc3339())
+    }
+}
+
+/// Cache for byte ranges read from object stores.
+///
+/// When enabled, the object stores returned by
+/// [`RuntimeEnv::object_store`](crate::runtime_env::RuntimeEnv::object_store)
+/// are wrapped in a [`CachingObjectStore`], which serves ranged reads (such as
+/// the column chunks of Parquet files) from this cache, avoiding downloading the
+/// same data again in subsequent queries.
+///
+/// Ranges are keyed by [`ByteRangeCacheKey`], which includes the version of the
+/// object. The cache also keeps track of the latest [`ObjectMeta`] observed for
+/// each object, so that the version of an object is known when reading ranges of
+/// it, and ranges of older versions are dropped when the object changes. Note
+/// that, similarly to the [`ListFilesCache`], changes to an object are only
+/// noticed once its new metadata is observed, for example when the object is
+/// listed again.
+///
+/// DataFusion provides a default implementation, [`DefaultByteRangeCache`], with
+/// an optional disk tier, and users can also provide their own implementations
+/// to implement custom caching strategies.
+///
+/// See [`crate::runtime_env::RuntimeEnv`] for more details.
+///
+/// [`CachingObjectStore`]: crate::cache::CachingObjectStore
+/// [`DefaultByteRangeCache`]: crate::cache::DefaultByteRangeCache
+pub trait ByteRangeCache: CacheAccessor<ByteRangeCacheKey, Bytes> {
+    /// Returns the cache's memory limit in bytes.
+    fn cache_limit(&self) -> usize;
+
+    /// Updates the cache with a new memory limit in bytes.
+    fn update_cache_limit(&self, limit: usize);
+
+    /// Returns the latest metadata observed for the object at `location` of the
+    /// object store at `store_url`, if any.
+    fn object_meta(&self, store_url: &str, location: &Path) -> Option<ObjectMeta>;
+
+    /// Records the latest metadata of an object, removing the ranges cached for
+    /// other versions of it.
+    fn update_object_meta(&self, store_url: &str, meta: &ObjectMeta);
+
+    /// Removes the metadata and all the cached ranges of an object, for example
+    /// because it was overwritten or deleted.
+    fn invalidate(&self, store_url: &str, location: &Path);
+
+    /// Retrieves the information about the ranges currently cached.
+    fn list_entries(&self) -> HashMap<ByteRangeCacheKey, ByteRangeCacheEntry>;
+
+    /// Returns the hit and miss counts and the size of the cache.
+    fn metrics(&self) -> ByteRangeCacheMetrics;
+}
+
+/// The tier of the [`ByteRangeCache`] holding a range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteRangeCacheTier {
+    Memory,
+    Disk,
+}
+
+impl Display for ByteRangeCacheTier {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Memory => write!(f, "memory"),
+            Self::Disk => write!(f, "disk"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Represents information about a cached byte range.
+/// This is used to expose the byte range cache contents to outside modules.
+pub struct ByteRangeCacheEntry {
+    /// Size of the cached range, in bytes.
+    pub size_bytes: usize,
+    /// Number of times this entry was retrieved.
+    pub hits: usize,
+    /// The tier holding the range.
+    pub tier: ByteRangeCacheTier,
+}
+
+/// Hit and miss counts and size of a [`ByteRangeCache`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ByteRangeCacheMetrics {
+    /// Number of lookups that found the range in the cache.
+    pub hits: usize,
+    /// Number of lookups that did not find the range in the cache.
+    pub misses: usize,
+    /// Size of the ranges cached in memory, in bytes.
+    pub memory_used: usize,
+    /// Size of the ranges cached on disk, in bytes.
+    pub disk_used: usize,
+}
+
+/// Statistics of a whole table, as stored in a [`TableStatisticsStore`]
+#[derive(Debug, Clone)]
+pub struct TableStatistics {
+    /// Row count and column statistics, with one entry in
+    /// [`Statistics::column_statistics`] per field of the table schema.
+    /// Columns that were not analyzed have unknown statistics.
+    pub statistics: Statistics,
+    /// Value distributions (histograms and most common values) of the
+    /// columns, if collected.
+    ///
+    /// This is type erased as the distribution types are defined by
+    /// `datafusion-physical-plan`: `ANALYZE TABLE` stores its
+    /// `operator_statistics::ColumnDistributions` here.
+    pub column_distributions: Option<Arc<dyn Any + Send + Sync>>,
+}
+
+impl TableStatistics {
+    /// Create table statistics without column distributions
+    pub fn new(statistics: Statistics) -> Self {
+        Self {
+            statistics,
+            column_distributions: None,
+        }
+    }
+
+    /// Set the value distributions of the columns
+    pub fn with_column_distributions(
+        mut self,
+        column_distributions: Arc<dyn Any + Send + Sync>,
+    ) -> Self {
+        self.column_distributions = Some(column_distributions);
+        self
+    }
+
+    /// Returns the value distributions of the columns if they are of type `T`
+    pub fn column_distributions<T: Any + Send + Sync>(&self) -> Option<Arc<T>> {
+        Arc::clone(self.column_distributions.as_ref()?)
+            .downcast::<T>()
+            .ok()
+    }
+}
+
+/// Store for table level statistics, such as those collected by `ANALYZE TABLE`.
+///
+/// Entries are keyed by the fully qualified [`TableReference`] of the table and
+/// hold the [`TableStatistics`] of the whole table.
+///
+/// Unlike the other caches, entries are not validated against the underlying
+/// data: they describe the table as of the time they were collected and are only
+/// replaced by collecting them again, or removed when the table is dropped.
+/// Tables should therefore only use them as estimates.
+///
+/// DataFusion provides an in-memory default, [`DefaultTableStatisticsStore`], and
+/// users can provide their own implementations, for example to persist the
+/// statistics across sessions.
+///
+/// See [`crate::runtime_env::RuntimeEnv`] for more details.
+pub trait TableStatisticsStore:
+    CacheAccessor<TableReference, Arc<TableStatistics>>
+{
+}
+
 impl Debug for dyn FileStatisticsCache {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "Cache name: {} with length: {}", self.name(), self.len())
@@ -314,6 +507,18 @@ impl Debug for dyn FileMetadataCache {
     }
 }
 
+impl Debug for dyn ByteRangeCache {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Cache name: {} with length: {}", self.name(), self.len())
+    }
+}
+
+impl Debug for dyn TableStatisticsStore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Cache name: {} with length: {}", self.name(), self.len())
+    }
+}
+
 /// Manages various caches used in DataFusion.
 ///
 /// Following DataFusion design principles, DataFusion provides default cache
@@ -326,6 +531,8 @@ pub struct CacheManager {
     file_statistic_cache: Option<Arc<dyn FileStatisticsCache>>,
     list_files_cache: Option<Arc<dyn ListFilesCache>>,
     file_metadata_cache: Arc<dyn FileMetadataCache>,
+    table_statistics_store: Arc<dyn TableStatisticsStore>,
+    byte_range_cache: Option<Arc<dyn ByteRangeCache>>,
 }
 
 impl CacheManager {
@@ -364,10 +571,32 @@ impl CacheManager {
         // the cache memory limit might have changed, ensure the limit is updated
         file_metadata_cache.update_cache_limit(config.metadata_cache_limit);
 
+        let table_statistics_store = config
+            .table_statistics_store
+            .as_ref()
+            .map(Arc::clone)
+            .unwrap_or_else(|| Arc::new(DefaultTableStatisticsStore::default()));
+
+        let byte_range_cache = match &config.byte_range_cache {
+            Some(brc) if config.byte_range_cache_limit > 0 => {
+                // the cache memory limit might have changed, ensure the limit is updated
+                brc.update_cache_limit(config.byte_range_cache_limit);
+                Some(Arc::clone(brc))
+            }
+            None if config.byte_range_cache_limit > 0 => {
+                let brc: Arc<dyn ByteRangeCache> =
+                    Arc::new(DefaultByteRangeCache::new(config.byte_range_cache_limit));
+                Some(brc)
+            }
+            _ => None,
+        };
+
         Ok(Arc::new(CacheManager {
             file_statistic_cache,
             list_files_cache,
             file_metadata_cache,
+            table_statistics_store,
+            byte_range_cache,
         }))
     }
 
@@ -402,10 +631,30 @@ impl CacheManager {
     pub fn get_metadata_cache_limit(&self) -> usize {
         self.file_metadata_cache.cache_limit()
     }
+
+    /// Get the store of table statistics.
+    pub fn get_table_statistics_store(&self) -> Arc<dyn TableStatisticsStore> {
+        Arc::clone(&self.table_statistics_store)
+    }
+
+    /// Get the cache of byte ranges read from object stores, if enabled.
+    pub fn get_byte_range_cache(&self) -> Option<Arc<dyn ByteRangeCache>> {
+        self.byte_range_cache.clone()
+    }
+
+    /// Get the memory limit of the byte range cache.
+    pub fn get_byte_range_cache_limit(&self) -> usize {
+        self.byte_range_cache
+            .as_ref()
+            .map_or(0, |c| c.cache_limit())
+    }
 }
 
 pub const DEFAULT_METADATA_CACHE_LIMIT: usize = 50 * 1024 * 1024; // 50M
 
+/// The byte range cache is disabled by default.
+pub const DEFAULT_BYTE_RANGE_CACHE_LIMIT: usize = 0;
+
 #[derive(Clone)]
 pub struct CacheManagerConfig {
     /// Enable caching of file statistics when listing files.
@@ -432,6 +681,17 @@ pub struct CacheManagerConfig {
     pub file_metadata_cache: Option<Arc<dyn FileMetadataCache>>,
     /// Limit of the file-embedded metadata cache, in bytes.
     pub metadata_cache_limit: usize,
+    /// Store of table statistics collected by `ANALYZE TABLE`.
+    /// If not provided, the [`CacheManager`] will create a [`DefaultTableStatisticsStore`].
+    pub table_statistics_store: Option<Arc<dyn TableStatisticsStore>>,
+    /// Cache of byte ranges read from object stores, used to avoid downloading the same data
+    /// multiple times (e.g., Parquet column chunks read from S3).
+    /// If not provided and `byte_range_cache_limit` is greater than zero, the [`CacheManager`]
+    /// will create a [`DefaultByteRangeCache`].
+    pub byte_range_cache: Option<Arc<dyn ByteRangeCache>>,
+    /// Limit of the in-memory part of the byte range cache, in bytes. The cache is disabled
+    /// when the limit is zero. Default: 0 (disabled).
+    pub byte_range_cache_limit: usize,
 }
 
 impl Default for CacheManagerConfig {
@@ -443,6 +703,9 @@ impl Default for CacheManagerConfig {
             list_files_cache_ttl: DEFAULT_LIST_FILES_CACHE_TTL,
             file_metadata_cache: Default::default(),
             metadata_cache_limit: DEFAULT_METADATA_CACHE_LIMIT,
+            table_statistics_store: Default::default(),
+            byte_range_cache: Default::default(),
+            byte_range_cache_limit: DEFAULT_BYTE_RANGE_CACHE_LIMIT,
         }
     }
 }
@@ -502,6 +765,41 @@ impl CacheManagerConfig {
         self.metadata_cache_limit = limit;
         self
     }
+
+    /// Sets the store for table statistics.
+    ///
+    /// Default is a [`DefaultTableStatisticsStore`].
+    pub fn with_table_statistics_store(
+        mut self,
+        store: Option<Arc<dyn TableStatisticsStore>>,
+    ) -> Self {
+        self.table_statistics_store = store;
+        self
+    }
+
+    /// Sets the cache for byte ranges read from object stores, along with its
+    /// current limit.
+    ///
+    /// Default is `None` (disabled).
+    pub fn with_byte_range_cache(
+        mut self,
+        cache: Option<Arc<dyn ByteRangeCache>>,
+    ) -> Self {
+        if let Some(cache) = &cache {
+            self.byte_range_cache_limit = cache.cache_limit();
+        }
+        self.byte_range_cache = cache;
+        self
+    }
+
+    /// Sets the limit of the in-memory part of the byte range cache, in bytes.
+    /// A limit of zero disables the cache.
+    ///
+    /// Default: 0 (disabled).
+    pub fn with_byte_range_cache_limit(mut self, limit: usize) -> Self {
+        self.byte_range_cache_limit = limit;
+        self
+    }
 }
 
 #[cfg(test)]
@@ -571,4 +869,38 @@ mod tests {
             "TTL should be overridden to 60 seconds when set in config"
         );
     }
+
+    #[test]
+    fn test_byte_range_cache_config() {
+        // disabled by default
+        let cache_manager =
+            CacheManager::try_new(&CacheManagerConfig::default()).unwrap();
+        assert!(cache_manager.get_byte_range_cache().is_none());
+        assert_eq!(cache_manager.get_byte_range_cache_limit(), 0);
+
+        // a default cache is created when a limit is set
+        let config = CacheManagerConfig::default().with_byte_range_cache_limit(1024);
+        let cache_manager = CacheManager::try_new(&config).unwrap();
+        assert_eq!(cache_manager.get_byte_range_cache_limit(), 1024);
+
+        // a provided cache keeps its limit, unless overridden
+        let cache: Arc<dyn ByteRangeCache> = Arc::new(DefaultByteRangeCache::new(2048));
+        let config =
+            CacheManagerConfig::default().with_byte_range_cache(Some(Arc::clone(&cache)));
+        let cache_manager = CacheManager::try_new(&config).unwrap();
+        assert_eq!(cache_manager.get_byte_range_cache_limit(), 2048);
+
+        let config = config.with_byte_range_cache_limit(4096);
+        let cache_manager = CacheManager::try_new(&config).unwrap();
+        assert_eq!(cache.cache_limit(), 4096);
+        assert!(Arc::ptr_eq(
+            &cache_manager.get_byte_range_cache().unwrap(),
+            &cache
+        ));
+
+        // a limit of zero disables the cache
+        let config = config.with_byte_range_cache_limit(0);
+        let cache_manager = CacheManager::try_new(&config).unwrap();
+        assert!(cache_manager.get_byte_range_cache().is_none());
+    }
 }
@@ -19,11 +19,14 @@ use std::collections::HashMap;
 
 use crate::cache::CacheAccessor;
 use crate::cache::cache_manager::{
-    CachedFileMetadata, FileStatisticsCache, FileStatisticsCacheEntry,
+    CachedFileMetadata, FileStatisticsCache, FileStatisticsCacheEntry, TableStatistics,
+    TableStatisticsStore,
 };
 
 use dashmap::DashMap;
+use datafusion_common::TableReference;
 use object_store::path::Path;
+use std::sync::Arc;
 
 pub use crate::cache::DefaultFilesMetadataCache;
 
@@ -98,6 +101,56 @@ impl FileStatisticsCache for DefaultFileStatisticsCache {
     }
 }
 
+/// Default in-memory implementation of [`TableStatisticsStore`]
+///
+/// Stores the statistics of each table for the lifetime of the session.
+///
+/// Uses DashMap for lock-free concurrent access.
+///
+/// [`TableStatisticsStore`]: crate::cache::cache_manager::TableStatisticsStore
+#[derive(Default)]
+pub struct DefaultTableStatisticsStore {
+    statistics: DashMap<TableReference, Arc<TableStatistics>>,
+}
+
+impl CacheAccessor<TableReference, Arc<TableStatistics>> for DefaultTableStatisticsStore {
+    fn get(&self, key: &TableReference) -> Option<Arc<TableStatistics>> {
+        self.statistics
+            .get(key)
+            .map(|entry| Arc::clone(entry.value()))
+    }
+
+    fn put(
+        &self,
+        key: &TableReference,
+        value: Arc<TableStatistics>,
+    ) -> Option<Arc<TableStatistics>> {
+        self.statistics.insert(key.clone(), value)
+    }
+
+    fn remove(&self, k: &TableReference) -> Option<Arc<TableStatistics>> {
+        self.statistics.remove(k).map(|(_, entry)| entry)
+    }
+
+    fn contains_key(&self, k: &TableReference) -> bool {
+        self.statistics.contains_key(k)
+    }
+
+    fn len(&self) -> usize {
+        self.statistics.len()
+    }
+
+    fn clear(&self) {
+        self.statistics.clear();
+    }
+
+    fn name(&self) -> String {
+        "DefaultTableStatisticsStore".to_string()
+    }
+}
+
+impl TableStatisticsStore for DefaultTableStatisticsStore {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -407,4 +460,25 @@ mod tests {
             ])
         );
     }
+
+    #[test]
+    fn test_table_statistics_store() {
+        let store = DefaultTableStatisticsStore::default();
+        let schema = Schema::new(vec![Field::new("a", DataType::Int32, true)]);
+        let table = TableReference::full("datafusion", "public", "t");
+        let other = TableReference::full("datafusion", "public", "other");
+
+        let statistics = Arc::new(TableStatistics::new(
+            Statistics::new_unknown(&schema).with_num_rows(Precision::Exact(10)),
+        ));
+        assert!(store.put(&table, Arc::clone(&statistics)).is_none());
+        assert!(store.contains_key(&table));
+        assert!(!store.contains_key(&other));
+        assert!(Arc::ptr_eq(&store.get(&table).unwrap(), &statistics));
+        assert_eq!(store.len(), 1);
+
+        assert!(store.remove(&table).is_some());
+        assert!(store.get(&table).is_none());
+        assert!(store.is_empty());
+    }
 }
@@ -0,0 +1,377 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use object_store::path::Path;
+use object_store::{
+    CopyOptions, GetOptions, GetRange, GetResult, GetResultPayload, ListResult,
+    MultipartUpload, ObjectMeta, ObjectStore, ObjectStoreExt, PutMultipartOptions,
+    PutOptions, PutPayload, PutResult, Result,
+};
+
+use crate::cache::cache_manager::{ByteRangeCache, ByteRangeCacheKey};
+
+/// An [`ObjectStore`] serving ranged reads from a [`ByteRangeCache`].
+///
+/// Bounded range requests, made with [`ObjectStore::get_opts`] or
+/// [`ObjectStore::get_ranges`], are looked up in the cache and only the missing
+/// ranges are read from the inner store, after which they are added to the
+/// cache. All other requests are forwarded to the inner store, while keeping
+/// the metadata of the objects tracked by the cache up to date: objects that are
+/// listed or read with a new version, written, copied over or deleted have their
+/// cached ranges removed.
+///
+/// [`RuntimeEnv::object_store`] wraps the registered object stores in a
+/// [`CachingObjectStore`] when a [`ByteRangeCache`] is configured.
+///
+/// [`RuntimeEnv::object_store`]: crate::runtime_env::RuntimeEnv::object_store
+#[derive(Debug)]
+pub struct CachingObjectStore {
+    inner: Arc<dyn ObjectStore>,
+    cache: Arc<dyn ByteRangeCache>,
+    /// Url of the inner store, scoping the cache entries of its objects
+    store_url: String,
+}
+
+impl CachingObjectStore {
+    /// Create a new [`CachingObjectStore`] caching the ranges read from `inner`,
+    /// the object store registered for `store_url`.
+    pub fn new(
+        inner: Arc<dyn ObjectStore>,
+        cache: Arc<dyn ByteRangeCache>,
+        store_url: impl Into<String>,
+    ) -> Self {
+        Self {
+            inner,
+            cache,
+            store_url: store_url.into(),
+        }
+    }
+
+    /// Returns the wrapped object store.
+    pub fn inner(&self) -> &Arc<dyn ObjectStore> {
+        &self.inner
+    }
+
+    /// Returns the latest known metadata of the object at `location`, fetching
+    /// it from the inner store if it is not known yet.
+    async fn object_meta(&self, location: &Path) -> Result<ObjectMeta> {
+        if let Some(meta) = self.cache.object_meta(&self.store_url, location) {
+            return Ok(meta);
+        }
+        let meta = self.inner.head(location).await?;
+        self.cache.update_object_meta(&self.store_url, &meta);
+        Ok(meta)
+    }
+}
+
+impl Display for CachingObjectStore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "CachingObjectStore({})", self.inner)
+    }
+}
+
+/// Returns true if `options` request the latest version of an object, without
+/// any precondition.
+fn is_unconditional(options: &GetOptions) -> bool {
+    options.if_match.is_none()
+        && options.if_none_match.is_none()
+        && options.if_modified_since.is_none()
+        && options.if_unmodified_since.is_none()
+        && options.version.is_none()
+}
+
+/// Updates the metadata of an object seen in a listing, if the cache tracks it.
+fn refresh_object_meta(cache: &dyn ByteRangeCache, store_url: &str, meta: &ObjectMeta) {
+    if cache.object_meta(store_url, &meta.location).is_some() {
+        cache.update_object_meta(store_url, meta);
+    }
+}
+
+#[async_trait]
+impl ObjectStore for CachingObjectStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> Result<PutResult> {
+        let result = self.inner.put_opts(location, payload, opts).await;
+        self.cache.invalidate(&self.store_url, location);
+        result
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOptions,
+    ) -> Result<Box<dyn MultipartUpload>> {
+        self.cache.invalidate(&self.store_url, location);
+        self.inner.put_multipart_opts(location, opts).await
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+        let range = match &options.range {
+            Some(GetRange::Bounded(range))
+                if !options.head && is_unconditional(&options) =>
+            {
+                Some(range.clone())
+            }
+            _ => None,
+        };
+        let Some(range) = range else {
+            let is_latest = options.version.is_none();
+            let result = self.inner.get_opts(location, options).await?;
+            if is_latest {
+                self.cache.update_object_meta(&self.store_url, &result.meta);
+            }
+            return Ok(result);
+        };
+
+        if let Some(meta) = self.cache.object_meta(&self.store_url, location) {
+            let key = ByteRangeCacheKey::new(&self.store_url, &meta, range.clone());
+            if let Some(bytes) = self.cache.get(&key) {
+                let range = range.start..range.start + bytes.len() as u64;
+                return Ok(GetResult {
+                    payload: GetResultPayload::Stream(
+                        futures::stream::once(async move { Ok(bytes) }).boxed(),
+                    ),
+                    meta,
+                    range,
+                    attributes: Default::default(),
+                });
+            }
+        }
+
+        let result = self.inner.get_opts(location, options).await?;
+        let meta = result.meta.clone();
+        let attributes = result.attributes.clone();
+        let result_range = result.range.clone();
+        let bytes = result.bytes().await?;
+
+        self.cache.update_object_meta(&self.store_url, &meta);
+        let key = ByteRangeCacheKey::new(&self.store_url, &meta, range);
+        self.cache.put(&key, bytes.clone());
+
+        Ok(GetResult {
+            payload: GetResultPayload::Stream(
+                futures::stream::once(async move { Ok(bytes) }).boxed(),
+            ),
+            meta,
+            range: result_range,
+            attributes,
+        })
+    }
+
+    async fn get_ranges(
+        &self,
+        location: &Path,
+        ranges: &[Range<u64>],
+    ) -> Result<Vec<Bytes>> {
+        let meta = self.object_meta(location).await?;
+        let keys: Vec<_> = ranges
+            .iter()
+            .map(|range| ByteRangeCacheKey::new(&self.store_url, &meta, range.clone()))
+            .collect();
+        let mut result: Vec<_> = keys.iter().map(|key| self.cache.get(key)).collect();
+
+        let missing: Vec<_> =
+            (0..ranges.len()).filter(|&i| result[i].is_none()).collect();
+        if !missing.is_empty() {
+            let missing_ranges: Vec<_> =
+                missing.iter().map(|&i| ranges[i].clone()).collect();
+            let fetched = self.inner.get_ranges(location, &missing_ranges).await?;
+            for (i, bytes) in missing.into_iter().zip(fetched) {
+                self.cache.put(&keys[i], bytes.clone());
+                result[i] = Some(bytes);
+            }
+        }
+
+        // all the ranges are either cached or fetched by now
+        Ok(result.into_iter().flatten().collect())
+    }
+
+    fn delete_stream(
+        &self,
+        locations: BoxStream<'static, Result<Path>>,
+    ) -> BoxStream<'static, Result<Path>> {
+        let cache = Arc::clone(&self.cache);
+        let store_url = self.store_url.clone();
+        self.inner
+            .delete_stream(locations)
+            .inspect_ok(move |location| cache.invalidate(&store_url, location))
+            .boxed()
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, Result<ObjectMeta>> {
+        let cache = Arc::clone(&self.cache);
+        let store_url = self.store_url.clone();
+        self.inner
+            .list(prefix)
+            .inspect_ok(move |meta| refresh_object_meta(cache.as_ref(), &store_url, meta))
+            .boxed()
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        let result = self.inner.list_with_delimiter(prefix).await?;
+        for meta in &result.objects {
+            refresh_object_meta(self.cache.as_ref(), &self.store_url, meta);
+        }
+        Ok(result)
+    }
+
+    async fn copy_opts(
+        &self,
+        from: &Path,
+        to: &Path,
+        options: CopyOptions,
+    ) -> Result<()> {
+        let result = self.inner.copy_opts(from, to, options).await;
+        self.cache.invalidate(&self.store_url, to);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::cache::CacheAccessor;
+    use crate::cache::DefaultByteRangeCache;
+    use object_store::memory::InMemory;
+
+    async fn setup() -> (
+        Arc<InMemory>,
+        Arc<DefaultByteRangeCache>,
+        CachingObjectStore,
+    ) {
+        let inner = Arc::new(InMemory::new());
+        inner
+            .put(
+                &Path::from("a.parquet"),
+                Bytes::from_static(b"0123456789").into(),
+            )
+            .await
+            .unwrap();
+        let cache = Arc::new(DefaultByteRangeCache::new(1024));
+        let store = CachingObjectStore::new(
+            Arc::clone(&inner) as _,
+            Arc::clone(&cache) as _,
+            "memory://",
+        );
+        (inner, cache, store)
+    }
+
+    #[tokio::test]
+    async fn test_get_ranges_are_cached() {
+        let (inner, cache, store) = setup().await;
+        let location = Path::from("a.parquet");
+
+        let bytes = store.get_ranges(&location, &[0..2, 4..6]).await.unwrap();
+        assert_eq!(
+            bytes,
+            vec![Bytes::from_static(b"01"), Bytes::from_static(b"45")]
+        );
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.metrics().misses, 2);
+
+        // served from the cache, even once the object is gone from the inner store
+        inner.delete(&location).await.unwrap();
+        let bytes = store.get_ranges(&location, &[4..6, 0..2]).await.unwrap();
+        assert_eq!(
+            bytes,
+            vec![Bytes::from_static(b"45"), Bytes::from_static(b"01")]
+        );
+        let bytes = store.get_range(&location, 0..2).await.unwrap();
+        assert_eq!(bytes, Bytes::from_static(b"01"));
+        assert_eq!(cache.metrics().hits, 3);
+    }
+
+    #[tokio::test]
+    async fn test_get_range_is_cached() {
+        let (inner, cache, store) = setup().await;
+        let location = Path::from("a.parquet");
+
+        let bytes = store.get_range(&location, 2..5).await.unwrap();
+        assert_eq!(bytes, Bytes::from_static(b"234"));
+        assert_eq!(cache.len(), 1);
+
+        inner.delete(&location).await.unwrap();
+        let result = store
+            .get_opts(
+                &location,
+                GetOptions {
+                    range: Some(GetRange::Bounded(2..5)),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.range, 2..5);
+        assert_eq!(result.meta.size, 10);
+        assert_eq!(result.bytes().await.unwrap(), Bytes::from_static(b"234"));
+    }
+
+    #[tokio::test]
+    async fn test_writes_invalidate_cached_ranges() {
+        let (_inner, cache, store) = setup().await;
+        let location = Path::from("a.parquet");
+
+        store.get_range(&location, 0..2).await.unwrap();
+        store
+            .put(&location, Bytes::from_static(b"abcdefghij").into())
+            .await
+            .unwrap();
+        assert!(cache.is_empty());
+        assert_eq!(
+            store.get_range(&location, 0..2).await.unwrap(),
+            Bytes::from_static(b"ab")
+        );
+
+        store.delete(&location).await.unwrap();
+        assert!(cache.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_new_versions_invalidate_cached_ranges() {
+        let (inner, cache, store) = setup().await;
+        let location = Path::from("a.parquet");
+
+        store.get_range(&location, 0..2).await.unwrap();
+        assert_eq!(cache.len(), 1);
+
+        // the object is overwritten behind the back of the caching store, and
+        // the new version is observed when listing it
+        inner
+            .put(&location, Bytes::from_static(b"abcdefghij").into())
+            .await
+            .unwrap();
+        store.list(None).try_collect::<Vec<_>>().await.unwrap();
+        assert!(cache.is_empty());
+        assert_eq!(
+            store.get_range(&location, 0..2).await.unwrap(),
+            Bytes::from_static(b"ab")
+        );
+    }
+}
@@ -19,9 +19,13 @@ pub mod cache_manager;
 pub mod cache_unit;
 pub mod lru_queue;
 
+mod byte_range_cache;
+mod caching_object_store;
 mod file_metadata_cache;
 mod list_files_cache;
 
+pub use byte_range_cache::DefaultByteRangeCache;
+pub use caching_object_store::CachingObjectStore;
 pub use file_metadata_cache::DefaultFilesMetadataCache;
 pub use list_files_cache::DefaultListFilesCache;
 pub use list_files_cache::ListFilesEntry;
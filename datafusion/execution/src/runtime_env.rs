@@ -25,9 +25,10 @@ use crate::{
     memory_pool::{
         GreedyMemoryPool, MemoryPool, TrackConsumersPool, UnboundedMemoryPool,
     },
-    object_store::{DefaultObjectStoreRegistry, ObjectStoreRegistry},
+    object_store::{DefaultObjectStoreRegistry, ObjectStoreRegistry, get_url_key},
 };
 
+use crate::cache::CachingObjectStore;
 use crate::cache::cache_manager::{CacheManager, CacheManagerConfig};
 #[cfg(feature = "parquet_encryption")]
 use crate::parquet_encryption::{EncryptionFactory, EncryptionFactoryRegistry};
@@ -103,6 +104,7 @@ fn create_runtime_config_entries(
     metadata_cache_limit: Option<String>,
     list_files_cache_limit: Option<String>,
     list_files_cache_ttl: Option<String>,
+    byte_range_cache_limit: Option<String>,
 ) -> Vec<ConfigEntry> {
     vec![
         ConfigEntry {
@@ -135,6 +137,11 @@ fn create_runtime_config_entries(
             value: list_files_cache_ttl,
             description: "TTL (time-to-live) of the entries in the list file cache. Supports units m (minutes), and s (seconds). Example: '2m' for 2 minutes.",
         },
+        ConfigEntry {
+            key: "datafusion.runtime.byte_range_cache_limit".to_string(),
+            value: byte_range_cache_limit,
+            description: "Maximum memory to use for caching byte ranges read from object stores, such as Parquet column chunks. The cache is disabled when set to 0. Supports suffixes K (kilobytes), M (megabytes), and G (gigabytes). Example: '2G' for 2 gigabytes.",
+        },
     ]
 }
 
@@ -195,8 +202,18 @@ impl RuntimeEnv {
     /// Retrieves a `ObjectStore` instance for a url by consulting the
     /// registry. See [`ObjectStoreRegistry::get_store`] for more
     /// details.
+    ///
+    /// If the [`CacheManager`] has a byte range cache, the store is wrapped
+    /// in a [`CachingObjectStore`] serving ranged reads from the cache.
     pub fn object_store(&self, url: impl AsRef<Url>) -> Result<Arc<dyn ObjectStore>> {
-        self.object_store_registry.get_store(url.as_ref())
+        let url = url.as_ref();
+        let store = self.object_store_registry.get_store(url)?;
+        Ok(match self.cache_manager.get_byte_range_cache() {
+            Some(cache) => {
+                Arc::new(CachingObjectStore::new(store, cache, get_url_key(url)))
+            }
+            None => store,
+        })
     }
 
     /// Returns the current spilling progress
@@ -296,6 +313,13 @@ impl RuntimeEnv {
             .get_list_files_cache_ttl()
             .map(format_duration);
 
+        let byte_range_cache_limit = self.cache_manager.get_byte_range_cache_limit();
+        let byte_range_cache_value = format_byte_size(
+            byte_range_cache_limit
+                .try_into()
+                .expect("Byte range cache size conversion failed"),
+        );
+
         create_runtime_config_entries(
             memory_limit_value,
             Some(max_temp_dir_value),
@@ -303,6 +327,7 @@ impl RuntimeEnv {
             Some(metadata_cache_value),
             Some(list_files_cache_value),
             list_files_cache_ttl,
+            Some(byte_range_cache_value),
         )
     }
 }
@@ -438,6 +463,13 @@ impl RuntimeEnvBuilder {
         self
     }
 
+    /// Specify the limit of the byte range cache, in bytes. A limit of zero
+    /// disables the cache.
+    pub fn with_byte_range_cache_limit(mut self, limit: usize) -> Self {
+        self.cache_manager = self.cache_manager.with_byte_range_cache_limit(limit);
+        self
+    }
+
     /// Build a RuntimeEnv
     pub fn build(self) -> Result<RuntimeEnv> {
         let Self {
@@ -487,6 +519,13 @@ impl RuntimeEnvBuilder {
                 runtime_env.cache_manager.get_file_metadata_cache(),
             ),
             metadata_cache_limit: runtime_env.cache_manager.get_metadata_cache_limit(),
+            table_statistics_store: Some(
+                runtime_env.cache_manager.get_table_statistics_store(),
+            ),
+            byte_range_cache: runtime_env.cache_manager.get_byte_range_cache(),
+            byte_range_cache_limit: runtime_env
+                .cache_manager
+                .get_byte_range_cache_limit(),
         };
 
         Self {
@@ -514,6 +553,7 @@ impl RuntimeEnvBuilder {
             Some("50M".to_owned()),
             Some("1M".to_owned()),
             None,
+            Some("0".to_owned()),
         )
     }
 
@@ -33,8 +33,8 @@ use crate::expr_rewriter::{
 use crate::logical_plan::{
     Aggregate, Analyze, Distinct, DistinctOn, EmptyRelation, Explain, Filter, Join,
     JoinConstraint, JoinType, Limit, LogicalPlan, Partitioning, PlanType, Prepare,
-    Projection, Repartition, Sort, SubqueryAlias, TableScan, Union, Unnest, Values,
-    Window,
+    Projection, Repartition, Sample, Sort, SubqueryAlias, TableScan, Union, Unnest,
+    Values, Window,
 };
 use crate::select_expr::SelectExpr;
 use crate::utils::{
@@ -54,8 +54,8 @@ use datafusion_common::display::ToStringifiedPlan;
 use datafusion_common::file_options::file_type::FileType;
 use datafusion_common::metadata::FieldMetadata;
 use datafusion_common::{
-    Column, Constraints, DFSchema, DFSchemaRef, NullEquality, Result, ScalarValue,
-    TableReference, ToDFSchema, UnnestOptions, exec_err,
+    Column, Constraints, DFSchema, DFSchemaRef, NullEquality, Result, SampleMethod,
+    ScalarValue, TableReference, ToDFSchema, UnnestOptions, exec_err,
     get_target_functional_dependencies, internal_datafusion_err, plan_datafusion_err,
     plan_err,
 };
@@ -1267,6 +1267,19 @@ impl LogicalPlanBuilder {
         })))
     }
 
+    /// Randomly sample `fraction` of the rows using the given method,
+    /// optionally with a fixed `seed` for reproducible samples
+    pub fn sample(
+        self,
+        method: SampleMethod,
+        fraction: f64,
+        seed: Option<u64>,
+    ) -> Result<Self> {
+        Sample::try_new(self.plan, method, fraction, seed)
+            .map(LogicalPlan::Sample)
+            .map(Self::new)
+    }
+
     /// Apply a window functions to extend the schema
     pub fn window(
         self,
@@ -1690,7 +1703,7 @@ pub fn build_join_schema(
                 .collect::<Vec<_>>();
             left_fields.into_iter().chain(right_fields).collect()
         }
-        JoinType::Left => {
+        JoinType::Left | JoinType::LeftAsOf => {
             // left then right, right set to nullable in case of not matched scenario
             let left_fields = left_fields
                 .map(|(q, f)| (q.cloned(), Arc::clone(f)))
@@ -60,6 +60,10 @@ pub enum DdlStatement {
     CreateFunction(CreateFunction),
     /// Drop function statement
     DropFunction(DropFunction),
+    /// Collects statistics for a table.
+    AnalyzeTable(AnalyzeTable),
+    /// Recomputes the contents of a materialized view.
+    RefreshMaterializedView(RefreshMaterializedView),
 }
 
 impl DdlStatement {
@@ -81,6 +85,11 @@ impl DdlStatement {
             DdlStatement::DropCatalogSchema(DropCatalogSchema { schema, .. }) => schema,
             DdlStatement::CreateFunction(CreateFunction { schema, .. }) => schema,
             DdlStatement::DropFunction(DropFunction { schema, .. }) => schema,
+            DdlStatement::AnalyzeTable(AnalyzeTable { schema, .. }) => schema,
+            DdlStatement::RefreshMaterializedView(RefreshMaterializedView {
+                schema,
+                ..
+            }) => schema,
         }
     }
 
@@ -99,6 +108,8 @@ impl DdlStatement {
             DdlStatement::DropCatalogSchema(_) => "DropCatalogSchema",
             DdlStatement::CreateFunction(_) => "CreateFunction",
             DdlStatement::DropFunction(_) => "DropFunction",
+            DdlStatement::AnalyzeTable(_) => "AnalyzeTable",
+            DdlStatement::RefreshMaterializedView(_) => "RefreshMaterializedView",
         }
     }
 
@@ -118,6 +129,8 @@ impl DdlStatement {
             DdlStatement::DropCatalogSchema(_) => vec![],
             DdlStatement::CreateFunction(_) => vec![],
             DdlStatement::DropFunction(_) => vec![],
+            DdlStatement::AnalyzeTable(_) => vec![],
+            DdlStatement::RefreshMaterializedView(_) => vec![],
         }
     }
 
@@ -153,8 +166,14 @@ impl DdlStatement {
                             write!(f, "CreateMemoryTable: {name:?} {constraints}")
                         }
                     }
-                    DdlStatement::CreateView(CreateView { name, .. }) => {
-                        write!(f, "CreateView: {name:?}")
+                    DdlStatement::CreateView(CreateView {
+                        name, materialized, ..
+                    }) => {
+                        if *materialized {
+                            write!(f, "CreateMaterializedView: {name:?}")
+                        } else {
+                            write!(f, "CreateView: {name:?}")
+                        }
                     }
                     DdlStatement::CreateCatalogSchema(CreateCatalogSchema {
                         schema_name,
@@ -197,6 +216,22 @@ impl DdlStatement {
                     DdlStatement::DropFunction(DropFunction { name, .. }) => {
                         write!(f, "DropFunction: name {name:?}")
                     }
+                    DdlStatement::AnalyzeTable(AnalyzeTable {
+                        name, columns, ..
+                    }) => match columns {
+                        Some(columns) => write!(
+                            f,
+                            "AnalyzeTable: {name:?} columns:=[{}]",
+                            columns.join(", ")
+                        ),
+                        None => write!(f, "AnalyzeTable: {name:?}"),
+                    },
+                    DdlStatement::RefreshMaterializedView(RefreshMaterializedView {
+                        name,
+                        ..
+                    }) => {
+                        write!(f, "RefreshMaterializedView: {name:?}")
+                    }
                 }
             }
         }
@@ -491,6 +526,9 @@ pub struct CreateView {
     pub definition: Option<String>,
     /// Whether the view is ephemeral
     pub temporary: bool,
+    /// Whether the results of the view are stored, see
+    /// [`DdlStatement::RefreshMaterializedView`]
+    pub materialized: bool,
 }
 
 /// Creates a catalog (aka "Database").
@@ -632,6 +670,8 @@ pub struct CreateFunction {
     pub name: String,
     pub args: Option<Vec<OperateFunctionArg>>,
     pub return_type: Option<DataType>,
+    /// Whether the function returns a table (`RETURNS TABLE`)
+    pub returns_table: bool,
     pub params: CreateFunctionBody,
     /// Dummy schema
     pub schema: DFSchemaRef,
@@ -647,6 +687,7 @@ impl PartialOrd for CreateFunction {
             pub name: &'a String,
             pub args: &'a Option<Vec<OperateFunctionArg>>,
             pub return_type: &'a Option<DataType>,
+            pub returns_table: &'a bool,
             pub params: &'a CreateFunctionBody,
         }
         let comparable_self = ComparableCreateFunction {
@@ -655,6 +696,7 @@ impl PartialOrd for CreateFunction {
             name: &self.name,
             args: &self.args,
             return_type: &self.return_type,
+            returns_table: &self.returns_table,
             params: &self.params,
         };
         let comparable_other = ComparableCreateFunction {
@@ -663,6 +705,7 @@ impl PartialOrd for CreateFunction {
             name: &other.name,
             args: &other.args,
             return_type: &other.return_type,
+            returns_table: &other.returns_table,
             params: &other.params,
         };
         comparable_self
@@ -759,6 +802,50 @@ impl PartialOrd for DropFunction {
     }
 }
 
+/// Collects statistics for a table, e.g.
+/// `ANALYZE TABLE t COMPUTE STATISTICS FOR COLUMNS a, b`
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct AnalyzeTable {
+    /// The table name
+    pub name: TableReference,
+    /// The columns to collect statistics for, or `None` for all columns
+    pub columns: Option<Vec<String>>,
+    /// Dummy schema
+    pub schema: DFSchemaRef,
+}
+
+// Manual implementation needed because of `schema` field. Comparison excludes this field.
+impl PartialOrd for AnalyzeTable {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match self.name.partial_cmp(&other.name) {
+            Some(Ordering::Equal) => self.columns.partial_cmp(&other.columns),
+            cmp => cmp,
+        }
+        // TODO (https://github.com/apache/datafusion/issues/17477) avoid recomparing all fields
+        .filter(|cmp| *cmp != Ordering::Equal || self == other)
+    }
+}
+
+/// Recomputes the stored results of a materialized view, e.g.
+/// `REFRESH MATERIALIZED VIEW v`
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct RefreshMaterializedView {
+    /// The view name
+    pub name: TableReference,
+    /// Dummy schema
+    pub schema: DFSchemaRef,
+}
+
+// Manual implementation needed because of `schema` field. Comparison excludes this field.
+impl PartialOrd for RefreshMaterializedView {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.name
+            .partial_cmp(&other.name)
+            // TODO (https://github.com/apache/datafusion/issues/17477) avoid recomparing all fields
+            .filter(|cmp| *cmp != Ordering::Equal || self == other)
+    }
+}
+
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub struct CreateIndex {
     pub name: Option<String>,
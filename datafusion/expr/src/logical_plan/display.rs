@@ -22,9 +22,9 @@ use std::fmt;
 
 use crate::{
     Aggregate, DescribeTable, Distinct, DistinctOn, DmlStatement, Expr, Filter, Join,
-    Limit, LogicalPlan, Partitioning, Projection, RecursiveQuery, Repartition, Sort,
-    Subquery, SubqueryAlias, TableProviderFilterPushDown, TableScan, Unnest, Values,
-    Window, expr_vec_fmt,
+    Limit, LogicalPlan, Partitioning, Projection, RecursiveQuery, Repartition, Sample,
+    Sort, Subquery, SubqueryAlias, TableProviderFilterPushDown, TableScan, Unnest,
+    Values, Window, expr_vec_fmt,
 };
 
 use crate::dml::CopyTo;
@@ -493,6 +493,19 @@ impl<'a, 'b> PgJsonVisitor<'a, 'b> {
                     "Filter": format!("{}", filter_expr)
                 })
             }
+            LogicalPlan::Sample(Sample {
+                method,
+                fraction,
+                seed,
+                ..
+            }) => {
+                json!({
+                    "Node Type": "Sample",
+                    "Method": method.to_string(),
+                    "Fraction": fraction,
+                    "Seed": seed
+                })
+            }
             LogicalPlan::Repartition(Repartition {
                 partitioning_scheme,
                 ..
@@ -23,9 +23,9 @@ use std::sync::Arc;
 
 use arrow::datatypes::{DataType, Field, Schema};
 use datafusion_common::file_options::file_type::FileType;
-use datafusion_common::{DFSchemaRef, TableReference};
+use datafusion_common::{DFSchema, DFSchemaRef, Result, TableReference};
 
-use crate::{LogicalPlan, TableSource};
+use crate::{Expr, LogicalPlan, TableSource};
 
 /// Operator that copies the contents of a database to file(s)
 #[derive(Clone)]
@@ -130,12 +130,16 @@ impl CopyTo {
 ///   from a query. This is similar to the `INSERT` operation, but it creates a new
 ///   table instead of modifying an existing one.
 ///
+/// * `MERGE INTO` - Inserts, updates and deletes rows of the table depending on
+///   whether they match the rows of a source relation. Calls [`TableProvider::merge_into`]
+///
 /// Note that the structure is adapted from substrait WriteRel)
 ///
 /// [`TableProvider`]: https://docs.rs/datafusion/latest/datafusion/datasource/trait.TableProvider.html
 /// [`TableProvider::insert_into`]: https://docs.rs/datafusion/latest/datafusion/datasource/trait.TableProvider.html#method.insert_into
 /// [`TableProvider::delete_from`]: https://docs.rs/datafusion/latest/datafusion/datasource/trait.TableProvider.html#method.delete_from
 /// [`TableProvider::update`]: https://docs.rs/datafusion/latest/datafusion/datasource/trait.TableProvider.html#method.update
+/// [`TableProvider::merge_into`]: https://docs.rs/datafusion/latest/datafusion/datasource/trait.TableProvider.html#method.merge_into
 #[derive(Clone)]
 pub struct DmlStatement {
     /// The table name
@@ -239,6 +243,9 @@ pub enum WriteOp {
     Ctas,
     /// `TRUNCATE` operation
     Truncate,
+    /// `MERGE INTO` operation. The input of the [`DmlStatement`] is the
+    /// source relation of the merge.
+    Merge(Box<MergeInto>),
 }
 
 impl WriteOp {
@@ -250,6 +257,7 @@ impl WriteOp {
             WriteOp::Update => "Update",
             WriteOp::Ctas => "Ctas",
             WriteOp::Truncate => "Truncate",
+            WriteOp::Merge(_) => "Merge",
         }
     }
 }
@@ -291,6 +299,136 @@ impl Display for InsertOp {
     }
 }
 
+/// The `ON` condition and `WHEN` clauses of a `MERGE INTO` statement.
+///
+/// The expressions refer to the columns of the target table, qualified with
+/// `target_alias`, and to the columns of the source relation. See
+/// [`MergeInto::schema`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Hash)]
+pub struct MergeInto {
+    /// The qualifier of the target table columns: the alias of the target
+    /// table, or its name if it has no alias
+    pub target_alias: TableReference,
+    /// The condition matching rows of the target table with rows of the source
+    pub on: Expr,
+    /// The `WHEN` clauses, in the order they are evaluated. Each row is
+    /// handled by the first clause that applies to it.
+    pub clauses: Vec<MergeClause>,
+}
+
+impl MergeInto {
+    /// Returns the schema the expressions of this statement are resolved
+    /// against: the columns of the target table followed by the columns of the
+    /// source relation.
+    pub fn schema(
+        &self,
+        target_schema: &Schema,
+        source_schema: &DFSchema,
+    ) -> Result<DFSchema> {
+        DFSchema::try_from_qualified_schema(self.target_alias.clone(), target_schema)?
+            .join(source_schema)
+    }
+}
+
+impl Display for MergeInto {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "on=[{}] clauses=[", self.on)?;
+        for (i, clause) in self.clauses.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{clause}")?;
+        }
+        write!(f, "]")
+    }
+}
+
+/// A `WHEN [NOT] MATCHED [AND <predicate>] THEN <action>` clause of a
+/// `MERGE INTO` statement.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Hash)]
+pub struct MergeClause {
+    /// The rows the clause applies to
+    pub kind: MergeClauseKind,
+    /// An additional condition the rows must satisfy
+    pub predicate: Option<Expr>,
+    /// What to do with the rows the clause applies to
+    pub action: MergeAction,
+}
+
+impl Display for MergeClause {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "WHEN {}", self.kind)?;
+        if let Some(predicate) = &self.predicate {
+            write!(f, " AND {predicate}")?;
+        }
+        write!(f, " THEN {}", self.action)
+    }
+}
+
+/// The rows a [`MergeClause`] applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Hash)]
+pub enum MergeClauseKind {
+    /// Target rows matching a source row (`WHEN MATCHED`)
+    Matched,
+    /// Source rows not matching any target row (`WHEN NOT MATCHED [BY TARGET]`).
+    /// The target columns are NULL for these rows.
+    NotMatchedByTarget,
+    /// Target rows not matching any source row (`WHEN NOT MATCHED BY SOURCE`).
+    /// The source columns are NULL for these rows.
+    NotMatchedBySource,
+}
+
+impl Display for MergeClauseKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            MergeClauseKind::Matched => write!(f, "MATCHED"),
+            MergeClauseKind::NotMatchedByTarget => write!(f, "NOT MATCHED BY TARGET"),
+            MergeClauseKind::NotMatchedBySource => write!(f, "NOT MATCHED BY SOURCE"),
+        }
+    }
+}
+
+/// The action of a [`MergeClause`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Hash)]
+pub enum MergeAction {
+    /// Update the target row. Holds the name and the new value of each
+    /// assigned column; the values are cast to the column types.
+    Update(Vec<(String, Expr)>),
+    /// Insert a row into the target table. Holds one value per column of the
+    /// target table, cast to the column type.
+    Insert(Vec<Expr>),
+    /// Delete the target row
+    Delete,
+}
+
+impl Display for MergeAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            MergeAction::Update(assignments) => {
+                write!(f, "UPDATE SET ")?;
+                for (i, (column, value)) in assignments.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{column} = {value}")?;
+                }
+                Ok(())
+            }
+            MergeAction::Insert(values) => {
+                write!(f, "INSERT VALUES (")?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{value}")?;
+                }
+                write!(f, ")")
+            }
+            MergeAction::Delete => write!(f, "DELETE"),
+        }
+    }
+}
+
 fn make_count_schema() -> DFSchemaRef {
     Arc::new(
         Schema::new(vec![Field::new("count", DataType::UInt64, false)])
@@ -32,16 +32,17 @@ pub use builder::{
     wrap_projection_for_join_if_necessary,
 };
 pub use ddl::{
-    CreateCatalog, CreateCatalogSchema, CreateExternalTable, CreateFunction,
-    CreateFunctionBody, CreateIndex, CreateMemoryTable, CreateView, DdlStatement,
-    DropCatalogSchema, DropFunction, DropTable, DropView, OperateFunctionArg,
+    AnalyzeTable, CreateCatalog, CreateCatalogSchema, CreateExternalTable,
+    CreateFunction, CreateFunctionBody, CreateIndex, CreateMemoryTable, CreateView,
+    DdlStatement, DropCatalogSchema, DropFunction, DropTable, DropView,
+    OperateFunctionArg, RefreshMaterializedView,
 };
 pub use dml::{DmlStatement, WriteOp};
 pub use plan::{
     Aggregate, Analyze, ColumnUnnestList, DescribeTable, Distinct, DistinctOn,
     EmptyRelation, Explain, ExplainOption, Extension, FetchType, Filter, Join,
     JoinConstraint, JoinType, Limit, LogicalPlan, Partitioning, PlanType, Projection,
-    RecursiveQuery, Repartition, SkipType, Sort, StringifiedPlan, Subquery,
+    RecursiveQuery, Repartition, Sample, SkipType, Sort, StringifiedPlan, Subquery,
     SubqueryAlias, TableScan, ToStringifiedPlan, Union, Unnest, Values, Window,
     projection_schema,
 };
@@ -39,7 +39,7 @@ use crate::expr_rewriter::{
 };
 use crate::logical_plan::display::{GraphvizVisitor, IndentVisitor};
 use crate::logical_plan::extension::UserDefinedLogicalNode;
-use crate::logical_plan::{DmlStatement, Statement};
+use crate::logical_plan::{DmlStatement, Statement, WriteOp};
 use crate::utils::{
     enumerate_grouping_sets, exprlist_to_fields, find_out_reference_exprs,
     grouping_set_expr_count, grouping_set_to_exprlist, split_conjunction,
@@ -60,8 +60,9 @@ use datafusion_common::tree_node::{
 use datafusion_common::{
     Column, Constraints, DFSchema, DFSchemaRef, DataFusionError, Dependency,
     FunctionalDependence, FunctionalDependencies, NullEquality, ParamValues, Result,
-    ScalarValue, Spans, TableReference, UnnestOptions, aggregate_functional_dependencies,
-    assert_eq_or_internal_err, assert_or_internal_err, internal_err, plan_err,
+    SampleMethod, ScalarValue, Spans, TableReference, UnnestOptions,
+    aggregate_functional_dependencies, assert_eq_or_internal_err, assert_or_internal_err,
+    internal_err, plan_err,
 };
 use indexmap::IndexSet;
 
@@ -238,6 +239,9 @@ pub enum LogicalPlan {
     /// used to add parallelism and is sometimes referred to as an
     /// "exchange" operator in other systems
     Repartition(Repartition),
+    /// Randomly samples the rows of the input. This is used to implement
+    /// SQL `TABLESAMPLE`.
+    Sample(Sample),
     /// Union multiple inputs with the same schema into a single
     /// output stream. This is used to implement SQL `UNION [ALL]` and
     /// `INTERSECT [ALL]`.
@@ -338,6 +342,7 @@ impl LogicalPlan {
             LogicalPlan::Sort(Sort { input, .. }) => input.schema(),
             LogicalPlan::Join(Join { schema, .. }) => schema,
             LogicalPlan::Repartition(Repartition { input, .. }) => input.schema(),
+            LogicalPlan::Sample(Sample { input, .. }) => input.schema(),
             LogicalPlan::Limit(Limit { input, .. }) => input.schema(),
             LogicalPlan::Statement(statement) => statement.schema(),
             LogicalPlan::Subquery(Subquery { subquery, .. }) => subquery.schema(),
@@ -454,6 +459,7 @@ impl LogicalPlan {
             LogicalPlan::Projection(Projection { input, .. }) => vec![input],
             LogicalPlan::Filter(Filter { input, .. }) => vec![input],
             LogicalPlan::Repartition(Repartition { input, .. }) => vec![input],
+            LogicalPlan::Sample(Sample { input, .. }) => vec![input],
             LogicalPlan::Window(Window { input, .. }) => vec![input],
             LogicalPlan::Aggregate(Aggregate { input, .. }) => vec![input],
             LogicalPlan::Sort(Sort { input, .. }) => vec![input],
@@ -545,6 +551,7 @@ impl LogicalPlan {
             | LogicalPlan::Sort(Sort { input, .. })
             | LogicalPlan::Limit(Limit { input, .. })
             | LogicalPlan::Repartition(Repartition { input, .. })
+            | LogicalPlan::Sample(Sample { input, .. })
             | LogicalPlan::Window(Window { input, .. }) => input.head_output_expr(),
             LogicalPlan::Join(Join {
                 left,
@@ -559,9 +566,10 @@ impl LogicalPlan {
                         left.head_output_expr()
                     }
                 }
-                JoinType::LeftSemi | JoinType::LeftAnti | JoinType::LeftMark => {
-                    left.head_output_expr()
-                }
+                JoinType::LeftSemi
+                | JoinType::LeftAnti
+                | JoinType::LeftMark
+                | JoinType::LeftAsOf => left.head_output_expr(),
                 JoinType::RightSemi | JoinType::RightAnti | JoinType::RightMark => {
                     right.head_output_expr()
                 }
@@ -642,6 +650,7 @@ impl LogicalPlan {
                 Filter::try_new(predicate, input).map(LogicalPlan::Filter)
             }
             LogicalPlan::Repartition(_) => Ok(self),
+            LogicalPlan::Sample(_) => Ok(self),
             LogicalPlan::Window(Window {
                 input,
                 window_expr,
@@ -845,6 +854,21 @@ impl LogicalPlan {
 
                 Filter::try_new(predicate, Arc::new(input)).map(LogicalPlan::Filter)
             }
+            LogicalPlan::Sample(Sample {
+                method,
+                fraction,
+                seed,
+                ..
+            }) => {
+                self.assert_no_expressions(expr)?;
+                let input = self.only_input(inputs)?;
+                Ok(LogicalPlan::Sample(Sample {
+                    input: Arc::new(input),
+                    method: *method,
+                    fraction: *fraction,
+                    seed: *seed,
+                }))
+            }
             LogicalPlan::Repartition(Repartition {
                 partitioning_scheme,
                 ..
@@ -1017,6 +1041,7 @@ impl LogicalPlan {
                 or_replace,
                 definition,
                 temporary,
+                materialized,
                 ..
             })) => {
                 self.assert_no_expressions(expr)?;
@@ -1027,6 +1052,7 @@ impl LogicalPlan {
                     or_replace: *or_replace,
                     temporary: *temporary,
                     definition: definition.clone(),
+                    materialized: *materialized,
                 })))
             }
             LogicalPlan::Extension(e) => Ok(LogicalPlan::Extension(Extension {
@@ -1356,14 +1382,16 @@ impl LogicalPlan {
                         (left_max, right_max, _) => Some(left_max * right_max),
                     }
                 }
-                JoinType::LeftSemi | JoinType::LeftAnti | JoinType::LeftMark => {
-                    left.max_rows()
-                }
+                JoinType::LeftSemi
+                | JoinType::LeftAnti
+                | JoinType::LeftMark
+                | JoinType::LeftAsOf => left.max_rows(),
                 JoinType::RightSemi | JoinType::RightAnti | JoinType::RightMark => {
                     right.max_rows()
                 }
             },
             LogicalPlan::Repartition(Repartition { input, .. }) => input.max_rows(),
+            LogicalPlan::Sample(Sample { input, .. }) => input.max_rows(),
             LogicalPlan::Union(Union { inputs, .. }) => {
                 inputs.iter().try_fold(0usize, |mut acc, plan| {
                     acc += plan.max_rows()?;
@@ -1414,6 +1442,7 @@ impl LogicalPlan {
             LogicalPlan::Aggregate(_) => Ok(None),
             LogicalPlan::Join(_) => Ok(None),
             LogicalPlan::Repartition(_) => Ok(None),
+            LogicalPlan::Sample(_) => Ok(None),
             LogicalPlan::Union(_) => Ok(None),
             LogicalPlan::EmptyRelation(_) => Ok(None),
             LogicalPlan::Subquery(_) => Ok(None),
@@ -1452,6 +1481,7 @@ impl LogicalPlan {
             LogicalPlan::Aggregate(_) => Ok(None),
             LogicalPlan::Join(_) => Ok(None),
             LogicalPlan::Repartition(_) => Ok(None),
+            LogicalPlan::Sample(_) => Ok(None),
             LogicalPlan::Union(_) => Ok(None),
             LogicalPlan::EmptyRelation(_) => Ok(None),
             LogicalPlan::Subquery(_) => Ok(None),
@@ -1971,7 +2001,11 @@ impl LogicalPlan {
                         Ok(())
                     }
                     LogicalPlan::Dml(DmlStatement { table_name, op, .. }) => {
-                        write!(f, "Dml: op=[{op}] table=[{table_name}]")
+                        write!(f, "Dml: op=[{op}] table=[{table_name}]")?;
+                        if let WriteOp::Merge(merge) = op {
+                            write!(f, " {merge}")?;
+                        }
+                        Ok(())
                     }
                     LogicalPlan::Copy(CopyTo {
                         input: _,
@@ -2074,6 +2108,18 @@ impl LogicalPlan {
                             }
                         }
                     }
+                    LogicalPlan::Sample(Sample {
+                        method,
+                        fraction,
+                        seed,
+                        ..
+                    }) => {
+                        write!(f, "Sample: method={method}, fraction={fraction}")?;
+                        if let Some(seed) = seed {
+                            write!(f, ", seed={seed}")?;
+                        }
+                        Ok(())
+                    }
                     LogicalPlan::Repartition(Repartition {
                         partitioning_scheme,
                         ..
@@ -2903,6 +2949,80 @@ pub struct Repartition {
     pub partitioning_scheme: Partitioning,
 }
 
+/// Randomly samples a fraction of the rows of its input.
+///
+/// This is used to implement SQL `TABLESAMPLE`.
+#[derive(Debug, Clone)]
+pub struct Sample {
+    /// The incoming logical plan
+    pub input: Arc<LogicalPlan>,
+    /// How rows are selected
+    pub method: SampleMethod,
+    /// Probability of each row to be selected, between 0.0 and 1.0
+    pub fraction: f64,
+    /// Seed of the random number generator. The same seed selects the
+    /// same rows of the same input, `None` uses a random seed.
+    pub seed: Option<u64>,
+}
+
+impl Sample {
+    /// Create a new sample node, validating that `fraction` is a probability
+    pub fn try_new(
+        input: Arc<LogicalPlan>,
+        method: SampleMethod,
+        fraction: f64,
+        seed: Option<u64>,
+    ) -> Result<Self> {
+        if !(0.0..=1.0).contains(&fraction) {
+            return plan_err!("Sample fraction must be between 0 and 1, got {fraction}");
+        }
+        Ok(Self {
+            input,
+            method,
+            fraction,
+            seed,
+        })
+    }
+}
+
+// Manual implementations needed because of the `f64` fraction
+impl PartialEq for Sample {
+    fn eq(&self, other: &Self) -> bool {
+        self.input == other.input
+            && self.method == other.method
+            && self.fraction.to_bits() == other.fraction.to_bits()
+            && self.seed == other.seed
+    }
+}
+
+impl Eq for Sample {}
+
+impl Hash for Sample {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.input.hash(state);
+        self.method.hash(state);
+        self.fraction.to_bits().hash(state);
+        self.seed.hash(state);
+    }
+}
+
+impl PartialOrd for Sample {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match self.input.partial_cmp(&other.input) {
+            Some(Ordering::Equal) => {}
+            cmp => return cmp,
+        }
+        match self.method.partial_cmp(&other.method) {
+            Some(Ordering::Equal) => {}
+            cmp => return cmp,
+        }
+        match self.fraction.total_cmp(&other.fraction) {
+            Ordering::Equal => self.seed.partial_cmp(&other.seed),
+            cmp => Some(cmp),
+        }
+    }
+}
+
 /// Union multiple inputs
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Union {
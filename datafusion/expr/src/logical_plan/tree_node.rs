@@ -40,9 +40,9 @@
 use crate::{
     Aggregate, Analyze, CreateMemoryTable, CreateView, DdlStatement, Distinct,
     DistinctOn, DmlStatement, Execute, Explain, Expr, Extension, Filter, Join, Limit,
-    LogicalPlan, Partitioning, Prepare, Projection, RecursiveQuery, Repartition, Sort,
-    Statement, Subquery, SubqueryAlias, TableScan, Union, Unnest, UserDefinedLogicalNode,
-    Values, Window, dml::CopyTo,
+    LogicalPlan, Partitioning, Prepare, Projection, RecursiveQuery, Repartition, Sample,
+    Sort, Statement, Subquery, SubqueryAlias, TableScan, Union, Unnest,
+    UserDefinedLogicalNode, Values, Window, dml::CopyTo,
 };
 use datafusion_common::tree_node::TreeNodeRefContainer;
 
@@ -150,6 +150,19 @@ impl TreeNode for LogicalPlan {
             LogicalPlan::Limit(Limit { skip, fetch, input }) => input
                 .map_elements(f)?
                 .update_data(|input| LogicalPlan::Limit(Limit { skip, fetch, input })),
+            LogicalPlan::Sample(Sample {
+                input,
+                method,
+                fraction,
+                seed,
+            }) => input.map_elements(f)?.update_data(|input| {
+                LogicalPlan::Sample(Sample {
+                    input,
+                    method,
+                    fraction,
+                    seed,
+                })
+            }),
             LogicalPlan::Subquery(Subquery {
                 subquery,
                 outer_ref_columns,
@@ -283,6 +296,7 @@ impl TreeNode for LogicalPlan {
                         or_replace,
                         definition,
                         temporary,
+                        materialized,
                     }) => input.map_elements(f)?.update_data(|input| {
                         DdlStatement::CreateView(CreateView {
                             name,
@@ -290,6 +304,7 @@ impl TreeNode for LogicalPlan {
                             or_replace,
                             definition,
                             temporary,
+                            materialized,
                         })
                     }),
                     // no inputs in these statements
@@ -301,7 +316,9 @@ impl TreeNode for LogicalPlan {
                     | DdlStatement::DropView(_)
                     | DdlStatement::DropCatalogSchema(_)
                     | DdlStatement::CreateFunction(_)
-                    | DdlStatement::DropFunction(_) => Transformed::no(ddl),
+                    | DdlStatement::DropFunction(_)
+                    | DdlStatement::AnalyzeTable(_)
+                    | DdlStatement::RefreshMaterializedView(_) => Transformed::no(ddl),
                 }
                 .update_data(LogicalPlan::Ddl)
             }
@@ -472,6 +489,7 @@ impl LogicalPlan {
             | LogicalPlan::Explain(_)
             | LogicalPlan::Union(_)
             | LogicalPlan::Distinct(Distinct::All(_))
+            | LogicalPlan::Sample(_)
             | LogicalPlan::Dml(_)
             | LogicalPlan::Ddl(_)
             | LogicalPlan::Copy(_)
@@ -666,6 +684,7 @@ impl LogicalPlan {
             | LogicalPlan::Explain(_)
             | LogicalPlan::Union(_)
             | LogicalPlan::Distinct(Distinct::All(_))
+            | LogicalPlan::Sample(_)
             | LogicalPlan::Dml(_)
             | LogicalPlan::Ddl(_)
             | LogicalPlan::Copy(_)
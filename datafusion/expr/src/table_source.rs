@@ -20,7 +20,7 @@
 use crate::{Expr, LogicalPlan};
 
 use arrow::datatypes::SchemaRef;
-use datafusion_common::{Constraints, Result};
+use datafusion_common::{Constraints, Result, Statistics};
 
 use std::{any::Any, borrow::Cow};
 
@@ -127,6 +127,14 @@ pub trait TableSource: Any + Sync + Send {
     fn get_column_default(&self, _column: &str) -> Option<&Expr> {
         None
     }
+
+    /// Get statistics for this table, if available.
+    ///
+    /// Optimizer rules may use these estimates to decide whether a rewrite
+    /// is beneficial.
+    fn statistics(&self) -> Option<Statistics> {
+        None
+    }
 }
 
 impl dyn TableSource {
@@ -260,6 +260,7 @@ fn table_options_to_rhash(mut options: TableOptions) -> SVec<(SString, SString)>
                 ConfigFileType::JSON => "json",
                 ConfigFileType::PARQUET => "parquet",
                 ConfigFileType::CSV => "csv",
+                ConfigFileType::AVRO => "avro",
             }
             .into(),
         );
@@ -477,6 +478,7 @@ fn table_options_from_rhashmap(options: SVec<(SString, SString)>) -> TableOption
         ConfigFileType::CSV,
         ConfigFileType::JSON,
         ConfigFileType::PARQUET,
+        ConfigFileType::AVRO,
     ];
     for format in formats {
         // It is imperative that if new enum variants are added below that they be
@@ -485,6 +487,7 @@ fn table_options_from_rhashmap(options: SVec<(SString, SString)>) -> TableOption
             ConfigFileType::CSV => "csv",
             ConfigFileType::PARQUET => "parquet",
             ConfigFileType::JSON => "json",
+            ConfigFileType::AVRO => "avro",
         };
         let format_options: HashMap<String, String> = options
             .iter()
@@ -509,7 +512,7 @@ fn table_options_from_rhashmap(options: SVec<(SString, SString)>) -> TableOption
         .iter()
         .filter_map(|(k, v)| {
             let (prefix, _) = k.split_once(".")?;
-            if !["json", "parquet", "csv"].contains(&prefix) {
+            if !["json", "parquet", "csv", "avro"].contains(&prefix) {
                 Some((k.to_owned(), v.to_owned()))
             } else {
                 None
@@ -527,6 +530,7 @@ fn table_options_from_rhashmap(options: SVec<(SString, SString)>) -> TableOption
             "csv" => Some(ConfigFileType::CSV),
             "parquet" => Some(ConfigFileType::PARQUET),
             "json" => Some(ConfigFileType::JSON),
+            "avro" => Some(ConfigFileType::AVRO),
             _ => None,
         });
     table_options
@@ -31,13 +31,17 @@
 
 use arrow::datatypes::DataType;
 use arrow::datatypes::Float64Type;
-use datafusion_common::ScalarValue;
 use datafusion_common::cast::as_primitive_array;
+use datafusion_common::{Result, ScalarValue, exec_err};
 use std::cmp::Ordering;
 use std::mem::{size_of, size_of_val};
 
 pub const DEFAULT_MAX_SIZE: usize = 100;
 
+/// The largest `max_size` of a sketch created by `tdigest_sketch` or read by
+/// [`TDigest::try_from_bytes`]
+pub const MAX_SKETCH_MAX_SIZE: usize = 100_000;
+
 // Cast a non-null [`ScalarValue::Float64`] to an [`f64`], or
 // panic.
 macro_rules! cast_scalar_f64 {
@@ -611,8 +615,119 @@ impl TDigest {
             centroids,
         }
     }
+
+    /// Serialize this [`TDigest`] to a stable binary format that can be
+    /// persisted and later restored with [`Self::try_from_bytes()`].
+    ///
+    /// The format consists of the magic bytes `TDG`, a format version byte
+    /// (currently `1`), followed by these little-endian fields:
+    ///
+    /// ```text
+    /// max_size: u64, sum: f64, count: f64, max: f64, min: f64,
+    /// num_centroids: u64, (mean: f64, weight: f64) * num_centroids
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(
+            TDIGEST_HEADER_LEN + TDIGEST_FIELDS_LEN + 16 * self.centroids.len(),
+        );
+        bytes.extend_from_slice(TDIGEST_MAGIC);
+        bytes.push(TDIGEST_FORMAT_VERSION);
+        bytes.extend_from_slice(&(self.max_size as u64).to_le_bytes());
+        for v in [self.sum, self.count, self.max, self.min] {
+            bytes.extend_from_slice(&v.to_le_bytes());
+        }
+        bytes.extend_from_slice(&(self.centroids.len() as u64).to_le_bytes());
+        for c in &self.centroids {
+            bytes.extend_from_slice(&c.mean.to_le_bytes());
+            bytes.extend_from_slice(&c.weight.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Deserialize a [`TDigest`] produced by [`Self::to_bytes()`].
+    ///
+    /// Unlike [`Self::from_scalar_state()`] the input is validated, so this
+    /// can be used on data read from external storage.
+    pub fn try_from_bytes(bytes: &[u8]) -> Result<Self> {
+        let Some(body) = bytes
+            .strip_prefix(TDIGEST_MAGIC.as_slice())
+            .and_then(|b| b.strip_prefix(&[TDIGEST_FORMAT_VERSION]))
+        else {
+            return exec_err!("Invalid t-digest sketch: unknown header");
+        };
+        if body.len() < TDIGEST_FIELDS_LEN {
+            return exec_err!(
+                "Invalid t-digest sketch: expected at least {} bytes, got {}",
+                TDIGEST_HEADER_LEN + TDIGEST_FIELDS_LEN,
+                bytes.len()
+            );
+        }
+
+        let mut words = body
+            .chunks_exact(8)
+            .map(|chunk| <[u8; 8]>::try_from(chunk).unwrap());
+        let mut next_u64 = || u64::from_le_bytes(words.next().unwrap());
+
+        let max_size = next_u64() as usize;
+        let sum = f64::from_bits(next_u64());
+        let count = f64::from_bits(next_u64());
+        let max = f64::from_bits(next_u64());
+        let min = f64::from_bits(next_u64());
+        let num_centroids = next_u64() as usize;
+
+        let expected_len = num_centroids
+            .checked_mul(16)
+            .and_then(|len| len.checked_add(TDIGEST_FIELDS_LEN));
+        if expected_len != Some(body.len()) {
+            return exec_err!(
+                "Invalid t-digest sketch: {num_centroids} centroids do not fit in {} bytes",
+                bytes.len()
+            );
+        }
+        if max_size == 0 || max_size > MAX_SKETCH_MAX_SIZE {
+            return exec_err!(
+                "Invalid t-digest sketch: max_size must be between 1 and {MAX_SKETCH_MAX_SIZE}, got {max_size}"
+            );
+        }
+        if !(count.is_finite() && count >= 0.0) {
+            return exec_err!("Invalid t-digest sketch: invalid count {count}");
+        }
+
+        let centroids = (0..num_centroids)
+            .map(|_| {
+                let mean = f64::from_bits(next_u64());
+                let weight = f64::from_bits(next_u64());
+                if mean.is_nan() || !(weight.is_finite() && weight > 0.0) {
+                    return exec_err!(
+                        "Invalid t-digest sketch: invalid centroid with mean {mean} and weight {weight}"
+                    );
+                }
+                Ok(Centroid::new(mean, weight))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if !centroids.windows(2).all(|w| w[0].cmp_mean(&w[1]).is_le()) {
+            return exec_err!("Invalid t-digest sketch: centroids are not sorted");
+        }
+
+        Ok(Self {
+            centroids,
+            max_size,
+            sum,
+            count,
+            max,
+            min,
+        })
+    }
 }
 
+/// Magic bytes identifying a serialized [`TDigest`], see [`TDigest::to_bytes()`]
+const TDIGEST_MAGIC: &[u8; 3] = b"TDG";
+/// Version of the [`TDigest::to_bytes()`] format
+const TDIGEST_FORMAT_VERSION: u8 = 1;
+const TDIGEST_HEADER_LEN: usize = TDIGEST_MAGIC.len() + 1;
+/// Length of the fixed size fields following the header
+const TDIGEST_FIELDS_LEN: usize = 6 * 8;
+
 #[cfg(debug_assertions)]
 fn is_sorted(values: &[f64]) -> bool {
     values.windows(2).all(|w| w[0].total_cmp(&w[1]).is_le())
@@ -760,4 +875,55 @@ mod tests {
         // The result should be approximately equal to the input value
         assert!((result - 15.699999988079073).abs() < 1e-10);
     }
+
+    #[test]
+    fn test_bytes_roundtrip() {
+        let t = TDigest::new(10);
+        let t = t.merge_unsorted_f64((1..=100).map(f64::from).collect());
+
+        let bytes = t.to_bytes();
+        assert_eq!(&bytes[..4], b"TDG\x01");
+        assert_eq!(TDigest::try_from_bytes(&bytes).unwrap(), t);
+
+        let empty = TDigest::new(10);
+        assert_eq!(
+            TDigest::try_from_bytes(&empty.to_bytes()).unwrap().count(),
+            0.0
+        );
+
+        assert!(TDigest::try_from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(TDigest::try_from_bytes(b"HLL\x01").is_err());
+    }
+
+    #[test]
+    fn test_bytes_malformed() {
+        let t = TDigest::new(10).merge_unsorted_f64(vec![1.0, 2.0, 3.0]);
+        let bytes = t.to_bytes();
+        let with_word = |offset: usize, word: u64| {
+            let mut bytes = bytes.clone();
+            bytes[offset..offset + 8].copy_from_slice(&word.to_le_bytes());
+            bytes
+        };
+        // Offsets of the fields following the header
+        let max_size = TDIGEST_HEADER_LEN;
+        let count = TDIGEST_HEADER_LEN + 16;
+        let first_weight = TDIGEST_HEADER_LEN + TDIGEST_FIELDS_LEN + 8;
+
+        for (offset, word) in [
+            (max_size, 0),
+            (max_size, u64::MAX),
+            (max_size, MAX_SKETCH_MAX_SIZE as u64 + 1),
+            (count, f64::NAN.to_bits()),
+            (count, (-1.0f64).to_bits()),
+            (first_weight, f64::INFINITY.to_bits()),
+            (first_weight, (-1.0f64).to_bits()),
+            (first_weight, 0.0f64.to_bits()),
+        ] {
+            let err = TDigest::try_from_bytes(&with_word(offset, word)).unwrap_err();
+            assert!(err.to_string().contains("Invalid t-digest sketch"), "{err}");
+        }
+
+        let largest = with_word(max_size, MAX_SKETCH_MAX_SIZE as u64);
+        assert!(TDigest::try_from_bytes(&largest).is_ok());
+    }
 }
@@ -0,0 +1,253 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the ANY_VALUE aggregation.
+
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, BooleanArray, BooleanBufferBuilder};
+use arrow::datatypes::{DataType, Field, FieldRef};
+use datafusion_common::{Result, not_impl_err};
+use datafusion_expr::function::{AccumulatorArgs, StateFieldsArgs};
+use datafusion_expr::utils::{AggregateOrderSensitivity, format_state_name};
+use datafusion_expr::{
+    Accumulator, AggregateUDFImpl, Documentation, EmitTo, GroupsAccumulator,
+    ReversedUDAF, Signature, Volatility,
+};
+use datafusion_macros::user_doc;
+
+use crate::first_last::TrivialFirstValueAccumulator;
+use crate::first_last::state::{
+    ValueState, new_value_state, take_need, value_state_supported,
+};
+
+make_udaf_expr_and_func!(
+    AnyValue,
+    any_value,
+    expression,
+    "Returns an arbitrary non-null value in a group of values.",
+    any_value_udaf
+);
+
+#[user_doc(
+    doc_section(label = "General Functions"),
+    description = "Returns an arbitrary non-null value from the group, or NULL if all values are NULL. Unlike `first_value`, no ordering is required or taken into account, which makes it cheaper to compute.",
+    syntax_example = "any_value(expression)",
+    sql_example = r#"```sql
+> SELECT any_value(column_name) FROM table_name;
++------------------------+
+| any_value(column_name) |
++------------------------+
+| some_value             |
++------------------------+
+```"#,
+    standard_argument(name = "expression",)
+)]
+/// ANY_VALUE aggregate UDF
+#[derive(PartialEq, Eq, Hash, Debug)]
+pub struct AnyValue {
+    signature: Signature,
+}
+
+impl Default for AnyValue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AnyValue {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(1, Volatility::Immutable),
+        }
+    }
+}
+
+impl AggregateUDFImpl for AnyValue {
+    fn name(&self) -> &str {
+        "any_value"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        not_impl_err!("Not called because the return_field_from_args is implemented")
+    }
+
+    fn return_field(&self, arg_fields: &[FieldRef]) -> Result<FieldRef> {
+        // Preserve metadata from the first argument field
+        Ok(Arc::new(
+            Field::new(
+                self.name(),
+                arg_fields[0].data_type().clone(),
+                true, // always nullable, there may be no rows
+            )
+            .with_metadata(arg_fields[0].metadata().clone()),
+        ))
+    }
+
+    fn accumulator(&self, acc_args: AccumulatorArgs) -> Result<Box<dyn Accumulator>> {
+        // Same as `first_value(expression) IGNORE NULLS` without an ordering
+        TrivialFirstValueAccumulator::try_new(acc_args.return_field.data_type(), true)
+            .map(|acc| Box::new(acc) as _)
+    }
+
+    fn state_fields(&self, args: StateFieldsArgs) -> Result<Vec<FieldRef>> {
+        Ok(vec![
+            Field::new(
+                format_state_name(args.name, "any_value"),
+                args.return_type().clone(),
+                true,
+            )
+            .into(),
+            Field::new(
+                format_state_name(args.name, "any_value_is_set"),
+                DataType::Boolean,
+                true,
+            )
+            .into(),
+        ])
+    }
+
+    fn groups_accumulator_supported(&self, args: AccumulatorArgs) -> bool {
+        value_state_supported(args.return_field.data_type())
+    }
+
+    fn create_groups_accumulator(
+        &self,
+        args: AccumulatorArgs,
+    ) -> Result<Box<dyn GroupsAccumulator>> {
+        Ok(Box::new(AnyValueGroupsAccumulator::try_new(
+            args.return_field.data_type(),
+        )?))
+    }
+
+    fn order_sensitivity(&self) -> AggregateOrderSensitivity {
+        AggregateOrderSensitivity::Insensitive
+    }
+
+    fn reverse_expr(&self) -> ReversedUDAF {
+        ReversedUDAF::Identical
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+/// [`GroupsAccumulator`] for `any_value`: keeps the first non-null value
+/// seen for each group.
+struct AnyValueGroupsAccumulator {
+    value: Box<dyn ValueState>,
+    /// Whether a non-null value has been seen for each group
+    is_set: BooleanBufferBuilder,
+}
+
+impl AnyValueGroupsAccumulator {
+    fn try_new(data_type: &DataType) -> Result<Self> {
+        Ok(Self {
+            value: new_value_state(data_type)?,
+            is_set: BooleanBufferBuilder::new(0),
+        })
+    }
+}
+
+impl GroupsAccumulator for AnyValueGroupsAccumulator {
+    fn update_batch(
+        &mut self,
+        values: &[ArrayRef],
+        group_indices: &[usize],
+        opt_filter: Option<&BooleanArray>,
+        total_num_groups: usize,
+    ) -> Result<()> {
+        self.value.resize(total_num_groups);
+        self.is_set.resize(total_num_groups);
+
+        let array = &values[0];
+        for (row, &group_idx) in group_indices.iter().enumerate() {
+            if self.is_set.get_bit(group_idx)
+                || array.is_null(row)
+                || opt_filter.is_some_and(|f| !f.is_valid(row) || !f.value(row))
+            {
+                continue;
+            }
+            self.value.update(group_idx, array, row)?;
+            self.is_set.set_bit(group_idx, true);
+        }
+        Ok(())
+    }
+
+    fn evaluate(&mut self, emit_to: EmitTo) -> Result<ArrayRef> {
+        take_need(&mut self.is_set, emit_to);
+        self.value.take(emit_to)
+    }
+
+    fn state(&mut self, emit_to: EmitTo) -> Result<Vec<ArrayRef>> {
+        let is_set = BooleanArray::new(take_need(&mut self.is_set, emit_to), None);
+        Ok(vec![self.value.take(emit_to)?, Arc::new(is_set)])
+    }
+
+    fn merge_batch(
+        &mut self,
+        values: &[ArrayRef],
+        group_indices: &[usize],
+        opt_filter: Option<&BooleanArray>,
+        total_num_groups: usize,
+    ) -> Result<()> {
+        // Only non-null values are ever set, so the value column alone tells
+        // which partial states hold a value
+        self.update_batch(&values[..1], group_indices, opt_filter, total_num_groups)
+    }
+
+    fn size(&self) -> usize {
+        self.value.size() + self.is_set.capacity() / 8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{AsArray, Int32Array};
+    use arrow::datatypes::Int32Type;
+
+    #[test]
+    fn any_value_groups_skips_nulls() -> Result<()> {
+        let mut acc = AnyValueGroupsAccumulator::try_new(&DataType::Int32)?;
+        let values: ArrayRef =
+            Arc::new(Int32Array::from(vec![None, Some(1), Some(2), None]));
+        acc.update_batch(&[values], &[0, 0, 1, 2], None, 3)?;
+        let state = acc.state(EmitTo::All)?;
+
+        let mut merged = AnyValueGroupsAccumulator::try_new(&DataType::Int32)?;
+        let values: ArrayRef = Arc::new(Int32Array::from(vec![Some(3), Some(4)]));
+        merged.update_batch(&[values], &[1, 2], None, 3)?;
+        merged.merge_batch(&state, &[0, 1, 2], None, 3)?;
+
+        let result = merged.evaluate(EmitTo::All)?;
+        assert_eq!(
+            result
+                .as_primitive::<Int32Type>()
+                .iter()
+                .collect::<Vec<_>>(),
+            vec![Some(1), Some(3), Some(4)]
+        );
+        Ok(())
+    }
+}
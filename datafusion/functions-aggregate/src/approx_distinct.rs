@@ -17,7 +17,7 @@
 
 //! Defines physical expressions that can evaluated at runtime during query execution
 
-use crate::hyperloglog::{HLL_HASH_STATE, HyperLogLog};
+use crate::hyperloglog::{HllHasher, HllValue, HyperLogLog, InternalHasher};
 use arrow::array::{Array, BinaryArray, StringViewArray};
 use arrow::array::{
     GenericBinaryArray, GenericStringArray, OffsetSizeTrait, PrimitiveArray,
@@ -46,7 +46,7 @@ use datafusion_functions_aggregate_common::aggregate::count_distinct::{
 use datafusion_functions_aggregate_common::noop_accumulator::NoopAccumulator;
 use datafusion_macros::user_doc;
 use std::fmt::{Debug, Formatter};
-use std::hash::{BuildHasher, Hash};
+use std::hash::Hash;
 use std::marker::PhantomData;
 
 make_udaf_expr_and_func!(
@@ -118,38 +118,44 @@ impl<A: Accumulator> Accumulator for ApproxDistinctBitmapWrapper<A> {
 }
 
 #[derive(Debug)]
-struct NumericHLLAccumulator<T>
+struct NumericHLLAccumulator<T, H>
 where
     T: ArrowPrimitiveType,
-    T::Native: Hash,
+    T::Native: HllValue,
+    H: HllHasher,
 {
     hll: HyperLogLog<T::Native>,
+    hasher: PhantomData<H>,
 }
 
-impl<T> NumericHLLAccumulator<T>
+impl<T, H> NumericHLLAccumulator<T, H>
 where
     T: ArrowPrimitiveType,
-    T::Native: Hash,
+    T::Native: HllValue,
+    H: HllHasher,
 {
     pub fn new() -> Self {
         Self {
             hll: HyperLogLog::new(),
+            hasher: PhantomData,
         }
     }
 }
 
 #[derive(Debug)]
-struct StringHLLAccumulator<T>
+struct StringHLLAccumulator<T, H>
 where
     T: OffsetSizeTrait,
+    H: HllHasher,
 {
     hll: HyperLogLog<str>,
-    phantom_data: PhantomData<T>,
+    phantom_data: PhantomData<(T, H)>,
 }
 
-impl<T> StringHLLAccumulator<T>
+impl<T, H> StringHLLAccumulator<T, H>
 where
     T: OffsetSizeTrait,
+    H: HllHasher,
 {
     pub fn new() -> Self {
         Self {
@@ -160,30 +166,40 @@ where
 }
 
 #[derive(Debug)]
-struct StringViewHLLAccumulator {
+struct StringViewHLLAccumulator<H>
+where
+    H: HllHasher,
+{
     hll: HyperLogLog<str>,
+    hasher: PhantomData<H>,
 }
 
-impl StringViewHLLAccumulator {
+impl<H> StringViewHLLAccumulator<H>
+where
+    H: HllHasher,
+{
     pub fn new() -> Self {
         Self {
             hll: HyperLogLog::new(),
+            hasher: PhantomData,
         }
     }
 }
 
 #[derive(Debug)]
-struct BinaryHLLAccumulator<T>
+struct BinaryHLLAccumulator<T, H>
 where
     T: OffsetSizeTrait,
+    H: HllHasher,
 {
     hll: HyperLogLog<[u8]>,
-    phantom_data: PhantomData<T>,
+    phantom_data: PhantomData<(T, H)>,
 }
 
-impl<T> BinaryHLLAccumulator<T>
+impl<T, H> BinaryHLLAccumulator<T, H>
 where
     T: OffsetSizeTrait,
+    H: HllHasher,
 {
     pub fn new() -> Self {
         Self {
@@ -226,22 +242,28 @@ macro_rules! default_accumulator_impl {
     };
 }
 
-impl<T> Accumulator for BinaryHLLAccumulator<T>
+impl<T, H> Accumulator for BinaryHLLAccumulator<T, H>
 where
     T: OffsetSizeTrait,
+    H: HllHasher,
 {
     fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
         let array: &GenericBinaryArray<T> =
             downcast_value!(values[0], GenericBinaryArray, T);
         // flatten because we would skip nulls
-        self.hll.extend(array.into_iter().flatten());
+        for value in array.iter().flatten() {
+            self.hll.add_hashed(H::hash_value(value));
+        }
         Ok(())
     }
 
     default_accumulator_impl!();
 }
 
-impl Accumulator for StringViewHLLAccumulator {
+impl<H> Accumulator for StringViewHLLAccumulator<H>
+where
+    H: HllHasher,
+{
     fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
         let array: &StringViewArray = downcast_value!(values[0], StringViewArray);
 
@@ -250,11 +272,13 @@ impl Accumulator for StringViewHLLAccumulator {
         if array.data_buffers().is_empty() {
             for (i, &view) in array.views().iter().enumerate() {
                 if !array.is_null(i) {
-                    self.hll.add_hashed(HLL_HASH_STATE.hash_one(view));
+                    self.hll.add_hashed(H::hash_value(&view));
                 }
             }
         } else {
-            self.hll.extend(array.iter().flatten());
+            for value in array.iter().flatten() {
+                self.hll.add_hashed(H::hash_value(value));
+            }
         }
 
         Ok(())
@@ -263,30 +287,36 @@ impl Accumulator for StringViewHLLAccumulator {
     default_accumulator_impl!();
 }
 
-impl<T> Accumulator for StringHLLAccumulator<T>
+impl<T, H> Accumulator for StringHLLAccumulator<T, H>
 where
     T: OffsetSizeTrait,
+    H: HllHasher,
 {
     fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
         let array: &GenericStringArray<T> =
             downcast_value!(values[0], GenericStringArray, T);
         // flatten because we would skip nulls
-        self.hll.extend(array.into_iter().flatten());
+        for value in array.iter().flatten() {
+            self.hll.add_hashed(H::hash_value(value));
+        }
         Ok(())
     }
 
     default_accumulator_impl!();
 }
 
-impl<T> Accumulator for NumericHLLAccumulator<T>
+impl<T, H> Accumulator for NumericHLLAccumulator<T, H>
 where
     T: ArrowPrimitiveType + Debug,
-    T::Native: Hash,
+    T::Native: HllValue,
+    H: HllHasher,
 {
     fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
         let array: &PrimitiveArray<T> = downcast_value!(values[0], PrimitiveArray, T);
         // flatten because we would skip nulls
-        self.hll.extend(array.into_iter().flatten());
+        for value in array.iter().flatten() {
+            self.hll.add_hashed(H::hash_value(&value));
+        }
         Ok(())
     }
 
@@ -409,58 +439,72 @@ impl AggregateUDFImpl for ApproxDistinct {
     fn accumulator(&self, acc_args: AccumulatorArgs) -> Result<Box<dyn Accumulator>> {
         let data_type = acc_args.expr_fields[0].data_type();
 
-        let accumulator: Box<dyn Accumulator> = match data_type {
+        match data_type {
             DataType::UInt8 | DataType::Int8 | DataType::UInt16 | DataType::Int16 => {
-                return get_small_int_approx_accumulator(data_type);
-            }
-            DataType::UInt32 => Box::new(NumericHLLAccumulator::<UInt32Type>::new()),
-            DataType::UInt64 => Box::new(NumericHLLAccumulator::<UInt64Type>::new()),
-            DataType::Int32 => Box::new(NumericHLLAccumulator::<Int32Type>::new()),
-            DataType::Int64 => Box::new(NumericHLLAccumulator::<Int64Type>::new()),
-            DataType::Date32 => Box::new(NumericHLLAccumulator::<Date32Type>::new()),
-            DataType::Date64 => Box::new(NumericHLLAccumulator::<Date64Type>::new()),
-            DataType::Time32(TimeUnit::Second) => {
-                Box::new(NumericHLLAccumulator::<Time32SecondType>::new())
-            }
-            DataType::Time32(TimeUnit::Millisecond) => {
-                Box::new(NumericHLLAccumulator::<Time32MillisecondType>::new())
-            }
-            DataType::Time64(TimeUnit::Microsecond) => {
-                Box::new(NumericHLLAccumulator::<Time64MicrosecondType>::new())
-            }
-            DataType::Time64(TimeUnit::Nanosecond) => {
-                Box::new(NumericHLLAccumulator::<Time64NanosecondType>::new())
-            }
-            DataType::Timestamp(TimeUnit::Second, _) => {
-                Box::new(NumericHLLAccumulator::<TimestampSecondType>::new())
-            }
-            DataType::Timestamp(TimeUnit::Millisecond, _) => {
-                Box::new(NumericHLLAccumulator::<TimestampMillisecondType>::new())
-            }
-            DataType::Timestamp(TimeUnit::Microsecond, _) => {
-                Box::new(NumericHLLAccumulator::<TimestampMicrosecondType>::new())
+                get_small_int_approx_accumulator(data_type)
             }
-            DataType::Timestamp(TimeUnit::Nanosecond, _) => {
-                Box::new(NumericHLLAccumulator::<TimestampNanosecondType>::new())
-            }
-            DataType::Utf8 => Box::new(StringHLLAccumulator::<i32>::new()),
-            DataType::LargeUtf8 => Box::new(StringHLLAccumulator::<i64>::new()),
-            DataType::Utf8View => Box::new(StringViewHLLAccumulator::new()),
-            DataType::Binary => Box::new(BinaryHLLAccumulator::<i32>::new()),
-            DataType::LargeBinary => Box::new(BinaryHLLAccumulator::<i64>::new()),
             DataType::Null => {
-                Box::new(NoopAccumulator::new(ScalarValue::UInt64(Some(0))))
-            }
-            other => {
-                return not_impl_err!(
-                    "Support for 'approx_distinct' for data type {other} is not implemented"
-                );
+                Ok(Box::new(NoopAccumulator::new(ScalarValue::UInt64(Some(0)))))
             }
-        };
-        Ok(accumulator)
+            _ => new_hll_accumulator::<InternalHasher>(data_type, self.name()),
+        }
     }
 
     fn documentation(&self) -> Option<&Documentation> {
         self.doc()
     }
 }
+
+/// Creates an accumulator that adds all non-null values of `data_type` to a
+/// [`HyperLogLog`], hashed with `H`.
+///
+/// The state of the returned accumulator holds the HLL registers, and it
+/// evaluates to the estimated number of distinct values.
+pub(crate) fn new_hll_accumulator<H: HllHasher>(
+    data_type: &DataType,
+    fn_name: &str,
+) -> Result<Box<dyn Accumulator>> {
+    let accumulator: Box<dyn Accumulator> = match data_type {
+        DataType::UInt32 => Box::new(NumericHLLAccumulator::<UInt32Type, H>::new()),
+        DataType::UInt64 => Box::new(NumericHLLAccumulator::<UInt64Type, H>::new()),
+        DataType::Int32 => Box::new(NumericHLLAccumulator::<Int32Type, H>::new()),
+        DataType::Int64 => Box::new(NumericHLLAccumulator::<Int64Type, H>::new()),
+        DataType::Date32 => Box::new(NumericHLLAccumulator::<Date32Type, H>::new()),
+        DataType::Date64 => Box::new(NumericHLLAccumulator::<Date64Type, H>::new()),
+        DataType::Time32(TimeUnit::Second) => {
+            Box::new(NumericHLLAccumulator::<Time32SecondType, H>::new())
+        }
+        DataType::Time32(TimeUnit::Millisecond) => {
+            Box::new(NumericHLLAccumulator::<Time32MillisecondType, H>::new())
+        }
+        DataType::Time64(TimeUnit::Microsecond) => {
+            Box::new(NumericHLLAccumulator::<Time64MicrosecondType, H>::new())
+        }
+        DataType::Time64(TimeUnit::Nanosecond) => {
+            Box::new(NumericHLLAccumulator::<Time64NanosecondType, H>::new())
+        }
+        DataType::Timestamp(TimeUnit::Second, _) => {
+            Box::new(NumericHLLAccumulator::<TimestampSecondType, H>::new())
+        }
+        DataType::Timestamp(TimeUnit::Millisecond, _) => {
+            Box::new(NumericHLLAccumulator::<TimestampMillisecondType, H>::new())
+        }
+        DataType::Timestamp(TimeUnit::Microsecond, _) => {
+            Box::new(NumericHLLAccumulator::<TimestampMicrosecondType, H>::new())
+        }
+        DataType::Timestamp(TimeUnit::Nanosecond, _) => {
+            Box::new(NumericHLLAccumulator::<TimestampNanosecondType, H>::new())
+        }
+        DataType::Utf8 => Box::new(StringHLLAccumulator::<i32, H>::new()),
+        DataType::LargeUtf8 => Box::new(StringHLLAccumulator::<i64, H>::new()),
+        DataType::Utf8View => Box::new(StringViewHLLAccumulator::<H>::new()),
+        DataType::Binary => Box::new(BinaryHLLAccumulator::<i32, H>::new()),
+        DataType::LargeBinary => Box::new(BinaryHLLAccumulator::<i64, H>::new()),
+        other => {
+            return not_impl_err!(
+                "Support for '{fn_name}' for data type {other} is not implemented"
+            );
+        }
+    };
+    Ok(accumulator)
+}
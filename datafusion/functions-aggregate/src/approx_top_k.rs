@@ -0,0 +1,550 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`ApproxTopK`]: most frequent values using the Space-Saving algorithm
+
+use std::cmp::Ordering;
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Debug;
+use std::mem::{size_of, size_of_val};
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, AsArray, StructArray, UInt64Array};
+use arrow::datatypes::{DataType, Field, FieldRef, Fields, UInt64Type};
+use datafusion_common::utils::SingleRowListArrayBuilder;
+use datafusion_common::{Result, ScalarValue, internal_err, not_impl_err, plan_err};
+use datafusion_expr::function::{AccumulatorArgs, StateFieldsArgs};
+use datafusion_expr::utils::format_state_name;
+use datafusion_expr::{
+    Accumulator, AggregateUDFImpl, Documentation, Signature, Volatility,
+};
+use datafusion_macros::user_doc;
+
+use crate::utils::validate_positive_integer_expr;
+
+make_udaf_expr_and_func!(
+    ApproxTopK,
+    approx_top_k,
+    expression k,
+    "Returns the approximate `k` most frequent values of a set of values",
+    approx_top_k_udaf
+);
+
+/// Number of counters kept per requested value. More counters make the
+/// counts of the `k` most frequent values more accurate.
+const COUNTERS_PER_VALUE: usize = 3;
+
+/// Minimum number of counters, so small `k` still gives useful results
+const MIN_COUNTERS: usize = 64;
+
+#[user_doc(
+    doc_section(label = "Approximate Functions"),
+    description = "Returns the approximate `k` most frequent non-null values as a list of structs with the fields `value`, `count` and `error`, ordered by descending `count`. `count` overestimates the true number of occurrences by at most `error`. Uses the Space-Saving algorithm, so memory usage does not depend on the number of distinct values.",
+    syntax_example = "approx_top_k(expression, k)",
+    sql_example = r#"```sql
+> SELECT approx_top_k(column_name, 2) AS top FROM table_name;
++-------------------------------------------------------------------+
+| top                                                               |
++-------------------------------------------------------------------+
+| [{value: a, count: 10, error: 0}, {value: b, count: 7, error: 0}] |
++-------------------------------------------------------------------+
+```"#,
+    standard_argument(name = "expression",),
+    argument(
+        name = "k",
+        description = "Number of values to return. Must be a positive integer literal."
+    )
+)]
+/// APPROX_TOP_K aggregate expression
+#[derive(PartialEq, Eq, Hash, Debug)]
+pub struct ApproxTopK {
+    signature: Signature,
+}
+
+impl Default for ApproxTopK {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ApproxTopK {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::user_defined(Volatility::Immutable),
+        }
+    }
+}
+
+impl AggregateUDFImpl for ApproxTopK {
+    fn name(&self) -> &str {
+        "approx_top_k"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        let [value, k] = arg_types else {
+            return plan_err!(
+                "{} was called with {} arguments. It requires 2.",
+                self.name(),
+                arg_types.len()
+            );
+        };
+        if !k.is_integer() && !k.is_null() {
+            return plan_err!(
+                "The second argument of {} must be an integer, got {k}",
+                self.name()
+            );
+        }
+        let value = match value {
+            DataType::Dictionary(_, value_type) => value_type.as_ref().clone(),
+            value => value.clone(),
+        };
+        Ok(vec![value, DataType::Int64])
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::new_list(
+            DataType::Struct(top_k_fields(&arg_types[0])),
+            true,
+        ))
+    }
+
+    fn state_fields(&self, args: StateFieldsArgs) -> Result<Vec<FieldRef>> {
+        let value_type = args.input_fields[0].data_type().clone();
+        Ok(vec![
+            Field::new_list(
+                format_state_name(args.name, "approx_top_k_values"),
+                Field::new_list_field(value_type, true),
+                true,
+            )
+            .into(),
+            Field::new_list(
+                format_state_name(args.name, "approx_top_k_counts"),
+                Field::new_list_field(DataType::UInt64, true),
+                true,
+            )
+            .into(),
+            Field::new_list(
+                format_state_name(args.name, "approx_top_k_errors"),
+                Field::new_list_field(DataType::UInt64, true),
+                true,
+            )
+            .into(),
+        ])
+    }
+
+    fn accumulator(&self, args: AccumulatorArgs) -> Result<Box<dyn Accumulator>> {
+        if args.is_distinct {
+            return not_impl_err!(
+                "APPROX_TOP_K(DISTINCT) aggregations are not available"
+            );
+        }
+        let k = validate_positive_integer_expr(&args.exprs[1], self.name(), "second")?;
+
+        Ok(Box::new(ApproxTopKAccumulator::new(
+            args.expr_fields[0].data_type().clone(),
+            k,
+        )))
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+/// Fields of the structs returned by `approx_top_k`
+fn top_k_fields(value_type: &DataType) -> Fields {
+    Fields::from(vec![
+        Field::new("value", value_type.clone(), true),
+        Field::new("count", DataType::UInt64, false),
+        Field::new("error", DataType::UInt64, false),
+    ])
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Counter {
+    /// Estimated number of occurrences, never less than the true number
+    count: u64,
+    /// Upper bound of how much `count` overestimates the true number
+    error: u64,
+}
+
+/// Space-Saving summary (Metwally et al., "Efficient Computation of Frequent
+/// and Top-k Elements in Data Streams") of the most frequent values.
+///
+/// At most `capacity` values are tracked. A new value replaces the least
+/// frequent tracked one and inherits its count as error. The tracked values
+/// are indexed by count, so finding the least frequent one takes
+/// O(log capacity). Two summaries are merged as described in Cafaro et al.,
+/// "A parallel space saving algorithm for frequent items", so partial
+/// aggregates can be combined.
+#[derive(Debug)]
+struct SpaceSaving {
+    capacity: usize,
+    /// The tracked values and their counters
+    entries: Vec<(ScalarValue, Counter)>,
+    /// Position of each tracked value in `entries`
+    positions: HashMap<ScalarValue, usize>,
+    /// `(count, position)` of each entry, ordered by count
+    by_count: BTreeSet<(u64, usize)>,
+}
+
+#[allow(clippy::allow_attributes, clippy::mutable_key_type)] // ScalarValue has interior mutability but is intentionally used as hash key
+impl SpaceSaving {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: vec![],
+            positions: HashMap::new(),
+            by_count: BTreeSet::new(),
+        }
+    }
+
+    /// Creates a summary tracking the given distinct values
+    fn from_entries(capacity: usize, entries: Vec<(ScalarValue, Counter)>) -> Self {
+        let mut summary = Self::new(capacity);
+        for (value, counter) in entries {
+            summary.push(value, counter);
+        }
+        summary
+    }
+
+    fn push(&mut self, value: ScalarValue, counter: Counter) {
+        let position = self.entries.len();
+        self.positions.insert(value.clone(), position);
+        self.by_count.insert((counter.count, position));
+        self.entries.push((value, counter));
+    }
+
+    fn get(&self, value: &ScalarValue) -> Option<Counter> {
+        self.positions
+            .get(value)
+            .map(|&position| self.entries[position].1)
+    }
+
+    /// Upper bound of the number of occurrences of any value that is not
+    /// tracked
+    fn untracked_count(&self) -> u64 {
+        if self.entries.len() < self.capacity {
+            return 0;
+        }
+        self.by_count.first().map_or(0, |&(count, _)| count)
+    }
+
+    /// Records `count` occurrences of `value`
+    fn insert(&mut self, value: ScalarValue, count: u64) {
+        if let Some(&position) = self.positions.get(&value) {
+            let counter = &mut self.entries[position].1;
+            self.by_count.remove(&(counter.count, position));
+            counter.count += count;
+            self.by_count.insert((counter.count, position));
+            return;
+        }
+        if self.entries.len() < self.capacity {
+            self.push(value, Counter { count, error: 0 });
+            return;
+        }
+
+        // The evicted value may have occurred up to `min_count` times, all
+        // of which could have been occurrences of `value`
+        let Some((min_count, position)) = self.by_count.pop_first() else {
+            return;
+        };
+        let counter = Counter {
+            count: min_count + count,
+            error: min_count,
+        };
+        let (evicted, _) =
+            std::mem::replace(&mut self.entries[position], (value.clone(), counter));
+        self.positions.remove(&evicted);
+        self.positions.insert(value, position);
+        self.by_count.insert((counter.count, position));
+    }
+
+    fn merge(&mut self, other: SpaceSaving) {
+        let untracked = self.untracked_count();
+        let other_untracked = other.untracked_count();
+
+        let mut entries = std::mem::take(&mut self.entries);
+        for (value, counter) in entries.iter_mut() {
+            let theirs = other.get(value).unwrap_or(Counter {
+                count: other_untracked,
+                error: other_untracked,
+            });
+            counter.count += theirs.count;
+            counter.error += theirs.error;
+        }
+        for (value, counter) in other.entries {
+            if !self.positions.contains_key(&value) {
+                entries.push((
+                    value,
+                    Counter {
+                        count: counter.count + untracked,
+                        error: counter.error + untracked,
+                    },
+                ));
+            }
+        }
+
+        if entries.len() > self.capacity {
+            entries.sort_by(|(_, a), (_, b)| b.count.cmp(&a.count));
+            entries.truncate(self.capacity);
+        }
+        *self = Self::from_entries(self.capacity, entries);
+    }
+
+    /// Returns the `k` values with the highest counts, ties broken by value
+    fn top_k(&self, k: usize) -> Vec<(&ScalarValue, &Counter)> {
+        let mut entries = self
+            .entries
+            .iter()
+            .map(|(value, counter)| (value, counter))
+            .collect::<Vec<_>>();
+        entries.sort_by(|(a_value, a), (b_value, b)| {
+            b.count
+                .cmp(&a.count)
+                .then_with(|| a_value.partial_cmp(b_value).unwrap_or(Ordering::Equal))
+        });
+        entries.truncate(k);
+        entries
+    }
+
+    fn size(&self) -> usize {
+        // each value is stored in both `entries` and `positions`
+        size_of_val(self)
+            + self.entries.capacity() * size_of::<(ScalarValue, Counter)>()
+            + self.positions.capacity() * size_of::<(ScalarValue, usize)>()
+            + self.by_count.len() * size_of::<(u64, usize)>()
+            + 2 * self
+                .entries
+                .iter()
+                .map(|(value, _)| value.size() - size_of_val(value))
+                .sum::<usize>()
+    }
+}
+
+#[derive(Debug)]
+struct ApproxTopKAccumulator {
+    data_type: DataType,
+    k: usize,
+    sketch: SpaceSaving,
+}
+
+impl ApproxTopKAccumulator {
+    fn new(data_type: DataType, k: usize) -> Self {
+        let capacity = k.saturating_mul(COUNTERS_PER_VALUE).max(MIN_COUNTERS);
+        Self {
+            data_type,
+            k,
+            sketch: SpaceSaving::new(capacity),
+        }
+    }
+}
+
+#[allow(clippy::allow_attributes, clippy::mutable_key_type)] // ScalarValue has interior mutability but is intentionally used as hash key
+impl Accumulator for ApproxTopKAccumulator {
+    fn state(&mut self) -> Result<Vec<ScalarValue>> {
+        let mut values = Vec::with_capacity(self.sketch.entries.len());
+        let mut counts = Vec::with_capacity(self.sketch.entries.len());
+        let mut errors = Vec::with_capacity(self.sketch.entries.len());
+        for (value, counter) in &self.sketch.entries {
+            values.push(value.clone());
+            counts.push(ScalarValue::from(counter.count));
+            errors.push(ScalarValue::from(counter.error));
+        }
+
+        Ok(vec![
+            ScalarValue::List(ScalarValue::new_list_nullable(&values, &self.data_type)),
+            ScalarValue::List(ScalarValue::new_list_nullable(&counts, &DataType::UInt64)),
+            ScalarValue::List(ScalarValue::new_list_nullable(&errors, &DataType::UInt64)),
+        ])
+    }
+
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        // Count the batch first, in order of first occurrence, so each
+        // distinct value only touches the summary once
+        let array = &values[0];
+        let mut batch_counts: Vec<(ScalarValue, u64)> = vec![];
+        let mut positions: HashMap<ScalarValue, usize> = HashMap::new();
+        for i in 0..array.len() {
+            if array.is_null(i) {
+                continue;
+            }
+            match positions.entry(ScalarValue::try_from_array(array, i)?) {
+                Entry::Occupied(entry) => batch_counts[*entry.get()].1 += 1,
+                Entry::Vacant(entry) => {
+                    batch_counts.push((entry.key().clone(), 1));
+                    entry.insert(batch_counts.len() - 1);
+                }
+            }
+        }
+
+        for (value, count) in batch_counts {
+            self.sketch.insert(value.compacted(), count);
+        }
+        Ok(())
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        let [values, counts, errors] = states else {
+            return internal_err!("approx_top_k expects 3 state fields");
+        };
+        let values = values.as_list::<i32>();
+        let counts = counts.as_list::<i32>();
+        let errors = errors.as_list::<i32>();
+
+        for row in 0..values.len() {
+            if values.is_null(row) {
+                continue;
+            }
+            let row_values = values.value(row);
+            let row_counts = counts.value(row);
+            let row_counts = row_counts.as_primitive::<UInt64Type>();
+            let row_errors = errors.value(row);
+            let row_errors = row_errors.as_primitive::<UInt64Type>();
+
+            let entries = (0..row_values.len())
+                .map(|i| {
+                    let counter = Counter {
+                        count: row_counts.value(i),
+                        error: row_errors.value(i),
+                    };
+                    Ok((ScalarValue::try_from_array(&row_values, i)?, counter))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            self.sketch
+                .merge(SpaceSaving::from_entries(self.sketch.capacity, entries));
+        }
+        Ok(())
+    }
+
+    fn evaluate(&mut self) -> Result<ScalarValue> {
+        let top_k = self.sketch.top_k(self.k);
+        if top_k.is_empty() {
+            return ScalarValue::try_from(&DataType::new_list(
+                DataType::Struct(top_k_fields(&self.data_type)),
+                true,
+            ));
+        }
+
+        let values =
+            ScalarValue::iter_to_array(top_k.iter().map(|(value, _)| (*value).clone()))?;
+        let counts = UInt64Array::from_iter_values(top_k.iter().map(|(_, c)| c.count));
+        let errors = UInt64Array::from_iter_values(top_k.iter().map(|(_, c)| c.error));
+        let entries = StructArray::try_new(
+            top_k_fields(&self.data_type),
+            vec![values, Arc::new(counts), Arc::new(errors)],
+            None,
+        )?;
+        Ok(SingleRowListArrayBuilder::new(Arc::new(entries)).build_list_scalar())
+    }
+
+    fn size(&self) -> usize {
+        size_of_val(self) - size_of_val(&self.sketch) + self.sketch.size()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::Int64Array;
+
+    fn counts_of(sketch: &SpaceSaving, k: usize) -> Vec<(i64, u64, u64)> {
+        sketch
+            .top_k(k)
+            .into_iter()
+            .map(|(value, counter)| {
+                let ScalarValue::Int64(Some(value)) = value else {
+                    unreachable!()
+                };
+                (*value, counter.count, counter.error)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn space_saving_evicts_least_frequent() {
+        let mut sketch = SpaceSaving::new(2);
+        sketch.insert(ScalarValue::from(1i64), 5);
+        sketch.insert(ScalarValue::from(2i64), 1);
+        sketch.insert(ScalarValue::from(3i64), 2);
+
+        // 3 replaced 2 and inherited its count as error
+        assert_eq!(counts_of(&sketch, 2), vec![(1, 5, 0), (3, 3, 1)]);
+    }
+
+    #[test]
+    fn space_saving_keeps_frequent_value_among_many() {
+        let mut sketch = SpaceSaving::new(4);
+        for value in 1..=1000i64 {
+            sketch.insert(ScalarValue::from(0i64), 1);
+            sketch.insert(ScalarValue::from(value), 1);
+        }
+
+        let top = counts_of(&sketch, 1);
+        assert_eq!(top[0].0, 0);
+        assert!(top[0].1 >= 1000);
+        // the counts of a full summary add up to the number of occurrences
+        let total: u64 = sketch.entries.iter().map(|(_, c)| c.count).sum();
+        assert_eq!(total, 2000);
+        assert_eq!(
+            sketch.untracked_count(),
+            sketch.entries.iter().map(|(_, c)| c.count).min().unwrap()
+        );
+    }
+
+    #[test]
+    fn space_saving_merge() {
+        let mut left = SpaceSaving::new(2);
+        left.insert(ScalarValue::from(1i64), 4);
+        left.insert(ScalarValue::from(2i64), 2);
+        let mut right = SpaceSaving::new(2);
+        right.insert(ScalarValue::from(1i64), 1);
+        right.insert(ScalarValue::from(3i64), 3);
+
+        left.merge(right);
+        // 2 may have occurred up to once on the right, 3 up to twice on the
+        // left, as both summaries are full
+        assert_eq!(counts_of(&left, 2), vec![(1, 5, 0), (3, 5, 2)]);
+    }
+
+    #[test]
+    fn approx_top_k_merge_states() -> Result<()> {
+        let mut acc1 = ApproxTopKAccumulator::new(DataType::Int64, 2);
+        let mut acc2 = ApproxTopKAccumulator::new(DataType::Int64, 2);
+        let values: ArrayRef =
+            Arc::new(Int64Array::from(vec![Some(1), Some(2), None, Some(1)]));
+        acc1.update_batch(&[values])?;
+        let values: ArrayRef = Arc::new(Int64Array::from(vec![2, 2, 3]));
+        acc2.update_batch(&[values])?;
+
+        let mut acc = ApproxTopKAccumulator::new(DataType::Int64, 2);
+        for state in [acc1.state()?, acc2.state()?] {
+            let state = state
+                .iter()
+                .map(|s| s.to_array())
+                .collect::<Result<Vec<_>>>()?;
+            acc.merge_batch(&state)?;
+        }
+        assert_eq!(counts_of(&acc.sketch, 2), vec![(2, 3, 0), (1, 2, 0)]);
+        Ok(())
+    }
+}
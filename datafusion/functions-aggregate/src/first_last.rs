@@ -39,7 +39,11 @@ use datafusion_common::{
     DataFusionError, Result, ScalarValue, arrow_datafusion_err, internal_err,
     not_impl_err,
 };
-use datafusion_expr::function::{AccumulatorArgs, StateFieldsArgs};
+use datafusion_expr::expr::{AggregateFunction, AggregateFunctionParams, NullTreatment};
+use datafusion_expr::function::{
+    AccumulatorArgs, AggregateFunctionSimplification, StateFieldsArgs,
+};
+use datafusion_expr::simplify::SimplifyContext;
 use datafusion_expr::utils::{AggregateOrderSensitivity, format_state_name};
 use datafusion_expr::{
     Accumulator, AggregateUDFImpl, Documentation, EmitTo, Expr, ExprFunctionExt,
@@ -49,9 +53,11 @@ use datafusion_functions_aggregate_common::utils::get_sort_options;
 use datafusion_macros::user_doc;
 use datafusion_physical_expr_common::sort_expr::LexOrdering;
 
-mod state;
+use crate::min_max::{max_by_udaf, min_by_udaf};
+
+pub(crate) mod state;
 
-use state::{BytesValueState, PrimitiveValueState, ValueState};
+use state::{BytesValueState, PrimitiveValueState, ValueState, value_state_supported};
 
 create_func!(FirstValue, first_value_udaf);
 create_func!(LastValue, last_value_udaf);
@@ -180,36 +186,58 @@ fn create_groups_accumulator(
 }
 
 fn groups_accumulator_supported(args: &AccumulatorArgs) -> bool {
-    use DataType::*;
-    !args.order_bys.is_empty()
-        && matches!(
-            args.return_field.data_type(),
-            Int8 | Int16
-                | Int32
-                | Int64
-                | UInt8
-                | UInt16
-                | UInt32
-                | UInt64
-                | Float16
-                | Float32
-                | Float64
-                | Decimal32(_, _)
-                | Decimal64(_, _)
-                | Decimal128(_, _)
-                | Decimal256(_, _)
-                | Date32
-                | Date64
-                | Time32(_)
-                | Time64(_)
-                | Timestamp(_, _)
-                | Utf8
-                | LargeUtf8
-                | Utf8View
-                | Binary
-                | LargeBinary
-                | BinaryView
-        )
+    !args.order_bys.is_empty() && value_state_supported(args.return_field.data_type())
+}
+
+/// Rewrites `first_value(x ORDER BY y)` / `last_value(x ORDER BY y)` to
+/// `min_by(x, y)` / `max_by(x, y)` if enabled by
+/// `datafusion.optimizer.enable_first_last_to_min_max_by`.
+///
+/// This is only done if `y` is not nullable, as `min_by` / `max_by` skip
+/// rows where `y` is NULL while the NULLS FIRST / LAST option decides whether
+/// `first_value` / `last_value` pick them.
+fn simplify_first_last(
+    aggregate_function: AggregateFunction,
+    info: &SimplifyContext,
+    is_first: bool,
+) -> Result<Expr> {
+    let params = &aggregate_function.params;
+    let rewritable = info
+        .config_options()
+        .optimizer
+        .enable_first_last_to_min_max_by
+        && params.args.len() == 1
+        && !params.distinct
+        && params.null_treatment != Some(NullTreatment::IgnoreNulls)
+        && params.order_by.len() == 1
+        && !info.nullable(&params.order_by[0].expr).unwrap_or(true);
+    if !rewritable {
+        return Ok(Expr::AggregateFunction(aggregate_function));
+    }
+
+    let AggregateFunctionParams {
+        mut args,
+        filter,
+        mut order_by,
+        ..
+    } = aggregate_function.params;
+    let sort = order_by.remove(0);
+    // `first_value ... ASC` and `last_value ... DESC` pick the smallest value
+    let func = if is_first == sort.asc {
+        min_by_udaf()
+    } else {
+        max_by_udaf()
+    };
+    args.push(sort.expr);
+
+    Ok(Expr::AggregateFunction(AggregateFunction::new_udf(
+        func,
+        args,
+        false,
+        filter,
+        vec![],
+        None,
+    )))
 }
 
 #[user_doc(
@@ -347,6 +375,12 @@ impl AggregateUDFImpl for FirstValue {
         true
     }
 
+    fn simplify(&self) -> Option<AggregateFunctionSimplification> {
+        Some(Box::new(|aggregate_function, info| {
+            simplify_first_last(aggregate_function, info, true)
+        }))
+    }
+
     fn documentation(&self) -> Option<&Documentation> {
         self.doc()
     }
@@ -1111,6 +1145,12 @@ impl AggregateUDFImpl for LastValue {
         true
     }
 
+    fn simplify(&self) -> Option<AggregateFunctionSimplification> {
+        Some(Box::new(|aggregate_function, info| {
+            simplify_first_last(aggregate_function, info, false)
+        }))
+    }
+
     fn documentation(&self) -> Option<&Documentation> {
         self.doc()
     }
@@ -24,7 +24,14 @@ use arrow::array::{
     StringBuilder, StringViewBuilder,
 };
 use arrow::buffer::{BooleanBuffer, NullBuffer};
-use arrow::datatypes::DataType;
+use arrow::datatypes::{
+    DataType, Date32Type, Date64Type, Decimal32Type, Decimal64Type, Decimal128Type,
+    Decimal256Type, Float16Type, Float32Type, Float64Type, Int8Type, Int16Type,
+    Int32Type, Int64Type, Time32MillisecondType, Time32SecondType, Time64MicrosecondType,
+    Time64NanosecondType, TimeUnit, TimestampMicrosecondType, TimestampMillisecondType,
+    TimestampNanosecondType, TimestampSecondType, UInt8Type, UInt16Type, UInt32Type,
+    UInt64Type,
+};
 use datafusion_common::{Result, internal_err};
 use datafusion_expr::EmitTo;
 
@@ -308,6 +315,108 @@ pub(crate) fn take_need(
     }
 }
 
+/// Returns true if [`new_value_state`] can create a [`ValueState`] for `data_type`
+pub(crate) fn value_state_supported(data_type: &DataType) -> bool {
+    use DataType::*;
+    matches!(
+        data_type,
+        Int8 | Int16
+            | Int32
+            | Int64
+            | UInt8
+            | UInt16
+            | UInt32
+            | UInt64
+            | Float16
+            | Float32
+            | Float64
+            | Decimal32(_, _)
+            | Decimal64(_, _)
+            | Decimal128(_, _)
+            | Decimal256(_, _)
+            | Date32
+            | Date64
+            | Time32(_)
+            | Time64(_)
+            | Timestamp(_, _)
+            | Utf8
+            | LargeUtf8
+            | Utf8View
+            | Binary
+            | LargeBinary
+            | BinaryView
+    )
+}
+
+/// Creates a type-erased [`ValueState`] for `data_type`.
+///
+/// This is used by aggregates that only need to remember one value per group
+/// (e.g. `any_value` or `max_by`) and do not benefit from monomorphizing
+/// their accumulator over the state type.
+pub(crate) fn new_value_state(data_type: &DataType) -> Result<Box<dyn ValueState>> {
+    macro_rules! primitive_state {
+        ($t:ty) => {
+            Ok(Box::new(PrimitiveValueState::<$t>::new(data_type.clone())))
+        };
+    }
+
+    match data_type {
+        DataType::Int8 => primitive_state!(Int8Type),
+        DataType::Int16 => primitive_state!(Int16Type),
+        DataType::Int32 => primitive_state!(Int32Type),
+        DataType::Int64 => primitive_state!(Int64Type),
+        DataType::UInt8 => primitive_state!(UInt8Type),
+        DataType::UInt16 => primitive_state!(UInt16Type),
+        DataType::UInt32 => primitive_state!(UInt32Type),
+        DataType::UInt64 => primitive_state!(UInt64Type),
+        DataType::Float16 => primitive_state!(Float16Type),
+        DataType::Float32 => primitive_state!(Float32Type),
+        DataType::Float64 => primitive_state!(Float64Type),
+
+        DataType::Decimal32(_, _) => primitive_state!(Decimal32Type),
+        DataType::Decimal64(_, _) => primitive_state!(Decimal64Type),
+        DataType::Decimal128(_, _) => primitive_state!(Decimal128Type),
+        DataType::Decimal256(_, _) => primitive_state!(Decimal256Type),
+
+        DataType::Timestamp(TimeUnit::Second, _) => {
+            primitive_state!(TimestampSecondType)
+        }
+        DataType::Timestamp(TimeUnit::Millisecond, _) => {
+            primitive_state!(TimestampMillisecondType)
+        }
+        DataType::Timestamp(TimeUnit::Microsecond, _) => {
+            primitive_state!(TimestampMicrosecondType)
+        }
+        DataType::Timestamp(TimeUnit::Nanosecond, _) => {
+            primitive_state!(TimestampNanosecondType)
+        }
+
+        DataType::Date32 => primitive_state!(Date32Type),
+        DataType::Date64 => primitive_state!(Date64Type),
+        DataType::Time32(TimeUnit::Second) => primitive_state!(Time32SecondType),
+        DataType::Time32(TimeUnit::Millisecond) => {
+            primitive_state!(Time32MillisecondType)
+        }
+        DataType::Time64(TimeUnit::Microsecond) => {
+            primitive_state!(Time64MicrosecondType)
+        }
+        DataType::Time64(TimeUnit::Nanosecond) => {
+            primitive_state!(Time64NanosecondType)
+        }
+
+        DataType::Utf8
+        | DataType::LargeUtf8
+        | DataType::Utf8View
+        | DataType::Binary
+        | DataType::LargeBinary
+        | DataType::BinaryView => {
+            Ok(Box::new(BytesValueState::try_new(data_type.clone())?))
+        }
+
+        _ => internal_err!("ValueState not supported for {}", data_type),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,277 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`Histogram`]: number of occurrences of every distinct value
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::mem::{size_of, size_of_val};
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, AsArray, MapArray, StructArray, UInt64Array};
+use arrow::buffer::OffsetBuffer;
+use arrow::datatypes::{DataType, Field, FieldRef, Fields, UInt64Type};
+use datafusion_common::{Result, ScalarValue, not_impl_err};
+use datafusion_expr::function::{AccumulatorArgs, StateFieldsArgs};
+use datafusion_expr::utils::format_state_name;
+use datafusion_expr::{
+    Accumulator, AggregateUDFImpl, Documentation, Signature, Volatility,
+};
+use datafusion_macros::user_doc;
+
+make_udaf_expr_and_func!(
+    Histogram,
+    histogram,
+    expression,
+    "Returns a map from every distinct value to its number of occurrences",
+    histogram_udaf
+);
+
+#[user_doc(
+    doc_section(label = "General Functions"),
+    description = "Returns a map from every distinct non-null input value to the number of times it occurs, ordered by value.",
+    syntax_example = "histogram(expression)",
+    sql_example = r#"```sql
+> SELECT histogram(column_name) AS hist FROM table_name;
++------------------+
+| hist             |
++------------------+
+| {a: 2, b: 1}     |
++------------------+
+```"#,
+    standard_argument(name = "expression",)
+)]
+/// HISTOGRAM aggregate expression. This keeps a count for every distinct input
+/// value, so memory usage grows with the cardinality of the input. See
+/// `approx_top_k` for bounded memory usage.
+#[derive(PartialEq, Eq, Hash, Debug)]
+pub struct Histogram {
+    signature: Signature,
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Histogram {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::user_defined(Volatility::Immutable),
+        }
+    }
+}
+
+impl AggregateUDFImpl for Histogram {
+    fn name(&self) -> &str {
+        "histogram"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        // Map keys can not be dictionary encoded
+        Ok(arg_types
+            .iter()
+            .map(|arg_type| match arg_type {
+                DataType::Dictionary(_, value_type) => value_type.as_ref().clone(),
+                arg_type => arg_type.clone(),
+            })
+            .collect())
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Map(
+            Arc::new(Field::new(
+                "entries",
+                DataType::Struct(entries_fields(&arg_types[0])),
+                false,
+            )),
+            false,
+        ))
+    }
+
+    fn state_fields(&self, args: StateFieldsArgs) -> Result<Vec<FieldRef>> {
+        let input_type = args.input_fields[0].data_type().clone();
+        Ok(vec![
+            Field::new_list(
+                format_state_name(args.name, "histogram_values"),
+                Field::new_list_field(input_type, true),
+                true,
+            )
+            .into(),
+            Field::new_list(
+                format_state_name(args.name, "histogram_counts"),
+                Field::new_list_field(DataType::UInt64, true),
+                true,
+            )
+            .into(),
+        ])
+    }
+
+    fn accumulator(&self, args: AccumulatorArgs) -> Result<Box<dyn Accumulator>> {
+        if args.is_distinct {
+            return not_impl_err!("HISTOGRAM(DISTINCT) aggregations are not available");
+        }
+
+        Ok(Box::new(HistogramAccumulator::new(
+            args.expr_fields[0].data_type().clone(),
+            args.return_field.data_type().clone(),
+        )))
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+/// Fields of the entries of the map returned by `histogram`
+fn entries_fields(key_type: &DataType) -> Fields {
+    Fields::from(vec![
+        Field::new("key", key_type.clone(), false),
+        Field::new("value", DataType::UInt64, true),
+    ])
+}
+
+/// Counts the occurrences of every distinct value.
+///
+/// Counts are decremented on retraction, so this accumulator can be used
+/// for sliding window frames.
+#[derive(Debug)]
+struct HistogramAccumulator {
+    data_type: DataType,
+    return_type: DataType,
+    counts: HashMap<ScalarValue, u64>,
+}
+
+impl HistogramAccumulator {
+    fn new(data_type: DataType, return_type: DataType) -> Self {
+        Self {
+            data_type,
+            return_type,
+            counts: HashMap::new(),
+        }
+    }
+}
+
+#[allow(clippy::allow_attributes, clippy::mutable_key_type)] // ScalarValue has interior mutability but is intentionally used as hash key
+impl Accumulator for HistogramAccumulator {
+    fn state(&mut self) -> Result<Vec<ScalarValue>> {
+        let (values, counts): (Vec<_>, Vec<_>) = self
+            .counts
+            .iter()
+            .map(|(value, count)| (value.clone(), ScalarValue::from(*count)))
+            .unzip();
+
+        Ok(vec![
+            ScalarValue::List(ScalarValue::new_list_nullable(&values, &self.data_type)),
+            ScalarValue::List(ScalarValue::new_list_nullable(&counts, &DataType::UInt64)),
+        ])
+    }
+
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        let array = &values[0];
+        for i in 0..array.len() {
+            if array.is_valid(i) {
+                let value = ScalarValue::try_from_array(array, i)?;
+                *self.counts.entry(value).or_default() += 1;
+            }
+        }
+        Ok(())
+    }
+
+    fn retract_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        let array = &values[0];
+        for i in 0..array.len() {
+            if array.is_valid(i) {
+                let value = ScalarValue::try_from_array(array, i)?;
+                if let Some(count) = self.counts.get_mut(&value) {
+                    *count -= 1;
+                    if *count == 0 {
+                        self.counts.remove(&value);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        let values = states[0].as_list::<i32>();
+        let counts = states[1].as_list::<i32>();
+
+        for (values, counts) in values.iter().zip(counts.iter()) {
+            let (Some(values), Some(counts)) = (values, counts) else {
+                continue;
+            };
+            let counts = counts.as_primitive::<UInt64Type>();
+            for i in 0..values.len() {
+                let value = ScalarValue::try_from_array(&values, i)?;
+                *self.counts.entry(value).or_default() += counts.value(i);
+            }
+        }
+        Ok(())
+    }
+
+    fn evaluate(&mut self) -> Result<ScalarValue> {
+        if self.counts.is_empty() {
+            return ScalarValue::try_from(&self.return_type);
+        }
+
+        let mut entries = self.counts.iter().collect::<Vec<_>>();
+        entries.sort_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+
+        let keys =
+            ScalarValue::iter_to_array(entries.iter().map(|(key, _)| (*key).clone()))?;
+        let counts =
+            UInt64Array::from_iter_values(entries.iter().map(|(_, count)| **count));
+        let DataType::Map(entries_field, sorted) = &self.return_type else {
+            unreachable!("histogram always returns a map")
+        };
+        let entries = StructArray::try_new(
+            entries_fields(&self.data_type),
+            vec![keys, Arc::new(counts)],
+            None,
+        )?;
+        let map = MapArray::try_new(
+            Arc::clone(entries_field),
+            OffsetBuffer::from_lengths([entries.len()]),
+            entries,
+            None,
+            *sorted,
+        )?;
+        Ok(ScalarValue::Map(Arc::new(map)))
+    }
+
+    fn size(&self) -> usize {
+        size_of_val(self)
+            + self.counts.capacity() * size_of::<(ScalarValue, u64)>()
+            + self
+                .counts
+                .keys()
+                .map(|value| value.size() - size_of_val(value))
+                .sum::<usize>()
+    }
+
+    fn supports_retract_batch(&self) -> bool {
+        true
+    }
+}
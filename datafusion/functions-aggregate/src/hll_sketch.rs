@@ -0,0 +1,522 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! HyperLogLog sketches exposed as SQL values: [`HllSketch`], [`HllMerge`]
+//! and [`HllEstimate`].
+//!
+//! Sketches are serialized to a stable binary format so they can be stored,
+//! for example in Parquet files, and merged later. The format consists of the
+//! magic bytes `HLL`, a format version byte (currently `1`), a byte
+//! identifying the hash function of the values (currently `1` for XXH64 with
+//! seed 0) and the HLL registers, one byte per register.
+
+use std::fmt::Debug;
+use std::mem::size_of_val;
+use std::sync::{Arc, LazyLock};
+
+use arrow::array::{ArrayRef, BinaryArray, UInt64Array};
+use arrow::datatypes::{DataType, Field, FieldRef};
+use datafusion_common::cast::as_binary_array;
+use datafusion_common::utils::take_function_args;
+use datafusion_common::{
+    Result, ScalarValue, downcast_value, exec_datafusion_err, exec_err,
+    internal_datafusion_err, internal_err, plan_err,
+};
+use datafusion_expr::function::{AccumulatorArgs, StateFieldsArgs};
+use datafusion_expr::utils::format_state_name;
+use datafusion_expr::{
+    Accumulator, AggregateUDFImpl, ColumnarValue, Documentation, Expr,
+    ScalarFunctionArgs, ScalarUDF, ScalarUDFImpl, Signature, Volatility,
+};
+use datafusion_macros::user_doc;
+
+use crate::approx_distinct::new_hll_accumulator;
+use crate::hyperloglog::{HLL_HASH_ID, HyperLogLog, SketchHasher};
+use crate::utils::coerce_sketch_type;
+
+make_udaf_expr_and_func!(
+    HllSketch,
+    hll_sketch,
+    expression,
+    "Creates a HyperLogLog sketch of the input values",
+    hll_sketch_udaf
+);
+
+make_udaf_expr_and_func!(
+    HllMerge,
+    hll_merge,
+    sketch,
+    "Merges HyperLogLog sketches into a single sketch",
+    hll_merge_udaf
+);
+
+/// Returns a [`ScalarUDF`] for [`HllEstimate`]
+pub fn hll_estimate_udf() -> Arc<ScalarUDF> {
+    static INSTANCE: LazyLock<Arc<ScalarUDF>> =
+        LazyLock::new(|| Arc::new(ScalarUDF::from(HllEstimate::new())));
+    Arc::clone(&INSTANCE)
+}
+
+/// Returns the estimated number of distinct values of a HyperLogLog sketch
+pub fn hll_estimate(sketch: Expr) -> Expr {
+    hll_estimate_udf().call(vec![sketch])
+}
+
+/// Magic bytes identifying a serialized HLL sketch
+const HLL_SKETCH_MAGIC: &[u8; 3] = b"HLL";
+/// Version of the serialized HLL sketch format
+const HLL_SKETCH_FORMAT_VERSION: u8 = 1;
+
+/// Serializes HLL registers to a sketch
+fn encode_hll_sketch(registers: &[u8]) -> ScalarValue {
+    let mut bytes = Vec::with_capacity(HLL_SKETCH_MAGIC.len() + 2 + registers.len());
+    bytes.extend_from_slice(HLL_SKETCH_MAGIC);
+    bytes.push(HLL_SKETCH_FORMAT_VERSION);
+    bytes.push(HLL_HASH_ID);
+    bytes.extend_from_slice(registers);
+    ScalarValue::Binary(Some(bytes))
+}
+
+/// Deserializes a sketch created by [`encode_hll_sketch`]
+fn decode_hll_sketch(bytes: &[u8]) -> Result<HyperLogLog<()>> {
+    let Some(body) = bytes
+        .strip_prefix(HLL_SKETCH_MAGIC.as_slice())
+        .and_then(|b| b.strip_prefix(&[HLL_SKETCH_FORMAT_VERSION]))
+    else {
+        return exec_err!("Invalid HLL sketch: unknown header");
+    };
+    let Some((&hash_id, registers)) = body.split_first() else {
+        return exec_err!("Invalid HLL sketch: missing hash function");
+    };
+    if hash_id != HLL_HASH_ID {
+        return exec_err!("Invalid HLL sketch: unknown hash function {hash_id}");
+    }
+    HyperLogLog::try_new_from_untrusted(registers)
+        .ok_or_else(|| exec_datafusion_err!("Invalid HLL sketch: invalid registers"))
+}
+
+/// Coerces the input of `hll_sketch` so that equal values of different types
+/// result in equal hashes, and thus in mergeable sketches.
+fn coerce_hll_input_type(arg_type: &DataType) -> Result<DataType> {
+    match arg_type {
+        DataType::Null
+        | DataType::Int8
+        | DataType::Int16
+        | DataType::Int32
+        | DataType::Int64 => Ok(DataType::Int64),
+        DataType::UInt8 | DataType::UInt16 | DataType::UInt32 | DataType::UInt64 => {
+            Ok(DataType::UInt64)
+        }
+        DataType::Utf8View => Ok(DataType::Utf8),
+        DataType::BinaryView | DataType::FixedSizeBinary(_) => Ok(DataType::Binary),
+        DataType::Utf8
+        | DataType::LargeUtf8
+        | DataType::Binary
+        | DataType::LargeBinary
+        | DataType::Date32
+        | DataType::Date64
+        | DataType::Time32(_)
+        | DataType::Time64(_)
+        | DataType::Timestamp(_, _) => Ok(arg_type.clone()),
+        DataType::Dictionary(_, value_type) => coerce_hll_input_type(value_type),
+        other => plan_err!("hll_sketch does not support input type {other}"),
+    }
+}
+
+fn hll_state_fields(name: &str) -> Vec<FieldRef> {
+    vec![
+        Field::new(
+            format_state_name(name, "hll_registers"),
+            DataType::Binary,
+            false,
+        )
+        .into(),
+    ]
+}
+
+#[user_doc(
+    doc_section(label = "Approximate Functions"),
+    description = "Creates a HyperLogLog sketch of the input values. The sketch is a binary value that can be stored and later combined with `hll_merge` and evaluated with `hll_estimate`. Sketches of values of different integer types, or of different string types, can be merged.",
+    syntax_example = "hll_sketch(expression)",
+    sql_example = r#"```sql
+> SELECT day, hll_sketch(user_id) AS users FROM events GROUP BY day;
++------------+--------------------------------+
+| day        | users                          |
++------------+--------------------------------+
+| 2024-01-01 | 484c4c0101000000000000000000.. |
++------------+--------------------------------+
+```"#,
+    standard_argument(name = "expression",)
+)]
+/// HLL_SKETCH aggregate expression
+#[derive(PartialEq, Eq, Hash, Debug)]
+pub struct HllSketch {
+    signature: Signature,
+}
+
+impl Default for HllSketch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HllSketch {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::user_defined(Volatility::Immutable),
+        }
+    }
+}
+
+impl AggregateUDFImpl for HllSketch {
+    fn name(&self) -> &str {
+        "hll_sketch"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        let [arg_type] = take_function_args(self.name(), arg_types)?;
+        Ok(vec![coerce_hll_input_type(arg_type)?])
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Binary)
+    }
+
+    fn is_nullable(&self) -> bool {
+        false
+    }
+
+    fn state_fields(&self, args: StateFieldsArgs) -> Result<Vec<FieldRef>> {
+        Ok(hll_state_fields(args.name))
+    }
+
+    fn accumulator(&self, acc_args: AccumulatorArgs) -> Result<Box<dyn Accumulator>> {
+        // Adding a value to a HyperLogLog is idempotent, so DISTINCT does not
+        // change the result
+        let data_type = acc_args.expr_fields[0].data_type();
+        Ok(Box::new(HllSketchAccumulator {
+            inner: new_hll_accumulator::<SketchHasher>(data_type, self.name())?,
+        }))
+    }
+
+    fn default_value(&self, _data_type: &DataType) -> Result<ScalarValue> {
+        Ok(encode_hll_sketch(HyperLogLog::<()>::new().as_ref()))
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+/// Accumulator for `hll_sketch`, wrapping an `approx_distinct` accumulator
+/// but evaluating to the serialized registers instead of the estimate.
+#[derive(Debug)]
+struct HllSketchAccumulator {
+    inner: Box<dyn Accumulator>,
+}
+
+impl Accumulator for HllSketchAccumulator {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        self.inner.update_batch(values)
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        self.inner.merge_batch(states)
+    }
+
+    fn state(&mut self) -> Result<Vec<ScalarValue>> {
+        self.inner.state()
+    }
+
+    fn evaluate(&mut self) -> Result<ScalarValue> {
+        match self.inner.state()?.as_slice() {
+            [ScalarValue::Binary(Some(registers))] => Ok(encode_hll_sketch(registers)),
+            other => internal_err!("Unexpected HLL state: {other:?}"),
+        }
+    }
+
+    fn size(&self) -> usize {
+        size_of_val(self) + self.inner.size()
+    }
+}
+
+#[user_doc(
+    doc_section(label = "Approximate Functions"),
+    description = "Merges HyperLogLog sketches created by `hll_sketch` or `hll_merge` into a single sketch, for example to roll up daily sketches into monthly ones. NULL sketches are ignored.",
+    syntax_example = "hll_merge(sketch)",
+    sql_example = r#"```sql
+> SELECT hll_estimate(hll_merge(users)) FROM daily_users;
++--------------------------------------------+
+| hll_estimate(hll_merge(daily_users.users)) |
++--------------------------------------------+
+| 42                                         |
++--------------------------------------------+
+```"#,
+    argument(name = "sketch", description = "HyperLogLog sketch to merge.")
+)]
+/// HLL_MERGE aggregate expression
+#[derive(PartialEq, Eq, Hash, Debug)]
+pub struct HllMerge {
+    signature: Signature,
+}
+
+impl Default for HllMerge {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HllMerge {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::user_defined(Volatility::Immutable),
+        }
+    }
+}
+
+impl AggregateUDFImpl for HllMerge {
+    fn name(&self) -> &str {
+        "hll_merge"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        let [arg_type] = take_function_args(self.name(), arg_types)?;
+        Ok(vec![coerce_sketch_type(arg_type, self.name())?])
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Binary)
+    }
+
+    fn is_nullable(&self) -> bool {
+        false
+    }
+
+    fn state_fields(&self, args: StateFieldsArgs) -> Result<Vec<FieldRef>> {
+        Ok(hll_state_fields(args.name))
+    }
+
+    fn accumulator(&self, _acc_args: AccumulatorArgs) -> Result<Box<dyn Accumulator>> {
+        // Merging is idempotent, so DISTINCT does not change the result
+        Ok(Box::new(HllMergeAccumulator::default()))
+    }
+
+    fn default_value(&self, _data_type: &DataType) -> Result<ScalarValue> {
+        Ok(encode_hll_sketch(HyperLogLog::<()>::new().as_ref()))
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+#[derive(Debug, Default)]
+struct HllMergeAccumulator {
+    hll: HyperLogLog<()>,
+}
+
+impl Accumulator for HllMergeAccumulator {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        let sketches = downcast_value!(values[0], BinaryArray);
+        for sketch in sketches.iter().flatten() {
+            self.hll.merge(&decode_hll_sketch(sketch)?);
+        }
+        Ok(())
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        let registers = downcast_value!(states[0], BinaryArray);
+        for registers in registers.iter() {
+            let registers = registers.ok_or_else(|| {
+                internal_datafusion_err!("Impossibly got empty binary array from states")
+            })?;
+            self.hll.merge(&registers.try_into()?);
+        }
+        Ok(())
+    }
+
+    fn state(&mut self) -> Result<Vec<ScalarValue>> {
+        Ok(vec![ScalarValue::from(&self.hll)])
+    }
+
+    fn evaluate(&mut self) -> Result<ScalarValue> {
+        Ok(encode_hll_sketch(self.hll.as_ref()))
+    }
+
+    fn size(&self) -> usize {
+        // HLL has static size
+        size_of_val(self)
+    }
+}
+
+#[user_doc(
+    doc_section(label = "Other Functions"),
+    description = "Returns the estimated number of distinct values of a HyperLogLog sketch created by `hll_sketch` or `hll_merge`.",
+    syntax_example = "hll_estimate(sketch)",
+    sql_example = r#"```sql
+> SELECT day, hll_estimate(users) FROM daily_users;
++------------+------------------------------------+
+| day        | hll_estimate(daily_users.users)    |
++------------+------------------------------------+
+| 2024-01-01 | 42                                 |
++------------+------------------------------------+
+```"#,
+    argument(name = "sketch", description = "HyperLogLog sketch to evaluate.")
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct HllEstimate {
+    signature: Signature,
+}
+
+impl Default for HllEstimate {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HllEstimate {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::user_defined(Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for HllEstimate {
+    fn name(&self) -> &str {
+        "hll_estimate"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        let [arg_type] = take_function_args(self.name(), arg_types)?;
+        Ok(vec![coerce_sketch_type(arg_type, self.name())?])
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::UInt64)
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        let [sketch] = take_function_args(self.name(), &args.args)?;
+        let estimate = |sketch: &[u8]| -> Result<u64> {
+            Ok(decode_hll_sketch(sketch)?.count() as u64)
+        };
+
+        match sketch {
+            ColumnarValue::Scalar(ScalarValue::Binary(sketch)) => {
+                let estimate = sketch.as_deref().map(estimate).transpose()?;
+                Ok(ColumnarValue::Scalar(ScalarValue::UInt64(estimate)))
+            }
+            ColumnarValue::Array(array) => {
+                let estimates = as_binary_array(array)?
+                    .iter()
+                    .map(|sketch| sketch.map(estimate).transpose())
+                    .collect::<Result<UInt64Array>>()?;
+                Ok(ColumnarValue::Array(Arc::new(estimates)))
+            }
+            other => internal_err!("Unexpected argument for hll_estimate: {other:?}"),
+        }
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::AsArray;
+    use arrow::array::{Array, Int64Array, StringArray};
+    use arrow::datatypes::UInt64Type;
+
+    fn sketch_of(values: ArrayRef) -> Result<ScalarValue> {
+        let mut acc = HllSketchAccumulator {
+            inner: new_hll_accumulator::<SketchHasher>(values.data_type(), "hll_sketch")?,
+        };
+        acc.update_batch(&[values])?;
+        acc.evaluate()
+    }
+
+    #[test]
+    fn hll_sketch_roundtrip() -> Result<()> {
+        let values: ArrayRef = Arc::new(Int64Array::from_iter_values(0..1000));
+        let ScalarValue::Binary(Some(sketch)) = sketch_of(values)? else {
+            unreachable!()
+        };
+        assert_eq!(&sketch[..5], b"HLL\x01\x01");
+        let estimate = decode_hll_sketch(&sketch)?.count();
+        assert!(
+            (980..=1020).contains(&estimate),
+            "unexpected estimate {estimate}"
+        );
+
+        assert!(decode_hll_sketch(&sketch[..sketch.len() - 1]).is_err());
+        assert!(decode_hll_sketch(b"TDG\x01").is_err());
+        let mut invalid = sketch.clone();
+        invalid[5] = u8::MAX;
+        assert!(decode_hll_sketch(&invalid).is_err());
+        let mut unknown_hash = sketch.clone();
+        unknown_hash[4] = 2;
+        assert!(decode_hll_sketch(&unknown_hash).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn hll_merge_and_estimate() -> Result<()> {
+        let first: ArrayRef = Arc::new(StringArray::from(vec!["a", "b", "c"]));
+        let second: ArrayRef =
+            Arc::new(StringArray::from(vec![Some("c"), None, Some("d")]));
+        let sketches =
+            ScalarValue::iter_to_array([sketch_of(first)?, sketch_of(second)?])?;
+
+        // merge the sketches in two partitions
+        let mut partial = HllMergeAccumulator::default();
+        partial.update_batch(&[sketches.slice(0, 1)])?;
+        let state = partial.state()?[0].to_array()?;
+        let mut merged = HllMergeAccumulator::default();
+        merged.update_batch(&[sketches.slice(1, 1)])?;
+        merged.merge_batch(&[state])?;
+
+        let merged = merged.evaluate()?.to_array()?;
+        let estimate = HllEstimate::new().invoke_with_args(ScalarFunctionArgs {
+            args: vec![ColumnarValue::Array(merged)],
+            arg_fields: vec![Field::new("sketch", DataType::Binary, true).into()],
+            number_rows: 1,
+            return_field: Field::new("estimate", DataType::UInt64, true).into(),
+            config_options: Arc::new(Default::default()),
+        })?;
+        let ColumnarValue::Array(estimate) = estimate else {
+            unreachable!()
+        };
+        assert_eq!(estimate.as_primitive::<UInt64Type>().value(0), 4);
+        assert_eq!(estimate.len(), 1);
+        Ok(())
+    }
+}
@@ -34,10 +34,13 @@
 //!
 //! This module also borrows some code structure from [pdatastructs.rs](https://github.com/crepererum/pdatastructs.rs/blob/3997ed50f6b6871c9e53c4c5e0f48f431405fc63/src/hyperloglog.rs).
 
+use std::fmt::Debug;
 use std::hash::BuildHasher;
 use std::hash::Hash;
 use std::marker::PhantomData;
 
+use twox_hash::XxHash64;
+
 /// The greater is P, the smaller the error.
 const HLL_P: usize = 14_usize;
 /// The number of bits of the hash value used determining the number of leading zeros
@@ -61,9 +64,83 @@ where
 /// shared across cluster, this HLL_HASH_STATE will have to be consistent across all
 /// parties otherwise we might have corruption. So ideally for later this seed
 /// shall be part of the serialized form (or stay unchanged across versions).
+///
+/// Sketches exposed to users (see `hll_sketch`) are hashed with
+/// [`SketchHasher`] instead.
 pub(crate) const HLL_HASH_STATE: foldhash::quality::FixedState =
     foldhash::quality::FixedState::with_seed(0);
 
+/// Identifies the hash function of [`SketchHasher`], XXH64 with seed 0.
+///
+/// The HLL registers are exposed to users as sketches (see `hll_sketch`),
+/// which may be persisted and merged with sketches created by other versions.
+/// Sketches therefore record this id, which must change whenever the hash
+/// function or the byte encoding of the values changes.
+pub(crate) const HLL_HASH_ID: u8 = 1;
+
+/// Hash function used to add values to a [`HyperLogLog`]
+pub(crate) trait HllHasher: Debug + Send + Sync + 'static {
+    fn hash_value<T: HllValue + ?Sized>(value: &T) -> u64;
+}
+
+/// Hashes values with [`HLL_HASH_STATE`]
+#[derive(Debug)]
+pub(crate) struct InternalHasher;
+
+impl HllHasher for InternalHasher {
+    #[inline]
+    fn hash_value<T: HllValue + ?Sized>(value: &T) -> u64 {
+        HLL_HASH_STATE.hash_one(value)
+    }
+}
+
+/// Hashes values with [`HllValue::stable_hash`], see [`HLL_HASH_ID`]
+#[derive(Debug)]
+pub(crate) struct SketchHasher;
+
+impl HllHasher for SketchHasher {
+    #[inline]
+    fn hash_value<T: HllValue + ?Sized>(value: &T) -> u64 {
+        value.stable_hash()
+    }
+}
+
+/// A value that can be added to a [`HyperLogLog`].
+///
+/// Besides [`Hash`], whose output is not stable across versions, values
+/// define a byte encoding that is hashed with XXH64 for persisted sketches.
+pub(crate) trait HllValue: Hash {
+    fn stable_hash(&self) -> u64;
+}
+
+impl HllValue for [u8] {
+    fn stable_hash(&self) -> u64 {
+        XxHash64::oneshot(0, self)
+    }
+}
+
+impl HllValue for str {
+    /// Strings are encoded as UTF-8
+    fn stable_hash(&self) -> u64 {
+        XxHash64::oneshot(0, self.as_bytes())
+    }
+}
+
+/// Integers are encoded as little endian bytes
+macro_rules! impl_hll_value_for_int {
+    ($($t:ty),*) => {
+        $(
+            impl HllValue for $t {
+                fn stable_hash(&self) -> u64 {
+                    XxHash64::oneshot(0, &self.to_le_bytes())
+                }
+            }
+        )*
+    };
+}
+
+impl_hll_value_for_int!(i16, i32, i64, i128, u16, u32, u64, u128);
+
 impl<T> Default for HyperLogLog<T>
 where
     T: Hash + ?Sized,
@@ -93,6 +170,17 @@ where
         }
     }
 
+    /// Creates a HyperLogLog from registers read from an untrusted source,
+    /// such as a persisted sketch. Returns `None` if the registers have the
+    /// wrong length or contain values that can not result from [`Self::add`].
+    pub(crate) fn try_new_from_untrusted(registers: &[u8]) -> Option<Self> {
+        let registers: [u8; NUM_REGISTERS] = registers.try_into().ok()?;
+        registers
+            .iter()
+            .all(|r| *r as usize <= HLL_Q + 1)
+            .then(|| Self::new_with_registers(registers))
+    }
+
     /// choice of hash function: foldhash is already an dependency
     /// and it fits the requirements of being a 64bit hash with
     /// reasonable performance.
@@ -109,8 +197,9 @@ where
 
     /// Adds a pre-computed hash value directly to the HyperLogLog.
     ///
-    /// The hash should be computed using [`HLL_HASH_STATE`], the same hasher used
-    /// by [`Self::add`].
+    /// The hash should be computed using an [`HllHasher`], and all values
+    /// added to the same HyperLogLog must use the same one. [`Self::add`]
+    /// hashes like [`InternalHasher`].
     #[inline]
     pub(crate) fn add_hashed(&mut self, hash: u64) {
         let index = (hash & HLL_P_MASK) as usize;
@@ -238,7 +327,7 @@ where
 
 #[cfg(test)]
 mod tests {
-    use super::{HyperLogLog, NUM_REGISTERS};
+    use super::{HllValue, HyperLogLog, NUM_REGISTERS};
 
     fn compare_with_delta(got: usize, expected: usize) {
         let expected = expected as f64;
@@ -371,4 +460,19 @@ mod tests {
         }
         compare_with_delta(hll.count(), 1000);
     }
+
+    #[test]
+    fn test_stable_hash() {
+        // Known XXH64 values with seed 0, which must never change
+        assert_eq!(b""[..].stable_hash(), 0xEF46DB3751D8E999);
+        assert_eq!(b"a"[..].stable_hash(), 0xD24EC4F1A98C6E5B);
+        assert_eq!("abc".stable_hash(), 0x44BC2CF5AD770999);
+        assert_eq!(
+            "Nobody inspects the spammish repetition".stable_hash(),
+            0xFBCEA83C8A378BF1
+        );
+        // Integers are hashed as little endian bytes
+        assert_eq!(42_i64.stable_hash(), 0xB556806FB6D14353);
+        assert_eq!("abc".stable_hash(), b"abc"[..].stable_hash());
+    }
 }
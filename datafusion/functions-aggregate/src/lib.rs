@@ -65,10 +65,12 @@
 #[macro_use]
 pub mod macros;
 
+pub mod any_value;
 pub mod approx_distinct;
 pub mod approx_median;
 pub mod approx_percentile_cont;
 pub mod approx_percentile_cont_with_weight;
+pub mod approx_top_k;
 pub mod array_agg;
 pub mod average;
 pub mod bit_and_or_xor;
@@ -78,15 +80,21 @@ pub mod count;
 pub mod covariance;
 pub mod first_last;
 pub mod grouping;
+pub mod histogram;
+pub mod hll_sketch;
 pub mod hyperloglog;
 pub mod median;
 pub mod min_max;
+pub mod mode;
+pub mod moments;
 pub mod nth_value;
 pub mod percentile_cont;
+pub mod percentile_disc;
 pub mod regr;
 pub mod stddev;
 pub mod string_agg;
 pub mod sum;
+pub mod tdigest_sketch;
 pub mod variance;
 
 pub mod planner;
@@ -96,16 +104,18 @@ use crate::approx_percentile_cont::approx_percentile_cont_udaf;
 use crate::approx_percentile_cont_with_weight::approx_percentile_cont_with_weight_udaf;
 use datafusion_common::Result;
 use datafusion_execution::FunctionRegistry;
-use datafusion_expr::AggregateUDF;
+use datafusion_expr::{AggregateUDF, ScalarUDF};
 use log::debug;
 use std::sync::Arc;
 
 /// Fluent-style API for creating `Expr`s
 pub mod expr_fn {
+    pub use super::any_value::any_value;
     pub use super::approx_distinct::approx_distinct;
     pub use super::approx_median::approx_median;
     pub use super::approx_percentile_cont::approx_percentile_cont;
     pub use super::approx_percentile_cont_with_weight::approx_percentile_cont_with_weight;
+    pub use super::approx_top_k::approx_top_k;
     pub use super::array_agg::array_agg;
     pub use super::average::avg;
     pub use super::average::avg_distinct;
@@ -122,11 +132,21 @@ pub mod expr_fn {
     pub use super::first_last::first_value;
     pub use super::first_last::last_value;
     pub use super::grouping::grouping;
+    pub use super::histogram::histogram;
+    pub use super::hll_sketch::hll_estimate;
+    pub use super::hll_sketch::hll_merge;
+    pub use super::hll_sketch::hll_sketch;
     pub use super::median::median;
     pub use super::min_max::max;
+    pub use super::min_max::max_by;
     pub use super::min_max::min;
+    pub use super::min_max::min_by;
+    pub use super::mode::mode;
+    pub use super::moments::kurtosis;
+    pub use super::moments::skewness;
     pub use super::nth_value::nth_value;
     pub use super::percentile_cont::percentile_cont;
+    pub use super::percentile_disc::percentile_disc;
     pub use super::regr::regr_avgx;
     pub use super::regr::regr_avgy;
     pub use super::regr::regr_count;
@@ -140,6 +160,9 @@ pub mod expr_fn {
     pub use super::stddev::stddev_pop;
     pub use super::sum::sum;
     pub use super::sum::sum_distinct;
+    pub use super::tdigest_sketch::tdigest_merge;
+    pub use super::tdigest_sketch::tdigest_quantile;
+    pub use super::tdigest_sketch::tdigest_sketch;
     pub use super::variance::var_pop;
     pub use super::variance::var_sample;
 }
@@ -156,6 +179,9 @@ pub fn all_default_aggregate_functions() -> Vec<Arc<AggregateUDF>> {
         sum::sum_udaf(),
         min_max::max_udaf(),
         min_max::min_udaf(),
+        min_max::max_by_udaf(),
+        min_max::min_by_udaf(),
+        any_value::any_value_udaf(),
         median::median_udaf(),
         count::count_udaf(),
         regr::regr_slope_udaf(),
@@ -171,11 +197,21 @@ pub fn all_default_aggregate_functions() -> Vec<Arc<AggregateUDF>> {
         variance::var_pop_udaf(),
         stddev::stddev_udaf(),
         stddev::stddev_pop_udaf(),
+        moments::skewness_udaf(),
+        moments::kurtosis_udaf(),
         approx_median::approx_median_udaf(),
         approx_distinct::approx_distinct_udaf(),
         approx_percentile_cont_udaf(),
         approx_percentile_cont_with_weight_udaf(),
+        approx_top_k::approx_top_k_udaf(),
+        hll_sketch::hll_sketch_udaf(),
+        hll_sketch::hll_merge_udaf(),
+        tdigest_sketch::tdigest_sketch_udaf(),
+        tdigest_sketch::tdigest_merge_udaf(),
         percentile_cont::percentile_cont_udaf(),
+        percentile_disc::percentile_disc_udaf(),
+        mode::mode_udaf(),
+        histogram::histogram_udaf(),
         string_agg::string_agg_udaf(),
         bit_and_or_xor::bit_and_udaf(),
         bit_and_or_xor::bit_or_udaf(),
@@ -188,6 +224,15 @@ pub fn all_default_aggregate_functions() -> Vec<Arc<AggregateUDF>> {
     ]
 }
 
+/// Returns all default scalar functions, which evaluate the sketches created
+/// by aggregate functions such as `hll_sketch`
+pub fn all_default_scalar_functions() -> Vec<Arc<ScalarUDF>> {
+    vec![
+        hll_sketch::hll_estimate_udf(),
+        tdigest_sketch::tdigest_quantile_udf(),
+    ]
+}
+
 /// Registers all enabled packages with a [`FunctionRegistry`]
 pub fn register_all(registry: &mut dyn FunctionRegistry) -> Result<()> {
     let functions: Vec<Arc<AggregateUDF>> = all_default_aggregate_functions();
@@ -200,6 +245,15 @@ pub fn register_all(registry: &mut dyn FunctionRegistry) -> Result<()> {
         Ok(()) as Result<()>
     })?;
 
+    let functions: Vec<Arc<ScalarUDF>> = all_default_scalar_functions();
+    functions.into_iter().try_for_each(|udf| {
+        let existing_udf = registry.register_udf(udf)?;
+        if let Some(existing_udf) = existing_udf {
+            debug!("Overwrite existing UDF: {}", existing_udf.name());
+        }
+        Ok(()) as Result<()>
+    })?;
+
     Ok(())
 }
 
@@ -17,7 +17,9 @@
 
 //! [`Max`] and [`MaxAccumulator`] accumulator for the `max` function
 //! [`Min`] and [`MinAccumulator`] accumulator for the `min` function
+//! [`MaxBy`] and [`MinBy`] for the `max_by` and `min_by` functions
 
+mod min_max_by;
 mod min_max_bytes;
 mod min_max_struct;
 
@@ -109,6 +111,23 @@ impl Default for Max {
         Self::new()
     }
 }
+/// Returns true if `new` should replace `cur` as the running maximum.
+///
+/// Incomparable values (e.g. `NaN`) also replace `cur`. This is shared by the
+/// `max` and `max_by` group accumulators so both pick the same row.
+#[inline]
+pub(crate) fn is_new_max<T: PartialOrd>(cur: &T, new: &T) -> bool {
+    matches!(new.partial_cmp(cur), Some(Ordering::Greater) | None)
+}
+
+/// Returns true if `new` should replace `cur` as the running minimum.
+///
+/// See [`is_new_max`] for the handling of incomparable values.
+#[inline]
+pub(crate) fn is_new_min<T: PartialOrd>(cur: &T, new: &T) -> bool {
+    matches!(new.partial_cmp(cur), Some(Ordering::Less) | None)
+}
+
 /// Creates a [`PrimitiveGroupsAccumulator`] for computing `MAX`
 /// the specified [`ArrowPrimitiveType`].
 ///
@@ -117,12 +136,8 @@ macro_rules! primitive_max_accumulator {
     ($DATA_TYPE:ident, $NATIVE:ident, $PRIMTYPE:ident) => {{
         Ok(Box::new(
             PrimitiveGroupsAccumulator::<$PRIMTYPE, _>::new($DATA_TYPE, |cur, new| {
-                match (new).partial_cmp(cur) {
-                    Some(Ordering::Greater) | None => {
-                        // new is Greater or None
-                        *cur = new
-                    }
-                    _ => {}
+                if is_new_max(cur, &new) {
+                    *cur = new
                 }
             })
             // Initialize each accumulator to $NATIVE::MIN
@@ -140,12 +155,8 @@ macro_rules! primitive_min_accumulator {
     ($DATA_TYPE:ident, $NATIVE:ident, $PRIMTYPE:ident) => {{
         Ok(Box::new(
             PrimitiveGroupsAccumulator::<$PRIMTYPE, _>::new(&$DATA_TYPE, |cur, new| {
-                match (new).partial_cmp(cur) {
-                    Some(Ordering::Less) | None => {
-                        // new is Less or NaN
-                        *cur = new
-                    }
-                    _ => {}
+                if is_new_min(cur, &new) {
+                    *cur = new
                 }
             })
             // Initialize each accumulator to $NATIVE::MAX
@@ -993,6 +1004,8 @@ make_udaf_expr_and_func!(
     min_udaf
 );
 
+pub use min_max_by::{MaxBy, MinBy, max_by, max_by_udaf, min_by, min_by_udaf};
+
 // Re-export accumulators from the common module for backwards compatibility
 pub use datafusion_functions_aggregate_common::min_max::{
     MaxAccumulator, MinAccumulator,
@@ -0,0 +1,887 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`MaxBy`] and [`MinBy`]: the value of one column at the row where
+//! another column is largest / smallest
+
+use std::cmp::Ordering;
+use std::mem::{size_of, size_of_val};
+use std::sync::Arc;
+
+use arrow::array::{
+    Array, ArrayRef, ArrowPrimitiveType, AsArray, BooleanArray, BooleanBufferBuilder,
+    PrimitiveArray, make_comparator,
+};
+use arrow::buffer::NullBuffer;
+use arrow::compute::{SortOptions, sort_to_indices};
+use arrow::datatypes::{
+    DataType, Date32Type, Date64Type, Decimal32Type, Decimal64Type, Decimal128Type,
+    Decimal256Type, DurationMicrosecondType, DurationMillisecondType,
+    DurationNanosecondType, DurationSecondType, Field, FieldRef, Float16Type,
+    Float32Type, Float64Type, Int8Type, Int16Type, Int32Type, Int64Type,
+    Time32MillisecondType, Time32SecondType, Time64MicrosecondType, Time64NanosecondType,
+    TimeUnit, TimestampMicrosecondType, TimestampMillisecondType,
+    TimestampNanosecondType, TimestampSecondType, UInt8Type, UInt16Type, UInt32Type,
+    UInt64Type,
+};
+use datafusion_common::{Result, ScalarValue, internal_err, not_impl_err, plan_err};
+use datafusion_expr::function::{AccumulatorArgs, StateFieldsArgs};
+use datafusion_expr::utils::{AggregateOrderSensitivity, format_state_name};
+use datafusion_expr::{
+    Accumulator, AggregateUDFImpl, Documentation, EmitTo, GroupsAccumulator,
+    ReversedUDAF, Signature, Volatility,
+};
+use datafusion_functions_aggregate_common::min_max::{max_batch, min_batch};
+use datafusion_macros::user_doc;
+
+use super::{is_new_max, is_new_min};
+use crate::first_last::state::{
+    ValueState, new_value_state, take_need, value_state_supported,
+};
+use crate::utils::validate_positive_integer_expr;
+
+make_udaf_expr_and_func!(
+    MaxBy,
+    max_by,
+    value by,
+    "Returns the value of `value` at the row where `by` is largest.",
+    max_by_udaf
+);
+
+make_udaf_expr_and_func!(
+    MinBy,
+    min_by,
+    value by,
+    "Returns the value of `value` at the row where `by` is smallest.",
+    min_by_udaf
+);
+
+#[user_doc(
+    doc_section(label = "General Functions"),
+    description = "Returns the value of `value` at the row where `by` is largest. Rows where `by` is NULL are ignored. If `n` is given, returns a list of the values at the `n` rows with the largest `by`, in descending order of `by`.",
+    syntax_example = "max_by(value, by[, n])",
+    sql_example = r#"```sql
+> SELECT max_by(name, salary) AS name FROM employees;
++-------+
+| name  |
++-------+
+| Alice |
++-------+
+> SELECT max_by(name, salary, 2) AS names FROM employees;
++--------------+
+| names        |
++--------------+
+| [Alice, Bob] |
++--------------+
+```"#,
+    argument(name = "value", description = "Expression whose value is returned."),
+    argument(
+        name = "by",
+        description = "Expression to find the maximum of. Can be a constant, column, or function, and any combination of operators."
+    ),
+    argument(
+        name = "n",
+        description = "Optional positive integer literal. Number of values to return."
+    )
+)]
+/// MAX_BY aggregate UDF, also available as `arg_max`
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct MaxBy {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl MaxBy {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::user_defined(Volatility::Immutable),
+            aliases: vec![String::from("arg_max")],
+        }
+    }
+}
+
+impl Default for MaxBy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AggregateUDFImpl for MaxBy {
+    fn name(&self) -> &str {
+        "max_by"
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        coerce_min_max_by_types(self.name(), arg_types)
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        not_impl_err!("Not called because the return_field_from_args is implemented")
+    }
+
+    fn return_field(&self, arg_fields: &[FieldRef]) -> Result<FieldRef> {
+        min_max_by_return_field(self.name(), arg_fields)
+    }
+
+    fn state_fields(&self, args: StateFieldsArgs) -> Result<Vec<FieldRef>> {
+        min_max_by_state_fields(&args)
+    }
+
+    fn accumulator(&self, acc_args: AccumulatorArgs) -> Result<Box<dyn Accumulator>> {
+        create_accumulator(&acc_args, true, self.name())
+    }
+
+    fn groups_accumulator_supported(&self, args: AccumulatorArgs) -> bool {
+        groups_accumulator_supported(&args)
+    }
+
+    fn create_groups_accumulator(
+        &self,
+        args: AccumulatorArgs,
+    ) -> Result<Box<dyn GroupsAccumulator>> {
+        create_groups_accumulator(&args, true, self.name())
+    }
+
+    fn order_sensitivity(&self) -> AggregateOrderSensitivity {
+        AggregateOrderSensitivity::Insensitive
+    }
+
+    fn reverse_expr(&self) -> ReversedUDAF {
+        ReversedUDAF::Identical
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+#[user_doc(
+    doc_section(label = "General Functions"),
+    description = "Returns the value of `value` at the row where `by` is smallest. Rows where `by` is NULL are ignored. If `n` is given, returns a list of the values at the `n` rows with the smallest `by`, in ascending order of `by`.",
+    syntax_example = "min_by(value, by[, n])",
+    sql_example = r#"```sql
+> SELECT min_by(name, salary) AS name FROM employees;
++-------+
+| name  |
++-------+
+| Carol |
++-------+
+> SELECT min_by(name, salary, 2) AS names FROM employees;
++--------------+
+| names        |
++--------------+
+| [Carol, Bob] |
++--------------+
+```"#,
+    argument(name = "value", description = "Expression whose value is returned."),
+    argument(
+        name = "by",
+        description = "Expression to find the minimum of. Can be a constant, column, or function, and any combination of operators."
+    ),
+    argument(
+        name = "n",
+        description = "Optional positive integer literal. Number of values to return."
+    )
+)]
+/// MIN_BY aggregate UDF, also available as `arg_min`
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct MinBy {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl MinBy {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::user_defined(Volatility::Immutable),
+            aliases: vec![String::from("arg_min")],
+        }
+    }
+}
+
+impl Default for MinBy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AggregateUDFImpl for MinBy {
+    fn name(&self) -> &str {
+        "min_by"
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        coerce_min_max_by_types(self.name(), arg_types)
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        not_impl_err!("Not called because the return_field_from_args is implemented")
+    }
+
+    fn return_field(&self, arg_fields: &[FieldRef]) -> Result<FieldRef> {
+        min_max_by_return_field(self.name(), arg_fields)
+    }
+
+    fn state_fields(&self, args: StateFieldsArgs) -> Result<Vec<FieldRef>> {
+        min_max_by_state_fields(&args)
+    }
+
+    fn accumulator(&self, acc_args: AccumulatorArgs) -> Result<Box<dyn Accumulator>> {
+        create_accumulator(&acc_args, false, self.name())
+    }
+
+    fn groups_accumulator_supported(&self, args: AccumulatorArgs) -> bool {
+        groups_accumulator_supported(&args)
+    }
+
+    fn create_groups_accumulator(
+        &self,
+        args: AccumulatorArgs,
+    ) -> Result<Box<dyn GroupsAccumulator>> {
+        create_groups_accumulator(&args, false, self.name())
+    }
+
+    fn order_sensitivity(&self) -> AggregateOrderSensitivity {
+        AggregateOrderSensitivity::Insensitive
+    }
+
+    fn reverse_expr(&self) -> ReversedUDAF {
+        ReversedUDAF::Identical
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+/// `value` is kept as is, dictionaries in `by` are unpacked like for
+/// `min`/`max`, and the optional `n` is coerced to `Int64`
+fn coerce_min_max_by_types(name: &str, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+    let [value, by, rest @ ..] = arg_types else {
+        return plan_err!(
+            "{name} was called with {} arguments. It requires 2 or 3.",
+            arg_types.len()
+        );
+    };
+    if rest.len() > 1 {
+        return plan_err!(
+            "{name} was called with {} arguments. It requires 2 or 3.",
+            arg_types.len()
+        );
+    }
+
+    let by = match by {
+        DataType::Dictionary(_, value_type) => value_type.as_ref().clone(),
+        by => by.clone(),
+    };
+    let mut coerced = vec![value.clone(), by];
+    if let Some(n) = rest.first() {
+        if !n.is_integer() && !n.is_null() {
+            return plan_err!("The third argument of {name} must be an integer, got {n}");
+        }
+        coerced.push(DataType::Int64);
+    }
+    Ok(coerced)
+}
+
+fn min_max_by_return_field(name: &str, arg_fields: &[FieldRef]) -> Result<FieldRef> {
+    let value = &arg_fields[0];
+    let field = if arg_fields.len() == 3 {
+        Field::new(
+            name,
+            DataType::List(Arc::new(Field::new_list_field(
+                value.data_type().clone(),
+                true,
+            ))),
+            true,
+        )
+    } else {
+        // Preserve metadata from the value argument field
+        Field::new(
+            name,
+            value.data_type().clone(),
+            true, // always nullable, there may be no rows
+        )
+        .with_metadata(value.metadata().clone())
+    };
+    Ok(Arc::new(field))
+}
+
+fn min_max_by_state_fields(args: &StateFieldsArgs) -> Result<Vec<FieldRef>> {
+    let value_type = args.input_fields[0].data_type().clone();
+    let by_type = args.input_fields[1].data_type().clone();
+    let (value_type, by_type) = if args.input_fields.len() == 3 {
+        (
+            DataType::List(Arc::new(Field::new_list_field(value_type, true))),
+            DataType::List(Arc::new(Field::new_list_field(by_type, true))),
+        )
+    } else {
+        (value_type, by_type)
+    };
+
+    Ok(vec![
+        Field::new(format_state_name(args.name, "value"), value_type, true).into(),
+        Field::new(format_state_name(args.name, "by"), by_type, true).into(),
+    ])
+}
+
+/// Returns the `n` argument, if any, which must be a positive integer literal
+fn get_limit(args: &AccumulatorArgs, name: &str) -> Result<Option<usize>> {
+    args.exprs
+        .get(2)
+        .map(|expr| validate_positive_integer_expr(expr, name, "third"))
+        .transpose()
+}
+
+fn create_accumulator(
+    args: &AccumulatorArgs,
+    is_max: bool,
+    name: &str,
+) -> Result<Box<dyn Accumulator>> {
+    let value_type = args.expr_fields[0].data_type();
+    let by_type = args.expr_fields[1].data_type();
+    match get_limit(args, name)? {
+        Some(_) if args.is_distinct => {
+            not_impl_err!("{name}(DISTINCT) with a limit is not supported")
+        }
+        Some(n) => Ok(Box::new(MinMaxByNAccumulator::new(
+            n, value_type, by_type, is_max,
+        ))),
+        // DISTINCT does not change the result of the single row variant
+        None => Ok(Box::new(MinMaxByAccumulator::try_new(
+            value_type, by_type, is_max,
+        )?)),
+    }
+}
+
+/// Returns true if `new` should replace `cur` as the current best `by` value
+#[inline]
+fn is_better<T: PartialOrd>(is_max: bool, cur: &T, new: &T) -> bool {
+    if is_max {
+        is_new_max(cur, new)
+    } else {
+        is_new_min(cur, new)
+    }
+}
+
+/// Accumulator for `max_by(value, by)` / `min_by(value, by)`.
+///
+/// Each batch is reduced with the same `max_batch` / `min_batch` kernels as
+/// `max` / `min`; only if that beats the current best is the row holding it
+/// located and its `value` copied.
+#[derive(Debug)]
+struct MinMaxByAccumulator {
+    value: ScalarValue,
+    by: ScalarValue,
+    is_max: bool,
+}
+
+impl MinMaxByAccumulator {
+    fn try_new(value_type: &DataType, by_type: &DataType, is_max: bool) -> Result<Self> {
+        Ok(Self {
+            value: ScalarValue::try_from(value_type)?,
+            by: ScalarValue::try_from(by_type)?,
+            is_max,
+        })
+    }
+}
+
+impl Accumulator for MinMaxByAccumulator {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        let (value, by) = (&values[0], &values[1]);
+        let best = if self.is_max {
+            max_batch(by)?
+        } else {
+            min_batch(by)?
+        };
+        if best.is_null()
+            || (!self.by.is_null() && !is_better(self.is_max, &self.by, &best))
+        {
+            return Ok(());
+        }
+
+        // Find the (first) row holding the new best value
+        let best_array = best.to_array()?;
+        let cmp =
+            make_comparator(by.as_ref(), best_array.as_ref(), SortOptions::default())?;
+        let Some(idx) = (0..by.len()).find(|&i| by.is_valid(i) && cmp(i, 0).is_eq())
+        else {
+            return internal_err!("Could not find the row holding {best}");
+        };
+
+        self.value = ScalarValue::try_from_array(value, idx)?;
+        self.value.compact();
+        self.by = best;
+        self.by.compact();
+        Ok(())
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        // The state has the same layout as the input: [value, by]
+        self.update_batch(states)
+    }
+
+    fn state(&mut self) -> Result<Vec<ScalarValue>> {
+        Ok(vec![self.value.clone(), self.by.clone()])
+    }
+
+    fn evaluate(&mut self) -> Result<ScalarValue> {
+        Ok(self.value.clone())
+    }
+
+    fn size(&self) -> usize {
+        size_of_val(self) - size_of_val(&self.value) - size_of_val(&self.by)
+            + self.value.size()
+            + self.by.size()
+    }
+}
+
+/// Accumulator for `max_by(value, by, n)` / `min_by(value, by, n)`.
+///
+/// Keeps the `n` best `(by, value)` pairs seen so far, ordered best first.
+#[derive(Debug)]
+struct MinMaxByNAccumulator {
+    n: usize,
+    value_type: DataType,
+    by_type: DataType,
+    is_max: bool,
+    /// `(by, value)` pairs, best first
+    entries: Vec<(ScalarValue, ScalarValue)>,
+}
+
+impl MinMaxByNAccumulator {
+    fn new(n: usize, value_type: &DataType, by_type: &DataType, is_max: bool) -> Self {
+        Self {
+            n,
+            value_type: value_type.clone(),
+            by_type: by_type.clone(),
+            is_max,
+            entries: vec![],
+        }
+    }
+
+    /// Merges the `n` best rows of `value` / `by` into `self.entries`
+    fn insert(&mut self, value: &ArrayRef, by: &ArrayRef) -> Result<()> {
+        let options = SortOptions {
+            descending: self.is_max,
+            nulls_first: false,
+        };
+        let indices = sort_to_indices(by, Some(options), Some(self.n))?;
+        for idx in indices.values().iter().map(|idx| *idx as usize) {
+            // NULLs are sorted last, so all remaining rows are NULL
+            if by.is_null(idx) {
+                break;
+            }
+            self.entries.push((
+                ScalarValue::try_from_array(by, idx)?.compacted(),
+                ScalarValue::try_from_array(value, idx)?.compacted(),
+            ));
+        }
+
+        let is_max = self.is_max;
+        self.entries.sort_by(|(a, _), (b, _)| {
+            let ordering = a.partial_cmp(b).unwrap_or(Ordering::Equal);
+            if is_max { ordering.reverse() } else { ordering }
+        });
+        self.entries.truncate(self.n);
+        Ok(())
+    }
+
+    fn to_list(values: Vec<ScalarValue>, data_type: &DataType) -> ScalarValue {
+        ScalarValue::List(ScalarValue::new_list_nullable(&values, data_type))
+    }
+}
+
+impl Accumulator for MinMaxByNAccumulator {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        self.insert(&values[0], &values[1])
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        let values = states[0].as_list::<i32>();
+        let by = states[1].as_list::<i32>();
+        for i in 0..values.len() {
+            if values.is_valid(i) && by.is_valid(i) {
+                self.insert(&values.value(i), &by.value(i))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn state(&mut self) -> Result<Vec<ScalarValue>> {
+        let (by, values): (Vec<_>, Vec<_>) = self.entries.iter().cloned().unzip();
+        Ok(vec![
+            Self::to_list(values, &self.value_type),
+            Self::to_list(by, &self.by_type),
+        ])
+    }
+
+    fn evaluate(&mut self) -> Result<ScalarValue> {
+        if self.entries.is_empty() {
+            let list_type = DataType::List(Arc::new(Field::new_list_field(
+                self.value_type.clone(),
+                true,
+            )));
+            return ScalarValue::try_from(&list_type);
+        }
+        let values = self
+            .entries
+            .iter()
+            .map(|(_, value)| value.clone())
+            .collect();
+        Ok(Self::to_list(values, &self.value_type))
+    }
+
+    fn size(&self) -> usize {
+        size_of_val(self)
+            + self.entries.capacity() * size_of::<(ScalarValue, ScalarValue)>()
+            + self
+                .entries
+                .iter()
+                .map(|(by, value)| {
+                    by.size() - size_of_val(by) + value.size() - size_of_val(value)
+                })
+                .sum::<usize>()
+    }
+}
+
+fn groups_accumulator_supported(args: &AccumulatorArgs) -> bool {
+    use DataType::*;
+    args.exprs.len() == 2
+        && value_state_supported(args.expr_fields[0].data_type())
+        && matches!(
+            args.expr_fields[1].data_type(),
+            Int8 | Int16
+                | Int32
+                | Int64
+                | UInt8
+                | UInt16
+                | UInt32
+                | UInt64
+                | Float16
+                | Float32
+                | Float64
+                | Decimal32(_, _)
+                | Decimal64(_, _)
+                | Decimal128(_, _)
+                | Decimal256(_, _)
+                | Date32
+                | Date64
+                | Time32(_)
+                | Time64(_)
+                | Timestamp(_, _)
+                | Duration(_)
+        )
+}
+
+fn create_groups_accumulator(
+    args: &AccumulatorArgs,
+    is_max: bool,
+    name: &str,
+) -> Result<Box<dyn GroupsAccumulator>> {
+    use DataType::*;
+    use TimeUnit::*;
+
+    let value_type = args.expr_fields[0].data_type();
+    let by_type = args.expr_fields[1].data_type();
+
+    macro_rules! instantiate {
+        ($t:ty) => {
+            Ok(Box::new(MinMaxByGroupsAccumulator::<$t>::try_new(
+                value_type, by_type, is_max,
+            )?))
+        };
+    }
+
+    match by_type {
+        Int8 => instantiate!(Int8Type),
+        Int16 => instantiate!(Int16Type),
+        Int32 => instantiate!(Int32Type),
+        Int64 => instantiate!(Int64Type),
+        UInt8 => instantiate!(UInt8Type),
+        UInt16 => instantiate!(UInt16Type),
+        UInt32 => instantiate!(UInt32Type),
+        UInt64 => instantiate!(UInt64Type),
+        Float16 => instantiate!(Float16Type),
+        Float32 => instantiate!(Float32Type),
+        Float64 => instantiate!(Float64Type),
+        Decimal32(_, _) => instantiate!(Decimal32Type),
+        Decimal64(_, _) => instantiate!(Decimal64Type),
+        Decimal128(_, _) => instantiate!(Decimal128Type),
+        Decimal256(_, _) => instantiate!(Decimal256Type),
+        Date32 => instantiate!(Date32Type),
+        Date64 => instantiate!(Date64Type),
+        Time32(Second) => instantiate!(Time32SecondType),
+        Time32(Millisecond) => instantiate!(Time32MillisecondType),
+        Time64(Microsecond) => instantiate!(Time64MicrosecondType),
+        Time64(Nanosecond) => instantiate!(Time64NanosecondType),
+        Timestamp(Second, _) => instantiate!(TimestampSecondType),
+        Timestamp(Millisecond, _) => instantiate!(TimestampMillisecondType),
+        Timestamp(Microsecond, _) => instantiate!(TimestampMicrosecondType),
+        Timestamp(Nanosecond, _) => instantiate!(TimestampNanosecondType),
+        Duration(Second) => instantiate!(DurationSecondType),
+        Duration(Millisecond) => instantiate!(DurationMillisecondType),
+        Duration(Microsecond) => instantiate!(DurationMicrosecondType),
+        Duration(Nanosecond) => instantiate!(DurationNanosecondType),
+        // This is only reached if groups_accumulator_supported is out of sync
+        _ => internal_err!(
+            "GroupsAccumulator not supported for {name}({value_type}, {by_type})"
+        ),
+    }
+}
+
+/// [`GroupsAccumulator`] for `max_by` / `min_by` with a primitive `by`.
+///
+/// Keeps the best `by` per group, compared with the same rules as the `max` /
+/// `min` group accumulators, and the `value` of the row it came from in a
+/// [`ValueState`].
+struct MinMaxByGroupsAccumulator<T: ArrowPrimitiveType> {
+    /// The `value` at the best row of each group
+    value: Box<dyn ValueState>,
+    /// The best `by` of each group, only valid if `seen` is set
+    by: Vec<T::Native>,
+    /// Whether a non-null `by` has been seen for each group
+    seen: BooleanBufferBuilder,
+    by_type: DataType,
+    is_max: bool,
+}
+
+impl<T: ArrowPrimitiveType> MinMaxByGroupsAccumulator<T> {
+    fn try_new(value_type: &DataType, by_type: &DataType, is_max: bool) -> Result<Self> {
+        Ok(Self {
+            value: new_value_state(value_type)?,
+            by: vec![],
+            seen: BooleanBufferBuilder::new(0),
+            by_type: by_type.clone(),
+            is_max,
+        })
+    }
+
+    fn resize(&mut self, new_size: usize) {
+        self.value.resize(new_size);
+        self.by.resize(new_size, T::default_value());
+        self.seen.resize(new_size);
+    }
+
+    fn take_by(&mut self, emit_to: EmitTo) -> ArrayRef {
+        let by = emit_to.take_needed(&mut self.by);
+        let nulls = NullBuffer::new(take_need(&mut self.seen, emit_to));
+        Arc::new(
+            PrimitiveArray::<T>::new(by.into(), Some(nulls))
+                .with_data_type(self.by_type.clone()),
+        )
+    }
+}
+
+impl<T: ArrowPrimitiveType> GroupsAccumulator for MinMaxByGroupsAccumulator<T> {
+    fn update_batch(
+        &mut self,
+        values: &[ArrayRef],
+        group_indices: &[usize],
+        opt_filter: Option<&BooleanArray>,
+        total_num_groups: usize,
+    ) -> Result<()> {
+        self.resize(total_num_groups);
+
+        let value = &values[0];
+        let by = values[1].as_primitive::<T>();
+        for (row, &group_idx) in group_indices.iter().enumerate() {
+            if by.is_null(row)
+                || opt_filter.is_some_and(|f| !f.is_valid(row) || !f.value(row))
+            {
+                continue;
+            }
+
+            let new = by.value(row);
+            if self.seen.get_bit(group_idx)
+                && !is_better(self.is_max, &self.by[group_idx], &new)
+            {
+                continue;
+            }
+
+            self.by[group_idx] = new;
+            self.seen.set_bit(group_idx, true);
+            self.value.update(group_idx, value, row)?;
+        }
+        Ok(())
+    }
+
+    fn evaluate(&mut self, emit_to: EmitTo) -> Result<ArrayRef> {
+        self.take_by(emit_to);
+        self.value.take(emit_to)
+    }
+
+    fn state(&mut self, emit_to: EmitTo) -> Result<Vec<ArrayRef>> {
+        let by = self.take_by(emit_to);
+        Ok(vec![self.value.take(emit_to)?, by])
+    }
+
+    fn merge_batch(
+        &mut self,
+        values: &[ArrayRef],
+        group_indices: &[usize],
+        opt_filter: Option<&BooleanArray>,
+        total_num_groups: usize,
+    ) -> Result<()> {
+        // The state has the same layout as the input: [value, by], with a
+        // NULL `by` for groups that did not see any rows
+        self.update_batch(values, group_indices, opt_filter, total_num_groups)
+    }
+
+    fn size(&self) -> usize {
+        self.value.size()
+            + self.by.capacity() * size_of::<T::Native>()
+            + self.seen.capacity() / 8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Float64Array, Int64Array, StringArray};
+
+    fn string_array(values: &[Option<&str>]) -> ArrayRef {
+        Arc::new(StringArray::from(values.to_vec()))
+    }
+
+    fn int64_array(values: &[Option<i64>]) -> ArrayRef {
+        Arc::new(Int64Array::from(values.to_vec()))
+    }
+
+    #[test]
+    fn max_by_ignores_null_by() -> Result<()> {
+        let mut acc =
+            MinMaxByAccumulator::try_new(&DataType::Utf8, &DataType::Int64, true)?;
+        acc.update_batch(&[
+            string_array(&[Some("a"), Some("b"), Some("c")]),
+            int64_array(&[Some(1), None, Some(3)]),
+        ])?;
+        acc.update_batch(&[
+            string_array(&[Some("d"), None]),
+            int64_array(&[Some(2), Some(3)]),
+        ])?;
+
+        // ties keep the first row seen
+        assert_eq!(acc.evaluate()?, ScalarValue::from("c"));
+        Ok(())
+    }
+
+    #[test]
+    fn min_by_merge() -> Result<()> {
+        let new_acc =
+            || MinMaxByAccumulator::try_new(&DataType::Utf8, &DataType::Float64, false);
+        let mut acc1 = new_acc()?;
+        let by: ArrayRef = Arc::new(Float64Array::from(vec![2.0, 1.5]));
+        acc1.update_batch(&[string_array(&[Some("a"), Some("b")]), by])?;
+        let mut acc2 = new_acc()?;
+        let by: ArrayRef = Arc::new(Float64Array::from(vec![3.0, -1.0]));
+        acc2.update_batch(&[string_array(&[Some("c"), None]), by])?;
+
+        let mut acc = new_acc()?;
+        for state in [acc1.state()?, acc2.state()?] {
+            let state = state
+                .iter()
+                .map(|s| s.to_array())
+                .collect::<Result<Vec<_>>>()?;
+            acc.merge_batch(&state)?;
+        }
+        // the value at the smallest `by` is NULL
+        assert_eq!(acc.evaluate()?, ScalarValue::Utf8(None));
+        Ok(())
+    }
+
+    #[test]
+    fn max_by_n() -> Result<()> {
+        let mut acc =
+            MinMaxByNAccumulator::new(2, &DataType::Utf8, &DataType::Int64, true);
+        acc.update_batch(&[
+            string_array(&[Some("a"), Some("b"), Some("c")]),
+            int64_array(&[Some(1), Some(5), None]),
+        ])?;
+        acc.update_batch(&[
+            string_array(&[Some("d"), Some("e")]),
+            int64_array(&[Some(3), Some(0)]),
+        ])?;
+
+        let ScalarValue::List(list) = acc.evaluate()? else {
+            unreachable!()
+        };
+        assert_eq!(list.len(), 1);
+        assert_eq!(
+            list.value(0).as_string::<i32>().iter().collect::<Vec<_>>(),
+            vec![Some("b"), Some("d")]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn min_by_groups() -> Result<()> {
+        let new_acc = || {
+            MinMaxByGroupsAccumulator::<Int64Type>::try_new(
+                &DataType::Utf8,
+                &DataType::Int64,
+                false,
+            )
+        };
+        let mut acc = new_acc()?;
+        acc.update_batch(
+            &[
+                string_array(&[Some("a"), Some("b"), Some("c"), Some("d")]),
+                int64_array(&[Some(3), Some(1), Some(2), None]),
+            ],
+            &[0, 0, 1, 2],
+            None,
+            3,
+        )?;
+        let state = acc.state(EmitTo::All)?;
+
+        let mut merged = new_acc()?;
+        merged.merge_batch(&state, &[0, 1, 2], None, 3)?;
+        merged.update_batch(
+            &[
+                string_array(&[Some("e"), Some("f")]),
+                int64_array(&[Some(0), Some(2)]),
+            ],
+            &[1, 1],
+            None,
+            3,
+        )?;
+
+        let result = merged.evaluate(EmitTo::All)?;
+        assert_eq!(
+            result.as_string::<i32>().iter().collect::<Vec<_>>(),
+            vec![Some("b"), Some("e"), None]
+        );
+        Ok(())
+    }
+}
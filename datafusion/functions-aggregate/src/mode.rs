@@ -0,0 +1,541 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`Mode`]: most frequent value aggregation.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::mem::{size_of, size_of_val};
+use std::sync::Arc;
+
+use arrow::array::{
+    Array, ArrayRef, ArrowNativeTypeOp, AsArray, BooleanArray, ListArray, PrimitiveArray,
+    PrimitiveBuilder, UInt64Array, downcast_integer,
+};
+use arrow::buffer::{OffsetBuffer, ScalarBuffer};
+use arrow::datatypes::{
+    ArrowPrimitiveType, DataType, Date32Type, Date64Type, Decimal32Type, Decimal64Type,
+    Decimal128Type, Decimal256Type, Field, FieldRef, Float16Type, Float32Type,
+    Float64Type, UInt64Type,
+};
+
+use datafusion_common::{Result, ScalarValue, not_impl_err};
+use datafusion_expr::function::{AccumulatorArgs, StateFieldsArgs};
+use datafusion_expr::utils::format_state_name;
+use datafusion_expr::{
+    Accumulator, AggregateUDFImpl, Documentation, EmitTo, GroupsAccumulator, Signature,
+    Volatility,
+};
+use datafusion_functions_aggregate_common::aggregate::groups_accumulator::accumulate::accumulate;
+use datafusion_functions_aggregate_common::utils::Hashable;
+use datafusion_macros::user_doc;
+
+make_udaf_expr_and_func!(
+    Mode,
+    mode,
+    expression,
+    "Returns the most frequent value of a set of values",
+    mode_udaf
+);
+
+#[user_doc(
+    doc_section(label = "General Functions"),
+    description = "Returns the most frequent non-null input value. If several values are equally frequent, the smallest of them is returned, or the largest one when ordered descending.",
+    syntax_example = "mode() WITHIN GROUP (ORDER BY expression)",
+    sql_example = r#"```sql
+> SELECT mode() WITHIN GROUP (ORDER BY column_name) FROM table_name;
++----------------------------------------------------+
+| mode() WITHIN GROUP (ORDER BY column_name)         |
++----------------------------------------------------+
+| 42                                                 |
++----------------------------------------------------+
+```
+
+An alternate syntax is also supported:
+```sql
+> SELECT mode(column_name) FROM table_name;
++-------------------+
+| mode(column_name) |
++-------------------+
+| 42                |
++-------------------+
+```"#,
+    standard_argument(name = "expression", prefix = "The")
+)]
+/// MODE aggregate expression. This keeps a count for every distinct input
+/// value, so memory usage grows with the cardinality of the input.
+#[derive(PartialEq, Eq, Hash, Debug)]
+pub struct Mode {
+    signature: Signature,
+}
+
+impl Default for Mode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Mode {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(1, Volatility::Immutable),
+        }
+    }
+}
+
+impl AggregateUDFImpl for Mode {
+    fn name(&self) -> &str {
+        "mode"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        Ok(arg_types[0].clone())
+    }
+
+    fn state_fields(&self, args: StateFieldsArgs) -> Result<Vec<FieldRef>> {
+        let input_type = args.input_fields[0].data_type().clone();
+        Ok(vec![
+            Field::new_list(
+                format_state_name(args.name, "mode_values"),
+                Field::new_list_field(input_type, true),
+                true,
+            )
+            .into(),
+            Field::new_list(
+                format_state_name(args.name, "mode_counts"),
+                Field::new_list_field(DataType::UInt64, true),
+                true,
+            )
+            .into(),
+        ])
+    }
+
+    fn accumulator(&self, args: AccumulatorArgs) -> Result<Box<dyn Accumulator>> {
+        if args.is_distinct {
+            return not_impl_err!("MODE(DISTINCT) aggregations are not available");
+        }
+
+        Ok(Box::new(ModeAccumulator::new(
+            args.expr_fields[0].data_type().clone(),
+            is_descending(&args),
+        )))
+    }
+
+    fn groups_accumulator_supported(&self, args: AccumulatorArgs) -> bool {
+        let dt = args.expr_fields[0].data_type();
+        !args.is_distinct
+            && (dt.is_integer()
+                || dt.is_floating()
+                || matches!(
+                    dt,
+                    DataType::Decimal32(_, _)
+                        | DataType::Decimal64(_, _)
+                        | DataType::Decimal128(_, _)
+                        | DataType::Decimal256(_, _)
+                        | DataType::Date32
+                        | DataType::Date64
+                ))
+    }
+
+    fn create_groups_accumulator(
+        &self,
+        args: AccumulatorArgs,
+    ) -> Result<Box<dyn GroupsAccumulator>> {
+        let dt = args.expr_fields[0].data_type().clone();
+        let descending = is_descending(&args);
+
+        macro_rules! helper {
+            ($t:ty, $dt:expr) => {
+                Ok(Box::new(ModeGroupsAccumulator::<$t>::new($dt, descending)))
+            };
+        }
+
+        downcast_integer! {
+            dt => (helper, dt),
+            DataType::Float16 => helper!(Float16Type, dt),
+            DataType::Float32 => helper!(Float32Type, dt),
+            DataType::Float64 => helper!(Float64Type, dt),
+            DataType::Decimal32(_, _) => helper!(Decimal32Type, dt),
+            DataType::Decimal64(_, _) => helper!(Decimal64Type, dt),
+            DataType::Decimal128(_, _) => helper!(Decimal128Type, dt),
+            DataType::Decimal256(_, _) => helper!(Decimal256Type, dt),
+            DataType::Date32 => helper!(Date32Type, dt),
+            DataType::Date64 => helper!(Date64Type, dt),
+            _ => not_impl_err!(
+                "ModeGroupsAccumulator not supported for {} with {}",
+                args.name,
+                dt
+            ),
+        }
+    }
+
+    fn supports_within_group_clause(&self) -> bool {
+        true
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+/// The WITHIN GROUP ordering only decides which value wins a tie
+fn is_descending(args: &AccumulatorArgs) -> bool {
+    args.order_bys
+        .first()
+        .map(|sort_expr| sort_expr.options.descending)
+        .unwrap_or(false)
+}
+
+/// Returns true if `candidate` should replace `current` as the mode
+fn is_better_mode(
+    candidate_count: u64,
+    current_count: u64,
+    ordering: Option<Ordering>,
+    descending: bool,
+) -> bool {
+    match candidate_count.cmp(&current_count) {
+        Ordering::Greater => true,
+        Ordering::Less => false,
+        Ordering::Equal if descending => ordering == Some(Ordering::Greater),
+        Ordering::Equal => ordering == Some(Ordering::Less),
+    }
+}
+
+/// Counts the occurrences of every distinct value of any type.
+///
+/// Counts are decremented on retraction, so this accumulator can be used
+/// for sliding window frames.
+#[derive(Debug)]
+struct ModeAccumulator {
+    data_type: DataType,
+    counts: HashMap<ScalarValue, u64>,
+    descending: bool,
+}
+
+impl ModeAccumulator {
+    fn new(data_type: DataType, descending: bool) -> Self {
+        Self {
+            data_type,
+            counts: HashMap::new(),
+            descending,
+        }
+    }
+}
+
+#[allow(clippy::allow_attributes, clippy::mutable_key_type)] // ScalarValue has interior mutability but is intentionally used as hash key
+impl Accumulator for ModeAccumulator {
+    fn state(&mut self) -> Result<Vec<ScalarValue>> {
+        let (values, counts): (Vec<_>, Vec<_>) = self
+            .counts
+            .iter()
+            .map(|(value, count)| (value.clone(), ScalarValue::from(*count)))
+            .unzip();
+
+        Ok(vec![
+            ScalarValue::List(ScalarValue::new_list_nullable(&values, &self.data_type)),
+            ScalarValue::List(ScalarValue::new_list_nullable(&counts, &DataType::UInt64)),
+        ])
+    }
+
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        let array = &values[0];
+        for i in 0..array.len() {
+            if array.is_valid(i) {
+                let value = ScalarValue::try_from_array(array, i)?;
+                *self.counts.entry(value).or_default() += 1;
+            }
+        }
+        Ok(())
+    }
+
+    fn retract_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        let array = &values[0];
+        for i in 0..array.len() {
+            if array.is_valid(i) {
+                let value = ScalarValue::try_from_array(array, i)?;
+                if let Some(count) = self.counts.get_mut(&value) {
+                    *count -= 1;
+                    if *count == 0 {
+                        self.counts.remove(&value);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        let values = states[0].as_list::<i32>();
+        let counts = states[1].as_list::<i32>();
+
+        for (values, counts) in values.iter().zip(counts.iter()) {
+            let (Some(values), Some(counts)) = (values, counts) else {
+                continue;
+            };
+            let counts = counts.as_primitive::<UInt64Type>();
+            for i in 0..values.len() {
+                let value = ScalarValue::try_from_array(&values, i)?;
+                *self.counts.entry(value).or_default() += counts.value(i);
+            }
+        }
+        Ok(())
+    }
+
+    fn evaluate(&mut self) -> Result<ScalarValue> {
+        let mode = self.counts.iter().reduce(|current, candidate| {
+            let ordering = candidate.0.partial_cmp(current.0);
+            if is_better_mode(*candidate.1, *current.1, ordering, self.descending) {
+                candidate
+            } else {
+                current
+            }
+        });
+
+        match mode {
+            Some((value, _)) => Ok(value.clone()),
+            None => ScalarValue::try_from(&self.data_type),
+        }
+    }
+
+    fn size(&self) -> usize {
+        size_of_val(self)
+            + self.counts.capacity() * size_of::<(ScalarValue, u64)>()
+            + self
+                .counts
+                .keys()
+                .map(|value| value.size() - size_of_val(value))
+                .sum::<usize>()
+    }
+
+    fn supports_retract_batch(&self) -> bool {
+        true
+    }
+}
+
+/// Counts the occurrences of every distinct value per group for primitive
+/// input types.
+#[derive(Debug)]
+struct ModeGroupsAccumulator<T: ArrowPrimitiveType + Send> {
+    data_type: DataType,
+    group_counts: Vec<HashMap<Hashable<T::Native>, u64>>,
+    descending: bool,
+}
+
+impl<T: ArrowPrimitiveType + Send> ModeGroupsAccumulator<T> {
+    fn new(data_type: DataType, descending: bool) -> Self {
+        Self {
+            data_type,
+            group_counts: vec![],
+            descending,
+        }
+    }
+}
+
+impl<T: ArrowPrimitiveType + Send> GroupsAccumulator for ModeGroupsAccumulator<T> {
+    fn update_batch(
+        &mut self,
+        values: &[ArrayRef],
+        group_indices: &[usize],
+        opt_filter: Option<&BooleanArray>,
+        total_num_groups: usize,
+    ) -> Result<()> {
+        let values = values[0].as_primitive::<T>();
+
+        self.group_counts.resize(total_num_groups, HashMap::new());
+        accumulate(group_indices, values, opt_filter, |group_index, value| {
+            *self.group_counts[group_index]
+                .entry(Hashable(value))
+                .or_default() += 1;
+        });
+
+        Ok(())
+    }
+
+    fn merge_batch(
+        &mut self,
+        values: &[ArrayRef],
+        group_indices: &[usize],
+        // Since aggregate filter should be applied in partial stage, in final stage there should be no filter
+        _opt_filter: Option<&BooleanArray>,
+        total_num_groups: usize,
+    ) -> Result<()> {
+        assert_eq!(values.len(), 2, "two arguments to merge_batch");
+
+        let partial_values = values[0].as_list::<i32>();
+        let partial_counts = values[1].as_list::<i32>();
+
+        self.group_counts.resize(total_num_groups, HashMap::new());
+        group_indices
+            .iter()
+            .zip(partial_values.iter().zip(partial_counts.iter()))
+            .for_each(|(&group_index, (values, counts))| {
+                let (Some(values), Some(counts)) = (values, counts) else {
+                    return;
+                };
+                let group_counts = &mut self.group_counts[group_index];
+                values
+                    .as_primitive::<T>()
+                    .values()
+                    .iter()
+                    .zip(counts.as_primitive::<UInt64Type>().values().iter())
+                    .for_each(|(&value, &count)| {
+                        *group_counts.entry(Hashable(value)).or_default() += count;
+                    });
+            });
+
+        Ok(())
+    }
+
+    fn state(&mut self, emit_to: EmitTo) -> Result<Vec<ArrayRef>> {
+        let emit_group_counts = emit_to.take_needed(&mut self.group_counts);
+
+        let mut offsets = Vec::with_capacity(emit_group_counts.len() + 1);
+        offsets.push(0);
+        let mut cur_len = 0_i32;
+        for group_counts in &emit_group_counts {
+            cur_len += group_counts.len() as i32;
+            offsets.push(cur_len);
+        }
+        let offsets = OffsetBuffer::new(ScalarBuffer::from(offsets));
+
+        let (values, counts): (Vec<_>, Vec<_>) = emit_group_counts
+            .into_iter()
+            .flat_map(|group_counts| group_counts.into_iter())
+            .map(|(value, count)| (value.0, count))
+            .unzip();
+
+        let values = PrimitiveArray::<T>::new(ScalarBuffer::from(values), None)
+            .with_data_type(self.data_type.clone());
+        let counts = UInt64Array::new(ScalarBuffer::from(counts), None);
+
+        Ok(vec![
+            Arc::new(ListArray::new(
+                Arc::new(Field::new_list_field(self.data_type.clone(), true)),
+                offsets.clone(),
+                Arc::new(values),
+                None,
+            )),
+            Arc::new(ListArray::new(
+                Arc::new(Field::new_list_field(DataType::UInt64, true)),
+                offsets,
+                Arc::new(counts),
+                None,
+            )),
+        ])
+    }
+
+    fn evaluate(&mut self, emit_to: EmitTo) -> Result<ArrayRef> {
+        let emit_group_counts = emit_to.take_needed(&mut self.group_counts);
+
+        let mut builder = PrimitiveBuilder::<T>::with_capacity(emit_group_counts.len())
+            .with_data_type(self.data_type.clone());
+        for group_counts in &emit_group_counts {
+            let mode = group_counts.iter().reduce(|current, candidate| {
+                let ordering = candidate.0.0.compare(current.0.0);
+                if is_better_mode(
+                    *candidate.1,
+                    *current.1,
+                    Some(ordering),
+                    self.descending,
+                ) {
+                    candidate
+                } else {
+                    current
+                }
+            });
+            builder.append_option(mode.map(|(value, _)| value.0));
+        }
+
+        Ok(Arc::new(builder.finish()))
+    }
+
+    fn size(&self) -> usize {
+        self.group_counts
+            .iter()
+            .map(|group_counts| {
+                group_counts.capacity() * size_of::<(Hashable<T::Native>, u64)>()
+            })
+            .sum::<usize>()
+            + self.group_counts.capacity()
+                * size_of::<HashMap<Hashable<T::Native>, u64>>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Int64Array, StringArray};
+
+    fn evaluate(acc: &mut ModeAccumulator, values: ArrayRef) -> Result<ScalarValue> {
+        acc.update_batch(&[values])?;
+        acc.evaluate()
+    }
+
+    #[test]
+    fn mode_ties_pick_first_value_in_order() -> Result<()> {
+        let values: ArrayRef = Arc::new(Int64Array::from(vec![
+            Some(3),
+            Some(1),
+            None,
+            Some(3),
+            Some(1),
+            Some(2),
+        ]));
+
+        let mut asc = ModeAccumulator::new(DataType::Int64, false);
+        assert_eq!(
+            evaluate(&mut asc, Arc::clone(&values))?,
+            ScalarValue::from(1i64)
+        );
+
+        let mut desc = ModeAccumulator::new(DataType::Int64, true);
+        assert_eq!(evaluate(&mut desc, values)?, ScalarValue::from(3i64));
+        Ok(())
+    }
+
+    #[test]
+    fn mode_retract_and_merge() -> Result<()> {
+        let mut acc = ModeAccumulator::new(DataType::Utf8, false);
+        let values: ArrayRef = Arc::new(StringArray::from(vec!["b", "a", "b"]));
+        assert_eq!(evaluate(&mut acc, values)?, ScalarValue::from("b"));
+
+        let retracted: ArrayRef = Arc::new(StringArray::from(vec!["b", "b"]));
+        acc.retract_batch(&[retracted])?;
+        assert_eq!(acc.evaluate()?, ScalarValue::from("a"));
+
+        let mut other = ModeAccumulator::new(DataType::Utf8, false);
+        let values: ArrayRef = Arc::new(StringArray::from(vec!["c", "c"]));
+        other.update_batch(&[values])?;
+        let state = other
+            .state()?
+            .iter()
+            .map(|s| s.to_array())
+            .collect::<Result<Vec<_>>>()?;
+        acc.merge_batch(&state)?;
+        assert_eq!(acc.evaluate()?, ScalarValue::from("c"));
+
+        let retracted: ArrayRef = Arc::new(StringArray::from(vec!["a", "c", "c"]));
+        acc.retract_batch(&[retracted])?;
+        assert_eq!(acc.evaluate()?, ScalarValue::Utf8(None));
+        Ok(())
+    }
+}
@@ -0,0 +1,543 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`Skewness`]: sample skewness aggregations.
+//! [`Kurtosis`]: sample excess kurtosis aggregations.
+
+use std::fmt::Debug;
+use std::mem::{size_of, size_of_val};
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, BooleanArray, Float64Array, UInt64Array};
+use arrow::buffer::NullBuffer;
+use arrow::datatypes::{DataType, Field, FieldRef};
+use datafusion_common::cast::{as_float64_array, as_uint64_array};
+use datafusion_common::{Result, ScalarValue, not_impl_err};
+use datafusion_expr::function::{AccumulatorArgs, StateFieldsArgs};
+use datafusion_expr::utils::format_state_name;
+use datafusion_expr::{
+    Accumulator, AggregateUDFImpl, Documentation, EmitTo, GroupsAccumulator, Signature,
+    Volatility,
+};
+use datafusion_functions_aggregate_common::aggregate::groups_accumulator::accumulate::accumulate;
+use datafusion_macros::user_doc;
+
+make_udaf_expr_and_func!(
+    Skewness,
+    skewness,
+    expression,
+    "Computes the sample skewness.",
+    skewness_udaf
+);
+
+make_udaf_expr_and_func!(
+    Kurtosis,
+    kurtosis,
+    expression,
+    "Computes the sample excess kurtosis.",
+    kurtosis_udaf
+);
+
+#[user_doc(
+    doc_section(label = "Statistical Functions"),
+    description = "Returns the sample skewness of a set of numbers. Returns NULL if there are fewer than three non-null values or all values are equal.",
+    syntax_example = "skewness(expression)",
+    sql_example = r#"```sql
+> SELECT skewness(column_name) FROM table_name;
++-----------------------+
+| skewness(column_name) |
++-----------------------+
+| 0.7528371991317999    |
++-----------------------+
+```"#,
+    standard_argument(name = "expression", prefix = "Numeric")
+)]
+#[derive(PartialEq, Eq, Hash, Debug)]
+pub struct Skewness {
+    signature: Signature,
+}
+
+impl Default for Skewness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Skewness {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::exact(vec![DataType::Float64], Volatility::Immutable),
+        }
+    }
+}
+
+impl AggregateUDFImpl for Skewness {
+    fn name(&self) -> &str {
+        "skewness"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Float64)
+    }
+
+    fn state_fields(&self, args: StateFieldsArgs) -> Result<Vec<FieldRef>> {
+        Ok(moments_state_fields(args.name))
+    }
+
+    fn accumulator(&self, acc_args: AccumulatorArgs) -> Result<Box<dyn Accumulator>> {
+        if acc_args.is_distinct {
+            return not_impl_err!("SKEWNESS(DISTINCT) aggregations are not available");
+        }
+        Ok(Box::new(MomentsAccumulator::new(MomentStat::Skewness)))
+    }
+
+    fn groups_accumulator_supported(&self, acc_args: AccumulatorArgs) -> bool {
+        !acc_args.is_distinct
+    }
+
+    fn create_groups_accumulator(
+        &self,
+        _args: AccumulatorArgs,
+    ) -> Result<Box<dyn GroupsAccumulator>> {
+        Ok(Box::new(MomentsGroupsAccumulator::new(
+            MomentStat::Skewness,
+        )))
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+#[user_doc(
+    doc_section(label = "Statistical Functions"),
+    description = "Returns the sample excess kurtosis of a set of numbers, so a normal distribution has a kurtosis of 0. Returns NULL if there are fewer than four non-null values or all values are equal.",
+    syntax_example = "kurtosis(expression)",
+    sql_example = r#"```sql
+> SELECT kurtosis(column_name) FROM table_name;
++-----------------------+
+| kurtosis(column_name) |
++-----------------------+
+| -1.2                  |
++-----------------------+
+```"#,
+    standard_argument(name = "expression", prefix = "Numeric")
+)]
+#[derive(PartialEq, Eq, Hash, Debug)]
+pub struct Kurtosis {
+    signature: Signature,
+}
+
+impl Default for Kurtosis {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Kurtosis {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::exact(vec![DataType::Float64], Volatility::Immutable),
+        }
+    }
+}
+
+impl AggregateUDFImpl for Kurtosis {
+    fn name(&self) -> &str {
+        "kurtosis"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Float64)
+    }
+
+    fn state_fields(&self, args: StateFieldsArgs) -> Result<Vec<FieldRef>> {
+        Ok(moments_state_fields(args.name))
+    }
+
+    fn accumulator(&self, acc_args: AccumulatorArgs) -> Result<Box<dyn Accumulator>> {
+        if acc_args.is_distinct {
+            return not_impl_err!("KURTOSIS(DISTINCT) aggregations are not available");
+        }
+        Ok(Box::new(MomentsAccumulator::new(MomentStat::Kurtosis)))
+    }
+
+    fn groups_accumulator_supported(&self, acc_args: AccumulatorArgs) -> bool {
+        !acc_args.is_distinct
+    }
+
+    fn create_groups_accumulator(
+        &self,
+        _args: AccumulatorArgs,
+    ) -> Result<Box<dyn GroupsAccumulator>> {
+        Ok(Box::new(MomentsGroupsAccumulator::new(
+            MomentStat::Kurtosis,
+        )))
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+fn moments_state_fields(name: &str) -> Vec<FieldRef> {
+    vec![
+        Field::new(format_state_name(name, "count"), DataType::UInt64, true),
+        Field::new(format_state_name(name, "sum"), DataType::Float64, true),
+        Field::new(format_state_name(name, "sum_sqr"), DataType::Float64, true),
+        Field::new(format_state_name(name, "sum_cub"), DataType::Float64, true),
+        Field::new(format_state_name(name, "sum_four"), DataType::Float64, true),
+    ]
+    .into_iter()
+    .map(Arc::new)
+    .collect()
+}
+
+/// The statistic computed from the [`Moments`] of the input
+#[derive(Debug, Clone, Copy)]
+enum MomentStat {
+    Skewness,
+    Kurtosis,
+}
+
+impl MomentStat {
+    fn evaluate(&self, moments: &Moments) -> Option<f64> {
+        match self {
+            Self::Skewness => moments.skewness(),
+            Self::Kurtosis => moments.kurtosis(),
+        }
+    }
+}
+
+/// Running power sums of the input values.
+///
+/// Unlike the Welford update used for variance, power sums can be updated,
+/// merged and retracted by plain addition and subtraction, which makes them
+/// usable for sliding window frames.
+#[derive(Debug, Default, Clone, Copy)]
+struct Moments {
+    count: u64,
+    sum: f64,
+    sum_sqr: f64,
+    sum_cub: f64,
+    sum_four: f64,
+}
+
+impl Moments {
+    fn update(&mut self, value: f64) {
+        self.count += 1;
+        self.sum += value;
+        self.sum_sqr += value * value;
+        self.sum_cub += value * value * value;
+        self.sum_four += value * value * value * value;
+    }
+
+    fn retract(&mut self, value: f64) {
+        self.count -= 1;
+        self.sum -= value;
+        self.sum_sqr -= value * value;
+        self.sum_cub -= value * value * value;
+        self.sum_four -= value * value * value * value;
+    }
+
+    fn merge(&mut self, other: &Moments) {
+        self.count += other.count;
+        self.sum += other.sum;
+        self.sum_sqr += other.sum_sqr;
+        self.sum_cub += other.sum_cub;
+        self.sum_four += other.sum_four;
+    }
+
+    /// Sample skewness, adjusted for bias by `sqrt(n(n-1)) / (n-2)`
+    fn skewness(&self) -> Option<f64> {
+        if self.count <= 2 {
+            return None;
+        }
+        let n = self.count as f64;
+        let temp = 1.0 / n;
+        let m2 = temp * (self.sum_sqr - self.sum * self.sum * temp);
+        if m2 <= 0.0 {
+            return None;
+        }
+        let m3 = temp
+            * (self.sum_cub - 3.0 * self.sum_sqr * self.sum * temp
+                + 2.0 * self.sum.powi(3) * temp * temp);
+        Some((n * (n - 1.0)).sqrt() / (n - 2.0) * m3 / m2.powf(1.5))
+    }
+
+    /// Sample excess kurtosis, adjusted for bias
+    fn kurtosis(&self) -> Option<f64> {
+        if self.count <= 3 {
+            return None;
+        }
+        let n = self.count as f64;
+        let temp = 1.0 / n;
+        let m2 = temp * (self.sum_sqr - self.sum * self.sum * temp);
+        if m2 <= 0.0 {
+            return None;
+        }
+        let m4 = temp
+            * (self.sum_four - 4.0 * self.sum_cub * self.sum * temp
+                + 6.0 * self.sum_sqr * self.sum * self.sum * temp * temp
+                - 3.0 * self.sum.powi(4) * temp.powi(3));
+        Some(
+            (n - 1.0) * ((n + 1.0) * m4 / (m2 * m2) - 3.0 * (n - 1.0))
+                / ((n - 2.0) * (n - 3.0)),
+        )
+    }
+}
+
+/// An accumulator to compute skewness or kurtosis
+#[derive(Debug)]
+struct MomentsAccumulator {
+    moments: Moments,
+    stat: MomentStat,
+}
+
+impl MomentsAccumulator {
+    fn new(stat: MomentStat) -> Self {
+        Self {
+            moments: Moments::default(),
+            stat,
+        }
+    }
+}
+
+impl Accumulator for MomentsAccumulator {
+    fn state(&mut self) -> Result<Vec<ScalarValue>> {
+        Ok(vec![
+            ScalarValue::from(self.moments.count),
+            ScalarValue::from(self.moments.sum),
+            ScalarValue::from(self.moments.sum_sqr),
+            ScalarValue::from(self.moments.sum_cub),
+            ScalarValue::from(self.moments.sum_four),
+        ])
+    }
+
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        let arr = as_float64_array(&values[0])?;
+        arr.iter()
+            .flatten()
+            .for_each(|value| self.moments.update(value));
+        Ok(())
+    }
+
+    fn retract_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        let arr = as_float64_array(&values[0])?;
+        arr.iter()
+            .flatten()
+            .for_each(|value| self.moments.retract(value));
+        Ok(())
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        let partial = PartialMoments::try_new(states)?;
+        for i in 0..partial.len() {
+            self.moments.merge(&partial.get(i));
+        }
+        Ok(())
+    }
+
+    fn evaluate(&mut self) -> Result<ScalarValue> {
+        Ok(ScalarValue::Float64(self.stat.evaluate(&self.moments)))
+    }
+
+    fn size(&self) -> usize {
+        size_of_val(self)
+    }
+
+    fn supports_retract_batch(&self) -> bool {
+        true
+    }
+}
+
+/// The intermediate state arrays produced by [`MomentsAccumulator::state`]
+/// and [`MomentsGroupsAccumulator::state`]
+struct PartialMoments<'a> {
+    counts: &'a UInt64Array,
+    sums: &'a Float64Array,
+    sum_sqrs: &'a Float64Array,
+    sum_cubs: &'a Float64Array,
+    sum_fours: &'a Float64Array,
+}
+
+impl<'a> PartialMoments<'a> {
+    fn try_new(states: &'a [ArrayRef]) -> Result<Self> {
+        Ok(Self {
+            counts: as_uint64_array(&states[0])?,
+            sums: as_float64_array(&states[1])?,
+            sum_sqrs: as_float64_array(&states[2])?,
+            sum_cubs: as_float64_array(&states[3])?,
+            sum_fours: as_float64_array(&states[4])?,
+        })
+    }
+
+    fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    fn get(&self, i: usize) -> Moments {
+        Moments {
+            count: self.counts.value(i),
+            sum: self.sums.value(i),
+            sum_sqr: self.sum_sqrs.value(i),
+            sum_cub: self.sum_cubs.value(i),
+            sum_four: self.sum_fours.value(i),
+        }
+    }
+}
+
+/// A groups accumulator to compute skewness or kurtosis
+#[derive(Debug)]
+struct MomentsGroupsAccumulator {
+    moments: Vec<Moments>,
+    stat: MomentStat,
+}
+
+impl MomentsGroupsAccumulator {
+    fn new(stat: MomentStat) -> Self {
+        Self {
+            moments: Vec::new(),
+            stat,
+        }
+    }
+}
+
+impl GroupsAccumulator for MomentsGroupsAccumulator {
+    fn update_batch(
+        &mut self,
+        values: &[ArrayRef],
+        group_indices: &[usize],
+        opt_filter: Option<&BooleanArray>,
+        total_num_groups: usize,
+    ) -> Result<()> {
+        assert_eq!(values.len(), 1, "single argument to update_batch");
+        let values = as_float64_array(&values[0])?;
+
+        self.moments.resize(total_num_groups, Moments::default());
+        accumulate(group_indices, values, opt_filter, |group_index, value| {
+            self.moments[group_index].update(value);
+        });
+        Ok(())
+    }
+
+    fn merge_batch(
+        &mut self,
+        values: &[ArrayRef],
+        group_indices: &[usize],
+        // Since aggregate filter should be applied in partial stage, in final stage there should be no filter
+        _opt_filter: Option<&BooleanArray>,
+        total_num_groups: usize,
+    ) -> Result<()> {
+        assert_eq!(values.len(), 5, "five arguments to merge_batch");
+        let partial = PartialMoments::try_new(values)?;
+
+        self.moments.resize(total_num_groups, Moments::default());
+        for (i, &group_index) in group_indices.iter().enumerate() {
+            self.moments[group_index].merge(&partial.get(i));
+        }
+        Ok(())
+    }
+
+    fn evaluate(&mut self, emit_to: EmitTo) -> Result<ArrayRef> {
+        let moments = emit_to.take_needed(&mut self.moments);
+        let results = moments
+            .iter()
+            .map(|moments| self.stat.evaluate(moments))
+            .collect::<Vec<_>>();
+        let nulls = NullBuffer::from_iter(results.iter().map(Option::is_some));
+        let values = results
+            .into_iter()
+            .map(Option::unwrap_or_default)
+            .collect::<Vec<_>>();
+        Ok(Arc::new(Float64Array::new(values.into(), Some(nulls))))
+    }
+
+    fn state(&mut self, emit_to: EmitTo) -> Result<Vec<ArrayRef>> {
+        let moments = emit_to.take_needed(&mut self.moments);
+
+        Ok(vec![
+            Arc::new(moments.iter().map(|m| m.count).collect::<UInt64Array>()),
+            Arc::new(moments.iter().map(|m| m.sum).collect::<Float64Array>()),
+            Arc::new(moments.iter().map(|m| m.sum_sqr).collect::<Float64Array>()),
+            Arc::new(moments.iter().map(|m| m.sum_cub).collect::<Float64Array>()),
+            Arc::new(moments.iter().map(|m| m.sum_four).collect::<Float64Array>()),
+        ])
+    }
+
+    fn size(&self) -> usize {
+        self.moments.capacity() * size_of::<Moments>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn moments_of(values: &[f64]) -> Moments {
+        let mut moments = Moments::default();
+        values.iter().for_each(|v| moments.update(*v));
+        moments
+    }
+
+    fn assert_close(actual: Option<f64>, expected: f64) {
+        let actual = actual.expect("statistic is defined");
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn skewness_and_kurtosis() {
+        let moments = moments_of(&[1.0, 2.0, 3.0, 4.0, 10.0]);
+        assert_close(moments.skewness(), 1.6970562748477143);
+        assert_close(moments.kurtosis(), 3.152);
+
+        // Symmetric input
+        let moments = moments_of(&[1.0, 2.0, 3.0, 4.0]);
+        assert_close(moments.skewness(), 0.0);
+        assert_close(moments.kurtosis(), -1.2);
+    }
+
+    #[test]
+    fn undefined_moments_are_null() {
+        assert_eq!(moments_of(&[1.0, 2.0]).skewness(), None);
+        assert_eq!(moments_of(&[1.0, 2.0, 3.0]).kurtosis(), None);
+        assert_eq!(moments_of(&[5.0, 5.0, 5.0, 5.0]).skewness(), None);
+        assert_eq!(moments_of(&[5.0, 5.0, 5.0, 5.0]).kurtosis(), None);
+    }
+
+    #[test]
+    fn retract_and_merge() {
+        let mut moments = moments_of(&[100.0, 1.0, 2.0]);
+        moments.retract(100.0);
+        moments.merge(&moments_of(&[3.0, 4.0]));
+        let expected = moments_of(&[1.0, 2.0, 3.0, 4.0]);
+        assert_eq!(moments.count, expected.count);
+        assert_close(moments.skewness(), 0.0);
+        assert_close(moments.kurtosis(), expected.kurtosis().unwrap());
+    }
+}
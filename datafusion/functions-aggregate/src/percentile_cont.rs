@@ -26,7 +26,10 @@ use arrow::array::{
 use arrow::buffer::{OffsetBuffer, ScalarBuffer};
 use arrow::{
     array::{Array, ArrayRef, AsArray},
-    datatypes::{DataType, Field, FieldRef, Float16Type, Float32Type, Float64Type},
+    datatypes::{
+        DataType, Field, FieldRef, Float16Type, Float32Type, Float64Type, Int8Type,
+        Int16Type, Int32Type, Int64Type, UInt8Type, UInt16Type, UInt32Type, UInt64Type,
+    },
 };
 
 use num_traits::AsPrimitive;
@@ -213,40 +216,14 @@ impl AggregateUDFImpl for PercentileCont {
     }
 
     fn accumulator(&self, args: AccumulatorArgs) -> Result<Box<dyn Accumulator>> {
-        let percentile = get_percentile(&args)?;
+        let method = PercentileMethod::Continuous(get_percentile(&args)?);
 
         let input_dt = args.expr_fields[0].data_type();
         if input_dt.is_null() {
             return Ok(Box::new(NoopAccumulator::new(ScalarValue::Float64(None))));
         }
 
-        if args.is_distinct {
-            match input_dt {
-                DataType::Float16 => Ok(Box::new(DistinctPercentileContAccumulator::<
-                    Float16Type,
-                >::new(percentile))),
-                DataType::Float32 => Ok(Box::new(DistinctPercentileContAccumulator::<
-                    Float32Type,
-                >::new(percentile))),
-                DataType::Float64 => Ok(Box::new(DistinctPercentileContAccumulator::<
-                    Float64Type,
-                >::new(percentile))),
-                dt => internal_err!("Unsupported datatype for percentile cont: {dt}"),
-            }
-        } else {
-            match input_dt {
-                DataType::Float16 => Ok(Box::new(
-                    PercentileContAccumulator::<Float16Type>::new(percentile),
-                )),
-                DataType::Float32 => Ok(Box::new(
-                    PercentileContAccumulator::<Float32Type>::new(percentile),
-                )),
-                DataType::Float64 => Ok(Box::new(
-                    PercentileContAccumulator::<Float64Type>::new(percentile),
-                )),
-                dt => internal_err!("Unsupported datatype for percentile cont: {dt}"),
-            }
-        }
+        percentile_accumulator(self.name(), method, input_dt, args.is_distinct)
     }
 
     fn groups_accumulator_supported(&self, args: AccumulatorArgs) -> bool {
@@ -257,26 +234,17 @@ impl AggregateUDFImpl for PercentileCont {
         &self,
         args: AccumulatorArgs,
     ) -> Result<Box<dyn GroupsAccumulator>> {
-        let percentile = get_percentile(&args)?;
-
-        let input_dt = args.expr_fields[0].data_type();
-        match input_dt {
-            DataType::Float16 => Ok(Box::new(PercentileContGroupsAccumulator::<
-                Float16Type,
-            >::new(percentile))),
-            DataType::Float32 => Ok(Box::new(PercentileContGroupsAccumulator::<
-                Float32Type,
-            >::new(percentile))),
-            DataType::Float64 => Ok(Box::new(PercentileContGroupsAccumulator::<
-                Float64Type,
-            >::new(percentile))),
-            dt => internal_err!("Unsupported datatype for percentile cont: {dt}"),
-        }
+        let method = PercentileMethod::Continuous(get_percentile(&args)?);
+        percentile_groups_accumulator(
+            self.name(),
+            method,
+            args.expr_fields[0].data_type(),
+        )
     }
 
     fn simplify(&self) -> Option<AggregateFunctionSimplification> {
         Some(Box::new(|aggregate_function, info| {
-            simplify_percentile_cont_aggregate(aggregate_function, info)
+            simplify_percentile_aggregate(aggregate_function, info)
         }))
     }
 
@@ -307,7 +275,106 @@ fn get_percentile(args: &AccumulatorArgs) -> Result<f64> {
     Ok(percentile)
 }
 
-fn simplify_percentile_cont_aggregate(
+/// How a percentile is derived from the (sorted) input values.
+///
+/// The exact percentile accumulators in this module are shared by
+/// `percentile_cont` and `percentile_disc`, which only differ in how the final
+/// value is picked from the collected input.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum PercentileMethod {
+    /// Linearly interpolate between the two closest ranks. The percentile has
+    /// already been flipped for descending orderings.
+    Continuous(f64),
+    /// Return the first value (in the requested ordering) whose position in
+    /// the ordering is greater than or equal to the percentile.
+    Discrete { percentile: f64, descending: bool },
+}
+
+impl PercentileMethod {
+    fn calculate<T: ArrowNumericType>(
+        &self,
+        values: &mut [T::Native],
+    ) -> Option<T::Native>
+    where
+        T::Native: Copy + AsPrimitive<f64>,
+        f64: AsPrimitive<T::Native>,
+    {
+        match *self {
+            Self::Continuous(percentile) => calculate_percentile::<T>(values, percentile),
+            Self::Discrete {
+                percentile,
+                descending,
+            } => calculate_percentile_disc::<T>(values, percentile, descending),
+        }
+    }
+}
+
+/// Creates an exact percentile [`Accumulator`] for `input_dt`
+pub(crate) fn percentile_accumulator(
+    name: &str,
+    method: PercentileMethod,
+    input_dt: &DataType,
+    is_distinct: bool,
+) -> Result<Box<dyn Accumulator>> {
+    macro_rules! helper {
+        ($t:ty) => {
+            if is_distinct {
+                Ok(Box::new(DistinctPercentileContAccumulator::<$t>::new(
+                    method,
+                )))
+            } else {
+                Ok(Box::new(PercentileContAccumulator::<$t>::new(method)))
+            }
+        };
+    }
+
+    match input_dt {
+        DataType::Int8 => helper!(Int8Type),
+        DataType::Int16 => helper!(Int16Type),
+        DataType::Int32 => helper!(Int32Type),
+        DataType::Int64 => helper!(Int64Type),
+        DataType::UInt8 => helper!(UInt8Type),
+        DataType::UInt16 => helper!(UInt16Type),
+        DataType::UInt32 => helper!(UInt32Type),
+        DataType::UInt64 => helper!(UInt64Type),
+        DataType::Float16 => helper!(Float16Type),
+        DataType::Float32 => helper!(Float32Type),
+        DataType::Float64 => helper!(Float64Type),
+        dt => internal_err!("Unsupported datatype for {name}: {dt}"),
+    }
+}
+
+/// Creates an exact percentile [`GroupsAccumulator`] for `input_dt`
+pub(crate) fn percentile_groups_accumulator(
+    name: &str,
+    method: PercentileMethod,
+    input_dt: &DataType,
+) -> Result<Box<dyn GroupsAccumulator>> {
+    macro_rules! helper {
+        ($t:ty) => {
+            Ok(Box::new(PercentileContGroupsAccumulator::<$t>::new(method)))
+        };
+    }
+
+    match input_dt {
+        DataType::Int8 => helper!(Int8Type),
+        DataType::Int16 => helper!(Int16Type),
+        DataType::Int32 => helper!(Int32Type),
+        DataType::Int64 => helper!(Int64Type),
+        DataType::UInt8 => helper!(UInt8Type),
+        DataType::UInt16 => helper!(UInt16Type),
+        DataType::UInt32 => helper!(UInt32Type),
+        DataType::UInt64 => helper!(UInt64Type),
+        DataType::Float16 => helper!(Float16Type),
+        DataType::Float32 => helper!(Float32Type),
+        DataType::Float64 => helper!(Float64Type),
+        dt => internal_err!("Unsupported datatype for {name}: {dt}"),
+    }
+}
+
+/// Rewrites exact percentiles of 0 and 1 to `min` / `max`, which holds for
+/// both the continuous and the discrete percentile.
+pub(crate) fn simplify_percentile_aggregate(
     aggregate_function: AggregateFunction,
     info: &SimplifyContext,
 ) -> Result<Expr> {
@@ -317,7 +384,8 @@ fn simplify_percentile_cont_aggregate(
     }
 
     let params = &aggregate_function.params;
-    let [value, percentile] = take_function_args("percentile_cont", &params.args)?;
+    let [value, percentile] =
+        take_function_args(aggregate_function.func.name(), &params.args)?;
     //
     // For simplicity we don't bother with null types (otherwise we'd need to
     // cast the return type)
@@ -376,14 +444,14 @@ fn simplify_percentile_cont_aggregate(
 #[derive(Debug)]
 struct PercentileContAccumulator<T: ArrowNumericType + Debug> {
     all_values: Vec<T::Native>,
-    percentile: f64,
+    method: PercentileMethod,
 }
 
 impl<T: ArrowNumericType + Debug> PercentileContAccumulator<T> {
-    fn new(percentile: f64) -> Self {
+    fn new(method: PercentileMethod) -> Self {
         Self {
             all_values: vec![],
-            percentile,
+            method,
         }
     }
 }
@@ -432,7 +500,7 @@ where
     }
 
     fn evaluate(&mut self) -> Result<ScalarValue> {
-        let value = calculate_percentile::<T>(&mut self.all_values, self.percentile);
+        let value = self.method.calculate::<T>(&mut self.all_values);
         ScalarValue::new_primitive::<T>(value, &T::DATA_TYPE)
     }
 
@@ -486,14 +554,14 @@ where
 #[derive(Debug)]
 struct PercentileContGroupsAccumulator<T: ArrowNumericType + Send> {
     group_values: Vec<Vec<T::Native>>,
-    percentile: f64,
+    method: PercentileMethod,
 }
 
 impl<T: ArrowNumericType + Send> PercentileContGroupsAccumulator<T> {
-    fn new(percentile: f64) -> Self {
+    fn new(method: PercentileMethod) -> Self {
         Self {
             group_values: vec![],
-            percentile,
+            method,
         }
     }
 }
@@ -512,7 +580,7 @@ where
         total_num_groups: usize,
     ) -> Result<()> {
         // For ordered-set aggregates, we only care about the ORDER BY column (first element)
-        // The percentile parameter is already stored in self.percentile
+        // The percentile parameter is already stored in self.method
 
         let values = values[0].as_primitive::<T>();
 
@@ -598,7 +666,7 @@ where
         let mut evaluate_result_builder =
             PrimitiveBuilder::<T>::with_capacity(emit_group_values.len());
         for values in &mut emit_group_values {
-            let value = calculate_percentile::<T>(values.as_mut_slice(), self.percentile);
+            let value = self.method.calculate::<T>(values.as_mut_slice());
             evaluate_result_builder.append_option(value);
         }
 
@@ -669,14 +737,14 @@ where
 #[derive(Debug)]
 struct DistinctPercentileContAccumulator<T: ArrowNumericType> {
     distinct_values: GenericDistinctBuffer<T>,
-    percentile: f64,
+    method: PercentileMethod,
 }
 
 impl<T: ArrowNumericType + Debug> DistinctPercentileContAccumulator<T> {
-    fn new(percentile: f64) -> Self {
+    fn new(method: PercentileMethod) -> Self {
         Self {
             distinct_values: GenericDistinctBuffer::new(T::DATA_TYPE),
-            percentile,
+            method,
         }
     }
 }
@@ -702,7 +770,7 @@ where
     fn evaluate(&mut self) -> Result<ScalarValue> {
         let mut values: Vec<T::Native> =
             self.distinct_values.values.iter().map(|v| v.0).collect();
-        let value = calculate_percentile::<T>(&mut values, self.percentile);
+        let value = self.method.calculate::<T>(&mut values);
         ScalarValue::new_primitive::<T>(value, &T::DATA_TYPE)
     }
 
@@ -807,9 +875,35 @@ where
     }
 }
 
+/// Calculate the discrete percentile value for a given set of values.
+///
+/// Returns the first value in the requested ordering whose position in that
+/// ordering (its cumulative distribution) is greater than or equal to
+/// `percentile`, i.e. the value at 1-based rank `ceil(percentile * n)`. No
+/// interpolation is done, so the result is always one of the input values.
+///
+/// Like [`calculate_percentile`] this partially sorts `values` in place
+/// without consuming them.
+fn calculate_percentile_disc<T: ArrowNumericType>(
+    values: &mut [T::Native],
+    percentile: f64,
+    descending: bool,
+) -> Option<T::Native> {
+    let len = values.len();
+    if len == 0 {
+        return None;
+    }
+
+    let rank = ((percentile * len as f64).ceil() as usize).clamp(1, len) - 1;
+    let index = if descending { len - 1 - rank } else { rank };
+    let (_, value, _) = values.select_nth_unstable_by(index, |x, y| x.compare(*y));
+    Some(*value)
+}
+
 #[cfg(test)]
 mod tests {
-    use super::calculate_percentile;
+    use super::{calculate_percentile, calculate_percentile_disc};
+    use arrow::datatypes::Int64Type;
     use half::f16;
 
     #[test]
@@ -832,4 +926,23 @@ mod tests {
             "unexpected result {result_f}"
         );
     }
+
+    #[test]
+    fn discrete_percentile_returns_input_values() {
+        let disc = |values: &[i64], percentile: f64, descending: bool| {
+            let mut values = values.to_vec();
+            calculate_percentile_disc::<Int64Type>(&mut values, percentile, descending)
+        };
+
+        assert_eq!(disc(&[], 0.5, false), None);
+        assert_eq!(disc(&[7], 0.5, false), Some(7));
+        assert_eq!(disc(&[4, 1, 3, 2], 0.0, false), Some(1));
+        assert_eq!(disc(&[4, 1, 3, 2], 0.25, false), Some(1));
+        assert_eq!(disc(&[4, 1, 3, 2], 0.3, false), Some(2));
+        assert_eq!(disc(&[4, 1, 3, 2], 0.5, false), Some(2));
+        assert_eq!(disc(&[4, 1, 3, 2], 1.0, false), Some(4));
+        assert_eq!(disc(&[4, 1, 3, 2], 0.25, true), Some(4));
+        assert_eq!(disc(&[4, 1, 3, 2], 0.5, true), Some(3));
+        assert_eq!(disc(&[4, 1, 3, 2], 1.0, true), Some(1));
+    }
 }
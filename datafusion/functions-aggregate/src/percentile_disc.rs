@@ -0,0 +1,243 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`PercentileDisc`]: exact discrete percentile aggregation.
+
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use arrow::datatypes::{DataType, Field, FieldRef};
+
+use datafusion_common::types::{NativeType, logical_float64};
+use datafusion_common::{Result, ScalarValue};
+use datafusion_expr::utils::format_state_name;
+use datafusion_expr::{
+    Accumulator, AggregateUDFImpl, Coercion, Documentation, Expr, GroupsAccumulator,
+    Signature, TypeSignature, TypeSignatureClass, Volatility,
+    expr::{AggregateFunction, Sort},
+    function::{AccumulatorArgs, AggregateFunctionSimplification, StateFieldsArgs},
+};
+use datafusion_functions_aggregate_common::noop_accumulator::NoopAccumulator;
+use datafusion_macros::user_doc;
+
+use crate::percentile_cont::{
+    PercentileMethod, percentile_accumulator, percentile_groups_accumulator,
+    simplify_percentile_aggregate,
+};
+use crate::utils::validate_percentile_expr;
+
+create_func!(PercentileDisc, percentile_disc_udaf);
+
+/// Computes the exact discrete percentile of a set of values
+pub fn percentile_disc(order_by: Sort, percentile: Expr) -> Expr {
+    let expr = order_by.expr.clone();
+    let args = vec![expr, percentile];
+
+    Expr::AggregateFunction(AggregateFunction::new_udf(
+        percentile_disc_udaf(),
+        args,
+        false,
+        None,
+        vec![order_by],
+        None,
+    ))
+}
+
+#[user_doc(
+    doc_section(label = "General Functions"),
+    description = "Returns the first input value whose position in the ordering is greater than or equal to the percentile. Unlike `percentile_cont`, no interpolation is done, so the result is always one of the input values.",
+    syntax_example = "percentile_disc(percentile) WITHIN GROUP (ORDER BY expression)",
+    sql_example = r#"```sql
+> SELECT percentile_disc(0.5) WITHIN GROUP (ORDER BY column_name) FROM table_name;
++-----------------------------------------------------------+
+| percentile_disc(0.5) WITHIN GROUP (ORDER BY column_name)  |
++-----------------------------------------------------------+
+| 45                                                        |
++-----------------------------------------------------------+
+```
+
+An alternate syntax is also supported:
+```sql
+> SELECT percentile_disc(column_name, 0.5) FROM table_name;
++--------------------------------------+
+| percentile_disc(column_name, 0.5)    |
++--------------------------------------+
+| 45                                   |
++--------------------------------------+
+```"#,
+    standard_argument(name = "expression", prefix = "The"),
+    argument(
+        name = "percentile",
+        description = "Percentile to compute. Must be a float value between 0 and 1 (inclusive)."
+    )
+)]
+/// PERCENTILE_DISC aggregate expression. Like PERCENTILE_CONT this is an exact
+/// calculation that stores all values in memory before computing the result,
+/// and it shares its accumulators with PERCENTILE_CONT.
+///
+/// Integer inputs keep their type, other numeric inputs are coerced to
+/// `Float64`.
+#[derive(PartialEq, Eq, Hash, Debug)]
+pub struct PercentileDisc {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl Default for PercentileDisc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PercentileDisc {
+    pub fn new() -> Self {
+        let percentile = Coercion::new_implicit(
+            TypeSignatureClass::Native(logical_float64()),
+            vec![TypeSignatureClass::Numeric],
+            NativeType::Float64,
+        );
+        Self {
+            signature: Signature::one_of(
+                vec![
+                    TypeSignature::Coercible(vec![
+                        Coercion::new_exact(TypeSignatureClass::Integer),
+                        percentile.clone(),
+                    ]),
+                    TypeSignature::Coercible(vec![
+                        Coercion::new_implicit(
+                            TypeSignatureClass::Float,
+                            vec![TypeSignatureClass::Numeric],
+                            NativeType::Float64,
+                        ),
+                        percentile,
+                    ]),
+                ],
+                Volatility::Immutable,
+            )
+            .with_parameter_names(vec!["expr", "percentile"])
+            .unwrap(),
+            aliases: vec![String::from("quantile_disc")],
+        }
+    }
+}
+
+impl AggregateUDFImpl for PercentileDisc {
+    fn name(&self) -> &str {
+        "percentile_disc"
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        match &arg_types[0] {
+            DataType::Null => Ok(DataType::Float64),
+            dt => Ok(dt.clone()),
+        }
+    }
+
+    fn state_fields(&self, args: StateFieldsArgs) -> Result<Vec<FieldRef>> {
+        let input_type = args.input_fields[0].data_type().clone();
+        if input_type.is_null() {
+            return Ok(vec![
+                Field::new(
+                    format_state_name(args.name, self.name()),
+                    DataType::Null,
+                    true,
+                )
+                .into(),
+            ]);
+        }
+
+        let field = Field::new_list_field(input_type, true);
+        let state_name = if args.is_distinct {
+            "distinct_percentile_disc"
+        } else {
+            "percentile_disc"
+        };
+
+        Ok(vec![
+            Field::new(
+                format_state_name(args.name, state_name),
+                DataType::List(Arc::new(field)),
+                true,
+            )
+            .into(),
+        ])
+    }
+
+    fn accumulator(&self, args: AccumulatorArgs) -> Result<Box<dyn Accumulator>> {
+        let method = get_method(&args)?;
+
+        let input_dt = args.expr_fields[0].data_type();
+        if input_dt.is_null() {
+            return Ok(Box::new(NoopAccumulator::new(ScalarValue::Float64(None))));
+        }
+
+        percentile_accumulator(self.name(), method, input_dt, args.is_distinct)
+    }
+
+    fn groups_accumulator_supported(&self, args: AccumulatorArgs) -> bool {
+        !args.is_distinct && !args.expr_fields[0].data_type().is_null()
+    }
+
+    fn create_groups_accumulator(
+        &self,
+        args: AccumulatorArgs,
+    ) -> Result<Box<dyn GroupsAccumulator>> {
+        let method = get_method(&args)?;
+        percentile_groups_accumulator(
+            self.name(),
+            method,
+            args.expr_fields[0].data_type(),
+        )
+    }
+
+    fn simplify(&self) -> Option<AggregateFunctionSimplification> {
+        Some(Box::new(|aggregate_function, info| {
+            simplify_percentile_aggregate(aggregate_function, info)
+        }))
+    }
+
+    fn supports_within_group_clause(&self) -> bool {
+        true
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+fn get_method(args: &AccumulatorArgs) -> Result<PercentileMethod> {
+    let percentile = validate_percentile_expr(&args.exprs[1], "PERCENTILE_DISC")?;
+
+    let descending = args
+        .order_bys
+        .first()
+        .map(|sort_expr| sort_expr.options.descending)
+        .unwrap_or(false);
+
+    Ok(PercentileMethod::Discrete {
+        percentile,
+        descending,
+    })
+}
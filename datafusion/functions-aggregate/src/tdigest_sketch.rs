@@ -0,0 +1,507 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! T-Digest sketches exposed as SQL values: [`TDigestSketch`],
+//! [`TDigestMerge`] and [`TDigestQuantile`].
+//!
+//! Sketches are serialized with [`TDigest::to_bytes`], a stable binary
+//! format, so they can be stored, for example in Parquet files, and merged
+//! later.
+//!
+//! KLL sketches, an alternative for quantiles with deterministic error
+//! bounds, are out of scope and not implemented.
+
+use std::fmt::Debug;
+use std::mem::size_of_val;
+use std::sync::{Arc, LazyLock};
+
+use arrow::array::{Array, ArrayRef, BinaryArray, Float64Array};
+use arrow::compute::{filter, is_not_null};
+use arrow::datatypes::{DataType, Field, FieldRef};
+use datafusion_common::cast::{as_binary_array, as_float64_array};
+use datafusion_common::utils::take_function_args;
+use datafusion_common::{Result, ScalarValue, downcast_value, exec_err, plan_err};
+use datafusion_expr::function::{AccumulatorArgs, StateFieldsArgs};
+use datafusion_expr::utils::format_state_name;
+use datafusion_expr::{
+    Accumulator, AggregateUDFImpl, ColumnarValue, Documentation, Expr,
+    ScalarFunctionArgs, ScalarUDF, ScalarUDFImpl, Signature, Volatility,
+};
+use datafusion_functions_aggregate_common::tdigest::{
+    DEFAULT_MAX_SIZE, MAX_SKETCH_MAX_SIZE, TDigest,
+};
+use datafusion_macros::user_doc;
+
+use crate::approx_percentile_cont::ApproxPercentileAccumulator;
+use crate::utils::{coerce_sketch_type, validate_positive_integer_expr};
+
+make_udaf_expr_and_func!(
+    TDigestSketch,
+    tdigest_sketch,
+    expression,
+    "Creates a t-digest sketch of the input values",
+    tdigest_sketch_udaf
+);
+
+make_udaf_expr_and_func!(
+    TDigestMerge,
+    tdigest_merge,
+    sketch,
+    "Merges t-digest sketches into a single sketch",
+    tdigest_merge_udaf
+);
+
+/// Returns a [`ScalarUDF`] for [`TDigestQuantile`]
+pub fn tdigest_quantile_udf() -> Arc<ScalarUDF> {
+    static INSTANCE: LazyLock<Arc<ScalarUDF>> =
+        LazyLock::new(|| Arc::new(ScalarUDF::from(TDigestQuantile::new())));
+    Arc::clone(&INSTANCE)
+}
+
+/// Returns the approximate quantile of the values of a t-digest sketch
+pub fn tdigest_quantile(sketch: Expr, quantile: Expr) -> Expr {
+    tdigest_quantile_udf().call(vec![sketch, quantile])
+}
+
+fn tdigest_state_fields(name: &str) -> Vec<FieldRef> {
+    vec![Field::new(format_state_name(name, "tdigest"), DataType::Binary, false).into()]
+}
+
+/// Merges the serialized digests in `sketches` into `digest`.
+///
+/// The merged digest keeps the `max_size` of the first non-empty digest.
+fn merge_sketches(digest: &mut TDigest, sketches: &ArrayRef) -> Result<()> {
+    let sketches = downcast_value!(sketches, BinaryArray);
+    let mut digests = Vec::with_capacity(sketches.len() + 1);
+    if digest.count() > 0.0 {
+        digests.push(digest.clone());
+    }
+    for sketch in sketches.iter().flatten() {
+        let other = TDigest::try_from_bytes(sketch)?;
+        if other.count() > 0.0 {
+            digests.push(other);
+        }
+    }
+
+    if !digests.is_empty() {
+        *digest = TDigest::merge_digests(&digests);
+    }
+    Ok(())
+}
+
+#[user_doc(
+    doc_section(label = "Approximate Functions"),
+    description = "Creates a t-digest sketch of the input values. The sketch is a binary value that can be stored and later combined with `tdigest_merge` and evaluated with `tdigest_quantile`. KLL quantile sketches are not supported.",
+    syntax_example = "tdigest_sketch(expression[, max_size])",
+    sql_example = r#"```sql
+> SELECT day, tdigest_sketch(latency) AS latencies FROM requests GROUP BY day;
++------------+--------------------------------+
+| day        | latencies                      |
++------------+--------------------------------+
+| 2024-01-01 | 54444701640000000000000000c0.. |
++------------+--------------------------------+
+```"#,
+    standard_argument(name = "expression", prefix = "Numeric"),
+    argument(
+        name = "max_size",
+        description = "Maximum number of centroids of the sketch. Larger values are more accurate but use more memory. Defaults to 100 and can be at most 100000."
+    )
+)]
+/// TDIGEST_SKETCH aggregate expression
+#[derive(PartialEq, Eq, Hash, Debug)]
+pub struct TDigestSketch {
+    signature: Signature,
+}
+
+impl Default for TDigestSketch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TDigestSketch {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::user_defined(Volatility::Immutable),
+        }
+    }
+}
+
+impl AggregateUDFImpl for TDigestSketch {
+    fn name(&self) -> &str {
+        "tdigest_sketch"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        let (value_type, max_size_type) = match arg_types {
+            [value_type] => (value_type, None),
+            [value_type, max_size_type] => (value_type, Some(max_size_type)),
+            _ => {
+                return plan_err!(
+                    "{} was called with {} arguments. It requires 1 or 2.",
+                    self.name(),
+                    arg_types.len()
+                );
+            }
+        };
+
+        if !value_type.is_numeric() && !value_type.is_null() {
+            return plan_err!("{} requires numeric input, got {value_type}", self.name());
+        }
+        let mut coerced = vec![DataType::Float64];
+        match max_size_type {
+            Some(t) if t.is_integer() || t.is_null() => coerced.push(DataType::Int64),
+            Some(t) => {
+                return plan_err!(
+                    "The second argument of {} must be an integer, got {t}",
+                    self.name()
+                );
+            }
+            None => {}
+        }
+        Ok(coerced)
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Binary)
+    }
+
+    fn is_nullable(&self) -> bool {
+        false
+    }
+
+    fn state_fields(&self, args: StateFieldsArgs) -> Result<Vec<FieldRef>> {
+        Ok(tdigest_state_fields(args.name))
+    }
+
+    fn accumulator(&self, acc_args: AccumulatorArgs) -> Result<Box<dyn Accumulator>> {
+        let max_size = acc_args
+            .exprs
+            .get(1)
+            .map(|expr| validate_positive_integer_expr(expr, self.name(), "second"))
+            .transpose()?
+            .unwrap_or(DEFAULT_MAX_SIZE);
+        if max_size > MAX_SKETCH_MAX_SIZE {
+            return plan_err!(
+                "The second argument of {} must be at most {MAX_SKETCH_MAX_SIZE}, got {max_size}",
+                self.name()
+            );
+        }
+        Ok(Box::new(TDigestSketchAccumulator {
+            digest: TDigest::new(max_size),
+        }))
+    }
+
+    fn default_value(&self, _data_type: &DataType) -> Result<ScalarValue> {
+        Ok(ScalarValue::Binary(Some(
+            TDigest::new(DEFAULT_MAX_SIZE).to_bytes(),
+        )))
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+#[derive(Debug)]
+struct TDigestSketchAccumulator {
+    digest: TDigest,
+}
+
+impl Accumulator for TDigestSketchAccumulator {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        let mut values = Arc::clone(&values[0]);
+        if values.null_count() > 0 {
+            values = filter(&values, &is_not_null(&values)?)?;
+        }
+        let sorted_values = &arrow::compute::sort(&values, None)?;
+        let sorted_values = ApproxPercentileAccumulator::convert_to_float(sorted_values)?;
+        self.digest = self.digest.merge_sorted_f64(&sorted_values);
+        Ok(())
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        merge_sketches(&mut self.digest, &states[0])
+    }
+
+    fn state(&mut self) -> Result<Vec<ScalarValue>> {
+        Ok(vec![self.evaluate()?])
+    }
+
+    fn evaluate(&mut self) -> Result<ScalarValue> {
+        Ok(ScalarValue::Binary(Some(self.digest.to_bytes())))
+    }
+
+    fn size(&self) -> usize {
+        size_of_val(self) + self.digest.size() - size_of_val(&self.digest)
+    }
+}
+
+#[user_doc(
+    doc_section(label = "Approximate Functions"),
+    description = "Merges t-digest sketches created by `tdigest_sketch` or `tdigest_merge` into a single sketch, for example to roll up daily sketches into monthly ones. NULL sketches are ignored.",
+    syntax_example = "tdigest_merge(sketch)",
+    sql_example = r#"```sql
+> SELECT tdigest_quantile(tdigest_merge(latencies), 0.99) FROM daily_latencies;
++--------------------------------------------------------------------------+
+| tdigest_quantile(tdigest_merge(daily_latencies.latencies),Float64(0.99)) |
++--------------------------------------------------------------------------+
+| 250.0                                                                    |
++--------------------------------------------------------------------------+
+```"#,
+    argument(name = "sketch", description = "T-digest sketch to merge.")
+)]
+/// TDIGEST_MERGE aggregate expression
+#[derive(PartialEq, Eq, Hash, Debug)]
+pub struct TDigestMerge {
+    signature: Signature,
+}
+
+impl Default for TDigestMerge {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TDigestMerge {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::user_defined(Volatility::Immutable),
+        }
+    }
+}
+
+impl AggregateUDFImpl for TDigestMerge {
+    fn name(&self) -> &str {
+        "tdigest_merge"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        let [arg_type] = take_function_args(self.name(), arg_types)?;
+        Ok(vec![coerce_sketch_type(arg_type, self.name())?])
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Binary)
+    }
+
+    fn is_nullable(&self) -> bool {
+        false
+    }
+
+    fn state_fields(&self, args: StateFieldsArgs) -> Result<Vec<FieldRef>> {
+        Ok(tdigest_state_fields(args.name))
+    }
+
+    fn accumulator(&self, _acc_args: AccumulatorArgs) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(TDigestMergeAccumulator {
+            digest: TDigest::new(DEFAULT_MAX_SIZE),
+        }))
+    }
+
+    fn default_value(&self, _data_type: &DataType) -> Result<ScalarValue> {
+        Ok(ScalarValue::Binary(Some(
+            TDigest::new(DEFAULT_MAX_SIZE).to_bytes(),
+        )))
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+/// Accumulator for `tdigest_merge`. Both its input and its state are
+/// serialized digests.
+#[derive(Debug)]
+struct TDigestMergeAccumulator {
+    digest: TDigest,
+}
+
+impl Accumulator for TDigestMergeAccumulator {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        merge_sketches(&mut self.digest, &values[0])
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        merge_sketches(&mut self.digest, &states[0])
+    }
+
+    fn state(&mut self) -> Result<Vec<ScalarValue>> {
+        Ok(vec![self.evaluate()?])
+    }
+
+    fn evaluate(&mut self) -> Result<ScalarValue> {
+        Ok(ScalarValue::Binary(Some(self.digest.to_bytes())))
+    }
+
+    fn size(&self) -> usize {
+        size_of_val(self) + self.digest.size() - size_of_val(&self.digest)
+    }
+}
+
+#[user_doc(
+    doc_section(label = "Other Functions"),
+    description = "Returns the approximate quantile of the values of a t-digest sketch created by `tdigest_sketch` or `tdigest_merge`, or NULL if the sketch is empty.",
+    syntax_example = "tdigest_quantile(sketch, quantile)",
+    sql_example = r#"```sql
+> SELECT day, tdigest_quantile(latencies, 0.5) FROM daily_latencies;
++------------+----------------------------------------------------------+
+| day        | tdigest_quantile(daily_latencies.latencies,Float64(0.5)) |
++------------+----------------------------------------------------------+
+| 2024-01-01 | 42.0                                                     |
++------------+----------------------------------------------------------+
+```"#,
+    argument(name = "sketch", description = "T-digest sketch to evaluate."),
+    argument(
+        name = "quantile",
+        description = "Quantile to compute. Must be a float value between 0 and 1 (inclusive)."
+    )
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct TDigestQuantile {
+    signature: Signature,
+}
+
+impl Default for TDigestQuantile {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TDigestQuantile {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::user_defined(Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for TDigestQuantile {
+    fn name(&self) -> &str {
+        "tdigest_quantile"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        let [sketch_type, quantile_type] = take_function_args(self.name(), arg_types)?;
+        if !quantile_type.is_numeric() && !quantile_type.is_null() {
+            return plan_err!(
+                "The second argument of {} must be numeric, got {quantile_type}",
+                self.name()
+            );
+        }
+        Ok(vec![
+            coerce_sketch_type(sketch_type, self.name())?,
+            DataType::Float64,
+        ])
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Float64)
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        let is_scalar = args
+            .args
+            .iter()
+            .all(|arg| matches!(arg, ColumnarValue::Scalar(_)));
+        let arrays = ColumnarValue::values_to_arrays(&args.args)?;
+        let [sketches, quantiles] = take_function_args(self.name(), &arrays)?;
+
+        let quantiles = as_float64_array(quantiles)?;
+        let result = as_binary_array(sketches)?
+            .iter()
+            .zip(quantiles.iter())
+            .map(|(sketch, quantile)| match (sketch, quantile) {
+                (Some(sketch), Some(quantile)) => estimate_quantile(sketch, quantile),
+                _ => Ok(None),
+            })
+            .collect::<Result<Float64Array>>()?;
+
+        if is_scalar {
+            ScalarValue::try_from_array(&result, 0).map(ColumnarValue::Scalar)
+        } else {
+            Ok(ColumnarValue::Array(Arc::new(result)))
+        }
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+fn estimate_quantile(sketch: &[u8], quantile: f64) -> Result<Option<f64>> {
+    if !(0.0..=1.0).contains(&quantile) {
+        return exec_err!(
+            "The quantile of tdigest_quantile must be between 0 and 1, got {quantile}"
+        );
+    }
+    let digest = TDigest::try_from_bytes(sketch)?;
+    if digest.count() == 0.0 {
+        return Ok(None);
+    }
+    Ok(Some(digest.estimate_quantile(quantile)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::Int64Array;
+
+    #[test]
+    fn tdigest_merge_sketches() -> Result<()> {
+        let mut sketches = vec![];
+        for values in [0..50, 50..100] {
+            let mut acc = TDigestSketchAccumulator {
+                digest: TDigest::new(DEFAULT_MAX_SIZE),
+            };
+            let values: ArrayRef = Arc::new(Int64Array::from_iter_values(values));
+            let values = arrow::compute::cast(&values, &DataType::Float64)?;
+            acc.update_batch(&[values])?;
+            sketches.push(acc.evaluate()?);
+        }
+        sketches.push(ScalarValue::Binary(None));
+        let sketches = ScalarValue::iter_to_array(sketches)?;
+
+        let mut acc = TDigestMergeAccumulator {
+            digest: TDigest::new(DEFAULT_MAX_SIZE),
+        };
+        acc.update_batch(&[sketches])?;
+        let ScalarValue::Binary(Some(merged)) = acc.evaluate()? else {
+            unreachable!()
+        };
+        assert_eq!(TDigest::try_from_bytes(&merged)?.count(), 100.0);
+        assert_eq!(estimate_quantile(&merged, 1.0)?, Some(99.0));
+        assert_eq!(estimate_quantile(&merged, 0.0)?, Some(0.0));
+        assert!(estimate_quantile(&merged, 1.5).is_err());
+
+        let empty = TDigest::new(DEFAULT_MAX_SIZE).to_bytes();
+        assert_eq!(estimate_quantile(&empty, 0.5)?, None);
+        Ok(())
+    }
+}
@@ -18,7 +18,7 @@
 use std::sync::Arc;
 
 use arrow::array::RecordBatch;
-use arrow::datatypes::Schema;
+use arrow::datatypes::{DataType, Schema};
 use datafusion_common::{DataFusionError, Result, ScalarValue, internal_err, plan_err};
 use datafusion_expr::ColumnarValue;
 use datafusion_physical_expr_common::physical_expr::PhysicalExpr;
@@ -75,3 +75,43 @@ pub(crate) fn validate_percentile_expr(
     }
     Ok(percentile)
 }
+
+/// Validates that an expression is a positive integer literal, such as the
+/// `k` of `approx_top_k`.
+///
+/// `arg_name` names the argument in error messages, e.g. `"second"`.
+pub(crate) fn validate_positive_integer_expr(
+    expr: &Arc<dyn PhysicalExpr>,
+    fn_name: &str,
+    arg_name: &str,
+) -> Result<usize> {
+    let scalar_value = get_scalar_value(expr).map_err(|_e| {
+        DataFusionError::Plan(format!(
+            "The {arg_name} argument of {fn_name} must be a literal"
+        ))
+    })?;
+
+    match scalar_value {
+        ScalarValue::Int64(Some(n)) if n > 0 => Ok(n as usize),
+        n => plan_err!(
+            "The {arg_name} argument of {fn_name} must be a positive integer, got {n}"
+        ),
+    }
+}
+
+/// Returns the type that a serialized sketch argument, such as the input of
+/// `hll_merge`, is coerced to.
+///
+/// Sketches are always processed as [`DataType::Binary`], but may be stored
+/// using any binary type, e.g. `BinaryView` when read from Parquet.
+pub(crate) fn coerce_sketch_type(arg_type: &DataType, fn_name: &str) -> Result<DataType> {
+    match arg_type {
+        DataType::Binary
+        | DataType::LargeBinary
+        | DataType::BinaryView
+        | DataType::FixedSizeBinary(_)
+        | DataType::Null => Ok(DataType::Binary),
+        DataType::Dictionary(_, value_type) => coerce_sketch_type(value_type, fn_name),
+        other => plan_err!("{fn_name} expects a binary sketch, got {other}"),
+    }
+}
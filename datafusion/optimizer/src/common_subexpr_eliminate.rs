@@ -567,6 +567,7 @@ impl OptimizerRule for CommonSubexprEliminate {
             LogicalPlan::Aggregate(agg) => self.try_optimize_aggregate(agg, config)?,
             LogicalPlan::Join(_)
             | LogicalPlan::Repartition(_)
+            | LogicalPlan::Sample(_)
             | LogicalPlan::Union(_)
             | LogicalPlan::TableScan(_)
             | LogicalPlan::Values(_)
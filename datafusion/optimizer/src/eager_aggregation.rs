@@ -0,0 +1,733 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`EagerAggregation`] pre-aggregates the input of an inner join below the
+//! join
+
+use std::sync::Arc;
+
+use crate::optimizer::ApplyOrder;
+use crate::utils::{project_as, unalias};
+use crate::{OptimizerConfig, OptimizerRule};
+
+use arrow::datatypes::DataType;
+use datafusion_common::tree_node::Transformed;
+use datafusion_common::{Column, Dependency, NullEquality, Result};
+use datafusion_expr::expr::AggregateFunction;
+use datafusion_expr::{
+    Aggregate, AggregateUDF, Expr, ExprSchemable, Join, JoinType, LogicalPlan, cast, lit,
+};
+
+/// Optimizer rule that pushes decomposable aggregates below inner joins.
+///
+/// For a star-schema query such as
+///
+/// ```text
+/// SELECT d.region, sum(f.amount)
+/// FROM fact f JOIN dim d ON f.dim_id = d.id
+/// GROUP BY d.region
+/// ```
+///
+/// where `d.id` is a key of `dim`, each row of `fact` matches at most one
+/// row of `dim`. `fact` can then be pre-aggregated on `f.dim_id` (and the
+/// grouping columns of `fact`) before the join, and the partial results
+/// combined by the aggregate above the join:
+///
+/// ```text
+/// Aggregate: groupBy=[[d.region]], aggr=[[sum(__eager_agg_1)]]
+///   Inner Join: f.dim_id = d.id
+///     Aggregate: groupBy=[[f.dim_id]], aggr=[[sum(f.amount) AS __eager_agg_1]]
+///       TableScan: fact
+///     TableScan: dim
+/// ```
+///
+/// `sum`, `count`, `min`, `max` and `avg` (as `sum / count`) are pushed
+/// down if all of them only read columns of the pre-aggregated input, and
+/// the join keys of the other input contain one of its unique keys, as
+/// reported by its [`FunctionalDependencies`].
+///
+/// As the pre-aggregation only pays off if it reduces the number of rows
+/// significantly, the aggregates are only pushed down if the statistics of
+/// the pre-aggregated table estimate at most
+/// `eager_aggregation_max_group_ratio` groups per row.
+///
+/// The rule is experimental and only applied if `enable_eager_aggregation` is
+/// set.
+///
+/// [`FunctionalDependencies`]: datafusion_common::FunctionalDependencies
+#[derive(Default, Debug)]
+pub struct EagerAggregation {}
+
+impl EagerAggregation {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl OptimizerRule for EagerAggregation {
+    fn name(&self) -> &str {
+        "eager_aggregation"
+    }
+
+    fn apply_order(&self) -> Option<ApplyOrder> {
+        Some(ApplyOrder::BottomUp)
+    }
+
+    fn supports_rewrite(&self) -> bool {
+        true
+    }
+
+    fn rewrite(
+        &self,
+        plan: LogicalPlan,
+        config: &dyn OptimizerConfig,
+    ) -> Result<Transformed<LogicalPlan>> {
+        if !config.options().optimizer.enable_eager_aggregation {
+            return Ok(Transformed::no(plan));
+        }
+        let LogicalPlan::Aggregate(aggregate) = &plan else {
+            return Ok(Transformed::no(plan));
+        };
+        match try_push_down(aggregate, config)? {
+            Some(rewritten) => Ok(Transformed::yes(rewritten)),
+            None => Ok(Transformed::no(plan)),
+        }
+    }
+}
+
+/// How an output of the original aggregate is computed from the outputs of
+/// the aggregate above the join
+enum Output {
+    /// The output at this index
+    Aggregate(usize),
+    /// The `sum` of partial counts at this index, wrapped in `coalesce(.., 0)`
+    Count(usize),
+    /// The quotient of the (sum, count) outputs at these indices
+    Avg(usize, usize),
+}
+
+fn try_push_down(
+    aggregate: &Aggregate,
+    config: &dyn OptimizerConfig,
+) -> Result<Option<LogicalPlan>> {
+    let LogicalPlan::Join(join) = aggregate.input.as_ref() else {
+        return Ok(None);
+    };
+    if join.join_type != JoinType::Inner
+        || join.filter.is_some()
+        || join.on.is_empty()
+        || join.null_equality != NullEquality::NullEqualsNothing
+        || join.null_aware
+    {
+        return Ok(None);
+    }
+    let Some(group_columns) = aggregate
+        .group_expr
+        .iter()
+        .map(|expr| match expr {
+            Expr::Column(column) => Some(column.clone()),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()
+    else {
+        return Ok(None);
+    };
+
+    let mut aggregates = Vec::with_capacity(aggregate.aggr_expr.len());
+    for (i, expr) in aggregate.aggr_expr.iter().enumerate() {
+        let Expr::AggregateFunction(function) = unalias(expr) else {
+            return Ok(None);
+        };
+        let params = &function.params;
+        if params.distinct || params.filter.is_some() || !params.order_by.is_empty() {
+            return Ok(None);
+        }
+        let supported = match function.func.name() {
+            "sum" | "count" | "min" | "max" => true,
+            // Other result types, such as decimals, are not computed as
+            // sum / count
+            "avg" => {
+                let field = aggregate.schema.field(aggregate.group_expr.len() + i);
+                *field.data_type() == DataType::Float64
+            }
+            _ => false,
+        };
+        if !supported {
+            return Ok(None);
+        }
+        aggregates.push(function);
+    }
+    let argument_columns = aggregate
+        .aggr_expr
+        .iter()
+        .flat_map(|expr| expr.column_refs())
+        .collect::<Vec<_>>();
+
+    // Pre-aggregate the side read by the aggregates, if the other side is
+    // unique on its join keys
+    let (left_keys, right_keys): (Vec<_>, Vec<_>) = join.on.iter().cloned().unzip();
+    let (side, keys) =
+        if is_pushable(&join.left, &argument_columns, &join.right, &right_keys) {
+            (&join.left, left_keys)
+        } else if is_pushable(&join.right, &argument_columns, &join.left, &left_keys) {
+            (&join.right, right_keys)
+        } else {
+            return Ok(None);
+        };
+
+    // Group by the join keys and the grouping columns of the side
+    let Some(key_columns) = keys
+        .iter()
+        .map(|key| key.try_as_col())
+        .collect::<Option<Vec<_>>>()
+    else {
+        return Ok(None);
+    };
+    let mut pre_group_columns: Vec<Column> = vec![];
+    for column in key_columns.into_iter().chain(&group_columns) {
+        if side.schema().has_column(column) && !pre_group_columns.contains(column) {
+            pre_group_columns.push(column.clone());
+        }
+    }
+
+    let Some((rows, distinct_counts)) = column_statistics(side, &pre_group_columns)
+    else {
+        return Ok(None);
+    };
+    let groups = distinct_counts
+        .into_iter()
+        .fold(1usize, usize::saturating_mul)
+        .min(rows);
+    let max_group_ratio = config.options().optimizer.eager_aggregation_max_group_ratio;
+    if groups as f64 > max_group_ratio * rows as f64 {
+        return Ok(None);
+    }
+
+    let registry = config.function_registry();
+    let Some(sum) = registry.and_then(|r| r.udaf("sum").ok()) else {
+        return Ok(None);
+    };
+    let mut partials = vec![];
+    let mut finals = vec![];
+    let mut outputs = Vec::with_capacity(aggregates.len());
+    let mut partial = |expr: Expr| {
+        let name = config.alias_generator().next("__eager_agg");
+        partials.push(expr.alias(&name));
+        Expr::Column(Column::from_name(name))
+    };
+    for function in aggregates {
+        let AggregateFunction { func, params } = function;
+        let call = |func: Arc<AggregateUDF>| {
+            Expr::AggregateFunction(AggregateFunction::new_udf(
+                func,
+                params.args.clone(),
+                false,
+                None,
+                vec![],
+                None,
+            ))
+        };
+        match func.name() {
+            "sum" | "min" | "max" => {
+                let column = partial(call(Arc::clone(func)));
+                outputs.push(Output::Aggregate(finals.len()));
+                finals.push(func.call(vec![column]));
+            }
+            "count" => {
+                let column = partial(call(Arc::clone(func)));
+                outputs.push(Output::Count(finals.len()));
+                finals.push(sum.call(vec![column]));
+            }
+            _ => {
+                let Some(count) = registry.and_then(|r| r.udaf("count").ok()) else {
+                    return Ok(None);
+                };
+                // Sum in Float64, like `avg` does, so that sums of integers
+                // can not overflow
+                let args = params
+                    .args
+                    .iter()
+                    .map(|arg| {
+                        Ok(match arg.get_type(side.schema())? {
+                            DataType::Float64 => arg.clone(),
+                            _ => cast(arg.clone(), DataType::Float64),
+                        })
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                let sum_column =
+                    partial(Expr::AggregateFunction(AggregateFunction::new_udf(
+                        Arc::clone(&sum),
+                        args,
+                        false,
+                        None,
+                        vec![],
+                        None,
+                    )));
+                let count_column = partial(call(count));
+                outputs.push(Output::Avg(finals.len(), finals.len() + 1));
+                finals.push(sum.call(vec![sum_column]));
+                finals.push(sum.call(vec![count_column]));
+            }
+        }
+    }
+
+    let pre_aggregate = LogicalPlan::Aggregate(Aggregate::try_new(
+        Arc::clone(side),
+        pre_group_columns.into_iter().map(Expr::Column).collect(),
+        partials,
+    )?);
+    let (left, right) = if Arc::ptr_eq(side, &join.left) {
+        (Arc::new(pre_aggregate), Arc::clone(&join.right))
+    } else {
+        (Arc::clone(&join.left), Arc::new(pre_aggregate))
+    };
+    let join = Join::try_new(
+        left,
+        right,
+        join.on.clone(),
+        None,
+        JoinType::Inner,
+        join.join_constraint,
+        join.null_equality,
+        false,
+    )?;
+    let num_groups = aggregate.group_expr.len();
+    let final_aggregate = LogicalPlan::Aggregate(Aggregate::try_new(
+        Arc::new(LogicalPlan::Join(join)),
+        aggregate.group_expr.clone(),
+        finals,
+    )?);
+
+    let columns = final_aggregate.schema().columns();
+    let (group_outputs, aggr_outputs) = columns.split_at(num_groups);
+    let coalesce = registry.and_then(|r| r.udf("coalesce").ok());
+    let mut exprs = group_outputs
+        .iter()
+        .cloned()
+        .map(Expr::Column)
+        .collect::<Vec<_>>();
+    for output in outputs {
+        let expr = match output {
+            Output::Aggregate(i) => Expr::Column(aggr_outputs[i].clone()),
+            // `count` is never NULL, while `sum` is nullable and, without
+            // grouping, NULL for no rows
+            Output::Count(i) => {
+                let Some(coalesce) = &coalesce else {
+                    return Ok(None);
+                };
+                coalesce.call(vec![Expr::Column(aggr_outputs[i].clone()), lit(0i64)])
+            }
+            Output::Avg(sum, count) => {
+                Expr::Column(aggr_outputs[sum].clone())
+                    / cast(Expr::Column(aggr_outputs[count].clone()), DataType::Float64)
+            }
+        };
+        exprs.push(expr);
+    }
+    project_as(final_aggregate, exprs, &aggregate.schema).map(Some)
+}
+
+/// Returns true if the aggregates, which read `argument_columns`, can be
+/// computed from a pre-aggregation of `side`, because `side` is not already
+/// aggregated and `other` is unique on `other_keys`
+fn is_pushable(
+    side: &LogicalPlan,
+    argument_columns: &[&Column],
+    other: &LogicalPlan,
+    other_keys: &[Expr],
+) -> bool {
+    if matches!(side, LogicalPlan::Aggregate(_)) {
+        return false;
+    }
+    if !argument_columns
+        .iter()
+        .all(|column| side.schema().has_column(column))
+    {
+        return false;
+    }
+    let other_schema = other.schema();
+    let key_indices = other_keys
+        .iter()
+        .filter_map(|key| key.try_as_col())
+        .filter_map(|column| other_schema.index_of_column(column).ok())
+        .collect::<Vec<_>>();
+    other_schema.functional_dependencies().iter().any(|dep| {
+        dep.mode == Dependency::Single
+            && dep
+                .source_indices
+                .iter()
+                .all(|index| key_indices.contains(index))
+    })
+}
+
+/// Returns the estimated number of rows of `plan` and the estimated number
+/// of distinct values of each of `columns`, from the statistics of the
+/// table scanned by `plan`
+fn column_statistics(
+    plan: &LogicalPlan,
+    columns: &[Column],
+) -> Option<(usize, Vec<usize>)> {
+    match plan {
+        LogicalPlan::TableScan(scan) => {
+            let statistics = scan.source.statistics()?;
+            let rows = *statistics.num_rows.get_value()?;
+            let distinct_counts = columns
+                .iter()
+                .map(|column| {
+                    let index = scan.projected_schema.index_of_column(column).ok()?;
+                    let index = match &scan.projection {
+                        Some(projection) => projection[index],
+                        None => index,
+                    };
+                    statistics
+                        .column_statistics
+                        .get(index)?
+                        .distinct_count
+                        .get_value()
+                        .copied()
+                })
+                .collect::<Option<Vec<_>>>()?;
+            Some((rows, distinct_counts))
+        }
+        LogicalPlan::Filter(filter) => column_statistics(&filter.input, columns),
+        LogicalPlan::SubqueryAlias(alias) => {
+            let columns = columns
+                .iter()
+                .map(|column| {
+                    let index = alias.schema.index_of_column(column).ok()?;
+                    Some(Column::from(alias.input.schema().qualified_field(index)))
+                })
+                .collect::<Option<Vec<_>>>()?;
+            column_statistics(&alias.input, &columns)
+        }
+        LogicalPlan::Projection(projection) => {
+            let columns = columns
+                .iter()
+                .map(|column| {
+                    let index = projection.schema.index_of_column(column).ok()?;
+                    unalias(&projection.expr[index]).try_as_col().cloned()
+                })
+                .collect::<Option<Vec<_>>>()?;
+            column_statistics(&projection.input, &columns)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OptimizerContext;
+    use crate::assert_optimized_plan_eq_snapshot;
+
+    use arrow::datatypes::{Field, FieldRef, Schema, SchemaRef};
+    use chrono::{DateTime, Utc};
+    use datafusion_common::alias::AliasGenerator;
+    use datafusion_common::config::ConfigOptions;
+    use datafusion_common::stats::Precision;
+    use datafusion_common::{ColumnStatistics, Constraint, Constraints, Statistics};
+    use datafusion_expr::registry::{FunctionRegistry, MemoryFunctionRegistry};
+    use datafusion_expr::{
+        ColumnarValue, LogicalPlanBuilder, ReturnFieldArgs, ScalarFunctionArgs,
+        ScalarUDF, ScalarUDFImpl, Signature, TableSource, Volatility, col,
+    };
+    use datafusion_functions_aggregate::count::count_udaf;
+    use datafusion_functions_aggregate::expr_fn::{avg, count, max, sum};
+    use datafusion_functions_aggregate::sum::sum_udaf;
+
+    /// `coalesce`, which is in `datafusion-functions`, this crate does not
+    /// depend on
+    #[derive(Debug, PartialEq, Eq, Hash)]
+    struct CoalesceStub {
+        signature: Signature,
+    }
+
+    impl ScalarUDFImpl for CoalesceStub {
+        fn name(&self) -> &str {
+            "coalesce"
+        }
+
+        fn signature(&self) -> &Signature {
+            &self.signature
+        }
+
+        fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+            Ok(arg_types[0].clone())
+        }
+
+        fn return_field_from_args(&self, args: ReturnFieldArgs) -> Result<FieldRef> {
+            let nullable = args.arg_fields.iter().all(|field| field.is_nullable());
+            Ok(Arc::new(Field::new(
+                self.name(),
+                args.arg_fields[0].data_type().clone(),
+                nullable,
+            )))
+        }
+
+        fn invoke_with_args(&self, _args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+            panic!("dummy - not implemented")
+        }
+    }
+
+    /// [`OptimizerConfig`] providing the `sum`, `count` and `coalesce`
+    /// functions
+    struct TestConfig {
+        inner: OptimizerContext,
+        registry: MemoryFunctionRegistry,
+    }
+
+    impl TestConfig {
+        fn new(options: ConfigOptions) -> Result<Self> {
+            let mut registry = MemoryFunctionRegistry::new();
+            registry.register_udaf(sum_udaf())?;
+            registry.register_udaf(count_udaf())?;
+            registry.register_udf(Arc::new(ScalarUDF::new_from_impl(CoalesceStub {
+                signature: Signature::variadic_any(Volatility::Immutable),
+            })))?;
+            Ok(Self {
+                inner: OptimizerContext::new_with_config_options(Arc::new(options)),
+                registry,
+            })
+        }
+    }
+
+    impl OptimizerConfig for TestConfig {
+        fn query_execution_start_time(&self) -> Option<DateTime<Utc>> {
+            self.inner.query_execution_start_time()
+        }
+
+        fn alias_generator(&self) -> &Arc<AliasGenerator> {
+            self.inner.alias_generator()
+        }
+
+        fn options(&self) -> Arc<ConfigOptions> {
+            self.inner.options()
+        }
+
+        fn function_registry(&self) -> Option<&dyn FunctionRegistry> {
+            Some(&self.registry)
+        }
+    }
+
+    /// [`TableSource`] with constraints and statistics
+    struct TestSource {
+        schema: SchemaRef,
+        constraints: Constraints,
+        statistics: Statistics,
+    }
+
+    impl TableSource for TestSource {
+        fn schema(&self) -> SchemaRef {
+            Arc::clone(&self.schema)
+        }
+
+        fn constraints(&self) -> Option<&Constraints> {
+            Some(&self.constraints)
+        }
+
+        fn statistics(&self) -> Option<Statistics> {
+            Some(self.statistics.clone())
+        }
+    }
+
+    /// A table `fact(dim_id, amount)` with 1000 rows and `dim_id_values`
+    /// distinct values of `dim_id`
+    fn fact(dim_id_values: usize) -> Result<LogicalPlan> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("dim_id", DataType::Int32, false),
+            Field::new("amount", DataType::Int64, true),
+        ]));
+        let statistics = Statistics {
+            num_rows: Precision::Inexact(1000),
+            total_byte_size: Precision::Absent,
+            column_statistics: vec![
+                ColumnStatistics::new_unknown()
+                    .with_distinct_count(Precision::Inexact(dim_id_values)),
+                ColumnStatistics::new_unknown(),
+            ],
+        };
+        let source = TestSource {
+            schema,
+            constraints: Constraints::default(),
+            statistics,
+        };
+        LogicalPlanBuilder::scan("fact", Arc::new(source), None)?.build()
+    }
+
+    /// A table `dim(id, region)`, with primary key `id` if `with_key` is set
+    fn dim(with_key: bool) -> Result<LogicalPlan> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("region", DataType::Utf8, true),
+        ]));
+        let constraints = if with_key {
+            Constraints::new_unverified(vec![Constraint::PrimaryKey(vec![0])])
+        } else {
+            Constraints::default()
+        };
+        let source = TestSource {
+            statistics: Statistics::new_unknown(&schema),
+            schema,
+            constraints,
+        };
+        LogicalPlanBuilder::scan("dim", Arc::new(source), None)?.build()
+    }
+
+    fn join(
+        fact: LogicalPlan,
+        dim: LogicalPlan,
+        join_type: JoinType,
+    ) -> Result<LogicalPlanBuilder> {
+        LogicalPlanBuilder::from(fact).join(
+            dim,
+            join_type,
+            (vec!["dim_id"], vec!["id"]),
+            None,
+        )
+    }
+
+    macro_rules! assert_optimized_plan_equal {
+        (
+            $plan:expr,
+            @ $expected:literal $(,)?
+        ) => {{
+            let mut options = ConfigOptions::default();
+            options.optimizer.enable_eager_aggregation = true;
+            let config = TestConfig::new(options)?;
+            let rules: Vec<Arc<dyn crate::OptimizerRule + Send + Sync>> =
+                vec![Arc::new(EagerAggregation::new())];
+            assert_optimized_plan_eq_snapshot!(config, rules, $plan, @ $expected,)
+        }};
+    }
+
+    #[test]
+    fn push_down_aggregates() -> Result<()> {
+        let plan = join(fact(10)?, dim(true)?, JoinType::Inner)?
+            .aggregate(
+                vec![col("dim.region")],
+                vec![
+                    sum(col("fact.amount")),
+                    count(col("fact.amount")),
+                    avg(col("fact.amount")),
+                ],
+            )?
+            .build()?;
+
+        assert_optimized_plan_equal!(plan, @r"
+        Projection: dim.region AS region, sum(__eager_agg_1) AS sum(fact.amount), coalesce(sum(__eager_agg_2), Int64(0)) AS count(fact.amount), sum(__eager_agg_3) / CAST(sum(__eager_agg_4) AS Float64) AS avg(fact.amount)
+          Aggregate: groupBy=[[dim.region]], aggr=[[sum(__eager_agg_1), sum(__eager_agg_2), sum(__eager_agg_3), sum(__eager_agg_4)]]
+            Inner Join: fact.dim_id = dim.id
+              Aggregate: groupBy=[[fact.dim_id]], aggr=[[sum(fact.amount) AS __eager_agg_1, count(fact.amount) AS __eager_agg_2, sum(CAST(fact.amount AS Float64)) AS __eager_agg_3, count(fact.amount) AS __eager_agg_4]]
+                TableScan: fact
+              TableScan: dim
+        ")
+    }
+
+    #[test]
+    fn push_down_right_input() -> Result<()> {
+        let plan = LogicalPlanBuilder::from(dim(true)?)
+            .join(
+                fact(10)?,
+                JoinType::Inner,
+                (vec!["id"], vec!["dim_id"]),
+                None,
+            )?
+            .aggregate(vec![col("dim.region")], vec![max(col("fact.amount"))])?
+            .build()?;
+
+        assert_optimized_plan_equal!(plan, @r"
+        Projection: dim.region AS region, max(__eager_agg_1) AS max(fact.amount)
+          Aggregate: groupBy=[[dim.region]], aggr=[[max(__eager_agg_1)]]
+            Inner Join: dim.id = fact.dim_id
+              TableScan: dim
+              Aggregate: groupBy=[[fact.dim_id]], aggr=[[max(fact.amount) AS __eager_agg_1]]
+                TableScan: fact
+        ")
+    }
+
+    #[test]
+    fn no_unique_key() -> Result<()> {
+        let plan = join(fact(10)?, dim(false)?, JoinType::Inner)?
+            .aggregate(vec![col("dim.region")], vec![sum(col("fact.amount"))])?
+            .build()?;
+
+        assert_optimized_plan_equal!(plan, @r"
+        Aggregate: groupBy=[[dim.region]], aggr=[[sum(fact.amount)]]
+          Inner Join: fact.dim_id = dim.id
+            TableScan: fact
+            TableScan: dim
+        ")
+    }
+
+    #[test]
+    fn too_many_groups() -> Result<()> {
+        let plan = join(fact(900)?, dim(true)?, JoinType::Inner)?
+            .aggregate(vec![col("dim.region")], vec![sum(col("fact.amount"))])?
+            .build()?;
+
+        assert_optimized_plan_equal!(plan, @r"
+        Aggregate: groupBy=[[dim.region]], aggr=[[sum(fact.amount)]]
+          Inner Join: fact.dim_id = dim.id
+            TableScan: fact
+            TableScan: dim
+        ")
+    }
+
+    #[test]
+    fn outer_join() -> Result<()> {
+        let plan = join(fact(10)?, dim(true)?, JoinType::Left)?
+            .aggregate(vec![col("dim.region")], vec![sum(col("fact.amount"))])?
+            .build()?;
+
+        assert_optimized_plan_equal!(plan, @r"
+        Aggregate: groupBy=[[dim.region]], aggr=[[sum(fact.amount)]]
+          Left Join: fact.dim_id = dim.id
+            TableScan: fact
+            TableScan: dim
+        ")
+    }
+
+    #[test]
+    fn aggregates_read_both_inputs() -> Result<()> {
+        let plan = join(fact(10)?, dim(true)?, JoinType::Inner)?
+            .aggregate(
+                Vec::<Expr>::new(),
+                vec![sum(col("fact.amount")), max(col("dim.region"))],
+            )?
+            .build()?;
+
+        assert_optimized_plan_equal!(plan, @r"
+        Aggregate: groupBy=[[]], aggr=[[sum(fact.amount), max(dim.region)]]
+          Inner Join: fact.dim_id = dim.id
+            TableScan: fact
+            TableScan: dim
+        ")
+    }
+
+    #[test]
+    fn disabled_by_default() -> Result<()> {
+        let plan = join(fact(10)?, dim(true)?, JoinType::Inner)?
+            .aggregate(vec![col("dim.region")], vec![sum(col("fact.amount"))])?
+            .build()?;
+
+        let config = TestConfig::new(ConfigOptions::default())?;
+        let rewritten = EagerAggregation::new().rewrite(plan.clone(), &config)?;
+        assert!(!rewritten.transformed);
+        assert_eq!(rewritten.data, plan);
+        Ok(())
+    }
+}
@@ -0,0 +1,657 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`MaterializedViewRewrite`] answers queries from the stored results of
+//! materialized views
+
+use std::sync::Arc;
+
+use crate::utils::{project_as, unalias};
+use crate::{OptimizerConfig, OptimizerRule};
+
+use datafusion_common::tree_node::{Transformed, TreeNode, TreeNodeRecursion};
+use datafusion_common::{Column, Result, TableReference};
+use datafusion_expr::expr::AggregateFunction;
+use datafusion_expr::utils::{conjunction, split_conjunction};
+use datafusion_expr::{
+    Aggregate, Expr, LogicalPlan, LogicalPlanBuilder, Projection, TableSource, lit,
+};
+
+/// A materialized view that [`MaterializedViewRewrite`] may use to answer
+/// queries, see [`OptimizerConfig::materialized_view_candidates`]
+#[derive(Debug, Clone)]
+pub struct MaterializedViewCandidate {
+    /// Name of the view, used to qualify the columns read from it
+    pub name: TableReference,
+    /// The analyzed plan whose results are stored by the view
+    pub plan: Arc<LogicalPlan>,
+    /// Source reading the stored results of the view
+    pub source: Arc<dyn TableSource>,
+}
+
+/// Optimizer rule that replaces parts of a plan with a scan of a
+/// materialized view whose stored results are fresh.
+///
+/// A part of the plan can be answered from a view if
+///
+/// * it is equal to the plan of the view,
+/// * it is `Projection(Filter(input))` and the view is
+///   `Projection(Filter(input))` with a subset of the predicates, and the
+///   remaining predicates and projected expressions can be computed from
+///   the columns of the view, or
+/// * it is `Aggregate(Filter(input))` and the view is
+///   `Aggregate(Filter(input))` with a subset of the predicates, grouped by
+///   a superset of the grouping expressions. The remaining predicates must
+///   only use the grouping expressions of the view, and `sum`, `count`,
+///   `min` and `max` are re-aggregated ("rolled up") from the stored
+///   results when the query groups by fewer expressions.
+///
+/// The rule runs before the other optimizer rules, so that both the query
+/// and the views are compared in their analyzed form.
+#[derive(Default, Debug)]
+pub struct MaterializedViewRewrite {}
+
+impl MaterializedViewRewrite {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl OptimizerRule for MaterializedViewRewrite {
+    fn name(&self) -> &str {
+        "materialized_view_rewrite"
+    }
+
+    fn supports_rewrite(&self) -> bool {
+        true
+    }
+
+    fn rewrite(
+        &self,
+        plan: LogicalPlan,
+        config: &dyn OptimizerConfig,
+    ) -> Result<Transformed<LogicalPlan>> {
+        if !config.options().optimizer.enable_materialized_view_rewrite {
+            return Ok(Transformed::no(plan));
+        }
+
+        let views = config
+            .materialized_view_candidates()
+            .into_iter()
+            .filter_map(View::try_new)
+            .collect::<Vec<_>>();
+        if views.is_empty() {
+            return Ok(Transformed::no(plan));
+        }
+
+        plan.transform_down_with_subqueries(|node| {
+            for view in &views {
+                if let Some(rewritten) = view.try_rewrite(&node, config)? {
+                    return Ok(Transformed::new(
+                        rewritten,
+                        true,
+                        TreeNodeRecursion::Jump,
+                    ));
+                }
+            }
+            Ok(Transformed::no(node))
+        })
+    }
+}
+
+/// A materialized view, with the columns of its stored results
+struct View {
+    name: TableReference,
+    plan: Arc<LogicalPlan>,
+    source: Arc<dyn TableSource>,
+    columns: Vec<Column>,
+}
+
+impl View {
+    /// Returns `None` if the results of the view can not be reused, as they
+    /// depend on volatile expressions such as `random()`
+    fn try_new(candidate: MaterializedViewCandidate) -> Option<Self> {
+        let MaterializedViewCandidate { name, plan, source } = candidate;
+        if is_volatile(&plan) {
+            return None;
+        }
+        let columns = plan
+            .schema()
+            .fields()
+            .iter()
+            .map(|field| Column::new(Some(name.clone()), field.name()))
+            .collect();
+        Some(Self {
+            name,
+            plan,
+            source,
+            columns,
+        })
+    }
+
+    /// Returns a plan computing the same results as `node` from the view, if
+    /// the view can answer it
+    fn try_rewrite(
+        &self,
+        node: &LogicalPlan,
+        config: &dyn OptimizerConfig,
+    ) -> Result<Option<LogicalPlan>> {
+        if node == self.plan.as_ref() {
+            let exprs = self.columns.iter().cloned().map(Expr::Column).collect();
+            return project_as(self.scan()?.build()?, exprs, node.schema()).map(Some);
+        }
+        if let Some(rewritten) = self.try_rewrite_filter(node)? {
+            return Ok(Some(rewritten));
+        }
+        self.try_rewrite_aggregate(node, config)
+    }
+
+    /// Answers `Projection(Filter(input))` from a view filtering the same
+    /// input by a subset of the predicates
+    fn try_rewrite_filter(&self, node: &LogicalPlan) -> Result<Option<LogicalPlan>> {
+        let (LogicalPlan::Projection(query), LogicalPlan::Projection(view)) =
+            (node, self.plan.as_ref())
+        else {
+            return Ok(None);
+        };
+        let (query_predicates, query_input) = split_filter(&query.input);
+        let (view_predicates, view_input) = split_filter(&view.input);
+        if query_input != view_input {
+            return Ok(None);
+        }
+        let Some(residual) = residual_predicates(&query_predicates, &view_predicates)
+        else {
+            return Ok(None);
+        };
+
+        let outputs = view
+            .expr
+            .iter()
+            .map(|expr| unalias(expr).clone())
+            .zip(self.columns.iter().cloned())
+            .collect::<Vec<_>>();
+        let Some(residual) = self.rewrite_exprs(residual, &outputs)? else {
+            return Ok(None);
+        };
+        let Some(exprs) = self.rewrite_exprs(query.expr.clone(), &outputs)? else {
+            return Ok(None);
+        };
+
+        let mut builder = self.scan()?;
+        if let Some(predicate) = conjunction(residual) {
+            builder = builder.filter(predicate)?;
+        }
+        project_as(builder.build()?, exprs, node.schema()).map(Some)
+    }
+
+    /// Answers `Aggregate(Filter(input))` from a view aggregating the same
+    /// input, filtered by a subset of the predicates, by at least the same
+    /// grouping expressions
+    fn try_rewrite_aggregate(
+        &self,
+        node: &LogicalPlan,
+        config: &dyn OptimizerConfig,
+    ) -> Result<Option<LogicalPlan>> {
+        let LogicalPlan::Aggregate(query) = node else {
+            return Ok(None);
+        };
+        // The view may rename, reorder or omit the outputs of its aggregate
+        let (view, view_columns): (&Aggregate, Vec<Option<Column>>) =
+            match self.plan.as_ref() {
+                LogicalPlan::Aggregate(view) => {
+                    (view, self.columns.iter().cloned().map(Some).collect())
+                }
+                LogicalPlan::Projection(Projection { expr, input, .. }) => {
+                    let LogicalPlan::Aggregate(view) = input.as_ref() else {
+                        return Ok(None);
+                    };
+                    let columns = view
+                        .schema
+                        .columns()
+                        .into_iter()
+                        .map(|column| {
+                            expr.iter()
+                            .position(
+                                |e| matches!(unalias(e), Expr::Column(c) if *c == column),
+                            )
+                            .map(|i| self.columns[i].clone())
+                        })
+                        .collect::<Vec<_>>();
+                    (view, columns)
+                }
+                _ => return Ok(None),
+            };
+        if has_grouping_set(query) || has_grouping_set(view) {
+            return Ok(None);
+        }
+
+        let (query_predicates, query_input) = split_filter(&query.input);
+        let (view_predicates, view_input) = split_filter(&view.input);
+        if query_input != view_input {
+            return Ok(None);
+        }
+        let Some(residual) = residual_predicates(&query_predicates, &view_predicates)
+        else {
+            return Ok(None);
+        };
+
+        let (group_columns, aggr_columns) = view_columns.split_at(view.group_expr.len());
+        let group_outputs = view
+            .group_expr
+            .iter()
+            .zip(group_columns)
+            .filter_map(|(expr, column)| Some((expr.clone(), column.clone()?)))
+            .collect::<Vec<_>>();
+        let Some(residual) = self.rewrite_exprs(residual, &group_outputs)? else {
+            return Ok(None);
+        };
+        let Some(group_expr) =
+            self.rewrite_exprs(query.group_expr.clone(), &group_outputs)?
+        else {
+            return Ok(None);
+        };
+        let find_aggregate = |expr: &Expr| {
+            view.aggr_expr
+                .iter()
+                .zip(aggr_columns)
+                .find(|(aggr, _)| unalias(aggr) == unalias(expr))
+                .and_then(|(_, column)| column.clone())
+        };
+
+        let mut builder = self.scan()?;
+        if let Some(predicate) = conjunction(residual) {
+            builder = builder.filter(predicate)?;
+        }
+
+        // If the query groups by all grouping columns of the view, each row
+        // of the view is one group of the query
+        let same_groups = group_outputs.len() == view.group_expr.len()
+            && group_outputs.iter().all(|(_, column)| {
+                group_expr
+                    .iter()
+                    .any(|expr| matches!(expr, Expr::Column(c) if c == column))
+            });
+        if same_groups {
+            let Some(aggr_expr) = query
+                .aggr_expr
+                .iter()
+                .map(|expr| find_aggregate(expr).map(Expr::Column))
+                .collect::<Option<Vec<_>>>()
+            else {
+                return Ok(None);
+            };
+            let exprs = group_expr.into_iter().chain(aggr_expr).collect();
+            return project_as(builder.build()?, exprs, node.schema()).map(Some);
+        }
+
+        let registry = config.function_registry();
+        let mut aggr_expr = Vec::with_capacity(query.aggr_expr.len());
+        let mut is_count = Vec::with_capacity(query.aggr_expr.len());
+        for expr in &query.aggr_expr {
+            let Expr::AggregateFunction(AggregateFunction { func, params }) =
+                unalias(expr)
+            else {
+                return Ok(None);
+            };
+            if params.distinct || params.filter.is_some() || !params.order_by.is_empty() {
+                return Ok(None);
+            }
+            let Some(column) = find_aggregate(expr) else {
+                return Ok(None);
+            };
+            let rolled_up = match func.name() {
+                "sum" | "min" | "max" => {
+                    Expr::AggregateFunction(AggregateFunction::new_udf(
+                        Arc::clone(func),
+                        vec![Expr::Column(column)],
+                        false,
+                        None,
+                        vec![],
+                        None,
+                    ))
+                }
+                "count" => match registry.and_then(|r| r.udaf("sum").ok()) {
+                    Some(sum) => sum.call(vec![Expr::Column(column)]),
+                    None => return Ok(None),
+                },
+                _ => return Ok(None),
+            };
+            aggr_expr.push(rolled_up);
+            is_count.push(func.name() == "count");
+        }
+
+        let num_groups = group_expr.len();
+        let aggregate = builder.aggregate(group_expr, aggr_expr)?.build()?;
+        let mut exprs = aggregate
+            .schema()
+            .columns()
+            .into_iter()
+            .map(Expr::Column)
+            .collect::<Vec<_>>();
+        // Without grouping, `count` of no rows is 0 while `sum` is NULL
+        if num_groups == 0 {
+            for (expr, is_count) in exprs.iter_mut().zip(is_count) {
+                if is_count {
+                    let Some(coalesce) = registry.and_then(|r| r.udf("coalesce").ok())
+                    else {
+                        return Ok(None);
+                    };
+                    *expr = coalesce.call(vec![expr.clone(), lit(0i64)]);
+                }
+            }
+        }
+        project_as(aggregate, exprs, node.schema()).map(Some)
+    }
+
+    fn scan(&self) -> Result<LogicalPlanBuilder> {
+        LogicalPlanBuilder::scan(self.name.clone(), Arc::clone(&self.source), None)
+    }
+
+    /// Replaces the parts of `exprs` in `outputs` with the corresponding
+    /// columns of the view, returning `None` if the rewritten expressions
+    /// still need columns that are not stored by the view
+    fn rewrite_exprs(
+        &self,
+        exprs: Vec<Expr>,
+        outputs: &[(Expr, Column)],
+    ) -> Result<Option<Vec<Expr>>> {
+        let mut rewritten = Vec::with_capacity(exprs.len());
+        for expr in exprs {
+            let expr = expr
+                .transform_down(|expr| {
+                    Ok(match outputs.iter().find(|(output, _)| *output == expr) {
+                        Some((_, column)) => Transformed::new(
+                            Expr::Column(column.clone()),
+                            true,
+                            TreeNodeRecursion::Jump,
+                        ),
+                        None => Transformed::no(expr),
+                    })
+                })?
+                .data;
+            let reads_view = !has_subquery(&expr)?
+                && expr
+                    .column_refs()
+                    .iter()
+                    .all(|column| column.relation.as_ref() == Some(&self.name));
+            if !reads_view {
+                return Ok(None);
+            }
+            rewritten.push(expr);
+        }
+        Ok(Some(rewritten))
+    }
+}
+
+/// Splits `plan` into the conjuncts of its top-level filter, if any, and
+/// the input of the filter
+fn split_filter(plan: &LogicalPlan) -> (Vec<&Expr>, &LogicalPlan) {
+    match plan {
+        LogicalPlan::Filter(filter) => {
+            (split_conjunction(&filter.predicate), filter.input.as_ref())
+        }
+        _ => (vec![], plan),
+    }
+}
+
+/// Returns the query predicates not applied by the view, or `None` if the
+/// view applies predicates not applied by the query
+fn residual_predicates(query: &[&Expr], view: &[&Expr]) -> Option<Vec<Expr>> {
+    view.iter()
+        .all(|predicate| query.contains(predicate))
+        .then(|| {
+            query
+                .iter()
+                .filter(|predicate| !view.contains(predicate))
+                .map(|predicate| (*predicate).clone())
+                .collect()
+        })
+}
+
+fn has_grouping_set(aggregate: &Aggregate) -> bool {
+    aggregate
+        .group_expr
+        .iter()
+        .any(|expr| matches!(expr, Expr::GroupingSet(_)))
+}
+
+fn has_subquery(expr: &Expr) -> Result<bool> {
+    expr.exists(|expr| {
+        Ok(matches!(
+            expr,
+            Expr::ScalarSubquery(_)
+                | Expr::Exists(_)
+                | Expr::InSubquery(_)
+                | Expr::SetComparison(_)
+                | Expr::OuterReferenceColumn(..)
+        ))
+    })
+}
+
+fn is_volatile(plan: &LogicalPlan) -> bool {
+    let mut volatile = false;
+    // The closure never returns an error
+    let _ = plan.apply_with_subqueries(|node| {
+        volatile = node.expressions().iter().any(Expr::is_volatile);
+        Ok(if volatile {
+            TreeNodeRecursion::Stop
+        } else {
+            TreeNodeRecursion::Continue
+        })
+    });
+    volatile
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OptimizerContext;
+    use crate::test::*;
+
+    use chrono::{DateTime, Utc};
+    use datafusion_common::alias::AliasGenerator;
+    use datafusion_common::config::ConfigOptions;
+    use datafusion_expr::col;
+    use datafusion_expr::logical_plan::builder::LogicalTableSource;
+    use datafusion_functions_aggregate::expr_fn::{avg, max, sum};
+
+    /// [`OptimizerConfig`] providing a single materialized view
+    struct TestConfig {
+        inner: OptimizerContext,
+        view: MaterializedViewCandidate,
+    }
+
+    impl TestConfig {
+        fn new(plan: LogicalPlan) -> Self {
+            let source =
+                Arc::new(LogicalTableSource::new(Arc::clone(plan.schema().inner())));
+            Self {
+                inner: OptimizerContext::new(),
+                view: MaterializedViewCandidate {
+                    name: TableReference::bare("mv"),
+                    plan: Arc::new(plan),
+                    source,
+                },
+            }
+        }
+    }
+
+    impl OptimizerConfig for TestConfig {
+        fn query_execution_start_time(&self) -> Option<DateTime<Utc>> {
+            self.inner.query_execution_start_time()
+        }
+
+        fn alias_generator(&self) -> &Arc<AliasGenerator> {
+            self.inner.alias_generator()
+        }
+
+        fn options(&self) -> Arc<ConfigOptions> {
+            self.inner.options()
+        }
+
+        fn materialized_view_candidates(&self) -> Vec<MaterializedViewCandidate> {
+            vec![self.view.clone()]
+        }
+    }
+
+    fn optimize(view: LogicalPlan, query: LogicalPlan) -> Result<LogicalPlan> {
+        let config = TestConfig::new(view);
+        let rewritten = MaterializedViewRewrite::new().rewrite(query.clone(), &config)?;
+        assert!(
+            rewritten
+                .data
+                .schema()
+                .logically_equivalent_names_and_types(query.schema())
+        );
+        Ok(rewritten.data)
+    }
+
+    #[test]
+    fn exact_match() -> Result<()> {
+        let view = LogicalPlanBuilder::from(test_table_scan()?)
+            .filter(col("a").gt(lit(1u32)))?
+            .project(vec![col("a"), col("b")])?
+            .build()?;
+        let query = LogicalPlanBuilder::from(view.clone())
+            .sort(vec![col("a").sort(true, false)])?
+            .build()?;
+
+        insta::assert_snapshot!(optimize(view, query)?, @r"
+        Sort: test.a ASC NULLS LAST
+          Projection: mv.a AS a, mv.b AS b
+            TableScan: mv
+        ");
+        Ok(())
+    }
+
+    #[test]
+    fn filter_subsumption() -> Result<()> {
+        let view = LogicalPlanBuilder::from(test_table_scan()?)
+            .filter(col("a").gt(lit(1u32)))?
+            .project(vec![col("a"), col("b") + col("c")])?
+            .build()?;
+        let query = LogicalPlanBuilder::from(test_table_scan()?)
+            .filter(col("a").gt(lit(1u32)).and(col("a").lt(lit(10u32))))?
+            .project(vec![col("b") + col("c")])?
+            .build()?;
+
+        insta::assert_snapshot!(optimize(view, query)?, @r"
+        Projection: mv.test.b + test.c AS test.b + test.c
+          Filter: mv.a < UInt32(10)
+            TableScan: mv
+        ");
+        Ok(())
+    }
+
+    #[test]
+    fn filter_needs_missing_column() -> Result<()> {
+        let view = LogicalPlanBuilder::from(test_table_scan()?)
+            .filter(col("a").gt(lit(1u32)))?
+            .project(vec![col("a")])?
+            .build()?;
+        let query = LogicalPlanBuilder::from(test_table_scan()?)
+            .filter(col("a").gt(lit(1u32)).and(col("b").lt(lit(10u32))))?
+            .project(vec![col("a")])?
+            .build()?;
+
+        let rewritten = optimize(view, query.clone())?;
+        assert_eq!(rewritten, query);
+        Ok(())
+    }
+
+    #[test]
+    fn view_filters_more_rows() -> Result<()> {
+        let view = LogicalPlanBuilder::from(test_table_scan()?)
+            .filter(col("a").gt(lit(1u32)))?
+            .project(vec![col("a")])?
+            .build()?;
+        let query = LogicalPlanBuilder::from(test_table_scan()?)
+            .project(vec![col("a")])?
+            .build()?;
+
+        let rewritten = optimize(view, query.clone())?;
+        assert_eq!(rewritten, query);
+        Ok(())
+    }
+
+    #[test]
+    fn aggregate_rollup() -> Result<()> {
+        let view = LogicalPlanBuilder::from(test_table_scan()?)
+            .aggregate(vec![col("a"), col("b")], vec![sum(col("c")), max(col("c"))])?
+            .build()?;
+        let query = LogicalPlanBuilder::from(test_table_scan()?)
+            .filter(col("a").eq(lit(1u32)))?
+            .aggregate(vec![col("b")], vec![max(col("c")), sum(col("c"))])?
+            .build()?;
+
+        insta::assert_snapshot!(optimize(view, query)?, @r"
+        Projection: mv.b AS b, max(mv.max(test.c)) AS max(test.c), sum(mv.sum(test.c)) AS sum(test.c)
+          Aggregate: groupBy=[[mv.b]], aggr=[[max(mv.max(test.c)), sum(mv.sum(test.c))]]
+            Filter: mv.a = UInt32(1)
+              TableScan: mv
+        ");
+        Ok(())
+    }
+
+    #[test]
+    fn aggregate_same_groups() -> Result<()> {
+        let view = LogicalPlanBuilder::from(test_table_scan()?)
+            .aggregate(vec![col("a")], vec![avg(col("c"))])?
+            .project(vec![
+                Expr::Column(Column::from_name("avg(test.c)")).alias("avg_c"),
+                col("a"),
+            ])?
+            .build()?;
+        let query = LogicalPlanBuilder::from(test_table_scan()?)
+            .aggregate(vec![col("a")], vec![avg(col("c"))])?
+            .build()?;
+
+        insta::assert_snapshot!(optimize(view, query)?, @r"
+        Projection: mv.a AS a, mv.avg_c AS avg(test.c)
+          TableScan: mv
+        ");
+        Ok(())
+    }
+
+    #[test]
+    fn aggregate_not_rolled_up() -> Result<()> {
+        let view = LogicalPlanBuilder::from(test_table_scan()?)
+            .aggregate(vec![col("a"), col("b")], vec![avg(col("c"))])?
+            .build()?;
+        let query = LogicalPlanBuilder::from(test_table_scan()?)
+            .aggregate(vec![col("a")], vec![avg(col("c"))])?
+            .build()?;
+
+        let rewritten = optimize(view, query.clone())?;
+        assert_eq!(rewritten, query);
+        Ok(())
+    }
+
+    #[test]
+    fn disabled() -> Result<()> {
+        let view = test_table_scan()?;
+        let query = test_table_scan()?;
+        let mut options = ConfigOptions::default();
+        options.optimizer.enable_materialized_view_rewrite = false;
+        let mut config = TestConfig::new(view);
+        config.inner = OptimizerContext::new_with_config_options(Arc::new(options));
+
+        let rewritten = MaterializedViewRewrite::new().rewrite(query, &config)?;
+        assert!(!rewritten.transformed);
+        Ok(())
+    }
+}
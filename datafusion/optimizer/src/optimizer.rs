@@ -35,6 +35,7 @@ use datafusion_expr::logical_plan::LogicalPlan;
 use crate::common_subexpr_eliminate::CommonSubexprEliminate;
 use crate::decorrelate_lateral_join::DecorrelateLateralJoin;
 use crate::decorrelate_predicate_subquery::DecorrelatePredicateSubquery;
+use crate::eager_aggregation::EagerAggregation;
 use crate::eliminate_cross_join::EliminateCrossJoin;
 use crate::eliminate_duplicated_expr::EliminateDuplicatedExpr;
 use crate::eliminate_filter::EliminateFilter;
@@ -45,6 +46,9 @@ use crate::eliminate_outer_join::EliminateOuterJoin;
 use crate::extract_equijoin_predicate::ExtractEquijoinPredicate;
 use crate::extract_leaf_expressions::{ExtractLeafExpressions, PushDownLeafProjections};
 use crate::filter_null_join_keys::FilterNullJoinKeys;
+use crate::materialized_view_rewrite::{
+    MaterializedViewCandidate, MaterializedViewRewrite,
+};
 use crate::optimize_projections::OptimizeProjections;
 use crate::optimize_unions::OptimizeUnions;
 use crate::plan_signature::LogicalPlanSignature;
@@ -146,6 +150,12 @@ pub trait OptimizerConfig {
     fn function_registry(&self) -> Option<&dyn FunctionRegistry> {
         None
     }
+
+    /// Return the materialized views whose stored results are fresh, which
+    /// [`MaterializedViewRewrite`] may use to answer queries
+    fn materialized_view_candidates(&self) -> Vec<MaterializedViewCandidate> {
+        vec![]
+    }
 }
 
 /// A standalone [`OptimizerConfig`] that can be used independently
@@ -278,6 +288,9 @@ impl Optimizer {
         //   (e.g. if the plan doesn't contain any of the nodes you are looking for
         //    return `Transformed::no`; only works if you control the traversal).
         let rules: Vec<Arc<dyn OptimizerRule + Sync + Send>> = vec![
+            // Compare queries to materialized views before they are optimized
+            // differently
+            Arc::new(MaterializedViewRewrite::new()),
             Arc::new(RewriteSetComparison::new()),
             Arc::new(OptimizeUnions::new()),
             Arc::new(SimplifyExpressions::new()),
@@ -298,6 +311,7 @@ impl Optimizer {
             Arc::new(PushDownLimit::new()),
             Arc::new(PushDownFilter::new()),
             Arc::new(SingleDistinctToGroupBy::new()),
+            Arc::new(EagerAggregation::new()),
             // The previous optimizations added expressions and projections,
             // that might benefit from the following rules
             Arc::new(EliminateGroupByConstant::new()),
@@ -65,6 +65,7 @@ impl OptimizerRule for PropagateEmptyRelation {
             | LogicalPlan::Sort(_)
             | LogicalPlan::SubqueryAlias(_)
             | LogicalPlan::Repartition(_)
+            | LogicalPlan::Sample(_)
             | LogicalPlan::Limit(_) => {
                 let empty = empty_child(&plan)?;
                 if let Some(empty_plan) = empty {
@@ -108,15 +109,15 @@ impl OptimizerRule for PropagateEmptyRelation {
                             schema: Arc::clone(&join.schema),
                         }),
                     )),
-                    JoinType::Left if left_empty => Ok(Transformed::yes(
-                        LogicalPlan::EmptyRelation(EmptyRelation {
+                    JoinType::Left | JoinType::LeftAsOf if left_empty => Ok(
+                        Transformed::yes(LogicalPlan::EmptyRelation(EmptyRelation {
                             produce_one_row: false,
                             schema: Arc::clone(&join.schema),
-                        }),
-                    )),
+                        })),
+                    ),
                     // Left Join with empty right: all left rows survive
                     // with NULLs for right columns.
-                    JoinType::Left if right_empty => {
+                    JoinType::Left | JoinType::LeftAsOf if right_empty => {
                         Ok(Transformed::yes(build_null_padded_projection(
                             Arc::clone(&join.left),
                             &join.schema,
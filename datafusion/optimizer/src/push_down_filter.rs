@@ -168,7 +168,7 @@ pub struct PushDownFilter {}
 pub(crate) fn lr_is_preserved(join_type: JoinType) -> (bool, bool) {
     match join_type {
         JoinType::Inner => (true, true),
-        JoinType::Left => (true, false),
+        JoinType::Left | JoinType::LeftAsOf => (true, false),
         JoinType::Right => (false, true),
         JoinType::Full => (false, false),
         // No columns from the right side of the join can be referenced in output
@@ -690,7 +690,7 @@ fn infer_join_predicates_from_on_filters(
             on_filters,
             inferred_predicates,
         ),
-        JoinType::Left | JoinType::LeftSemi | JoinType::LeftMark => {
+        JoinType::Left | JoinType::LeftSemi | JoinType::LeftMark | JoinType::LeftAsOf => {
             infer_join_predicates_impl::<true, false>(
                 join_col_keys,
                 on_filters,
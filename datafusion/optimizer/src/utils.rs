@@ -28,7 +28,9 @@ use datafusion_common::tree_node::{TransformedResult, TreeNode};
 use datafusion_common::{Column, DFSchema, Result, ScalarValue};
 use datafusion_expr::execution_props::ExecutionProps;
 use datafusion_expr::expr_rewriter::replace_col;
-use datafusion_expr::{ColumnarValue, Expr, logical_plan::LogicalPlan};
+use datafusion_expr::{
+    ColumnarValue, Expr, ExprSchemable, Projection, cast, logical_plan::LogicalPlan,
+};
 use datafusion_physical_expr::create_physical_expr;
 use log::{debug, trace};
 use std::sync::Arc;
@@ -191,6 +193,37 @@ fn coerce(expr: Expr, schema: &DFSchema) -> Result<Expr> {
     expr.rewrite(&mut expr_rewrite).data()
 }
 
+/// Projects `exprs` as the fields of `schema`, casting them to the types of
+/// the fields if needed
+pub(crate) fn project_as(
+    input: LogicalPlan,
+    exprs: Vec<Expr>,
+    schema: &DFSchema,
+) -> Result<LogicalPlan> {
+    let exprs = exprs
+        .into_iter()
+        .zip(schema.iter())
+        .map(|(expr, (qualifier, field))| {
+            let expr = expr.unalias();
+            let expr = if expr.get_type(input.schema())? == *field.data_type() {
+                expr
+            } else {
+                cast(expr, field.data_type().clone())
+            };
+            Ok(expr.alias_qualified(qualifier.cloned(), field.name()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Projection::try_new(exprs, Arc::new(input)).map(LogicalPlan::Projection)
+}
+
+/// Returns the expression below any aliases of `expr`
+pub(crate) fn unalias(expr: &Expr) -> &Expr {
+    match expr {
+        Expr::Alias(alias) => unalias(&alias.expr),
+        _ => expr,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
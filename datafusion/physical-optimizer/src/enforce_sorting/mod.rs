@@ -117,15 +117,15 @@ fn update_sort_ctx_children_data(
             let required_orderings = child_plan.required_input_ordering();
             let flags = child_plan.maintains_input_order();
             // Add parent node to the tree if there is at least one child with
-            // a sort connection:
-            izip!(flags, required_orderings).any(|(maintains, required_ordering)| {
-                let propagates_ordering =
-                    (maintains && required_ordering.is_none()) || is_spm;
-                // `connected_to_sort` only returns the correct answer with bottom-up traversal
-                let connected_to_sort =
-                    child_node.children.iter().any(|child| child.data);
-                propagates_ordering && connected_to_sort
-            })
+            // a sort connection that propagates its ordering:
+            izip!(flags, required_orderings, &child_node.children).any(
+                |(maintains, required_ordering, child)| {
+                    let propagates_ordering =
+                        (maintains && required_ordering.is_none()) || is_spm;
+                    // `child.data` only returns the correct answer with bottom-up traversal
+                    propagates_ordering && child.data
+                },
+            )
         }
     }
 
@@ -750,12 +750,21 @@ fn remove_corresponding_sort_from_sub_plan(
     } else {
         let mut any_connection = false;
         let required_dist = node.plan.required_input_distribution();
+        let is_spm = is_sort_preserving_merge(&node.plan);
+        // Only follow children whose ordering is propagated by this node; the
+        // sorts below other children satisfy requirements of this node itself.
+        let propagates_ordering = izip!(
+            node.plan.maintains_input_order(),
+            node.plan.required_input_ordering()
+        )
+        .map(|(maintains, required)| (maintains && required.is_none()) || is_spm)
+        .collect::<Vec<_>>();
         node.children = node
             .children
             .into_iter()
             .enumerate()
             .map(|(idx, child)| {
-                if child.data {
+                if child.data && propagates_ordering[idx] {
                     any_connection = true;
                     remove_corresponding_sort_from_sub_plan(
                         child,
@@ -648,6 +648,15 @@ fn expr_source_side(
                 None
             }
         }
+        // The right side of an as-of join does not maintain its order
+        JoinType::LeftAsOf => ordering
+            .iter()
+            .all(|e| {
+                e.expr
+                    .downcast_ref::<Column>()
+                    .is_some_and(|c| c.index() < left_columns_len)
+            })
+            .then_some((JoinSide::Left, ordering)),
         JoinType::LeftSemi | JoinType::LeftAnti => ordering
             .iter()
             .all(|e| e.expr.is::<Column>())
@@ -0,0 +1,903 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! The [`JoinReorder`] rule reorders trees of inner joins by estimated cost.
+//!
+//! Directly connected inner equi-joins and cross joins form a join graph
+//! whose vertices are the join inputs ("relations") and whose edges are the
+//! equi-join keys. The rule estimates the output cardinality of every
+//! candidate join from the row counts and distinct counts that the
+//! [`StatisticsRegistry`] reports for the relations, and picks the (possibly
+//! bushy) join order with the smallest sum of intermediate result sizes:
+//!
+//! - Join graphs with at most `cost_based_join_reorder_max_relations`
+//!   relations are ordered by dynamic programming over all connected
+//!   subgraphs, so cross products are only introduced between parts of the
+//!   graph that are not connected by any join key.
+//! - Larger join graphs are ordered greedily, always joining the two
+//!   intermediate results with the smallest estimated output.
+//!
+//! The rule runs before [`JoinSelection`](crate::join_selection::JoinSelection),
+//! which then chooses the build side and partition mode of each join.
+
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use crate::PhysicalOptimizerRule;
+use crate::optimizer::{ConfigOnlyContext, PhysicalOptimizerContext};
+use arrow::datatypes::SchemaRef;
+use datafusion_common::config::ConfigOptions;
+use datafusion_common::error::Result;
+use datafusion_common::tree_node::{
+    Transformed, TransformedResult, TreeNode, TreeNodeRecursion,
+};
+use datafusion_common::{JoinType, NullEquality, internal_datafusion_err};
+use datafusion_physical_expr::PhysicalExprRef;
+use datafusion_physical_expr::expressions::Column;
+use datafusion_physical_plan::ExecutionPlan;
+use datafusion_physical_plan::joins::{
+    CrossJoinExec, HashJoinExec, HashJoinExecBuilder, PartitionMode,
+};
+use datafusion_physical_plan::operator_statistics::StatisticsRegistry;
+use datafusion_physical_plan::projection::{ProjectionExec, ProjectionExpr};
+
+/// Upper bound for `cost_based_join_reorder_max_relations`, as the dynamic
+/// programming table has `2^n` entries for `n` relations
+const MAX_DP_RELATIONS: usize = 16;
+
+/// Minimum relative cost improvement for replacing the original join order,
+/// which keeps plans stable when orders are equally good
+const MIN_COST_IMPROVEMENT: f64 = 1e-6;
+
+/// The [`JoinReorder`] rule reorders trees of inner equi-joins and cross
+/// joins using cardinality estimates from the [`StatisticsRegistry`].
+///
+/// Only [`HashJoinExec`]s without a join filter, fetch or null-equal keys
+/// whose keys are plain columns, [`CrossJoinExec`]s and column-only
+/// [`ProjectionExec`]s between them are reordered. Join graphs are left
+/// unchanged unless every relation has a row count estimate.
+///
+/// Used configurations:
+/// - `config.optimizer.enable_cost_based_join_reorder`: enables the rule
+/// - `config.optimizer.cost_based_join_reorder_max_relations`: the largest
+///   join graph ordered by dynamic programming (at most 16)
+#[derive(Default, Debug)]
+pub struct JoinReorder {}
+
+impl JoinReorder {
+    #[expect(missing_docs)]
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl PhysicalOptimizerRule for JoinReorder {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        config: &ConfigOptions,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        self.optimize_with_context(plan, &ConfigOnlyContext::new(config))
+    }
+
+    fn optimize_with_context(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        context: &dyn PhysicalOptimizerContext,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let config = context.config_options();
+        if !config.optimizer.enable_cost_based_join_reorder {
+            return Ok(plan);
+        }
+        let mut default_registry = None;
+        let registry = context.statistics_registry().unwrap_or_else(|| {
+            default_registry.insert(StatisticsRegistry::default_with_builtin_providers())
+        });
+        let max_dp_relations = config
+            .optimizer
+            .cost_based_join_reorder_max_relations
+            .min(MAX_DP_RELATIONS);
+        reorder_joins(plan, registry, max_dp_relations).data()
+    }
+
+    fn name(&self) -> &str {
+        "join_reorder"
+    }
+
+    fn schema_check(&self) -> bool {
+        true
+    }
+}
+
+/// Reorders every join graph of `plan`, from the top down.
+fn reorder_joins(
+    plan: Arc<dyn ExecutionPlan>,
+    registry: &StatisticsRegistry,
+    max_dp_relations: usize,
+) -> Result<Transformed<Arc<dyn ExecutionPlan>>> {
+    plan.transform_down(|plan| {
+        let Some(graph) = JoinGraph::try_new(&plan) else {
+            return Ok(Transformed::no(plan));
+        };
+        let Some(estimator) = JoinEstimator::try_new(&graph, registry)? else {
+            return Ok(Transformed::no(plan));
+        };
+
+        // The traversal does not descend into the graph below, so join graphs
+        // inside the relations are reordered here
+        let mut relations_changed = false;
+        let relations = graph
+            .relations
+            .iter()
+            .map(|relation| {
+                let relation =
+                    reorder_joins(Arc::clone(relation), registry, max_dp_relations)?;
+                relations_changed |= relation.transformed;
+                Ok(relation.data)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let original = estimator.estimate_tree(&graph.order);
+        let best = if graph.relations.len() <= max_dp_relations {
+            estimator.dynamic_programming_order()
+        } else {
+            estimator.greedy_order()
+        };
+
+        let (new_plan, transformed) =
+            if best.cost < original.cost * (1.0 - MIN_COST_IMPROVEMENT) {
+                (graph.build(&best.tree, relations, &plan.schema())?, true)
+            } else if relations_changed {
+                (replace_relations(&plan, &mut relations.into_iter())?, true)
+            } else {
+                (plan, false)
+            };
+        Ok(Transformed::new(
+            new_plan,
+            transformed,
+            TreeNodeRecursion::Jump,
+        ))
+    })
+}
+
+/// A column of a relation of a [`JoinGraph`], as `(relation, column index)`
+type RelationColumn = (usize, usize);
+
+/// A join order, as a binary tree over the relations of a [`JoinGraph`]
+#[derive(Debug, Clone)]
+enum JoinTree {
+    Relation(usize),
+    Join(Box<JoinTree>, Box<JoinTree>),
+}
+
+impl JoinTree {
+    fn relations(&self) -> Vec<usize> {
+        match self {
+            JoinTree::Relation(relation) => vec![*relation],
+            JoinTree::Join(left, right) => {
+                let mut relations = left.relations();
+                relations.extend(right.relations());
+                relations
+            }
+        }
+    }
+}
+
+/// An equi-join key `left = right` between two relations
+#[derive(Debug)]
+struct JoinEdge {
+    left: RelationColumn,
+    right: RelationColumn,
+}
+
+/// A plan node that is part of a join graph
+enum GraphNode<'a> {
+    Join {
+        left: &'a Arc<dyn ExecutionPlan>,
+        right: &'a Arc<dyn ExecutionPlan>,
+        /// Column indices of the equi-join keys in the left and right input
+        on: Vec<(usize, usize)>,
+        projection: Option<&'a [usize]>,
+        partition_mode: Option<PartitionMode>,
+    },
+    Projection {
+        input: &'a Arc<dyn ExecutionPlan>,
+        columns: Vec<usize>,
+    },
+}
+
+impl<'a> GraphNode<'a> {
+    fn try_new(plan: &'a Arc<dyn ExecutionPlan>) -> Option<Self> {
+        if let Some(join) = plan.downcast_ref::<HashJoinExec>() {
+            if join.join_type != JoinType::Inner
+                || join.filter.is_some()
+                || join.null_equality != NullEquality::NullEqualsNothing
+                || join.null_aware
+                || join.fetch().is_some()
+            {
+                return None;
+            }
+            let on = join
+                .on()
+                .iter()
+                .map(|(left, right)| {
+                    let left = left.downcast_ref::<Column>()?;
+                    let right = right.downcast_ref::<Column>()?;
+                    Some((left.index(), right.index()))
+                })
+                .collect::<Option<Vec<_>>>()?;
+            Some(Self::Join {
+                left: join.left(),
+                right: join.right(),
+                on,
+                projection: join.projection.as_deref(),
+                partition_mode: Some(*join.partition_mode()),
+            })
+        } else if let Some(join) = plan.downcast_ref::<CrossJoinExec>() {
+            Some(Self::Join {
+                left: join.left(),
+                right: join.right(),
+                on: vec![],
+                projection: None,
+                partition_mode: None,
+            })
+        } else if let Some(projection) = plan.downcast_ref::<ProjectionExec>() {
+            // projections are only part of the graph between joins
+            Self::try_new(projection.input())?;
+            let columns = projection
+                .expr()
+                .iter()
+                .map(|expr| Some(expr.expr.downcast_ref::<Column>()?.index()))
+                .collect::<Option<Vec<_>>>()?;
+            Some(Self::Projection {
+                input: projection.input(),
+                columns,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+/// A tree of directly connected inner joins, flattened into its relations
+/// and the equi-join keys between them
+#[derive(Debug)]
+struct JoinGraph {
+    relations: Vec<Arc<dyn ExecutionPlan>>,
+    edges: Vec<JoinEdge>,
+    /// The output columns of the root of the join tree
+    output: Vec<RelationColumn>,
+    /// The join order of the original plan
+    order: JoinTree,
+    partition_mode: PartitionMode,
+}
+
+impl JoinGraph {
+    /// Flattens the join tree rooted at `plan`, returning `None` if `plan` is
+    /// not a join or the tree has fewer than three relations.
+    fn try_new(plan: &Arc<dyn ExecutionPlan>) -> Option<Self> {
+        if !matches!(GraphNode::try_new(plan)?, GraphNode::Join { .. }) {
+            return None;
+        }
+        let mut graph = Self {
+            relations: vec![],
+            edges: vec![],
+            output: vec![],
+            order: JoinTree::Relation(0),
+            partition_mode: PartitionMode::Auto,
+        };
+        let mut partition_mode = None;
+        let (order, output) = graph.add_node(plan, &mut partition_mode);
+        if graph.relations.len() < 3 {
+            return None;
+        }
+        graph.order = order;
+        graph.output = output;
+        graph.partition_mode = partition_mode.unwrap_or(PartitionMode::Auto);
+        Some(graph)
+    }
+
+    /// Adds the subtree rooted at `plan`, returning its join order and the
+    /// relation column of each of its output columns.
+    fn add_node(
+        &mut self,
+        plan: &Arc<dyn ExecutionPlan>,
+        partition_mode: &mut Option<PartitionMode>,
+    ) -> (JoinTree, Vec<RelationColumn>) {
+        match GraphNode::try_new(plan) {
+            Some(GraphNode::Join {
+                left,
+                right,
+                on,
+                projection,
+                partition_mode: join_partition_mode,
+            }) => {
+                if partition_mode.is_none() {
+                    *partition_mode = join_partition_mode;
+                }
+                let (left_order, left_columns) = self.add_node(left, partition_mode);
+                let (right_order, right_columns) = self.add_node(right, partition_mode);
+                self.edges
+                    .extend(on.into_iter().map(|(left, right)| JoinEdge {
+                        left: left_columns[left],
+                        right: right_columns[right],
+                    }));
+
+                let columns = left_columns.into_iter().chain(right_columns);
+                let columns = match projection {
+                    Some(projection) => {
+                        let columns = columns.collect::<Vec<_>>();
+                        projection.iter().map(|&index| columns[index]).collect()
+                    }
+                    None => columns.collect(),
+                };
+                let order = JoinTree::Join(Box::new(left_order), Box::new(right_order));
+                (order, columns)
+            }
+            Some(GraphNode::Projection { input, columns }) => {
+                let (order, input_columns) = self.add_node(input, partition_mode);
+                let columns = columns.into_iter().map(|index| input_columns[index]);
+                (order, columns.collect())
+            }
+            None => {
+                let relation = self.relations.len();
+                self.relations.push(Arc::clone(plan));
+                let columns = (0..plan.schema().fields().len())
+                    .map(|column| (relation, column))
+                    .collect();
+                (JoinTree::Relation(relation), columns)
+            }
+        }
+    }
+
+    /// Builds the plan for the join order `tree` on top of `relations`,
+    /// producing the output `schema` of the original join tree.
+    fn build(
+        &self,
+        tree: &JoinTree,
+        relations: Vec<Arc<dyn ExecutionPlan>>,
+        schema: &SchemaRef,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        // Only keep the columns of each relation that are part of the output
+        // or a join key, as the projections between the original joins are lost
+        let mut required = vec![BTreeSet::new(); relations.len()];
+        let edge_columns = self.edges.iter().flat_map(|edge| [edge.left, edge.right]);
+        for (relation, column) in self.output.iter().copied().chain(edge_columns) {
+            required[relation].insert(column);
+        }
+        let mut column_positions = Vec::with_capacity(relations.len());
+        let relations = relations
+            .into_iter()
+            .zip(required)
+            .map(|(relation, required)| {
+                let num_columns = relation.schema().fields().len();
+                if required.is_empty() || required.len() == num_columns {
+                    column_positions.push((0..num_columns).map(Some).collect());
+                    return Ok(relation);
+                }
+                let mut positions = vec![None; num_columns];
+                for (position, &column) in required.iter().enumerate() {
+                    positions[column] = Some(position);
+                }
+                column_positions.push(positions);
+                let schema = relation.schema();
+                let exprs = required.iter().map(|&column| {
+                    let name = schema.field(column).name();
+                    ProjectionExpr {
+                        expr: Arc::new(Column::new(name, column)),
+                        alias: name.clone(),
+                    }
+                });
+                Ok(Arc::new(ProjectionExec::try_new(exprs, relation)?) as _)
+            })
+            .collect::<Result<Vec<Arc<dyn ExecutionPlan>>>>()?;
+
+        let (plan, columns) = self.build_tree(tree, &relations, &column_positions)?;
+
+        let positions = self
+            .output
+            .iter()
+            .map(|column| {
+                columns.iter().position(|c| c == column).ok_or_else(|| {
+                    internal_datafusion_err!("Join reordering lost column {column:?}")
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let plan_schema = plan.schema();
+        let is_identity = positions.len() == columns.len()
+            && positions.iter().enumerate().all(|(index, &position)| {
+                index == position
+                    && plan_schema.field(position).name() == schema.field(index).name()
+            });
+        if is_identity {
+            return Ok(plan);
+        }
+        let exprs =
+            positions
+                .into_iter()
+                .zip(schema.fields())
+                .map(|(position, field)| ProjectionExpr {
+                    expr: Arc::new(Column::new(
+                        plan_schema.field(position).name(),
+                        position,
+                    )),
+                    alias: field.name().clone(),
+                });
+        Ok(Arc::new(ProjectionExec::try_new(exprs, plan)?))
+    }
+
+    fn build_tree(
+        &self,
+        tree: &JoinTree,
+        relations: &[Arc<dyn ExecutionPlan>],
+        column_positions: &[Vec<Option<usize>>],
+    ) -> Result<(Arc<dyn ExecutionPlan>, Vec<RelationColumn>)> {
+        let (left, right) = match tree {
+            JoinTree::Relation(relation) => {
+                let columns = column_positions[*relation]
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, position)| position.is_some())
+                    .map(|(column, _)| (*relation, column))
+                    .collect();
+                return Ok((Arc::clone(&relations[*relation]), columns));
+            }
+            JoinTree::Join(left, right) => (left, right),
+        };
+        let (left, left_columns) = self.build_tree(left, relations, column_positions)?;
+        let (right, right_columns) =
+            self.build_tree(right, relations, column_positions)?;
+
+        let key = |plan: &Arc<dyn ExecutionPlan>,
+                   columns: &[RelationColumn],
+                   column: &RelationColumn| {
+            let index = columns.iter().position(|c| c == column)?;
+            let name = plan.schema().field(index).name().clone();
+            Some(Arc::new(Column::new(&name, index)) as PhysicalExprRef)
+        };
+        let on = self
+            .edges
+            .iter()
+            .filter_map(|edge| {
+                let left_key = key(&left, &left_columns, &edge.left);
+                let right_key = key(&right, &right_columns, &edge.right);
+                if let (Some(left_key), Some(right_key)) = (left_key, right_key) {
+                    return Some((left_key, right_key));
+                }
+                let left_key = key(&left, &left_columns, &edge.right)?;
+                let right_key = key(&right, &right_columns, &edge.left)?;
+                Some((left_key, right_key))
+            })
+            .collect::<Vec<_>>();
+
+        let join: Arc<dyn ExecutionPlan> = if on.is_empty() {
+            Arc::new(CrossJoinExec::new(left, right))
+        } else {
+            HashJoinExecBuilder::new(left, right, on, JoinType::Inner)
+                .with_partition_mode(self.partition_mode)
+                .build_exec()?
+        };
+        let columns = left_columns.into_iter().chain(right_columns).collect();
+        Ok((join, columns))
+    }
+}
+
+/// Replaces the relations of the join tree rooted at `plan`, keeping its
+/// join order.
+fn replace_relations(
+    plan: &Arc<dyn ExecutionPlan>,
+    relations: &mut impl Iterator<Item = Arc<dyn ExecutionPlan>>,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    if GraphNode::try_new(plan).is_none() {
+        return relations
+            .next()
+            .ok_or_else(|| internal_datafusion_err!("Missing relation of join graph"));
+    }
+    let children = plan
+        .children()
+        .into_iter()
+        .map(|child| replace_relations(child, relations))
+        .collect::<Result<Vec<_>>>()?;
+    Arc::clone(plan).with_new_children(children)
+}
+
+/// A join order with its estimated output cardinality and cost
+#[derive(Debug, Clone)]
+struct Candidate {
+    tree: JoinTree,
+    cardinality: f64,
+    /// The sum of the cardinalities of all joins
+    cost: f64,
+}
+
+/// Cardinality estimates for the joins of a [`JoinGraph`]
+struct JoinEstimator<'a> {
+    graph: &'a JoinGraph,
+    /// Estimated row count of each relation
+    rows: Vec<f64>,
+    /// Selectivity of each edge
+    selectivities: Vec<f64>,
+}
+
+impl<'a> JoinEstimator<'a> {
+    /// Collects the relation statistics, returning `None` if any relation has
+    /// no row count estimate.
+    ///
+    /// Like `JoinStatisticsProvider`, the selectivity of a join key is
+    /// `1 / max(left_ndv, right_ndv)`, treating the keys of a join as
+    /// independent. A missing distinct count is replaced by the row count of
+    /// its relation, which is an upper bound of it.
+    fn try_new(
+        graph: &'a JoinGraph,
+        registry: &StatisticsRegistry,
+    ) -> Result<Option<Self>> {
+        let mut rows = Vec::with_capacity(graph.relations.len());
+        let mut statistics = Vec::with_capacity(graph.relations.len());
+        for relation in &graph.relations {
+            let relation_statistics = registry.compute(relation.as_ref())?;
+            let Some(&num_rows) = relation_statistics.base().num_rows.get_value() else {
+                return Ok(None);
+            };
+            rows.push(num_rows as f64);
+            statistics.push(relation_statistics);
+        }
+
+        let distinct_count = |(relation, column): RelationColumn| {
+            let num_rows = rows[relation];
+            statistics[relation]
+                .base()
+                .column_statistics
+                .get(column)
+                .and_then(|stats| stats.distinct_count.get_value())
+                .map_or(num_rows, |&ndv| (ndv as f64).min(num_rows))
+                .max(1.0)
+        };
+        let selectivities = graph
+            .edges
+            .iter()
+            .map(|edge| 1.0 / distinct_count(edge.left).max(distinct_count(edge.right)))
+            .collect();
+
+        Ok(Some(Self {
+            graph,
+            rows,
+            selectivities,
+        }))
+    }
+
+    /// The combined selectivity of the edges between the relations in
+    /// `left` and `right`, or `None` if no edge connects them.
+    fn selectivity(
+        &self,
+        in_left: impl Fn(usize) -> bool,
+        in_right: impl Fn(usize) -> bool,
+    ) -> Option<f64> {
+        self.graph
+            .edges
+            .iter()
+            .zip(&self.selectivities)
+            .filter(|(edge, _)| {
+                (in_left(edge.left.0) && in_right(edge.right.0))
+                    || (in_left(edge.right.0) && in_right(edge.left.0))
+            })
+            .map(|(_, selectivity)| *selectivity)
+            .reduce(|a, b| a * b)
+    }
+
+    fn relation(&self, relation: usize) -> Candidate {
+        Candidate {
+            tree: JoinTree::Relation(relation),
+            cardinality: self.rows[relation],
+            cost: 0.0,
+        }
+    }
+
+    /// Joins two candidates, with the smaller one as the build side.
+    fn join(&self, left: &Candidate, right: &Candidate, selectivity: f64) -> Candidate {
+        let cardinality = left.cardinality * right.cardinality * selectivity;
+        let (build, probe) = if left.cardinality <= right.cardinality {
+            (left, right)
+        } else {
+            (right, left)
+        };
+        Candidate {
+            tree: JoinTree::Join(
+                Box::new(build.tree.clone()),
+                Box::new(probe.tree.clone()),
+            ),
+            cardinality,
+            cost: left.cost + right.cost + cardinality,
+        }
+    }
+
+    /// Estimates the cost of an existing join order.
+    fn estimate_tree(&self, tree: &JoinTree) -> Candidate {
+        match tree {
+            JoinTree::Relation(relation) => self.relation(*relation),
+            JoinTree::Join(left, right) => {
+                let left_relations = left.relations();
+                let right_relations = right.relations();
+                let selectivity = self
+                    .selectivity(
+                        |r| left_relations.contains(&r),
+                        |r| right_relations.contains(&r),
+                    )
+                    .unwrap_or(1.0);
+                let mut joined = self.join(
+                    &self.estimate_tree(left),
+                    &self.estimate_tree(right),
+                    selectivity,
+                );
+                // keep the original sides
+                joined.tree = tree.clone();
+                joined
+            }
+        }
+    }
+
+    /// Finds the cheapest join order without cross products inside each
+    /// connected part of the graph, by dynamic programming over all connected
+    /// subsets of relations.
+    fn dynamic_programming_order(&self) -> Candidate {
+        let num_relations = self.rows.len();
+        let mut neighbors = vec![0u64; num_relations];
+        for edge in &self.graph.edges {
+            neighbors[edge.left.0] |= 1 << edge.right.0;
+            neighbors[edge.right.0] |= 1 << edge.left.0;
+        }
+        let neighbors_of = |set: u64| {
+            (0..num_relations)
+                .filter(|&relation| set & (1 << relation) != 0)
+                .fold(0, |acc, relation| acc | neighbors[relation])
+        };
+
+        // best[set] is the cheapest order for a connected set of relations
+        let mut best: Vec<Option<Candidate>> = vec![None; 1 << num_relations];
+        for relation in 0..num_relations {
+            best[1 << relation] = Some(self.relation(relation));
+        }
+        for set in 1u64..(1 << num_relations) {
+            if set.count_ones() < 2 {
+                continue;
+            }
+            // visit each split once, with the lowest relation on the left
+            let lowest = set & set.wrapping_neg();
+            let mut left = (set - 1) & set;
+            while left != 0 {
+                let right = set & !left;
+                if left & lowest != 0
+                    && neighbors_of(left) & right != 0
+                    && let (Some(left_best), Some(right_best)) =
+                        (&best[left as usize], &best[right as usize])
+                {
+                    let selectivity = self
+                        .selectivity(|r| left & (1 << r) != 0, |r| right & (1 << r) != 0)
+                        .unwrap_or(1.0);
+                    let candidate = self.join(left_best, right_best, selectivity);
+                    if best[set as usize]
+                        .as_ref()
+                        .is_none_or(|current| candidate.cost < current.cost)
+                    {
+                        best[set as usize] = Some(candidate);
+                    }
+                }
+                left = (left - 1) & set;
+            }
+        }
+
+        // Join the connected components of the graph with cross joins, from
+        // the smallest to the largest
+        let mut remaining: u64 = (1 << num_relations) - 1;
+        let mut components = vec![];
+        while remaining != 0 {
+            let mut component = remaining & remaining.wrapping_neg();
+            loop {
+                let grown = component | (neighbors_of(component) & remaining);
+                if grown == component {
+                    break;
+                }
+                component = grown;
+            }
+            remaining &= !component;
+            components.push(
+                best[component as usize]
+                    .take()
+                    .expect("connected sets of relations have a join order"),
+            );
+        }
+        components.sort_by(|a, b| a.cardinality.total_cmp(&b.cardinality));
+        let mut components = components.into_iter();
+        let first = components.next().unwrap_or_else(|| self.relation(0));
+        components.fold(first, |acc, component| self.join(&acc, &component, 1.0))
+    }
+
+    /// Builds a join order by repeatedly joining the two connected
+    /// candidates with the smallest estimated output, falling back to a cross
+    /// join of the two smallest candidates if none are connected.
+    fn greedy_order(&self) -> Candidate {
+        let num_relations = self.rows.len();
+        let mut candidates = (0..num_relations)
+            .map(|relation| Some(self.relation(relation)))
+            .collect::<Vec<_>>();
+        // the index of the candidate that contains each relation
+        let mut candidate_of = (0..num_relations).collect::<Vec<_>>();
+
+        for _ in 1..num_relations {
+            let mut next: Option<(usize, usize, Option<f64>, f64)> = None;
+            for i in 0..num_relations {
+                let Some(left) = &candidates[i] else { continue };
+                for j in i + 1..num_relations {
+                    let Some(right) = &candidates[j] else {
+                        continue;
+                    };
+                    let selectivity = self
+                        .selectivity(|r| candidate_of[r] == i, |r| candidate_of[r] == j);
+                    let cardinality =
+                        left.cardinality * right.cardinality * selectivity.unwrap_or(1.0);
+                    // prefer joins over cross joins, then smaller outputs
+                    let is_better = match &next {
+                        None => true,
+                        Some((_, _, best_selectivity, best_cardinality)) => {
+                            (selectivity.is_some(), -cardinality)
+                                > (best_selectivity.is_some(), -best_cardinality)
+                        }
+                    };
+                    if is_better {
+                        next = Some((i, j, selectivity, cardinality));
+                    }
+                }
+            }
+
+            let Some((i, j, selectivity, _)) = next else {
+                break;
+            };
+            let (Some(left), Some(right)) = (candidates[i].take(), candidates[j].take())
+            else {
+                unreachable!("candidates are only taken once");
+            };
+            for candidate in &mut candidate_of {
+                if *candidate == j {
+                    *candidate = i;
+                }
+            }
+            candidates[i] = Some(self.join(&left, &right, selectivity.unwrap_or(1.0)));
+        }
+
+        candidates
+            .into_iter()
+            .flatten()
+            .next()
+            .unwrap_or_else(|| self.relation(0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use datafusion_physical_plan::empty::EmptyExec;
+
+    fn relation(name: &str) -> Arc<dyn ExecutionPlan> {
+        Arc::new(EmptyExec::new(Arc::new(Schema::new(vec![Field::new(
+            name,
+            DataType::Int32,
+            false,
+        )]))))
+    }
+
+    /// A chain `0 - 1 - 2 - ...` of relations with the given row counts,
+    /// joined on unique keys
+    fn chain_graph(rows: &[f64]) -> JoinGraph {
+        let relations = (0..rows.len())
+            .map(|i| relation(&format!("c{i}")))
+            .collect();
+        let edges = (1..rows.len())
+            .map(|i| JoinEdge {
+                left: (i - 1, 0),
+                right: (i, 0),
+            })
+            .collect();
+        JoinGraph {
+            relations,
+            edges,
+            output: vec![],
+            order: JoinTree::Relation(0),
+            partition_mode: PartitionMode::Auto,
+        }
+    }
+
+    /// Estimates with unique join keys, i.e. a selectivity of
+    /// `1 / max(left_rows, right_rows)` for each edge
+    fn estimator<'a>(graph: &'a JoinGraph, rows: &[f64]) -> JoinEstimator<'a> {
+        let selectivities = graph
+            .edges
+            .iter()
+            .map(|edge| 1.0 / rows[edge.left.0].max(rows[edge.right.0]))
+            .collect();
+        JoinEstimator {
+            graph,
+            rows: rows.to_vec(),
+            selectivities,
+        }
+    }
+
+    fn format_tree(tree: &JoinTree) -> String {
+        match tree {
+            JoinTree::Relation(relation) => relation.to_string(),
+            JoinTree::Join(left, right) => {
+                format!("({} {})", format_tree(left), format_tree(right))
+            }
+        }
+    }
+
+    #[test]
+    fn dynamic_programming_avoids_cross_products() {
+        // 0 and 2 are small but not connected, so joining them first would be
+        // a cross product
+        let rows = [10.0, 1000.0, 10.0];
+        let graph = chain_graph(&rows);
+        let best = estimator(&graph, &rows).dynamic_programming_order();
+        assert_eq!(format_tree(&best.tree), "(0 (2 1))");
+    }
+
+    #[test]
+    fn dynamic_programming_finds_bushy_orders() {
+        // 1 and 2 join on a key with few distinct values, so both are first
+        // joined with the small relation that filters them
+        let rows = [10.0, 1000.0, 1000.0, 10.0];
+        let graph = chain_graph(&rows);
+        let mut estimator = estimator(&graph, &rows);
+        estimator.selectivities[1] = 0.1;
+        let best = estimator.dynamic_programming_order();
+        assert_eq!(format_tree(&best.tree), "((0 1) (3 2))");
+        assert_eq!(best.cost, 30.0);
+
+        let left_deep = JoinTree::Join(
+            Box::new(JoinTree::Join(
+                Box::new(JoinTree::Join(
+                    Box::new(JoinTree::Relation(0)),
+                    Box::new(JoinTree::Relation(1)),
+                )),
+                Box::new(JoinTree::Relation(2)),
+            )),
+            Box::new(JoinTree::Relation(3)),
+        );
+        assert_eq!(estimator.estimate_tree(&left_deep).cost, 1020.0);
+    }
+
+    #[test]
+    fn disconnected_components_are_cross_joined_last() {
+        let rows = [100.0, 10.0, 5.0, 50.0];
+        let mut graph = chain_graph(&rows);
+        // 0 - 1 and 2 - 3
+        graph.edges.remove(1);
+        let best = estimator(&graph, &rows).dynamic_programming_order();
+        assert_eq!(format_tree(&best.tree), "((2 3) (1 0))");
+    }
+
+    #[test]
+    fn greedy_joins_smallest_results_first() {
+        let rows = [1000.0, 10.0, 100.0, 1.0];
+        let graph = chain_graph(&rows);
+        let estimator = estimator(&graph, &rows);
+        let greedy = estimator.greedy_order();
+        assert_eq!(format_tree(&greedy.tree), "(((3 2) 1) 0)");
+        // the greedy order is optimal for this chain
+        let best = estimator.dynamic_programming_order();
+        assert!((greedy.cost - best.cost).abs() < 1e-9);
+    }
+}
@@ -33,6 +33,7 @@ use crate::filter_pushdown::{
 };
 use crate::joins::Map;
 use crate::joins::array_map::ArrayMap;
+use crate::joins::hash_join::grace::GraceHashJoin;
 use crate::joins::hash_join::inlist_builder::build_struct_inlist_values;
 use crate::joins::hash_join::shared_bounds::{
     ColumnBounds, PartitionBounds, PushdownStrategy, SharedBuildAccumulator,
@@ -46,13 +47,14 @@ use crate::joins::utils::{
     swap_join_projection, update_hash,
 };
 use crate::joins::{JoinOn, JoinOnRef, PartitionMode, SharedBitmapBuilder};
-use crate::metrics::{Count, MetricBuilder, MetricCategory};
+use crate::metrics::{Count, MetricBuilder, MetricCategory, SpillMetrics};
 use crate::projection::{
     EmbeddedProjection, JoinData, ProjectionExec, try_embed_projection,
     try_pushdown_through_join,
 };
 use crate::repartition::REPARTITION_RANDOM_STATE;
 use crate::spill::get_record_batch_memory_size;
+use crate::spill::spill_manager::SpillManager;
 use crate::{
     DisplayAs, DisplayFormatType, Distribution, ExecutionPlan, Partitioning,
     PlanProperties, SendableRecordBatchStream, Statistics,
@@ -110,6 +112,7 @@ fn try_create_array_map(
     batches: &[RecordBatch],
     on_left: &[PhysicalExprRef],
     reservation: &mut MemoryReservation,
+    hash_table_reserved: usize,
     perfect_hash_join_small_build_threshold: usize,
     perfect_hash_join_min_key_density: f64,
     null_equality: NullEquality,
@@ -175,8 +178,9 @@ fn try_create_array_map(
         return Ok(None);
     }
 
+    // The memory reserved for the hash table is used for the array map instead
     let mem_size = ArrayMap::estimate_memory_size(min_val, max_val, num_row);
-    reservation.try_grow(mem_size)?;
+    try_grow_reserved(reservation, mem_size, hash_table_reserved)?;
 
     let batch = concat_batches(schema, batches)?;
     let left_values = evaluate_expressions_to_arrays(on_left, &batch)?;
@@ -402,6 +406,13 @@ impl HashJoinExecBuilder {
             preserve_properties,
         } = self;
 
+        if exec.join_type().is_asof() {
+            return plan_err!(
+                "HashJoinExec does not support {} joins, use AsOfJoinExec instead",
+                exec.join_type()
+            );
+        }
+
         // Validate null_aware flag
         if exec.null_aware {
             let join_type = exec.join_type();
@@ -710,6 +721,29 @@ impl From<&HashJoinExec> for HashJoinExecBuilder {
 ///                       └───────────────┘     └───────────────┘
 /// ```
 ///
+/// # Spilling
+///
+/// By default the entire build side of each partition must fit in memory, and
+/// the join fails with a "Resources exhausted" error otherwise. When
+/// `datafusion.execution.enable_hash_join_spill` is set, a join in
+/// [`PartitionMode::Partitioned`] instead falls back to a partitioned ("grace")
+/// hash join once its memory reservation can no longer grow:
+///
+/// 1. The buffered build-side batches, the rest of the build side and then the
+///    whole probe side are hash-partitioned on the join keys into
+///    `datafusion.execution.hash_join_spill_partitions` spill files each.
+/// 2. Matching rows always land in the same spill partition, so each pair of
+///    build/probe spill files is joined independently with the regular
+///    in-memory algorithm, one pair at a time.
+/// 3. A build spill partition that is still too large is split again with a
+///    different hash seed, up to a fixed recursion depth.
+///
+/// Spilling is never used in [`PartitionMode::CollectLeft`] (the build side is
+/// shared by all output partitions), for null-aware anti joins, or when the
+/// probe side ordering must be preserved. A partition that spills can not
+/// contribute its build side to the dynamic filter, so the filter lets all
+/// probe rows of that partition pass. See [`GraceHashJoin`] for details.
+///
 /// # Clone / Shared State
 ///
 /// Note this structure includes a [`OnceAsync`] that is used to coordinate the
@@ -911,6 +945,89 @@ impl HashJoinExec {
         self.dynamic_filter.as_ref().map(|df| &df.filter)
     }
 
+    /// Column indices of the output, after applying the embedded projection
+    fn column_indices_after_projection(&self) -> Vec<ColumnIndex> {
+        match self.projection.as_ref() {
+            Some(projection) => projection
+                .iter()
+                .map(|i| self.column_indices[*i].clone())
+                .collect(),
+            None => self.column_indices.clone(),
+        }
+    }
+
+    /// Returns true if this join may fall back to a spilling [`GraceHashJoin`]
+    /// when its build side does not fit in memory.
+    ///
+    /// See the "Spilling" section of [`HashJoinExec`] for the conditions.
+    fn can_spill(&self, context: &TaskContext) -> bool {
+        let options = &context.session_config().options().execution;
+        options.enable_hash_join_spill
+            && options.hash_join_spill_partitions > 1
+            && self.mode == PartitionMode::Partitioned
+            && !self.null_aware
+            && self.right.output_ordering().is_none()
+            && context.runtime_env().disk_manager.tmp_files_enabled()
+    }
+
+    /// Executes `partition` of a [`PartitionMode::Partitioned`] join that
+    /// spills both of its inputs to disk if the build side does not fit in
+    /// memory
+    fn execute_with_spill(
+        &self,
+        partition: usize,
+        context: &Arc<TaskContext>,
+        join_metrics: BuildProbeJoinMetrics,
+        array_map_created_count: Count,
+        build_accumulator: Option<Arc<SharedBuildAccumulator>>,
+    ) -> Result<SendableRecordBatchStream> {
+        let left_stream = self.left.execute(partition, Arc::clone(context))?;
+        let right_stream = self.right.execute(partition, Arc::clone(context))?;
+
+        let reservation = MemoryConsumer::new(format!("HashJoinInput[{partition}]"))
+            .with_can_spill(true)
+            .register(context.memory_pool());
+
+        let spill_metrics = SpillMetrics::new(&self.metrics, partition);
+        let spill_compression = context.session_config().spill_compression();
+        let left_spill_manager = SpillManager::new(
+            context.runtime_env(),
+            spill_metrics.clone(),
+            self.left.schema(),
+        )
+        .with_compression_type(spill_compression);
+        let right_spill_manager =
+            SpillManager::new(context.runtime_env(), spill_metrics, self.right.schema())
+                .with_compression_type(spill_compression);
+
+        let grace = GraceHashJoin {
+            partition,
+            schema: self.schema(),
+            on_left: self.on.iter().map(|(l, _)| Arc::clone(l)).collect(),
+            on_right: self.on.iter().map(|(_, r)| Arc::clone(r)).collect(),
+            filter: self.filter.clone(),
+            join_type: self.join_type,
+            random_state: self.random_state.random_state().clone(),
+            column_indices: self.column_indices_after_projection(),
+            null_equality: self.null_equality,
+            batch_size: context.session_config().batch_size(),
+            fetch: self.fetch,
+            num_spill_partitions: context
+                .session_config()
+                .options()
+                .execution
+                .hash_join_spill_partitions,
+            config: Arc::clone(context.session_config().options()),
+            join_metrics,
+            array_map_created_count,
+            left_spill_manager,
+            right_spill_manager,
+            build_accumulator,
+        };
+
+        Ok(grace.execute(left_stream, right_stream, reservation))
+    }
+
     /// Calculate order preservation flags for this hash join.
     fn maintains_input_order(join_type: JoinType) -> Vec<bool> {
         vec![
@@ -997,6 +1114,7 @@ impl HashJoinExec {
                 JoinType::Left
                 | JoinType::LeftAnti
                 | JoinType::LeftMark
+                | JoinType::LeftAsOf
                 | JoinType::Full => EmissionType::Both,
             }
         } else {
@@ -1052,7 +1170,7 @@ impl HashJoinExec {
         let right = self.right();
         let new_join = self
             .builder()
-            .with_type(self.join_type.swap())
+            .with_type(self.join_type.try_swap()?)
             .with_new_children(vec![Arc::clone(right), Arc::clone(left)])?
             .with_on(
                 self.on()
@@ -1364,6 +1482,15 @@ impl ExecutionPlan for HashJoinExec {
                     array_map_created_count,
                 ))
             })?,
+            PartitionMode::Partitioned if self.can_spill(&context) => {
+                return self.execute_with_spill(
+                    partition,
+                    &context,
+                    join_metrics,
+                    array_map_created_count,
+                    build_accumulator,
+                );
+            }
             PartitionMode::Partitioned => {
                 let left_stream = self.left.execute(partition, Arc::clone(&context))?;
 
@@ -1398,15 +1525,6 @@ impl ExecutionPlan for HashJoinExec {
         // over the right that uses this information to issue new batches.
         let right_stream = self.right.execute(partition, context)?;
 
-        // update column indices to reflect the projection
-        let column_indices_after_projection = match self.projection.as_ref() {
-            Some(projection) => projection
-                .iter()
-                .map(|i| self.column_indices[*i].clone())
-                .collect(),
-            None => self.column_indices.clone(),
-        };
-
         let on_right = self
             .on
             .iter()
@@ -1422,7 +1540,7 @@ impl ExecutionPlan for HashJoinExec {
             right_stream,
             self.random_state.random_state().clone(),
             join_metrics,
-            column_indices_after_projection,
+            self.column_indices_after_projection(),
             self.null_equality,
             HashJoinStreamState::WaitBuildSide,
             BuildSide::Initial(BuildSideInitialState { left_fut }),
@@ -1713,7 +1831,7 @@ impl ExecutionPlan for HashJoinExec {
 fn lr_is_preserved(join_type: JoinType) -> (bool, bool) {
     match join_type {
         JoinType::Inner => (true, true),
-        JoinType::Left => (true, false),
+        JoinType::Left | JoinType::LeftAsOf => (true, false),
         JoinType::Right => (false, true),
         JoinType::Full => (false, false),
         // Filters in semi/anti joins are either on the preserved side, or on join keys,
@@ -1807,17 +1925,20 @@ impl CollectLeftAccumulator {
 }
 
 /// State for collecting the build-side data during hash join
-struct BuildSideState {
-    batches: Vec<RecordBatch>,
-    num_rows: usize,
+pub(super) struct BuildSideState {
+    pub(super) batches: Vec<RecordBatch>,
+    pub(super) num_rows: usize,
     metrics: BuildProbeJoinMetrics,
-    reservation: MemoryReservation,
+    pub(super) reservation: MemoryReservation,
     bounds_accumulators: Option<Vec<CollectLeftAccumulator>>,
+    /// Memory of `reservation` already reserved for the hash table by
+    /// [`Self::try_reserve_hash_table`]
+    hash_table_reserved: usize,
 }
 
 impl BuildSideState {
     /// Create a new BuildSideState with optional accumulators for bounds computation
-    fn try_new(
+    pub(super) fn try_new(
         metrics: BuildProbeJoinMetrics,
         reservation: MemoryReservation,
         on_left: Vec<Arc<dyn PhysicalExpr>>,
@@ -1837,11 +1958,81 @@ impl BuildSideState {
                         .collect::<Result<Vec<_>>>()
                 })
                 .transpose()?,
+            hash_table_reserved: 0,
         })
     }
+
+    /// Reserves memory for `batch` and buffers it.
+    ///
+    /// If the reservation cannot grow, the error is returned and the state
+    /// is left unchanged, so the caller may still spill `batch` elsewhere.
+    pub(super) fn push_batch(&mut self, batch: &RecordBatch) -> Result<()> {
+        let batch_size = get_record_batch_memory_size(batch);
+        // Reserve memory for incoming batch
+        self.reservation.try_grow(batch_size)?;
+
+        // Update accumulators if computing bounds
+        if let Some(ref mut accumulators) = self.bounds_accumulators {
+            for accumulator in accumulators {
+                accumulator.update_batch(batch)?;
+            }
+        }
+
+        // Update metrics
+        self.metrics.build_mem_used.add(batch_size);
+        self.metrics.build_input_batches.add(1);
+        self.metrics.build_input_rows.add(batch.num_rows());
+        // Update row count
+        self.num_rows += batch.num_rows();
+        // Push batch to output
+        self.batches.push(batch.clone());
+        Ok(())
+    }
+
+    /// Reserves the memory for the hash table of the buffered rows.
+    ///
+    /// [`build_left_data`] consumes the buffered batches, so this allows the
+    /// caller to find out whether building would run out of memory while the
+    /// batches can still be spilled. The memory stays reserved, and is used by
+    /// [`build_left_data`] for the hash table (or the array map) it builds.
+    pub(super) fn try_reserve_hash_table(&mut self) -> Result<()> {
+        let estimated_hashtable_size = if self.num_rows > u32::MAX as usize {
+            estimate_memory_size::<(u64, u64)>(
+                self.num_rows,
+                size_of::<JoinHashMapU64>(),
+            )?
+        } else {
+            estimate_memory_size::<(u32, u64)>(
+                self.num_rows,
+                size_of::<JoinHashMapU32>(),
+            )?
+        };
+        try_grow_reserved(
+            &mut self.reservation,
+            estimated_hashtable_size,
+            self.hash_table_reserved,
+        )?;
+        self.hash_table_reserved = estimated_hashtable_size;
+        Ok(())
+    }
+}
+
+/// Grows `reservation` by `size` bytes, of which `reserved` bytes are already
+/// reserved. If `reserved` exceeds `size`, the surplus is released.
+fn try_grow_reserved(
+    reservation: &mut MemoryReservation,
+    size: usize,
+    reserved: usize,
+) -> Result<()> {
+    if size > reserved {
+        reservation.try_grow(size - reserved)
+    } else {
+        reservation.shrink(reserved - size);
+        Ok(())
+    }
 }
 
-fn should_collect_min_max_for_perfect_hash(
+pub(super) fn should_collect_min_max_for_perfect_hash(
     on_left: &[PhysicalExprRef],
     schema: &SchemaRef,
 ) -> Result<bool> {
@@ -1911,29 +2102,46 @@ async fn collect_left_input(
 
     let state = left_stream
         .try_fold(initial, |mut state, batch| async move {
-            // Update accumulators if computing bounds
-            if let Some(ref mut accumulators) = state.bounds_accumulators {
-                for accumulator in accumulators {
-                    accumulator.update_batch(&batch)?;
-                }
-            }
-
-            // Decide if we spill or not
-            let batch_size = get_record_batch_memory_size(&batch);
-            // Reserve memory for incoming batch
-            state.reservation.try_grow(batch_size)?;
-            // Update metrics
-            state.metrics.build_mem_used.add(batch_size);
-            state.metrics.build_input_batches.add(1);
-            state.metrics.build_input_rows.add(batch.num_rows());
-            // Update row count
-            state.num_rows += batch.num_rows();
-            // Push batch to output
-            state.batches.push(batch);
+            state.push_batch(&batch)?;
             Ok(state)
         })
         .await?;
 
+    build_left_data(
+        state,
+        &schema,
+        &random_state,
+        &on_left,
+        with_visited_indices_bitmap,
+        probe_threads_count,
+        should_compute_dynamic_filters,
+        should_collect_min_max_for_phj,
+        &config,
+        null_equality,
+        &array_map_created_count,
+    )
+}
+
+/// Builds the hash table (or perfect hash array map) for the build-side
+/// batches buffered in `state`.
+///
+/// This is the second half of [`collect_left_input`], split out so that
+/// the spilling hash join in [`super::grace`] can build a [`JoinLeftData`]
+/// for each spilled partition from batches it has buffered itself.
+#[expect(clippy::too_many_arguments)]
+pub(super) fn build_left_data(
+    state: BuildSideState,
+    schema: &SchemaRef,
+    random_state: &RandomState,
+    on_left: &[PhysicalExprRef],
+    with_visited_indices_bitmap: bool,
+    probe_threads_count: usize,
+    should_compute_dynamic_filters: bool,
+    should_collect_min_max_for_phj: bool,
+    config: &ConfigOptions,
+    null_equality: NullEquality,
+    array_map_created_count: &Count,
+) -> Result<JoinLeftData> {
     // Extract fields from state
     let BuildSideState {
         batches,
@@ -1941,6 +2149,7 @@ async fn collect_left_input(
         metrics,
         mut reservation,
         bounds_accumulators,
+        hash_table_reserved,
     } = state;
 
     // Compute bounds
@@ -1958,10 +2167,11 @@ async fn collect_left_input(
     let (join_hash_map, batch, left_values) =
         if let Some((array_map, batch, left_value)) = try_create_array_map(
             &bounds,
-            &schema,
+            schema,
             &batches,
-            &on_left,
+            on_left,
             &mut reservation,
+            hash_table_reserved,
             config.execution.perfect_hash_join_small_build_threshold,
             config.execution.perfect_hash_join_min_key_density,
             null_equality,
@@ -1982,13 +2192,21 @@ async fn collect_left_input(
             let mut hashmap: Box<dyn JoinHashMapType> = if num_rows > u32::MAX as usize {
                 let estimated_hashtable_size =
                     estimate_memory_size::<(u64, u64)>(num_rows, fixed_size_u64)?;
-                reservation.try_grow(estimated_hashtable_size)?;
+                try_grow_reserved(
+                    &mut reservation,
+                    estimated_hashtable_size,
+                    hash_table_reserved,
+                )?;
                 metrics.build_mem_used.add(estimated_hashtable_size);
                 Box::new(JoinHashMapU64::with_capacity(num_rows))
             } else {
                 let estimated_hashtable_size =
                     estimate_memory_size::<(u32, u64)>(num_rows, fixed_size_u32)?;
-                reservation.try_grow(estimated_hashtable_size)?;
+                try_grow_reserved(
+                    &mut reservation,
+                    estimated_hashtable_size,
+                    hash_table_reserved,
+                )?;
                 metrics.build_mem_used.add(estimated_hashtable_size);
                 Box::new(JoinHashMapU32::with_capacity(num_rows))
             };
@@ -2003,11 +2221,11 @@ async fn collect_left_input(
                 hashes_buffer.clear();
                 hashes_buffer.resize(batch.num_rows(), 0);
                 update_hash(
-                    &on_left,
+                    on_left,
                     batch,
                     &mut *hashmap,
                     offset,
-                    &random_state,
+                    random_state,
                     &mut hashes_buffer,
                     0,
                     true,
@@ -2016,9 +2234,9 @@ async fn collect_left_input(
             }
 
             // Merge all batches into a single batch, so we can directly index into the arrays
-            let batch = concat_batches(&schema, batches_iter.clone())?;
+            let batch = concat_batches(schema, batches_iter.clone())?;
 
-            let left_values = evaluate_expressions_to_arrays(&on_left, &batch)?;
+            let left_values = evaluate_expressions_to_arrays(on_left, &batch)?;
 
             (Map::HashMap(hashmap), batch, left_values)
         };
@@ -5447,6 +5665,220 @@ mod tests {
         Ok(())
     }
 
+    /// Builds a single partition table of `num_rows` rows in batches of 100
+    /// rows, with join keys `b = i % modulo`
+    fn build_spill_table(
+        names: (&str, &str, &str),
+        num_rows: i32,
+        modulo: i32,
+    ) -> Arc<dyn ExecutionPlan> {
+        let batches = (0..num_rows)
+            .collect::<Vec<_>>()
+            .chunks(100)
+            .map(|chunk| {
+                build_table_i32(
+                    (names.0, &chunk.to_vec()),
+                    (names.1, &chunk.iter().map(|i| i % modulo).collect()),
+                    (names.2, &chunk.iter().map(|i| i % 7).collect()),
+                )
+            })
+            .collect::<Vec<_>>();
+        let schema = batches[0].schema();
+        TestMemoryExec::try_new_exec(&[batches], schema, None).unwrap()
+    }
+
+    fn spill_task_ctx(memory_limit: Option<usize>) -> Arc<TaskContext> {
+        let mut session_config = SessionConfig::default().with_batch_size(64);
+        session_config
+            .options_mut()
+            .execution
+            .enable_hash_join_spill = true;
+        let mut runtime = RuntimeEnvBuilder::new();
+        if let Some(memory_limit) = memory_limit {
+            runtime = runtime.with_memory_limit(memory_limit, 1.0);
+        }
+        Arc::new(
+            TaskContext::default()
+                .with_session_config(session_config)
+                .with_runtime(runtime.build_arc().unwrap()),
+        )
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn partitioned_join_spills_build_side(
+        #[values(
+            JoinType::Inner,
+            JoinType::Left,
+            JoinType::Right,
+            JoinType::Full,
+            JoinType::LeftSemi,
+            JoinType::LeftAnti,
+            JoinType::RightSemi,
+            JoinType::RightAnti,
+            JoinType::LeftMark,
+            JoinType::RightMark
+        )]
+        join_type: JoinType,
+        #[values(false, true)] with_filter: bool,
+    ) -> Result<()> {
+        let left = build_spill_table(("a1", "b1", "c1"), 2000, 97);
+        let right = build_spill_table(("a2", "b2", "c2"), 500, 131);
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+        )];
+        let filter = with_filter.then(prepare_join_filter);
+
+        let join = HashJoinExec::try_new(
+            left,
+            right,
+            on,
+            filter,
+            &join_type,
+            None,
+            PartitionMode::Partitioned,
+            NullEquality::NullEqualsNothing,
+            false,
+        )?;
+
+        // Reference result computed without a memory limit
+        let expected = common::collect(join.execute(0, spill_task_ctx(None))?).await?;
+        assert_eq!(join.metrics().unwrap().spill_count(), Some(0));
+
+        let join = join.builder().reset_state().build()?;
+        let stream = join.execute(0, spill_task_ctx(Some(16 * 1024)))?;
+        let batches = common::collect(stream).await?;
+
+        let metrics = join.metrics().unwrap();
+        assert!(metrics.spill_count().unwrap() > 0);
+        assert!(metrics.spilled_rows().unwrap() > 0);
+        assert_eq!(
+            batches_to_sort_string(&batches),
+            batches_to_sort_string(&expected)
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn partitioned_join_spill_respects_fetch() -> Result<()> {
+        let left = build_spill_table(("a1", "b1", "c1"), 2000, 97);
+        let right = build_spill_table(("a2", "b2", "c2"), 500, 131);
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+        )];
+
+        let join = HashJoinExec::try_new(
+            left,
+            right,
+            on,
+            None,
+            &JoinType::Inner,
+            None,
+            PartitionMode::Partitioned,
+            NullEquality::NullEqualsNothing,
+            false,
+        )?
+        .builder()
+        .with_fetch(Some(150))
+        .build()?;
+
+        let stream = join.execute(0, spill_task_ctx(Some(16 * 1024)))?;
+        let batches = common::collect(stream).await?;
+
+        assert!(join.metrics().unwrap().spill_count().unwrap() > 0);
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 150);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn partitioned_join_spills_with_dynamic_filter_pushdown() -> Result<()> {
+        let left = build_spill_table(("a1", "b1", "c1"), 2000, 97);
+        let right = build_spill_table(("a2", "b2", "c2"), 500, 131);
+        let on: JoinOn = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+        )];
+
+        // Without a memory limit, the build side is reported to the filter
+        let (join, dynamic_filter) = hash_join_with_dynamic_filter_and_mode(
+            Arc::clone(&left),
+            Arc::clone(&right),
+            on.clone(),
+            JoinType::Inner,
+            PartitionMode::Partitioned,
+        )?;
+        let expected = common::collect(join.execute(0, spill_task_ctx(None))?).await?;
+        assert_eq!(join.metrics().unwrap().spill_count(), Some(0));
+        dynamic_filter.wait_complete().await;
+        assert_ne!(dynamic_filter.current()?.to_string(), "true");
+
+        // The join still spills, and the filter lets all probe rows pass
+        let (join, dynamic_filter) = hash_join_with_dynamic_filter_and_mode(
+            left,
+            right,
+            on,
+            JoinType::Inner,
+            PartitionMode::Partitioned,
+        )?;
+        let stream = join.execute(0, spill_task_ctx(Some(16 * 1024)))?;
+        let batches = common::collect(stream).await?;
+
+        let metrics = join.metrics().unwrap();
+        assert!(metrics.spill_count().unwrap() > 0);
+        assert_eq!(
+            batches_to_sort_string(&batches),
+            batches_to_sort_string(&expected)
+        );
+        tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            dynamic_filter.wait_complete(),
+        )
+        .await
+        .expect("spilling partition should complete the dynamic filter");
+        assert_eq!(dynamic_filter.current()?.to_string(), "true");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn partitioned_join_spill_skewed_key_overallocation() -> Result<()> {
+        // All build rows share a single key, so splitting the spilled
+        // partitions again can never make the build side fit in memory
+        let left = build_spill_table(("a1", "b1", "c1"), 2000, 1);
+        let right = build_spill_table(("a2", "b2", "c2"), 500, 131);
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+        )];
+
+        let join = HashJoinExec::try_new(
+            left,
+            right,
+            on,
+            None,
+            &JoinType::Inner,
+            None,
+            PartitionMode::Partitioned,
+            NullEquality::NullEqualsNothing,
+            false,
+        )?;
+
+        let stream = join.execute(0, spill_task_ctx(Some(16 * 1024)))?;
+        let err = common::collect(stream).await.unwrap_err();
+
+        assert!(join.metrics().unwrap().spill_count().unwrap() > 0);
+        assert_contains!(
+            err.to_string(),
+            "Resources exhausted: Additional allocation failed for HashJoinInput[0]"
+        );
+
+        Ok(())
+    }
+
     fn build_table_struct(
         struct_name: &str,
         field_name_and_values: (&str, &Vec<Option<i32>>),
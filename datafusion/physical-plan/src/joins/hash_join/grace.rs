@@ -0,0 +1,602 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Spilling ("grace") fallback for [`super::HashJoinExec`]
+//!
+//! See comments in [`GraceHashJoin`] for details.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::joins::PartitionMode;
+use crate::joins::hash_join::exec::{
+    BuildSideState, JoinLeftData, build_left_data,
+    should_collect_min_max_for_perfect_hash,
+};
+use crate::joins::hash_join::shared_bounds::SharedBuildAccumulator;
+use crate::joins::hash_join::stream::{
+    BuildSide, BuildSideInitialState, HashJoinStream, HashJoinStreamState,
+};
+use crate::joins::utils::{
+    BuildProbeJoinMetrics, ColumnIndex, JoinFilter, OnceFut, need_produce_result_in_final,
+};
+use crate::metrics::Count;
+use crate::spill::in_progress_spill_file::InProgressSpillFile;
+use crate::spill::spill_manager::SpillManager;
+use crate::stream::RecordBatchStreamAdapter;
+use crate::{EmptyRecordBatchStream, SendableRecordBatchStream};
+
+use arrow::array::UInt32Array;
+use arrow::compute::take_arrays;
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::{RecordBatch, RecordBatchOptions};
+use datafusion_common::config::ConfigOptions;
+use datafusion_common::hash_utils::{RandomState, create_hashes};
+use datafusion_common::{DataFusionError, JoinType, NullEquality, Result};
+use datafusion_execution::disk_manager::RefCountedTempFile;
+use datafusion_execution::memory_pool::MemoryReservation;
+use datafusion_physical_expr::PhysicalExprRef;
+use datafusion_physical_expr_common::utils::evaluate_expressions_to_arrays;
+use futures::{StreamExt, TryStreamExt, stream};
+
+/// Seed of the hash used to assign rows to spill partitions.
+///
+/// It differs from both the `RepartitionExec` seed and the seed of the join hash table
+/// so that the rows of one input partition (which all share the same
+/// `RepartitionExec` hash modulo the partition count) are spread evenly over
+/// the spill partitions. Each recursion level adds its depth to the seed.
+const SPILL_PARTITION_SEED: u64 = 6_364_136_223_846_793_005;
+
+/// Maximum number of times a spill partition whose build side still does not
+/// fit in memory is split again before giving up.
+///
+/// Splitting cannot help when a single join key has more build rows than fit
+/// in memory, so the recursion has to stop at some point.
+const MAX_SPILL_DEPTH: usize = 3;
+
+/// Executes one output partition of a [`PartitionMode::Partitioned`] hash join,
+/// falling back to a partitioned ("grace") hash join when the build side does
+/// not fit in memory.
+///
+/// The build side is first buffered exactly like the in-memory join does. If
+/// it (and the hash table to be built over it) fits into the memory
+/// reservation, the regular [`HashJoinStream`] runs on the buffered batches.
+///
+/// Otherwise the join switches to spilling:
+///
+/// ```text
+///   build side                        probe side
+///       │                                  │
+///       ▼                                  ▼
+///  hash(keys) % N                    hash(keys) % N
+///   │    │    │                       │    │    │
+///   ▼    ▼    ▼                       ▼    ▼    ▼
+///  [0]  [1] .. [N-1]  spill files    [0]  [1] .. [N-1]
+///   │    │                            │    │
+///   └────┼────────── HashJoinStream ──┘    │
+///        └────────── HashJoinStream ───────┘
+///                         ...
+/// ```
+///
+/// 1. The buffered build batches, the rest of the build side and then the
+///    entire probe side are hash-partitioned on the join keys and written to
+///    `N` spill files per side.
+/// 2. Since rows with equal keys always land in the same spill partition,
+///    every join type (including outer, semi, anti and mark joins) can be
+///    evaluated independently for each pair of spill files. The pairs are
+///    joined one after another with a regular [`HashJoinStream`], so only
+///    the build side of a single spill partition is in memory at a time.
+/// 3. If the build side of a spill partition still does not fit, that pair
+///    is split again into `N` partitions using a different hash seed, up to
+///    [`MAX_SPILL_DEPTH`] times.
+///
+/// The probe side ordering is not preserved, which is why
+/// [`super::HashJoinExec`] only uses this stream when its probe side is unordered.
+///
+/// If the join produces a dynamic filter, the build side is reported to the
+/// [`SharedBuildAccumulator`] when it fits in memory. Otherwise the partition
+/// is reported as canceled as soon as the join starts spilling, so the filter
+/// lets all probe rows of this partition pass and the other partitions do not
+/// wait for it.
+pub(super) struct GraceHashJoin {
+    /// Output partition being executed
+    pub(super) partition: usize,
+    /// Output schema of the join
+    pub(super) schema: SchemaRef,
+    /// Join keys of the build side
+    pub(super) on_left: Vec<PhysicalExprRef>,
+    /// Join keys of the probe side
+    pub(super) on_right: Vec<PhysicalExprRef>,
+    /// Optional join filter
+    pub(super) filter: Option<JoinFilter>,
+    /// Type of the join
+    pub(super) join_type: JoinType,
+    /// Random state used by the join hash tables
+    pub(super) random_state: RandomState,
+    /// Output columns, after projection
+    pub(super) column_indices: Vec<ColumnIndex>,
+    /// Null equality of the join keys
+    pub(super) null_equality: NullEquality,
+    /// Maximum output batch size
+    pub(super) batch_size: usize,
+    /// Optional limit on the number of output rows
+    pub(super) fetch: Option<usize>,
+    /// Number of spill partitions each input is split into
+    pub(super) num_spill_partitions: usize,
+    /// Session configuration
+    pub(super) config: Arc<ConfigOptions>,
+    /// Join metrics
+    pub(super) join_metrics: BuildProbeJoinMetrics,
+    /// Number of perfect hash join maps created
+    pub(super) array_map_created_count: Count,
+    /// Spill manager for the build side
+    pub(super) left_spill_manager: SpillManager,
+    /// Spill manager for the probe side
+    pub(super) right_spill_manager: SpillManager,
+    /// Collects the build side of all partitions for the dynamic filter, if
+    /// any. Taken once the build side of this partition is reported.
+    pub(super) build_accumulator: Option<Arc<SharedBuildAccumulator>>,
+}
+
+impl Drop for GraceHashJoin {
+    fn drop(&mut self) {
+        // The build side was never reported, e.g. because the join spilled or
+        // failed, so the dynamic filter must not wait for this partition
+        if let Some(build_accumulator) = self.build_accumulator.take() {
+            build_accumulator.report_canceled_partition(self.partition);
+        }
+    }
+}
+
+/// A pair of build/probe spill files holding the rows of one spill partition
+struct SpilledPartition {
+    /// Build side rows, `None` if the partition has no build rows
+    left: Option<RefCountedTempFile>,
+    /// Probe side rows, `None` if the partition has no probe rows
+    right: Option<RefCountedTempFile>,
+    /// Number of times the rows of this partition were split
+    depth: usize,
+}
+
+impl GraceHashJoin {
+    /// Returns the output stream of the join.
+    ///
+    /// `reservation` tracks the memory used by the build side; it is also
+    /// used for the build side of each spill partition.
+    pub(super) fn execute(
+        self,
+        left: SendableRecordBatchStream,
+        right: SendableRecordBatchStream,
+        reservation: MemoryReservation,
+    ) -> SendableRecordBatchStream {
+        let schema = Arc::clone(&self.schema);
+        let stream = stream::once(self.start(left, right, reservation)).try_flatten();
+        Box::pin(RecordBatchStreamAdapter::new(schema, stream))
+    }
+
+    /// Buffers the build side, and either joins in memory or spills both
+    /// inputs and joins them partition by partition.
+    async fn start(
+        mut self,
+        mut left: SendableRecordBatchStream,
+        right: SendableRecordBatchStream,
+        reservation: MemoryReservation,
+    ) -> Result<SendableRecordBatchStream> {
+        let build_timer = self.join_metrics.build_time.timer();
+        let mut state =
+            self.new_build_state(reservation, self.build_accumulator.is_some())?;
+
+        // The batch that did not fit into memory, if any
+        let mut overflow = None;
+        while let Some(batch) = left.next().await.transpose()? {
+            match state.push_batch(&batch) {
+                Ok(()) => {}
+                Err(e) if is_resources_exhausted(&e) => {
+                    overflow = Some(batch);
+                    break;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        let fits_in_memory = match overflow {
+            Some(_) => false,
+            None => match state.try_reserve_hash_table() {
+                Ok(()) => true,
+                Err(e) if is_resources_exhausted(&e) => false,
+                Err(e) => return Err(e),
+            },
+        };
+
+        if fits_in_memory {
+            let left_data =
+                self.build_left_data(state, self.build_accumulator.is_some())?;
+            build_timer.done();
+            // The stream reports the build side to the accumulator
+            let build_accumulator = self.build_accumulator.take();
+            return Ok(self.join_partition(
+                left_data,
+                right,
+                self.fetch,
+                build_accumulator,
+            ));
+        }
+
+        // The spilled build side can not be used for the dynamic filter
+        if let Some(build_accumulator) = self.build_accumulator.take() {
+            build_accumulator.report_canceled_partition(self.partition);
+        }
+
+        // Spill everything seen so far, followed by the rest of the build side
+        let mut left_spill = SpillPartitioner::new(
+            &self.left_spill_manager,
+            &self.on_left,
+            self.num_spill_partitions,
+            0,
+        );
+        for batch in state.batches.drain(..) {
+            left_spill.push_batch(&batch)?;
+        }
+        state.reservation.free();
+        let reservation = state.reservation.take();
+        drop(state);
+        if let Some(batch) = overflow {
+            left_spill.push_batch(&batch)?;
+        }
+        while let Some(batch) = left.next().await.transpose()? {
+            left_spill.push_batch(&batch)?;
+        }
+        let left_files = left_spill.finish()?;
+        build_timer.done();
+
+        let right_files = SpillPartitioner::new(
+            &self.right_spill_manager,
+            &self.on_right,
+            self.num_spill_partitions,
+            0,
+        )
+        .spill_stream(right)
+        .await?;
+
+        let pending = left_files
+            .into_iter()
+            .zip(right_files)
+            .map(|(left, right)| SpilledPartition {
+                left,
+                right,
+                depth: 0,
+            })
+            .collect();
+
+        Ok(self.join_spilled_partitions(pending, reservation))
+    }
+
+    /// Joins the spilled partitions in `pending` one after another
+    fn join_spilled_partitions(
+        self,
+        pending: VecDeque<SpilledPartition>,
+        reservation: MemoryReservation,
+    ) -> SendableRecordBatchStream {
+        let schema = Arc::clone(&self.schema);
+        // Output rows produced so far, used to apply `fetch` across partitions
+        let produced = Arc::new(AtomicUsize::new(0));
+
+        let partitions = stream::try_unfold(
+            (self, pending, reservation, Arc::clone(&produced)),
+            |(join, mut pending, reservation, produced)| async move {
+                let fetch = join
+                    .fetch
+                    .map(|fetch| fetch.saturating_sub(produced.load(Ordering::Relaxed)));
+                if fetch == Some(0) {
+                    return Ok(None);
+                }
+
+                while let Some(partition) = pending.pop_front() {
+                    if join.can_skip(&partition) {
+                        continue;
+                    }
+
+                    let left_data = match join
+                        .load_partition(partition.left.clone(), reservation.new_empty())
+                        .await
+                    {
+                        Ok(left_data) => left_data,
+                        Err(e)
+                            if is_resources_exhausted(&e)
+                                && partition.depth < MAX_SPILL_DEPTH =>
+                        {
+                            // Split this partition again and process its parts
+                            // before the remaining partitions
+                            for split in
+                                join.split_partition(partition).await?.into_iter().rev()
+                            {
+                                pending.push_front(split);
+                            }
+                            continue;
+                        }
+                        Err(e) => return Err(e),
+                    };
+
+                    let right = match partition.right {
+                        Some(file) => {
+                            join.right_spill_manager.read_spill_as_stream(file, None)?
+                        }
+                        None => Box::pin(EmptyRecordBatchStream::new(Arc::clone(
+                            join.right_spill_manager.schema(),
+                        ))),
+                    };
+
+                    let stream = join.join_partition(left_data, right, fetch, None);
+                    return Ok(Some((stream, (join, pending, reservation, produced))));
+                }
+
+                Ok(None)
+            },
+        )
+        .try_flatten()
+        .inspect_ok(move |batch| {
+            produced.fetch_add(batch.num_rows(), Ordering::Relaxed);
+        });
+
+        Box::pin(RecordBatchStreamAdapter::new(schema, partitions))
+    }
+
+    /// Returns true if `partition` can not produce any output rows
+    fn can_skip(&self, partition: &SpilledPartition) -> bool {
+        (partition.left.is_none()
+            && self.join_type.empty_build_side_produces_empty_result())
+            || (partition.right.is_none()
+                && !need_produce_result_in_final(self.join_type))
+    }
+
+    /// Reads the build side of a spill partition into memory and builds its
+    /// hash table
+    async fn load_partition(
+        &self,
+        file: Option<RefCountedTempFile>,
+        reservation: MemoryReservation,
+    ) -> Result<JoinLeftData> {
+        let build_timer = self.join_metrics.build_time.timer();
+        let mut state = self.new_build_state(reservation, false)?;
+        if let Some(file) = file {
+            let mut stream = self.left_spill_manager.read_spill_as_stream(file, None)?;
+            while let Some(batch) = stream.next().await.transpose()? {
+                state.push_batch(&batch)?;
+            }
+        }
+        let left_data = self.build_left_data(state, false)?;
+        build_timer.done();
+        Ok(left_data)
+    }
+
+    /// Splits both sides of `partition` into `num_spill_partitions` smaller
+    /// partitions, using a hash seed that differs from the one of `partition`
+    async fn split_partition(
+        &self,
+        partition: SpilledPartition,
+    ) -> Result<Vec<SpilledPartition>> {
+        let depth = partition.depth + 1;
+        let left_files = self
+            .split_file(
+                &self.left_spill_manager,
+                &self.on_left,
+                partition.left,
+                depth,
+            )
+            .await?;
+        let right_files = self
+            .split_file(
+                &self.right_spill_manager,
+                &self.on_right,
+                partition.right,
+                depth,
+            )
+            .await?;
+
+        Ok(left_files
+            .into_iter()
+            .zip(right_files)
+            .map(|(left, right)| SpilledPartition { left, right, depth })
+            .collect())
+    }
+
+    /// Re-partitions the contents of a spill file at recursion level `depth`
+    async fn split_file(
+        &self,
+        spill_manager: &SpillManager,
+        on: &[PhysicalExprRef],
+        file: Option<RefCountedTempFile>,
+        depth: usize,
+    ) -> Result<Vec<Option<RefCountedTempFile>>> {
+        let partitioner =
+            SpillPartitioner::new(spill_manager, on, self.num_spill_partitions, depth);
+        match file {
+            Some(file) => {
+                partitioner
+                    .spill_stream(spill_manager.read_spill_as_stream(file, None)?)
+                    .await
+            }
+            None => partitioner.finish(),
+        }
+    }
+
+    /// Returns an empty build side state, collecting the bounds of the join
+    /// keys if `compute_dynamic_filters` is set
+    fn new_build_state(
+        &self,
+        reservation: MemoryReservation,
+        compute_dynamic_filters: bool,
+    ) -> Result<BuildSideState> {
+        BuildSideState::try_new(
+            self.join_metrics.clone(),
+            reservation,
+            self.on_left.clone(),
+            self.left_spill_manager.schema(),
+            compute_dynamic_filters
+                || should_collect_min_max_for_perfect_hash(
+                    &self.on_left,
+                    self.left_spill_manager.schema(),
+                )?,
+        )
+    }
+
+    fn build_left_data(
+        &self,
+        state: BuildSideState,
+        compute_dynamic_filters: bool,
+    ) -> Result<JoinLeftData> {
+        let schema = self.left_spill_manager.schema();
+        build_left_data(
+            state,
+            schema,
+            &self.random_state,
+            &self.on_left,
+            need_produce_result_in_final(self.join_type),
+            1,
+            compute_dynamic_filters,
+            should_collect_min_max_for_perfect_hash(&self.on_left, schema)?,
+            &self.config,
+            self.null_equality,
+            &self.array_map_created_count,
+        )
+    }
+
+    /// Creates a regular in-memory [`HashJoinStream`] probing `left_data` with
+    /// `right`, which reports `left_data` to `build_accumulator` if set
+    fn join_partition(
+        &self,
+        left_data: JoinLeftData,
+        right: SendableRecordBatchStream,
+        fetch: Option<usize>,
+        build_accumulator: Option<Arc<SharedBuildAccumulator>>,
+    ) -> SendableRecordBatchStream {
+        let left_fut = OnceFut::new(async move { Ok(left_data) });
+        Box::pin(HashJoinStream::new(
+            self.partition,
+            Arc::clone(&self.schema),
+            self.on_right.clone(),
+            self.filter.clone(),
+            self.join_type,
+            right,
+            self.random_state.clone(),
+            self.join_metrics.clone(),
+            self.column_indices.clone(),
+            self.null_equality,
+            HashJoinStreamState::WaitBuildSide,
+            BuildSide::Initial(BuildSideInitialState { left_fut }),
+            self.batch_size,
+            vec![],
+            false,
+            build_accumulator,
+            PartitionMode::Partitioned,
+            false,
+            fetch,
+        ))
+    }
+}
+
+/// Hash-partitions batches on their join keys and appends each part to the
+/// spill file of its partition
+struct SpillPartitioner<'a> {
+    spill_manager: &'a SpillManager,
+    on: &'a [PhysicalExprRef],
+    random_state: RandomState,
+    hashes_buffer: Vec<u64>,
+    /// Spill files, created when the first row of a partition arrives
+    files: Vec<Option<InProgressSpillFile>>,
+}
+
+impl<'a> SpillPartitioner<'a> {
+    fn new(
+        spill_manager: &'a SpillManager,
+        on: &'a [PhysicalExprRef],
+        num_partitions: usize,
+        depth: usize,
+    ) -> Self {
+        Self {
+            spill_manager,
+            on,
+            random_state: RandomState::with_seed(
+                SPILL_PARTITION_SEED.wrapping_add(depth as u64),
+            ),
+            hashes_buffer: vec![],
+            files: (0..num_partitions).map(|_| None).collect(),
+        }
+    }
+
+    fn push_batch(&mut self, batch: &RecordBatch) -> Result<()> {
+        let num_partitions = self.files.len();
+        let keys = evaluate_expressions_to_arrays(self.on, batch)?;
+        self.hashes_buffer.clear();
+        self.hashes_buffer.resize(batch.num_rows(), 0);
+        create_hashes(&keys, &self.random_state, &mut self.hashes_buffer)?;
+
+        let mut indices = vec![vec![]; num_partitions];
+        for (row, hash) in self.hashes_buffer.iter().enumerate() {
+            indices[(*hash % num_partitions as u64) as usize].push(row as u32);
+        }
+
+        for (partition, partition_indices) in indices.into_iter().enumerate() {
+            if partition_indices.is_empty() {
+                continue;
+            }
+            let partition_indices = UInt32Array::from(partition_indices);
+            let columns = take_arrays(batch.columns(), &partition_indices, None)?;
+            let options =
+                RecordBatchOptions::new().with_row_count(Some(partition_indices.len()));
+            let part =
+                RecordBatch::try_new_with_options(batch.schema(), columns, &options)?;
+
+            let file = match &mut self.files[partition] {
+                Some(file) => file,
+                file @ None => file.insert(
+                    self.spill_manager
+                        .create_in_progress_file("HashJoin spill partition")?,
+                ),
+            };
+            file.append_batch(&part)?;
+        }
+        Ok(())
+    }
+
+    /// Partitions and spills all batches of `stream`
+    async fn spill_stream(
+        mut self,
+        mut stream: SendableRecordBatchStream,
+    ) -> Result<Vec<Option<RefCountedTempFile>>> {
+        while let Some(batch) = stream.next().await.transpose()? {
+            self.push_batch(&batch)?;
+        }
+        self.finish()
+    }
+
+    /// Finishes all spill files, returning `None` for empty partitions
+    fn finish(self) -> Result<Vec<Option<RefCountedTempFile>>> {
+        self.files
+            .into_iter()
+            .map(|file| match file {
+                Some(mut file) => file.finish(),
+                None => Ok(None),
+            })
+            .collect()
+    }
+}
+
+fn is_resources_exhausted(e: &DataFusionError) -> bool {
+    matches!(e.find_root(), DataFusionError::ResourcesExhausted(_))
+}
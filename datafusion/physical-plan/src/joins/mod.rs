@@ -26,7 +26,7 @@ pub use hash_join::{
 pub use nested_loop_join::{NestedLoopJoinExec, NestedLoopJoinExecBuilder};
 use parking_lot::Mutex;
 // Note: SortMergeJoin is not used in plans yet
-pub use piecewise_merge_join::PiecewiseMergeJoinExec;
+pub use piecewise_merge_join::{AsOfJoinExec, PiecewiseMergeJoinExec};
 pub use sort_merge_join::SortMergeJoinExec;
 pub use symmetric_hash_join::SymmetricHashJoinExec;
 pub mod chain;
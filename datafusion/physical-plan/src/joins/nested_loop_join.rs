@@ -63,7 +63,8 @@ use datafusion_common::cast::as_boolean_array;
 use datafusion_common::tree_node::TreeNodeRecursion;
 use datafusion_common::{
     JoinSide, Result, ScalarValue, Statistics, arrow_err, assert_eq_or_internal_err,
-    internal_datafusion_err, internal_err, project_schema, unwrap_or_internal_err,
+    internal_datafusion_err, internal_err, plan_err, project_schema,
+    unwrap_or_internal_err,
 };
 use datafusion_execution::TaskContext;
 use datafusion_execution::disk_manager::RefCountedTempFile;
@@ -277,6 +278,12 @@ impl NestedLoopJoinExecBuilder {
             projection,
         } = self;
 
+        if join_type.is_asof() {
+            return plan_err!(
+                "NestedLoopJoinExec does not support {join_type} joins, use AsOfJoinExec instead"
+            );
+        }
+
         let left_schema = left.schema();
         let right_schema = right.schema();
         check_join_is_valid(&left_schema, &right_schema, &[])?;
@@ -397,6 +404,7 @@ impl NestedLoopJoinExec {
                 JoinType::Left
                 | JoinType::LeftAnti
                 | JoinType::LeftMark
+                | JoinType::LeftAsOf
                 | JoinType::Full => EmissionType::Both,
             }
         } else {
@@ -455,7 +463,7 @@ impl NestedLoopJoinExec {
             Arc::clone(right),
             Arc::clone(left),
             self.filter().map(JoinFilter::swap),
-            &self.join_type().swap(),
+            &self.join_type().try_swap()?,
             swap_join_projection(
                 left.schema().fields().len(),
                 right.schema().fields().len(),
@@ -0,0 +1,967 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the as-of join plan, see [`AsOfJoinExec`]
+
+use std::cmp::Ordering;
+use std::fmt::Formatter;
+use std::ops::Range;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use arrow::array::{Array, RecordBatch, RecordBatchOptions, UInt32Builder};
+use arrow::compute::take_arrays;
+use arrow::row::{RowConverter, Rows, SortField};
+use arrow_ord::ord::make_comparator;
+use arrow_schema::{SchemaRef, SortOptions};
+use datafusion_common::tree_node::TreeNodeRecursion;
+use datafusion_common::{
+    HashMap, JoinSide, JoinType, NullEquality, Result, assert_or_internal_err,
+    internal_err, plan_err,
+};
+use datafusion_execution::memory_pool::{MemoryConsumer, MemoryReservation};
+use datafusion_execution::{SendableRecordBatchStream, TaskContext};
+use datafusion_expr::Operator;
+use datafusion_physical_expr::equivalence::join_equivalence_properties;
+use datafusion_physical_expr::{
+    Distribution, LexOrdering, OrderingRequirements, PhysicalExpr, PhysicalExprRef,
+    PhysicalSortExpr,
+};
+use datafusion_physical_expr_common::physical_expr::fmt_sql;
+use futures::{Stream, StreamExt, ready};
+
+use crate::execution_plan::{EmissionType, boundedness_from_children};
+use crate::joins::JoinOn;
+use crate::joins::utils::{
+    BuildProbeJoinMetrics, OnceAsync, OnceFut, build_join_schema, check_join_is_valid,
+};
+use crate::metrics::{ExecutionPlanMetricsSet, MetricsSet};
+use crate::{
+    DisplayAs, DisplayFormatType, ExecutionPlan, ExecutionPlanProperties, PlanProperties,
+    RecordBatchStream, check_if_same_properties,
+};
+
+use super::exec::{BufferedSideData, build_buffered_data};
+
+/// `AsOfJoinExec` evaluates a [`JoinType::LeftAsOf`] join, such as
+///
+/// ```sql
+/// SELECT *
+/// FROM trades t ASOF JOIN quotes q
+///   MATCH_CONDITION (t.ts >= q.ts)
+///   ON t.symbol = q.symbol
+/// ```
+///
+/// Every row of the left input is joined with at most one row of the right
+/// input: among the right rows with equal join keys (`on`), the one closest to
+/// the left row that satisfies the match condition `left_expr <op> right_expr`.
+/// Left rows without a matching right row are joined with NULL values.
+///
+/// | Operator | Matching right row                                       |
+/// |----------|----------------------------------------------------------|
+/// | `>=`     | greatest `right_expr` less than or equal to `left_expr`  |
+/// | `>`      | greatest `right_expr` less than `left_expr`              |
+/// | `<=`     | smallest `right_expr` greater than or equal to `left_expr` |
+/// | `<`      | smallest `right_expr` greater than `left_expr`           |
+///
+/// # Algorithm
+///
+/// Like [`PiecewiseMergeJoinExec`], the right side is buffered and the left
+/// side is streamed. The right side is required to be sorted on the join keys
+/// followed by the match expression, so that the rows of each key form a range
+/// that is sorted on the match expression. For each streamed row, the range of
+/// its join key is looked up and binary searched for the closest match.
+///
+/// With join keys, both inputs are hash partitioned on the keys and each
+/// streamed partition is joined with the matching buffered partition. Without
+/// join keys, the buffered side is collected into a single partition and shared
+/// by all streamed partitions. The output preserves the order of the left input.
+///
+/// [`PiecewiseMergeJoinExec`]: super::PiecewiseMergeJoinExec
+#[derive(Debug)]
+pub struct AsOfJoinExec {
+    /// Left streamed execution plan
+    pub streamed: Arc<dyn ExecutionPlan>,
+    /// Right buffered execution plan
+    pub buffered: Arc<dyn ExecutionPlan>,
+    /// Equality join keys as (left, right) expressions
+    pub on: JoinOn,
+    /// The (left, right) expressions compared by the match condition
+    pub match_on: (PhysicalExprRef, PhysicalExprRef),
+    /// Comparison operator of the match condition
+    pub operator: Operator,
+    /// Defines the null equality for the join keys
+    null_equality: NullEquality,
+    /// The schema once the join is applied
+    schema: SchemaRef,
+    /// Buffered data shared by all streamed partitions when there are no
+    /// join keys
+    buffered_fut: OnceAsync<AsOfBufferedData>,
+    /// Execution metrics
+    metrics: ExecutionPlanMetricsSet,
+    /// The order required on the buffered side: the join keys, followed by
+    /// the match expression
+    buffered_required_order: LexOrdering,
+    /// Cache holding plan properties like equivalences, output partitioning etc.
+    cache: Arc<PlanProperties>,
+}
+
+impl AsOfJoinExec {
+    pub fn try_new(
+        streamed: Arc<dyn ExecutionPlan>,
+        buffered: Arc<dyn ExecutionPlan>,
+        on: JoinOn,
+        match_on: (PhysicalExprRef, PhysicalExprRef),
+        operator: Operator,
+        null_equality: NullEquality,
+    ) -> Result<Self> {
+        if !matches!(
+            operator,
+            Operator::Lt | Operator::LtEq | Operator::Gt | Operator::GtEq
+        ) {
+            return plan_err!(
+                "AsOfJoinExec match condition must use one of <, <=, >, >=, got {operator}"
+            );
+        }
+
+        let streamed_schema = streamed.schema();
+        let buffered_schema = buffered.schema();
+        check_join_is_valid(&streamed_schema, &buffered_schema, &on)?;
+
+        let buffered_required_order = on
+            .iter()
+            .map(|(_, r)| PhysicalSortExpr::new_default(Arc::clone(r)))
+            .chain([PhysicalSortExpr::new(
+                Arc::clone(&match_on.1),
+                SortOptions::new(false, true),
+            )]);
+        let Some(buffered_required_order) = LexOrdering::new(buffered_required_order)
+        else {
+            return internal_err!(
+                "AsOfJoinExec requires valid sort expressions for its right side"
+            );
+        };
+
+        let schema = Arc::new(
+            build_join_schema(&streamed_schema, &buffered_schema, &JoinType::LeftAsOf).0,
+        );
+        let cache =
+            Self::compute_properties(&streamed, &buffered, Arc::clone(&schema), &on)?;
+
+        Ok(Self {
+            streamed,
+            buffered,
+            on,
+            match_on,
+            operator,
+            null_equality,
+            schema,
+            buffered_fut: Default::default(),
+            metrics: ExecutionPlanMetricsSet::new(),
+            buffered_required_order,
+            cache: Arc::new(cache),
+        })
+    }
+
+    /// Reference to streamed side execution plan
+    pub fn streamed(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.streamed
+    }
+
+    /// Reference to buffered side execution plan
+    pub fn buffered(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.buffered
+    }
+
+    /// Equality join keys
+    pub fn on(&self) -> &[(PhysicalExprRef, PhysicalExprRef)] {
+        &self.on
+    }
+
+    /// Null equality of the join keys
+    pub fn null_equality(&self) -> NullEquality {
+        self.null_equality
+    }
+
+    fn compute_properties(
+        streamed: &Arc<dyn ExecutionPlan>,
+        buffered: &Arc<dyn ExecutionPlan>,
+        schema: SchemaRef,
+        on: &[(PhysicalExprRef, PhysicalExprRef)],
+    ) -> Result<PlanProperties> {
+        let eq_properties = join_equivalence_properties(
+            streamed.equivalence_properties().clone(),
+            buffered.equivalence_properties().clone(),
+            &JoinType::LeftAsOf,
+            schema,
+            &[true, false],
+            Some(JoinSide::Left),
+            on,
+        )?;
+
+        // The left columns come first in the output, so the partitioning of
+        // the streamed side is preserved
+        Ok(PlanProperties::new(
+            eq_properties,
+            streamed.output_partitioning().clone(),
+            EmissionType::Incremental,
+            boundedness_from_children([streamed, buffered]),
+        ))
+    }
+
+    fn with_new_children_and_same_properties(
+        &self,
+        mut children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Self {
+        let streamed = children.swap_remove(0);
+        let buffered = children.swap_remove(0);
+        Self {
+            streamed,
+            buffered,
+            on: self.on.clone(),
+            match_on: self.match_on.clone(),
+            operator: self.operator,
+            null_equality: self.null_equality,
+            schema: Arc::clone(&self.schema),
+            buffered_required_order: self.buffered_required_order.clone(),
+            cache: Arc::clone(&self.cache),
+
+            // Re-set state.
+            metrics: ExecutionPlanMetricsSet::new(),
+            buffered_fut: Default::default(),
+        }
+    }
+}
+
+impl ExecutionPlan for AsOfJoinExec {
+    fn name(&self) -> &str {
+        "AsOfJoinExec"
+    }
+
+    fn properties(&self) -> &Arc<PlanProperties> {
+        &self.cache
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
+        vec![&self.streamed, &self.buffered]
+    }
+
+    fn apply_expressions(
+        &self,
+        f: &mut dyn FnMut(&dyn PhysicalExpr) -> Result<TreeNodeRecursion>,
+    ) -> Result<TreeNodeRecursion> {
+        let mut tnr = TreeNodeRecursion::Continue;
+        for (left, right) in &self.on {
+            tnr = tnr.visit_sibling(|| f(left.as_ref()))?;
+            tnr = tnr.visit_sibling(|| f(right.as_ref()))?;
+        }
+        tnr.visit_sibling(|| f(self.match_on.0.as_ref()))?
+            .visit_sibling(|| f(self.match_on.1.as_ref()))
+    }
+
+    fn required_input_distribution(&self) -> Vec<Distribution> {
+        if self.on.is_empty() {
+            return vec![
+                Distribution::UnspecifiedDistribution,
+                Distribution::SinglePartition,
+            ];
+        }
+        let (streamed_expr, buffered_expr) = self
+            .on
+            .iter()
+            .map(|(l, r)| (Arc::clone(l), Arc::clone(r)))
+            .unzip();
+        vec![
+            Distribution::HashPartitioned(streamed_expr),
+            Distribution::HashPartitioned(buffered_expr),
+        ]
+    }
+
+    fn required_input_ordering(&self) -> Vec<Option<OrderingRequirements>> {
+        vec![
+            None,
+            Some(OrderingRequirements::from(
+                self.buffered_required_order.clone(),
+            )),
+        ]
+    }
+
+    fn maintains_input_order(&self) -> Vec<bool> {
+        vec![true, false]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        check_if_same_properties!(self, children);
+        match &children[..] {
+            [streamed, buffered] => Ok(Arc::new(AsOfJoinExec::try_new(
+                Arc::clone(streamed),
+                Arc::clone(buffered),
+                self.on.clone(),
+                self.match_on.clone(),
+                self.operator,
+                self.null_equality,
+            )?)),
+            _ => internal_err!(
+                "AsOfJoinExec should have 2 children, found {}",
+                children.len()
+            ),
+        }
+    }
+
+    fn reset_state(self: Arc<Self>) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(self.with_new_children_and_same_properties(vec![
+            Arc::clone(&self.streamed),
+            Arc::clone(&self.buffered),
+        ])))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let metrics = BuildProbeJoinMetrics::new(partition, &self.metrics);
+        let collect_buffered_partition = |buffered_partition| -> Result<_> {
+            let reservation = MemoryConsumer::new(format!("AsOfJoinInput[{partition}]"))
+                .register(context.memory_pool());
+            let buffered_stream = self
+                .buffered
+                .execute(buffered_partition, Arc::clone(&context))?;
+            Ok(collect_buffered_data(
+                buffered_stream,
+                self.on.iter().map(|(_, r)| Arc::clone(r)).collect(),
+                Arc::clone(&self.match_on.1),
+                self.null_equality,
+                metrics.clone(),
+                reservation,
+            ))
+        };
+        let buffered_fut = if self.on.is_empty() {
+            self.buffered_fut
+                .try_once(|| collect_buffered_partition(0))?
+        } else {
+            let streamed_partitions =
+                self.streamed.output_partitioning().partition_count();
+            let buffered_partitions =
+                self.buffered.output_partitioning().partition_count();
+            assert_or_internal_err!(
+                streamed_partitions == buffered_partitions,
+                "Invalid AsOfJoinExec, partition count mismatch {streamed_partitions}!={buffered_partitions},\
+                 consider using RepartitionExec"
+            );
+            OnceFut::new(collect_buffered_partition(partition)?)
+        };
+
+        let streamed = self.streamed.execute(partition, Arc::clone(&context))?;
+        Ok(Box::pin(AsOfJoinStream {
+            schema: Arc::clone(&self.schema),
+            streamed,
+            on_streamed: self.on.iter().map(|(l, _)| Arc::clone(l)).collect(),
+            match_streamed: Arc::clone(&self.match_on.0),
+            operator: self.operator,
+            null_equality: self.null_equality,
+            buffered_fut,
+            buffered_data: None,
+            join_metrics: metrics,
+        }))
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+}
+
+impl DisplayAs for AsOfJoinExec {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut Formatter) -> std::fmt::Result {
+        let match_condition = format!(
+            "({} {} {})",
+            fmt_sql(self.match_on.0.as_ref()),
+            self.operator,
+            fmt_sql(self.match_on.1.as_ref())
+        );
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                let on = self
+                    .on
+                    .iter()
+                    .map(|(c1, c2)| format!("({c1}, {c2})"))
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                let display_null_equality =
+                    if self.null_equality == NullEquality::NullEqualsNull {
+                        ", NullsEqual: true"
+                    } else {
+                        ""
+                    };
+                write!(
+                    f,
+                    "AsOfJoinExec: match_condition={match_condition}, on=[{on}]{display_null_equality}"
+                )
+            }
+            DisplayFormatType::TreeRender => {
+                let on = self
+                    .on
+                    .iter()
+                    .map(|(c1, c2)| {
+                        format!("({} = {})", fmt_sql(c1.as_ref()), fmt_sql(c2.as_ref()))
+                    })
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                writeln!(f, "match_condition={match_condition}")?;
+                if !self.on.is_empty() {
+                    writeln!(f, "on={on}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// The buffered side of an [`AsOfJoinExec`]
+struct AsOfBufferedData {
+    /// All buffered rows, sorted on the join keys followed by the match
+    /// values, and the values of the match expression
+    data: BufferedSideData,
+    /// Converts join keys into rows, `None` if there are no join keys
+    key_converter: Option<RowConverter>,
+    /// Rows of each join key, excluding rows with NULL match values
+    key_ranges: HashMap<Box<[u8]>, Range<usize>>,
+    _reservation: MemoryReservation,
+}
+
+impl AsOfBufferedData {
+    /// Returns the rows with the join key of row `idx` of `keys`
+    fn key_range(&self, keys: Option<&Rows>, idx: usize) -> Option<Range<usize>> {
+        match keys {
+            Some(keys) => self.key_ranges.get(keys.row(idx).as_ref()).cloned(),
+            // Without join keys, all rows with non-NULL match values are candidates
+            None => {
+                let num_rows = self.data.batch().num_rows();
+                let nulls = self.data.values().null_count();
+                (nulls < num_rows).then(|| nulls..num_rows)
+            }
+        }
+    }
+}
+
+async fn collect_buffered_data(
+    buffered: SendableRecordBatchStream,
+    on_buffered: Vec<PhysicalExprRef>,
+    match_buffered: PhysicalExprRef,
+    null_equality: NullEquality,
+    metrics: BuildProbeJoinMetrics,
+    reservation: MemoryReservation,
+) -> Result<AsOfBufferedData> {
+    let build_time = metrics.build_time.clone();
+    let build_timer = build_time.timer();
+
+    // The key ranges are accounted separately from the buffered rows
+    let key_reservation = reservation.new_empty();
+    let data = build_buffered_data(
+        buffered,
+        match_buffered,
+        metrics.clone(),
+        reservation,
+        false,
+        1,
+    )
+    .await?;
+    let (batch, values) = (data.batch(), data.values());
+    let num_rows = batch.num_rows();
+    let mut size_estimation = 0;
+
+    let mut key_ranges = HashMap::new();
+    let key_converter = if on_buffered.is_empty() {
+        None
+    } else {
+        let keys = on_buffered
+            .iter()
+            .map(|expr| expr.evaluate(batch)?.into_array(num_rows))
+            .collect::<Result<Vec<_>>>()?;
+        let converter = RowConverter::new(
+            keys.iter()
+                .map(|key| SortField::new(key.data_type().clone()))
+                .collect(),
+        )?;
+        let rows = converter.convert_columns(&keys)?;
+        size_estimation += rows.size();
+
+        // The rows are sorted on the join keys, so the rows of each key are
+        // contiguous and sorted on the match values, NULL values first.
+        let mut start = 0;
+        while start < num_rows {
+            let key = rows.row(start);
+            let mut end = start + 1;
+            while end < num_rows && rows.row(end) == key {
+                end += 1;
+            }
+            let has_null_key = keys.iter().any(|key| key.is_null(start));
+            let first_non_null = partition_point(start..end, |idx| values.is_null(idx));
+            if first_non_null < end
+                && (null_equality == NullEquality::NullEqualsNull || !has_null_key)
+            {
+                size_estimation += key.as_ref().len();
+                key_ranges.insert(Box::from(key.as_ref()), first_non_null..end);
+            }
+            start = end;
+        }
+        Some(converter)
+    };
+
+    key_reservation.try_grow(size_estimation)?;
+    metrics.build_mem_used.add(size_estimation);
+    build_timer.done();
+
+    Ok(AsOfBufferedData {
+        data,
+        key_converter,
+        key_ranges,
+        _reservation: key_reservation,
+    })
+}
+
+/// Returns the first index in `range` for which `pred` is false, assuming
+/// `pred` is true for all indices before it and false for all indices after.
+fn partition_point(range: Range<usize>, pred: impl Fn(usize) -> bool) -> usize {
+    let (mut low, mut high) = (range.start, range.end);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if pred(mid) {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    low
+}
+
+/// Streams the left input of an [`AsOfJoinExec`], joining each batch with the
+/// buffered right input
+struct AsOfJoinStream {
+    schema: SchemaRef,
+    streamed: SendableRecordBatchStream,
+    on_streamed: Vec<PhysicalExprRef>,
+    match_streamed: PhysicalExprRef,
+    operator: Operator,
+    null_equality: NullEquality,
+    buffered_fut: OnceFut<AsOfBufferedData>,
+    buffered_data: Option<Arc<AsOfBufferedData>>,
+    join_metrics: BuildProbeJoinMetrics,
+}
+
+impl AsOfJoinStream {
+    fn poll_next_impl(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<RecordBatch>>> {
+        let buffered_data = match &self.buffered_data {
+            Some(buffered_data) => Arc::clone(buffered_data),
+            None => {
+                let buffered_data = ready!(self.buffered_fut.get_shared(cx))?;
+                self.buffered_data = Some(Arc::clone(&buffered_data));
+                buffered_data
+            }
+        };
+
+        match ready!(self.streamed.poll_next_unpin(cx)) {
+            Some(Ok(batch)) => {
+                self.join_metrics.input_batches.add(1);
+                self.join_metrics.input_rows.add(batch.num_rows());
+                let timer = self.join_metrics.join_time.timer();
+                let result = self.join_batch(&buffered_data, &batch);
+                timer.done();
+                Poll::Ready(Some(result))
+            }
+            other => Poll::Ready(other),
+        }
+    }
+
+    /// Joins each row of `batch` with its closest match in `buffered_data`
+    fn join_batch(
+        &self,
+        buffered_data: &AsOfBufferedData,
+        batch: &RecordBatch,
+    ) -> Result<RecordBatch> {
+        let num_rows = batch.num_rows();
+        let values = self.match_streamed.evaluate(batch)?.into_array(num_rows)?;
+        let keys = self
+            .on_streamed
+            .iter()
+            .map(|expr| expr.evaluate(batch)?.into_array(num_rows))
+            .collect::<Result<Vec<_>>>()?;
+        let key_rows = buffered_data
+            .key_converter
+            .as_ref()
+            .map(|converter| converter.convert_columns(&keys))
+            .transpose()?;
+        // Compares a buffered match value with a streamed match value
+        let compare = make_comparator(
+            buffered_data.data.values().as_ref(),
+            values.as_ref(),
+            SortOptions::default(),
+        )?;
+
+        let mut indices = UInt32Builder::with_capacity(num_rows);
+        for idx in 0..num_rows {
+            let has_null_key = self.null_equality == NullEquality::NullEqualsNothing
+                && keys.iter().any(|key| key.is_null(idx));
+            let range = if values.is_null(idx) || has_null_key {
+                None
+            } else {
+                buffered_data.key_range(key_rows.as_ref(), idx)
+            };
+            let matched =
+                range.and_then(|range| {
+                    let (start, end) = (range.start, range.end);
+                    match self.operator {
+                        // The last buffered row with a value <= (or <) the streamed value
+                        Operator::GtEq | Operator::Gt => {
+                            let point = partition_point(range, |buffered_idx| {
+                                match compare(buffered_idx, idx) {
+                                    Ordering::Less => true,
+                                    Ordering::Equal => self.operator == Operator::GtEq,
+                                    Ordering::Greater => false,
+                                }
+                            });
+                            (point > start).then(|| point - 1)
+                        }
+                        // The first buffered row with a value >= (or >) the streamed value
+                        _ => {
+                            let point = partition_point(range, |buffered_idx| {
+                                match compare(buffered_idx, idx) {
+                                    Ordering::Less => true,
+                                    Ordering::Equal => self.operator == Operator::Lt,
+                                    Ordering::Greater => false,
+                                }
+                            });
+                            (point < end).then_some(point)
+                        }
+                    }
+                });
+            indices.append_option(matched.map(|matched| matched as u32));
+        }
+        let indices = indices.finish();
+
+        let buffered_columns =
+            take_arrays(buffered_data.data.batch().columns(), &indices, None)?;
+        let columns = batch
+            .columns()
+            .iter()
+            .cloned()
+            .chain(buffered_columns)
+            .collect();
+        let options = RecordBatchOptions::new().with_row_count(Some(num_rows));
+        Ok(RecordBatch::try_new_with_options(
+            Arc::clone(&self.schema),
+            columns,
+            &options,
+        )?)
+    }
+}
+
+impl Stream for AsOfJoinStream {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let poll = self.poll_next_impl(cx);
+        self.join_metrics.baseline.record_poll(poll)
+    }
+}
+
+impl RecordBatchStream for AsOfJoinStream {
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.schema)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common;
+    use crate::test::{TestMemoryExec, build_table_i32};
+    use arrow::array::Int32Array;
+    use arrow::compute::concat_batches;
+    use arrow_schema::{DataType, Field, Schema};
+    use datafusion_common::assert_contains;
+    use datafusion_common::test_util::batches_to_string;
+    use datafusion_physical_expr::expressions::Column;
+    use insta::assert_snapshot;
+
+    fn build_table(
+        a: (&str, &Vec<i32>),
+        b: (&str, &Vec<i32>),
+        c: (&str, &Vec<i32>),
+    ) -> Arc<dyn ExecutionPlan> {
+        let batch = build_table_i32(a, b, c);
+        let schema = batch.schema();
+        TestMemoryExec::try_new_exec(&[vec![batch]], schema, None).unwrap()
+    }
+
+    fn build_nullable_table(
+        a: (&str, &Vec<Option<i32>>),
+        b: (&str, &Vec<Option<i32>>),
+    ) -> Arc<dyn ExecutionPlan> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new(a.0, DataType::Int32, true),
+            Field::new(b.0, DataType::Int32, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            Arc::clone(&schema),
+            vec![
+                Arc::new(Int32Array::from(a.1.clone())),
+                Arc::new(Int32Array::from(b.1.clone())),
+            ],
+        )
+        .unwrap();
+        TestMemoryExec::try_new_exec(&[vec![batch]], schema, None).unwrap()
+    }
+
+    fn col(name: &str, plan: &Arc<dyn ExecutionPlan>) -> PhysicalExprRef {
+        Arc::new(Column::new_with_schema(name, &plan.schema()).unwrap())
+    }
+
+    async fn join_collect(
+        left: Arc<dyn ExecutionPlan>,
+        right: Arc<dyn ExecutionPlan>,
+        on: JoinOn,
+        match_on: (&str, &str),
+        operator: Operator,
+    ) -> Result<Vec<RecordBatch>> {
+        let match_on = (col(match_on.0, &left), col(match_on.1, &right));
+        let join = AsOfJoinExec::try_new(
+            left,
+            right,
+            on,
+            match_on,
+            operator,
+            NullEquality::NullEqualsNothing,
+        )?;
+        let stream = join.execute(0, Arc::new(TaskContext::default()))?;
+        common::collect(stream).await
+    }
+
+    #[tokio::test]
+    async fn asof_join_with_keys() -> Result<()> {
+        // trades
+        let left = build_table(
+            ("sym1", &vec![1, 2, 1, 1, 3]),
+            ("ts1", &vec![5, 5, 10, 1, 7]),
+            ("qty", &vec![100, 200, 300, 400, 500]),
+        );
+        // quotes, sorted on (sym2, ts2)
+        let right = build_table(
+            ("sym2", &vec![1, 1, 1, 2, 2]),
+            ("ts2", &vec![2, 4, 9, 5, 6]),
+            ("price", &vec![10, 11, 12, 20, 21]),
+        );
+        let on = vec![(col("sym1", &left), col("sym2", &right))];
+
+        let batches =
+            join_collect(left, right, on, ("ts1", "ts2"), Operator::GtEq).await?;
+        assert_snapshot!(batches_to_string(&batches), @r"
+        +------+-----+-----+------+-----+-------+
+        | sym1 | ts1 | qty | sym2 | ts2 | price |
+        +------+-----+-----+------+-----+-------+
+        | 1    | 5   | 100 | 1    | 4   | 11    |
+        | 2    | 5   | 200 | 2    | 5   | 20    |
+        | 1    | 10  | 300 | 1    | 9   | 12    |
+        | 1    | 1   | 400 |      |     |       |
+        | 3    | 7   | 500 |      |     |       |
+        +------+-----+-----+------+-----+-------+
+        ");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn asof_join_operators() -> Result<()> {
+        let left = build_table(
+            ("a1", &vec![1, 2, 3, 4, 5]),
+            ("b1", &vec![0, 2, 3, 6, 7]),
+            ("c1", &vec![0, 0, 0, 0, 0]),
+        );
+        let right = build_table(
+            ("a2", &vec![1, 1, 1]),
+            ("b2", &vec![2, 4, 6]),
+            ("c2", &vec![20, 40, 60]),
+        );
+
+        let mut results = vec![];
+        for operator in [Operator::GtEq, Operator::Gt, Operator::LtEq, Operator::Lt] {
+            let batches = join_collect(
+                Arc::clone(&left),
+                Arc::clone(&right),
+                vec![],
+                ("b1", "b2"),
+                operator,
+            )
+            .await?;
+            let batch = concat_batches(&batches[0].schema(), &batches)?;
+            let matched = batch
+                .column_by_name("c2")
+                .unwrap()
+                .as_any()
+                .downcast_ref::<Int32Array>()
+                .unwrap()
+                .iter()
+                .collect::<Vec<_>>();
+            results.push((operator, matched));
+        }
+
+        assert_eq!(
+            results,
+            vec![
+                (
+                    Operator::GtEq,
+                    vec![None, Some(20), Some(20), Some(60), Some(60)]
+                ),
+                (Operator::Gt, vec![None, None, Some(20), Some(40), Some(60)]),
+                (
+                    Operator::LtEq,
+                    vec![Some(20), Some(20), Some(40), Some(60), None]
+                ),
+                (Operator::Lt, vec![Some(20), Some(40), Some(40), None, None]),
+            ]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn asof_join_nulls() -> Result<()> {
+        let left = build_nullable_table(
+            ("k1", &vec![Some(1), None, Some(1), Some(2)]),
+            ("t1", &vec![Some(5), Some(5), None, Some(5)]),
+        );
+        // sorted on (k2, t2) with NULLs first
+        let right = build_nullable_table(
+            ("k2", &vec![None, Some(1), Some(1), Some(2)]),
+            ("t2", &vec![Some(1), None, Some(3), None]),
+        );
+        let on = vec![(col("k1", &left), col("k2", &right))];
+
+        let batches = join_collect(left, right, on, ("t1", "t2"), Operator::GtEq).await?;
+        assert_snapshot!(batches_to_string(&batches), @r"
+        +----+----+----+----+
+        | k1 | t1 | k2 | t2 |
+        +----+----+----+----+
+        | 1  | 5  | 1  | 3  |
+        |    | 5  |    |    |
+        | 1  |    |    |    |
+        | 2  | 5  |    |    |
+        +----+----+----+----+
+        ");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn asof_join_partitioned_on_keys() -> Result<()> {
+        // trades, hash partitioned on sym1
+        let left_partitions = [
+            build_table_i32(
+                ("sym1", &vec![1, 1]),
+                ("ts1", &vec![5, 10]),
+                ("qty", &vec![100, 300]),
+            ),
+            build_table_i32(
+                ("sym1", &vec![2, 3]),
+                ("ts1", &vec![5, 7]),
+                ("qty", &vec![200, 500]),
+            ),
+        ];
+        // quotes, hash partitioned on sym2 and sorted on (sym2, ts2)
+        let right_partitions = [
+            build_table_i32(
+                ("sym2", &vec![1, 1, 1]),
+                ("ts2", &vec![2, 4, 9]),
+                ("price", &vec![10, 11, 12]),
+            ),
+            build_table_i32(
+                ("sym2", &vec![2, 2]),
+                ("ts2", &vec![5, 6]),
+                ("price", &vec![20, 21]),
+            ),
+        ];
+        let left: Arc<dyn ExecutionPlan> = TestMemoryExec::try_new_exec(
+            &left_partitions.clone().map(|batch| vec![batch]),
+            left_partitions[0].schema(),
+            None,
+        )?;
+        let right: Arc<dyn ExecutionPlan> = TestMemoryExec::try_new_exec(
+            &right_partitions.clone().map(|batch| vec![batch]),
+            right_partitions[0].schema(),
+            None,
+        )?;
+        let on = vec![(col("sym1", &left), col("sym2", &right))];
+        let match_on = (col("ts1", &left), col("ts2", &right));
+        let join = AsOfJoinExec::try_new(
+            left,
+            right,
+            on,
+            match_on,
+            Operator::GtEq,
+            NullEquality::NullEqualsNothing,
+        )?;
+        assert!(matches!(
+            join.required_input_distribution()[..],
+            [
+                Distribution::HashPartitioned(_),
+                Distribution::HashPartitioned(_)
+            ]
+        ));
+
+        let context = Arc::new(TaskContext::default());
+        let mut batches = vec![];
+        for partition in 0..2 {
+            let stream = join.execute(partition, Arc::clone(&context))?;
+            batches.extend(common::collect(stream).await?);
+        }
+        assert_snapshot!(batches_to_string(&batches), @r"
+        +------+-----+-----+------+-----+-------+
+        | sym1 | ts1 | qty | sym2 | ts2 | price |
+        +------+-----+-----+------+-----+-------+
+        | 1    | 5   | 100 | 1    | 4   | 11    |
+        | 1    | 10  | 300 | 1    | 9   | 12    |
+        | 2    | 5   | 200 | 2    | 5   | 20    |
+        | 3    | 7   | 500 |      |     |       |
+        +------+-----+-----+------+-----+-------+
+        ");
+        Ok(())
+    }
+
+    #[test]
+    fn asof_join_rejects_equality_match_condition() {
+        let left = build_table(("a1", &vec![1]), ("b1", &vec![1]), ("c1", &vec![1]));
+        let right = build_table(("a2", &vec![1]), ("b2", &vec![1]), ("c2", &vec![1]));
+        let match_on = (col("b1", &left), col("b2", &right));
+        let err = AsOfJoinExec::try_new(
+            left,
+            right,
+            vec![],
+            match_on,
+            Operator::Eq,
+            NullEquality::NullEqualsNothing,
+        )
+        .unwrap_err();
+        assert_contains!(err.to_string(), "must use one of <, <=, >, >=");
+    }
+}
@@ -24,7 +24,7 @@ use arrow::{
 use arrow_schema::{SchemaRef, SortOptions};
 use datafusion_common::not_impl_err;
 use datafusion_common::tree_node::TreeNodeRecursion;
-use datafusion_common::{JoinSide, Result, internal_err};
+use datafusion_common::{JoinSide, Result, internal_err, plan_err};
 use datafusion_execution::{
     SendableRecordBatchStream,
     memory_pool::{MemoryConsumer, MemoryReservation},
@@ -295,6 +295,12 @@ impl PiecewiseMergeJoinExec {
         join_type: JoinType,
         num_partitions: usize,
     ) -> Result<Self> {
+        if join_type.is_asof() {
+            return plan_err!(
+                "PiecewiseMergeJoinExec does not support {join_type} joins, use AsOfJoinExec instead"
+            );
+        }
+
         // TODO: Implement existence joins for PiecewiseMergeJoin
         if is_existence_join(join_type) {
             return not_impl_err!(
@@ -413,7 +419,8 @@ impl PiecewiseMergeJoinExec {
             JoinType::Left
             | JoinType::LeftAnti
             | JoinType::LeftSemi
-            | JoinType::LeftMark => JoinSide::Left,
+            | JoinType::LeftMark
+            | JoinType::LeftAsOf => JoinSide::Left,
         }
     }
 
@@ -647,7 +654,7 @@ impl DisplayAs for PiecewiseMergeJoinExec {
     }
 }
 
-async fn build_buffered_data(
+pub(super) async fn build_buffered_data(
     buffered: SendableRecordBatchStream,
     on_buffered: PhysicalExprRef,
     metrics: BuildProbeJoinMetrics,
@@ -17,8 +17,10 @@
 
 //! PiecewiseMergeJoin is currently experimental
 
+pub use asof_join::AsOfJoinExec;
 pub use exec::PiecewiseMergeJoinExec;
 
+mod asof_join;
 mod classic_join;
 mod exec;
 mod utils;
@@ -290,6 +290,9 @@ pub fn get_corrected_filter_mask(
         | JoinType::RightAnti => {
             unreachable!("Semi/anti/mark joins are handled by BitwiseSortMergeJoinStream")
         }
+        JoinType::LeftAsOf => {
+            unreachable!("SortMergeJoinExec does not support ASOF joins")
+        }
         JoinType::Inner => None,
     }
 }
@@ -383,6 +386,9 @@ pub fn filter_record_batch_by_join_type(
         | JoinType::RightMark => unreachable!(
             "Semi/anti/mark joins are handled by SemiAntiMarkSortMergeJoinStream"
         ),
+        JoinType::LeftAsOf => {
+            unreachable!("SortMergeJoinExec does not support ASOF joins")
+        }
         JoinType::Inner => Ok(filter_record_batch(record_batch, corrected_mask)?),
     }
 }
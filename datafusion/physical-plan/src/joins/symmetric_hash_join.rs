@@ -228,6 +228,11 @@ impl SymmetricHashJoinExec {
                 "On constraints in SymmetricHashJoinExec should be non-empty"
             );
         }
+        if join_type.is_asof() {
+            return plan_err!(
+                "SymmetricHashJoinExec does not support {join_type} joins, use AsOfJoinExec instead"
+            );
+        }
 
         // Check if the join is valid with the given on constraints:
         check_join_is_valid(&left_schema, &right_schema, &on)?;
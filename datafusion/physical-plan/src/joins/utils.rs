@@ -230,7 +230,7 @@ pub struct ColumnIndex {
 fn output_join_field(old_field: &Field, join_type: &JoinType, is_left: bool) -> Field {
     let force_nullable = match join_type {
         JoinType::Inner => false,
-        JoinType::Left => !is_left, // right input is padded with nulls
+        JoinType::Left | JoinType::LeftAsOf => !is_left, // right input is padded with nulls
         JoinType::Right => is_left, // left input is padded with nulls
         JoinType::Full => true,     // both inputs can be padded with nulls
         JoinType::LeftSemi => false, // doesn't introduce nulls
@@ -289,7 +289,11 @@ pub fn build_join_schema(
     };
 
     let (fields, column_indices): (SchemaBuilder, Vec<ColumnIndex>) = match join_type {
-        JoinType::Inner | JoinType::Left | JoinType::Full | JoinType::Right => {
+        JoinType::Inner
+        | JoinType::Left
+        | JoinType::Full
+        | JoinType::Right
+        | JoinType::LeftAsOf => {
             // left then right
             left_fields().chain(right_fields()).unzip()
         }
@@ -595,6 +599,15 @@ fn estimate_join_cardinality(
                 column_statistics,
             })
         }
+        // Each left row is joined with at most one right row
+        JoinType::LeftAsOf => Some(PartialJoinStatistics {
+            num_rows: *left_stats.num_rows.get_value()?,
+            column_statistics: left_stats
+                .column_statistics
+                .into_iter()
+                .chain(right_stats.column_statistics)
+                .collect(),
+        }),
     }
 }
 
@@ -1222,7 +1235,7 @@ pub(crate) fn adjust_indices_by_join_type(
             // matched
             Ok((left_indices, right_indices))
         }
-        JoinType::Left => {
+        JoinType::Left | JoinType::LeftAsOf => {
             // matched
             Ok((left_indices, right_indices))
             // unmatched left row will be produced in the end of loop, and it has been set in the left visited bitmap
@@ -1623,9 +1636,11 @@ pub(crate) fn symmetric_join_output_partitioning(
     let left_partitioning = left.output_partitioning();
     let right_partitioning = right.output_partitioning();
     let result = match join_type {
-        JoinType::Left | JoinType::LeftSemi | JoinType::LeftAnti | JoinType::LeftMark => {
-            left_partitioning.clone()
-        }
+        JoinType::Left
+        | JoinType::LeftSemi
+        | JoinType::LeftAnti
+        | JoinType::LeftMark
+        | JoinType::LeftAsOf => left_partitioning.clone(),
         JoinType::RightSemi | JoinType::RightAnti | JoinType::RightMark => {
             right_partitioning.clone()
         }
@@ -1657,7 +1672,8 @@ pub(crate) fn asymmetric_join_output_partitioning(
         | JoinType::LeftSemi
         | JoinType::LeftAnti
         | JoinType::Full
-        | JoinType::LeftMark => Partitioning::UnknownPartitioning(
+        | JoinType::LeftMark
+        | JoinType::LeftAsOf => Partitioning::UnknownPartitioning(
             right.output_partitioning().partition_count(),
         ),
     };
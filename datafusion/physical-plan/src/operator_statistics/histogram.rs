@@ -0,0 +1,1047 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Histogram and most-common-values statistics extensions.
+//!
+//! [`ColumnDistributions`] is an [`ExtendedStatistics`] extension describing
+//! the value distribution of each column with an equi-depth [`Histogram`] and
+//! a list of most common values. [`HistogramStatisticsProvider`] uses it to
+//! estimate the selectivity of range and equality predicates and the
+//! cardinality of equi-joins, which min/max intervals badly misjudge for
+//! skewed columns.
+//!
+//! Distributions enter the registry walk at the leaves: data sources attach
+//! them to the statistics of their scans, and the provider propagates them
+//! through projections (cardinality preserving operators keep them via
+//! [`PassthroughStatisticsProvider`]).
+//!
+//! [`PassthroughStatisticsProvider`]: super::PassthroughStatisticsProvider
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use arrow::array::ArrayRef;
+use arrow::compute::{SortOptions, sort};
+use arrow::datatypes::DataType;
+use datafusion_common::stats::Precision;
+use datafusion_common::{Result, ScalarValue, Statistics, plan_err};
+use datafusion_expr::Operator;
+use datafusion_physical_expr::PhysicalExpr;
+use datafusion_physical_expr::expressions::{
+    BinaryExpr, Column, InListExpr, IsNotNullExpr, IsNullExpr, Literal, NotExpr,
+};
+
+use super::{
+    ExtendedStatistics, StatisticsProvider, StatisticsResult, bound_join_cardinality,
+    computed_with_row_count, ndv_after_selectivity, rescale_byte_size,
+};
+use crate::ExecutionPlan;
+use crate::filter::FilterExec;
+use crate::joins::{HashJoinExec, JoinOnRef, SortMergeJoinExec};
+use crate::projection::ProjectionExec;
+
+/// A bucket of a [`Histogram`], covering the values in `[lower, upper]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistogramBucket {
+    /// Smallest value in the bucket
+    pub lower: ScalarValue,
+    /// Largest value in the bucket
+    pub upper: ScalarValue,
+    /// Number of rows with a value in the bucket
+    pub num_rows: usize,
+    /// Number of distinct values in the bucket
+    pub distinct_count: usize,
+}
+
+/// Equi-depth histogram of the non-null values of a column.
+///
+/// Each bucket holds roughly the same number of rows, so skewed value ranges
+/// are described by narrow buckets. Buckets are sorted and do not overlap:
+/// all rows with the same value fall into the same bucket.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram {
+    buckets: Vec<HistogramBucket>,
+}
+
+impl Histogram {
+    /// Create a histogram from buckets, which must be sorted and must not
+    /// overlap.
+    pub fn try_new(buckets: Vec<HistogramBucket>) -> Result<Self> {
+        for bucket in &buckets {
+            if !matches!(
+                bucket.lower.partial_cmp(&bucket.upper),
+                Some(Ordering::Less | Ordering::Equal)
+            ) {
+                return plan_err!(
+                    "Histogram bucket lower bound {} must not be greater than its upper bound {}",
+                    bucket.lower,
+                    bucket.upper
+                );
+            }
+        }
+        for pair in buckets.windows(2) {
+            if !matches!(
+                pair[0].upper.partial_cmp(&pair[1].lower),
+                Some(Ordering::Less)
+            ) {
+                return plan_err!(
+                    "Histogram buckets must be sorted and must not overlap, but {} is followed by {}",
+                    pair[0].upper,
+                    pair[1].lower
+                );
+            }
+        }
+        Ok(Self { buckets })
+    }
+
+    /// Build an equi-depth histogram with at most `num_buckets` buckets from
+    /// the values of an array.
+    ///
+    /// Returns `None` if the array has no non-null values.
+    pub fn try_from_array(values: &ArrayRef, num_buckets: usize) -> Result<Option<Self>> {
+        let non_null = values.len() - values.null_count();
+        if non_null == 0 || num_buckets == 0 {
+            return Ok(None);
+        }
+        let sorted = sort(
+            values,
+            Some(SortOptions {
+                descending: false,
+                nulls_first: false,
+            }),
+        )?;
+
+        let rows_per_bucket = non_null.div_ceil(num_buckets);
+        let mut buckets: Vec<HistogramBucket> = vec![];
+        for index in 0..non_null {
+            let value = ScalarValue::try_from_array(&sorted, index)?;
+            match buckets.last_mut() {
+                // rows with the same value stay in the same bucket
+                Some(bucket) if bucket.upper == value => bucket.num_rows += 1,
+                Some(bucket) if bucket.num_rows < rows_per_bucket => {
+                    bucket.upper = value;
+                    bucket.num_rows += 1;
+                    bucket.distinct_count += 1;
+                }
+                _ => buckets.push(HistogramBucket {
+                    lower: value.clone(),
+                    upper: value,
+                    num_rows: 1,
+                    distinct_count: 1,
+                }),
+            }
+        }
+        Ok(Some(Self { buckets }))
+    }
+
+    /// Returns the buckets of the histogram
+    pub fn buckets(&self) -> &[HistogramBucket] {
+        &self.buckets
+    }
+
+    /// Total number of rows described by the histogram
+    pub fn num_rows(&self) -> usize {
+        self.buckets.iter().map(|bucket| bucket.num_rows).sum()
+    }
+
+    /// Total number of distinct values described by the histogram
+    pub fn distinct_count(&self) -> usize {
+        self.buckets
+            .iter()
+            .map(|bucket| bucket.distinct_count)
+            .sum()
+    }
+
+    /// Returns the bucket containing `value`, if any
+    fn bucket_of(&self, value: &ScalarValue) -> Option<&HistogramBucket> {
+        self.buckets.iter().find(|bucket| {
+            matches!(
+                bucket.lower.partial_cmp(value),
+                Some(Ordering::Less | Ordering::Equal)
+            ) && matches!(
+                bucket.upper.partial_cmp(value),
+                Some(Ordering::Greater | Ordering::Equal)
+            )
+        })
+    }
+
+    /// Estimates the number of rows with a value less than `value`,
+    /// interpolating linearly within the bucket containing it.
+    ///
+    /// Returns `None` if `value` can not be compared to the bucket bounds.
+    fn rows_less_than(&self, value: &ScalarValue) -> Option<f64> {
+        let mut rows = 0.0;
+        for bucket in &self.buckets {
+            match (
+                bucket.lower.partial_cmp(value)?,
+                bucket.upper.partial_cmp(value)?,
+            ) {
+                (_, Ordering::Less) => rows += bucket.num_rows as f64,
+                (Ordering::Less, _) => {
+                    let fraction =
+                        interpolate(&bucket.lower, &bucket.upper, value).unwrap_or(0.5);
+                    rows += bucket.num_rows as f64 * fraction;
+                }
+                _ => break,
+            }
+        }
+        Some(rows)
+    }
+}
+
+/// Returns the position of `value` within `[lower, upper]` as a fraction, for
+/// types that can be converted to numbers
+fn interpolate(
+    lower: &ScalarValue,
+    upper: &ScalarValue,
+    value: &ScalarValue,
+) -> Option<f64> {
+    let lower = scalar_to_f64(lower)?;
+    let upper = scalar_to_f64(upper)?;
+    let value = scalar_to_f64(value)?;
+    (upper > lower).then(|| ((value - lower) / (upper - lower)).clamp(0.0, 1.0))
+}
+
+fn scalar_to_f64(value: &ScalarValue) -> Option<f64> {
+    let value = if value.data_type().is_temporal() {
+        value.cast_to(&DataType::Int64).ok()?
+    } else {
+        value.clone()
+    };
+    match value.cast_to(&DataType::Float64).ok()? {
+        ScalarValue::Float64(Some(value)) if value.is_finite() => Some(value),
+        _ => None,
+    }
+}
+
+/// Value distribution of a single column: an optional equi-depth
+/// [`Histogram`] of its non-null values and its most common values along
+/// with their number of rows.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnDistribution {
+    num_rows: usize,
+    null_count: usize,
+    distinct_count: Option<usize>,
+    histogram: Option<Histogram>,
+    most_common_values: Vec<(ScalarValue, usize)>,
+}
+
+impl ColumnDistribution {
+    /// Create a new column distribution.
+    ///
+    /// `num_rows` includes nulls. `distinct_count` is the number of distinct
+    /// non-null values, which defaults to the distinct count of the
+    /// histogram. The histogram, if any, describes all non-null values,
+    /// including the most common ones.
+    pub fn try_new(
+        num_rows: usize,
+        null_count: usize,
+        distinct_count: Option<usize>,
+        histogram: Option<Histogram>,
+        most_common_values: Vec<(ScalarValue, usize)>,
+    ) -> Result<Self> {
+        let non_null = num_rows.saturating_sub(null_count);
+        if null_count > num_rows {
+            return plan_err!(
+                "Column distribution has {null_count} nulls but only {num_rows} rows"
+            );
+        }
+        if let Some(histogram) = &histogram
+            && histogram.num_rows() > non_null
+        {
+            return plan_err!(
+                "Histogram describes {} rows but the column only has {non_null} non-null rows",
+                histogram.num_rows()
+            );
+        }
+        let common_rows: usize = most_common_values.iter().map(|(_, rows)| rows).sum();
+        if common_rows > non_null {
+            return plan_err!(
+                "Most common values describe {common_rows} rows but the column only has {non_null} non-null rows"
+            );
+        }
+        let distinct_count =
+            distinct_count.or_else(|| histogram.as_ref().map(Histogram::distinct_count));
+        Ok(Self {
+            num_rows,
+            null_count,
+            distinct_count,
+            histogram,
+            most_common_values,
+        })
+    }
+
+    /// Compute the distribution of the values of an array, with an equi-depth
+    /// histogram of at most `num_buckets` buckets and at most
+    /// `max_most_common_values` most common values.
+    ///
+    /// Only values that are more common than the average value are kept as
+    /// most common values.
+    pub fn try_from_array(
+        values: &ArrayRef,
+        num_buckets: usize,
+        max_most_common_values: usize,
+    ) -> Result<Self> {
+        let histogram = Histogram::try_from_array(values, num_buckets)?;
+        let null_count = values.null_count();
+        let non_null = values.len() - null_count;
+
+        let mut counts: HashMap<ScalarValue, usize> = HashMap::new();
+        for index in 0..values.len() {
+            if values.is_valid(index) {
+                let value = ScalarValue::try_from_array(values, index)?;
+                *counts.entry(value).or_default() += 1;
+            }
+        }
+        let distinct_count = counts.len();
+        let average = non_null as f64 / distinct_count.max(1) as f64;
+        let mut most_common_values = counts
+            .into_iter()
+            .filter(|(_, rows)| *rows as f64 > average)
+            .collect::<Vec<_>>();
+        most_common_values.sort_by(|(value_a, rows_a), (value_b, rows_b)| {
+            rows_b
+                .cmp(rows_a)
+                .then_with(|| value_a.partial_cmp(value_b).unwrap_or(Ordering::Equal))
+        });
+        most_common_values.truncate(max_most_common_values);
+
+        Self::try_new(
+            values.len(),
+            null_count,
+            Some(distinct_count),
+            histogram,
+            most_common_values,
+        )
+    }
+
+    /// Number of rows, including nulls
+    pub fn num_rows(&self) -> usize {
+        self.num_rows
+    }
+
+    /// Number of null rows
+    pub fn null_count(&self) -> usize {
+        self.null_count
+    }
+
+    /// Number of distinct non-null values, if known
+    pub fn distinct_count(&self) -> Option<usize> {
+        self.distinct_count
+    }
+
+    /// The equi-depth histogram of the non-null values, if any
+    pub fn histogram(&self) -> Option<&Histogram> {
+        self.histogram.as_ref()
+    }
+
+    /// The most common values and their number of rows
+    pub fn most_common_values(&self) -> &[(ScalarValue, usize)] {
+        &self.most_common_values
+    }
+
+    fn non_null_rows(&self) -> usize {
+        self.num_rows - self.null_count
+    }
+
+    fn fraction(&self, rows: f64) -> f64 {
+        if self.num_rows == 0 {
+            0.0
+        } else {
+            (rows / self.num_rows as f64).clamp(0.0, 1.0)
+        }
+    }
+
+    /// Fraction of rows that are null
+    pub fn null_fraction(&self) -> f64 {
+        self.fraction(self.null_count as f64)
+    }
+
+    /// Estimates the number of rows equal to `value`
+    fn rows_equal_to(&self, value: &ScalarValue) -> Option<f64> {
+        if value.is_null() {
+            return Some(0.0);
+        }
+        if let Some((_, rows)) = self.most_common_values.iter().find(|(v, _)| v == value)
+        {
+            return Some(*rows as f64);
+        }
+
+        // The value is not one of the most common values: spread the rows of
+        // the remaining values evenly over them
+        let (rows, distinct_count, common) = match &self.histogram {
+            Some(histogram) => {
+                let Some(bucket) = histogram.bucket_of(value) else {
+                    return Some(0.0);
+                };
+                let common = self.most_common_values.iter().filter(|(v, _)| {
+                    matches!(
+                        bucket.lower.partial_cmp(v),
+                        Some(Ordering::Less | Ordering::Equal)
+                    ) && matches!(
+                        bucket.upper.partial_cmp(v),
+                        Some(Ordering::Greater | Ordering::Equal)
+                    )
+                });
+                (
+                    bucket.num_rows,
+                    bucket.distinct_count,
+                    common.collect::<Vec<_>>(),
+                )
+            }
+            None => (
+                self.non_null_rows(),
+                self.distinct_count?,
+                self.most_common_values.iter().collect(),
+            ),
+        };
+        let common_rows: usize = common.iter().map(|(_, rows)| rows).sum();
+        let remaining_distinct = distinct_count.saturating_sub(common.len());
+        if remaining_distinct == 0 {
+            return Some(0.0);
+        }
+        Some(rows.saturating_sub(common_rows) as f64 / remaining_distinct as f64)
+    }
+
+    /// Estimates the fraction of rows for which `column <op> value` holds,
+    /// for comparison operators.
+    ///
+    /// Returns `None` if the distribution can not estimate the predicate.
+    pub fn comparison_selectivity(
+        &self,
+        op: Operator,
+        value: &ScalarValue,
+    ) -> Option<f64> {
+        if value.is_null() {
+            return Some(0.0);
+        }
+        let non_null = self.non_null_rows() as f64;
+        let rows = match op {
+            Operator::Eq => self.rows_equal_to(value)?,
+            Operator::NotEq => non_null - self.rows_equal_to(value)?,
+            Operator::Lt | Operator::LtEq | Operator::Gt | Operator::GtEq => {
+                let histogram = self.histogram.as_ref()?;
+                // the histogram may only describe a sample of the rows
+                let scale = non_null / histogram.num_rows().max(1) as f64;
+                let less = histogram.rows_less_than(value)? * scale;
+                let less_or_equal = (less + self.rows_equal_to(value)?).min(non_null);
+                match op {
+                    Operator::Lt => less,
+                    Operator::LtEq => less_or_equal,
+                    Operator::Gt => non_null - less_or_equal,
+                    _ => non_null - less,
+                }
+            }
+            _ => return None,
+        };
+        Some(self.fraction(rows))
+    }
+}
+
+/// [`ExtendedStatistics`] extension holding the [`ColumnDistribution`] of
+/// each column of a plan's output, in schema order.
+///
+/// Columns without a known distribution are `None`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ColumnDistributions {
+    columns: Vec<Option<Arc<ColumnDistribution>>>,
+}
+
+impl ColumnDistributions {
+    /// Create new column distributions, with one entry per column
+    pub fn new(columns: Vec<Option<Arc<ColumnDistribution>>>) -> Self {
+        Self { columns }
+    }
+
+    /// Returns the distribution of the column at `index`, if known
+    pub fn column(&self, index: usize) -> Option<&Arc<ColumnDistribution>> {
+        self.columns.get(index).and_then(Option::as_ref)
+    }
+
+    /// Returns the distributions of all columns
+    pub fn columns(&self) -> &[Option<Arc<ColumnDistribution>>] {
+        &self.columns
+    }
+
+    /// Returns true if no column has a known distribution
+    pub fn is_empty(&self) -> bool {
+        self.columns.iter().all(Option::is_none)
+    }
+
+    /// Maps the distributions to the output of projection expressions.
+    ///
+    /// Expressions that are plain column references keep the distribution of
+    /// the column; other expressions have unknown distributions.
+    pub fn project<'a>(
+        &self,
+        exprs: impl IntoIterator<Item = &'a Arc<dyn PhysicalExpr>>,
+    ) -> Self {
+        let columns = exprs
+            .into_iter()
+            .map(|expr| {
+                expr.downcast_ref::<Column>()
+                    .and_then(|column| self.column(column.index()))
+                    .cloned()
+            })
+            .collect();
+        Self { columns }
+    }
+
+    /// Estimates the fraction of rows for which `predicate` holds.
+    ///
+    /// Supports comparisons of columns with literals, `IN` lists of literals,
+    /// `IS [NOT] NULL` and their combinations with `AND`, `OR` and `NOT`.
+    /// Returns `None` if any part of the predicate can not be estimated.
+    pub fn predicate_selectivity(
+        &self,
+        predicate: &Arc<dyn PhysicalExpr>,
+    ) -> Option<f64> {
+        if let Some(binary) = predicate.downcast_ref::<BinaryExpr>() {
+            let op = *binary.op();
+            return match op {
+                Operator::And => Some(
+                    self.predicate_selectivity(binary.left())?
+                        * self.predicate_selectivity(binary.right())?,
+                ),
+                Operator::Or => {
+                    let left = self.predicate_selectivity(binary.left())?;
+                    let right = self.predicate_selectivity(binary.right())?;
+                    Some(left + right - left * right)
+                }
+                _ => self.comparison_selectivity(binary.left(), op, binary.right()),
+            };
+        }
+        if let Some(not) = predicate.downcast_ref::<NotExpr>() {
+            return Some(1.0 - self.predicate_selectivity(not.arg())?);
+        }
+        if let Some(is_null) = predicate.downcast_ref::<IsNullExpr>() {
+            return Some(self.column_distribution(is_null.arg())?.null_fraction());
+        }
+        if let Some(is_not_null) = predicate.downcast_ref::<IsNotNullExpr>() {
+            return Some(
+                1.0 - self.column_distribution(is_not_null.arg())?.null_fraction(),
+            );
+        }
+        if let Some(in_list) = predicate.downcast_ref::<InListExpr>() {
+            let distribution = self.column_distribution(in_list.expr())?;
+            let mut selectivity = 0.0;
+            for item in in_list.list() {
+                let value = item.downcast_ref::<Literal>()?.value();
+                selectivity +=
+                    distribution.comparison_selectivity(Operator::Eq, value)?;
+            }
+            let selectivity = selectivity.min(1.0 - distribution.null_fraction());
+            return Some(if in_list.negated() {
+                1.0 - distribution.null_fraction() - selectivity
+            } else {
+                selectivity
+            });
+        }
+        None
+    }
+
+    fn column_distribution(
+        &self,
+        expr: &Arc<dyn PhysicalExpr>,
+    ) -> Option<&ColumnDistribution> {
+        let column = expr.downcast_ref::<Column>()?;
+        self.column(column.index()).map(AsRef::as_ref)
+    }
+
+    fn comparison_selectivity(
+        &self,
+        left: &Arc<dyn PhysicalExpr>,
+        op: Operator,
+        right: &Arc<dyn PhysicalExpr>,
+    ) -> Option<f64> {
+        if let (Some(distribution), Some(literal)) = (
+            self.column_distribution(left),
+            right.downcast_ref::<Literal>(),
+        ) {
+            distribution.comparison_selectivity(op, literal.value())
+        } else if let (Some(literal), Some(distribution)) = (
+            left.downcast_ref::<Literal>(),
+            self.column_distribution(right),
+        ) {
+            distribution.comparison_selectivity(op.swap()?, literal.value())
+        } else {
+            None
+        }
+    }
+}
+
+/// Estimates the selectivity of the equi-join condition `left = right` from
+/// the distributions of the join keys, relative to the Cartesian product.
+///
+/// Matching most common values contribute their exact frequencies; the
+/// remaining values are assumed to be spread evenly over the remaining
+/// distinct values, following PostgreSQL's `eqjoinsel`. Returns `None` if
+/// either side lacks a distinct count.
+pub fn equi_join_selectivity(
+    left: &ColumnDistribution,
+    right: &ColumnDistribution,
+) -> Option<f64> {
+    let left_distinct = left.distinct_count()?.max(1) as f64;
+    let right_distinct = right.distinct_count()?.max(1) as f64;
+    let left_non_null = 1.0 - left.null_fraction();
+    let right_non_null = 1.0 - right.null_fraction();
+
+    if left.most_common_values.is_empty() || right.most_common_values.is_empty() {
+        return Some(left_non_null * right_non_null / left_distinct.max(right_distinct));
+    }
+
+    let frequency = |distribution: &ColumnDistribution, rows: usize| {
+        distribution.fraction(rows as f64)
+    };
+    let mut match_product = 0.0;
+    let mut left_matched = 0.0;
+    let mut right_matched = 0.0;
+    let mut matches = 0.0;
+    for (value, left_rows) in &left.most_common_values {
+        if let Some((_, right_rows)) =
+            right.most_common_values.iter().find(|(v, _)| v == value)
+        {
+            let left_frequency = frequency(left, *left_rows);
+            let right_frequency = frequency(right, *right_rows);
+            match_product += left_frequency * right_frequency;
+            left_matched += left_frequency;
+            right_matched += right_frequency;
+            matches += 1.0;
+        }
+    }
+    let common_frequency = |distribution: &ColumnDistribution| {
+        distribution
+            .most_common_values
+            .iter()
+            .map(|(_, rows)| frequency(distribution, *rows))
+            .sum::<f64>()
+    };
+    let left_common = common_frequency(left);
+    let right_common = common_frequency(right);
+    let left_unmatched = left_common - left_matched;
+    let right_unmatched = right_common - right_matched;
+    let left_other = (left_non_null - left_common).max(0.0);
+    let right_other = (right_non_null - right_common).max(0.0);
+    let left_common_count = left.most_common_values.len() as f64;
+    let right_common_count = right.most_common_values.len() as f64;
+
+    // selectivity seen from each side, the smaller one is used
+    let one_sided = |unmatched: f64,
+                     other: f64,
+                     other_side_other: f64,
+                     other_side_unmatched: f64,
+                     other_side_distinct: f64,
+                     other_side_common_count: f64| {
+        let mut selectivity = match_product;
+        if other_side_distinct > other_side_common_count {
+            selectivity += unmatched * other_side_other
+                / (other_side_distinct - other_side_common_count);
+        }
+        if other_side_distinct > matches {
+            selectivity += other * (other_side_other + other_side_unmatched)
+                / (other_side_distinct - matches);
+        }
+        selectivity
+    };
+    let left_selectivity = one_sided(
+        left_unmatched,
+        left_other,
+        right_other,
+        right_unmatched,
+        right_distinct,
+        right_common_count,
+    );
+    let right_selectivity = one_sided(
+        right_unmatched,
+        right_other,
+        left_other,
+        left_unmatched,
+        left_distinct,
+        left_common_count,
+    );
+    Some(left_selectivity.min(right_selectivity).clamp(0.0, 1.0))
+}
+
+/// Statistics provider that uses [`ColumnDistributions`] to estimate the
+/// output of filters and equi-joins.
+///
+/// - [`FilterExec`](crate::filter::FilterExec): estimates the selectivity of
+///   the predicate with [`ColumnDistributions::predicate_selectivity`]
+/// - [`ProjectionExec`](crate::projection::ProjectionExec): maps the
+///   distributions of the input to the projected columns
+/// - [`HashJoinExec`](crate::joins::HashJoinExec) and
+///   [`SortMergeJoinExec`](crate::joins::SortMergeJoinExec): estimates the
+///   selectivity of each join key with [`equi_join_selectivity`], falling
+///   back to the NDV of keys without distributions
+///
+/// Delegates when the inputs carry no distributions, or when the predicate or
+/// join keys can not be estimated from them.
+#[derive(Debug, Default)]
+pub struct HistogramStatisticsProvider;
+
+impl StatisticsProvider for HistogramStatisticsProvider {
+    fn compute_statistics(
+        &self,
+        plan: &dyn ExecutionPlan,
+        child_stats: &[ExtendedStatistics],
+    ) -> Result<StatisticsResult> {
+        if let Some(filter) = plan.downcast_ref::<FilterExec>() {
+            filter_statistics(filter, child_stats)
+        } else if let Some(projection) = plan.downcast_ref::<ProjectionExec>() {
+            projection_statistics(projection, child_stats)
+        } else if let Some(hash_join) = plan.downcast_ref::<HashJoinExec>() {
+            join_statistics(plan, hash_join.on(), *hash_join.join_type(), child_stats)
+        } else if let Some(smj) = plan.downcast_ref::<SortMergeJoinExec>() {
+            join_statistics(plan, smj.on(), smj.join_type(), child_stats)
+        } else {
+            Ok(StatisticsResult::Delegate)
+        }
+    }
+}
+
+fn input_distributions(
+    child_stats: &[ExtendedStatistics],
+) -> Option<&ColumnDistributions> {
+    child_stats
+        .first()?
+        .get_extension::<ColumnDistributions>()
+        .filter(|distributions| !distributions.is_empty())
+}
+
+fn filter_statistics(
+    filter: &FilterExec,
+    child_stats: &[ExtendedStatistics],
+) -> Result<StatisticsResult> {
+    let Some(distributions) = input_distributions(child_stats) else {
+        return Ok(StatisticsResult::Delegate);
+    };
+    let Some(selectivity) = distributions.predicate_selectivity(filter.predicate())
+    else {
+        return Ok(StatisticsResult::Delegate);
+    };
+
+    let input_stats = child_stats[0].base().clone();
+    let input_rows = match input_stats.num_rows.get_value() {
+        Some(&rows) => rows,
+        None => match distributions.columns().iter().flatten().next() {
+            Some(distribution) => distribution.num_rows(),
+            None => return Ok(StatisticsResult::Delegate),
+        },
+    };
+    let mut stats: Statistics = FilterExec::statistics_helper(
+        &filter.input().schema(),
+        input_stats,
+        filter.predicate(),
+        filter.default_selectivity(),
+    )?;
+
+    let selectivity = selectivity.clamp(0.0, 1.0);
+    let num_rows = (input_rows as f64 * selectivity).round() as usize;
+    rescale_byte_size(&mut stats, Precision::Inexact(num_rows));
+    if input_rows > 0 {
+        for column in &mut stats.column_statistics {
+            if let Some(&ndv) = column.distinct_count.get_value() {
+                column.distinct_count = Precision::Inexact(ndv_after_selectivity(
+                    ndv,
+                    input_rows,
+                    selectivity,
+                ));
+            }
+        }
+    }
+
+    let stats = stats.project(filter.projection().as_ref());
+    Ok(StatisticsResult::Computed(ExtendedStatistics::new(stats)))
+}
+
+fn projection_statistics(
+    projection: &ProjectionExec,
+    child_stats: &[ExtendedStatistics],
+) -> Result<StatisticsResult> {
+    let Some(distributions) = input_distributions(child_stats) else {
+        return Ok(StatisticsResult::Delegate);
+    };
+
+    let stats = projection
+        .projection_expr()
+        .project_statistics(child_stats[0].base().clone(), &projection.schema())?;
+    let distributions = distributions.project(
+        projection
+            .projection_expr()
+            .iter()
+            .map(|projection_expr| &projection_expr.expr),
+    );
+    let mut stats = ExtendedStatistics::new(stats);
+    stats.set_extension(distributions);
+    Ok(StatisticsResult::Computed(stats))
+}
+
+fn join_statistics(
+    plan: &dyn ExecutionPlan,
+    on: JoinOnRef,
+    join_type: datafusion_common::JoinType,
+    child_stats: &[ExtendedStatistics],
+) -> Result<StatisticsResult> {
+    if child_stats.len() != 2 || on.is_empty() {
+        return Ok(StatisticsResult::Delegate);
+    }
+    let left = &child_stats[0];
+    let right = &child_stats[1];
+    let (Some(&left_rows), Some(&right_rows)) = (
+        left.base().num_rows.get_value(),
+        right.base().num_rows.get_value(),
+    ) else {
+        return Ok(StatisticsResult::Delegate);
+    };
+    let left_distributions = left.get_extension::<ColumnDistributions>();
+    let right_distributions = right.get_extension::<ColumnDistributions>();
+
+    let mut selectivity = 1.0;
+    let mut used_distributions = false;
+    for (left_key, right_key) in on {
+        let (Some(left_key), Some(right_key)) = (
+            left_key.downcast_ref::<Column>(),
+            right_key.downcast_ref::<Column>(),
+        ) else {
+            return Ok(StatisticsResult::Delegate);
+        };
+
+        let key_selectivity = match (
+            left_distributions.and_then(|d| d.column(left_key.index())),
+            right_distributions.and_then(|d| d.column(right_key.index())),
+        ) {
+            (Some(left_distribution), Some(right_distribution)) => {
+                used_distributions = true;
+                equi_join_selectivity(left_distribution, right_distribution)
+            }
+            _ => None,
+        };
+        let key_selectivity = key_selectivity.or_else(|| {
+            let left_ndv = left
+                .base()
+                .column_statistics
+                .get(left_key.index())?
+                .distinct_count
+                .get_value()
+                .copied()?;
+            let right_ndv = right
+                .base()
+                .column_statistics
+                .get(right_key.index())?
+                .distinct_count
+                .get_value()
+                .copied()?;
+            Some(1.0 / left_ndv.max(right_ndv).max(1) as f64)
+        });
+        let Some(key_selectivity) = key_selectivity else {
+            return Ok(StatisticsResult::Delegate);
+        };
+        selectivity *= key_selectivity;
+    }
+    if !used_distributions {
+        return Ok(StatisticsResult::Delegate);
+    }
+
+    let cartesian = left_rows as f64 * right_rows as f64;
+    let inner_estimate =
+        (cartesian * selectivity).round().min(usize::MAX as f64) as usize;
+    let estimate =
+        bound_join_cardinality(join_type, inner_estimate, left_rows, right_rows);
+    computed_with_row_count(plan, Precision::Inexact(estimate))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Int32Array, StringArray};
+    use arrow::datatypes::{Field, Schema};
+    use datafusion_physical_expr::expressions::{col, in_list, lit};
+
+    fn int_distribution(values: Vec<Option<i32>>) -> ColumnDistribution {
+        let array: ArrayRef = Arc::new(Int32Array::from(values));
+        ColumnDistribution::try_from_array(&array, 4, 2).unwrap()
+    }
+
+    /// 100 rows: value 1 appears 50 times, values 2..=41 once each and the
+    /// remaining 10 rows are null
+    fn skewed() -> ColumnDistribution {
+        let mut values = vec![Some(1); 50];
+        values.extend((2..=41).map(Some));
+        values.extend(std::iter::repeat_n(None, 10));
+        int_distribution(values)
+    }
+
+    #[test]
+    fn equi_depth_histogram() -> Result<()> {
+        let array: ArrayRef =
+            Arc::new(Int32Array::from(vec![5, 1, 1, 1, 2, 3, 4, 4, 6, 7]));
+        let histogram = Histogram::try_from_array(&array, 3)?.unwrap();
+        let bounds = histogram
+            .buckets()
+            .iter()
+            .map(|b| {
+                (
+                    b.lower.clone(),
+                    b.upper.clone(),
+                    b.num_rows,
+                    b.distinct_count,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            bounds,
+            vec![
+                (ScalarValue::from(1), ScalarValue::from(2), 4, 2),
+                (ScalarValue::from(3), ScalarValue::from(5), 4, 3),
+                (ScalarValue::from(6), ScalarValue::from(7), 2, 2),
+            ]
+        );
+        assert_eq!(histogram.num_rows(), 10);
+        assert_eq!(histogram.distinct_count(), 7);
+
+        // overlapping buckets are rejected
+        let bucket = |lower: i32, upper: i32| HistogramBucket {
+            lower: ScalarValue::from(lower),
+            upper: ScalarValue::from(upper),
+            num_rows: 1,
+            distinct_count: 1,
+        };
+        assert!(Histogram::try_new(vec![bucket(1, 3), bucket(3, 4)]).is_err());
+        assert!(Histogram::try_new(vec![bucket(2, 1)]).is_err());
+        assert!(Histogram::try_new(vec![bucket(1, 2), bucket(3, 4)]).is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn most_common_values() {
+        let distribution = skewed();
+        assert_eq!(distribution.num_rows(), 100);
+        assert_eq!(distribution.null_count(), 10);
+        assert_eq!(distribution.distinct_count(), Some(41));
+        assert_eq!(
+            distribution.most_common_values(),
+            &[(ScalarValue::from(1), 50)]
+        );
+    }
+
+    #[test]
+    fn comparison_selectivity() {
+        let distribution = skewed();
+        let selectivity = |op, value: i32| {
+            distribution
+                .comparison_selectivity(op, &ScalarValue::from(value))
+                .unwrap()
+        };
+
+        // the most common value is estimated exactly
+        assert_eq!(selectivity(Operator::Eq, 1), 0.5);
+        // the other values share the remaining rows
+        assert!((selectivity(Operator::Eq, 20) - 0.01).abs() < 1e-9);
+        assert_eq!(selectivity(Operator::Eq, 100), 0.0);
+        assert!((selectivity(Operator::NotEq, 1) - 0.4).abs() < 1e-9);
+
+        // half of the rows are 1, the rest is spread over 2..=41
+        assert!(selectivity(Operator::Lt, 1) < 0.01);
+        assert!((selectivity(Operator::LtEq, 1) - 0.5).abs() < 0.05);
+        assert!((selectivity(Operator::Gt, 21) - 0.2).abs() < 0.05);
+        assert!((selectivity(Operator::GtEq, 42)).abs() < 1e-9);
+        assert_eq!(
+            distribution.comparison_selectivity(Operator::Eq, &ScalarValue::Int32(None)),
+            Some(0.0)
+        );
+    }
+
+    #[test]
+    fn string_comparison_selectivity() -> Result<()> {
+        let array: ArrayRef =
+            Arc::new(StringArray::from(vec!["a", "a", "a", "b", "c", "d"]));
+        let distribution = ColumnDistribution::try_from_array(&array, 2, 1)?;
+        let eq = distribution
+            .comparison_selectivity(Operator::Eq, &ScalarValue::from("a"))
+            .unwrap();
+        assert_eq!(eq, 0.5);
+        // strings can not be interpolated, so half of the bucket [b, d] is
+        // assumed to be less than c
+        let lt = distribution
+            .comparison_selectivity(Operator::Lt, &ScalarValue::from("c"))
+            .unwrap();
+        assert_eq!(lt, 0.75);
+        let lt = distribution
+            .comparison_selectivity(Operator::Lt, &ScalarValue::from("e"))
+            .unwrap();
+        assert_eq!(lt, 1.0);
+        Ok(())
+    }
+
+    #[test]
+    fn predicate_selectivity() -> Result<()> {
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Int32, true),
+        ]);
+        let distributions =
+            ColumnDistributions::new(vec![Some(Arc::new(skewed())), None]);
+        let a = col("a", &schema)?;
+        let b = col("b", &schema)?;
+        let eq = |value: i32| -> Arc<dyn PhysicalExpr> {
+            Arc::new(BinaryExpr::new(Arc::clone(&a), Operator::Eq, lit(value)))
+        };
+
+        let selectivity =
+            |expr: Arc<dyn PhysicalExpr>| distributions.predicate_selectivity(&expr);
+        assert_eq!(selectivity(eq(1)), Some(0.5));
+        // literal on the left
+        let swapped: Arc<dyn PhysicalExpr> =
+            Arc::new(BinaryExpr::new(lit(1), Operator::Eq, Arc::clone(&a)));
+        assert_eq!(selectivity(swapped), Some(0.5));
+        let or: Arc<dyn PhysicalExpr> =
+            Arc::new(BinaryExpr::new(eq(1), Operator::Or, eq(2)));
+        assert!((selectivity(or).unwrap() - 0.505).abs() < 1e-9);
+        let not: Arc<dyn PhysicalExpr> = Arc::new(NotExpr::new(eq(1)));
+        assert_eq!(selectivity(not), Some(0.5));
+        let is_null: Arc<dyn PhysicalExpr> = Arc::new(IsNullExpr::new(Arc::clone(&a)));
+        assert_eq!(selectivity(is_null), Some(0.1));
+        let in_list = in_list(Arc::clone(&a), vec![lit(1), lit(2)], &false, &schema)?;
+        assert!((selectivity(in_list).unwrap() - 0.51).abs() < 1e-9);
+
+        // columns without a distribution can not be estimated
+        let on_b: Arc<dyn PhysicalExpr> =
+            Arc::new(BinaryExpr::new(Arc::clone(&b), Operator::Eq, lit(1)));
+        assert_eq!(selectivity(Arc::clone(&on_b)), None);
+        let and: Arc<dyn PhysicalExpr> =
+            Arc::new(BinaryExpr::new(eq(1), Operator::And, on_b));
+        assert_eq!(selectivity(and), None);
+        Ok(())
+    }
+
+    #[test]
+    fn join_selectivity() {
+        // both sides are dominated by the same value
+        let left = skewed();
+        let right = skewed();
+        let selectivity = equi_join_selectivity(&left, &right).unwrap();
+        // 0.5 * 0.5 from the common value, plus the other values matching
+        // each other: 0.4 * 0.4 / 40
+        assert!((selectivity - 0.254).abs() < 1e-9);
+
+        // without most common values, fall back to the distinct counts
+        let uniform = int_distribution((0..100).map(Some).collect());
+        let selectivity = equi_join_selectivity(&uniform, &uniform).unwrap();
+        assert!((selectivity - 0.01).abs() < 1e-9);
+    }
+}
@@ -41,14 +41,24 @@
 //!
 //! The following providers are included and can be registered in this order:
 //!
-//! 1. [`FilterStatisticsProvider`] - selectivity-based filter estimation
-//! 2. [`ProjectionStatisticsProvider`] - column mapping through projections
-//! 3. [`PassthroughStatisticsProvider`] - passthrough for cardinality-preserving operators
-//! 4. [`AggregateStatisticsProvider`] - NDV-based GROUP BY cardinality estimation
-//! 5. [`JoinStatisticsProvider`] - NDV-based join output estimation (hash, sort-merge, cross)
-//! 6. [`LimitStatisticsProvider`] - caps output at the fetch limit (local and global)
-//! 7. [`UnionStatisticsProvider`] - sums input row counts
-//! 8. [`DefaultStatisticsProvider`] - fallback to `partition_statistics(None)`
+//! 1. [`HistogramStatisticsProvider`] - histogram and most-common-values based
+//!    filter and equi-join estimation, when [`ColumnDistributions`] are available
+//! 2. [`FilterStatisticsProvider`] - selectivity-based filter estimation
+//! 3. [`ProjectionStatisticsProvider`] - column mapping through projections
+//! 4. [`PassthroughStatisticsProvider`] - passthrough for cardinality-preserving operators
+//! 5. [`AggregateStatisticsProvider`] - NDV-based GROUP BY cardinality estimation
+//! 6. [`JoinStatisticsProvider`] - NDV-based join output estimation (hash, sort-merge, cross)
+//! 7. [`LimitStatisticsProvider`] - caps output at the fetch limit (local and global)
+//! 8. [`UnionStatisticsProvider`] - sums input row counts
+//! 9. [`DefaultStatisticsProvider`] - fallback to `partition_statistics(None)`
+//!
+//! # Histograms
+//!
+//! [`ColumnDistributions`] is a built-in [`ExtendedStatistics`] extension
+//! holding an equi-depth [`Histogram`] and the most common values of each
+//! column. Data sources attach it to the statistics of their scans, and
+//! [`HistogramStatisticsProvider`] uses it to estimate range and equality
+//! predicates and join cardinality on skewed columns.
 //!
 //! # Relationship to [#20184](https://github.com/apache/datafusion/issues/20184)
 //!
@@ -96,6 +106,13 @@ use datafusion_common::{Result, Statistics};
 
 use crate::ExecutionPlan;
 
+mod histogram;
+
+pub use histogram::{
+    ColumnDistribution, ColumnDistributions, Histogram, HistogramBucket,
+    HistogramStatisticsProvider, equi_join_selectivity,
+};
+
 // ============================================================================
 // ExtendedStatistics: Statistics with type-safe extensions
 // ============================================================================
@@ -319,16 +336,23 @@ impl StatisticsRegistry {
     /// Create a registry pre-loaded with the standard built-in providers.
     ///
     /// Provider order (first match wins):
-    /// 1. [`FilterStatisticsProvider`]
-    /// 2. [`ProjectionStatisticsProvider`]
-    /// 3. [`PassthroughStatisticsProvider`]
-    /// 4. [`AggregateStatisticsProvider`]
-    /// 5. [`JoinStatisticsProvider`]
-    /// 6. [`LimitStatisticsProvider`]
-    /// 7. [`UnionStatisticsProvider`]
-    /// 8. [`DefaultStatisticsProvider`]
+    /// 1. [`HistogramStatisticsProvider`]
+    /// 2. [`FilterStatisticsProvider`]
+    /// 3. [`ProjectionStatisticsProvider`]
+    /// 4. [`PassthroughStatisticsProvider`]
+    /// 5. [`AggregateStatisticsProvider`]
+    /// 6. [`JoinStatisticsProvider`]
+    /// 7. [`LimitStatisticsProvider`]
+    /// 8. [`UnionStatisticsProvider`]
+    /// 9. [`DefaultStatisticsProvider`]
+    ///
+    /// Data sources are defined in a downstream crate, so the provider that
+    /// attaches their histograms, `DataSourceStatisticsProvider`, is not part
+    /// of this chain. Sessions built with default features register it in
+    /// front of these providers.
     pub fn default_with_builtin_providers() -> Self {
         Self::with_providers(vec![
+            Arc::new(HistogramStatisticsProvider),
             Arc::new(FilterStatisticsProvider),
             Arc::new(ProjectionStatisticsProvider),
             Arc::new(PassthroughStatisticsProvider),
@@ -515,6 +539,39 @@ fn computed_with_row_count(
     Ok(StatisticsResult::Computed(ExtendedStatistics::new(base)))
 }
 
+/// Applies join-type-aware bounds to an inner join cardinality estimate.
+///
+/// Outer joins produce at least the rows of their preserved sides, semi joins
+/// at most the rows of their probe side and anti joins the rows that did not
+/// match.
+fn bound_join_cardinality(
+    join_type: datafusion_common::JoinType,
+    inner_estimate: usize,
+    left_rows: usize,
+    right_rows: usize,
+) -> usize {
+    use datafusion_common::JoinType;
+
+    match join_type {
+        JoinType::Inner => inner_estimate,
+        JoinType::Left => inner_estimate.max(left_rows),
+        JoinType::Right => inner_estimate.max(right_rows),
+        JoinType::Full => {
+            // At least left + right - matched, but never less than inner
+            let outer_bound = left_rows
+                .saturating_add(right_rows)
+                .saturating_sub(inner_estimate);
+            inner_estimate.max(outer_bound)
+        }
+        JoinType::LeftSemi => inner_estimate.min(left_rows),
+        JoinType::RightSemi => inner_estimate.min(right_rows),
+        JoinType::LeftAnti => left_rows.saturating_sub(inner_estimate.min(left_rows)),
+        JoinType::RightAnti => right_rows.saturating_sub(inner_estimate.min(right_rows)),
+        JoinType::LeftMark | JoinType::LeftAsOf => left_rows,
+        JoinType::RightMark => right_rows,
+    }
+}
+
 /// Statistics provider for [`FilterExec`](crate::filter::FilterExec) that uses
 /// pre-computed enhanced child statistics from the registry walk.
 ///
@@ -842,27 +899,8 @@ impl StatisticsProvider for JoinStatisticsProvider {
             return Ok(StatisticsResult::Delegate);
         };
 
-        // Apply join-type-aware cardinality bounds
-        let estimated = match join_type {
-            JoinType::Inner => inner_estimate,
-            JoinType::Left => inner_estimate.max(left_rows),
-            JoinType::Right => inner_estimate.max(right_rows),
-            JoinType::Full => {
-                // At least left + right - matched, but never less than inner
-                let outer_bound = left_rows
-                    .saturating_add(right_rows)
-                    .saturating_sub(inner_estimate);
-                inner_estimate.max(outer_bound)
-            }
-            JoinType::LeftSemi => inner_estimate.min(left_rows),
-            JoinType::RightSemi => inner_estimate.min(right_rows),
-            JoinType::LeftAnti => left_rows.saturating_sub(inner_estimate.min(left_rows)),
-            JoinType::RightAnti => {
-                right_rows.saturating_sub(inner_estimate.min(right_rows))
-            }
-            JoinType::LeftMark => left_rows,
-            JoinType::RightMark => right_rows,
-        };
+        let estimated =
+            bound_join_cardinality(join_type, inner_estimate, left_rows, right_rows);
 
         // NL join inner with exact inputs is an exact Cartesian product;
         // NDV-based estimates are inherently inexact.
@@ -2317,4 +2355,84 @@ mod tests {
         assert_eq!(stats_b.base.num_rows, Precision::Inexact(50));
         Ok(())
     }
+
+    // =========================================================================
+    // HistogramStatisticsProvider tests
+    // =========================================================================
+
+    /// Registry with the built-in providers that attaches the distribution of
+    /// a skewed column `a` to every [`MockSourceExec`]: value 1 appears in half
+    /// of the 100 rows, values 2..=51 once each
+    fn registry_with_skewed_distributions() -> Result<StatisticsRegistry> {
+        let mut values = vec![1; 50];
+        values.extend(2..=51);
+        let array: arrow::array::ArrayRef =
+            Arc::new(arrow::array::Int32Array::from(values));
+        let distributions = ColumnDistributions::new(vec![
+            Some(Arc::new(ColumnDistribution::try_from_array(&array, 10, 5)?)),
+            None,
+        ]);
+
+        let mut registry = StatisticsRegistry::default_with_builtin_providers();
+        registry.register(Arc::new(ClosureStatisticsProvider::new(
+            move |plan, _child_stats| {
+                if plan.downcast_ref::<MockSourceExec>().is_none() {
+                    return Ok(StatisticsResult::Delegate);
+                }
+                let mut stats =
+                    ExtendedStatistics::new_arc(plan.partition_statistics(None)?);
+                stats.set_extension(distributions.clone());
+                Ok(StatisticsResult::Computed(stats))
+            },
+        )));
+        Ok(registry)
+    }
+
+    #[test]
+    fn test_histogram_provider_filter() -> Result<()> {
+        let registry = registry_with_skewed_distributions()?;
+        let source = make_source(100);
+        let schema = source.schema();
+
+        // a = 1 selects the most common value
+        let predicate =
+            Arc::new(BinaryExpr::new(col("a", &schema)?, Operator::Eq, lit(1i32)));
+        let filter: Arc<dyn ExecutionPlan> =
+            Arc::new(FilterExec::try_new(predicate, Arc::clone(&source))?);
+        let stats = registry.compute(filter.as_ref())?;
+        assert_eq!(stats.base.num_rows, Precision::Inexact(50));
+
+        // a > 1 selects the other half, through a projection reordering the
+        // columns
+        let projection: Arc<dyn ExecutionPlan> = Arc::new(ProjectionExec::try_new(
+            vec![
+                (col("b", &schema)?, "b".to_string()),
+                (col("a", &schema)?, "a".to_string()),
+            ],
+            source,
+        )?);
+        let predicate = Arc::new(BinaryExpr::new(
+            col("a", &projection.schema())?,
+            Operator::Gt,
+            lit(1i32),
+        ));
+        let filter: Arc<dyn ExecutionPlan> =
+            Arc::new(FilterExec::try_new(predicate, projection)?);
+        let stats = registry.compute(filter.as_ref())?;
+        assert_eq!(stats.base.num_rows, Precision::Inexact(50));
+        Ok(())
+    }
+
+    #[test]
+    fn test_histogram_provider_join() -> Result<()> {
+        // Both sides have value 1 in half of their rows: the most common
+        // values match 50 * 50 times, and the remaining values of each side
+        // contribute 0.5 * 0.5 / 50 of the Cartesian product. NDV alone
+        // would estimate 100 * 100 / 51 = 196 rows.
+        let registry = registry_with_skewed_distributions()?;
+        let join = make_hash_join(make_source(100), make_source(100))?;
+        let stats = registry.compute(join.as_ref())?;
+        assert_eq!(stats.base.num_rows, Precision::Inexact(2550));
+        Ok(())
+    }
 }
@@ -0,0 +1,429 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the SAMPLE plan, used to implement `TABLESAMPLE`
+
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use super::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet};
+use super::{
+    DisplayAs, ExecutionPlanProperties, PlanProperties, RecordBatchStream,
+    SendableRecordBatchStream, Statistics,
+};
+use crate::execution_plan::CardinalityEffect;
+use crate::{DisplayFormatType, ExecutionPlan, check_if_same_properties};
+
+use arrow::array::BooleanArray;
+use arrow::compute::filter_record_batch;
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+use datafusion_common::tree_node::TreeNodeRecursion;
+use datafusion_common::{Result, SampleMethod, internal_err};
+use datafusion_execution::TaskContext;
+use datafusion_physical_expr::PhysicalExpr;
+use futures::stream::{Stream, StreamExt};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Randomly samples the rows of its input, used to implement `TABLESAMPLE`.
+///
+/// Every row is selected with probability `fraction`. With
+/// [`SampleMethod::Bernoulli`] this decision is made for each row, with
+/// [`SampleMethod::System`] for each input batch.
+///
+/// The random number generator of each partition is seeded from `seed` and
+/// the partition index, so the same seed only selects the same rows for an
+/// identical physical plan over the same data: the input must produce the same
+/// batches in the same partitions and in the same order. Changing the number
+/// of partitions, how files are split into partitions or the batch size
+/// changes the selected rows. Without a seed, a random one is chosen for each
+/// execution.
+#[derive(Debug, Clone)]
+pub struct SampleExec {
+    /// Input execution plan
+    input: Arc<dyn ExecutionPlan>,
+    /// How rows are selected
+    method: SampleMethod,
+    /// Probability of each row to be selected
+    fraction: f64,
+    /// Seed of the random number generator
+    seed: Option<u64>,
+    /// Execution metrics
+    metrics: ExecutionPlanMetricsSet,
+    cache: Arc<PlanProperties>,
+}
+
+impl SampleExec {
+    /// Create a new SampleExec
+    pub fn try_new(
+        input: Arc<dyn ExecutionPlan>,
+        method: SampleMethod,
+        fraction: f64,
+        seed: Option<u64>,
+    ) -> Result<Self> {
+        if !(0.0..=1.0).contains(&fraction) {
+            return internal_err!(
+                "SampleExec fraction must be between 0 and 1, got {fraction}"
+            );
+        }
+        let cache = Self::compute_properties(&input);
+        Ok(Self {
+            input,
+            method,
+            fraction,
+            seed,
+            metrics: ExecutionPlanMetricsSet::new(),
+            cache: Arc::new(cache),
+        })
+    }
+
+    /// Input execution plan
+    pub fn input(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.input
+    }
+
+    /// How rows are selected
+    pub fn method(&self) -> SampleMethod {
+        self.method
+    }
+
+    /// Probability of each row to be selected
+    pub fn fraction(&self) -> f64 {
+        self.fraction
+    }
+
+    /// Seed of the random number generator, if fixed
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    /// This function creates the cache object that stores the plan properties such as schema, equivalence properties, ordering, partitioning, etc.
+    fn compute_properties(input: &Arc<dyn ExecutionPlan>) -> PlanProperties {
+        PlanProperties::new(
+            input.equivalence_properties().clone(),
+            input.output_partitioning().clone(),
+            input.pipeline_behavior(),
+            input.boundedness(),
+        )
+    }
+
+    fn with_new_children_and_same_properties(
+        &self,
+        mut children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Self {
+        Self {
+            input: children.swap_remove(0),
+            metrics: ExecutionPlanMetricsSet::new(),
+            ..Self::clone(self)
+        }
+    }
+}
+
+impl DisplayAs for SampleExec {
+    fn fmt_as(
+        &self,
+        t: DisplayFormatType,
+        f: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                write!(
+                    f,
+                    "SampleExec: method={}, fraction={}",
+                    self.method, self.fraction
+                )?;
+                if let Some(seed) = self.seed {
+                    write!(f, ", seed={seed}")?;
+                }
+                Ok(())
+            }
+            DisplayFormatType::TreeRender => {
+                writeln!(f, "method={}", self.method)?;
+                write!(f, "fraction={}", self.fraction)
+            }
+        }
+    }
+}
+
+impl ExecutionPlan for SampleExec {
+    fn name(&self) -> &'static str {
+        "SampleExec"
+    }
+
+    fn properties(&self) -> &Arc<PlanProperties> {
+        &self.cache
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
+        vec![&self.input]
+    }
+
+    fn maintains_input_order(&self) -> Vec<bool> {
+        vec![true]
+    }
+
+    /// Repartitioning the input would make the selected rows depend on how
+    /// rows are distributed among the partitions.
+    fn benefits_from_input_partitioning(&self) -> Vec<bool> {
+        vec![false]
+    }
+
+    fn apply_expressions(
+        &self,
+        _f: &mut dyn FnMut(&dyn PhysicalExpr) -> Result<TreeNodeRecursion>,
+    ) -> Result<TreeNodeRecursion> {
+        Ok(TreeNodeRecursion::Continue)
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        mut children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        check_if_same_properties!(self, children);
+        Ok(Arc::new(SampleExec::try_new(
+            children.swap_remove(0),
+            self.method,
+            self.fraction,
+            self.seed,
+        )?))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let seed = match self.seed {
+            Some(seed) => seed.wrapping_add(partition as u64),
+            None => rand::random(),
+        };
+        Ok(Box::pin(SampleStream {
+            input: self.input.execute(partition, context)?,
+            method: self.method,
+            fraction: self.fraction,
+            rng: StdRng::seed_from_u64(seed),
+            baseline_metrics: BaselineMetrics::new(&self.metrics, partition),
+        }))
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    fn partition_statistics(&self, partition: Option<usize>) -> Result<Arc<Statistics>> {
+        let stats = Arc::unwrap_or_clone(self.input.partition_statistics(partition)?);
+        Ok(Arc::new(stats.with_sample_fraction(self.fraction)))
+    }
+
+    fn cardinality_effect(&self) -> CardinalityEffect {
+        CardinalityEffect::LowerEqual
+    }
+}
+
+/// Stream that samples the batches of its input
+struct SampleStream {
+    input: SendableRecordBatchStream,
+    method: SampleMethod,
+    fraction: f64,
+    rng: StdRng,
+    baseline_metrics: BaselineMetrics,
+}
+
+impl SampleStream {
+    /// Returns the selected rows of `batch`, or `None` if no row is selected
+    fn sample(&mut self, batch: RecordBatch) -> Result<Option<RecordBatch>> {
+        let batch = match self.method {
+            SampleMethod::Bernoulli => {
+                let selection = (0..batch.num_rows())
+                    .map(|_| Some(self.rng.random::<f64>() < self.fraction))
+                    .collect::<BooleanArray>();
+                filter_record_batch(&batch, &selection)?
+            }
+            SampleMethod::System => {
+                if self.rng.random::<f64>() < self.fraction {
+                    batch
+                } else {
+                    return Ok(None);
+                }
+            }
+        };
+        Ok((batch.num_rows() > 0).then_some(batch))
+    }
+}
+
+impl Stream for SampleStream {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        loop {
+            let poll = match self.input.poll_next_unpin(cx) {
+                Poll::Ready(Some(Ok(batch))) => {
+                    let elapsed_compute = self.baseline_metrics.elapsed_compute().clone();
+                    let timer = elapsed_compute.timer();
+                    let sampled = self.sample(batch);
+                    timer.done();
+                    match sampled {
+                        Ok(Some(batch)) => Poll::Ready(Some(Ok(batch))),
+                        // skip batches without selected rows
+                        Ok(None) => continue,
+                        Err(e) => Poll::Ready(Some(Err(e))),
+                    }
+                }
+                other => other,
+            };
+            return self.baseline_metrics.record_poll(poll);
+        }
+    }
+}
+
+impl RecordBatchStream for SampleStream {
+    fn schema(&self) -> SchemaRef {
+        self.input.schema()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::collect;
+    use crate::test::TestMemoryExec;
+    use crate::test::exec::MockExec;
+
+    use arrow::array::{Int32Array, RecordBatchOptions};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use datafusion_common::assert_batches_eq;
+
+    fn test_schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]))
+    }
+
+    /// 10 batches of 100 rows each with values 0..1000
+    fn test_input() -> Result<Arc<dyn ExecutionPlan>> {
+        let schema = test_schema();
+        let batches = (0..10)
+            .map(|i| {
+                RecordBatch::try_new(
+                    Arc::clone(&schema),
+                    vec![Arc::new(Int32Array::from_iter_values(
+                        i * 100..(i + 1) * 100,
+                    ))],
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(TestMemoryExec::try_new_exec(&[batches], schema, None)?)
+    }
+
+    async fn sample(
+        method: SampleMethod,
+        fraction: f64,
+        seed: Option<u64>,
+    ) -> Result<Vec<i32>> {
+        let exec = Arc::new(SampleExec::try_new(test_input()?, method, fraction, seed)?);
+        let batches = collect(exec.execute(0, Arc::new(TaskContext::default()))?).await?;
+        Ok(batches
+            .iter()
+            .flat_map(|batch| {
+                let values = batch.column(0).as_any().downcast_ref::<Int32Array>();
+                values.unwrap().values().to_vec()
+            })
+            .collect())
+    }
+
+    #[tokio::test]
+    async fn bernoulli_sample_is_deterministic_per_seed() -> Result<()> {
+        let first = sample(SampleMethod::Bernoulli, 0.3, Some(42)).await?;
+        let second = sample(SampleMethod::Bernoulli, 0.3, Some(42)).await?;
+        assert_eq!(first, second);
+        // roughly 30% of the 1000 rows are selected, in input order
+        assert!((200..400).contains(&first.len()), "{}", first.len());
+        assert!(first.is_sorted());
+
+        let other = sample(SampleMethod::Bernoulli, 0.3, Some(43)).await?;
+        assert_ne!(first, other);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn system_sample_selects_whole_batches() -> Result<()> {
+        let rows = sample(SampleMethod::System, 0.5, Some(7)).await?;
+        assert_eq!(rows, sample(SampleMethod::System, 0.5, Some(7)).await?);
+        assert_eq!(rows.len() % 100, 0);
+        for block in rows.chunks(100) {
+            assert_eq!(block[0] % 100, 0);
+            assert_eq!(block[99], block[0] + 99);
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn sample_all_or_nothing() -> Result<()> {
+        for method in [SampleMethod::Bernoulli, SampleMethod::System] {
+            assert_eq!(sample(method, 1.0, None).await?.len(), 1000);
+            assert!(sample(method, 0.0, None).await?.is_empty());
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn sample_skips_empty_batches() -> Result<()> {
+        let schema = test_schema();
+        let empty = RecordBatch::try_new_with_options(
+            Arc::clone(&schema),
+            vec![Arc::new(Int32Array::from(Vec::<i32>::new()))],
+            &RecordBatchOptions::new().with_row_count(Some(0)),
+        )?;
+        let batch = RecordBatch::try_new(
+            Arc::clone(&schema),
+            vec![Arc::new(Int32Array::from(vec![1, 2, 3]))],
+        )?;
+        let input = Arc::new(MockExec::new(vec![Ok(empty), Ok(batch)], schema));
+        let exec = SampleExec::try_new(input, SampleMethod::Bernoulli, 1.0, Some(1))?;
+        let batches = collect(exec.execute(0, Arc::new(TaskContext::default()))?).await?;
+        assert_batches_eq!(
+            &[
+                "+---+", "| a |", "+---+", "| 1 |", "| 2 |", "| 3 |", "+---+"
+            ],
+            &batches
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn sample_statistics_are_scaled() -> Result<()> {
+        let exec =
+            SampleExec::try_new(test_input()?, SampleMethod::Bernoulli, 0.1, None)?;
+        let stats = exec.partition_statistics(None)?;
+        assert_eq!(
+            stats.num_rows,
+            datafusion_common::stats::Precision::Inexact(100)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_fraction() -> Result<()> {
+        let err = SampleExec::try_new(test_input()?, SampleMethod::System, 1.5, None)
+            .unwrap_err();
+        assert!(err.to_string().contains("between 0 and 1"), "{err}");
+        Ok(())
+    }
+}
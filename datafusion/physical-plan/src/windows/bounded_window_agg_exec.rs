@@ -27,7 +27,11 @@ use std::sync::Arc;
 use std::task::{Context, Poll};
 
 use super::utils::create_schema;
-use crate::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet};
+use crate::metrics::{
+    BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet, SpillMetrics,
+};
+use crate::spill::SpillManager;
+use crate::spill::get_record_batch_memory_size;
 use crate::windows::{
     calc_requirements, get_ordered_partition_by_indices, get_partition_by_sort_exprs,
     window_equivalence_properties,
@@ -53,8 +57,11 @@ use datafusion_common::utils::{
 };
 use datafusion_common::{
     HashMap, Result, arrow_datafusion_err, exec_datafusion_err, exec_err,
+    internal_datafusion_err, internal_err,
 };
 use datafusion_execution::TaskContext;
+use datafusion_execution::disk_manager::RefCountedTempFile;
+use datafusion_execution::memory_pool::{MemoryConsumer, MemoryReservation};
 use datafusion_expr::ColumnarValue;
 use datafusion_expr::window_state::{PartitionBatchState, WindowAggState};
 use datafusion_physical_expr::window::{
@@ -67,8 +74,9 @@ use datafusion_physical_expr_common::sort_expr::{
 
 use crate::execution_plan::CardinalityEffect;
 use datafusion_common::hash_utils::RandomState;
+use futures::future::BoxFuture;
 use futures::stream::Stream;
-use futures::{StreamExt, ready};
+use futures::{FutureExt, StreamExt, TryStreamExt, ready};
 use hashbrown::hash_table::HashTable;
 use indexmap::IndexMap;
 use log::debug;
@@ -374,14 +382,32 @@ impl ExecutionPlan for BoundedWindowAggExec {
         partition: usize,
         context: Arc<TaskContext>,
     ) -> Result<SendableRecordBatchStream> {
-        let input = self.input.execute(partition, context)?;
+        let input = self.input.execute(partition, Arc::clone(&context))?;
         let search_mode = self.get_search_algo()?;
+        // Only idle partitions in `Linear` mode can be spilled, see
+        // `BoundedWindowAggStream::spill_idle_partitions`
+        let can_spill = search_mode.is_mode_linear()
+            && context.runtime_env().disk_manager.tmp_files_enabled();
+        let reservation =
+            MemoryConsumer::new(format!("BoundedWindowAggStream[{partition}]"))
+                .with_can_spill(can_spill)
+                .register(context.memory_pool());
+        let spill_manager = can_spill.then(|| {
+            SpillManager::new(
+                context.runtime_env(),
+                SpillMetrics::new(&self.metrics, partition),
+                self.input.schema(),
+            )
+            .with_compression_type(context.session_config().spill_compression())
+        });
         let stream = Box::pin(BoundedWindowAggStream::new(
             Arc::clone(&self.schema),
             self.window_expr.clone(),
             input,
             BaselineMetrics::new(&self.metrics, partition),
             search_mode,
+            reservation,
+            spill_manager,
         )?);
         Ok(stream)
     }
@@ -959,6 +985,32 @@ pub struct BoundedWindowAggStream {
     /// Search mode for partition columns. This determines the algorithm with
     /// which we group each partition.
     search_mode: Box<dyn PartitionSearcher>,
+    /// Memory reservation for `input_buffer` and `partition_buffers`
+    reservation: MemoryReservation,
+    /// Spills idle partitions to disk, `None` if spilling is not supported
+    spill_manager: Option<SpillManager>,
+    /// Partitions whose buffered rows are spilled to disk
+    spilled_partitions: HashMap<PartitionKey, SpilledPartition>,
+    /// Spilled partitions being read back before processing the next input batch
+    restoring: Option<RestoringPartitions>,
+}
+
+/// A partition whose buffered rows were spilled to disk. Its states are kept
+/// in memory, as they are small compared to the buffered rows.
+struct SpilledPartition {
+    file: RefCountedTempFile,
+    /// The state of the partition buffer, with an empty `record_batch`
+    batch_state: PartitionBatchState,
+    /// The state of the partition for each window expression
+    window_states: Vec<WindowState>,
+}
+
+/// Spilled partitions that receive new rows from `batch`
+struct RestoringPartitions {
+    batch: RecordBatch,
+    partitions: Vec<(PartitionKey, SpilledPartition)>,
+    /// Reads the buffered rows of `partitions`, in the same order
+    read: BoxFuture<'static, Result<Vec<RecordBatch>>>,
 }
 
 impl BoundedWindowAggStream {
@@ -1002,6 +1054,8 @@ impl BoundedWindowAggStream {
         input: SendableRecordBatchStream,
         baseline_metrics: BaselineMetrics,
         search_mode: Box<dyn PartitionSearcher>,
+        reservation: MemoryReservation,
+        spill_manager: Option<SpillManager>,
     ) -> Result<Self> {
         let state = window_expr.iter().map(|_| IndexMap::new()).collect();
         let empty_batch = RecordBatch::new_empty(Arc::clone(&schema));
@@ -1015,6 +1069,10 @@ impl BoundedWindowAggStream {
             window_expr,
             baseline_metrics,
             search_mode,
+            reservation,
+            spill_manager,
+            spilled_partitions: HashMap::new(),
+            restoring: None,
         })
     }
 
@@ -1061,19 +1119,42 @@ impl BoundedWindowAggStream {
         }
 
         let elapsed_compute = self.baseline_metrics.elapsed_compute().clone();
-        match ready!(self.input.poll_next_unpin(cx)) {
+        let batch = match self.restoring.as_mut() {
+            Some(RestoringPartitions { read, .. }) => {
+                let restored = ready!(read.poll_unpin(cx))?;
+                let Some(RestoringPartitions {
+                    batch, partitions, ..
+                }) = self.restoring.take()
+                else {
+                    unreachable!()
+                };
+                self.restore_partitions(partitions, restored);
+                Some(Ok(batch))
+            }
+            None => ready!(self.input.poll_next_unpin(cx)),
+        };
+        match batch {
             Some(Ok(batch)) => {
                 // Start the timer for compute time within this operator. It will be
                 // stopped when dropped.
                 let _timer = elapsed_compute.timer();
 
+                // Spilled partitions receiving new rows are read back first
+                let partitions = self.take_spilled_partitions(&batch)?;
+                if !partitions.is_empty() {
+                    self.restoring =
+                        Some(self.read_spilled_partitions(batch, partitions)?);
+                    return self.poll_next_inner(cx);
+                }
                 self.search_mode.update_partition_batch(
                     &mut self.input_buffer,
                     batch,
                     &self.window_expr,
                     &mut self.partition_buffers,
                 )?;
-                if let Some(batch) = self.compute_aggregates()? {
+                let output = self.compute_aggregates()?;
+                self.update_reservation()?;
+                if let Some(batch) = output {
                     return Poll::Ready(Some(Ok(batch)));
                 }
                 self.poll_next_inner(cx)
@@ -1083,6 +1164,9 @@ impl BoundedWindowAggStream {
                 let _timer = elapsed_compute.timer();
 
                 self.finished = true;
+                // Spilled partitions have no pending rows, so they do not
+                // produce any more output
+                self.spilled_partitions.clear();
                 for (_, partition_batch_state) in self.partition_buffers.iter_mut() {
                     partition_batch_state.is_end = true;
                 }
@@ -1094,6 +1178,172 @@ impl BoundedWindowAggStream {
         }
     }
 
+    /// Returns the memory used by the buffered input rows
+    fn buffered_memory_size(&self) -> usize {
+        get_record_batch_memory_size(&self.input_buffer)
+            + self
+                .partition_buffers
+                .values()
+                .map(|state| get_record_batch_memory_size(&state.record_batch))
+                .sum::<usize>()
+    }
+
+    /// Resizes the memory reservation to the buffered input rows, spilling
+    /// idle partitions if the reservation can not grow.
+    ///
+    /// The reservation is best-effort: the buffered rows are needed to
+    /// calculate the window results, so if they still do not fit (e.g. in
+    /// `Sorted` and `PartiallySorted` modes, which never spill, or when no
+    /// partition is idle), the reservation keeps its current size instead of
+    /// failing the query.
+    fn update_reservation(&mut self) -> Result<()> {
+        let size = self.buffered_memory_size();
+        if self.reservation.try_resize(size).is_err() && self.spill_manager.is_some() {
+            self.spill_idle_partitions()?;
+            // Shrinking never fails, and a failed growth keeps the current size
+            let _ = self.reservation.try_resize(self.buffered_memory_size());
+        }
+        Ok(())
+    }
+
+    /// Spills the buffered rows of all idle partitions to disk.
+    ///
+    /// A partition is idle if the results of all its rows have been calculated
+    /// and emitted for every window expression. Its buffered rows are only
+    /// needed again once new rows of the partition arrive. In `Linear` mode,
+    /// partitions never end before the input does, so without spilling the
+    /// rows buffered for window frames of idle partitions accumulate.
+    fn spill_idle_partitions(&mut self) -> Result<()> {
+        let Some(spill_manager) = &self.spill_manager else {
+            return Ok(());
+        };
+        let idle_partitions = self
+            .partition_buffers
+            .iter()
+            .filter(|(key, batch_state)| {
+                batch_state.record_batch.num_rows() > 0
+                    && self.window_agg_states.iter().all(|states| {
+                        states.get(*key).is_some_and(|WindowState { state, .. }| {
+                            !state.is_end
+                                && state.last_calculated_index
+                                    == batch_state.record_batch.num_rows()
+                                && state.out_col.is_empty()
+                                && state.n_row_result_missing == 0
+                        })
+                    })
+            })
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<_>>();
+
+        for key in idle_partitions {
+            let Some(mut batch_state) = self.partition_buffers.shift_remove(&key) else {
+                continue;
+            };
+            let window_states = self
+                .window_agg_states
+                .iter_mut()
+                .map(|states| states.shift_remove(&key))
+                .collect::<Option<Vec<_>>>()
+                .ok_or_else(|| {
+                    internal_datafusion_err!("Missing window state of idle partition")
+                })?;
+            let empty_batch = RecordBatch::new_empty(batch_state.record_batch.schema());
+            let batch = std::mem::replace(&mut batch_state.record_batch, empty_batch);
+            let Some(file) = spill_manager
+                .spill_record_batch_and_finish(&[batch], "BoundedWindowAggExec spill")?
+            else {
+                return internal_err!("Failed to spill idle partition");
+            };
+            self.spilled_partitions.insert(
+                key,
+                SpilledPartition {
+                    file,
+                    batch_state,
+                    window_states,
+                },
+            );
+        }
+        Ok(())
+    }
+
+    /// Removes and returns the spilled partitions that have rows in `batch`
+    fn take_spilled_partitions(
+        &mut self,
+        batch: &RecordBatch,
+    ) -> Result<Vec<(PartitionKey, SpilledPartition)>> {
+        let mut partitions = vec![];
+        if self.spilled_partitions.is_empty() {
+            return Ok(partitions);
+        }
+        let partition_bys =
+            evaluate_partition_by_column_values(batch, &self.window_expr)?;
+        for row in 0..batch.num_rows() {
+            if self.spilled_partitions.is_empty() {
+                break;
+            }
+            let key = get_row_at_idx(&partition_bys, row)?;
+            if let Some(partition) = self.spilled_partitions.remove(&key) {
+                partitions.push((key, partition));
+            }
+        }
+        Ok(partitions)
+    }
+
+    /// Starts reading back the buffered rows of spilled `partitions`, which
+    /// receive new rows from `batch`
+    fn read_spilled_partitions(
+        &self,
+        batch: RecordBatch,
+        partitions: Vec<(PartitionKey, SpilledPartition)>,
+    ) -> Result<RestoringPartitions> {
+        let spill_manager = self.spill_manager.as_ref().ok_or_else(|| {
+            internal_datafusion_err!("BoundedWindowAggStream can not read spill files")
+        })?;
+        let schema = self.input.schema();
+        let streams = partitions
+            .iter()
+            .map(|(_, partition)| {
+                spill_manager.read_spill_as_stream(partition.file.clone(), None)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let read = futures::future::try_join_all(streams.into_iter().map(|stream| {
+            let schema = Arc::clone(&schema);
+            async move {
+                let batches = stream.try_collect::<Vec<_>>().await?;
+                Ok(concat_batches(&schema, &batches)?)
+            }
+        }))
+        .boxed();
+        Ok(RestoringPartitions {
+            batch,
+            partitions,
+            read,
+        })
+    }
+
+    /// Puts spilled `partitions` back into the partition buffers, with their
+    /// buffered rows read back from disk
+    fn restore_partitions(
+        &mut self,
+        partitions: Vec<(PartitionKey, SpilledPartition)>,
+        batches: Vec<RecordBatch>,
+    ) {
+        for ((key, partition), batch) in partitions.into_iter().zip(batches) {
+            let SpilledPartition {
+                mut batch_state,
+                window_states,
+                ..
+            } = partition;
+            batch_state.record_batch = batch;
+            self.partition_buffers.insert(key.clone(), batch_state);
+            for (states, window_state) in
+                self.window_agg_states.iter_mut().zip(window_states)
+            {
+                states.insert(key.clone(), window_state);
+            }
+        }
+    }
+
     /// Prunes the sections of the record batch (for each partition)
     /// that we no longer need to calculate the window function result.
     fn prune_partition_batches(&mut self) {
@@ -1278,7 +1528,7 @@ mod tests {
     use crate::{ExecutionPlan, displayable, execute_stream};
 
     use arrow::array::{
-        RecordBatch,
+        Int64Array, RecordBatch, UInt64Array,
         builder::{Int64Builder, UInt64Builder},
     };
     use arrow::compute::SortOptions;
@@ -1286,6 +1536,7 @@ mod tests {
     use datafusion_common::test_util::batches_to_string;
     use datafusion_common::{Result, ScalarValue, exec_datafusion_err};
     use datafusion_execution::config::SessionConfig;
+    use datafusion_execution::runtime_env::RuntimeEnvBuilder;
     use datafusion_execution::{
         RecordBatchStream, SendableRecordBatchStream, TaskContext,
     };
@@ -1869,4 +2120,108 @@ mod tests {
         ));
         Ok(())
     }
+
+    /// Sliding `COUNT` over 6400 rows in 8 partitions, where each input batch
+    /// belongs to a single partition. All rows of a partition stay within its
+    /// window frame, so they remain buffered.
+    ///
+    /// In `Linear` mode the input is ordered by `sn` and the partitions are
+    /// interleaved. In `Sorted` mode the input is ordered by `hash, sn`, so
+    /// the rows of each partition are contiguous.
+    fn bounded_window_exec_partitioned_count(
+        mode: InputOrderMode,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let schema = test_schema();
+        let sorted = matches!(mode, InputOrderMode::Sorted);
+        let batches = (0..64)
+            .map(|batch| {
+                let rows = (batch * 100)..(batch * 100 + 100);
+                let partition = if sorted { batch / 8 } else { batch % 8 };
+                RecordBatch::try_new(
+                    Arc::clone(&schema),
+                    vec![
+                        Arc::new(UInt64Array::from_iter_values(rows)),
+                        Arc::new(Int64Array::from_value(partition as i64, 100)),
+                    ],
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let sort_hash = PhysicalSortExpr::new_default(col("hash", &schema)?);
+        let sort_sn = PhysicalSortExpr::new_default(col("sn", &schema)?);
+        let ordering = if sorted {
+            LexOrdering::new([sort_hash, sort_sn.clone()])
+        } else {
+            LexOrdering::new([sort_sn.clone()])
+        };
+        let input = TestMemoryExec::try_new(&[batches], Arc::clone(&schema), None)?
+            .try_with_sort_information(vec![ordering.unwrap()])?;
+        let window_expr = create_window_expr(
+            &WindowFunctionDefinition::AggregateUDF(count_udaf()),
+            "count".to_string(),
+            &[col("sn", &schema)?],
+            &[col("hash", &schema)?],
+            &[sort_sn],
+            Arc::new(WindowFrame::new_bounds(
+                WindowFrameUnits::Rows,
+                WindowFrameBound::Preceding(ScalarValue::UInt64(Some(10_000))),
+                WindowFrameBound::CurrentRow,
+            )),
+            Arc::clone(&schema),
+            false,
+            false,
+            None,
+        )?;
+        Ok(Arc::new(BoundedWindowAggExec::try_new(
+            vec![window_expr],
+            Arc::new(TestMemoryExec::update_cache(&Arc::new(input))),
+            mode,
+            false,
+        )?))
+    }
+
+    /// Runs `window` with the given memory limit
+    async fn collect_with_memory_limit(
+        window: &Arc<dyn ExecutionPlan>,
+        memory_limit: usize,
+    ) -> Result<Vec<RecordBatch>> {
+        let runtime = RuntimeEnvBuilder::new()
+            .with_memory_limit(memory_limit, 1.0)
+            .build_arc()?;
+        let task_ctx = Arc::new(TaskContext::default().with_runtime(runtime));
+        collect(window.execute(0, task_ctx)?).await
+    }
+
+    #[tokio::test]
+    async fn bounded_window_exec_linear_mode_spills_idle_partitions() -> Result<()> {
+        let window = bounded_window_exec_partitioned_count(InputOrderMode::Linear)?;
+        let expected =
+            collect(window.execute(0, Arc::new(TaskContext::default()))?).await?;
+        assert_eq!(window.metrics().unwrap().spill_count(), Some(0));
+
+        let window = bounded_window_exec_partitioned_count(InputOrderMode::Linear)?;
+        let batches = collect_with_memory_limit(&window, 32 * 1024).await?;
+        let metrics = window.metrics().unwrap();
+        assert!(metrics.spill_count().unwrap() > 0);
+        assert!(metrics.spilled_rows().unwrap() > 0);
+        assert_eq!(batches_to_string(&batches), batches_to_string(&expected));
+        Ok(())
+    }
+
+    /// `Sorted` mode never spills, so a memory limit smaller than a single
+    /// partition must not fail the query
+    #[tokio::test]
+    async fn bounded_window_exec_sorted_mode_under_memory_limit() -> Result<()> {
+        let window = bounded_window_exec_partitioned_count(InputOrderMode::Sorted)?;
+        let expected =
+            collect(window.execute(0, Arc::new(TaskContext::default()))?).await?;
+
+        let window = bounded_window_exec_partitioned_count(InputOrderMode::Sorted)?;
+        let batches = collect_with_memory_limit(&window, 1024).await?;
+        assert_eq!(
+            window.metrics().unwrap().spill_count().unwrap_or_default(),
+            0
+        );
+        assert_eq!(batches_to_string(&batches), batches_to_string(&expected));
+        Ok(())
+    }
 }
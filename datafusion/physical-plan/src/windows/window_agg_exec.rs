@@ -17,13 +17,21 @@
 
 //! Stream and channel implementations for window function expressions.
 
+use std::ops::Range;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
 
 use super::utils::create_schema;
+use crate::EmptyRecordBatchStream;
 use crate::execution_plan::{CardinalityEffect, EmissionType};
-use crate::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet};
+use crate::metrics::{
+    BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet, SpillMetrics, Time,
+};
+use crate::spill::SpillManager;
+use crate::spill::get_record_batch_memory_size;
+use crate::spill::in_progress_spill_file::InProgressSpillFile;
+use crate::stream::RecordBatchStreamAdapter;
 use crate::windows::{
     calc_requirements, get_ordered_partition_by_indices, get_partition_by_sort_exprs,
     window_equivalence_properties,
@@ -41,16 +49,32 @@ use arrow::error::ArrowError;
 use arrow::record_batch::RecordBatch;
 use datafusion_common::stats::Precision;
 use datafusion_common::tree_node::TreeNodeRecursion;
-use datafusion_common::utils::{evaluate_partition_ranges, transpose};
-use datafusion_common::{Result, assert_eq_or_internal_err};
+use datafusion_common::utils::{evaluate_partition_ranges, get_row_at_idx, transpose};
+use datafusion_common::{
+    Result, ScalarValue, assert_eq_or_internal_err, internal_datafusion_err,
+};
 use datafusion_execution::TaskContext;
+use datafusion_execution::memory_pool::{MemoryConsumer, MemoryReservation};
 use datafusion_physical_expr_common::sort_expr::{
     OrderingRequirements, PhysicalSortExpr,
 };
 
-use futures::{Stream, StreamExt, ready};
+use futures::{Stream, StreamExt, ready, stream};
 
 /// Window execution plan
+///
+/// The input is buffered until it is exhausted. Under memory pressure, the
+/// buffered input is spilled to disk and the window functions are then
+/// evaluated over the spilled input one partition at a time (see
+/// [`WindowAggStream`]).
+///
+/// # Limitations
+///
+/// Each partition is still evaluated in memory, so a single partition larger
+/// than the memory limit, or the whole input of a window without
+/// `PARTITION BY`, fails with [`DataFusionError::ResourcesExhausted`].
+///
+/// [`DataFusionError::ResourcesExhausted`]: datafusion_common::DataFusionError::ResourcesExhausted
 #[derive(Debug, Clone)]
 pub struct WindowAggExec {
     /// Input plan
@@ -277,7 +301,19 @@ impl ExecutionPlan for WindowAggExec {
         partition: usize,
         context: Arc<TaskContext>,
     ) -> Result<SendableRecordBatchStream> {
-        let input = self.input.execute(partition, context)?;
+        let input = self.input.execute(partition, Arc::clone(&context))?;
+        let can_spill = context.runtime_env().disk_manager.tmp_files_enabled();
+        let reservation = MemoryConsumer::new(format!("WindowAggStream[{partition}]"))
+            .with_can_spill(can_spill)
+            .register(context.memory_pool());
+        let spill_manager = can_spill.then(|| {
+            SpillManager::new(
+                context.runtime_env(),
+                SpillMetrics::new(&self.metrics, partition),
+                self.input.schema(),
+            )
+            .with_compression_type(context.session_config().spill_compression())
+        });
         let stream = Box::pin(WindowAggStream::new(
             Arc::clone(&self.schema),
             self.window_expr.clone(),
@@ -285,6 +321,8 @@ impl ExecutionPlan for WindowAggExec {
             BaselineMetrics::new(&self.metrics, partition),
             self.partition_by_sort_keys()?,
             self.ordered_partition_by_indices.clone(),
+            reservation,
+            spill_manager,
         )?);
         Ok(stream)
     }
@@ -329,6 +367,15 @@ fn compute_window_aggregates(
 }
 
 /// stream for window aggregation plan
+///
+/// All input batches are buffered until the input is exhausted, as window
+/// frames may span entire partitions. The buffered batches are tracked by a
+/// spillable [`MemoryReservation`]. When it cannot grow, the buffered batches
+/// are spilled to disk (if a [`SpillManager`] is available). Once the input is
+/// exhausted, the spilled batches are read back in input order and, since the
+/// input is sorted on the `PARTITION BY` columns, the window functions are
+/// evaluated one partition at a time, so only a single partition has to fit
+/// in memory.
 pub struct WindowAggStream {
     schema: SchemaRef,
     input: SendableRecordBatchStream,
@@ -338,10 +385,19 @@ pub struct WindowAggStream {
     partition_by_sort_keys: Vec<PhysicalSortExpr>,
     baseline_metrics: BaselineMetrics,
     ordered_partition_by_indices: Vec<usize>,
+    /// Memory reservation for the buffered input batches
+    reservation: MemoryReservation,
+    /// Spills buffered batches to disk, `None` if spilling is disabled
+    spill_manager: Option<SpillManager>,
+    /// Batches spilled so far. They precede the batches in `batches`.
+    in_progress_spill_file: Option<InProgressSpillFile>,
+    /// Output once the input is exhausted, if any batches were spilled
+    spilled_output: Option<SendableRecordBatchStream>,
 }
 
 impl WindowAggStream {
     /// Create a new WindowAggStream
+    #[expect(clippy::too_many_arguments)]
     pub fn new(
         schema: SchemaRef,
         window_expr: Vec<Arc<dyn WindowExpr>>,
@@ -349,6 +405,8 @@ impl WindowAggStream {
         baseline_metrics: BaselineMetrics,
         partition_by_sort_keys: Vec<PhysicalSortExpr>,
         ordered_partition_by_indices: Vec<usize>,
+        reservation: MemoryReservation,
+        spill_manager: Option<SpillManager>,
     ) -> Result<Self> {
         // In WindowAggExec all partition by columns should be ordered.
         assert_eq_or_internal_err!(
@@ -365,6 +423,10 @@ impl WindowAggStream {
             baseline_metrics,
             partition_by_sort_keys,
             ordered_partition_by_indices,
+            reservation,
+            spill_manager,
+            in_progress_spill_file: None,
+            spilled_output: None,
         })
     }
 
@@ -376,18 +438,139 @@ impl WindowAggStream {
         if batch.num_rows() == 0 {
             return Ok(None);
         }
+        self.partition_evaluator().evaluate(&batch).map(Some)
+    }
+
+    fn partition_evaluator(&self) -> PartitionEvaluator {
+        PartitionEvaluator {
+            schema: Arc::clone(&self.schema),
+            window_expr: self.window_expr.clone(),
+            partition_by_sort_keys: self
+                .ordered_partition_by_indices
+                .iter()
+                .map(|idx| self.partition_by_sort_keys[*idx].clone())
+                .collect(),
+        }
+    }
+
+    /// Buffers `batch`, spilling the buffered batches to disk if the memory
+    /// reservation cannot grow
+    fn buffer_batch(&mut self, batch: RecordBatch) -> Result<()> {
+        let size = get_record_batch_memory_size(&batch);
+        match self.reservation.try_grow(size) {
+            Ok(()) => {
+                self.batches.push(batch);
+                Ok(())
+            }
+            Err(e) if self.spill_manager.is_none() => Err(e),
+            Err(_) => {
+                self.spill_batches()?;
+                if self.reservation.try_grow(size).is_ok() {
+                    self.batches.push(batch);
+                } else {
+                    self.spill_file()?.append_batch(&batch)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Appends all buffered batches to the spill file and frees their memory
+    fn spill_batches(&mut self) -> Result<()> {
+        let batches = std::mem::take(&mut self.batches);
+        let spill_file = self.spill_file()?;
+        for batch in &batches {
+            spill_file.append_batch(batch)?;
+        }
+        self.reservation.free();
+        Ok(())
+    }
+
+    fn spill_file(&mut self) -> Result<&mut InProgressSpillFile> {
+        match &mut self.in_progress_spill_file {
+            Some(file) => Ok(file),
+            file @ None => {
+                let spill_manager = self.spill_manager.as_ref().ok_or_else(|| {
+                    internal_datafusion_err!("WindowAggStream can not spill")
+                })?;
+                Ok(file.insert(
+                    spill_manager.create_in_progress_file("WindowAggExec spill")?,
+                ))
+            }
+        }
+    }
+
+    /// Returns a stream evaluating the window functions one partition at a
+    /// time over the spilled input
+    fn evaluate_spilled(&mut self) -> Result<SendableRecordBatchStream> {
+        // Spill the remaining buffered batches as well, so that the memory
+        // reservation only has to account for the partition being evaluated
+        self.spill_batches()?;
+        let spill_manager = self.spill_manager.as_ref().ok_or_else(|| {
+            internal_datafusion_err!("WindowAggStream can not read spill files")
+        })?;
+        let input = match self.in_progress_spill_file.take() {
+            Some(mut file) => match file.finish()? {
+                Some(file) => spill_manager.read_spill_as_stream(file, None)?,
+                None => Box::pin(EmptyRecordBatchStream::new(self.input.schema())),
+            },
+            None => Box::pin(EmptyRecordBatchStream::new(self.input.schema())),
+        };
+
+        let state = SpilledEvaluationState {
+            input,
+            evaluator: self.partition_evaluator(),
+            pending: vec![],
+            reservation: self.reservation.take(),
+            elapsed_compute: self.baseline_metrics.elapsed_compute().clone(),
+        };
+        let output = stream::try_unfold(state, |mut state| async move {
+            let output = state.next_output().await?;
+            Ok(output.map(|batch| (batch, state)))
+        });
+
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            Arc::clone(&self.schema),
+            output,
+        )))
+    }
+}
+
+/// Evaluates the window expressions over batches containing whole partitions
+struct PartitionEvaluator {
+    schema: SchemaRef,
+    window_expr: Vec<Arc<dyn WindowExpr>>,
+    /// `PARTITION BY` sort keys, in input ordering
+    partition_by_sort_keys: Vec<PhysicalSortExpr>,
+}
 
+impl PartitionEvaluator {
+    /// Returns the partition boundaries of `batch`
+    fn partition_ranges(&self, batch: &RecordBatch) -> Result<Vec<Range<usize>>> {
         let partition_by_sort_keys = self
-            .ordered_partition_by_indices
+            .partition_by_sort_keys
+            .iter()
+            .map(|key| key.evaluate_to_sort_column(batch))
+            .collect::<Result<Vec<_>>>()?;
+        evaluate_partition_ranges(batch.num_rows(), &partition_by_sort_keys)
+    }
+
+    /// Returns the `PARTITION BY` values of `row` in `batch`
+    fn partition_key(&self, batch: &RecordBatch, row: usize) -> Result<Vec<ScalarValue>> {
+        let columns = self
+            .partition_by_sort_keys
             .iter()
-            .map(|idx| self.partition_by_sort_keys[*idx].evaluate_to_sort_column(&batch))
+            .map(|key| key.expr.evaluate(batch)?.into_array(batch.num_rows()))
             .collect::<Result<Vec<_>>>()?;
-        let partition_points =
-            evaluate_partition_ranges(batch.num_rows(), &partition_by_sort_keys)?;
+        get_row_at_idx(&columns, row)
+    }
 
+    /// Evaluates the window expressions for each partition of `batch` and
+    /// appends the results to its columns
+    fn evaluate(&self, batch: &RecordBatch) -> Result<RecordBatch> {
         let mut partition_results = vec![];
         // Calculate window cols
-        for partition_point in partition_points {
+        for partition_point in self.partition_ranges(batch)? {
             let length = partition_point.end - partition_point.start;
             partition_results.push(compute_window_aggregates(
                 &self.window_expr,
@@ -407,10 +590,87 @@ impl WindowAggStream {
         let mut batch_columns = batch.columns().to_vec();
         // calculate window cols
         batch_columns.extend_from_slice(&columns);
-        Ok(Some(RecordBatch::try_new(
+        Ok(RecordBatch::try_new(
             Arc::clone(&self.schema),
             batch_columns,
-        )?))
+        )?)
+    }
+}
+
+/// State of the partition at a time evaluation of spilled input
+struct SpilledEvaluationState {
+    input: SendableRecordBatchStream,
+    evaluator: PartitionEvaluator,
+    /// Rows of the partition(s) not known to be complete yet
+    pending: Vec<RecordBatch>,
+    reservation: MemoryReservation,
+    elapsed_compute: Time,
+}
+
+impl SpilledEvaluationState {
+    /// Reads input until at least one partition is complete, and returns the
+    /// window results for all complete partitions.
+    ///
+    /// Returns `None` once all partitions have been evaluated.
+    async fn next_output(&mut self) -> Result<Option<RecordBatch>> {
+        loop {
+            let Some(batch) = self.input.next().await.transpose()? else {
+                if self.pending.is_empty() {
+                    return Ok(None);
+                }
+                let elapsed_compute = self.elapsed_compute.clone();
+                let _timer = elapsed_compute.timer();
+                let output = self.evaluate_pending();
+                self.reservation.free();
+                return output.map(Some);
+            };
+            if batch.num_rows() == 0 {
+                continue;
+            }
+            // A partition that does not fit into memory can not be evaluated
+            self.reservation
+                .try_grow(get_record_batch_memory_size(&batch))?;
+
+            let elapsed_compute = self.elapsed_compute.clone();
+            let _timer = elapsed_compute.timer();
+            let ranges = self.evaluator.partition_ranges(&batch)?;
+            // The first partition of `batch` continues the last pending one if
+            // their partition keys are equal
+            let continues_pending = match self.pending.last() {
+                Some(last) => {
+                    self.evaluator.partition_key(last, last.num_rows() - 1)?
+                        == self.evaluator.partition_key(&batch, 0)?
+                }
+                None => true,
+            };
+
+            // Only the last partition of `batch` may continue in the next batch
+            let last = ranges
+                .last()
+                .cloned()
+                .unwrap_or_else(|| 0..batch.num_rows());
+            if ranges.len() == 1 && continues_pending {
+                self.pending.push(batch);
+                continue;
+            }
+
+            // All pending rows and all rows before the last partition belong
+            // to complete partitions
+            self.pending.push(batch.slice(0, last.start));
+            let output = self.evaluate_pending()?;
+            self.pending
+                .push(batch.slice(last.start, last.end - last.start));
+            self.reservation.try_resize(
+                self.pending.iter().map(get_record_batch_memory_size).sum(),
+            )?;
+            return Ok(Some(output));
+        }
+    }
+
+    fn evaluate_pending(&mut self) -> Result<RecordBatch> {
+        let pending = std::mem::take(&mut self.pending);
+        let batch = concat_batches(&self.input.schema(), &pending)?;
+        self.evaluator.evaluate(&batch)
     }
 }
 
@@ -437,12 +697,24 @@ impl WindowAggStream {
         }
 
         loop {
+            if let Some(output) = self.spilled_output.as_mut() {
+                let poll = output.poll_next_unpin(cx);
+                if matches!(poll, Poll::Ready(None)) {
+                    self.finished = true;
+                }
+                return poll;
+            }
+
             return Poll::Ready(Some(match ready!(self.input.poll_next_unpin(cx)) {
                 Some(Ok(batch)) => {
-                    self.batches.push(batch);
+                    self.buffer_batch(batch)?;
                     continue;
                 }
                 Some(Err(e)) => Err(e),
+                None if self.in_progress_spill_file.is_some() => {
+                    self.spilled_output = Some(self.evaluate_spilled()?);
+                    continue;
+                }
                 None => {
                     let Some(result) = self.compute_aggregates()? else {
                         return Poll::Ready(None);
@@ -468,21 +740,30 @@ impl RecordBatchStream for WindowAggStream {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::common::collect;
     use crate::test::TestMemoryExec;
     use crate::windows::create_window_expr;
+    use arrow::array::Int64Array;
+    use arrow::compute::SortOptions;
     use arrow::datatypes::{DataType, Field, Schema};
-    use datafusion_common::ScalarValue;
+    use datafusion_common::test_util::batches_to_string;
+    use datafusion_common::{DataFusionError, ScalarValue, assert_contains};
+    use datafusion_execution::disk_manager::{DiskManagerBuilder, DiskManagerMode};
+    use datafusion_execution::runtime_env::RuntimeEnvBuilder;
     use datafusion_expr::{
         WindowFrame, WindowFrameBound, WindowFrameUnits, WindowFunctionDefinition,
     };
     use datafusion_functions_aggregate::count::count_udaf;
+    use datafusion_functions_aggregate::sum::sum_udaf;
+    use datafusion_physical_expr::LexOrdering;
+    use datafusion_physical_expr::expressions::col;
 
     #[test]
     fn test_window_agg_cardinality_effect() -> Result<()> {
         let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int64, true)]));
         let input: Arc<dyn ExecutionPlan> =
             Arc::new(TestMemoryExec::try_new(&[], Arc::clone(&schema), None)?);
-        let args = vec![crate::expressions::col("a", &schema)?];
+        let args = vec![col("a", &schema)?];
         let window_expr = create_window_expr(
             &WindowFunctionDefinition::AggregateUDF(count_udaf()),
             "count(a)".to_string(),
@@ -507,4 +788,122 @@ mod tests {
         ));
         Ok(())
     }
+
+    /// `SUM(b) OVER (PARTITION BY a)` over 2000 rows sorted on `a`, with
+    /// partitions of 150 rows spanning multiple batches
+    fn window_sum_by_partition() -> Result<Arc<dyn ExecutionPlan>> {
+        window_sum(true)
+    }
+
+    /// `SUM(b) OVER ()` over the rows of [`window_sum_by_partition`]
+    fn window_sum_without_partition() -> Result<Arc<dyn ExecutionPlan>> {
+        window_sum(false)
+    }
+
+    fn window_sum(partition_by: bool) -> Result<Arc<dyn ExecutionPlan>> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int64, false),
+            Field::new("b", DataType::Int64, false),
+        ]));
+        let batches = (0..20)
+            .map(|batch| {
+                let rows = (batch * 100)..(batch * 100 + 100);
+                RecordBatch::try_new(
+                    Arc::clone(&schema),
+                    vec![
+                        Arc::new(Int64Array::from_iter_values(
+                            rows.clone().map(|r| r / 150),
+                        )),
+                        Arc::new(Int64Array::from_iter_values(rows)),
+                    ],
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let sort_a = PhysicalSortExpr::new(col("a", &schema)?, SortOptions::default());
+        let input = TestMemoryExec::try_new(&[batches], Arc::clone(&schema), None)?
+            .try_with_sort_information(vec![
+                LexOrdering::new([sort_a.clone()]).unwrap(),
+            ])?;
+        let partition_by_exprs = if partition_by {
+            vec![col("a", &schema)?]
+        } else {
+            vec![]
+        };
+        let window_expr = create_window_expr(
+            &WindowFunctionDefinition::AggregateUDF(sum_udaf()),
+            "sum(b)".to_string(),
+            &[col("b", &schema)?],
+            &partition_by_exprs,
+            &[],
+            Arc::new(WindowFrame::new(None)),
+            Arc::clone(&schema),
+            false,
+            false,
+            None,
+        )?;
+        Ok(Arc::new(WindowAggExec::try_new(
+            vec![window_expr],
+            Arc::new(TestMemoryExec::update_cache(&Arc::new(input))),
+            false,
+        )?))
+    }
+
+    fn task_ctx(memory_limit: Option<usize>, disk_enabled: bool) -> Arc<TaskContext> {
+        let mut runtime = RuntimeEnvBuilder::new();
+        if let Some(memory_limit) = memory_limit {
+            runtime = runtime.with_memory_limit(memory_limit, 1.0);
+        }
+        if !disk_enabled {
+            runtime = runtime.with_disk_manager_builder(
+                DiskManagerBuilder::default().with_mode(DiskManagerMode::Disabled),
+            );
+        }
+        Arc::new(TaskContext::default().with_runtime(runtime.build_arc().unwrap()))
+    }
+
+    #[tokio::test]
+    async fn test_window_agg_spills_partitions() -> Result<()> {
+        let window = window_sum_by_partition()?;
+        let expected = collect(window.execute(0, task_ctx(None, true))?).await?;
+        assert_eq!(window.metrics().unwrap().spill_count(), Some(0));
+
+        let window = window_sum_by_partition()?;
+        let batches =
+            collect(window.execute(0, task_ctx(Some(16 * 1024), true))?).await?;
+        let metrics = window.metrics().unwrap();
+        assert!(metrics.spill_count().unwrap() > 0);
+        assert!(metrics.spilled_rows().unwrap() > 0);
+        assert_eq!(batches_to_string(&batches), batches_to_string(&expected));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_window_agg_exceeds_memory_without_disk() -> Result<()> {
+        let window = window_sum_by_partition()?;
+        let err = collect(window.execute(0, task_ctx(Some(16 * 1024), false))?)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.find_root(),
+            DataFusionError::ResourcesExhausted(_)
+        ));
+        assert_contains!(err.to_string(), "WindowAggStream[0]");
+        Ok(())
+    }
+
+    /// Spilling does not help when the whole input is a single partition, as
+    /// each partition is evaluated in memory
+    #[tokio::test]
+    async fn test_window_agg_without_partition_exceeds_memory() -> Result<()> {
+        let window = window_sum_without_partition()?;
+        let err = collect(window.execute(0, task_ctx(Some(16 * 1024), true))?)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.find_root(),
+            DataFusionError::ResourcesExhausted(_)
+        ));
+        assert!(window.metrics().unwrap().spill_count().unwrap() > 0);
+        Ok(())
+    }
 }
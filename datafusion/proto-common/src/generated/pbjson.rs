@@ -4871,6 +4871,7 @@ impl serde::Serialize for JoinType {
             Self::Rightanti => "RIGHTANTI",
             Self::Leftmark => "LEFTMARK",
             Self::Rightmark => "RIGHTMARK",
+            Self::Leftasof => "LEFTASOF",
         };
         serializer.serialize_str(variant)
     }
@@ -4892,6 +4893,7 @@ impl<'de> serde::Deserialize<'de> for JoinType {
             "RIGHTANTI",
             "LEFTMARK",
             "RIGHTMARK",
+            "LEFTASOF",
         ];
 
         struct GeneratedVisitor;
@@ -4942,6 +4944,7 @@ impl<'de> serde::Deserialize<'de> for JoinType {
                     "RIGHTANTI" => Ok(JoinType::Rightanti),
                     "LEFTMARK" => Ok(JoinType::Leftmark),
                     "RIGHTMARK" => Ok(JoinType::Rightmark),
+                    "LEFTASOF" => Ok(JoinType::Leftasof),
                     _ => Err(serde::de::Error::unknown_variant(value, FIELDS)),
                 }
             }
@@ -1012,6 +1012,7 @@ pub enum JoinType {
     Rightanti = 7,
     Leftmark = 8,
     Rightmark = 9,
+    Leftasof = 10,
 }
 impl JoinType {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -1030,6 +1031,7 @@ impl JoinType {
             Self::Rightanti => "RIGHTANTI",
             Self::Leftmark => "LEFTMARK",
             Self::Rightmark => "RIGHTMARK",
+            Self::Leftasof => "LEFTASOF",
         }
     }
     /// Creates an enum from field names used in the ProtoBuf definition.
@@ -1045,6 +1047,7 @@ impl JoinType {
             "RIGHTANTI" => Some(Self::Rightanti),
             "LEFTMARK" => Some(Self::Leftmark),
             "RIGHTMARK" => Some(Self::Rightmark),
+            "LEFTASOF" => Some(Self::Leftasof),
             _ => None,
         }
     }
@@ -209,6 +209,7 @@ impl From<protobuf::JoinType> for JoinType {
             protobuf::JoinType::Rightanti => JoinType::RightAnti,
             protobuf::JoinType::Leftmark => JoinType::LeftMark,
             protobuf::JoinType::Rightmark => JoinType::RightMark,
+            protobuf::JoinType::Leftasof => JoinType::LeftAsOf,
         }
     }
 }
@@ -536,7 +536,7 @@ impl AsLogicalPlan for LogicalPlanNode {
                         FileFormatType::Avro(..) => {
                             #[cfg(feature = "avro")]
                             {
-                                Arc::new(AvroFormat)
+                                Arc::new(AvroFormat::default())
                             }
                             #[cfg(not(feature = "avro"))]
                             {
@@ -759,6 +759,7 @@ impl AsLogicalPlan for LogicalPlanNode {
                     input: Arc::new(plan),
                     or_replace: create_view.or_replace,
                     definition,
+                    materialized: false,
                 })))
             }
             LogicalPlanType::CreateCatalogSchema(create_catalog_schema) => {
@@ -1686,12 +1687,19 @@ impl AsLogicalPlan for LogicalPlanNode {
                     )),
                 })
             }
+            LogicalPlan::Ddl(DdlStatement::CreateView(CreateView {
+                materialized: true,
+                ..
+            })) => Err(proto_error(
+                "LogicalPlan serde is not yet implemented for materialized views",
+            )),
             LogicalPlan::Ddl(DdlStatement::CreateView(CreateView {
                 name,
                 input,
                 or_replace,
                 definition,
                 temporary,
+                materialized: false,
             })) => Ok(LogicalPlanNode {
                 logical_plan_type: Some(LogicalPlanType::CreateView(Box::new(
                     protobuf::CreateViewNode {
@@ -1892,6 +1900,14 @@ impl AsLogicalPlan for LogicalPlanNode {
             LogicalPlan::Ddl(DdlStatement::DropFunction(_)) => Err(proto_error(
                 "LogicalPlan serde is not yet implemented for DropFunction",
             )),
+            LogicalPlan::Ddl(DdlStatement::AnalyzeTable(_)) => Err(proto_error(
+                "LogicalPlan serde is not yet implemented for AnalyzeTable",
+            )),
+            LogicalPlan::Ddl(DdlStatement::RefreshMaterializedView(_)) => {
+                Err(proto_error(
+                    "LogicalPlan serde is not yet implemented for RefreshMaterializedView",
+                ))
+            }
             LogicalPlan::Statement(_) => Err(proto_error(
                 "LogicalPlan serde is not yet implemented for Statement",
             )),
@@ -1904,7 +1920,7 @@ impl AsLogicalPlan for LogicalPlanNode {
             }) => {
                 let input =
                     LogicalPlanNode::try_from_logical_plan(input, extension_codec)?;
-                let dml_type: dml_node::Type = op.into();
+                let dml_type: dml_node::Type = op.try_into()?;
                 Ok(LogicalPlanNode {
                     logical_plan_type: Some(LogicalPlanType::Dml(Box::new(DmlNode {
                         input: Some(Box::new(input)),
@@ -1945,6 +1961,9 @@ impl AsLogicalPlan for LogicalPlanNode {
             LogicalPlan::DescribeTable(_) => Err(proto_error(
                 "LogicalPlan serde is not yet implemented for DescribeTable",
             )),
+            LogicalPlan::Sample(_) => Err(proto_error(
+                "LogicalPlan serde is not yet implemented for Sample",
+            )),
             LogicalPlan::RecursiveQuery(recursive) => {
                 let static_term = LogicalPlanNode::try_from_logical_plan(
                     recursive.static_term.as_ref(),
@@ -726,6 +726,7 @@ impl From<JoinType> for protobuf::JoinType {
             JoinType::RightAnti => protobuf::JoinType::Rightanti,
             JoinType::LeftMark => protobuf::JoinType::Leftmark,
             JoinType::RightMark => protobuf::JoinType::Rightmark,
+            JoinType::LeftAsOf => protobuf::JoinType::Leftasof,
         }
     }
 }
@@ -748,9 +749,11 @@ impl From<NullEquality> for protobuf::NullEquality {
     }
 }
 
-impl From<&WriteOp> for protobuf::dml_node::Type {
-    fn from(t: &WriteOp) -> Self {
-        match t {
+impl TryFrom<&WriteOp> for protobuf::dml_node::Type {
+    type Error = Error;
+
+    fn try_from(t: &WriteOp) -> Result<Self, Self::Error> {
+        Ok(match t {
             WriteOp::Insert(InsertOp::Append) => protobuf::dml_node::Type::InsertAppend,
             WriteOp::Insert(InsertOp::Overwrite) => {
                 protobuf::dml_node::Type::InsertOverwrite
@@ -760,7 +763,12 @@ impl From<&WriteOp> for protobuf::dml_node::Type {
             WriteOp::Update => protobuf::dml_node::Type::Update,
             WriteOp::Ctas => protobuf::dml_node::Type::Ctas,
             WriteOp::Truncate => protobuf::dml_node::Type::Truncate,
-        }
+            WriteOp::Merge(_) => {
+                return Err(Error::NotImplemented(
+                    "LogicalPlan serde is not yet implemented for MERGE".to_string(),
+                ));
+            }
+        })
     }
 }
 
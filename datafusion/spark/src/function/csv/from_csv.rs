@@ -0,0 +1,270 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, AsArray, StringArray, StructArray, new_empty_array};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Field, FieldRef, Fields};
+use datafusion_common::{Result, ScalarValue, exec_err, internal_err};
+use datafusion_expr::{
+    ColumnarValue, ReturnFieldArgs, ScalarFunctionArgs, ScalarUDFImpl, Signature,
+    TypeSignature, Volatility,
+};
+
+use super::options::CsvOptions;
+use crate::function::error_utils::invalid_arg_count_exec_err;
+use crate::function::schema_utils::{as_nullable, parse_ddl_schema};
+use crate::function::utils::{ParseMode, options_from_map};
+
+/// Spark-compatible `from_csv` expression
+///
+/// <https://spark.apache.org/docs/latest/api/sql/index.html#from_csv>
+///
+/// Parses a CSV record into a struct described by a DDL schema string, e.g.
+/// `from_csv('1, 0.8', 'a INT, b DOUBLE')`.
+///
+/// `from_csv(csv_string, schema[, options]) -> Struct`
+///
+/// The `sep`, `quote`, `escape`, `nullValue` and `mode` options are supported:
+///
+/// - `PERMISSIVE` (default): fields that cannot be converted to the schema
+///   type, and fields missing from the record, are set to NULL
+/// - `FAILFAST`: records that do not match the schema raise an error
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct SparkFromCsv {
+    signature: Signature,
+}
+
+impl Default for SparkFromCsv {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SparkFromCsv {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(
+                vec![TypeSignature::String(2), TypeSignature::Any(3)],
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for SparkFromCsv {
+    fn name(&self) -> &str {
+        "from_csv"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        internal_err!("return_field_from_args should be used instead")
+    }
+
+    fn return_field_from_args(&self, args: ReturnFieldArgs) -> Result<FieldRef> {
+        if !(2..=3).contains(&args.arg_fields.len()) {
+            return Err(invalid_arg_count_exec_err(
+                self.name(),
+                (2, 3),
+                args.arg_fields.len(),
+            ));
+        }
+
+        let Some(schema) =
+            args.scalar_arguments[1].and_then(|sv| sv.try_as_str().flatten())
+        else {
+            return exec_err!(
+                "{} requires its second argument to be a constant schema string",
+                self.name()
+            );
+        };
+
+        let data_type = as_nullable(parse_ddl_schema(schema)?);
+        let DataType::Struct(fields) = &data_type else {
+            return exec_err!(
+                "{} requires a struct schema, got {}",
+                self.name(),
+                data_type
+            );
+        };
+        if let Some(field) = fields.iter().find(|f| f.data_type().is_nested()) {
+            return exec_err!(
+                "{} does not support the type {} of field {}",
+                self.name(),
+                field.data_type(),
+                field.name()
+            );
+        }
+        Ok(Arc::new(Field::new(self.name(), data_type, true)))
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        let options = match args.args.get(2) {
+            Some(options) => CsvOptions::from_options(
+                self.name(),
+                &options_from_map(self.name(), options)?,
+            )?,
+            None => CsvOptions::default(),
+        };
+
+        let csv_array = args.args[0].to_array(args.number_rows)?;
+        let csv_array = cast(&csv_array, &DataType::Utf8)?;
+        let result = from_csv_inner(
+            csv_array.as_string::<i32>(),
+            args.return_field.data_type(),
+            &options,
+        )?;
+
+        Ok(ColumnarValue::Array(result))
+    }
+}
+
+fn from_csv_inner(
+    csv_array: &StringArray,
+    data_type: &DataType,
+    options: &CsvOptions,
+) -> Result<ArrayRef> {
+    let DataType::Struct(fields) = data_type else {
+        return internal_err!("from_csv expects a struct schema, got {data_type}");
+    };
+    if csv_array.is_empty() {
+        return Ok(new_empty_array(data_type));
+    }
+
+    let rows = csv_array
+        .iter()
+        .map(|csv| {
+            let Some(csv) = csv else {
+                return ScalarValue::try_from(data_type);
+            };
+
+            let (value, malformed) = record_to_struct(csv, fields, options)?;
+            match options.mode {
+                ParseMode::FailFast if malformed => exec_err!(
+                    "Malformed records are detected in record parsing: {csv}. Parse Mode: FAILFAST"
+                ),
+                _ => Ok(value),
+            }
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    ScalarValue::iter_to_array(rows)
+}
+
+/// Converts a CSV record into a struct, also returning whether the record is
+/// malformed, i.e. it has the wrong number of fields or a field could not be
+/// converted to its type
+fn record_to_struct(
+    csv: &str,
+    fields: &Fields,
+    options: &CsvOptions,
+) -> Result<(ScalarValue, bool)> {
+    let tokens = options.split_record(csv);
+    let mut malformed = tokens
+        .as_ref()
+        .is_none_or(|tokens| tokens.len() != fields.len());
+
+    let columns = fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let token = tokens.as_ref().and_then(|tokens| tokens.get(i)?.as_deref());
+            let value = match token {
+                // Spark reads unquoted values ignoring surrounding whitespace,
+                // except for strings
+                Some(token) if field.data_type() == &DataType::Utf8 => {
+                    ScalarValue::Utf8(Some(token.to_string()))
+                }
+                Some(token) => ScalarValue::Utf8(Some(token.trim().to_string()))
+                    .cast_to(field.data_type())
+                    .or_else(|_| {
+                        malformed = true;
+                        ScalarValue::try_from(field.data_type())
+                    })?,
+                None => ScalarValue::try_from(field.data_type())?,
+            };
+            value.to_array()
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let value = ScalarValue::Struct(Arc::new(StructArray::try_new(
+        fields.clone(),
+        columns,
+        None,
+    )?));
+    Ok((value, malformed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Array, Float64Array, Int32Array};
+    use arrow::datatypes::{Float64Type, Int32Type};
+
+    fn run(csv: Vec<Option<&str>>, options: &CsvOptions) -> Result<ArrayRef> {
+        let data_type =
+            as_nullable(parse_ddl_schema("a INT, b DOUBLE, c STRING").unwrap());
+        from_csv_inner(&StringArray::from(csv), &data_type, options)
+    }
+
+    #[test]
+    fn test_from_csv_permissive() {
+        let result = run(
+            vec![
+                Some("1, 0.8,\"x,y\""),
+                Some("x,2"),
+                Some("3,4,z,extra"),
+                None,
+            ],
+            &CsvOptions::default(),
+        )
+        .unwrap();
+        let result = result.as_struct();
+
+        assert_eq!(result.null_count(), 1);
+        assert_eq!(
+            result.column(0).as_primitive::<Int32Type>(),
+            &Int32Array::from(vec![Some(1), None, Some(3), None])
+        );
+        assert_eq!(
+            result.column(1).as_primitive::<Float64Type>(),
+            &Float64Array::from(vec![Some(0.8), Some(2.0), Some(4.0), None])
+        );
+        assert_eq!(
+            result.column(2).as_string::<i32>(),
+            &StringArray::from(vec![Some("x,y"), None, Some("z"), None])
+        );
+    }
+
+    #[test]
+    fn test_from_csv_failfast() {
+        let options = CsvOptions {
+            mode: ParseMode::FailFast,
+            ..Default::default()
+        };
+        assert!(run(vec![Some("1,2,x")], &options).is_ok());
+        for csv in ["1,2", "x,2,y", "1,2,\"y"] {
+            let err = run(vec![Some(csv)], &options).unwrap_err();
+            assert!(err.to_string().contains("Parse Mode: FAILFAST"), "{csv}");
+        }
+    }
+}
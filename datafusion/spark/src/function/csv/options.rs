@@ -0,0 +1,182 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! The CSV options shared by the Spark CSV functions.
+
+use std::collections::HashMap;
+
+use datafusion_common::{Result, exec_err};
+
+use crate::function::utils::ParseMode;
+
+/// The subset of Spark's CSV options supported by the CSV functions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvOptions {
+    /// Field separator, the `sep` or `delimiter` option (default `,`)
+    pub delimiter: u8,
+    /// The `quote` option (default `"`)
+    pub quote: u8,
+    /// The `escape` option (default `\`)
+    pub escape: u8,
+    /// Unquoted fields equal to the `nullValue` option are read as NULL
+    /// (default: the empty string)
+    pub null_value: String,
+    pub mode: ParseMode,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            quote: b'"',
+            escape: b'\\',
+            null_value: String::new(),
+            mode: ParseMode::Permissive,
+        }
+    }
+}
+
+impl CsvOptions {
+    /// Reads the options returned by
+    /// [`options_from_map`](crate::function::utils::options_from_map)
+    pub fn from_options(
+        function_name: &str,
+        options: &HashMap<String, String>,
+    ) -> Result<Self> {
+        let defaults = Self::default();
+        let delimiter = options.get("sep").or_else(|| options.get("delimiter"));
+        Ok(Self {
+            delimiter: match delimiter {
+                Some(value) => single_byte(function_name, "sep", value)?,
+                None => defaults.delimiter,
+            },
+            quote: match options.get("quote") {
+                Some(value) => single_byte(function_name, "quote", value)?,
+                None => defaults.quote,
+            },
+            escape: match options.get("escape") {
+                Some(value) => single_byte(function_name, "escape", value)?,
+                None => defaults.escape,
+            },
+            null_value: options.get("nullvalue").cloned().unwrap_or_default(),
+            mode: ParseMode::from_options(function_name, options)?,
+        })
+    }
+
+    /// Splits a single CSV record into its fields, returning `None` if a
+    /// quoted field is not terminated.
+    ///
+    /// Unquoted fields equal to the null value are returned as `None`.
+    pub fn split_record(&self, record: &str) -> Option<Vec<Option<String>>> {
+        let (delimiter, quote, escape) = (
+            self.delimiter as char,
+            self.quote as char,
+            self.escape as char,
+        );
+        let mut fields = vec![];
+        let mut field = String::new();
+        let (mut quoted, mut in_quotes, mut at_start) = (false, false, true);
+
+        let mut chars = record.chars().peekable();
+        while let Some(c) = chars.next() {
+            if in_quotes {
+                if (c == escape || c == quote) && chars.peek() == Some(&quote) {
+                    field.push(quote);
+                    chars.next();
+                } else if c == quote {
+                    in_quotes = false;
+                } else {
+                    field.push(c);
+                }
+            } else if c == delimiter {
+                fields.push(self.finish_field(std::mem::take(&mut field), quoted));
+                (quoted, at_start) = (false, true);
+                continue;
+            } else if c == quote && at_start {
+                (quoted, in_quotes) = (true, true);
+            } else {
+                // text after a closing quote is kept, like Spark's parser does
+                field.push(c);
+            }
+            at_start = false;
+        }
+
+        if in_quotes {
+            return None;
+        }
+        fields.push(self.finish_field(field, quoted));
+        Some(fields)
+    }
+
+    fn finish_field(&self, field: String, quoted: bool) -> Option<String> {
+        (quoted || field != self.null_value).then_some(field)
+    }
+}
+
+fn single_byte(function_name: &str, option: &str, value: &str) -> Result<u8> {
+    match value {
+        "\\t" => Ok(b'\t'),
+        _ if value.len() == 1 && value.is_ascii() => Ok(value.as_bytes()[0]),
+        _ => exec_err!(
+            "{function_name} requires the {option} option to be a single ASCII character, got '{value}'"
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split(options: &CsvOptions, record: &str) -> Option<Vec<Option<String>>> {
+        options.split_record(record)
+    }
+
+    fn fields(values: &[Option<&str>]) -> Option<Vec<Option<String>>> {
+        Some(values.iter().map(|v| v.map(String::from)).collect())
+    }
+
+    #[test]
+    fn test_split_record() {
+        let options = CsvOptions::default();
+        assert_eq!(
+            split(&options, r#"1,"a,b",,"""q""","\"e""#),
+            fields(&[Some("1"), Some("a,b"), None, Some("\"q\""), Some("\"e")])
+        );
+        assert_eq!(split(&options, "a,"), fields(&[Some("a"), None]));
+        assert_eq!(split(&options, r#""",x"#), fields(&[Some(""), Some("x")]));
+        assert_eq!(split(&options, r#""unterminated"#), None);
+    }
+
+    #[test]
+    fn test_csv_options() {
+        let options = HashMap::from([
+            ("sep".to_string(), "\\t".to_string()),
+            ("nullvalue".to_string(), "-".to_string()),
+            ("mode".to_string(), "failfast".to_string()),
+        ]);
+        let options = CsvOptions::from_options("from_csv", &options).unwrap();
+        assert_eq!(options.delimiter, b'\t');
+        assert_eq!(options.mode, ParseMode::FailFast);
+        assert_eq!(
+            split(&options, "1\t-\t"),
+            fields(&[Some("1"), None, Some("")])
+        );
+
+        let options = HashMap::from([("sep".to_string(), "ab".to_string())]);
+        assert!(CsvOptions::from_options("from_csv", &options).is_err());
+    }
+}
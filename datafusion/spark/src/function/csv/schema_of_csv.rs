@@ -0,0 +1,165 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, AsArray, StringArray, StringBuilder};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Field};
+use datafusion_common::{Result, exec_err};
+use datafusion_expr::{
+    ColumnarValue, ScalarFunctionArgs, ScalarUDFImpl, Signature, TypeSignature,
+    Volatility,
+};
+
+use super::options::CsvOptions;
+use crate::function::schema_utils::data_type_to_ddl;
+use crate::function::utils::options_from_map;
+
+/// Spark-compatible `schema_of_csv` expression
+///
+/// <https://spark.apache.org/docs/latest/api/sql/index.html#schema_of_csv>
+///
+/// Infers the schema of a CSV record and returns it in DDL format, e.g.
+/// `schema_of_csv('1,abc')` returns `STRUCT<_c0: INT, _c1: STRING>`.
+///
+/// `schema_of_csv(csv_string[, options]) -> Utf8`
+///
+/// Like Spark, fields are named `_c0`, `_c1`, ... and each value is inferred
+/// as the first of `INT`, `BIGINT`, `DOUBLE` and `BOOLEAN` it can be parsed
+/// as, falling back to `STRING`. The `sep`, `quote`, `escape` and
+/// `nullValue` options are supported.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct SparkSchemaOfCsv {
+    signature: Signature,
+}
+
+impl Default for SparkSchemaOfCsv {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SparkSchemaOfCsv {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(
+                vec![TypeSignature::String(1), TypeSignature::Any(2)],
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for SparkSchemaOfCsv {
+    fn name(&self) -> &str {
+        "schema_of_csv"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Utf8)
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        let options = match args.args.get(1) {
+            Some(options) => CsvOptions::from_options(
+                self.name(),
+                &options_from_map(self.name(), options)?,
+            )?,
+            None => CsvOptions::default(),
+        };
+
+        let csv_array = args.args[0].to_array(args.number_rows)?;
+        let csv_array = cast(&csv_array, &DataType::Utf8)?;
+        let result = schema_of_csv_inner(csv_array.as_string::<i32>(), &options)?;
+
+        Ok(ColumnarValue::Array(result))
+    }
+}
+
+fn schema_of_csv_inner(
+    csv_array: &StringArray,
+    options: &CsvOptions,
+) -> Result<ArrayRef> {
+    let mut builder = StringBuilder::with_capacity(csv_array.len(), 0);
+
+    for csv in csv_array.iter() {
+        let Some(csv) = csv else {
+            builder.append_null();
+            continue;
+        };
+        let Some(tokens) = options.split_record(csv) else {
+            return exec_err!("Cannot infer the schema of malformed CSV: {csv}");
+        };
+        let fields = tokens
+            .iter()
+            .enumerate()
+            .map(|(i, token)| {
+                let data_type = token.as_deref().map_or(DataType::Null, infer_type);
+                Field::new(format!("_c{i}"), data_type, true)
+            })
+            .collect();
+        builder.append_value(data_type_to_ddl(&DataType::Struct(fields))?);
+    }
+
+    Ok(Arc::new(builder.finish()))
+}
+
+/// Infers the type of a CSV value, following Spark's `CSVInferSchema`
+fn infer_type(value: &str) -> DataType {
+    if value.parse::<i32>().is_ok() {
+        DataType::Int32
+    } else if value.parse::<i64>().is_ok() {
+        DataType::Int64
+    } else if value.parse::<f64>().is_ok() {
+        DataType::Float64
+    } else if value.eq_ignore_ascii_case("true") || value.eq_ignore_ascii_case("false") {
+        DataType::Boolean
+    } else {
+        DataType::Utf8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::Array;
+
+    #[test]
+    fn test_schema_of_csv() {
+        let input = StringArray::from(vec![
+            Some("1,abc"),
+            Some("3000000000,1.5,true,,\"2\""),
+            None,
+        ]);
+        let result = schema_of_csv_inner(&input, &CsvOptions::default()).unwrap();
+        let result = result.as_string::<i32>();
+        assert_eq!(result.value(0), "STRUCT<_c0: INT, _c1: STRING>");
+        assert_eq!(
+            result.value(1),
+            "STRUCT<_c0: BIGINT, _c1: DOUBLE, _c2: BOOLEAN, _c3: STRING, _c4: INT>"
+        );
+        assert!(result.is_null(2));
+
+        let input = StringArray::from(vec!["\"abc"]);
+        assert!(schema_of_csv_inner(&input, &CsvOptions::default()).is_err());
+    }
+}
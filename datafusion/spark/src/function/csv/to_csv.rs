@@ -0,0 +1,211 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, AsArray, StringBuilder, StructArray};
+use arrow::compute::cast;
+use arrow::csv::WriterBuilder;
+use arrow::datatypes::{DataType, Field, FieldRef, Schema};
+use arrow::record_batch::RecordBatch;
+use datafusion_common::{Result, exec_err, internal_datafusion_err, internal_err};
+use datafusion_expr::{
+    ColumnarValue, ReturnFieldArgs, ScalarFunctionArgs, ScalarUDFImpl, Signature,
+    Volatility,
+};
+
+use super::options::CsvOptions;
+use crate::function::error_utils::{
+    invalid_arg_count_exec_err, unsupported_data_type_exec_err,
+};
+use crate::function::utils::options_from_map;
+
+/// Spark-compatible `to_csv` expression
+///
+/// <https://spark.apache.org/docs/latest/api/sql/index.html#to_csv>
+///
+/// Converts a struct into a CSV record, e.g.
+/// `to_csv(named_struct('a', 1, 'b', 2))` returns `1,2`.
+///
+/// `to_csv(expr[, options]) -> Utf8`
+///
+/// The `sep`, `quote`, `escape` and `nullValue` options are supported.
+/// NULL fields are written as the null value, which is empty by default.
+/// Returns NULL if the input is NULL.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct SparkToCsv {
+    signature: Signature,
+}
+
+impl Default for SparkToCsv {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SparkToCsv {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::variadic_any(Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for SparkToCsv {
+    fn name(&self) -> &str {
+        "to_csv"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        internal_err!("return_field_from_args should be used instead")
+    }
+
+    fn return_field_from_args(&self, args: ReturnFieldArgs) -> Result<FieldRef> {
+        if !(1..=2).contains(&args.arg_fields.len()) {
+            return Err(invalid_arg_count_exec_err(
+                self.name(),
+                (1, 2),
+                args.arg_fields.len(),
+            ));
+        }
+
+        let input = &args.arg_fields[0];
+        let DataType::Struct(fields) = input.data_type() else {
+            return Err(unsupported_data_type_exec_err(
+                self.name(),
+                "STRUCT",
+                input.data_type(),
+            ));
+        };
+        // like Spark, only flat structs can be written as CSV
+        if let Some(field) = fields.iter().find(|f| f.data_type().is_nested()) {
+            return exec_err!(
+                "{} does not support the type {} of field {}",
+                self.name(),
+                field.data_type(),
+                field.name()
+            );
+        }
+
+        Ok(Arc::new(Field::new(
+            self.name(),
+            DataType::Utf8,
+            input.is_nullable(),
+        )))
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        let options = match args.args.get(1) {
+            Some(options) => CsvOptions::from_options(
+                self.name(),
+                &options_from_map(self.name(), options)?,
+            )?,
+            None => CsvOptions::default(),
+        };
+
+        let input = args.args[0].to_array(args.number_rows)?;
+        let Some(input) = input.as_struct_opt() else {
+            return internal_err!("to_csv expects a struct, got {}", input.data_type());
+        };
+        let result = to_csv_inner(input, &options)?;
+
+        Ok(ColumnarValue::Array(result))
+    }
+}
+
+fn to_csv_inner(input: &StructArray, options: &CsvOptions) -> Result<ArrayRef> {
+    // the fields of NULL structs may contain nulls even if they are declared
+    // non-nullable, and the CSV writer cannot write the Null type
+    let mut fields = Vec::with_capacity(input.num_columns());
+    let mut columns = Vec::with_capacity(input.num_columns());
+    for (field, column) in input.fields().iter().zip(input.columns()) {
+        let column = match column.data_type() {
+            DataType::Null => cast(column, &DataType::Utf8)?,
+            _ => Arc::clone(column),
+        };
+        fields.push(Field::new(field.name(), column.data_type().clone(), true));
+        columns.push(column);
+    }
+    let batch = RecordBatch::try_new(Arc::new(Schema::new(fields)), columns)?;
+
+    let mut builder = StringBuilder::with_capacity(input.len(), input.len() * 16);
+    for row in 0..input.len() {
+        if input.is_null(row) {
+            builder.append_null();
+            continue;
+        }
+
+        let mut writer = WriterBuilder::new()
+            .with_header(false)
+            .with_delimiter(options.delimiter)
+            .with_quote(options.quote)
+            .with_escape(options.escape)
+            .with_double_quote(false)
+            .with_null(options.null_value.clone())
+            .build(Vec::new());
+        writer.write(&batch.slice(row, 1))?;
+        let record = String::from_utf8(writer.into_inner())
+            .map_err(|e| internal_datafusion_err!("to_csv wrote invalid UTF-8: {e}"))?;
+        builder.append_value(record.trim_end_matches(['\r', '\n']));
+    }
+
+    Ok(Arc::new(builder.finish()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Int32Array, StringArray};
+    use arrow::buffer::NullBuffer;
+
+    #[test]
+    fn test_to_csv() {
+        let input = StructArray::new(
+            vec![
+                Field::new("a", DataType::Int32, true),
+                Field::new("b", DataType::Utf8, true),
+            ]
+            .into(),
+            vec![
+                Arc::new(Int32Array::from(vec![Some(1), None, Some(3)])),
+                Arc::new(StringArray::from(vec![Some("x,y"), Some("z"), None])),
+            ],
+            Some(NullBuffer::from(vec![true, true, false])),
+        );
+
+        let result = to_csv_inner(&input, &CsvOptions::default()).unwrap();
+        assert_eq!(
+            result.as_string::<i32>(),
+            &StringArray::from(vec![Some("1,\"x,y\""), Some(",z"), None])
+        );
+
+        let options = CsvOptions {
+            delimiter: b'|',
+            null_value: "-".to_string(),
+            ..Default::default()
+        };
+        let result = to_csv_inner(&input, &options).unwrap();
+        assert_eq!(
+            result.as_string::<i32>(),
+            &StringArray::from(vec![Some("1|x,y"), Some("-|z"), None])
+        );
+    }
+}
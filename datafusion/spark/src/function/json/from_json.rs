@@ -0,0 +1,346 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::sync::Arc;
+
+use arrow::array::{
+    Array, ArrayRef, AsArray, MapArray, StringArray, StructArray, new_empty_array,
+};
+use arrow::buffer::OffsetBuffer;
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Field, FieldRef};
+use datafusion_common::{Result, ScalarValue, exec_err, internal_err};
+use datafusion_expr::{
+    ColumnarValue, ReturnFieldArgs, ScalarFunctionArgs, ScalarUDFImpl, Signature,
+    TypeSignature, Volatility,
+};
+use serde_json::Value;
+
+use crate::function::error_utils::invalid_arg_count_exec_err;
+use crate::function::schema_utils::{as_nullable, parse_ddl_schema};
+use crate::function::utils::{ParseMode, options_from_map};
+
+/// Spark-compatible `from_json` expression
+///
+/// <https://spark.apache.org/docs/latest/api/sql/index.html#from_json>
+///
+/// Parses a JSON string into a value of the type described by a DDL schema
+/// string, e.g. `from_json('{"a": 1}', 'a INT, b STRING')`.
+///
+/// `from_json(json_string, schema[, options]) -> Struct | List | Map`
+///
+/// The optional `options` map supports the `mode` option:
+///
+/// - `PERMISSIVE` (default): fields that cannot be converted to the schema
+///   type are set to NULL; a malformed JSON document produces a struct whose
+///   fields are all NULL
+/// - `FAILFAST`: malformed records raise an error
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct SparkFromJson {
+    signature: Signature,
+}
+
+impl Default for SparkFromJson {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SparkFromJson {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(
+                vec![TypeSignature::String(2), TypeSignature::Any(3)],
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for SparkFromJson {
+    fn name(&self) -> &str {
+        "from_json"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        internal_err!("return_field_from_args should be used instead")
+    }
+
+    fn return_field_from_args(&self, args: ReturnFieldArgs) -> Result<FieldRef> {
+        if !(2..=3).contains(&args.arg_fields.len()) {
+            return Err(invalid_arg_count_exec_err(
+                self.name(),
+                (2, 3),
+                args.arg_fields.len(),
+            ));
+        }
+
+        let Some(schema) =
+            args.scalar_arguments[1].and_then(|sv| sv.try_as_str().flatten())
+        else {
+            return exec_err!(
+                "{} requires its second argument to be a constant schema string",
+                self.name()
+            );
+        };
+
+        // Spark always reads JSON with a nullable version of the schema
+        let data_type = as_nullable(parse_ddl_schema(schema)?);
+        Ok(Arc::new(Field::new(self.name(), data_type, true)))
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        let mode = match args.args.get(2) {
+            Some(options) => ParseMode::from_options(
+                self.name(),
+                &options_from_map(self.name(), options)?,
+            )?,
+            None => ParseMode::Permissive,
+        };
+
+        let json_array = args.args[0].to_array(args.number_rows)?;
+        let json_array = cast(&json_array, &DataType::Utf8)?;
+        let result = from_json_inner(
+            json_array.as_string::<i32>(),
+            args.return_field.data_type(),
+            mode,
+        )?;
+
+        Ok(ColumnarValue::Array(result))
+    }
+}
+
+fn from_json_inner(
+    json_array: &StringArray,
+    data_type: &DataType,
+    mode: ParseMode,
+) -> Result<ArrayRef> {
+    if json_array.is_empty() {
+        return Ok(new_empty_array(data_type));
+    }
+
+    let rows = json_array
+        .iter()
+        .map(|json| {
+            let Some(json) = json else {
+                return ScalarValue::try_from(data_type);
+            };
+
+            let mut malformed = false;
+            let value = match serde_json::from_str::<Value>(json) {
+                Ok(value) => json_to_scalar(&value, data_type, &mut malformed)?,
+                Err(_) => {
+                    malformed = true;
+                    ScalarValue::try_from(data_type)?
+                }
+            };
+
+            if !malformed {
+                return Ok(value);
+            }
+            match (mode, data_type) {
+                (ParseMode::FailFast, _) => exec_err!(
+                    "Malformed records are detected in record parsing: {json}. Parse Mode: FAILFAST"
+                ),
+                // PERMISSIVE keeps the partial result of a struct, and falls
+                // back to a struct of nulls if nothing could be read
+                (ParseMode::Permissive, DataType::Struct(_)) if !value.is_null() => {
+                    Ok(value)
+                }
+                (ParseMode::Permissive, DataType::Struct(_)) => json_to_scalar(
+                    &Value::Object(serde_json::Map::new()),
+                    data_type,
+                    &mut false,
+                ),
+                (ParseMode::Permissive, _) => ScalarValue::try_from(data_type),
+            }
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    ScalarValue::iter_to_array(rows)
+}
+
+/// Converts a JSON value into a [`ScalarValue`] of `data_type`.
+///
+/// Values that do not match the requested type are replaced by NULL and flag
+/// the record as `malformed`.
+fn json_to_scalar(
+    value: &Value,
+    data_type: &DataType,
+    malformed: &mut bool,
+) -> Result<ScalarValue> {
+    let scalar = match (value, data_type) {
+        (Value::Null, _) => None,
+        (Value::Bool(b), DataType::Boolean) => Some(ScalarValue::Boolean(Some(*b))),
+        (
+            Value::Number(n),
+            DataType::Int8
+            | DataType::Int16
+            | DataType::Int32
+            | DataType::Int64
+            | DataType::Float32
+            | DataType::Float64
+            | DataType::Decimal128(_, _),
+        ) => ScalarValue::Utf8(Some(n.to_string()))
+            .cast_to(data_type)
+            .ok(),
+        (Value::String(s), DataType::Utf8) => Some(ScalarValue::Utf8(Some(s.clone()))),
+        // Spark keeps the raw JSON text when a non-string value is read as a string
+        (other, DataType::Utf8) => Some(ScalarValue::Utf8(Some(other.to_string()))),
+        (Value::String(s), DataType::Date32 | DataType::Timestamp(_, _)) => {
+            ScalarValue::Utf8(Some(s.clone())).cast_to(data_type).ok()
+        }
+        (Value::Array(items), DataType::List(field)) => {
+            let values = items
+                .iter()
+                .map(|item| json_to_scalar(item, field.data_type(), malformed))
+                .collect::<Result<Vec<_>>>()?;
+            Some(ScalarValue::List(ScalarValue::new_list_nullable(
+                &values,
+                field.data_type(),
+            )))
+        }
+        (Value::Object(map), DataType::Map(entries, sorted)) => {
+            let DataType::Struct(kv_fields) = entries.data_type() else {
+                return internal_err!("Invalid map entries type {}", entries.data_type());
+            };
+            let mut keys = Vec::with_capacity(map.len());
+            let mut values = Vec::with_capacity(map.len());
+            for (key, value) in map {
+                let key = Value::String(key.clone());
+                match json_to_scalar(&key, kv_fields[0].data_type(), malformed)? {
+                    key if key.is_null() => continue,
+                    key => keys.push(key),
+                }
+                values.push(json_to_scalar(value, kv_fields[1].data_type(), malformed)?);
+            }
+
+            let entries_array = StructArray::try_new(
+                kv_fields.clone(),
+                vec![
+                    scalars_to_array(keys, kv_fields[0].data_type())?,
+                    scalars_to_array(values, kv_fields[1].data_type())?,
+                ],
+                None,
+            )?;
+            let map_array = MapArray::try_new(
+                Arc::clone(entries),
+                OffsetBuffer::from_lengths([entries_array.len()]),
+                entries_array,
+                None,
+                *sorted,
+            )?;
+            Some(ScalarValue::Map(Arc::new(map_array)))
+        }
+        (Value::Object(map), DataType::Struct(fields)) => {
+            let columns = fields
+                .iter()
+                .map(|field| {
+                    let value = map.get(field.name()).unwrap_or(&Value::Null);
+                    json_to_scalar(value, field.data_type(), malformed)?.to_array()
+                })
+                .collect::<Result<Vec<_>>>()?;
+            Some(ScalarValue::Struct(Arc::new(StructArray::try_new(
+                fields.clone(),
+                columns,
+                None,
+            )?)))
+        }
+        _ => None,
+    };
+
+    match scalar {
+        Some(scalar) => Ok(scalar),
+        None => {
+            if !value.is_null() {
+                *malformed = true;
+            }
+            ScalarValue::try_from(data_type)
+        }
+    }
+}
+
+fn scalars_to_array(scalars: Vec<ScalarValue>, data_type: &DataType) -> Result<ArrayRef> {
+    if scalars.is_empty() {
+        Ok(new_empty_array(data_type))
+    } else {
+        ScalarValue::iter_to_array(scalars)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::Int32Array;
+
+    fn struct_type() -> DataType {
+        as_nullable(parse_ddl_schema("a INT, b STRING").unwrap())
+    }
+
+    #[test]
+    fn test_from_json_permissive() {
+        let input = StringArray::from(vec![
+            Some(r#"{"a": 1, "b": "x"}"#),
+            Some(r#"{"a": "not an int", "b": [1, 2]}"#),
+            Some("not json"),
+            None,
+        ]);
+        let result =
+            from_json_inner(&input, &struct_type(), ParseMode::Permissive).unwrap();
+        let result = result.as_struct();
+
+        assert_eq!(result.null_count(), 1);
+        assert!(result.is_null(3));
+        let a = result
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int32Array>()
+            .unwrap();
+        assert_eq!(a, &Int32Array::from(vec![Some(1), None, None, None]));
+        let b = result.column(1).as_string::<i32>();
+        assert_eq!(
+            b,
+            &StringArray::from(vec![Some("x"), Some("[1,2]"), None, None])
+        );
+    }
+
+    #[test]
+    fn test_from_json_failfast() {
+        let input = StringArray::from(vec![Some(r#"{"a": 1}"#), Some("{")]);
+        let result = from_json_inner(&input, &struct_type(), ParseMode::FailFast);
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("Parse Mode: FAILFAST")
+        );
+    }
+
+    #[test]
+    fn test_from_json_nested() {
+        let data_type =
+            as_nullable(parse_ddl_schema("a ARRAY<INT>, m MAP<STRING, BIGINT>").unwrap());
+        let input = StringArray::from(vec![r#"{"a": [1, null], "m": {"k": 2}}"#]);
+        let result = from_json_inner(&input, &data_type, ParseMode::FailFast).unwrap();
+        assert_eq!(result.data_type(), &data_type);
+        assert_eq!(result.null_count(), 0);
+    }
+}
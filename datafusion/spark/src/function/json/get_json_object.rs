@@ -0,0 +1,253 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, AsArray, StringArray, StringBuilder};
+use arrow::compute::cast;
+use arrow::datatypes::DataType;
+use datafusion_common::{Result, ScalarValue};
+use datafusion_expr::{
+    ColumnarValue, ScalarFunctionArgs, ScalarUDFImpl, Signature, Volatility,
+};
+use serde_json::Value;
+
+/// Spark-compatible `get_json_object` expression
+///
+/// <https://spark.apache.org/docs/latest/api/sql/index.html#get_json_object>
+///
+/// Extracts a JSON object from a JSON string based on a JSON path.
+///
+/// `get_json_object(json_string, path) -> Utf8`
+///
+/// The same JSON path subset as Spark is supported: the root `$`, child
+/// access with `.name` or `['name']`, array indexing with `[n]`, and the
+/// array wildcard `[*]`.
+///
+/// - String values are returned without quotes, other values as JSON text
+/// - If a wildcard matches several values they are returned as a JSON array
+/// - Returns NULL if the JSON or the path is invalid, or nothing matches
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct SparkGetJsonObject {
+    signature: Signature,
+}
+
+impl Default for SparkGetJsonObject {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SparkGetJsonObject {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::string(2, Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for SparkGetJsonObject {
+    fn name(&self) -> &str {
+        "get_json_object"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Utf8)
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        let json_array = args.args[0].to_array(args.number_rows)?;
+        let json_array = cast(&json_array, &DataType::Utf8)?;
+        let json_array = json_array.as_string::<i32>();
+
+        let result = match &args.args[1] {
+            // parse a constant path only once for the whole batch
+            ColumnarValue::Scalar(path) => {
+                let path = cast_to_utf8(path)?;
+                let segments = path.as_deref().and_then(parse_json_path);
+                get_json_object_inner(json_array, |_| segments.as_deref())
+            }
+            ColumnarValue::Array(paths) => {
+                let paths = cast(paths, &DataType::Utf8)?;
+                let segments = paths
+                    .as_string::<i32>()
+                    .iter()
+                    .map(|path| path.and_then(parse_json_path))
+                    .collect::<Vec<_>>();
+                get_json_object_inner(json_array, |row| segments[row].as_deref())
+            }
+        };
+
+        Ok(ColumnarValue::Array(result))
+    }
+}
+
+fn cast_to_utf8(value: &ScalarValue) -> Result<Option<String>> {
+    match value.cast_to(&DataType::Utf8)? {
+        ScalarValue::Utf8(s) => Ok(s),
+        _ => Ok(None),
+    }
+}
+
+fn get_json_object_inner<'a>(
+    json_array: &StringArray,
+    path_for_row: impl Fn(usize) -> Option<&'a [PathSegment]>,
+) -> ArrayRef {
+    let mut builder = StringBuilder::with_capacity(json_array.len(), 0);
+
+    for (row, json) in json_array.iter().enumerate() {
+        let result = json
+            .zip(path_for_row(row))
+            .and_then(|(json, path)| get_json_object(json, path));
+        builder.append_option(result);
+    }
+
+    Arc::new(builder.finish())
+}
+
+/// A single step of a Spark JSON path
+#[derive(Debug, Clone, PartialEq)]
+enum PathSegment {
+    Key(String),
+    Index(usize),
+    Wildcard,
+}
+
+/// Parses a Spark JSON path, returning `None` if it is invalid
+fn parse_json_path(path: &str) -> Option<Vec<PathSegment>> {
+    let mut rest = path.strip_prefix('$')?;
+    let mut segments = vec![];
+
+    while !rest.is_empty() {
+        if let Some(after_dot) = rest.strip_prefix('.') {
+            let end = after_dot.find(['.', '[']).unwrap_or(after_dot.len());
+            let name = &after_dot[..end];
+            if name.is_empty() {
+                return None;
+            }
+            segments.push(PathSegment::Key(name.to_string()));
+            rest = &after_dot[end..];
+        } else if let Some(after_bracket) = rest.strip_prefix('[') {
+            if let Some(after_wildcard) = after_bracket.strip_prefix("*]") {
+                segments.push(PathSegment::Wildcard);
+                rest = after_wildcard;
+            } else if let Some(quoted) = after_bracket.strip_prefix('\'') {
+                let end = quoted.find("']")?;
+                segments.push(PathSegment::Key(quoted[..end].to_string()));
+                rest = &quoted[end + 2..];
+            } else {
+                let end = after_bracket.find(']')?;
+                let index = after_bracket[..end].trim().parse().ok()?;
+                segments.push(PathSegment::Index(index));
+                rest = &after_bracket[end + 1..];
+            }
+        } else {
+            return None;
+        }
+    }
+
+    Some(segments)
+}
+
+fn get_json_object(json: &str, path: &[PathSegment]) -> Option<String> {
+    let value: Value = serde_json::from_str(json).ok()?;
+
+    let mut matches = vec![];
+    collect_matches(&value, path, &mut matches);
+
+    // only a wildcard can produce several matches, which Spark wraps in an array
+    let result = match matches.as_slice() {
+        [] => return None,
+        [single] => (*single).clone(),
+        _ => Value::Array(matches.into_iter().cloned().collect()),
+    };
+
+    match result {
+        Value::Null => None,
+        Value::String(s) => Some(s),
+        other => Some(other.to_string()),
+    }
+}
+
+fn collect_matches<'a>(value: &'a Value, path: &[PathSegment], out: &mut Vec<&'a Value>) {
+    let Some((segment, rest)) = path.split_first() else {
+        out.push(value);
+        return;
+    };
+
+    match (segment, value) {
+        (PathSegment::Key(key), Value::Object(map)) => {
+            if let Some(child) = map.get(key) {
+                collect_matches(child, rest, out);
+            }
+        }
+        (PathSegment::Index(index), Value::Array(items)) => {
+            if let Some(child) = items.get(*index) {
+                collect_matches(child, rest, out);
+            }
+        }
+        (PathSegment::Wildcard, Value::Array(items)) => {
+            for child in items {
+                collect_matches(child, rest, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_json_path() {
+        assert_eq!(
+            parse_json_path("$.a['b c'][1][*]"),
+            Some(vec![
+                PathSegment::Key("a".to_string()),
+                PathSegment::Key("b c".to_string()),
+                PathSegment::Index(1),
+                PathSegment::Wildcard,
+            ])
+        );
+        assert_eq!(parse_json_path("$"), Some(vec![]));
+        assert_eq!(parse_json_path("a.b"), None);
+        assert_eq!(parse_json_path("$..a"), None);
+        assert_eq!(parse_json_path("$[x]"), None);
+    }
+
+    #[test]
+    fn test_get_json_object() {
+        let get = |json: &str, path: &str| {
+            get_json_object(json, &parse_json_path(path).unwrap())
+        };
+        let json =
+            r#"{"a": "b", "c": {"d": [1, 2, {"e": null}]}, "f": [{"g": 1}, {"g": 2}]}"#;
+
+        assert_eq!(get(json, "$.a"), Some("b".to_string()));
+        assert_eq!(get(json, "$.c.d"), Some(r#"[1,2,{"e":null}]"#.to_string()));
+        assert_eq!(get(json, "$.c.d[1]"), Some("2".to_string()));
+        assert_eq!(get(json, "$.c.d[2].e"), None);
+        assert_eq!(get(json, "$.f[*].g"), Some("[1,2]".to_string()));
+        assert_eq!(get(json, "$.missing"), None);
+        assert_eq!(get("not json", "$.a"), None);
+    }
+}
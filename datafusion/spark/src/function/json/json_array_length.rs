@@ -0,0 +1,109 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, AsArray, Int32Array};
+use arrow::compute::cast;
+use arrow::datatypes::DataType;
+use datafusion_common::Result;
+use datafusion_expr::{
+    ColumnarValue, ScalarFunctionArgs, ScalarUDFImpl, Signature, Volatility,
+};
+use datafusion_functions::utils::make_scalar_function;
+use serde_json::Value;
+
+/// Spark-compatible `json_array_length` expression
+///
+/// <https://spark.apache.org/docs/latest/api/sql/index.html#json_array_length>
+///
+/// Returns the number of elements in the outermost JSON array, or NULL if the
+/// input is NULL, not valid JSON or not a JSON array.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct SparkJsonArrayLength {
+    signature: Signature,
+}
+
+impl Default for SparkJsonArrayLength {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SparkJsonArrayLength {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::string(1, Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for SparkJsonArrayLength {
+    fn name(&self) -> &str {
+        "json_array_length"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Int32)
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        make_scalar_function(json_array_length_inner, vec![])(&args.args)
+    }
+}
+
+fn json_array_length_inner(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let json_array = cast(&args[0], &DataType::Utf8)?;
+
+    let result = json_array
+        .as_string::<i32>()
+        .iter()
+        .map(|json| match serde_json::from_str::<Value>(json?) {
+            Ok(Value::Array(items)) => i32::try_from(items.len()).ok(),
+            _ => None,
+        })
+        .collect::<Int32Array>();
+
+    Ok(Arc::new(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::StringArray;
+    use arrow::datatypes::Int32Type;
+
+    #[test]
+    fn test_json_array_length() {
+        let input: ArrayRef = Arc::new(StringArray::from(vec![
+            Some("[1, 2, [3, 4]]"),
+            Some("[]"),
+            Some(r#"{"a": 1}"#),
+            Some("[1, 2"),
+            None,
+        ]));
+        let result = json_array_length_inner(&[input]).unwrap();
+        assert_eq!(
+            result.as_primitive::<Int32Type>(),
+            &Int32Array::from(vec![Some(3), Some(0), None, None, None])
+        );
+    }
+}
@@ -0,0 +1,120 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, AsArray, ListBuilder, StringBuilder};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Field};
+use datafusion_common::Result;
+use datafusion_expr::{
+    ColumnarValue, ScalarFunctionArgs, ScalarUDFImpl, Signature, Volatility,
+};
+use datafusion_functions::utils::make_scalar_function;
+use serde_json::Value;
+
+/// Spark-compatible `json_object_keys` expression
+///
+/// <https://spark.apache.org/docs/latest/api/sql/index.html#json_object_keys>
+///
+/// Returns all the keys of the outermost JSON object as an array, in the
+/// order they appear in the input. Returns NULL if the input is NULL, not
+/// valid JSON or not a JSON object.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct SparkJsonObjectKeys {
+    signature: Signature,
+}
+
+impl Default for SparkJsonObjectKeys {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SparkJsonObjectKeys {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::string(1, Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for SparkJsonObjectKeys {
+    fn name(&self) -> &str {
+        "json_object_keys"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::new_list(DataType::Utf8, true))
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        make_scalar_function(json_object_keys_inner, vec![])(&args.args)
+    }
+}
+
+fn json_object_keys_inner(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let json_array = cast(&args[0], &DataType::Utf8)?;
+    let json_array = json_array.as_string::<i32>();
+
+    let mut builder = ListBuilder::with_capacity(StringBuilder::new(), json_array.len())
+        .with_field(Arc::new(Field::new_list_field(DataType::Utf8, true)));
+
+    for json in json_array.iter() {
+        match json.map(serde_json::from_str::<Value>) {
+            Some(Ok(Value::Object(map))) => {
+                for key in map.keys() {
+                    builder.values().append_value(key);
+                }
+                builder.append(true);
+            }
+            _ => builder.append_null(),
+        }
+    }
+
+    Ok(Arc::new(builder.finish()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Array, StringArray};
+
+    #[test]
+    fn test_json_object_keys() {
+        let input: ArrayRef = Arc::new(StringArray::from(vec![
+            Some(r#"{"f1": "abc", "f2": {"f3": "a", "f4": "b"}}"#),
+            Some("{}"),
+            Some("[1, 2]"),
+            None,
+        ]));
+        let result = json_object_keys_inner(&[input]).unwrap();
+        let result = result.as_list::<i32>();
+
+        assert_eq!(
+            result.value(0).as_string::<i32>(),
+            &StringArray::from(vec!["f1", "f2"])
+        );
+        assert_eq!(result.value(1).len(), 0);
+        assert!(result.is_null(2));
+        assert!(result.is_null(3));
+    }
+}
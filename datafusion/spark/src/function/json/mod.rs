@@ -15,24 +15,74 @@
 // specific language governing permissions and limitations
 // under the License.
 
+pub mod from_json;
+pub mod get_json_object;
+pub mod json_array_length;
+pub mod json_object_keys;
 pub mod json_tuple;
+pub mod schema_of_json;
+pub mod to_json;
 
 use datafusion_expr::ScalarUDF;
 use datafusion_functions::make_udf_function;
 use std::sync::Arc;
 
+make_udf_function!(from_json::SparkFromJson, from_json);
+make_udf_function!(get_json_object::SparkGetJsonObject, get_json_object);
+make_udf_function!(json_array_length::SparkJsonArrayLength, json_array_length);
+make_udf_function!(json_object_keys::SparkJsonObjectKeys, json_object_keys);
 make_udf_function!(json_tuple::JsonTuple, json_tuple);
+make_udf_function!(schema_of_json::SparkSchemaOfJson, schema_of_json);
+make_udf_function!(to_json::SparkToJson, to_json);
 
 pub mod expr_fn {
     use datafusion_functions::export_functions;
 
+    export_functions!((
+        from_json,
+        "Parses a JSON string into a struct, array or map described by a DDL schema string.",
+        args,
+    ));
+    export_functions!((
+        get_json_object,
+        "Extracts a JSON object from a JSON string based on the given JSON path.",
+        json path
+    ));
+    export_functions!((
+        json_array_length,
+        "Returns the number of elements in the outermost JSON array.",
+        json
+    ));
+    export_functions!((
+        json_object_keys,
+        "Returns all the keys of the outermost JSON object as an array.",
+        json
+    ));
     export_functions!((
         json_tuple,
         "Extracts top-level fields from a JSON string and returns them as a struct.",
         args,
     ));
+    export_functions!((
+        schema_of_json,
+        "Returns the schema of a JSON string in DDL format.",
+        args,
+    ));
+    export_functions!((
+        to_json,
+        "Converts a struct, map or array value into a JSON string.",
+        args,
+    ));
 }
 
 pub fn functions() -> Vec<Arc<ScalarUDF>> {
-    vec![json_tuple()]
+    vec![
+        from_json(),
+        get_json_object(),
+        json_array_length(),
+        json_object_keys(),
+        json_tuple(),
+        schema_of_json(),
+        to_json(),
+    ]
 }
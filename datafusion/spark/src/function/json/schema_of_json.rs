@@ -0,0 +1,225 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, AsArray, StringBuilder};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Field, Fields};
+use datafusion_common::{Result, exec_err};
+use datafusion_expr::{
+    ColumnarValue, ScalarFunctionArgs, ScalarUDFImpl, Signature, TypeSignature,
+    Volatility,
+};
+use serde_json::{Number, Value};
+
+use crate::function::schema_utils::data_type_to_ddl;
+
+/// Precision Spark uses for integers that do not fit in a `BIGINT`
+const BIG_INTEGER_PRECISION: u8 = 38;
+
+/// Spark-compatible `schema_of_json` expression
+///
+/// <https://spark.apache.org/docs/latest/api/sql/index.html#schema_of_json>
+///
+/// Infers the schema of a JSON string and returns it in DDL format, e.g.
+/// `schema_of_json('[{"col": 0}]')` returns `ARRAY<STRUCT<col: BIGINT>>`.
+///
+/// `schema_of_json(json_string[, options]) -> Utf8`
+///
+/// Like Spark, integers are inferred as `BIGINT`, floating point numbers as
+/// `DOUBLE`, object fields are sorted by name and conflicting types are
+/// widened, falling back to `STRING`. Options are accepted for compatibility
+/// but ignored.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct SparkSchemaOfJson {
+    signature: Signature,
+}
+
+impl Default for SparkSchemaOfJson {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SparkSchemaOfJson {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(
+                vec![TypeSignature::String(1), TypeSignature::Any(2)],
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for SparkSchemaOfJson {
+    fn name(&self) -> &str {
+        "schema_of_json"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Utf8)
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        let json_array = args.args[0].to_array(args.number_rows)?;
+        let result = schema_of_json_inner(&json_array)?;
+
+        Ok(ColumnarValue::Array(result))
+    }
+}
+
+fn schema_of_json_inner(json_array: &ArrayRef) -> Result<ArrayRef> {
+    let json_array = cast(json_array, &DataType::Utf8)?;
+    let json_array = json_array.as_string::<i32>();
+    let mut builder = StringBuilder::with_capacity(json_array.len(), 0);
+
+    for json in json_array.iter() {
+        let Some(json) = json else {
+            builder.append_null();
+            continue;
+        };
+        let Ok(value) = serde_json::from_str::<Value>(json) else {
+            return exec_err!("Cannot infer the schema of malformed JSON: {json}");
+        };
+        builder.append_value(data_type_to_ddl(&infer_type(&value))?);
+    }
+
+    Ok(Arc::new(builder.finish()))
+}
+
+/// Infers the Spark type of a JSON value, using [`DataType::Null`] for values
+/// whose type is unknown (rendered as `STRING`)
+fn infer_type(value: &Value) -> DataType {
+    match value {
+        Value::Null => DataType::Null,
+        Value::Bool(_) => DataType::Boolean,
+        Value::Number(n) => infer_number_type(n),
+        Value::String(_) => DataType::Utf8,
+        Value::Array(items) => {
+            let element = items
+                .iter()
+                .map(infer_type)
+                .reduce(|a, b| merge_types(&a, &b))
+                .unwrap_or(DataType::Null);
+            DataType::new_list(element, true)
+        }
+        Value::Object(map) => {
+            let sorted = map
+                .iter()
+                .map(|(k, v)| (k.as_str(), infer_type(v)))
+                .collect::<BTreeMap<_, _>>();
+            DataType::Struct(
+                sorted
+                    .into_iter()
+                    .map(|(name, data_type)| Field::new(name, data_type, true))
+                    .collect(),
+            )
+        }
+    }
+}
+
+fn infer_number_type(n: &Number) -> DataType {
+    if n.is_i64() {
+        DataType::Int64
+    } else if n.is_u64() {
+        DataType::Decimal128(BIG_INTEGER_PRECISION, 0)
+    } else {
+        DataType::Float64
+    }
+}
+
+/// Finds the narrowest type both inferred types can be widened to, following
+/// Spark's `JsonInferSchema.compatibleType`
+fn merge_types(a: &DataType, b: &DataType) -> DataType {
+    match (a, b) {
+        _ if a == b => a.clone(),
+        (DataType::Null, other) | (other, DataType::Null) => other.clone(),
+        (DataType::Int64, DataType::Float64)
+        | (DataType::Float64, DataType::Int64)
+        | (DataType::Decimal128(_, _), DataType::Float64)
+        | (DataType::Float64, DataType::Decimal128(_, _)) => DataType::Float64,
+        (DataType::Int64, DataType::Decimal128(_, _))
+        | (DataType::Decimal128(_, _), DataType::Int64) => {
+            DataType::Decimal128(BIG_INTEGER_PRECISION, 0)
+        }
+        (DataType::List(a), DataType::List(b)) => {
+            DataType::new_list(merge_types(a.data_type(), b.data_type()), true)
+        }
+        (DataType::Struct(a), DataType::Struct(b)) => {
+            let mut merged = a
+                .iter()
+                .map(|f| (f.name().as_str(), f.data_type().clone()))
+                .collect::<BTreeMap<_, _>>();
+            for field in b.iter() {
+                let data_type = match merged.get(field.name().as_str()) {
+                    Some(existing) => merge_types(existing, field.data_type()),
+                    None => field.data_type().clone(),
+                };
+                merged.insert(field.name().as_str(), data_type);
+            }
+            DataType::Struct(
+                merged
+                    .into_iter()
+                    .map(|(name, data_type)| Field::new(name, data_type, true))
+                    .collect::<Fields>(),
+            )
+        }
+        _ => DataType::Utf8,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Array, StringArray};
+
+    fn schema_of(json: &str) -> String {
+        let input: ArrayRef = Arc::new(StringArray::from(vec![json]));
+        let result = schema_of_json_inner(&input).unwrap();
+        result.as_string::<i32>().value(0).to_string()
+    }
+
+    #[test]
+    fn test_schema_of_json() {
+        assert_eq!(schema_of(r#"[{"col": 0}]"#), "ARRAY<STRUCT<col: BIGINT>>");
+        assert_eq!(
+            schema_of(r#"{"b": 1.5, "a": [1, 2.5], "c": null, "d": {"e": true}}"#),
+            "STRUCT<a: ARRAY<DOUBLE>, b: DOUBLE, c: STRING, d: STRUCT<e: BOOLEAN>>"
+        );
+        assert_eq!(
+            schema_of(r#"[{"a": 1}, {"b": "x"}, {"a": "y"}]"#),
+            "ARRAY<STRUCT<a: STRING, b: STRING>>"
+        );
+        assert_eq!(schema_of("[]"), "ARRAY<STRING>");
+    }
+
+    #[test]
+    fn test_schema_of_json_null_and_malformed() {
+        let input: ArrayRef = Arc::new(StringArray::from(vec![None::<&str>]));
+        assert!(schema_of_json_inner(&input).unwrap().is_null(0));
+
+        let input: ArrayRef = Arc::new(StringArray::from(vec!["{"]));
+        assert!(schema_of_json_inner(&input).is_err());
+    }
+}
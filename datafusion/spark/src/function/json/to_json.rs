@@ -0,0 +1,330 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::fmt::{Debug, Write};
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, AsArray, StringBuilder};
+use arrow::datatypes::{
+    DataType, Field, FieldRef, Float32Type, Float64Type, Int8Type, Int16Type, Int32Type,
+    Int64Type,
+};
+use arrow::util::display::{ArrayFormatter, FormatOptions};
+use datafusion_common::{Result, exec_err, internal_err};
+use datafusion_expr::{
+    ColumnarValue, ReturnFieldArgs, ScalarFunctionArgs, ScalarUDFImpl, Signature,
+    Volatility,
+};
+use num_traits::Float;
+
+use crate::function::error_utils::{
+    invalid_arg_count_exec_err, unsupported_data_type_exec_err,
+};
+use crate::function::utils::options_from_map;
+
+/// Spark-compatible `to_json` expression
+///
+/// <https://spark.apache.org/docs/latest/api/sql/index.html#to_json>
+///
+/// Converts a struct, map or array value into a JSON string.
+///
+/// `to_json(expr[, options]) -> Utf8`
+///
+/// - Struct fields with NULL values are omitted, unless the
+///   `ignoreNullFields` option is set to `false`
+/// - Map keys are written using their string representation
+/// - Returns NULL if the input is NULL
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct SparkToJson {
+    signature: Signature,
+}
+
+impl Default for SparkToJson {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SparkToJson {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::variadic_any(Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for SparkToJson {
+    fn name(&self) -> &str {
+        "to_json"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        internal_err!("return_field_from_args should be used instead")
+    }
+
+    fn return_field_from_args(&self, args: ReturnFieldArgs) -> Result<FieldRef> {
+        if !(1..=2).contains(&args.arg_fields.len()) {
+            return Err(invalid_arg_count_exec_err(
+                self.name(),
+                (1, 2),
+                args.arg_fields.len(),
+            ));
+        }
+
+        let input = &args.arg_fields[0];
+        match input.data_type() {
+            DataType::Struct(_)
+            | DataType::Map(_, _)
+            | DataType::List(_)
+            | DataType::LargeList(_) => {}
+            other => {
+                return Err(unsupported_data_type_exec_err(
+                    self.name(),
+                    "STRUCT, MAP or ARRAY",
+                    other,
+                ));
+            }
+        }
+
+        Ok(Arc::new(Field::new(
+            self.name(),
+            DataType::Utf8,
+            input.is_nullable(),
+        )))
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        let ignore_null_fields = match args.args.get(1) {
+            Some(options) => options_from_map(self.name(), options)?
+                .get("ignorenullfields")
+                .is_none_or(|v| !v.eq_ignore_ascii_case("false")),
+            None => true,
+        };
+
+        let input = args.args[0].to_array(args.number_rows)?;
+        let result = to_json_inner(&input, ignore_null_fields)?;
+
+        Ok(ColumnarValue::Array(result))
+    }
+}
+
+fn to_json_inner(input: &ArrayRef, ignore_null_fields: bool) -> Result<ArrayRef> {
+    let writer = JsonWriter { ignore_null_fields };
+    let mut builder = StringBuilder::with_capacity(input.len(), input.len() * 16);
+    let mut buffer = String::new();
+
+    for row in 0..input.len() {
+        if input.is_null(row) {
+            builder.append_null();
+            continue;
+        }
+        buffer.clear();
+        writer.write_value(input.as_ref(), row, &mut buffer)?;
+        builder.append_value(&buffer);
+    }
+
+    Ok(Arc::new(builder.finish()))
+}
+
+struct JsonWriter {
+    ignore_null_fields: bool,
+}
+
+impl JsonWriter {
+    /// Appends the JSON representation of `array[idx]` to `out`
+    fn write_value(&self, array: &dyn Array, idx: usize, out: &mut String) -> Result<()> {
+        if array.is_null(idx) {
+            out.push_str("null");
+            return Ok(());
+        }
+
+        match array.data_type() {
+            DataType::Null => out.push_str("null"),
+            DataType::Boolean => out.push_str(if array.as_boolean().value(idx) {
+                "true"
+            } else {
+                "false"
+            }),
+            DataType::Int8 => {
+                write!(out, "{}", array.as_primitive::<Int8Type>().value(idx))?
+            }
+            DataType::Int16 => {
+                write!(out, "{}", array.as_primitive::<Int16Type>().value(idx))?
+            }
+            DataType::Int32 => {
+                write!(out, "{}", array.as_primitive::<Int32Type>().value(idx))?
+            }
+            DataType::Int64 => {
+                write!(out, "{}", array.as_primitive::<Int64Type>().value(idx))?
+            }
+            DataType::Float32 => {
+                write_float(array.as_primitive::<Float32Type>().value(idx), out)?
+            }
+            DataType::Float64 => {
+                write_float(array.as_primitive::<Float64Type>().value(idx), out)?
+            }
+            // decimals are written as unquoted numbers
+            DataType::Decimal128(_, _) | DataType::Decimal256(_, _) => {
+                out.push_str(&format_value(array, idx)?)
+            }
+            DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View => {
+                let value = match array.data_type() {
+                    DataType::Utf8 => array.as_string::<i32>().value(idx),
+                    DataType::LargeUtf8 => array.as_string::<i64>().value(idx),
+                    _ => array.as_string_view().value(idx),
+                };
+                write_json_string(value, out)?
+            }
+            DataType::List(_) | DataType::LargeList(_) => {
+                let values = match array.data_type() {
+                    DataType::List(_) => array.as_list::<i32>().value(idx),
+                    _ => array.as_list::<i64>().value(idx),
+                };
+                out.push('[');
+                for i in 0..values.len() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    self.write_value(values.as_ref(), i, out)?;
+                }
+                out.push(']');
+            }
+            DataType::Map(_, _) => {
+                let entries = array.as_map().value(idx);
+                let (keys, values) = (entries.column(0), entries.column(1));
+                out.push('{');
+                for i in 0..entries.len() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    write_json_string(&format_value(keys.as_ref(), i)?, out)?;
+                    out.push(':');
+                    self.write_value(values.as_ref(), i, out)?;
+                }
+                out.push('}');
+            }
+            DataType::Struct(fields) => {
+                let struct_array = array.as_struct();
+                out.push('{');
+                let mut first = true;
+                for (field, column) in fields.iter().zip(struct_array.columns()) {
+                    if self.ignore_null_fields && column.is_null(idx) {
+                        continue;
+                    }
+                    if !first {
+                        out.push(',');
+                    }
+                    first = false;
+                    write_json_string(field.name(), out)?;
+                    out.push(':');
+                    self.write_value(column.as_ref(), idx, out)?;
+                }
+                out.push('}');
+            }
+            // dates, timestamps, binary and other types are written as strings
+            _ => write_json_string(&format_value(array, idx)?, out)?,
+        }
+        Ok(())
+    }
+}
+
+/// Writes a floating point value, quoting non-finite values like Spark does
+fn write_float<T: Float + Debug>(value: T, out: &mut String) -> Result<()> {
+    if value.is_nan() {
+        out.push_str("\"NaN\"");
+    } else if value.is_infinite() {
+        out.push_str(if value.is_sign_positive() {
+            "\"Infinity\""
+        } else {
+            "\"-Infinity\""
+        });
+    } else {
+        write!(out, "{value:?}")?;
+    }
+    Ok(())
+}
+
+fn write_json_string(value: &str, out: &mut String) -> Result<()> {
+    match serde_json::to_string(value) {
+        Ok(escaped) => {
+            out.push_str(&escaped);
+            Ok(())
+        }
+        Err(e) => exec_err!("Failed to write JSON string: {e}"),
+    }
+}
+
+fn format_value(array: &dyn Array, idx: usize) -> Result<String> {
+    let formatter = ArrayFormatter::try_new(array, &FormatOptions::default())?;
+    Ok(formatter.value(idx).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Float64Array, Int32Array, ListArray, StringArray, StructArray};
+    use arrow::datatypes::Fields;
+
+    fn struct_input() -> ArrayRef {
+        let fields = Fields::from(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Utf8, true),
+            Field::new("c", DataType::Float64, true),
+        ]);
+        Arc::new(StructArray::new(
+            fields,
+            vec![
+                Arc::new(Int32Array::from(vec![Some(1), None, Some(3)])),
+                Arc::new(StringArray::from(vec![Some("x\"y"), Some("z"), None])),
+                Arc::new(Float64Array::from(vec![Some(1.0), Some(f64::NAN), None])),
+            ],
+            Some(vec![true, true, false].into()),
+        ))
+    }
+
+    #[test]
+    fn test_to_json_struct() {
+        let result = to_json_inner(&struct_input(), true).unwrap();
+        let result = result.as_string::<i32>();
+        assert_eq!(result.value(0), r#"{"a":1,"b":"x\"y","c":1.0}"#);
+        assert_eq!(result.value(1), r#"{"b":"z","c":"NaN"}"#);
+        assert!(result.is_null(2));
+
+        let result = to_json_inner(&struct_input(), false).unwrap();
+        assert_eq!(
+            result.as_string::<i32>().value(1),
+            r#"{"a":null,"b":"z","c":"NaN"}"#
+        );
+    }
+
+    #[test]
+    fn test_to_json_list() {
+        let input: ArrayRef =
+            Arc::new(ListArray::from_iter_primitive::<Int32Type, _, _>(vec![
+                Some(vec![Some(1), None, Some(2)]),
+                Some(vec![]),
+            ]));
+        let result = to_json_inner(&input, true).unwrap();
+        let result = result.as_string::<i32>();
+        assert_eq!(result.value(0), "[1,null,2]");
+        assert_eq!(result.value(1), "[]");
+    }
+}
@@ -37,6 +37,7 @@ mod null_utils;
 pub mod predicate;
 pub mod string;
 pub mod r#struct;
+pub mod schema_utils;
 pub mod table;
 pub mod url;
 pub mod utils;
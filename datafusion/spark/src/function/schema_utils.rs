@@ -0,0 +1,482 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Helpers for Spark DDL-formatted schema strings, as accepted by functions
+//! like `from_json` and produced by functions like `schema_of_json`.
+
+use std::sync::Arc;
+
+use arrow::datatypes::{DataType, Field, Fields, TimeUnit};
+use datafusion_common::{Result, exec_datafusion_err, exec_err};
+
+/// Default precision and scale of a Spark `DECIMAL` without parameters
+const DEFAULT_DECIMAL_PRECISION: u8 = 10;
+const DEFAULT_DECIMAL_SCALE: i8 = 0;
+
+/// Parses a Spark DDL schema string into an Arrow [`DataType`].
+///
+/// Both of the forms accepted by Spark are supported:
+///
+/// - a comma separated field list such as `a INT, b ARRAY<STRING>`, which is
+///   returned as a [`DataType::Struct`]
+/// - a single type such as `STRUCT<a: INT>`, `ARRAY<INT>` or `MAP<STRING, INT>`
+pub fn parse_ddl_schema(ddl: &str) -> Result<DataType> {
+    let tokens = tokenize(ddl)?;
+    let mut parser = DdlParser { tokens, pos: 0 };
+
+    let is_single_type = matches!(
+        (parser.peek(), parser.tokens.get(1)),
+        (Some(Token::Word(w)), Some(Token::LAngle))
+            if ["STRUCT", "ARRAY", "MAP"].iter().any(|k| w.eq_ignore_ascii_case(k))
+    );
+    let data_type = if is_single_type {
+        parser.parse_type()?
+    } else {
+        DataType::Struct(parser.parse_fields(None)?)
+    };
+
+    match parser.peek() {
+        None => Ok(data_type),
+        Some(token) => exec_err!("Unexpected token {token:?} in DDL schema '{ddl}'"),
+    }
+}
+
+/// Formats a [`DataType`] the way Spark renders a `DataType.sql`, for
+/// example `STRUCT<a: BIGINT, b: ARRAY<STRING>>`.
+pub fn data_type_to_ddl(data_type: &DataType) -> Result<String> {
+    let ddl = match data_type {
+        DataType::Null | DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View => {
+            "STRING".to_string()
+        }
+        DataType::Boolean => "BOOLEAN".to_string(),
+        DataType::Int8 => "TINYINT".to_string(),
+        DataType::Int16 => "SMALLINT".to_string(),
+        DataType::Int32 => "INT".to_string(),
+        DataType::Int64 => "BIGINT".to_string(),
+        DataType::Float32 => "FLOAT".to_string(),
+        DataType::Float64 => "DOUBLE".to_string(),
+        DataType::Decimal128(p, s) => format!("DECIMAL({p},{s})"),
+        DataType::Binary | DataType::LargeBinary | DataType::BinaryView => {
+            "BINARY".to_string()
+        }
+        DataType::Date32 => "DATE".to_string(),
+        DataType::Timestamp(_, Some(_)) => "TIMESTAMP".to_string(),
+        DataType::Timestamp(_, None) => "TIMESTAMP_NTZ".to_string(),
+        DataType::List(field) | DataType::LargeList(field) => {
+            format!("ARRAY<{}>", data_type_to_ddl(field.data_type())?)
+        }
+        DataType::Map(entries, _) => {
+            let DataType::Struct(kv) = entries.data_type() else {
+                return exec_err!("Invalid map entries type {}", entries.data_type());
+            };
+            format!(
+                "MAP<{}, {}>",
+                data_type_to_ddl(kv[0].data_type())?,
+                data_type_to_ddl(kv[1].data_type())?
+            )
+        }
+        DataType::Struct(fields) => {
+            let fields = fields
+                .iter()
+                .map(|f| {
+                    Ok(format!(
+                        "{}: {}",
+                        quote_if_needed(f.name()),
+                        data_type_to_ddl(f.data_type())?
+                    ))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            format!("STRUCT<{}>", fields.join(", "))
+        }
+        other => return exec_err!("Data type {other} has no Spark DDL representation"),
+    };
+    Ok(ddl)
+}
+
+/// Marks every nested field as nullable, keeping map keys non-nullable
+pub fn as_nullable(data_type: DataType) -> DataType {
+    match data_type {
+        DataType::List(field) => {
+            DataType::new_list(as_nullable(field.data_type().clone()), true)
+        }
+        DataType::Map(entries, sorted) => {
+            let DataType::Struct(kv) = entries.data_type() else {
+                return DataType::Map(entries, sorted);
+            };
+            let kv: Fields = vec![
+                Arc::clone(&kv[0]),
+                Arc::new(
+                    kv[1]
+                        .as_ref()
+                        .clone()
+                        .with_data_type(as_nullable(kv[1].data_type().clone()))
+                        .with_nullable(true),
+                ),
+            ]
+            .into();
+            DataType::Map(
+                Arc::new(
+                    entries
+                        .as_ref()
+                        .clone()
+                        .with_data_type(DataType::Struct(kv)),
+                ),
+                sorted,
+            )
+        }
+        DataType::Struct(fields) => DataType::Struct(
+            fields
+                .iter()
+                .map(|f| {
+                    f.as_ref()
+                        .clone()
+                        .with_data_type(as_nullable(f.data_type().clone()))
+                        .with_nullable(true)
+                })
+                .collect::<Vec<_>>()
+                .into(),
+        ),
+        other => other,
+    }
+}
+
+/// Quotes a field name with backticks unless it is a plain identifier
+fn quote_if_needed(name: &str) -> String {
+    let is_plain = !name.is_empty()
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !name.chars().all(|c| c.is_ascii_digit());
+    if is_plain {
+        name.to_string()
+    } else {
+        format!("`{}`", name.replace('`', "``"))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    StringLiteral(String),
+    Number(u64),
+    LAngle,
+    RAngle,
+    LParen,
+    RParen,
+    Comma,
+    Colon,
+}
+
+fn tokenize(ddl: &str) -> Result<Vec<Token>> {
+    let mut tokens = vec![];
+    let mut chars = ddl.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '<' | '>' | '(' | ')' | ',' | ':' => {
+                chars.next();
+                tokens.push(match c {
+                    '<' => Token::LAngle,
+                    '>' => Token::RAngle,
+                    '(' => Token::LParen,
+                    ')' => Token::RParen,
+                    ',' => Token::Comma,
+                    _ => Token::Colon,
+                });
+            }
+            '`' | '\'' | '"' => {
+                chars.next();
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        // a doubled quote character escapes itself
+                        Some(q) if q == c && chars.peek() == Some(&c) => {
+                            chars.next();
+                            value.push(c);
+                        }
+                        Some(q) if q == c => break,
+                        Some(other) => value.push(other),
+                        None => {
+                            return exec_err!("Unterminated quote in DDL schema '{ddl}'");
+                        }
+                    }
+                }
+                tokens.push(if c == '`' {
+                    Token::Quoted(value)
+                } else {
+                    Token::StringLiteral(value)
+                });
+            }
+            c if c.is_ascii_digit() => {
+                let mut digits = String::new();
+                while let Some(&d) = chars.peek().filter(|d| d.is_ascii_digit()) {
+                    digits.push(d);
+                    chars.next();
+                }
+                let number = digits.parse().map_err(|_| {
+                    exec_datafusion_err!("Invalid number {digits} in DDL schema '{ddl}'")
+                })?;
+                tokens.push(Token::Number(number));
+            }
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut word = String::new();
+                while let Some(&w) =
+                    chars.peek().filter(|w| w.is_alphanumeric() || **w == '_')
+                {
+                    word.push(w);
+                    chars.next();
+                }
+                tokens.push(Token::Word(word));
+            }
+            other => {
+                return exec_err!("Unexpected character '{other}' in DDL schema '{ddl}'");
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct DdlParser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl DdlParser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn consume(&mut self, expected: &Token) -> bool {
+        if self.peek() == Some(expected) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn consume_keyword(&mut self, keyword: &str) -> bool {
+        match self.peek() {
+            Some(Token::Word(w)) if w.eq_ignore_ascii_case(keyword) => {
+                self.pos += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<()> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            other => exec_err!("Expected {expected:?} in DDL schema, got {other:?}"),
+        }
+    }
+
+    fn expect_number(&mut self) -> Result<u64> {
+        match self.next() {
+            Some(Token::Number(n)) => Ok(n),
+            other => exec_err!("Expected a number in DDL schema, got {other:?}"),
+        }
+    }
+
+    /// Parses `name [:] type [NOT NULL] [COMMENT '...']` entries separated by
+    /// commas, until `terminator` (or the end of input if `None`) is reached
+    fn parse_fields(&mut self, terminator: Option<&Token>) -> Result<Fields> {
+        let mut fields = vec![];
+        loop {
+            let name = match self.next() {
+                Some(Token::Word(name)) | Some(Token::Quoted(name)) => name,
+                other => {
+                    return exec_err!(
+                        "Expected a field name in DDL schema, got {other:?}"
+                    );
+                }
+            };
+            self.consume(&Token::Colon);
+            let data_type = self.parse_type()?;
+            let nullable = !(self.consume_keyword("NOT") && self.consume_keyword("NULL"));
+            if self.consume_keyword("COMMENT") {
+                match self.next() {
+                    Some(Token::StringLiteral(_)) => {}
+                    other => {
+                        return exec_err!(
+                            "Expected a comment string in DDL schema, got {other:?}"
+                        );
+                    }
+                }
+            }
+            fields.push(Field::new(name, data_type, nullable));
+
+            if !self.consume(&Token::Comma) {
+                break;
+            }
+        }
+
+        if let Some(terminator) = terminator {
+            self.expect(terminator.clone())?;
+        }
+        Ok(fields.into())
+    }
+
+    fn parse_type(&mut self) -> Result<DataType> {
+        let name = match self.next() {
+            Some(Token::Word(name)) => name.to_ascii_uppercase(),
+            other => {
+                return exec_err!("Expected a data type in DDL schema, got {other:?}");
+            }
+        };
+
+        let data_type = match name.as_str() {
+            "BOOLEAN" => DataType::Boolean,
+            "TINYINT" | "BYTE" => DataType::Int8,
+            "SMALLINT" | "SHORT" => DataType::Int16,
+            "INT" | "INTEGER" => DataType::Int32,
+            "BIGINT" | "LONG" => DataType::Int64,
+            "FLOAT" | "REAL" => DataType::Float32,
+            "DOUBLE" => DataType::Float64,
+            "STRING" => DataType::Utf8,
+            "VARCHAR" | "CHAR" => {
+                self.expect(Token::LParen)?;
+                self.expect_number()?;
+                self.expect(Token::RParen)?;
+                DataType::Utf8
+            }
+            "BINARY" => DataType::Binary,
+            "DATE" => DataType::Date32,
+            "TIMESTAMP" | "TIMESTAMP_LTZ" => {
+                DataType::Timestamp(TimeUnit::Microsecond, Some(Arc::from("UTC")))
+            }
+            "TIMESTAMP_NTZ" => DataType::Timestamp(TimeUnit::Microsecond, None),
+            "DECIMAL" | "DEC" | "NUMERIC" => {
+                let (mut precision, mut scale) =
+                    (DEFAULT_DECIMAL_PRECISION, DEFAULT_DECIMAL_SCALE);
+                if self.consume(&Token::LParen) {
+                    precision = u8::try_from(self.expect_number()?).map_err(|_| {
+                        exec_datafusion_err!("Decimal precision too large")
+                    })?;
+                    scale = 0;
+                    if self.consume(&Token::Comma) {
+                        scale = i8::try_from(self.expect_number()?).map_err(|_| {
+                            exec_datafusion_err!("Decimal scale too large")
+                        })?;
+                    }
+                    self.expect(Token::RParen)?;
+                }
+                if precision == 0 || precision > 38 || scale as u8 > precision {
+                    return exec_err!(
+                        "Invalid DECIMAL({precision},{scale}) in DDL schema"
+                    );
+                }
+                DataType::Decimal128(precision, scale)
+            }
+            "ARRAY" => {
+                self.expect(Token::LAngle)?;
+                let element = self.parse_type()?;
+                self.expect(Token::RAngle)?;
+                DataType::new_list(element, true)
+            }
+            "MAP" => {
+                self.expect(Token::LAngle)?;
+                let key = self.parse_type()?;
+                self.expect(Token::Comma)?;
+                let value = self.parse_type()?;
+                self.expect(Token::RAngle)?;
+                let entries = Field::new(
+                    "entries",
+                    DataType::Struct(
+                        vec![
+                            Field::new("key", key, false),
+                            Field::new("value", value, true),
+                        ]
+                        .into(),
+                    ),
+                    false,
+                );
+                DataType::Map(Arc::new(entries), false)
+            }
+            "STRUCT" => {
+                self.expect(Token::LAngle)?;
+                if self.consume(&Token::RAngle) {
+                    DataType::Struct(Fields::empty())
+                } else {
+                    DataType::Struct(self.parse_fields(Some(&Token::RAngle))?)
+                }
+            }
+            other => return exec_err!("Unsupported data type '{other}' in DDL schema"),
+        };
+        Ok(data_type)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_field_list() {
+        let parsed =
+            parse_ddl_schema("a INT, `b c` ARRAY<STRING> NOT NULL, d DECIMAL(5, 2)")
+                .unwrap();
+        let expected = DataType::Struct(
+            vec![
+                Field::new("a", DataType::Int32, true),
+                Field::new("b c", DataType::new_list(DataType::Utf8, true), false),
+                Field::new("d", DataType::Decimal128(5, 2), true),
+            ]
+            .into(),
+        );
+        assert_eq!(parsed, expected);
+    }
+
+    #[test]
+    fn test_parse_single_type() {
+        let parsed =
+            parse_ddl_schema("struct<a: bigint, b: map<string, double>>").unwrap();
+        assert_eq!(
+            data_type_to_ddl(&parsed).unwrap(),
+            "STRUCT<a: BIGINT, b: MAP<STRING, DOUBLE>>"
+        );
+        assert_eq!(
+            parse_ddl_schema("ARRAY<INT>").unwrap(),
+            DataType::new_list(DataType::Int32, true)
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(parse_ddl_schema("a FOO").is_err());
+        assert!(parse_ddl_schema("a INT,").is_err());
+        assert!(parse_ddl_schema("STRUCT<a: INT").is_err());
+        assert!(parse_ddl_schema("a DECIMAL(40, 2)").is_err());
+    }
+
+    #[test]
+    fn test_quote_if_needed() {
+        assert_eq!(quote_if_needed("abc_1"), "abc_1");
+        assert_eq!(quote_if_needed("a b"), "`a b`");
+        assert_eq!(quote_if_needed("123"), "`123`");
+    }
+}
@@ -15,6 +15,94 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use arrow::array::{Array, AsArray};
+use arrow::compute::cast;
+use arrow::datatypes::DataType;
+use datafusion_common::{Result, ScalarValue, exec_err};
+use datafusion_expr::ColumnarValue;
+use datafusion_physical_expr::PhysicalExpr;
+use datafusion_physical_expr::expressions::Literal;
+
+/// Reads a constant Spark `options` map argument (e.g. `map('mode', 'FAILFAST')`)
+/// into a map whose keys are lower cased, as Spark option names are case-insensitive.
+pub fn options_from_map(
+    function_name: &str,
+    options: &ColumnarValue,
+) -> Result<HashMap<String, String>> {
+    let options = options.to_array(1)?;
+    let Some(options) = options.as_map_opt() else {
+        return exec_err!(
+            "{function_name} expects its options to be a map, got {}",
+            options.data_type()
+        );
+    };
+    if options.is_empty() || options.is_null(0) {
+        return Ok(HashMap::new());
+    }
+
+    let entries = options.value(0);
+    let keys = cast(entries.column(0), &DataType::Utf8)?;
+    let values = cast(entries.column(1), &DataType::Utf8)?;
+    let (keys, values) = (keys.as_string::<i32>(), values.as_string::<i32>());
+
+    Ok((0..keys.len())
+        .filter(|&i| !values.is_null(i))
+        .map(|i| (keys.value(i).to_lowercase(), values.value(i).to_string()))
+        .collect())
+}
+
+/// Spark's handling of malformed records in functions like `from_json`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseMode {
+    /// Sets the values that cannot be parsed to NULL
+    Permissive,
+    /// Raises an error on the first malformed record
+    FailFast,
+}
+
+impl ParseMode {
+    /// Reads the `mode` option out of options returned by [`options_from_map`]
+    pub fn from_options(
+        function_name: &str,
+        options: &HashMap<String, String>,
+    ) -> Result<Self> {
+        match options
+            .get("mode")
+            .map(|m| m.to_ascii_uppercase())
+            .as_deref()
+        {
+            Some("FAILFAST") => Ok(ParseMode::FailFast),
+            Some("DROPMALFORMED") => exec_err!(
+                "{function_name} doesn't support the DROPMALFORMED mode. Acceptable modes are PERMISSIVE and FAILFAST"
+            ),
+            // Spark falls back to PERMISSIVE for unknown modes
+            _ => Ok(ParseMode::Permissive),
+        }
+    }
+}
+
+/// Reads the argument at `index` of a window function, which Spark requires
+/// to be a constant. Returns `None` if the argument was not given.
+pub fn window_literal_argument(
+    function_name: &str,
+    args: &[Arc<dyn PhysicalExpr>],
+    index: usize,
+) -> Result<Option<ScalarValue>> {
+    let Some(arg) = args.get(index) else {
+        return Ok(None);
+    };
+    match arg.downcast_ref::<Literal>() {
+        Some(literal) => Ok(Some(literal.value().clone())),
+        None => exec_err!(
+            "{function_name} requires argument {} to be a constant, got {arg}",
+            index + 1
+        ),
+    }
+}
+
 #[cfg(test)]
 pub mod test {
     /// $FUNC ScalarUDFImpl to test
@@ -0,0 +1,114 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::iter;
+use std::ops::Range;
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, Float64Array};
+use arrow::datatypes::{DataType, Field, FieldRef};
+use datafusion_common::Result;
+use datafusion_expr::{PartitionEvaluator, Signature, Volatility, WindowUDFImpl};
+use datafusion_functions_window_common::field::WindowUDFFieldArgs;
+use datafusion_functions_window_common::partition::PartitionEvaluatorArgs;
+
+/// Spark-compatible `cume_dist` window function
+///
+/// <https://spark.apache.org/docs/latest/api/sql/index.html#cume_dist>
+///
+/// Returns the number of rows preceding or peer with the current row divided
+/// by the number of rows in the partition, as a non-nullable `DOUBLE`.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct SparkCumeDist {
+    signature: Signature,
+}
+
+impl Default for SparkCumeDist {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SparkCumeDist {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::nullary(Volatility::Immutable),
+        }
+    }
+}
+
+impl WindowUDFImpl for SparkCumeDist {
+    fn name(&self) -> &str {
+        "cume_dist"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn partition_evaluator(
+        &self,
+        _partition_evaluator_args: PartitionEvaluatorArgs,
+    ) -> Result<Box<dyn PartitionEvaluator>> {
+        Ok(Box::new(SparkCumeDistEvaluator))
+    }
+
+    fn field(&self, field_args: WindowUDFFieldArgs) -> Result<FieldRef> {
+        Ok(Field::new(field_args.name(), DataType::Float64, false).into())
+    }
+}
+
+#[derive(Debug)]
+struct SparkCumeDistEvaluator;
+
+impl PartitionEvaluator for SparkCumeDistEvaluator {
+    fn evaluate_all_with_rank(
+        &self,
+        num_rows: usize,
+        ranks_in_partition: &[Range<usize>],
+    ) -> Result<ArrayRef> {
+        let num_rows = num_rows as f64;
+        let result =
+            Float64Array::from_iter_values(ranks_in_partition.iter().flat_map(|peers| {
+                let value = peers.end as f64 / num_rows;
+                iter::repeat_n(value, peers.len())
+            }));
+        Ok(Arc::new(result))
+    }
+
+    fn include_rank(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::AsArray;
+    use arrow::datatypes::Float64Type;
+
+    #[test]
+    fn test_cume_dist() {
+        let result = SparkCumeDistEvaluator
+            .evaluate_all_with_rank(4, &[0..2, 2..3, 3..4])
+            .unwrap();
+        assert_eq!(
+            result.as_primitive::<Float64Type>().values().to_vec(),
+            vec![0.5, 0.5, 0.75, 1.0]
+        );
+    }
+}
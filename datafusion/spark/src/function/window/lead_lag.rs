@@ -0,0 +1,272 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use arrow::array::{Array, ArrayRef, BooleanArray, UInt64Array};
+use arrow::compute::kernels::zip::zip;
+use arrow::compute::take;
+use arrow::datatypes::{DataType, FieldRef};
+use datafusion_common::{Result, exec_err};
+use datafusion_expr::{PartitionEvaluator, Signature, Volatility, WindowUDFImpl};
+use datafusion_functions_window_common::field::WindowUDFFieldArgs;
+use datafusion_functions_window_common::partition::PartitionEvaluatorArgs;
+
+use crate::function::error_utils::invalid_arg_count_exec_err;
+use crate::function::utils::window_literal_argument;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SparkLeadLagKind {
+    Lead,
+    Lag,
+}
+
+/// Spark-compatible `lead` and `lag` window functions
+///
+/// <https://spark.apache.org/docs/latest/api/sql/index.html#lead>
+/// <https://spark.apache.org/docs/latest/api/sql/index.html#lag>
+///
+/// `lead(input[, offset[, default]])` returns the value of `input` at the
+/// `offset`-th row after the current row in the partition, and `lag` the
+/// value `offset` rows before it. The offset defaults to 1 and must be a
+/// constant, but may be negative.
+///
+/// Unlike DataFusion's `lead` and `lag`, the default may be any expression
+/// and is evaluated on the current row. It is only used when the target row
+/// does not exist: a NULL input value at the target row is returned as is.
+///
+/// With `IGNORE NULLS` the offset counts only rows where `input` is not
+/// NULL, and the default is used if there are not enough such rows.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct SparkLeadLag {
+    signature: Signature,
+    kind: SparkLeadLagKind,
+}
+
+impl SparkLeadLag {
+    pub fn new(kind: SparkLeadLagKind) -> Self {
+        Self {
+            signature: Signature::user_defined(Volatility::Immutable),
+            kind,
+        }
+    }
+}
+
+impl WindowUDFImpl for SparkLeadLag {
+    fn name(&self) -> &str {
+        match self.kind {
+            SparkLeadLagKind::Lead => "lead",
+            SparkLeadLagKind::Lag => "lag",
+        }
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        if !(1..=3).contains(&arg_types.len()) {
+            return Err(invalid_arg_count_exec_err(
+                self.name(),
+                (1, 3),
+                arg_types.len(),
+            ));
+        }
+        if let Some(offset) = arg_types.get(1)
+            && !offset.is_integer()
+            && !offset.is_null()
+        {
+            return exec_err!("{} requires an integer offset, got {offset}", self.name());
+        }
+
+        // the default is cast to the input type, unless the input is an
+        // untyped NULL whose type is then taken from the default
+        let value_type = match (&arg_types[0], arg_types.get(2)) {
+            (DataType::Null, Some(default)) => default.clone(),
+            (input, _) => input.clone(),
+        };
+        // the offset is left as is, so that it remains a literal
+        let mut coerced = vec![value_type.clone()];
+        coerced.extend(arg_types.get(1).cloned());
+        if arg_types.len() > 2 {
+            coerced.push(value_type);
+        }
+        Ok(coerced)
+    }
+
+    fn partition_evaluator(
+        &self,
+        partition_evaluator_args: PartitionEvaluatorArgs,
+    ) -> Result<Box<dyn PartitionEvaluator>> {
+        let offset = match window_literal_argument(
+            self.name(),
+            partition_evaluator_args.input_exprs(),
+            1,
+        )? {
+            None => 1,
+            Some(offset) if offset.is_null() => {
+                return exec_err!("{} requires a non-NULL offset", self.name());
+            }
+            Some(offset) => i64::try_from(offset.cast_to(&DataType::Int64)?)?,
+        };
+
+        // the evaluator works with the distance from the current row to the
+        // target row, which is negative for `lag`
+        let distance = match self.kind {
+            SparkLeadLagKind::Lead => offset,
+            SparkLeadLagKind::Lag => -offset,
+        };
+        Ok(Box::new(SparkLeadLagEvaluator {
+            distance,
+            ignore_nulls: partition_evaluator_args.ignore_nulls(),
+        }))
+    }
+
+    fn field(&self, field_args: WindowUDFFieldArgs) -> Result<FieldRef> {
+        let input_fields = field_args.input_fields();
+        let value_field = match (input_fields[0].data_type(), input_fields.get(2)) {
+            (DataType::Null, Some(default)) => default,
+            _ => &input_fields[0],
+        };
+        Ok(value_field
+            .as_ref()
+            .clone()
+            .with_name(field_args.name())
+            .with_nullable(true)
+            .into())
+    }
+}
+
+#[derive(Debug)]
+struct SparkLeadLagEvaluator {
+    /// Signed distance from the current row to the target row
+    distance: i64,
+    ignore_nulls: bool,
+}
+
+impl SparkLeadLagEvaluator {
+    /// The index of the target row of each row, or `None` if it does not
+    /// exist in the partition
+    fn target_indices(&self, input: &ArrayRef) -> Vec<Option<u64>> {
+        let num_rows = input.len() as i64;
+        if !self.ignore_nulls || self.distance == 0 {
+            return (0..num_rows)
+                .map(|row| {
+                    let target = row.checked_add(self.distance)?;
+                    (0..num_rows).contains(&target).then_some(target as u64)
+                })
+                .collect();
+        }
+
+        let nulls = input.logical_nulls();
+        let valid = (0..input.len())
+            .filter(|&row| nulls.as_ref().is_none_or(|nulls| nulls.is_valid(row)))
+            .collect::<Vec<_>>();
+        let steps = self.distance.unsigned_abs() as usize;
+        (0..input.len())
+            .map(|row| {
+                let target = if self.distance > 0 {
+                    let after = valid.partition_point(|&v| v <= row);
+                    valid.get(after.checked_add(steps - 1)?)
+                } else {
+                    let before = valid.partition_point(|&v| v < row);
+                    valid.get(before.checked_sub(steps)?)
+                };
+                target.map(|&target| target as u64)
+            })
+            .collect()
+    }
+}
+
+impl PartitionEvaluator for SparkLeadLagEvaluator {
+    fn evaluate_all(
+        &mut self,
+        values: &[ArrayRef],
+        _num_rows: usize,
+    ) -> Result<ArrayRef> {
+        let input = &values[0];
+        let indices = UInt64Array::from(self.target_indices(input));
+        let shifted = take(input.as_ref(), &indices, None)?;
+
+        match values.get(2) {
+            Some(default) => {
+                let has_target = BooleanArray::from_iter(
+                    indices.iter().map(|index| Some(index.is_some())),
+                );
+                Ok(zip(&has_target, &shifted, default)?)
+            }
+            None => Ok(shifted),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{AsArray, Int32Array};
+    use arrow::datatypes::Int32Type;
+    use std::sync::Arc;
+
+    fn evaluate(distance: i64, ignore_nulls: bool, default: Option<i32>) -> Int32Array {
+        let input: ArrayRef = Arc::new(Int32Array::from(vec![
+            Some(1),
+            None,
+            Some(3),
+            None,
+            Some(5),
+        ]));
+        let mut values = vec![Arc::clone(&input), Arc::clone(&input)];
+        if let Some(default) = default {
+            // a per-row default, as Spark allows arbitrary expressions
+            values.push(Arc::new(Int32Array::from_iter_values(
+                (0..5).map(|row| default + row),
+            )));
+        }
+        let mut evaluator = SparkLeadLagEvaluator {
+            distance,
+            ignore_nulls,
+        };
+        let result = evaluator.evaluate_all(&values, 5).unwrap();
+        result.as_primitive::<Int32Type>().clone()
+    }
+
+    #[test]
+    fn test_lead_lag() {
+        assert_eq!(
+            evaluate(-1, false, None),
+            Int32Array::from(vec![None, Some(1), None, Some(3), None])
+        );
+        assert_eq!(
+            evaluate(2, false, Some(10)),
+            Int32Array::from(vec![Some(3), None, Some(5), Some(13), Some(14)])
+        );
+        assert_eq!(
+            evaluate(0, false, None),
+            Int32Array::from(vec![Some(1), None, Some(3), None, Some(5)])
+        );
+    }
+
+    #[test]
+    fn test_lead_lag_ignore_nulls() {
+        assert_eq!(
+            evaluate(-1, true, None),
+            Int32Array::from(vec![None, Some(1), Some(1), Some(3), Some(3)])
+        );
+        assert_eq!(
+            evaluate(2, true, Some(10)),
+            Int32Array::from(vec![Some(5), Some(5), Some(12), Some(13), Some(14)])
+        );
+    }
+}
@@ -18,8 +18,79 @@
 use datafusion_expr::WindowUDF;
 use std::sync::Arc;
 
-pub mod expr_fn {}
+pub mod cume_dist;
+pub mod lead_lag;
+pub mod nth_value;
+pub mod ntile;
+pub mod percent_rank;
+
+use lead_lag::{SparkLeadLag, SparkLeadLagKind};
+
+pub mod expr_fn {
+    use datafusion_functions::export_functions;
+
+    export_functions!((
+        cume_dist,
+        "Returns the fraction of rows preceding or peer with the current row",
+    ));
+    export_functions!((
+        lag,
+        "Returns the value of the row at the given offset before the current row, or the default value",
+        args,
+    ));
+    export_functions!((
+        lead,
+        "Returns the value of the row at the given offset after the current row, or the default value",
+        args,
+    ));
+    export_functions!((
+        nth_value,
+        "Returns the value of the row at the given offset in the window frame",
+        args,
+    ));
+    export_functions!((
+        ntile,
+        "Divides the rows of each partition into buckets and returns the bucket of the current row",
+        buckets
+    ));
+    export_functions!((
+        percent_rank,
+        "Returns the relative rank of the current row within its partition",
+    ));
+}
+
+pub fn cume_dist() -> Arc<WindowUDF> {
+    Arc::new(WindowUDF::new_from_impl(cume_dist::SparkCumeDist::new()))
+}
+pub fn lag() -> Arc<WindowUDF> {
+    Arc::new(WindowUDF::new_from_impl(SparkLeadLag::new(
+        SparkLeadLagKind::Lag,
+    )))
+}
+pub fn lead() -> Arc<WindowUDF> {
+    Arc::new(WindowUDF::new_from_impl(SparkLeadLag::new(
+        SparkLeadLagKind::Lead,
+    )))
+}
+pub fn nth_value() -> Arc<WindowUDF> {
+    Arc::new(WindowUDF::new_from_impl(nth_value::SparkNthValue::new()))
+}
+pub fn ntile() -> Arc<WindowUDF> {
+    Arc::new(WindowUDF::new_from_impl(ntile::SparkNtile::new()))
+}
+pub fn percent_rank() -> Arc<WindowUDF> {
+    Arc::new(WindowUDF::new_from_impl(
+        percent_rank::SparkPercentRank::new(),
+    ))
+}
 
 pub fn functions() -> Vec<Arc<WindowUDF>> {
-    vec![]
+    vec![
+        cume_dist(),
+        lag(),
+        lead(),
+        nth_value(),
+        ntile(),
+        percent_rank(),
+    ]
 }
@@ -0,0 +1,198 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::ops::Range;
+
+use arrow::array::{Array, ArrayRef};
+use arrow::datatypes::{DataType, FieldRef};
+use datafusion_common::{Result, ScalarValue, exec_err};
+use datafusion_expr::{PartitionEvaluator, Signature, Volatility, WindowUDFImpl};
+use datafusion_functions_window_common::field::WindowUDFFieldArgs;
+use datafusion_functions_window_common::partition::PartitionEvaluatorArgs;
+
+use crate::function::error_utils::invalid_arg_count_exec_err;
+use crate::function::utils::window_literal_argument;
+
+/// Spark-compatible `nth_value` window function
+///
+/// <https://spark.apache.org/docs/latest/api/sql/index.html#nth_value>
+///
+/// `nth_value(input, offset[, ignoreNulls])` returns the value of `input` at
+/// the `offset`-th row of the window frame, or NULL if the frame has fewer
+/// rows. `offset` must be a positive constant.
+///
+/// Nulls are skipped when counting rows if either `IGNORE NULLS` is given
+/// or the constant `ignoreNulls` argument is true.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct SparkNthValue {
+    signature: Signature,
+}
+
+impl Default for SparkNthValue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SparkNthValue {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::user_defined(Volatility::Immutable),
+        }
+    }
+}
+
+impl WindowUDFImpl for SparkNthValue {
+    fn name(&self) -> &str {
+        "nth_value"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        if !(2..=3).contains(&arg_types.len()) {
+            return Err(invalid_arg_count_exec_err(
+                self.name(),
+                (2, 3),
+                arg_types.len(),
+            ));
+        }
+        if !arg_types[1].is_integer() {
+            return exec_err!(
+                "{} requires an integer offset, got {}",
+                self.name(),
+                arg_types[1]
+            );
+        }
+
+        if let Some(ignore_nulls) = arg_types.get(2)
+            && !matches!(ignore_nulls, DataType::Boolean | DataType::Null)
+        {
+            return exec_err!(
+                "{} requires ignoreNulls to be a boolean, got {ignore_nulls}",
+                self.name()
+            );
+        }
+        Ok(arg_types.to_vec())
+    }
+
+    fn partition_evaluator(
+        &self,
+        partition_evaluator_args: PartitionEvaluatorArgs,
+    ) -> Result<Box<dyn PartitionEvaluator>> {
+        let args = partition_evaluator_args.input_exprs();
+        let n = match window_literal_argument(self.name(), args, 1)? {
+            Some(n) if !n.is_null() => i64::try_from(n.cast_to(&DataType::Int64)?)?,
+            _ => 0,
+        };
+        if n <= 0 {
+            return exec_err!(
+                "{} requires the offset to be a positive integer, got {n}",
+                self.name()
+            );
+        }
+
+        let ignore_nulls = match window_literal_argument(self.name(), args, 2)? {
+            Some(ScalarValue::Boolean(Some(ignore_nulls))) => ignore_nulls,
+            _ => false,
+        };
+
+        Ok(Box::new(SparkNthValueEvaluator {
+            n: n as usize,
+            ignore_nulls: ignore_nulls || partition_evaluator_args.ignore_nulls(),
+        }))
+    }
+
+    fn field(&self, field_args: WindowUDFFieldArgs) -> Result<FieldRef> {
+        Ok(field_args.input_fields()[0]
+            .as_ref()
+            .clone()
+            .with_name(field_args.name())
+            .with_nullable(true)
+            .into())
+    }
+}
+
+#[derive(Debug)]
+struct SparkNthValueEvaluator {
+    /// 1-based position of the row in the frame
+    n: usize,
+    ignore_nulls: bool,
+}
+
+impl PartitionEvaluator for SparkNthValueEvaluator {
+    fn evaluate(
+        &mut self,
+        values: &[ArrayRef],
+        range: &Range<usize>,
+    ) -> Result<ScalarValue> {
+        let input = &values[0];
+        let index = if self.ignore_nulls {
+            let nulls = input.logical_nulls();
+            range
+                .clone()
+                .filter(|&row| nulls.as_ref().is_none_or(|nulls| nulls.is_valid(row)))
+                .nth(self.n - 1)
+        } else {
+            range.clone().nth(self.n - 1)
+        };
+
+        match index {
+            Some(index) => ScalarValue::try_from_array(input, index),
+            None => ScalarValue::try_from(input.data_type()),
+        }
+    }
+
+    fn supports_bounded_execution(&self) -> bool {
+        true
+    }
+
+    fn uses_window_frame(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::Int32Array;
+    use std::sync::Arc;
+
+    fn evaluate(n: usize, ignore_nulls: bool, range: Range<usize>) -> ScalarValue {
+        let input: ArrayRef =
+            Arc::new(Int32Array::from(vec![None, Some(2), None, Some(4)]));
+        let mut evaluator = SparkNthValueEvaluator { n, ignore_nulls };
+        evaluator.evaluate(&[input], &range).unwrap()
+    }
+
+    #[test]
+    fn test_nth_value() {
+        assert_eq!(evaluate(2, false, 0..4), ScalarValue::Int32(Some(2)));
+        assert_eq!(evaluate(3, false, 0..4), ScalarValue::Int32(None));
+        assert_eq!(evaluate(3, false, 0..2), ScalarValue::Int32(None));
+        assert_eq!(evaluate(1, false, 1..4), ScalarValue::Int32(Some(2)));
+    }
+
+    #[test]
+    fn test_nth_value_ignore_nulls() {
+        assert_eq!(evaluate(1, true, 0..4), ScalarValue::Int32(Some(2)));
+        assert_eq!(evaluate(2, true, 0..4), ScalarValue::Int32(Some(4)));
+        assert_eq!(evaluate(2, true, 0..3), ScalarValue::Int32(None));
+    }
+}
@@ -0,0 +1,162 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, Int32Array};
+use arrow::datatypes::{DataType, Field, FieldRef};
+use datafusion_common::{Result, exec_err};
+use datafusion_expr::{PartitionEvaluator, Signature, Volatility, WindowUDFImpl};
+use datafusion_functions_window_common::field::WindowUDFFieldArgs;
+use datafusion_functions_window_common::partition::PartitionEvaluatorArgs;
+
+use crate::function::error_utils::invalid_arg_count_exec_err;
+use crate::function::utils::window_literal_argument;
+
+/// Spark-compatible `ntile` window function
+///
+/// <https://spark.apache.org/docs/latest/api/sql/index.html#ntile>
+///
+/// `ntile(n)` divides the rows of each partition into `n` buckets ranging
+/// from 1 to `n` and returns the bucket of the current row as an `INT`.
+///
+/// Like Spark, when the rows cannot be divided evenly the first
+/// `num_rows % n` buckets receive one extra row, e.g. 10 rows split into 4
+/// buckets have sizes 3, 3, 2 and 2. `n` must be a positive constant.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct SparkNtile {
+    signature: Signature,
+}
+
+impl Default for SparkNtile {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SparkNtile {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::user_defined(Volatility::Immutable),
+        }
+    }
+}
+
+impl WindowUDFImpl for SparkNtile {
+    fn name(&self) -> &str {
+        "ntile"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        if arg_types.len() != 1 {
+            return Err(invalid_arg_count_exec_err(
+                self.name(),
+                (1, 1),
+                arg_types.len(),
+            ));
+        }
+        if !arg_types[0].is_integer() && !arg_types[0].is_null() {
+            return exec_err!(
+                "{} requires an integer number of buckets, got {}",
+                self.name(),
+                arg_types[0]
+            );
+        }
+        Ok(arg_types.to_vec())
+    }
+
+    fn partition_evaluator(
+        &self,
+        partition_evaluator_args: PartitionEvaluatorArgs,
+    ) -> Result<Box<dyn PartitionEvaluator>> {
+        let buckets = window_literal_argument(
+            self.name(),
+            partition_evaluator_args.input_exprs(),
+            0,
+        )?;
+        let buckets = match buckets {
+            Some(buckets) if !buckets.is_null() => {
+                i64::try_from(buckets.cast_to(&DataType::Int64)?)?
+            }
+            _ => return exec_err!("Buckets expression must not be null"),
+        };
+        if buckets <= 0 {
+            return exec_err!("Buckets expression must be positive, but got: {buckets}");
+        }
+
+        Ok(Box::new(SparkNtileEvaluator {
+            buckets: buckets as usize,
+        }))
+    }
+
+    fn field(&self, field_args: WindowUDFFieldArgs) -> Result<FieldRef> {
+        Ok(Field::new(field_args.name(), DataType::Int32, false).into())
+    }
+}
+
+#[derive(Debug)]
+struct SparkNtileEvaluator {
+    buckets: usize,
+}
+
+impl PartitionEvaluator for SparkNtileEvaluator {
+    fn evaluate_all(
+        &mut self,
+        _values: &[ArrayRef],
+        num_rows: usize,
+    ) -> Result<ArrayRef> {
+        let bucket_size = num_rows / self.buckets;
+        let remainder = num_rows % self.buckets;
+        // rows that belong to the larger buckets at the start
+        let large_rows = remainder * (bucket_size + 1);
+
+        let result = Int32Array::from_iter_values((0..num_rows).map(|row| {
+            let bucket = if row < large_rows {
+                row / (bucket_size + 1)
+            } else {
+                remainder + (row - large_rows) / bucket_size
+            };
+            bucket as i32 + 1
+        }));
+        Ok(Arc::new(result))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::AsArray;
+    use arrow::datatypes::Int32Type;
+
+    fn ntile(buckets: usize, num_rows: usize) -> Vec<i32> {
+        let mut evaluator = SparkNtileEvaluator { buckets };
+        let result = evaluator.evaluate_all(&[], num_rows).unwrap();
+        result.as_primitive::<Int32Type>().values().to_vec()
+    }
+
+    #[test]
+    fn test_ntile() {
+        assert_eq!(ntile(4, 10), vec![1, 1, 1, 2, 2, 2, 3, 3, 4, 4]);
+        assert_eq!(ntile(2, 4), vec![1, 1, 2, 2]);
+        assert_eq!(ntile(5, 3), vec![1, 2, 3]);
+        assert_eq!(ntile(3, 0), Vec::<i32>::new());
+    }
+}
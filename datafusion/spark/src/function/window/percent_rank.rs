@@ -0,0 +1,121 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::iter;
+use std::ops::Range;
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, Float64Array};
+use arrow::datatypes::{DataType, Field, FieldRef};
+use datafusion_common::Result;
+use datafusion_expr::{PartitionEvaluator, Signature, Volatility, WindowUDFImpl};
+use datafusion_functions_window_common::field::WindowUDFFieldArgs;
+use datafusion_functions_window_common::partition::PartitionEvaluatorArgs;
+
+/// Spark-compatible `percent_rank` window function
+///
+/// <https://spark.apache.org/docs/latest/api/sql/index.html#percent_rank>
+///
+/// Returns `(rank - 1) / (num_rows - 1)` as a non-nullable `DOUBLE`, where
+/// peers share the rank of their first row. A partition with a single row
+/// has a percent rank of 0.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct SparkPercentRank {
+    signature: Signature,
+}
+
+impl Default for SparkPercentRank {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SparkPercentRank {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::nullary(Volatility::Immutable),
+        }
+    }
+}
+
+impl WindowUDFImpl for SparkPercentRank {
+    fn name(&self) -> &str {
+        "percent_rank"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn partition_evaluator(
+        &self,
+        _partition_evaluator_args: PartitionEvaluatorArgs,
+    ) -> Result<Box<dyn PartitionEvaluator>> {
+        Ok(Box::new(SparkPercentRankEvaluator))
+    }
+
+    fn field(&self, field_args: WindowUDFFieldArgs) -> Result<FieldRef> {
+        Ok(Field::new(field_args.name(), DataType::Float64, false).into())
+    }
+}
+
+#[derive(Debug)]
+struct SparkPercentRankEvaluator;
+
+impl PartitionEvaluator for SparkPercentRankEvaluator {
+    fn evaluate_all_with_rank(
+        &self,
+        num_rows: usize,
+        ranks_in_partition: &[Range<usize>],
+    ) -> Result<ArrayRef> {
+        let denominator = num_rows.saturating_sub(1).max(1) as f64;
+        let result =
+            Float64Array::from_iter_values(ranks_in_partition.iter().flat_map(|peers| {
+                let value = peers.start as f64 / denominator;
+                iter::repeat_n(value, peers.len())
+            }));
+        Ok(Arc::new(result))
+    }
+
+    fn include_rank(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::AsArray;
+    use arrow::datatypes::Float64Type;
+
+    fn percent_rank(num_rows: usize, ranks: &[Range<usize>]) -> Vec<f64> {
+        let result = SparkPercentRankEvaluator
+            .evaluate_all_with_rank(num_rows, ranks)
+            .unwrap();
+        result.as_primitive::<Float64Type>().values().to_vec()
+    }
+
+    #[test]
+    fn test_percent_rank() {
+        assert_eq!(percent_rank(0, &[]), Vec::<f64>::new());
+        assert_eq!(percent_rank(1, &[0..1]), vec![0.0]);
+        assert_eq!(
+            percent_rank(5, &[0..2, 2..3, 3..5]),
+            vec![0.0, 0.0, 0.5, 0.75, 0.75]
+        );
+    }
+}
@@ -0,0 +1,470 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! A minimal XML document model shared by the Spark XML functions.
+//!
+//! Nodes are stored in an arena in document order, so comparing two
+//! [`NodeId`]s compares their position in the document. Documents are parsed
+//! without namespace processing, like Spark does: prefixed names such as
+//! `ns:a` are kept as is and `xmlns` declarations are ordinary attributes.
+//!
+//! Documents are parsed and traversed without recursion, and elements can be
+//! nested at most [`MAX_NESTING_DEPTH`] levels deep.
+
+use datafusion_common::{Result, exec_err};
+
+/// Index of a node in an [`XmlDocument`]
+pub type NodeId = usize;
+
+/// Maximum depth of nested elements in a document
+pub const MAX_NESTING_DEPTH: usize = 1000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum XmlNodeKind {
+    /// The document node, parent of the document element
+    Root,
+    Element(String),
+    Attribute {
+        name: String,
+        value: String,
+    },
+    Text(String),
+}
+
+#[derive(Debug)]
+pub struct XmlNode {
+    pub kind: XmlNodeKind,
+    pub parent: Option<NodeId>,
+    pub children: Vec<NodeId>,
+    pub attributes: Vec<NodeId>,
+}
+
+/// A parsed, well-formed XML document
+#[derive(Debug)]
+pub struct XmlDocument {
+    nodes: Vec<XmlNode>,
+}
+
+impl XmlDocument {
+    /// The id of the document node
+    pub const ROOT: NodeId = 0;
+
+    /// Parses `xml`, returning an error if it is not a well-formed document.
+    ///
+    /// Comments, processing instructions and the document type declaration
+    /// are skipped.
+    pub fn parse(xml: &str) -> Result<Self> {
+        let mut parser = Parser {
+            input: xml,
+            pos: 0,
+            document: XmlDocument {
+                nodes: vec![XmlNode {
+                    kind: XmlNodeKind::Root,
+                    parent: None,
+                    children: vec![],
+                    attributes: vec![],
+                }],
+            },
+            open: vec![Self::ROOT],
+        };
+        parser.parse()?;
+        Ok(parser.document)
+    }
+
+    pub fn node(&self, id: NodeId) -> &XmlNode {
+        &self.nodes[id]
+    }
+
+    /// The single top-level element of the document
+    pub fn document_element(&self) -> Option<NodeId> {
+        self.nodes[Self::ROOT]
+            .children
+            .iter()
+            .copied()
+            .find(|&id| matches!(self.nodes[id].kind, XmlNodeKind::Element(_)))
+    }
+
+    /// The name of an element or attribute, or an empty string for other nodes
+    pub fn name(&self, id: NodeId) -> &str {
+        match &self.nodes[id].kind {
+            XmlNodeKind::Element(name) | XmlNodeKind::Attribute { name, .. } => name,
+            _ => "",
+        }
+    }
+
+    /// The XPath string-value of a node: the concatenation of all descendant
+    /// text for elements and the document, or the value itself otherwise
+    pub fn string_value(&self, id: NodeId) -> String {
+        match &self.nodes[id].kind {
+            XmlNodeKind::Attribute { value, .. } => value.clone(),
+            XmlNodeKind::Text(text) => text.clone(),
+            XmlNodeKind::Root | XmlNodeKind::Element(_) => {
+                let mut value = String::new();
+                self.append_text(id, &mut value);
+                value
+            }
+        }
+    }
+
+    fn append_text(&self, id: NodeId, out: &mut String) {
+        // children are pushed in reverse so that they are visited in order
+        let mut stack = self.nodes[id]
+            .children
+            .iter()
+            .rev()
+            .copied()
+            .collect::<Vec<_>>();
+        while let Some(node) = stack.pop() {
+            match &self.nodes[node].kind {
+                XmlNodeKind::Text(text) => out.push_str(text),
+                XmlNodeKind::Element(_) => {
+                    stack.extend(self.nodes[node].children.iter().rev().copied())
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Descendants of `id` in document order, excluding attributes
+    pub fn descendants(&self, id: NodeId) -> Vec<NodeId> {
+        let mut descendants = vec![];
+        let mut stack = self.nodes[id]
+            .children
+            .iter()
+            .rev()
+            .copied()
+            .collect::<Vec<_>>();
+        while let Some(node) = stack.pop() {
+            descendants.push(node);
+            stack.extend(self.nodes[node].children.iter().rev().copied());
+        }
+        descendants
+    }
+
+    /// Child elements of `id` in document order
+    pub fn child_elements(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        self.nodes[id]
+            .children
+            .iter()
+            .copied()
+            .filter(|&child| matches!(self.nodes[child].kind, XmlNodeKind::Element(_)))
+    }
+
+    fn push(&mut self, kind: XmlNodeKind, parent: NodeId) -> NodeId {
+        let id = self.nodes.len();
+        self.nodes.push(XmlNode {
+            kind,
+            parent: Some(parent),
+            children: vec![],
+            attributes: vec![],
+        });
+        id
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+    document: XmlDocument,
+    /// Currently open elements, starting with the document node
+    open: Vec<NodeId>,
+}
+
+impl Parser<'_> {
+    fn rest(&self) -> &str {
+        &self.input[self.pos..]
+    }
+
+    fn skip_past(&mut self, terminator: &str) -> Result<()> {
+        match self.rest().find(terminator) {
+            Some(offset) => {
+                self.pos += offset + terminator.len();
+                Ok(())
+            }
+            None => exec_err!("Invalid XML document: missing '{terminator}'"),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let trimmed = self.rest().trim_start();
+        self.pos = self.input.len() - trimmed.len();
+    }
+
+    fn current(&self) -> NodeId {
+        *self.open.last().unwrap_or(&XmlDocument::ROOT)
+    }
+
+    fn parse(&mut self) -> Result<()> {
+        while self.pos < self.input.len() {
+            let rest = self.rest();
+            if rest.starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if rest.starts_with("<![CDATA[") {
+                self.pos += "<![CDATA[".len();
+                let end = match self.rest().find("]]>") {
+                    Some(end) => end,
+                    None => return exec_err!("Invalid XML document: missing ']]>'"),
+                };
+                let text = self.rest()[..end].to_string();
+                self.pos += end + "]]>".len();
+                self.add_text(text)?;
+            } else if rest.starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if rest.starts_with("<!") {
+                // a document type declaration, possibly with an internal subset
+                match (rest.find('['), rest.find('>')) {
+                    (Some(open), Some(close)) if open < close => self.skip_past("]>")?,
+                    _ => self.skip_past(">")?,
+                }
+            } else if rest.starts_with("</") {
+                self.parse_end_tag()?;
+            } else if rest.starts_with('<') {
+                self.parse_start_tag()?;
+            } else {
+                let end = rest.find('<').unwrap_or(rest.len());
+                let text = unescape(&rest[..end])?;
+                self.pos += end;
+                self.add_text(text)?;
+            }
+        }
+
+        if self.open.len() > 1 {
+            return exec_err!(
+                "Invalid XML document: unclosed element <{}>",
+                self.document.name(self.current())
+            );
+        }
+        if self.document.document_element().is_none() {
+            return exec_err!("Invalid XML document: no root element");
+        }
+        Ok(())
+    }
+
+    fn add_text(&mut self, text: String) -> Result<()> {
+        let parent = self.current();
+        if parent == XmlDocument::ROOT {
+            if text.trim().is_empty() {
+                return Ok(());
+            }
+            return exec_err!("Invalid XML document: text outside of the root element");
+        }
+        let id = self.document.push(XmlNodeKind::Text(text), parent);
+        self.document.nodes[parent].children.push(id);
+        Ok(())
+    }
+
+    fn parse_name(&mut self) -> Result<String> {
+        let rest = self.rest();
+        let end = rest
+            .find(|c: char| c.is_whitespace() || matches!(c, '/' | '>' | '=' | '<'))
+            .unwrap_or(rest.len());
+        if end == 0 {
+            return exec_err!("Invalid XML document: expected a name");
+        }
+        let name = rest[..end].to_string();
+        self.pos += end;
+        Ok(name)
+    }
+
+    fn parse_start_tag(&mut self) -> Result<()> {
+        self.pos += 1;
+        let name = self.parse_name()?;
+        // the open elements start with the document node, which is not nested
+        if self.open.len() > MAX_NESTING_DEPTH {
+            return exec_err!(
+                "Invalid XML document: elements are nested more than {MAX_NESTING_DEPTH} levels deep"
+            );
+        }
+        let parent = self.current();
+        if parent == XmlDocument::ROOT && self.document.document_element().is_some() {
+            return exec_err!("Invalid XML document: multiple root elements");
+        }
+        let element = self.document.push(XmlNodeKind::Element(name), parent);
+        self.document.nodes[parent].children.push(element);
+
+        loop {
+            self.skip_whitespace();
+            let rest = self.rest();
+            if rest.starts_with("/>") {
+                self.pos += 2;
+                return Ok(());
+            } else if rest.starts_with('>') {
+                self.pos += 1;
+                self.open.push(element);
+                return Ok(());
+            } else if rest.is_empty() {
+                return exec_err!("Invalid XML document: unterminated start tag");
+            }
+
+            let attr_name = self.parse_name()?;
+            self.skip_whitespace();
+            if !self.rest().starts_with('=') {
+                return exec_err!(
+                    "Invalid XML document: attribute '{attr_name}' has no value"
+                );
+            }
+            self.pos += 1;
+            self.skip_whitespace();
+            let Some(quote) = self
+                .rest()
+                .chars()
+                .next()
+                .filter(|c| *c == '"' || *c == '\'')
+            else {
+                return exec_err!(
+                    "Invalid XML document: attribute '{attr_name}' value is not quoted"
+                );
+            };
+            self.pos += 1;
+            let Some(end) = self.rest().find(quote) else {
+                return exec_err!("Invalid XML document: unterminated attribute value");
+            };
+            let value = unescape(&self.rest()[..end])?;
+            self.pos += end + 1;
+
+            let attribute = self.document.push(
+                XmlNodeKind::Attribute {
+                    name: attr_name,
+                    value,
+                },
+                element,
+            );
+            self.document.nodes[element].attributes.push(attribute);
+        }
+    }
+
+    fn parse_end_tag(&mut self) -> Result<()> {
+        self.pos += 2;
+        let name = self.parse_name()?;
+        self.skip_whitespace();
+        if !self.rest().starts_with('>') {
+            return exec_err!("Invalid XML document: unterminated end tag </{name}");
+        }
+        self.pos += 1;
+
+        let current = self.current();
+        if current == XmlDocument::ROOT || self.document.name(current) != name {
+            return exec_err!("Invalid XML document: unexpected end tag </{name}>");
+        }
+        self.open.pop();
+        Ok(())
+    }
+}
+
+/// Replaces the predefined XML entities and character references
+fn unescape(text: &str) -> Result<String> {
+    if !text.contains('&') {
+        return Ok(text.to_string());
+    }
+
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        result.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find(';') else {
+            return exec_err!("Invalid XML document: unterminated entity reference");
+        };
+        let entity = &rest[start + 1..start + end];
+        let c = match entity {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(|dec| dec.parse::<u32>()))
+                .and_then(|code| code.ok())
+                .and_then(char::from_u32),
+        };
+        match c {
+            Some(c) => result.push(c),
+            None => return exec_err!("Invalid XML document: unknown entity &{entity};"),
+        }
+        rest = &rest[start + end + 1..];
+    }
+    result.push_str(rest);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_document() {
+        let doc = XmlDocument::parse(
+            r#"<?xml version="1.0"?><!-- c --><a x="1 &amp; 2"><b>t&lt;1</b><![CDATA[<c>]]><d/></a>"#,
+        )
+        .unwrap();
+        let a = doc.document_element().unwrap();
+        assert_eq!(doc.name(a), "a");
+        assert_eq!(doc.string_value(a), "t<1<c>");
+        assert_eq!(doc.string_value(doc.node(a).attributes[0]), "1 & 2");
+        assert_eq!(
+            doc.child_elements(a)
+                .map(|id| doc.name(id).to_string())
+                .collect::<Vec<_>>(),
+            vec!["b", "d"]
+        );
+    }
+
+    #[test]
+    fn test_parse_malformed() {
+        for xml in [
+            "",
+            "<a>",
+            "<a></b>",
+            "<a/><b/>",
+            "text",
+            "<a x=1/>",
+            "<a>&foo;</a>",
+        ] {
+            assert!(XmlDocument::parse(xml).is_err(), "{xml} should not parse");
+        }
+    }
+
+    #[test]
+    fn test_parse_deeply_nested() {
+        let nested =
+            |depth: usize| format!("{}x{}", "<a>".repeat(depth), "</a>".repeat(depth));
+
+        let doc = XmlDocument::parse(&nested(MAX_NESTING_DEPTH)).unwrap();
+        let a = doc.document_element().unwrap();
+        assert_eq!(doc.string_value(a), "x");
+        assert_eq!(doc.descendants(a).len(), MAX_NESTING_DEPTH);
+
+        let err = XmlDocument::parse(&nested(MAX_NESTING_DEPTH + 1)).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("nested more than 1000 levels deep")
+        );
+    }
+
+    #[test]
+    fn test_parse_prefixed_names() {
+        let doc = XmlDocument::parse(r#"<ns:a xmlns:ns="urn:x"><ns:b ns:c="1"/></ns:a>"#)
+            .unwrap();
+        let a = doc.document_element().unwrap();
+        assert_eq!(doc.name(a), "ns:a");
+        assert_eq!(doc.name(doc.node(a).attributes[0]), "xmlns:ns");
+        let b = doc.child_elements(a).next().unwrap();
+        assert_eq!(doc.name(b), "ns:b");
+        assert_eq!(doc.string_value(doc.node(b).attributes[0]), "1");
+    }
+}
@@ -0,0 +1,395 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, AsArray, StringArray, StructArray, new_empty_array};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Field, FieldRef, Fields};
+use datafusion_common::{Result, ScalarValue, exec_err, internal_err};
+use datafusion_expr::{
+    ColumnarValue, ReturnFieldArgs, ScalarFunctionArgs, ScalarUDFImpl, Signature,
+    TypeSignature, Volatility,
+};
+
+use super::document::{NodeId, XmlDocument, XmlNodeKind};
+use crate::function::error_utils::invalid_arg_count_exec_err;
+use crate::function::schema_utils::{as_nullable, parse_ddl_schema};
+use crate::function::utils::{ParseMode, options_from_map};
+
+/// Prefix Spark uses for struct fields read from attributes
+const ATTRIBUTE_PREFIX: &str = "_";
+/// Name of the struct field Spark uses for the text of an element that also
+/// has attributes or child elements
+const VALUE_TAG: &str = "_VALUE";
+
+/// Spark-compatible `from_xml` expression
+///
+/// <https://spark.apache.org/docs/latest/api/sql/index.html#from_xml>
+///
+/// Parses an XML string into a struct described by a DDL schema string, e.g.
+/// `from_xml('<p><a>1</a><b>0.8</b></p>', 'a INT, b DOUBLE')`.
+///
+/// `from_xml(xml_string, schema[, options]) -> Struct`
+///
+/// The root element is read as the row: struct fields are read from child
+/// elements with the same name, from attributes when the field name is
+/// prefixed with `_`, and `_VALUE` holds the text of the element itself.
+/// Array fields collect all repeated child elements.
+///
+/// The optional `options` map supports the `mode` option:
+///
+/// - `PERMISSIVE` (default): values that cannot be converted to the schema
+///   type are set to NULL; a malformed XML document produces a struct whose
+///   fields are all NULL
+/// - `FAILFAST`: malformed records raise an error
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct SparkFromXml {
+    signature: Signature,
+}
+
+impl Default for SparkFromXml {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SparkFromXml {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(
+                vec![TypeSignature::String(2), TypeSignature::Any(3)],
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for SparkFromXml {
+    fn name(&self) -> &str {
+        "from_xml"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        internal_err!("return_field_from_args should be used instead")
+    }
+
+    fn return_field_from_args(&self, args: ReturnFieldArgs) -> Result<FieldRef> {
+        if !(2..=3).contains(&args.arg_fields.len()) {
+            return Err(invalid_arg_count_exec_err(
+                self.name(),
+                (2, 3),
+                args.arg_fields.len(),
+            ));
+        }
+
+        let Some(schema) =
+            args.scalar_arguments[1].and_then(|sv| sv.try_as_str().flatten())
+        else {
+            return exec_err!(
+                "{} requires its second argument to be a constant schema string",
+                self.name()
+            );
+        };
+
+        let data_type = as_nullable(parse_ddl_schema(schema)?);
+        if !matches!(data_type, DataType::Struct(_)) {
+            return exec_err!(
+                "{} requires a struct schema, got {}",
+                self.name(),
+                data_type
+            );
+        }
+        Ok(Arc::new(Field::new(self.name(), data_type, true)))
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        let mode = match args.args.get(2) {
+            Some(options) => ParseMode::from_options(
+                self.name(),
+                &options_from_map(self.name(), options)?,
+            )?,
+            None => ParseMode::Permissive,
+        };
+
+        let xml_array = args.args[0].to_array(args.number_rows)?;
+        let xml_array = cast(&xml_array, &DataType::Utf8)?;
+        let result = from_xml_inner(
+            xml_array.as_string::<i32>(),
+            args.return_field.data_type(),
+            mode,
+        )?;
+
+        Ok(ColumnarValue::Array(result))
+    }
+}
+
+fn from_xml_inner(
+    xml_array: &StringArray,
+    data_type: &DataType,
+    mode: ParseMode,
+) -> Result<ArrayRef> {
+    let DataType::Struct(fields) = data_type else {
+        return internal_err!("from_xml expects a struct schema, got {data_type}");
+    };
+    if xml_array.is_empty() {
+        return Ok(new_empty_array(data_type));
+    }
+
+    let rows = xml_array
+        .iter()
+        .map(|xml| {
+            let Some(xml) = xml else {
+                return ScalarValue::try_from(data_type);
+            };
+
+            let mut malformed = false;
+            let value = match XmlDocument::parse(xml) {
+                Ok(doc) => match doc.document_element() {
+                    Some(root) => {
+                        element_to_scalar(&doc, root, data_type, &mut malformed)?
+                    }
+                    None => {
+                        malformed = true;
+                        null_fields_struct(fields)?
+                    }
+                },
+                Err(_) => {
+                    malformed = true;
+                    null_fields_struct(fields)?
+                }
+            };
+
+            match mode {
+                ParseMode::FailFast if malformed => exec_err!(
+                    "Malformed records are detected in record parsing: {xml}. Parse Mode: FAILFAST"
+                ),
+                _ => Ok(value),
+            }
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    ScalarValue::iter_to_array(rows)
+}
+
+/// A non-null struct whose fields are all NULL, which Spark returns for
+/// malformed records in PERMISSIVE mode
+fn null_fields_struct(fields: &Fields) -> Result<ScalarValue> {
+    let columns = fields
+        .iter()
+        .map(|field| ScalarValue::try_from(field.data_type())?.to_array())
+        .collect::<Result<Vec<_>>>()?;
+    Ok(ScalarValue::Struct(Arc::new(StructArray::try_new(
+        fields.clone(),
+        columns,
+        None,
+    )?)))
+}
+
+/// Converts an element into a [`ScalarValue`] of `data_type`.
+///
+/// Values that do not match the requested type are replaced by NULL and flag
+/// the record as `malformed`.
+fn element_to_scalar(
+    doc: &XmlDocument,
+    element: NodeId,
+    data_type: &DataType,
+    malformed: &mut bool,
+) -> Result<ScalarValue> {
+    let DataType::Struct(fields) = data_type else {
+        return text_to_scalar(&doc.string_value(element), data_type, malformed);
+    };
+
+    let columns = fields
+        .iter()
+        .map(|field| field_to_scalar(doc, element, field, malformed)?.to_array())
+        .collect::<Result<Vec<_>>>()?;
+    Ok(ScalarValue::Struct(Arc::new(StructArray::try_new(
+        fields.clone(),
+        columns,
+        None,
+    )?)))
+}
+
+fn field_to_scalar(
+    doc: &XmlDocument,
+    element: NodeId,
+    field: &Field,
+    malformed: &mut bool,
+) -> Result<ScalarValue> {
+    let name = field.name();
+    let data_type = field.data_type();
+
+    if name == VALUE_TAG {
+        return text_to_scalar(&direct_text(doc, element), data_type, malformed);
+    }
+    if let Some(attribute_name) = name.strip_prefix(ATTRIBUTE_PREFIX) {
+        let attribute = doc.node(element).attributes.iter().find_map(|&id| {
+            match &doc.node(id).kind {
+                XmlNodeKind::Attribute { name, value } if name == attribute_name => {
+                    Some(value)
+                }
+                _ => None,
+            }
+        });
+        if let Some(value) = attribute {
+            return text_to_scalar(value, data_type, malformed);
+        }
+    }
+
+    let children = doc
+        .child_elements(element)
+        .filter(|&child| doc.name(child) == name.as_str());
+    match data_type {
+        DataType::List(item) => {
+            let values = children
+                .map(|child| element_to_scalar(doc, child, item.data_type(), malformed))
+                .collect::<Result<Vec<_>>>()?;
+            if values.is_empty() {
+                return ScalarValue::try_from(data_type);
+            }
+            Ok(ScalarValue::List(ScalarValue::new_list_nullable(
+                &values,
+                item.data_type(),
+            )))
+        }
+        // like Spark, the last occurrence wins if a non-array field repeats
+        _ => match children.last() {
+            Some(child) => element_to_scalar(doc, child, data_type, malformed),
+            None => ScalarValue::try_from(data_type),
+        },
+    }
+}
+
+/// The text directly contained by an element, excluding its descendants
+fn direct_text(doc: &XmlDocument, element: NodeId) -> String {
+    doc.node(element)
+        .children
+        .iter()
+        .filter_map(|&child| match &doc.node(child).kind {
+            XmlNodeKind::Text(text) => Some(text.as_str()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Converts element or attribute text to a primitive value, ignoring
+/// surrounding whitespace. Empty text is read as NULL.
+fn text_to_scalar(
+    text: &str,
+    data_type: &DataType,
+    malformed: &mut bool,
+) -> Result<ScalarValue> {
+    let text = text.trim();
+    if text.is_empty() {
+        return ScalarValue::try_from(data_type);
+    }
+
+    let value = match data_type {
+        DataType::Utf8 => Some(ScalarValue::Utf8(Some(text.to_string()))),
+        DataType::Boolean
+        | DataType::Int8
+        | DataType::Int16
+        | DataType::Int32
+        | DataType::Int64
+        | DataType::Float32
+        | DataType::Float64
+        | DataType::Decimal128(_, _)
+        | DataType::Date32
+        | DataType::Timestamp(_, _) => ScalarValue::Utf8(Some(text.to_string()))
+            .cast_to(data_type)
+            .ok(),
+        _ => None,
+    };
+
+    match value {
+        Some(value) => Ok(value),
+        None => {
+            *malformed = true;
+            ScalarValue::try_from(data_type)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Array, Int32Array};
+    use arrow::datatypes::Int32Type;
+
+    fn run(schema: &str, xml: Vec<Option<&str>>, mode: ParseMode) -> Result<ArrayRef> {
+        let data_type = as_nullable(parse_ddl_schema(schema).unwrap());
+        from_xml_inner(&StringArray::from(xml), &data_type, mode)
+    }
+
+    #[test]
+    fn test_from_xml() {
+        let result = run(
+            "a INT, b DOUBLE, _id STRING, c ARRAY<INT>",
+            vec![
+                Some("<p id='x'><a>1</a><b> 0.8 </b><c>1</c><c>2</c></p>"),
+                Some("<p><a>not an int</a></p>"),
+                Some("<p><a>"),
+                None,
+            ],
+            ParseMode::Permissive,
+        )
+        .unwrap();
+        let result = result.as_struct();
+
+        assert_eq!(result.null_count(), 1);
+        assert_eq!(
+            result.column(0).as_primitive::<Int32Type>(),
+            &Int32Array::from(vec![Some(1), None, None, None])
+        );
+        assert_eq!(
+            result.column(2).as_string::<i32>(),
+            &StringArray::from(vec![Some("x"), None, None, None])
+        );
+        let c = result.column(3).as_list::<i32>();
+        assert_eq!(c.value_length(0), 2);
+        assert!(c.is_null(1));
+    }
+
+    #[test]
+    fn test_from_xml_value_tag() {
+        let result = run(
+            "b STRUCT<_VALUE: STRING, _n: INT>",
+            vec![Some("<p><b n='2'>text</b></p>")],
+            ParseMode::FailFast,
+        )
+        .unwrap();
+        let b = result.as_struct().column(0).as_struct();
+        assert_eq!(b.column(0).as_string::<i32>().value(0), "text");
+        assert_eq!(b.column(1).as_primitive::<Int32Type>().value(0), 2);
+    }
+
+    #[test]
+    fn test_from_xml_failfast() {
+        let result = run("a INT", vec![Some("<p><a>x</a></p>")], ParseMode::FailFast);
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("Parse Mode: FAILFAST")
+        );
+    }
+}
@@ -15,11 +15,97 @@
 // specific language governing permissions and limitations
 // under the License.
 
+mod document;
+pub mod from_xml;
+pub mod xpath;
+mod xpath_eval;
+
 use datafusion_expr::ScalarUDF;
+use datafusion_functions::make_udf_function;
 use std::sync::Arc;
 
-pub mod expr_fn {}
+use xpath::{SparkXPath, XPathKind};
+
+make_udf_function!(from_xml::SparkFromXml, from_xml);
+make_udf_function!(SparkXPath, xpath, || SparkXPath::new(XPathKind::Array));
+make_udf_function!(SparkXPath, xpath_boolean, || SparkXPath::new(
+    XPathKind::Boolean
+));
+make_udf_function!(SparkXPath, xpath_double, || SparkXPath::new(
+    XPathKind::Double
+));
+make_udf_function!(SparkXPath, xpath_float, || SparkXPath::new(
+    XPathKind::Float
+));
+make_udf_function!(SparkXPath, xpath_int, || SparkXPath::new(XPathKind::Int));
+make_udf_function!(SparkXPath, xpath_long, || SparkXPath::new(XPathKind::Long));
+make_udf_function!(SparkXPath, xpath_short, || SparkXPath::new(
+    XPathKind::Short
+));
+make_udf_function!(SparkXPath, xpath_string, || SparkXPath::new(
+    XPathKind::String
+));
+
+pub mod expr_fn {
+    use datafusion_functions::export_functions;
+
+    export_functions!((
+        from_xml,
+        "Parses an XML string into a struct described by a DDL schema string.",
+        args,
+    ));
+    export_functions!((
+        xpath,
+        "Returns the values of the nodes of the XML document that match the XPath expression.",
+        xml path
+    ));
+    export_functions!((
+        xpath_boolean,
+        "Returns true if the XPath expression evaluates to true, or a matching node is found.",
+        xml path
+    ));
+    export_functions!((
+        xpath_double,
+        "Returns the double value of the XPath expression, or NaN if it is not numeric.",
+        xml path
+    ));
+    export_functions!((
+        xpath_float,
+        "Returns the float value of the XPath expression, or NaN if it is not numeric.",
+        xml path
+    ));
+    export_functions!((
+        xpath_int,
+        "Returns the integer value of the XPath expression, or zero if it is not numeric.",
+        xml path
+    ));
+    export_functions!((
+        xpath_long,
+        "Returns the long value of the XPath expression, or zero if it is not numeric.",
+        xml path
+    ));
+    export_functions!((
+        xpath_short,
+        "Returns the short value of the XPath expression, or zero if it is not numeric.",
+        xml path
+    ));
+    export_functions!((
+        xpath_string,
+        "Returns the text contents of the first node that matches the XPath expression.",
+        xml path
+    ));
+}
 
 pub fn functions() -> Vec<Arc<ScalarUDF>> {
-    vec![]
+    vec![
+        from_xml(),
+        xpath(),
+        xpath_boolean(),
+        xpath_double(),
+        xpath_float(),
+        xpath_int(),
+        xpath_long(),
+        xpath_short(),
+        xpath_string(),
+    ]
 }
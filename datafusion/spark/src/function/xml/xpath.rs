@@ -0,0 +1,321 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::sync::Arc;
+
+use arrow::array::{
+    ArrayRef, AsArray, BooleanBuilder, Float32Builder, Float64Builder, Int16Builder,
+    Int32Builder, Int64Builder, ListBuilder, StringArray, StringBuilder,
+};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Field};
+use datafusion_common::{Result, exec_err};
+use datafusion_expr::{
+    ColumnarValue, ScalarFunctionArgs, ScalarUDFImpl, Signature, Volatility,
+};
+
+use super::document::{XmlDocument, XmlNodeKind};
+use super::xpath_eval::{XPath, XPathValue};
+
+/// The flavour of a Spark `xpath_*` function, which decides how the result
+/// of the XPath expression is converted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum XPathKind {
+    /// `xpath`: the values of all matching nodes as an array of strings
+    Array,
+    /// `xpath_string`
+    String,
+    /// `xpath_boolean`
+    Boolean,
+    /// `xpath_short`
+    Short,
+    /// `xpath_int`
+    Int,
+    /// `xpath_long`
+    Long,
+    /// `xpath_float`
+    Float,
+    /// `xpath_double`, also available as `xpath_number`
+    Double,
+}
+
+/// Spark-compatible `xpath`, `xpath_string`, `xpath_boolean`, `xpath_short`,
+/// `xpath_int`, `xpath_long`, `xpath_float` and `xpath_double` expressions
+///
+/// <https://spark.apache.org/docs/latest/api/sql/index.html#xpath>
+///
+/// `xpath_*(xml, path)` evaluates an XPath 1.0 expression against an XML
+/// document, with the document node as the context node.
+///
+/// - Returns NULL if either argument is NULL or the XML is empty
+/// - Malformed XML or an invalid path raise an error
+/// - `xpath` returns the value of each matching node; element nodes have no
+///   value and are returned as NULL, like in Spark
+/// - Numeric functions return 0 if nothing matches or the value is not
+///   numeric (except `xpath_float` and `xpath_double`, which return NaN)
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct SparkXPath {
+    signature: Signature,
+    kind: XPathKind,
+    aliases: Vec<String>,
+}
+
+impl SparkXPath {
+    pub fn new(kind: XPathKind) -> Self {
+        let aliases = match kind {
+            XPathKind::Double => vec![String::from("xpath_number")],
+            _ => vec![],
+        };
+        Self {
+            signature: Signature::string(2, Volatility::Immutable),
+            kind,
+            aliases,
+        }
+    }
+}
+
+impl ScalarUDFImpl for SparkXPath {
+    fn name(&self) -> &str {
+        match self.kind {
+            XPathKind::Array => "xpath",
+            XPathKind::String => "xpath_string",
+            XPathKind::Boolean => "xpath_boolean",
+            XPathKind::Short => "xpath_short",
+            XPathKind::Int => "xpath_int",
+            XPathKind::Long => "xpath_long",
+            XPathKind::Float => "xpath_float",
+            XPathKind::Double => "xpath_double",
+        }
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(match self.kind {
+            XPathKind::Array => DataType::new_list(DataType::Utf8, true),
+            XPathKind::String => DataType::Utf8,
+            XPathKind::Boolean => DataType::Boolean,
+            XPathKind::Short => DataType::Int16,
+            XPathKind::Int => DataType::Int32,
+            XPathKind::Long => DataType::Int64,
+            XPathKind::Float => DataType::Float32,
+            XPathKind::Double => DataType::Float64,
+        })
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        let xml = args.args[0].to_array(args.number_rows)?;
+        let xml = cast(&xml, &DataType::Utf8)?;
+        let paths = args.args[1].to_array(args.number_rows)?;
+        let paths = cast(&paths, &DataType::Utf8)?;
+
+        let result =
+            xpath_inner(self.kind, xml.as_string::<i32>(), paths.as_string::<i32>())?;
+        Ok(ColumnarValue::Array(result))
+    }
+}
+
+fn xpath_inner(
+    kind: XPathKind,
+    xml: &StringArray,
+    paths: &StringArray,
+) -> Result<ArrayRef> {
+    let len = xml.len();
+    let array: ArrayRef = match kind {
+        XPathKind::Array => {
+            let mut builder = ListBuilder::with_capacity(StringBuilder::new(), len)
+                .with_field(Arc::new(Field::new_list_field(DataType::Utf8, true)));
+            for_each_row(xml, paths, |row| {
+                let Some((doc, value)) = row else {
+                    builder.append_null();
+                    return Ok(());
+                };
+                let XPathValue::NodeSet(nodes) = value else {
+                    return exec_err!("xpath expression does not evaluate to a node set");
+                };
+                for node in nodes {
+                    match &doc.node(node).kind {
+                        XmlNodeKind::Attribute { value, .. } => {
+                            builder.values().append_value(value)
+                        }
+                        XmlNodeKind::Text(text) => builder.values().append_value(text),
+                        _ => builder.values().append_null(),
+                    }
+                }
+                builder.append(true);
+                Ok(())
+            })?;
+            Arc::new(builder.finish())
+        }
+        XPathKind::String => {
+            let mut builder = StringBuilder::with_capacity(len, 0);
+            for_each_row(xml, paths, |row| {
+                builder.append_option(row.map(|(doc, v)| v.to_xpath_string(doc)));
+                Ok(())
+            })?;
+            Arc::new(builder.finish())
+        }
+        XPathKind::Boolean => {
+            let mut builder = BooleanBuilder::with_capacity(len);
+            for_each_row(xml, paths, |row| {
+                builder.append_option(row.map(|(_, v)| v.to_boolean()));
+                Ok(())
+            })?;
+            Arc::new(builder.finish())
+        }
+        // `as` conversions saturate and map NaN to 0, like Java's
+        // `Double.intValue()` used by Spark
+        XPathKind::Short => {
+            let mut builder = Int16Builder::with_capacity(len);
+            for_each_row(xml, paths, |row| {
+                builder.append_option(row.map(|(doc, v)| v.to_number(doc) as i16));
+                Ok(())
+            })?;
+            Arc::new(builder.finish())
+        }
+        XPathKind::Int => {
+            let mut builder = Int32Builder::with_capacity(len);
+            for_each_row(xml, paths, |row| {
+                builder.append_option(row.map(|(doc, v)| v.to_number(doc) as i32));
+                Ok(())
+            })?;
+            Arc::new(builder.finish())
+        }
+        XPathKind::Long => {
+            let mut builder = Int64Builder::with_capacity(len);
+            for_each_row(xml, paths, |row| {
+                builder.append_option(row.map(|(doc, v)| v.to_number(doc) as i64));
+                Ok(())
+            })?;
+            Arc::new(builder.finish())
+        }
+        XPathKind::Float => {
+            let mut builder = Float32Builder::with_capacity(len);
+            for_each_row(xml, paths, |row| {
+                builder.append_option(row.map(|(doc, v)| v.to_number(doc) as f32));
+                Ok(())
+            })?;
+            Arc::new(builder.finish())
+        }
+        XPathKind::Double => {
+            let mut builder = Float64Builder::with_capacity(len);
+            for_each_row(xml, paths, |row| {
+                builder.append_option(row.map(|(doc, v)| v.to_number(doc)));
+                Ok(())
+            })?;
+            Arc::new(builder.finish())
+        }
+    };
+    Ok(array)
+}
+
+/// Evaluates the path of each row against its XML document, passing `None`
+/// to `f` for rows whose result is NULL
+fn for_each_row(
+    xml: &StringArray,
+    paths: &StringArray,
+    mut f: impl FnMut(Option<(&XmlDocument, XPathValue)>) -> Result<()>,
+) -> Result<()> {
+    // paths are almost always constant, so only recompile when they change
+    let mut compiled: Option<(&str, XPath)> = None;
+
+    for (xml, path) in xml.iter().zip(paths.iter()) {
+        let (Some(xml), Some(path)) = (xml, path) else {
+            f(None)?;
+            continue;
+        };
+        if xml.is_empty() {
+            f(None)?;
+            continue;
+        }
+
+        if compiled.as_ref().is_none_or(|(p, _)| *p != path) {
+            compiled = Some((path, XPath::parse(path)?));
+        }
+        let Some((_, xpath)) = &compiled else {
+            unreachable!("path was compiled above");
+        };
+
+        let doc = XmlDocument::parse(xml)?;
+        let value = xpath.evaluate(&doc)?;
+        f(Some((&doc, value)))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Array, Int32Array};
+    use arrow::datatypes::{Float64Type, Int32Type};
+
+    fn run(kind: XPathKind, xml: Vec<Option<&str>>, path: &str) -> ArrayRef {
+        let paths = StringArray::from(vec![path; xml.len()]);
+        xpath_inner(kind, &StringArray::from(xml), &paths).unwrap()
+    }
+
+    #[test]
+    fn test_xpath_array() {
+        let xml = "<a><b>b1</b><b>b2</b><c id='x'>c1</c></a>";
+        let result = run(XPathKind::Array, vec![Some(xml), None], "a/b/text()");
+        let result = result.as_list::<i32>();
+        assert_eq!(
+            result.value(0).as_string::<i32>(),
+            &StringArray::from(vec!["b1", "b2"])
+        );
+        assert!(result.is_null(1));
+
+        // element nodes have no value
+        let result = run(XPathKind::Array, vec![Some(xml)], "a/b");
+        assert_eq!(result.as_list::<i32>().value(0).null_count(), 2);
+
+        let result = run(XPathKind::Array, vec![Some(xml)], "a/c/@id");
+        assert_eq!(
+            result.as_list::<i32>().value(0).as_string::<i32>(),
+            &StringArray::from(vec!["x"])
+        );
+    }
+
+    #[test]
+    fn test_xpath_numbers() {
+        let xml = "<a><b>1</b><b>2</b><c>x</c></a>";
+        let result = run(XPathKind::Int, vec![Some(xml), Some(""), None], "sum(a/b)");
+        assert_eq!(
+            result.as_primitive::<Int32Type>(),
+            &Int32Array::from(vec![Some(3), None, None])
+        );
+
+        let result = run(XPathKind::Int, vec![Some(xml)], "a/c");
+        assert_eq!(result.as_primitive::<Int32Type>().value(0), 0);
+
+        let result = run(XPathKind::Double, vec![Some(xml)], "a/c");
+        assert!(result.as_primitive::<Float64Type>().value(0).is_nan());
+    }
+
+    #[test]
+    fn test_xpath_malformed() {
+        let paths = StringArray::from(vec!["a"]);
+        let xml = StringArray::from(vec!["<a><b></a>"]);
+        assert!(xpath_inner(XPathKind::String, &xml, &paths).is_err());
+    }
+}
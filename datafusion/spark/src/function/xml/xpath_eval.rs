@@ -0,0 +1,1248 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! An XPath 1.0 expression parser and evaluator over [`XmlDocument`]s, as
+//! used by the Spark `xpath_*` functions.
+//!
+//! The abbreviated and unabbreviated location path syntax, predicates,
+//! operators and the XPath 1.0 core function library are supported.
+//! Variables are not. As documents are parsed without namespace processing,
+//! prefixed names such as `ns:a` match the element or attribute with that
+//! exact name.
+//!
+//! Expressions can be nested at most [`MAX_EXPR_DEPTH`] levels deep, which
+//! bounds the recursion of parsing and evaluating them.
+
+use datafusion_common::{Result, exec_datafusion_err, exec_err};
+
+use super::document::{NodeId, XmlDocument, XmlNodeKind};
+
+/// Maximum depth of nested XPath expressions, counting parentheses,
+/// predicates, function arguments and operators
+pub const MAX_EXPR_DEPTH: usize = 100;
+
+/// The result of evaluating an XPath expression
+#[derive(Debug, Clone, PartialEq)]
+pub enum XPathValue {
+    /// Nodes in document order, without duplicates
+    NodeSet(Vec<NodeId>),
+    Boolean(bool),
+    Number(f64),
+    String(String),
+}
+
+impl XPathValue {
+    /// Converts the value following the XPath `string()` function
+    pub fn to_xpath_string(&self, doc: &XmlDocument) -> String {
+        match self {
+            XPathValue::NodeSet(nodes) => nodes
+                .first()
+                .map(|&node| doc.string_value(node))
+                .unwrap_or_default(),
+            XPathValue::Boolean(b) => b.to_string(),
+            XPathValue::Number(n) => format_number(*n),
+            XPathValue::String(s) => s.clone(),
+        }
+    }
+
+    /// Converts the value following the XPath `number()` function
+    pub fn to_number(&self, doc: &XmlDocument) -> f64 {
+        match self {
+            XPathValue::Boolean(b) => f64::from(u8::from(*b)),
+            XPathValue::Number(n) => *n,
+            XPathValue::String(s) => parse_number(s),
+            XPathValue::NodeSet(_) => parse_number(&self.to_xpath_string(doc)),
+        }
+    }
+
+    /// Converts the value following the XPath `boolean()` function
+    pub fn to_boolean(&self) -> bool {
+        match self {
+            XPathValue::NodeSet(nodes) => !nodes.is_empty(),
+            XPathValue::Boolean(b) => *b,
+            XPathValue::Number(n) => *n != 0.0 && !n.is_nan(),
+            XPathValue::String(s) => !s.is_empty(),
+        }
+    }
+}
+
+fn parse_number(s: &str) -> f64 {
+    let s = s.trim();
+    let digits = s.strip_prefix('-').unwrap_or(s);
+    let is_xpath_number = !digits.is_empty()
+        && digits != "."
+        && digits.chars().all(|c| c.is_ascii_digit() || c == '.')
+        && digits.matches('.').count() <= 1;
+    if is_xpath_number {
+        s.parse().unwrap_or(f64::NAN)
+    } else {
+        f64::NAN
+    }
+}
+
+fn format_number(n: f64) -> String {
+    if n.is_nan() {
+        "NaN".to_string()
+    } else if n.is_infinite() {
+        let infinity = if n > 0.0 { "Infinity" } else { "-Infinity" };
+        infinity.to_string()
+    } else if n == n.trunc() && n.abs() < 1e18 {
+        format!("{}", n as i64)
+    } else {
+        format!("{n}")
+    }
+}
+
+/// A compiled XPath expression
+#[derive(Debug, Clone, PartialEq)]
+pub struct XPath {
+    expr: Expr,
+}
+
+impl XPath {
+    pub fn parse(path: &str) -> Result<Self> {
+        let tokens = tokenize(path)?;
+        let mut parser = ExprParser {
+            tokens,
+            pos: 0,
+            depth: 0,
+        };
+        let expr = parser.parse_or()?;
+        if let Some(token) = parser.peek() {
+            return exec_err!("Invalid XPath '{path}': unexpected token {token:?}");
+        }
+        Ok(Self { expr })
+    }
+
+    /// Evaluates the expression with the document node as context
+    pub fn evaluate(&self, doc: &XmlDocument) -> Result<XPathValue> {
+        let context = Context {
+            node: XmlDocument::ROOT,
+            position: 1,
+            size: 1,
+        };
+        Evaluator { doc }.eval(&self.expr, &context)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Slash,
+    DoubleSlash,
+    LBracket,
+    RBracket,
+    LParen,
+    RParen,
+    At,
+    Dot,
+    DotDot,
+    Comma,
+    Pipe,
+    Star,
+    DoubleColon,
+    Eq,
+    NotEq,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Plus,
+    Minus,
+    Literal(String),
+    Number(f64),
+    Name(String),
+}
+
+fn tokenize(path: &str) -> Result<Vec<Token>> {
+    let chars = path.chars().collect::<Vec<_>>();
+    let mut tokens = vec![];
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        let (token, len) = match c {
+            c if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            '/' if next == Some('/') => (Token::DoubleSlash, 2),
+            '/' => (Token::Slash, 1),
+            '[' => (Token::LBracket, 1),
+            ']' => (Token::RBracket, 1),
+            '(' => (Token::LParen, 1),
+            ')' => (Token::RParen, 1),
+            '@' => (Token::At, 1),
+            ',' => (Token::Comma, 1),
+            '|' => (Token::Pipe, 1),
+            '*' => (Token::Star, 1),
+            ':' if next == Some(':') => (Token::DoubleColon, 2),
+            '=' => (Token::Eq, 1),
+            '!' if next == Some('=') => (Token::NotEq, 2),
+            '<' if next == Some('=') => (Token::Le, 2),
+            '<' => (Token::Lt, 1),
+            '>' if next == Some('=') => (Token::Ge, 2),
+            '>' => (Token::Gt, 1),
+            '+' => (Token::Plus, 1),
+            '-' => (Token::Minus, 1),
+            '.' if next == Some('.') => (Token::DotDot, 2),
+            '.' if !next.is_some_and(|n| n.is_ascii_digit()) => (Token::Dot, 1),
+            '"' | '\'' => {
+                let Some(len) = chars[i + 1..].iter().position(|&q| q == c) else {
+                    return exec_err!("Invalid XPath '{path}': unterminated literal");
+                };
+                let literal = chars[i + 1..i + 1 + len].iter().collect();
+                (Token::Literal(literal), len + 2)
+            }
+            c if c.is_ascii_digit() || c == '.' => {
+                let len = chars[i..]
+                    .iter()
+                    .position(|d| !(d.is_ascii_digit() || *d == '.'))
+                    .unwrap_or(chars.len() - i);
+                let number = chars[i..i + len].iter().collect::<String>();
+                let number = number.parse().map_err(|_| {
+                    exec_datafusion_err!("Invalid XPath '{path}': bad number {number}")
+                })?;
+                (Token::Number(number), len)
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut len = 0;
+                while let Some(&n) = chars.get(i + len) {
+                    let is_prefix_separator = n == ':'
+                        && chars.get(i + len + 1).is_some_and(|a| a.is_alphabetic());
+                    if n.is_alphanumeric()
+                        || matches!(n, '_' | '-' | '.')
+                        || is_prefix_separator
+                    {
+                        len += 1;
+                    } else {
+                        break;
+                    }
+                }
+                (Token::Name(chars[i..i + len].iter().collect()), len)
+            }
+            other => {
+                return exec_err!(
+                    "Invalid XPath '{path}': unexpected character '{other}'"
+                );
+            }
+        };
+        tokens.push(token);
+        i += len;
+    }
+
+    Ok(tokens)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CompareOp {
+    Eq,
+    NotEq,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ArithOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Axis {
+    Child,
+    Descendant,
+    DescendantOrSelf,
+    SelfAxis,
+    Parent,
+    Ancestor,
+    AncestorOrSelf,
+    FollowingSibling,
+    PrecedingSibling,
+    Attribute,
+}
+
+impl Axis {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "child" => Axis::Child,
+            "descendant" => Axis::Descendant,
+            "descendant-or-self" => Axis::DescendantOrSelf,
+            "self" => Axis::SelfAxis,
+            "parent" => Axis::Parent,
+            "ancestor" => Axis::Ancestor,
+            "ancestor-or-self" => Axis::AncestorOrSelf,
+            "following-sibling" => Axis::FollowingSibling,
+            "preceding-sibling" => Axis::PrecedingSibling,
+            "attribute" => Axis::Attribute,
+            _ => return None,
+        })
+    }
+
+    /// Whether proximity positions count backwards in document order
+    fn is_reverse(&self) -> bool {
+        matches!(
+            self,
+            Axis::Parent | Axis::Ancestor | Axis::AncestorOrSelf | Axis::PrecedingSibling
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum NodeTest {
+    Name(String),
+    AnyName,
+    Text,
+    Node,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Step {
+    axis: Axis,
+    test: NodeTest,
+    predicates: Vec<Expr>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Or(Box<Expr>, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Compare(CompareOp, Box<Expr>, Box<Expr>),
+    Arith(ArithOp, Box<Expr>, Box<Expr>),
+    Negate(Box<Expr>),
+    Union(Box<Expr>, Box<Expr>),
+    /// A location path starting at the document node (`absolute`) or the
+    /// context node, or at the node set produced by `start`
+    Path {
+        start: Option<Box<Expr>>,
+        absolute: bool,
+        steps: Vec<Step>,
+    },
+    Filter(Box<Expr>, Vec<Expr>),
+    Literal(String),
+    Number(f64),
+    Function(String, Vec<Expr>),
+}
+
+struct ExprParser {
+    tokens: Vec<Token>,
+    pos: usize,
+    /// Depth of the expression being parsed
+    depth: usize,
+}
+
+impl ExprParser {
+    /// Increments the depth of the expression being parsed, returning an
+    /// error if it exceeds [`MAX_EXPR_DEPTH`]
+    fn nest(&mut self) -> Result<()> {
+        self.depth += 1;
+        if self.depth > MAX_EXPR_DEPTH {
+            return exec_err!(
+                "Invalid XPath: expression is nested more than {MAX_EXPR_DEPTH} levels deep"
+            );
+        }
+        Ok(())
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<&Token> {
+        self.tokens.get(self.pos + offset)
+    }
+
+    fn consume(&mut self, token: &Token) -> bool {
+        if self.peek() == Some(token) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn consume_operator_name(&mut self, name: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Name(n)) if n == name) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: Token) -> Result<()> {
+        if self.consume(&token) {
+            Ok(())
+        } else {
+            exec_err!("Invalid XPath: expected {token:?}, got {:?}", self.peek())
+        }
+    }
+
+    /// Parses an expression, which may be nested in another one
+    fn parse_or(&mut self) -> Result<Expr> {
+        let depth = self.depth;
+        self.nest()?;
+        let mut expr = self.parse_and()?;
+        while self.consume_operator_name("or") {
+            // each operator adds a level to the left operand
+            self.nest()?;
+            expr = Expr::Or(Box::new(expr), Box::new(self.parse_and()?));
+        }
+        self.depth = depth;
+        Ok(expr)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr> {
+        let depth = self.depth;
+        let mut expr = self.parse_equality()?;
+        while self.consume_operator_name("and") {
+            self.nest()?;
+            expr = Expr::And(Box::new(expr), Box::new(self.parse_equality()?));
+        }
+        self.depth = depth;
+        Ok(expr)
+    }
+
+    fn parse_equality(&mut self) -> Result<Expr> {
+        let depth = self.depth;
+        let mut expr = self.parse_relational()?;
+        loop {
+            let op = match self.peek() {
+                Some(Token::Eq) => CompareOp::Eq,
+                Some(Token::NotEq) => CompareOp::NotEq,
+                _ => break,
+            };
+            self.pos += 1;
+            self.nest()?;
+            let right = self.parse_relational()?;
+            expr = Expr::Compare(op, Box::new(expr), Box::new(right));
+        }
+        self.depth = depth;
+        Ok(expr)
+    }
+
+    fn parse_relational(&mut self) -> Result<Expr> {
+        let depth = self.depth;
+        let mut expr = self.parse_additive()?;
+        loop {
+            let op = match self.peek() {
+                Some(Token::Lt) => CompareOp::Lt,
+                Some(Token::Le) => CompareOp::Le,
+                Some(Token::Gt) => CompareOp::Gt,
+                Some(Token::Ge) => CompareOp::Ge,
+                _ => break,
+            };
+            self.pos += 1;
+            self.nest()?;
+            let right = self.parse_additive()?;
+            expr = Expr::Compare(op, Box::new(expr), Box::new(right));
+        }
+        self.depth = depth;
+        Ok(expr)
+    }
+
+    fn parse_additive(&mut self) -> Result<Expr> {
+        let depth = self.depth;
+        let mut expr = self.parse_multiplicative()?;
+        loop {
+            let op = match self.peek() {
+                Some(Token::Plus) => ArithOp::Add,
+                Some(Token::Minus) => ArithOp::Sub,
+                _ => break,
+            };
+            self.pos += 1;
+            self.nest()?;
+            let right = self.parse_multiplicative()?;
+            expr = Expr::Arith(op, Box::new(expr), Box::new(right));
+        }
+        self.depth = depth;
+        Ok(expr)
+    }
+
+    fn parse_multiplicative(&mut self) -> Result<Expr> {
+        let depth = self.depth;
+        let mut expr = self.parse_unary()?;
+        loop {
+            // after an operand, `*`, `div` and `mod` are operators
+            let op = match self.peek() {
+                Some(Token::Star) => ArithOp::Mul,
+                Some(Token::Name(n)) if n == "div" => ArithOp::Div,
+                Some(Token::Name(n)) if n == "mod" => ArithOp::Mod,
+                _ => break,
+            };
+            self.pos += 1;
+            self.nest()?;
+            let right = self.parse_unary()?;
+            expr = Expr::Arith(op, Box::new(expr), Box::new(right));
+        }
+        self.depth = depth;
+        Ok(expr)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr> {
+        let depth = self.depth;
+        if self.consume(&Token::Minus) {
+            self.nest()?;
+            let expr = Expr::Negate(Box::new(self.parse_unary()?));
+            self.depth = depth;
+            return Ok(expr);
+        }
+        let mut expr = self.parse_path()?;
+        while self.consume(&Token::Pipe) {
+            self.nest()?;
+            expr = Expr::Union(Box::new(expr), Box::new(self.parse_path()?));
+        }
+        self.depth = depth;
+        Ok(expr)
+    }
+
+    /// Whether the next tokens start a filter expression rather than a
+    /// location path
+    fn at_filter_expr(&self) -> bool {
+        match self.peek() {
+            Some(Token::Literal(_) | Token::Number(_) | Token::LParen) => true,
+            Some(Token::Name(name)) => {
+                self.peek_at(1) == Some(&Token::LParen) && !is_node_type(name)
+            }
+            _ => false,
+        }
+    }
+
+    fn parse_path(&mut self) -> Result<Expr> {
+        if self.at_filter_expr() {
+            let primary = self.parse_primary()?;
+            let predicates = self.parse_predicates()?;
+            let filter = if predicates.is_empty() {
+                primary
+            } else {
+                Expr::Filter(Box::new(primary), predicates)
+            };
+
+            let mut steps = vec![];
+            if self.consume(&Token::Slash) {
+                self.parse_relative_path(&mut steps)?;
+            } else if self.consume(&Token::DoubleSlash) {
+                steps.push(descendant_or_self_step());
+                self.parse_relative_path(&mut steps)?;
+            } else {
+                return Ok(filter);
+            }
+            return Ok(Expr::Path {
+                start: Some(Box::new(filter)),
+                absolute: false,
+                steps,
+            });
+        }
+
+        let mut steps = vec![];
+        let absolute = if self.consume(&Token::Slash) {
+            // a lone `/` selects the document node
+            if self.at_step_start() {
+                self.parse_relative_path(&mut steps)?;
+            }
+            true
+        } else if self.consume(&Token::DoubleSlash) {
+            steps.push(descendant_or_self_step());
+            self.parse_relative_path(&mut steps)?;
+            true
+        } else {
+            self.parse_relative_path(&mut steps)?;
+            false
+        };
+        Ok(Expr::Path {
+            start: None,
+            absolute,
+            steps,
+        })
+    }
+
+    fn at_step_start(&self) -> bool {
+        matches!(
+            self.peek(),
+            Some(Token::Name(_) | Token::Star | Token::At | Token::Dot | Token::DotDot)
+        )
+    }
+
+    fn parse_relative_path(&mut self, steps: &mut Vec<Step>) -> Result<()> {
+        steps.push(self.parse_step()?);
+        loop {
+            if self.consume(&Token::Slash) {
+                steps.push(self.parse_step()?);
+            } else if self.consume(&Token::DoubleSlash) {
+                steps.push(descendant_or_self_step());
+                steps.push(self.parse_step()?);
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn parse_step(&mut self) -> Result<Step> {
+        if self.consume(&Token::Dot) {
+            return Ok(Step {
+                axis: Axis::SelfAxis,
+                test: NodeTest::Node,
+                predicates: vec![],
+            });
+        }
+        if self.consume(&Token::DotDot) {
+            return Ok(Step {
+                axis: Axis::Parent,
+                test: NodeTest::Node,
+                predicates: vec![],
+            });
+        }
+
+        let axis = if self.consume(&Token::At) {
+            Axis::Attribute
+        } else if let (Some(Token::Name(name)), Some(Token::DoubleColon)) =
+            (self.peek(), self.peek_at(1))
+        {
+            let Some(axis) = Axis::from_name(name) else {
+                return exec_err!("Invalid XPath: unsupported axis '{name}'");
+            };
+            self.pos += 2;
+            axis
+        } else {
+            Axis::Child
+        };
+
+        let test = match self.peek().cloned() {
+            Some(Token::Star) => {
+                self.pos += 1;
+                NodeTest::AnyName
+            }
+            Some(Token::Name(name)) if self.peek_at(1) == Some(&Token::LParen) => {
+                self.pos += 2;
+                self.expect(Token::RParen)?;
+                match name.as_str() {
+                    "text" => NodeTest::Text,
+                    "node" => NodeTest::Node,
+                    other => {
+                        return exec_err!(
+                            "Invalid XPath: unsupported node test {other}()"
+                        );
+                    }
+                }
+            }
+            Some(Token::Name(name)) => {
+                self.pos += 1;
+                NodeTest::Name(name)
+            }
+            other => {
+                return exec_err!("Invalid XPath: expected a node test, got {other:?}");
+            }
+        };
+
+        Ok(Step {
+            axis,
+            test,
+            predicates: self.parse_predicates()?,
+        })
+    }
+
+    fn parse_predicates(&mut self) -> Result<Vec<Expr>> {
+        let mut predicates = vec![];
+        while self.consume(&Token::LBracket) {
+            predicates.push(self.parse_or()?);
+            self.expect(Token::RBracket)?;
+        }
+        Ok(predicates)
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr> {
+        match self.peek().cloned() {
+            Some(Token::Literal(s)) => {
+                self.pos += 1;
+                Ok(Expr::Literal(s))
+            }
+            Some(Token::Number(n)) => {
+                self.pos += 1;
+                Ok(Expr::Number(n))
+            }
+            Some(Token::LParen) => {
+                self.pos += 1;
+                let expr = self.parse_or()?;
+                self.expect(Token::RParen)?;
+                Ok(expr)
+            }
+            Some(Token::Name(name)) => {
+                self.pos += 2;
+                let mut args = vec![];
+                if !self.consume(&Token::RParen) {
+                    loop {
+                        args.push(self.parse_or()?);
+                        if self.consume(&Token::RParen) {
+                            break;
+                        }
+                        self.expect(Token::Comma)?;
+                    }
+                }
+                Ok(Expr::Function(name, args))
+            }
+            other => exec_err!("Invalid XPath: unexpected token {other:?}"),
+        }
+    }
+}
+
+fn is_node_type(name: &str) -> bool {
+    matches!(name, "text" | "node" | "comment" | "processing-instruction")
+}
+
+fn descendant_or_self_step() -> Step {
+    Step {
+        axis: Axis::DescendantOrSelf,
+        test: NodeTest::Node,
+        predicates: vec![],
+    }
+}
+
+struct Context {
+    node: NodeId,
+    position: usize,
+    size: usize,
+}
+
+struct Evaluator<'a> {
+    doc: &'a XmlDocument,
+}
+
+impl Evaluator<'_> {
+    fn eval(&self, expr: &Expr, ctx: &Context) -> Result<XPathValue> {
+        let value = match expr {
+            Expr::Or(a, b) => XPathValue::Boolean(
+                self.eval(a, ctx)?.to_boolean() || self.eval(b, ctx)?.to_boolean(),
+            ),
+            Expr::And(a, b) => XPathValue::Boolean(
+                self.eval(a, ctx)?.to_boolean() && self.eval(b, ctx)?.to_boolean(),
+            ),
+            Expr::Compare(op, a, b) => {
+                let (a, b) = (self.eval(a, ctx)?, self.eval(b, ctx)?);
+                XPathValue::Boolean(self.compare(*op, &a, &b))
+            }
+            Expr::Arith(op, a, b) => {
+                let a = self.eval(a, ctx)?.to_number(self.doc);
+                let b = self.eval(b, ctx)?.to_number(self.doc);
+                XPathValue::Number(match op {
+                    ArithOp::Add => a + b,
+                    ArithOp::Sub => a - b,
+                    ArithOp::Mul => a * b,
+                    ArithOp::Div => a / b,
+                    ArithOp::Mod => a % b,
+                })
+            }
+            Expr::Negate(e) => {
+                XPathValue::Number(-self.eval(e, ctx)?.to_number(self.doc))
+            }
+            Expr::Union(a, b) => {
+                let mut nodes = self.eval_node_set(a, ctx)?;
+                nodes.extend(self.eval_node_set(b, ctx)?);
+                nodes.sort_unstable();
+                nodes.dedup();
+                XPathValue::NodeSet(nodes)
+            }
+            Expr::Path {
+                start,
+                absolute,
+                steps,
+            } => {
+                let mut nodes = match start {
+                    Some(start) => self.eval_node_set(start, ctx)?,
+                    None if *absolute => vec![XmlDocument::ROOT],
+                    None => vec![ctx.node],
+                };
+                for step in steps {
+                    nodes = self.eval_step(step, &nodes)?;
+                }
+                XPathValue::NodeSet(nodes)
+            }
+            Expr::Filter(primary, predicates) => {
+                let nodes = self.eval_node_set(primary, ctx)?;
+                XPathValue::NodeSet(self.filter(nodes, predicates)?)
+            }
+            Expr::Literal(s) => XPathValue::String(s.clone()),
+            Expr::Number(n) => XPathValue::Number(*n),
+            Expr::Function(name, args) => self.eval_function(name, args, ctx)?,
+        };
+        Ok(value)
+    }
+
+    fn eval_node_set(&self, expr: &Expr, ctx: &Context) -> Result<Vec<NodeId>> {
+        match self.eval(expr, ctx)? {
+            XPathValue::NodeSet(nodes) => Ok(nodes),
+            other => {
+                exec_err!("XPath expression does not evaluate to a node set: {other:?}")
+            }
+        }
+    }
+
+    fn eval_step(&self, step: &Step, input: &[NodeId]) -> Result<Vec<NodeId>> {
+        let mut result = vec![];
+        for &node in input {
+            let mut candidates = self
+                .axis_nodes(step.axis, node)
+                .into_iter()
+                .filter(|&n| self.matches_test(step.axis, &step.test, n))
+                .collect::<Vec<_>>();
+            // predicates see the nodes in proximity order
+            if step.axis.is_reverse() {
+                candidates.reverse();
+            }
+            result.extend(self.filter(candidates, &step.predicates)?);
+        }
+        result.sort_unstable();
+        result.dedup();
+        Ok(result)
+    }
+
+    fn filter(&self, mut nodes: Vec<NodeId>, predicates: &[Expr]) -> Result<Vec<NodeId>> {
+        for predicate in predicates {
+            let size = nodes.len();
+            let mut kept = vec![];
+            for (i, &node) in nodes.iter().enumerate() {
+                let ctx = Context {
+                    node,
+                    position: i + 1,
+                    size,
+                };
+                let keep = match self.eval(predicate, &ctx)? {
+                    XPathValue::Number(n) => n == (i + 1) as f64,
+                    other => other.to_boolean(),
+                };
+                if keep {
+                    kept.push(node);
+                }
+            }
+            nodes = kept;
+        }
+        Ok(nodes)
+    }
+
+    /// Nodes on `axis` from `node`, in document order
+    fn axis_nodes(&self, axis: Axis, node: NodeId) -> Vec<NodeId> {
+        let doc = self.doc;
+        let n = doc.node(node);
+        match axis {
+            Axis::Child => n.children.clone(),
+            Axis::Attribute => n.attributes.clone(),
+            Axis::SelfAxis => vec![node],
+            Axis::Parent => n.parent.into_iter().collect(),
+            Axis::Descendant => doc.descendants(node),
+            Axis::DescendantOrSelf => {
+                let mut nodes = vec![node];
+                nodes.extend(doc.descendants(node));
+                nodes
+            }
+            Axis::Ancestor | Axis::AncestorOrSelf => {
+                let mut nodes = vec![];
+                if axis == Axis::AncestorOrSelf {
+                    nodes.push(node);
+                }
+                let mut current = n.parent;
+                while let Some(parent) = current {
+                    nodes.push(parent);
+                    current = doc.node(parent).parent;
+                }
+                nodes.reverse();
+                nodes
+            }
+            Axis::FollowingSibling | Axis::PrecedingSibling => {
+                if matches!(n.kind, XmlNodeKind::Attribute { .. }) {
+                    return vec![];
+                }
+                let Some(parent) = n.parent else {
+                    return vec![];
+                };
+                let siblings = &doc.node(parent).children;
+                let index = siblings.iter().position(|&s| s == node).unwrap_or(0);
+                if axis == Axis::FollowingSibling {
+                    siblings[index + 1..].to_vec()
+                } else {
+                    siblings[..index].to_vec()
+                }
+            }
+        }
+    }
+
+    fn matches_test(&self, axis: Axis, test: &NodeTest, node: NodeId) -> bool {
+        let kind = &self.doc.node(node).kind;
+        match test {
+            NodeTest::Node => true,
+            NodeTest::Text => matches!(kind, XmlNodeKind::Text(_)),
+            // the principal node type of the attribute axis is attribute
+            NodeTest::AnyName if axis == Axis::Attribute => {
+                matches!(kind, XmlNodeKind::Attribute { .. })
+            }
+            NodeTest::AnyName => matches!(kind, XmlNodeKind::Element(_)),
+            NodeTest::Name(name) if axis == Axis::Attribute => {
+                matches!(kind, XmlNodeKind::Attribute { name: n, .. } if n == name)
+            }
+            NodeTest::Name(name) => matches!(kind, XmlNodeKind::Element(n) if n == name),
+        }
+    }
+
+    fn compare(&self, op: CompareOp, a: &XPathValue, b: &XPathValue) -> bool {
+        let doc = self.doc;
+        match (a, b) {
+            (XPathValue::NodeSet(x), XPathValue::NodeSet(y)) => x.iter().any(|&nx| {
+                let sx = XPathValue::String(doc.string_value(nx));
+                y.iter().any(|&ny| {
+                    compare_atomic(
+                        doc,
+                        op,
+                        &sx,
+                        &XPathValue::String(doc.string_value(ny)),
+                    )
+                })
+            }),
+            (XPathValue::NodeSet(x), XPathValue::Boolean(_)) => {
+                compare_atomic(doc, op, &XPathValue::Boolean(!x.is_empty()), b)
+            }
+            (XPathValue::Boolean(_), XPathValue::NodeSet(y)) => {
+                compare_atomic(doc, op, a, &XPathValue::Boolean(!y.is_empty()))
+            }
+            (XPathValue::NodeSet(x), other) => x.iter().any(|&n| {
+                compare_atomic(doc, op, &XPathValue::String(doc.string_value(n)), other)
+            }),
+            (other, XPathValue::NodeSet(y)) => y.iter().any(|&n| {
+                compare_atomic(doc, op, other, &XPathValue::String(doc.string_value(n)))
+            }),
+            _ => compare_atomic(doc, op, a, b),
+        }
+    }
+
+    fn eval_function(
+        &self,
+        name: &str,
+        args: &[Expr],
+        ctx: &Context,
+    ) -> Result<XPathValue> {
+        let doc = self.doc;
+        let values = args
+            .iter()
+            .map(|arg| self.eval(arg, ctx))
+            .collect::<Result<Vec<_>>>()?;
+        let arity = |min: usize, max: usize| {
+            if values.len() < min || values.len() > max {
+                exec_err!(
+                    "Invalid XPath: {name}() expects {min} to {max} arguments, got {}",
+                    values.len()
+                )
+            } else {
+                Ok(())
+            }
+        };
+        // string arguments default to the string-value of the context node
+        let string_arg = |i: usize| match values.get(i) {
+            Some(value) => value.to_xpath_string(doc),
+            None => doc.string_value(ctx.node),
+        };
+        let node_set_arg = |i: usize| match values.get(i) {
+            Some(XPathValue::NodeSet(nodes)) => Ok(nodes.clone()),
+            Some(other) => {
+                exec_err!("Invalid XPath: {name}() expects a node set, got {other:?}")
+            }
+            None => Ok(vec![ctx.node]),
+        };
+
+        let value = match name {
+            "last" => {
+                arity(0, 0)?;
+                XPathValue::Number(ctx.size as f64)
+            }
+            "position" => {
+                arity(0, 0)?;
+                XPathValue::Number(ctx.position as f64)
+            }
+            "count" => {
+                arity(1, 1)?;
+                XPathValue::Number(node_set_arg(0)?.len() as f64)
+            }
+            "name" | "local-name" => {
+                arity(0, 1)?;
+                let node_name = node_set_arg(0)?
+                    .first()
+                    .map(|&n| doc.name(n))
+                    .unwrap_or_default();
+                let node_name = match name {
+                    "local-name" => node_name.rsplit(':').next().unwrap_or(node_name),
+                    _ => node_name,
+                };
+                XPathValue::String(node_name.to_string())
+            }
+            "string" => {
+                arity(0, 1)?;
+                XPathValue::String(string_arg(0))
+            }
+            "concat" => {
+                if values.len() < 2 {
+                    return exec_err!(
+                        "Invalid XPath: concat() expects at least 2 arguments"
+                    );
+                }
+                XPathValue::String(
+                    values.iter().map(|v| v.to_xpath_string(doc)).collect(),
+                )
+            }
+            "starts-with" => {
+                arity(2, 2)?;
+                XPathValue::Boolean(string_arg(0).starts_with(&string_arg(1)))
+            }
+            "contains" => {
+                arity(2, 2)?;
+                XPathValue::Boolean(string_arg(0).contains(&string_arg(1)))
+            }
+            "substring-before" => {
+                arity(2, 2)?;
+                let (s, pattern) = (string_arg(0), string_arg(1));
+                XPathValue::String(
+                    s.find(&pattern)
+                        .map(|i| s[..i].to_string())
+                        .unwrap_or_default(),
+                )
+            }
+            "substring-after" => {
+                arity(2, 2)?;
+                let (s, pattern) = (string_arg(0), string_arg(1));
+                XPathValue::String(
+                    s.find(&pattern)
+                        .map(|i| s[i + pattern.len()..].to_string())
+                        .unwrap_or_default(),
+                )
+            }
+            "substring" => {
+                arity(2, 3)?;
+                let s = string_arg(0);
+                let start = values[1].to_number(doc).round();
+                let end = match values.get(2) {
+                    Some(len) => start + len.to_number(doc).round(),
+                    None => f64::INFINITY,
+                };
+                XPathValue::String(
+                    s.chars()
+                        .enumerate()
+                        .filter(|(i, _)| {
+                            let position = (*i + 1) as f64;
+                            position >= start && position < end
+                        })
+                        .map(|(_, c)| c)
+                        .collect(),
+                )
+            }
+            "string-length" => {
+                arity(0, 1)?;
+                XPathValue::Number(string_arg(0).chars().count() as f64)
+            }
+            "normalize-space" => {
+                arity(0, 1)?;
+                XPathValue::String(
+                    string_arg(0)
+                        .split_whitespace()
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                )
+            }
+            "translate" => {
+                arity(3, 3)?;
+                let from = string_arg(1).chars().collect::<Vec<_>>();
+                let to = string_arg(2).chars().collect::<Vec<_>>();
+                XPathValue::String(
+                    string_arg(0)
+                        .chars()
+                        .filter_map(|c| match from.iter().position(|&f| f == c) {
+                            Some(i) => to.get(i).copied(),
+                            None => Some(c),
+                        })
+                        .collect(),
+                )
+            }
+            "boolean" => {
+                arity(1, 1)?;
+                XPathValue::Boolean(values[0].to_boolean())
+            }
+            "not" => {
+                arity(1, 1)?;
+                XPathValue::Boolean(!values[0].to_boolean())
+            }
+            "true" | "false" => {
+                arity(0, 0)?;
+                XPathValue::Boolean(name == "true")
+            }
+            "number" => {
+                arity(0, 1)?;
+                XPathValue::Number(match values.first() {
+                    Some(value) => value.to_number(doc),
+                    None => parse_number(&doc.string_value(ctx.node)),
+                })
+            }
+            "sum" => {
+                arity(1, 1)?;
+                XPathValue::Number(
+                    node_set_arg(0)?
+                        .iter()
+                        .map(|&n| parse_number(&doc.string_value(n)))
+                        .sum(),
+                )
+            }
+            "floor" | "ceiling" | "round" => {
+                arity(1, 1)?;
+                let n = values[0].to_number(doc);
+                XPathValue::Number(match name {
+                    "floor" => n.floor(),
+                    "ceiling" => n.ceil(),
+                    // XPath rounds halves towards positive infinity
+                    _ => (n + 0.5).floor(),
+                })
+            }
+            other => return exec_err!("Invalid XPath: unknown function {other}()"),
+        };
+        Ok(value)
+    }
+}
+
+fn compare_atomic(
+    doc: &XmlDocument,
+    op: CompareOp,
+    a: &XPathValue,
+    b: &XPathValue,
+) -> bool {
+    let is_equality = matches!(op, CompareOp::Eq | CompareOp::NotEq);
+    if is_equality {
+        let equal = match (a, b) {
+            (XPathValue::Boolean(_), _) | (_, XPathValue::Boolean(_)) => {
+                a.to_boolean() == b.to_boolean()
+            }
+            (XPathValue::Number(_), _) | (_, XPathValue::Number(_)) => {
+                a.to_number(doc) == b.to_number(doc)
+            }
+            _ => a.to_xpath_string(doc) == b.to_xpath_string(doc),
+        };
+        return if op == CompareOp::Eq { equal } else { !equal };
+    }
+
+    let (a, b) = (a.to_number(doc), b.to_number(doc));
+    match op {
+        CompareOp::Lt => a < b,
+        CompareOp::Le => a <= b,
+        CompareOp::Gt => a > b,
+        _ => a >= b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::function::xml::document::MAX_NESTING_DEPTH;
+
+    fn eval(xml: &str, path: &str) -> (XmlDocument, XPathValue) {
+        let doc = XmlDocument::parse(xml).unwrap();
+        let value = XPath::parse(path).unwrap().evaluate(&doc).unwrap();
+        (doc, value)
+    }
+
+    fn eval_string(xml: &str, path: &str) -> String {
+        let (doc, value) = eval(xml, path);
+        value.to_xpath_string(&doc)
+    }
+
+    #[test]
+    fn test_location_paths() {
+        let xml = r#"<a><b id="1">b1</b><b id="2">b2</b><c><b>b3</b></c></a>"#;
+        assert_eq!(eval_string(xml, "a/b"), "b1");
+        assert_eq!(eval_string(xml, "/a/b[2]"), "b2");
+        assert_eq!(eval_string(xml, "a/b[last()]/@id"), "2");
+        assert_eq!(eval_string(xml, "a/b[@id='2']"), "b2");
+        assert_eq!(eval_string(xml, "//c/b/text()"), "b3");
+        assert_eq!(eval_string(xml, "count(//b)"), "3");
+        assert_eq!(eval_string(xml, "name(a/*[3])"), "c");
+        assert_eq!(eval_string(xml, "a/c/b/../../b[1]"), "b1");
+        assert_eq!(eval_string(xml, "a/b[2]/preceding-sibling::b"), "b1");
+        assert_eq!(eval_string(xml, "a/missing"), "");
+    }
+
+    #[test]
+    fn test_operators_and_functions() {
+        let xml = "<a><b>1</b><b>2</b><b>4</b><s> x  y </s></a>";
+        assert_eq!(eval_string(xml, "sum(a/b)"), "7");
+        assert_eq!(eval_string(xml, "sum(a/b) div 2"), "3.5");
+        assert_eq!(eval_string(xml, "a/b = 2"), "true");
+        assert_eq!(eval_string(xml, "a/b > 4"), "false");
+        assert_eq!(eval_string(xml, "-a/b[1] * 3 mod 2"), "-1");
+        assert_eq!(eval_string(xml, "normalize-space(a/s)"), "x y");
+        assert_eq!(eval_string(xml, "concat(a/b[1], '-', a/b[3])"), "1-4");
+        assert_eq!(eval_string(xml, "substring('12345', 2, 3)"), "234");
+        assert_eq!(eval_string(xml, "a/s | a/b[1]"), "1");
+        assert_eq!(eval_string(xml, "number(a/s)"), "NaN");
+    }
+
+    #[test]
+    fn test_invalid_paths() {
+        for path in ["a/", "a[1", "a/b/", "'abc", "a::b"] {
+            assert!(XPath::parse(path).is_err(), "{path} should not parse");
+        }
+
+        let doc = XmlDocument::parse("<a/>").unwrap();
+        let unknown = XPath::parse("unknown(a)").unwrap();
+        assert!(unknown.evaluate(&doc).is_err());
+    }
+
+    #[test]
+    fn test_deeply_nested() {
+        let depth = MAX_NESTING_DEPTH;
+        let xml = format!("{}x{}", "<a>".repeat(depth), "</a>".repeat(depth));
+        assert_eq!(eval_string(&xml, "count(//a)"), depth.to_string());
+        assert_eq!(
+            eval_string(&xml, "count(/a/descendant-or-self::a)"),
+            depth.to_string()
+        );
+        assert_eq!(eval_string(&xml, "//a[not(a)]"), "x");
+
+        // expressions may be nested up to the maximum depth
+        let nested =
+            |depth: usize| format!("{}1{}", "(".repeat(depth - 1), ")".repeat(depth - 1));
+        assert_eq!(eval_string(&xml, &nested(MAX_EXPR_DEPTH)), "1");
+        for path in [
+            nested(MAX_EXPR_DEPTH + 1),
+            format!("{}1", "-".repeat(MAX_EXPR_DEPTH)),
+            ["1"; MAX_EXPR_DEPTH + 1].join(" + "),
+            format!(
+                "a{}{}",
+                "[a".repeat(MAX_EXPR_DEPTH),
+                "]".repeat(MAX_EXPR_DEPTH)
+            ),
+        ] {
+            let err = XPath::parse(&path).unwrap_err();
+            assert!(
+                err.to_string().contains("nested more than 100 levels deep"),
+                "{path}: {err}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_prefixed_names() {
+        let xml = r#"<ns:a xmlns:ns="urn:x"><ns:b ns:c="1">b</ns:b></ns:a>"#;
+        assert_eq!(eval_string(xml, "ns:a/ns:b"), "b");
+        assert_eq!(eval_string(xml, "//ns:b/@ns:c"), "1");
+        assert_eq!(eval_string(xml, "name(/*)"), "ns:a");
+    }
+}
@@ -730,6 +730,9 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                     name.as_str(),
                     "percentile_cont"
                         | "quantile_cont"
+                        | "percentile_disc"
+                        | "quantile_disc"
+                        | "mode"
                         | "approx_percentile_cont"
                         | "approx_percentile_cont_with_weight"
                 );
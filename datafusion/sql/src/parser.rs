@@ -274,6 +274,56 @@ impl fmt::Display for ResetStatement {
     }
 }
 
+/// DataFusion extension for `ANALYZE TABLE`
+///
+/// Syntax:
+///
+/// ```sql
+/// ANALYZE TABLE <table_name> [COMPUTE STATISTICS [FOR COLUMNS <column_name>, ...]]
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnalyzeTableStatement {
+    /// Table name
+    pub table_name: ObjectName,
+    /// Columns to collect statistics for, or `None` for all columns
+    pub columns: Option<Vec<Ident>>,
+}
+
+impl fmt::Display for AnalyzeTableStatement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ANALYZE TABLE {} COMPUTE STATISTICS", self.table_name)?;
+        if let Some(columns) = &self.columns {
+            write!(f, " FOR COLUMNS ")?;
+            for (i, column) in columns.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{column}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// DataFusion extension for `REFRESH MATERIALIZED VIEW`
+///
+/// Syntax:
+///
+/// ```sql
+/// REFRESH MATERIALIZED VIEW <view_name>
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RefreshMaterializedViewStatement {
+    /// View name
+    pub view_name: ObjectName,
+}
+
+impl fmt::Display for RefreshMaterializedViewStatement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "REFRESH MATERIALIZED VIEW {}", self.view_name)
+    }
+}
+
 /// DataFusion SQL Statement.
 ///
 /// This can either be a [`Statement`] from [`sqlparser`] from a
@@ -293,6 +343,10 @@ pub enum Statement {
     Explain(ExplainStatement),
     /// Extension: `RESET`
     Reset(ResetStatement),
+    /// Extension: `ANALYZE TABLE`
+    AnalyzeTable(AnalyzeTableStatement),
+    /// Extension: `REFRESH MATERIALIZED VIEW`
+    RefreshMaterializedView(RefreshMaterializedViewStatement),
 }
 
 impl fmt::Display for Statement {
@@ -303,6 +357,8 @@ impl fmt::Display for Statement {
             Statement::CopyTo(stmt) => write!(f, "{stmt}"),
             Statement::Explain(stmt) => write!(f, "{stmt}"),
             Statement::Reset(stmt) => write!(f, "{stmt}"),
+            Statement::AnalyzeTable(stmt) => write!(f, "{stmt}"),
+            Statement::RefreshMaterializedView(stmt) => write!(f, "{stmt}"),
         }
     }
 }
@@ -570,6 +626,20 @@ impl<'a> DFParser<'a> {
                         self.parser.next_token(); // RESET
                         self.parse_reset()
                     }
+                    Keyword::ANALYZE => {
+                        if let Token::Word(w) = self.parser.peek_nth_token(1).token
+                            && w.keyword == Keyword::TABLE
+                        {
+                            self.parser.next_token(); // ANALYZE
+                            return self.parse_analyze_table();
+                        }
+                        // use sqlparser-rs parser
+                        self.parse_and_handle_statement()
+                    }
+                    Keyword::REFRESH => {
+                        self.parser.next_token(); // REFRESH
+                        self.parse_refresh_materialized_view()
+                    }
                     _ => {
                         // use sqlparser-rs parser
                         self.parse_and_handle_statement()
@@ -814,6 +884,44 @@ impl<'a> DFParser<'a> {
         Ok(Statement::Reset(ResetStatement::Variable(variable)))
     }
 
+    /// Parse a SQL `ANALYZE TABLE` statement
+    pub fn parse_analyze_table(&mut self) -> Result<Statement, DataFusionError> {
+        self.parser.expect_keyword(Keyword::TABLE)?;
+        let table_name = self.parser.parse_object_name(true)?;
+
+        let mut columns = None;
+        if self
+            .parser
+            .parse_keywords(&[Keyword::COMPUTE, Keyword::STATISTICS])
+            && self
+                .parser
+                .parse_keywords(&[Keyword::FOR, Keyword::COLUMNS])
+        {
+            columns = Some(
+                self.parser
+                    .parse_comma_separated(|parser| parser.parse_identifier())?,
+            );
+        }
+
+        Ok(Statement::AnalyzeTable(AnalyzeTableStatement {
+            table_name,
+            columns,
+        }))
+    }
+
+    /// Parse a SQL `REFRESH MATERIALIZED VIEW` statement
+    pub fn parse_refresh_materialized_view(
+        &mut self,
+    ) -> Result<Statement, DataFusionError> {
+        self.parser
+            .expect_keywords(&[Keyword::MATERIALIZED, Keyword::VIEW])?;
+        let view_name = self.parser.parse_object_name(true)?;
+
+        Ok(Statement::RefreshMaterializedView(
+            RefreshMaterializedViewStatement { view_name },
+        ))
+    }
+
     pub fn parse_explain_format(&mut self) -> Result<Option<String>, DataFusionError> {
         if !self.parser.parse_keyword(Keyword::FORMAT) {
             return Ok(None);
@@ -1842,6 +1950,44 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn analyze_table() -> Result<(), DataFusionError> {
+        let expected = Statement::AnalyzeTable(AnalyzeTableStatement {
+            table_name: ObjectName::from(vec![Ident::new("t")]),
+            columns: None,
+        });
+        expect_parse_ok("ANALYZE TABLE t", expected.clone())?;
+        assert_eq!(
+            verified_stmt("ANALYZE TABLE t COMPUTE STATISTICS"),
+            expected
+        );
+
+        let sql = "ANALYZE TABLE foo.t COMPUTE STATISTICS FOR COLUMNS a, b";
+        let expected = Statement::AnalyzeTable(AnalyzeTableStatement {
+            table_name: ObjectName::from(vec![Ident::new("foo"), Ident::new("t")]),
+            columns: Some(vec![Ident::new("a"), Ident::new("b")]),
+        });
+        assert_eq!(verified_stmt(sql), expected);
+
+        expect_parse_error(
+            "ANALYZE TABLE t COMPUTE STATISTICS FOR COLUMNS",
+            "Expected: identifier",
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn refresh_materialized_view() -> Result<(), DataFusionError> {
+        let expected =
+            Statement::RefreshMaterializedView(RefreshMaterializedViewStatement {
+                view_name: ObjectName::from(vec![Ident::new("foo"), Ident::new("v")]),
+            });
+        assert_eq!(verified_stmt("REFRESH MATERIALIZED VIEW foo.v"), expected);
+
+        expect_parse_error("REFRESH VIEW v", "Expected: MATERIALIZED");
+        Ok(())
+    }
+
     #[test]
     fn explain_copy_to_table_to_table() -> Result<(), DataFusionError> {
         let cases = vec![
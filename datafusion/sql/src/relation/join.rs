@@ -16,10 +16,14 @@
 // under the License.
 
 use crate::planner::{ContextProvider, PlannerContext, SqlToRel};
-use datafusion_common::{Column, Result, not_impl_err, plan_datafusion_err};
-use datafusion_expr::{JoinType, LogicalPlan, LogicalPlanBuilder};
+use datafusion_common::{Column, Result, not_impl_err, plan_datafusion_err, plan_err};
+use datafusion_expr::utils::{find_valid_equijoin_key_pair, split_conjunction_owned};
+use datafusion_expr::{
+    BinaryExpr, Expr, JoinType, LogicalPlan, LogicalPlanBuilder, Operator,
+};
 use sqlparser::ast::{
-    Join, JoinConstraint, JoinOperator, ObjectName, TableFactor, TableWithJoins,
+    Expr as SQLExpr, Join, JoinConstraint, JoinOperator, ObjectName, TableFactor,
+    TableWithJoins,
 };
 use std::collections::HashSet;
 
@@ -98,6 +102,16 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
             JoinOperator::CrossJoin(JoinConstraint::None) => {
                 self.parse_cross_join(left, right)
             }
+            JoinOperator::AsOf {
+                match_condition,
+                constraint,
+            } => self.parse_asof_join(
+                left,
+                right,
+                match_condition,
+                constraint,
+                planner_context,
+            ),
             other => not_impl_err!("Unsupported JOIN operator {other:?}"),
         }
     }
@@ -110,6 +124,74 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         LogicalPlanBuilder::from(left).cross_join(right)?.build()
     }
 
+    /// Plans `left ASOF JOIN right MATCH_CONDITION (..) [ON ..]` into a
+    /// [`JoinType::LeftAsOf`] join.
+    ///
+    /// The join filter is the match condition, a single comparison between
+    /// the two inputs, and the equality predicates of the `ON` clause are the
+    /// equijoin keys.
+    fn parse_asof_join(
+        &self,
+        left: LogicalPlan,
+        right: LogicalPlan,
+        match_condition: SQLExpr,
+        constraint: JoinConstraint,
+        planner_context: &mut PlannerContext,
+    ) -> Result<LogicalPlan> {
+        let join_schema = left.schema().join(right.schema())?;
+        let match_condition =
+            self.sql_to_expr(match_condition, &join_schema, planner_context)?;
+        match &match_condition {
+            Expr::BinaryExpr(BinaryExpr {
+                op: Operator::Lt | Operator::LtEq | Operator::Gt | Operator::GtEq,
+                ..
+            }) => {}
+            _ => {
+                return plan_err!(
+                    "ASOF JOIN MATCH_CONDITION must be a comparison using one of <, <=, >, >=, got {match_condition}"
+                );
+            }
+        }
+
+        let mut keys = (vec![], vec![]);
+        match constraint {
+            JoinConstraint::None => {}
+            JoinConstraint::On(sql_expr) => {
+                let on = self.sql_to_expr(sql_expr, &join_schema, planner_context)?;
+                for expr in split_conjunction_owned(on) {
+                    let Expr::BinaryExpr(BinaryExpr {
+                        left: left_key,
+                        op: Operator::Eq,
+                        right: right_key,
+                    }) = &expr
+                    else {
+                        return plan_err!(
+                            "ASOF JOIN ON clause only supports equality predicates, got {expr}"
+                        );
+                    };
+                    let Some((left_key, right_key)) = find_valid_equijoin_key_pair(
+                        left_key,
+                        right_key,
+                        left.schema(),
+                        right.schema(),
+                    )?
+                    else {
+                        return plan_err!(
+                            "ASOF JOIN ON clause must compare an expression of the left input with an expression of the right input, got {expr}"
+                        );
+                    };
+                    keys.0.push(left_key);
+                    keys.1.push(right_key);
+                }
+            }
+            other => return not_impl_err!("Unsupported ASOF JOIN constraint {other:?}"),
+        }
+
+        LogicalPlanBuilder::from(left)
+            .join_with_expr_keys(right, JoinType::LeftAsOf, keys, Some(match_condition))?
+            .build()
+    }
+
     fn parse_join(
         &self,
         left: LogicalPlan,
@@ -29,9 +29,13 @@ use datafusion_expr::planner::{
 };
 use datafusion_expr::{Expr, LogicalPlan, LogicalPlanBuilder, expr::Unnest};
 use datafusion_expr::{Subquery, SubqueryAlias};
-use sqlparser::ast::{FunctionArg, FunctionArgExpr, Spanned, TableFactor};
+use sqlparser::ast::{
+    FunctionArg, FunctionArgExpr, Spanned, TableFactor, TableSampleKind,
+};
 
 mod join;
+mod pivot;
+mod sample;
 
 struct SqlToRelRelationContext<'a, 'b, S: ContextProvider> {
     planner: &'a SqlToRel<'b, S>,
@@ -150,9 +154,13 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         let relation_span = relation.span();
         let (plan, alias) = match relation {
             TableFactor::Table {
-                name, alias, args, ..
+                name,
+                alias,
+                args,
+                sample,
+                ..
             } => {
-                if let Some(func_args) = args {
+                let (plan, alias) = if let Some(func_args) = args {
                     let tbl_func_name =
                         name.0.first().unwrap().as_ident().unwrap().to_string();
                     let args = func_args
@@ -210,6 +218,14 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                         }?,
                         alias,
                     )
+                };
+                // TABLESAMPLE is applied to the table before it is aliased
+                match sample {
+                    Some(
+                        TableSampleKind::BeforeTableAlias(sample)
+                        | TableSampleKind::AfterTableAlias(sample),
+                    ) => (self.sample_to_plan(plan, *sample)?, alias),
+                    None => (plan, alias),
                 }
             }
             TableFactor::Derived {
@@ -294,6 +310,44 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                         .build()?;
                 (plan, alias)
             }
+            TableFactor::Pivot {
+                table,
+                aggregate_functions,
+                value_column,
+                value_source,
+                default_on_null,
+                alias,
+            } => {
+                let input = self.create_relation(*table, planner_context)?;
+                let plan = self.pivot_to_plan(
+                    input,
+                    aggregate_functions,
+                    value_column,
+                    value_source,
+                    default_on_null,
+                    planner_context,
+                )?;
+                (plan, alias)
+            }
+            TableFactor::Unpivot {
+                table,
+                value,
+                name,
+                columns,
+                null_inclusion,
+                alias,
+            } => {
+                let input = self.create_relation(*table, planner_context)?;
+                let plan = self.unpivot_to_plan(
+                    input,
+                    value,
+                    name,
+                    columns,
+                    null_inclusion.as_ref(),
+                    planner_context,
+                )?;
+                (plan, alias)
+            }
             // @todo Support TableFactory::TableFunction?
             _ => {
                 return not_impl_err!(
@@ -0,0 +1,266 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Planning of the `PIVOT` and `UNPIVOT` table operators.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use crate::planner::{ContextProvider, PlannerContext, SqlToRel};
+
+use datafusion_common::{Column, DFSchema, Result, ScalarValue, not_impl_err, plan_err};
+use datafusion_expr::expr::Alias;
+use datafusion_expr::{Expr, LogicalPlan, LogicalPlanBuilder, Union, lit, when};
+use sqlparser::ast::{
+    Expr as SQLExpr, ExprWithAlias, Ident, NullInclusion, PivotValueSource,
+};
+
+impl<S: ContextProvider> SqlToRel<'_, S> {
+    /// Plans `input PIVOT (agg [AS alias], ... FOR col IN (value [AS alias], ...))`.
+    ///
+    /// The pivot is rewritten into a conditional aggregation that groups by
+    /// all input columns not referenced by the aggregates or the pivot column:
+    ///
+    /// ```text
+    /// Projection: region, Q1, Q2
+    ///   Aggregate: groupBy=[[region]], aggr=[[
+    ///     sum(amount) FILTER (WHERE quarter = 'Q1') AS Q1,
+    ///     sum(amount) FILTER (WHERE quarter = 'Q2') AS Q2]]
+    /// ```
+    ///
+    /// One output column is produced for every pivot value and aggregate,
+    /// named after the value, suffixed with `_<aggregate>` if there is more
+    /// than one aggregate or the aggregate is aliased.
+    pub(super) fn pivot_to_plan(
+        &self,
+        input: LogicalPlan,
+        aggregate_functions: Vec<ExprWithAlias>,
+        value_column: Vec<SQLExpr>,
+        value_source: PivotValueSource,
+        default_on_null: Option<SQLExpr>,
+        planner_context: &mut PlannerContext,
+    ) -> Result<LogicalPlan> {
+        let schema = Arc::clone(input.schema());
+
+        let [value_column] = <[SQLExpr; 1]>::try_from(value_column).or_else(|_| {
+            not_impl_err!("PIVOT on more than one column is not supported")
+        })?;
+        let pivot_expr = self.sql_to_expr(value_column, &schema, planner_context)?;
+
+        let PivotValueSource::List(values) = value_source else {
+            return not_impl_err!(
+                "PIVOT with ANY or a subquery as the value source is not supported"
+            );
+        };
+        let values = values
+            .into_iter()
+            .map(|value| {
+                let expr = self.sql_to_expr(value.expr, &schema, planner_context)?;
+                let name = match value.alias {
+                    Some(alias) => self.ident_normalizer.normalize(alias),
+                    None => pivot_value_name(&expr),
+                };
+                Ok((expr, name))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let multiple_aggregates = aggregate_functions.len() > 1;
+        let aggregates = aggregate_functions
+            .into_iter()
+            .map(|agg| {
+                let suffix = match agg.alias {
+                    Some(alias) => Some(self.ident_normalizer.normalize(alias)),
+                    None if multiple_aggregates => Some(agg.expr.to_string()),
+                    None => None,
+                };
+                // `count(*)` is planned as an aliased `count(1)`
+                let expr = match self.sql_to_expr(agg.expr, &schema, planner_context)? {
+                    Expr::Alias(Alias { expr, .. }) => *expr,
+                    expr => expr,
+                };
+                match expr {
+                    Expr::AggregateFunction(func) => Ok((func, suffix)),
+                    other => {
+                        plan_err!("PIVOT expects an aggregate function, got {other}")
+                    }
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // Every column that is neither pivoted nor aggregated is a grouping column
+        let mut referenced = pivot_expr
+            .column_refs()
+            .into_iter()
+            .cloned()
+            .collect::<HashSet<_>>();
+        for (func, _) in &aggregates {
+            let agg = Expr::AggregateFunction(func.clone());
+            referenced.extend(agg.column_refs().into_iter().cloned());
+        }
+        let group_expr = schema
+            .columns()
+            .into_iter()
+            .filter(|column| !referenced.contains(column))
+            .map(Expr::Column)
+            .collect::<Vec<_>>();
+
+        let mut aggr_expr = Vec::with_capacity(values.len() * aggregates.len());
+        for (value, value_name) in &values {
+            for (func, suffix) in &aggregates {
+                let mut func = func.clone();
+                let condition = pivot_expr.clone().eq(value.clone());
+                func.params.filter = Some(Box::new(match func.params.filter.take() {
+                    Some(filter) => condition.and(*filter),
+                    None => condition,
+                }));
+                let name = match suffix {
+                    Some(suffix) => format!("{value_name}_{suffix}"),
+                    None => value_name.clone(),
+                };
+                aggr_expr.push(Expr::AggregateFunction(func).alias(name));
+            }
+        }
+
+        let group_len = group_expr.len();
+        let plan = LogicalPlanBuilder::from(input)
+            .aggregate(group_expr, aggr_expr)?
+            .build()?;
+
+        // DEFAULT ON NULL replaces NULL results of the aggregates
+        let default_on_null = default_on_null
+            .map(|expr| self.sql_to_expr(expr, &DFSchema::empty(), planner_context))
+            .transpose()?;
+        let projection = plan
+            .schema()
+            .columns()
+            .into_iter()
+            .enumerate()
+            .map(|(i, column)| match &default_on_null {
+                Some(default_on_null) if i >= group_len => {
+                    let name = column.name.clone();
+                    let value = Expr::Column(column);
+                    when(value.clone().is_null(), default_on_null.clone())
+                        .otherwise(value)
+                        .map(|expr| expr.alias(name))
+                }
+                _ => Ok(Expr::Column(column)),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        LogicalPlanBuilder::from(plan).project(projection)?.build()
+    }
+
+    /// Plans `input UNPIVOT [INCLUDE | EXCLUDE NULLS] (value FOR name IN (col [AS label], ...))`.
+    ///
+    /// The unpivot is rewritten into a `UNION ALL` with one branch per
+    /// unpivoted column. Each branch keeps the remaining input columns and
+    /// adds the label of the column as `name` and its content as `value`.
+    /// Unless `INCLUDE NULLS` is specified, rows with a `NULL` value are
+    /// filtered out.
+    pub(super) fn unpivot_to_plan(
+        &self,
+        input: LogicalPlan,
+        value: SQLExpr,
+        name: Ident,
+        columns: Vec<ExprWithAlias>,
+        null_inclusion: Option<&NullInclusion>,
+        planner_context: &mut PlannerContext,
+    ) -> Result<LogicalPlan> {
+        let schema = Arc::clone(input.schema());
+
+        let SQLExpr::Identifier(value) = value else {
+            return not_impl_err!(
+                "UNPIVOT into more than one value column is not supported"
+            );
+        };
+        let value_name = self.ident_normalizer.normalize(value);
+        let name = self.ident_normalizer.normalize(name);
+
+        let columns = columns
+            .into_iter()
+            .map(|column| {
+                let Expr::Column(col) =
+                    self.sql_to_expr(column.expr, &schema, planner_context)?
+                else {
+                    return plan_err!("UNPIVOT expects a column of the input");
+                };
+                let label = match column.alias {
+                    Some(alias) => self.ident_normalizer.normalize(alias),
+                    None => col.name.clone(),
+                };
+                Ok((col, label))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if columns.is_empty() {
+            return plan_err!("UNPIVOT requires at least one column");
+        }
+
+        let unpivoted = columns.iter().map(|(col, _)| col).collect::<HashSet<_>>();
+        let kept = schema
+            .columns()
+            .into_iter()
+            .filter(|column| !unpivoted.contains(column))
+            .map(Expr::Column)
+            .collect::<Vec<_>>();
+
+        let input = Arc::new(input);
+        let mut branches = columns
+            .into_iter()
+            .map(|(col, label)| {
+                let projection = kept
+                    .iter()
+                    .cloned()
+                    .chain([
+                        lit(label).alias(&name),
+                        Expr::Column(col).alias(&value_name),
+                    ])
+                    .collect::<Vec<_>>();
+                Ok(Arc::new(
+                    LogicalPlanBuilder::new_from_arc(Arc::clone(&input))
+                        .project(projection)?
+                        .build()?,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let plan = if branches.len() == 1 {
+            Arc::unwrap_or_clone(branches.remove(0))
+        } else {
+            LogicalPlan::Union(Union::try_new_with_loose_types(branches)?)
+        };
+
+        match null_inclusion {
+            Some(NullInclusion::IncludeNulls) => Ok(plan),
+            Some(NullInclusion::ExcludeNulls) | None => LogicalPlanBuilder::from(plan)
+                .filter(Expr::Column(Column::new_unqualified(value_name)).is_not_null())?
+                .build(),
+        }
+    }
+}
+
+/// Returns the output column name of a pivot value without an alias.
+fn pivot_value_name(value: &Expr) -> String {
+    match value {
+        Expr::Literal(
+            ScalarValue::Utf8(Some(s))
+            | ScalarValue::Utf8View(Some(s))
+            | ScalarValue::LargeUtf8(Some(s)),
+            _,
+        ) => s.clone(),
+        Expr::Literal(scalar, _) => scalar.to_string(),
+        other => other.schema_name().to_string(),
+    }
+}
@@ -0,0 +1,98 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Planning of the `TABLESAMPLE` clause.
+
+use crate::planner::{ContextProvider, SqlToRel};
+
+use datafusion_common::{Result, SampleMethod, not_impl_err, plan_err};
+use datafusion_expr::{LogicalPlan, LogicalPlanBuilder};
+use sqlparser::ast::{
+    Expr as SQLExpr, TableSample, TableSampleMethod, TableSampleUnit, Value,
+};
+
+impl<S: ContextProvider> SqlToRel<'_, S> {
+    /// Plans `input TABLESAMPLE [BERNOULLI | SYSTEM] (percent [PERCENT]) [REPEATABLE (seed)]`.
+    ///
+    /// `BERNOULLI` (and `ROW`) samples individual rows, while `SYSTEM` (and
+    /// `BLOCK`) samples whole blocks of rows, such as Parquet row groups.
+    /// Without a method, rows are sampled.
+    pub(super) fn sample_to_plan(
+        &self,
+        input: LogicalPlan,
+        sample: TableSample,
+    ) -> Result<LogicalPlan> {
+        if sample.bucket.is_some() {
+            return not_impl_err!("TABLESAMPLE with BUCKET is not supported");
+        }
+        if sample.offset.is_some() {
+            return not_impl_err!("TABLESAMPLE with OFFSET is not supported");
+        }
+
+        let method = match sample.name {
+            Some(TableSampleMethod::Bernoulli | TableSampleMethod::Row) | None => {
+                SampleMethod::Bernoulli
+            }
+            Some(TableSampleMethod::System | TableSampleMethod::Block) => {
+                SampleMethod::System
+            }
+        };
+
+        let Some(quantity) = sample.quantity else {
+            return plan_err!("TABLESAMPLE requires a sample size");
+        };
+        if quantity.unit == Some(TableSampleUnit::Rows) {
+            return not_impl_err!("TABLESAMPLE with a number of ROWS is not supported");
+        }
+        let percent = match &quantity.value {
+            SQLExpr::Value(value) => match &value.value {
+                Value::Number(n, _) => n.parse::<f64>().ok(),
+                _ => None,
+            },
+            _ => None,
+        };
+        let Some(percent) = percent else {
+            return plan_err!(
+                "TABLESAMPLE expects a numeric percentage, got {}",
+                quantity.value
+            );
+        };
+        if !(0.0..=100.0).contains(&percent) {
+            return plan_err!(
+                "TABLESAMPLE percentage must be between 0 and 100, got {percent}"
+            );
+        }
+
+        let seed = sample
+            .seed
+            .map(|seed| match &seed.value {
+                Value::Number(n, _) => n.parse::<u64>().or_else(|_| {
+                    plan_err!("TABLESAMPLE seed must be a non-negative integer, got {n}")
+                }),
+                other => {
+                    plan_err!(
+                        "TABLESAMPLE seed must be a non-negative integer, got {other}"
+                    )
+                }
+            })
+            .transpose()?;
+
+        LogicalPlanBuilder::from(input)
+            .sample(method, percent / 100.0, seed)?
+            .build()
+    }
+}
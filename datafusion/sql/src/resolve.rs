@@ -186,6 +186,12 @@ fn visit_statement(statement: &DFStatement, visitor: &mut RelationVisitor) -> Re
             visit_statement(&explain.statement, visitor)?;
         }
         DFStatement::Reset(_) => {}
+        DFStatement::AnalyzeTable(analyze) => {
+            control_flow_to_result(visitor.insert_relation(&analyze.table_name))?;
+        }
+        DFStatement::RefreshMaterializedView(refresh) => {
+            control_flow_to_result(visitor.insert_relation(&refresh.view_name))?;
+        }
     }
     Ok(())
 }
@@ -21,8 +21,9 @@ use std::str::FromStr;
 use std::sync::Arc;
 
 use crate::parser::{
-    CopyToSource, CopyToStatement, CreateExternalTable, DFParser, ExplainStatement,
-    LexOrdering, ResetStatement, Statement as DFStatement,
+    AnalyzeTableStatement, CopyToSource, CopyToStatement, CreateExternalTable, DFParser,
+    ExplainStatement, LexOrdering, RefreshMaterializedViewStatement, ResetStatement,
+    Statement as DFStatement,
 };
 use crate::planner::{
     ContextProvider, PlannerContext, SqlToRel, object_name_to_qualifier,
@@ -38,26 +39,30 @@ use datafusion_common::{
     internal_err, not_impl_err, plan_datafusion_err, plan_err, schema_err,
     unqualified_field_not_found,
 };
-use datafusion_expr::dml::{CopyTo, InsertOp};
+use datafusion_expr::dml::{
+    CopyTo, InsertOp, MergeAction, MergeClause, MergeClauseKind, MergeInto,
+};
 use datafusion_expr::expr_rewriter::normalize_col_with_schemas_and_ambiguity_check;
 use datafusion_expr::logical_plan::DdlStatement;
 use datafusion_expr::logical_plan::builder::project;
 use datafusion_expr::utils::expr_to_columns;
 use datafusion_expr::{
-    Analyze, CreateCatalog, CreateCatalogSchema,
+    Analyze, AnalyzeTable, CreateCatalog, CreateCatalogSchema,
     CreateExternalTable as PlanCreateExternalTable, CreateFunction, CreateFunctionBody,
     CreateIndex as PlanCreateIndex, CreateMemoryTable, CreateView, Deallocate,
     DescribeTable, DmlStatement, DropCatalogSchema, DropFunction, DropTable, DropView,
     EmptyRelation, Execute, Explain, ExplainFormat, Expr, ExprSchemable, Filter,
     LogicalPlan, LogicalPlanBuilder, OperateFunctionArg, PlanType, Prepare,
-    ResetVariable, SetVariable, SortExpr, Statement as PlanStatement, ToStringifiedPlan,
-    TransactionAccessMode, TransactionConclusion, TransactionEnd,
-    TransactionIsolationLevel, TransactionStart, Volatility, WriteOp, cast, col,
+    RefreshMaterializedView, ResetVariable, SetVariable, SortExpr,
+    Statement as PlanStatement, TableSource, ToStringifiedPlan, TransactionAccessMode,
+    TransactionConclusion, TransactionEnd, TransactionIsolationLevel, TransactionStart,
+    Volatility, WriteOp, cast, col,
 };
 use sqlparser::ast::{
     self, BeginTransactionKind, CheckConstraint, ForeignKeyConstraint, IndexColumn,
-    IndexType, NullsDistinctOption, OrderByExpr, OrderByOptions, PrimaryKeyConstraint,
-    Set, ShowStatementIn, ShowStatementOptions, SqliteOnConflict, TableObject,
+    IndexType, Merge, MergeInsertExpr, MergeInsertKind, MergeUpdateExpr,
+    NullsDistinctOption, OrderByExpr, OrderByOptions, PrimaryKeyConstraint, Set,
+    ShowStatementIn, ShowStatementOptions, SqliteOnConflict, TableObject,
     UniqueConstraint, Update, UpdateTableFromKind, ValueWithSpan,
 };
 use sqlparser::ast::{
@@ -234,6 +239,10 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                 statement,
             }) => self.explain_to_plan(verbose, analyze, format, *statement),
             DFStatement::Reset(statement) => self.reset_statement_to_plan(statement),
+            DFStatement::AnalyzeTable(statement) => self.analyze_table_to_plan(statement),
+            DFStatement::RefreshMaterializedView(statement) => {
+                self.refresh_materialized_view_to_plan(statement)
+            }
         }
     }
 
@@ -605,9 +614,6 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                 secure,
                 name_before_not_exists,
             }) => {
-                if materialized {
-                    return not_impl_err!("Materialized views not supported")?;
-                }
                 if !cluster_by.is_empty() {
                     return not_impl_err!("Cluster by not supported")?;
                 }
@@ -651,6 +657,7 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                     query,
                     or_replace,
                     temporary,
+                    materialized,
                     ..
                 }) = stmt
                 else {
@@ -679,6 +686,7 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                     or_replace,
                     definition: Some(sql),
                     temporary,
+                    materialized,
                 })))
             }
             Statement::ShowCreate { obj_type, obj_name } => match obj_type {
@@ -738,7 +746,8 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                             schema: DFSchemaRef::new(DFSchema::empty()),
                         })))
                     }
-                    ObjectType::View => {
+                    // Materialized views are dropped like any other view
+                    ObjectType::View | ObjectType::MaterializedView => {
                         Ok(LogicalPlan::Ddl(DdlStatement::DropView(DropView {
                             name,
                             if_exists,
@@ -1109,6 +1118,25 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                 self.update_to_plan(table, &assignments, update_from, selection)
             }
 
+            Statement::Merge(Merge {
+                table,
+                source,
+                on,
+                clauses,
+                output,
+                optimizer_hint,
+                merge_token: _,
+                into: _,
+            }) => {
+                if output.is_some() {
+                    plan_err!("MERGE with an OUTPUT or RETURNING clause not supported")?;
+                }
+                if optimizer_hint.is_some() {
+                    plan_err!("Optimizer hints not supported")?;
+                }
+                self.merge_to_plan(table, source, *on, clauses)
+            }
+
             Statement::Delete(Delete {
                 tables,
                 using,
@@ -1259,9 +1287,11 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                 language,
                 ..
             }) => {
-                let return_type = match return_type {
-                    Some(t) => Some(self.convert_data_type_to_field(&t)?),
-                    None => None,
+                let (return_type, returns_table) = match return_type {
+                    // The columns of a table function are defined by its body
+                    Some(ast::DataType::Table(_)) => (None, true),
+                    Some(t) => (Some(self.convert_data_type_to_field(&t)?), false),
+                    None => (None, false),
                 };
                 let mut planner_context = PlannerContext::new();
                 let empty_schema = &DFSchema::empty();
@@ -1393,6 +1423,7 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                     temporary,
                     name,
                     return_type: return_type.map(|f| f.data_type().clone()),
+                    returns_table,
                     args,
                     params,
                     schema: DFSchemaRef::new(DFSchema::empty()),
@@ -2106,6 +2137,56 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         }
     }
 
+    fn analyze_table_to_plan(
+        &self,
+        statement: AnalyzeTableStatement,
+    ) -> Result<LogicalPlan> {
+        // Do a table lookup to verify the table and the columns exist
+        let table_ref = self.object_name_to_table_reference(statement.table_name)?;
+        let table_source = self.context_provider.get_table_source(table_ref.clone())?;
+        let table_schema = table_source.schema();
+
+        let columns = statement
+            .columns
+            .map(|columns| {
+                columns
+                    .into_iter()
+                    .map(|ident| {
+                        let column = self.ident_normalizer.normalize(ident);
+                        if table_schema.column_with_name(&column).is_none() {
+                            return plan_err!(
+                                "Column {column} not found in table {table_ref}"
+                            );
+                        }
+                        Ok(column)
+                    })
+                    .collect::<Result<Vec<_>>>()
+            })
+            .transpose()?;
+
+        Ok(LogicalPlan::Ddl(DdlStatement::AnalyzeTable(AnalyzeTable {
+            name: table_ref,
+            columns,
+            schema: DFSchemaRef::new(DFSchema::empty()),
+        })))
+    }
+
+    fn refresh_materialized_view_to_plan(
+        &self,
+        statement: RefreshMaterializedViewStatement,
+    ) -> Result<LogicalPlan> {
+        // Do a table lookup to verify the view exists
+        let table_ref = self.object_name_to_table_reference(statement.view_name)?;
+        self.context_provider.get_table_source(table_ref.clone())?;
+
+        Ok(LogicalPlan::Ddl(DdlStatement::RefreshMaterializedView(
+            RefreshMaterializedView {
+                name: table_ref,
+                schema: DFSchemaRef::new(DFSchema::empty()),
+            },
+        )))
+    }
+
     fn delete_to_plan(
         &self,
         table_name: &ObjectName,
@@ -2273,6 +2354,259 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         Ok(plan)
     }
 
+    fn merge_to_plan(
+        &self,
+        table: TableFactor,
+        source: TableFactor,
+        on: SQLExpr,
+        clauses: Vec<ast::MergeClause>,
+    ) -> Result<LogicalPlan> {
+        let (table_name, table_alias) = match table {
+            TableFactor::Table { name, alias, .. } => (name, alias),
+            _ => plan_err!("Cannot merge into non-table relation!")?,
+        };
+
+        // Do a table lookup to verify the table exists
+        let table_name = self.object_name_to_table_reference(table_name)?;
+        let table_source = self.context_provider.get_table_source(table_name.clone())?;
+        let target_alias = match table_alias {
+            Some(alias) if !alias.columns.is_empty() => {
+                plan_err!("Column aliases for the MERGE target table are not supported")?
+            }
+            Some(alias) => {
+                TableReference::bare(self.ident_normalizer.normalize(alias.name))
+            }
+            None => table_name.clone(),
+        };
+
+        let mut planner_context = PlannerContext::new();
+        let source = self.plan_from_tables(
+            vec![TableWithJoins {
+                relation: source,
+                joins: vec![],
+            }],
+            &mut planner_context,
+        )?;
+        // The target table columns followed by the source columns, see `MergeInto::schema`
+        let schema = DFSchema::try_from_qualified_schema(
+            target_alias.clone(),
+            &table_source.schema(),
+        )?
+        .join(source.schema())?;
+
+        let on = self.merge_expr_to_plan(on, &schema, &mut planner_context)?;
+        let clauses = clauses
+            .into_iter()
+            .map(|clause| {
+                self.merge_clause_to_plan(
+                    clause,
+                    &target_alias,
+                    &table_source,
+                    &schema,
+                    &mut planner_context,
+                )
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let plan = LogicalPlan::Dml(DmlStatement::new(
+            table_name,
+            table_source,
+            WriteOp::Merge(Box::new(MergeInto {
+                target_alias,
+                on,
+                clauses,
+            })),
+            Arc::new(source),
+        ));
+        Ok(plan)
+    }
+
+    fn merge_clause_to_plan(
+        &self,
+        clause: ast::MergeClause,
+        target_alias: &TableReference,
+        table_source: &Arc<dyn TableSource>,
+        schema: &DFSchema,
+        planner_context: &mut PlannerContext,
+    ) -> Result<MergeClause> {
+        let kind = match clause.clause_kind {
+            ast::MergeClauseKind::Matched => MergeClauseKind::Matched,
+            ast::MergeClauseKind::NotMatched
+            | ast::MergeClauseKind::NotMatchedByTarget => {
+                MergeClauseKind::NotMatchedByTarget
+            }
+            ast::MergeClauseKind::NotMatchedBySource => {
+                MergeClauseKind::NotMatchedBySource
+            }
+        };
+        let predicate = clause
+            .predicate
+            .map(|predicate| self.merge_expr_to_plan(predicate, schema, planner_context))
+            .transpose()?;
+        let table_schema = table_source.schema();
+
+        let action = match clause.action {
+            ast::MergeAction::Insert(_)
+                if kind != MergeClauseKind::NotMatchedByTarget =>
+            {
+                plan_err!("MERGE INSERT is only allowed in WHEN NOT MATCHED clauses")?
+            }
+            ast::MergeAction::Update(_) | ast::MergeAction::Delete { .. }
+                if kind == MergeClauseKind::NotMatchedByTarget =>
+            {
+                plan_err!(
+                    "MERGE UPDATE and DELETE are not allowed in WHEN NOT MATCHED clauses"
+                )?
+            }
+            ast::MergeAction::Update(MergeUpdateExpr {
+                assignments,
+                update_predicate,
+                delete_predicate,
+                update_token: _,
+            }) => {
+                if update_predicate.is_some() || delete_predicate.is_some() {
+                    not_impl_err!("MERGE UPDATE with a WHERE clause not supported")?;
+                }
+                let assignments = assignments
+                    .into_iter()
+                    .map(|assign| {
+                        let cols = match &assign.target {
+                            AssignmentTarget::ColumnName(cols) => cols,
+                            _ => plan_err!("Tuples are not supported")?,
+                        };
+                        let col_name: &Ident = cols
+                            .0
+                            .iter()
+                            .last()
+                            .ok_or_else(|| plan_datafusion_err!("Empty column id"))?
+                            .as_ident()
+                            .unwrap();
+                        let col_name = self.ident_normalizer.normalize(col_name.clone());
+                        // Validate that the assignment target column exists
+                        let field = table_schema.field_with_name(&col_name).map_err(|_| {
+                            plan_datafusion_err!(
+                                "Column '{col_name}' does not exist in the MERGE target table"
+                            )
+                        })?;
+                        let value =
+                            self.merge_expr_to_plan(assign.value, schema, planner_context)?;
+                        // Cast to target column type, if necessary
+                        Ok((col_name, value.cast_to(field.data_type(), schema)?))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                MergeAction::Update(assignments)
+            }
+            ast::MergeAction::Delete { .. } => MergeAction::Delete,
+            ast::MergeAction::Insert(MergeInsertExpr {
+                columns,
+                kind,
+                insert_predicate,
+                insert_token: _,
+                kind_token: _,
+            }) => {
+                if insert_predicate.is_some() {
+                    not_impl_err!("MERGE INSERT with a WHERE clause not supported")?;
+                }
+                let mut rows = match kind {
+                    MergeInsertKind::Values(values) => values.rows,
+                    MergeInsertKind::Row => {
+                        not_impl_err!("MERGE INSERT ROW not supported")?
+                    }
+                };
+                if rows.len() != 1 {
+                    plan_err!("MERGE INSERT must specify exactly one row of values")?;
+                }
+                let row = rows.remove(0);
+
+                // The index of the target table column each value is inserted into
+                let column_indices = if columns.is_empty() {
+                    (0..table_schema.fields().len()).collect::<Vec<_>>()
+                } else {
+                    let mut column_indices = Vec::with_capacity(columns.len());
+                    for column in columns {
+                        let ident = column
+                            .0
+                            .last()
+                            .and_then(|part| part.as_ident())
+                            .ok_or_else(|| plan_datafusion_err!("Empty column id"))?;
+                        let c = self.ident_normalizer.normalize(ident.clone());
+                        let index = table_schema.index_of(&c).map_err(|_| {
+                            plan_datafusion_err!(
+                                "Column '{c}' does not exist in the MERGE target table"
+                            )
+                        })?;
+                        if column_indices.contains(&index) {
+                            return schema_err!(SchemaError::DuplicateUnqualifiedField {
+                                name: c,
+                            });
+                        }
+                        column_indices.push(index);
+                    }
+                    column_indices
+                };
+                if row.len() != column_indices.len() {
+                    plan_err!("Column count doesn't match insert query!")?;
+                }
+
+                let mut values = vec![None; table_schema.fields().len()];
+                for (index, value) in column_indices.into_iter().zip(row) {
+                    let value =
+                        self.merge_expr_to_plan(value, schema, planner_context)?;
+                    if value
+                        .column_refs()
+                        .iter()
+                        .any(|column| column.relation.as_ref() == Some(target_alias))
+                    {
+                        plan_err!(
+                            "MERGE INSERT values cannot reference the target table, got {value}"
+                        )?;
+                    }
+                    values[index] = Some(value);
+                }
+                let values = table_schema
+                    .fields()
+                    .iter()
+                    .zip(values)
+                    .map(|(field, value)| {
+                        // Fill in the default value for the columns that are not specified
+                        let value = value.unwrap_or_else(|| {
+                            table_source
+                                .get_column_default(field.name())
+                                .cloned()
+                                .unwrap_or(Expr::Literal(ScalarValue::Null, None))
+                        });
+                        value.cast_to(field.data_type(), schema)
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                MergeAction::Insert(values)
+            }
+        };
+
+        Ok(MergeClause {
+            kind,
+            predicate,
+            action,
+        })
+    }
+
+    /// Plans an expression of a `MERGE` statement against the target table
+    /// and source relation columns in `schema`.
+    fn merge_expr_to_plan(
+        &self,
+        sql: SQLExpr,
+        schema: &DFSchema,
+        planner_context: &mut PlannerContext,
+    ) -> Result<Expr> {
+        let expr = self.sql_to_expr(sql, schema, planner_context)?;
+        let mut using_columns = HashSet::new();
+        expr_to_columns(&expr, &mut using_columns)?;
+        normalize_col_with_schemas_and_ambiguity_check(
+            expr,
+            &[&[schema]],
+            &[using_columns],
+        )
+    }
+
     fn insert_to_plan(
         &self,
         table_name: ObjectName,
@@ -482,6 +482,8 @@ enum TableFactorBuilder {
     Derived(DerivedRelationBuilder),
     Unnest(UnnestRelationBuilder),
     Flatten(FlattenRelationBuilder),
+    Pivot(PivotRelationBuilder),
+    Unpivot(UnpivotRelationBuilder),
     Empty,
 }
 
@@ -508,6 +510,16 @@ impl RelationBuilder {
         self
     }
 
+    pub fn pivot(&mut self, value: PivotRelationBuilder) -> &mut Self {
+        self.relation = Some(TableFactorBuilder::Pivot(value));
+        self
+    }
+
+    pub fn unpivot(&mut self, value: UnpivotRelationBuilder) -> &mut Self {
+        self.relation = Some(TableFactorBuilder::Unpivot(value));
+        self
+    }
+
     pub fn empty(&mut self) -> &mut Self {
         self.relation = Some(TableFactorBuilder::Empty);
         self
@@ -527,17 +539,36 @@ impl RelationBuilder {
             Some(TableFactorBuilder::Flatten(ref mut rel_builder)) => {
                 rel_builder.alias = value;
             }
+            Some(TableFactorBuilder::Pivot(ref mut rel_builder)) => {
+                rel_builder.alias = value;
+            }
+            Some(TableFactorBuilder::Unpivot(ref mut rel_builder)) => {
+                rel_builder.alias = value;
+            }
             Some(TableFactorBuilder::Empty) => (),
             None => (),
         }
         new
     }
+    /// Sets the `TABLESAMPLE` clause of the relation, returning `false` if the
+    /// relation is not a table.
+    pub fn sample(&mut self, value: Option<ast::TableSampleKind>) -> bool {
+        match self.relation {
+            Some(TableFactorBuilder::Table(ref mut rel_builder)) => {
+                rel_builder.sample = value;
+                true
+            }
+            _ => false,
+        }
+    }
     pub fn build(&self) -> Result<Option<ast::TableFactor>, BuilderError> {
         Ok(match self.relation {
             Some(TableFactorBuilder::Table(ref value)) => Some(value.build()?),
             Some(TableFactorBuilder::Derived(ref value)) => Some(value.build()?),
             Some(TableFactorBuilder::Unnest(ref value)) => Some(value.build()?),
             Some(TableFactorBuilder::Flatten(ref value)) => Some(value.build()?),
+            Some(TableFactorBuilder::Pivot(ref value)) => Some(value.build()?),
+            Some(TableFactorBuilder::Unpivot(ref value)) => Some(value.build()?),
             Some(TableFactorBuilder::Empty) => None,
             None => return Err(Into::into(UninitializedFieldError::from("relation"))),
         })
@@ -563,6 +594,7 @@ pub struct TableRelationBuilder {
     version: Option<ast::TableVersion>,
     partitions: Vec<ast::Ident>,
     index_hints: Vec<ast::TableIndexHints>,
+    sample: Option<ast::TableSampleKind>,
 }
 
 impl TableRelationBuilder {
@@ -594,6 +626,10 @@ impl TableRelationBuilder {
         self.index_hints = value;
         self
     }
+    pub fn sample(&mut self, value: Option<ast::TableSampleKind>) -> &mut Self {
+        self.sample = value;
+        self
+    }
     pub fn build(&self) -> Result<ast::TableFactor, BuilderError> {
         Ok(ast::TableFactor::Table {
             name: match self.name {
@@ -610,7 +646,7 @@ impl TableRelationBuilder {
             partitions: self.partitions.clone(),
             with_ordinality: false,
             json_path: None,
-            sample: None,
+            sample: self.sample.clone(),
             index_hints: self.index_hints.clone(),
         })
     }
@@ -623,6 +659,7 @@ impl TableRelationBuilder {
             version: Default::default(),
             partitions: Default::default(),
             index_hints: Default::default(),
+            sample: Default::default(),
         }
     }
 }
@@ -813,6 +850,171 @@ impl Default for FlattenRelationBuilder {
     }
 }
 
+/// Builds a `table PIVOT (agg, ... FOR column IN (value, ...))` table factor.
+#[derive(Clone)]
+pub struct PivotRelationBuilder {
+    pub alias: Option<ast::TableAlias>,
+    /// The input table that is pivoted.
+    pub table: Option<Box<ast::TableFactor>>,
+    /// The aggregates computed for every pivot value.
+    pub aggregate_functions: Vec<ast::Expr>,
+    /// The column whose values become output columns.
+    pub value_column: Option<ast::Expr>,
+    /// The values of `value_column` to pivot on, with the name of their
+    /// output column.
+    pub values: Vec<ast::ExprWithAlias>,
+}
+
+impl PivotRelationBuilder {
+    pub fn alias(&mut self, value: Option<ast::TableAlias>) -> &mut Self {
+        self.alias = value;
+        self
+    }
+
+    pub fn table(&mut self, value: ast::TableFactor) -> &mut Self {
+        self.table = Some(Box::new(value));
+        self
+    }
+
+    pub fn aggregate_functions(&mut self, value: Vec<ast::Expr>) -> &mut Self {
+        self.aggregate_functions = value;
+        self
+    }
+
+    pub fn value_column(&mut self, value: ast::Expr) -> &mut Self {
+        self.value_column = Some(value);
+        self
+    }
+
+    pub fn values(&mut self, value: Vec<ast::ExprWithAlias>) -> &mut Self {
+        self.values = value;
+        self
+    }
+
+    pub fn build(&self) -> Result<ast::TableFactor, BuilderError> {
+        Ok(ast::TableFactor::Pivot {
+            table: self.table.clone().ok_or_else(|| {
+                BuilderError::from(UninitializedFieldError::from("table"))
+            })?,
+            aggregate_functions: self
+                .aggregate_functions
+                .iter()
+                .map(|expr| ast::ExprWithAlias {
+                    expr: expr.clone(),
+                    alias: None,
+                })
+                .collect(),
+            value_column: vec![self.value_column.clone().ok_or_else(|| {
+                BuilderError::from(UninitializedFieldError::from("value_column"))
+            })?],
+            value_source: ast::PivotValueSource::List(self.values.clone()),
+            default_on_null: None,
+            alias: self.alias.clone(),
+        })
+    }
+
+    fn create_empty() -> Self {
+        Self {
+            alias: None,
+            table: None,
+            aggregate_functions: vec![],
+            value_column: None,
+            values: vec![],
+        }
+    }
+}
+
+impl Default for PivotRelationBuilder {
+    fn default() -> Self {
+        Self::create_empty()
+    }
+}
+
+/// Builds a `table UNPIVOT [INCLUDE NULLS] (value FOR name IN (column, ...))`
+/// table factor.
+#[derive(Clone)]
+pub struct UnpivotRelationBuilder {
+    pub alias: Option<ast::TableAlias>,
+    /// The input table that is unpivoted.
+    pub table: Option<Box<ast::TableFactor>>,
+    /// The output column holding the values of the unpivoted columns.
+    pub value: Option<ast::Ident>,
+    /// The output column holding the labels of the unpivoted columns.
+    pub name: Option<ast::Ident>,
+    /// The unpivoted columns, with an optional label.
+    pub columns: Vec<ast::ExprWithAlias>,
+    /// Whether rows with a NULL value are kept.
+    pub include_nulls: bool,
+}
+
+impl UnpivotRelationBuilder {
+    pub fn alias(&mut self, value: Option<ast::TableAlias>) -> &mut Self {
+        self.alias = value;
+        self
+    }
+
+    pub fn table(&mut self, value: ast::TableFactor) -> &mut Self {
+        self.table = Some(Box::new(value));
+        self
+    }
+
+    pub fn value(&mut self, value: ast::Ident) -> &mut Self {
+        self.value = Some(value);
+        self
+    }
+
+    pub fn name(&mut self, value: ast::Ident) -> &mut Self {
+        self.name = Some(value);
+        self
+    }
+
+    pub fn columns(&mut self, value: Vec<ast::ExprWithAlias>) -> &mut Self {
+        self.columns = value;
+        self
+    }
+
+    pub fn include_nulls(&mut self, value: bool) -> &mut Self {
+        self.include_nulls = value;
+        self
+    }
+
+    pub fn build(&self) -> Result<ast::TableFactor, BuilderError> {
+        Ok(ast::TableFactor::Unpivot {
+            table: self.table.clone().ok_or_else(|| {
+                BuilderError::from(UninitializedFieldError::from("table"))
+            })?,
+            value: ast::Expr::Identifier(self.value.clone().ok_or_else(|| {
+                BuilderError::from(UninitializedFieldError::from("value"))
+            })?),
+            name: self.name.clone().ok_or_else(|| {
+                BuilderError::from(UninitializedFieldError::from("name"))
+            })?,
+            columns: self.columns.clone(),
+            null_inclusion: self
+                .include_nulls
+                .then_some(ast::NullInclusion::IncludeNulls),
+            alias: self.alias.clone(),
+        })
+    }
+
+    fn create_empty() -> Self {
+        Self {
+            alias: None,
+            table: None,
+            value: None,
+            name: None,
+            columns: vec![],
+            include_nulls: false,
+        }
+    }
+}
+
+impl Default for UnpivotRelationBuilder {
+    fn default() -> Self {
+        Self::create_empty()
+    }
+}
+
 /// Runtime error when a `build()` method is called and one or more required fields
 /// do not have a value.
 #[derive(Debug, Clone)]
@@ -232,6 +232,18 @@ pub trait Dialect: Send + Sync {
         false
     }
 
+    /// Allow to unparse PIVOT and UNPIVOT plans as [ast::TableFactor::Pivot]
+    /// and [ast::TableFactor::Unpivot].
+    ///
+    /// The SQL planner rewrites `PIVOT` into a conditional aggregation and
+    /// `UNPIVOT` into a `UNION ALL`. When this returns `true`, an aliased
+    /// relation with the plan shape produced by the planner is unparsed back
+    /// into the table operator. Otherwise, or if the shape is not recognized
+    /// (e.g. after optimization), the equivalent query is emitted.
+    fn supports_pivot(&self) -> bool {
+        false
+    }
+
     /// Allows the dialect to override column alias unparsing if the dialect has specific rules.
     /// Returns None if the default unparsing should be used, or Some(String) if there is
     /// a custom implementation for the alias.
@@ -498,6 +510,10 @@ impl Dialect for DuckDBDialect {
         BinaryOperator::DuckIntegerDivide
     }
 
+    fn supports_pivot(&self) -> bool {
+        true
+    }
+
     fn with_custom_scalar_overrides(
         mut self,
         handlers: Vec<(&str, ScalarFnToSqlHandler)>,
@@ -686,6 +702,10 @@ impl Dialect for BigQueryDialect {
         true
     }
 
+    fn supports_pivot(&self) -> bool {
+        true
+    }
+
     fn supports_column_alias_in_table_alias(&self) -> bool {
         false
     }
@@ -748,6 +768,7 @@ impl BigQueryDialect {
 /// - Does not support column aliases in table alias definitions
 ///   (Snowflake accepts the syntax but silently ignores the renames in join contexts)
 /// - Unparses `UNNEST` plans as `LATERAL FLATTEN(INPUT => expr, ...)`
+/// - Supports `PIVOT` and `UNPIVOT`
 pub struct SnowflakeDialect {}
 
 #[expect(clippy::new_without_default)]
@@ -790,6 +811,10 @@ impl Dialect for SnowflakeDialect {
     fn unnest_as_lateral_flatten(&self) -> bool {
         true
     }
+
+    fn supports_pivot(&self) -> bool {
+        true
+    }
 }
 
 pub struct CustomDialect {
@@ -815,6 +840,7 @@ pub struct CustomDialect {
     full_qualified_col: bool,
     unnest_as_table_factor: bool,
     unnest_as_lateral_flatten: bool,
+    supports_pivot: bool,
 }
 
 impl Default for CustomDialect {
@@ -845,6 +871,7 @@ impl Default for CustomDialect {
             full_qualified_col: false,
             unnest_as_table_factor: false,
             unnest_as_lateral_flatten: false,
+            supports_pivot: false,
         }
     }
 }
@@ -963,6 +990,10 @@ impl Dialect for CustomDialect {
     fn unnest_as_lateral_flatten(&self) -> bool {
         self.unnest_as_lateral_flatten
     }
+
+    fn supports_pivot(&self) -> bool {
+        self.supports_pivot
+    }
 }
 
 /// `CustomDialectBuilder` to build `CustomDialect` using builder pattern
@@ -1002,6 +1033,7 @@ pub struct CustomDialectBuilder {
     full_qualified_col: bool,
     unnest_as_table_factor: bool,
     unnest_as_lateral_flatten: bool,
+    supports_pivot: bool,
 }
 
 impl Default for CustomDialectBuilder {
@@ -1038,6 +1070,7 @@ impl CustomDialectBuilder {
             full_qualified_col: false,
             unnest_as_table_factor: false,
             unnest_as_lateral_flatten: false,
+            supports_pivot: false,
         }
     }
 
@@ -1066,6 +1099,7 @@ impl CustomDialectBuilder {
             full_qualified_col: self.full_qualified_col,
             unnest_as_table_factor: self.unnest_as_table_factor,
             unnest_as_lateral_flatten: self.unnest_as_lateral_flatten,
+            supports_pivot: self.supports_pivot,
         }
     }
 
@@ -1220,4 +1254,10 @@ impl CustomDialectBuilder {
         self.unnest_as_lateral_flatten = unnest_as_lateral_flatten;
         self
     }
+
+    /// Customize the dialect to unparse PIVOT and UNPIVOT plans as table operators
+    pub fn with_supports_pivot(mut self, supports_pivot: bool) -> Self {
+        self.supports_pivot = supports_pivot;
+        self
+    }
 }
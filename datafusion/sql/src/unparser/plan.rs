@@ -18,8 +18,9 @@
 use super::{
     Unparser,
     ast::{
-        BuilderError, DerivedRelationBuilder, QueryBuilder, RelationBuilder,
-        SelectBuilder, TableRelationBuilder, TableWithJoinsBuilder,
+        BuilderError, DerivedRelationBuilder, PivotRelationBuilder, QueryBuilder,
+        RelationBuilder, SelectBuilder, TableRelationBuilder, TableWithJoinsBuilder,
+        UnpivotRelationBuilder,
     },
     rewrite::{
         TableAliasRewriter, inject_column_aliases_into_subquery, normalize_union_schema,
@@ -42,15 +43,15 @@ use crate::unparser::{
 };
 use crate::utils::UNNEST_PLACEHOLDER;
 use datafusion_common::{
-    Column, DataFusionError, Result, ScalarValue, TableReference, assert_or_internal_err,
-    internal_datafusion_err, internal_err, not_impl_err,
+    Column, DataFusionError, Result, SampleMethod, ScalarValue, TableReference,
+    assert_or_internal_err, internal_datafusion_err, internal_err, not_impl_err,
     tree_node::{Transformed, TransformedResult, TreeNode, TreeNodeRecursion},
 };
 use datafusion_expr::expr::{OUTER_REFERENCE_COLUMN_PREFIX, UNNEST_COLUMN_PREFIX};
 use datafusion_expr::{
     BinaryExpr, Distinct, Expr, JoinConstraint, JoinType, LogicalPlan,
-    LogicalPlanBuilder, Operator, Projection, SortExpr, TableScan, Unnest,
-    UserDefinedLogicalNode, expr::Alias,
+    LogicalPlanBuilder, Operator, Projection, Sample, SortExpr, SubqueryAlias, TableScan,
+    Unnest, UserDefinedLogicalNode, expr::Alias,
 };
 use sqlparser::ast::{self, Ident, OrderByKind, SetExpr, TableAliasColumnDef};
 use std::{sync::Arc, vec};
@@ -115,6 +116,7 @@ impl Unparser<'_> {
             | LogicalPlan::Sort(_)
             | LogicalPlan::Join(_)
             | LogicalPlan::Repartition(_)
+            | LogicalPlan::Sample(_)
             | LogicalPlan::Union(_)
             | LogicalPlan::TableScan(_)
             | LogicalPlan::EmptyRelation(_)
@@ -863,6 +865,13 @@ impl Unparser<'_> {
                     &mut right_relation,
                 )?;
 
+                if join.join_type == JoinType::LeftAsOf && !table_scan_filters.is_empty()
+                {
+                    return not_impl_err!(
+                        "Unparsing ASOF joins with table scan filters is not supported"
+                    );
+                }
+
                 let (join_filters, where_filters) = Self::split_join_on_and_where_filters(
                     join.join_type,
                     &join.filter,
@@ -873,6 +882,18 @@ impl Unparser<'_> {
                     select.selection(Some(filter_expr));
                 }
 
+                // The filter of an ASOF join is its match condition
+                let (match_condition, join_filters) = if join.join_type
+                    == JoinType::LeftAsOf
+                {
+                    let Some(match_condition) = join_filters else {
+                        return internal_err!("ASOF join is missing its match condition");
+                    };
+                    (Some(match_condition), None)
+                } else {
+                    (None, join_filters)
+                };
+
                 let join_constraint = self.join_constraint_to_sql(
                     join.join_constraint,
                     &join.on,
@@ -952,15 +973,23 @@ impl Unparser<'_> {
                     JoinType::Inner
                     | JoinType::Left
                     | JoinType::Right
-                    | JoinType::Full => {
+                    | JoinType::Full
+                    | JoinType::LeftAsOf => {
                         let Ok(Some(relation)) = right_relation.build() else {
                             return internal_err!("Failed to build right relation");
                         };
+                        let join_operator = match match_condition {
+                            Some(match_condition) => ast::JoinOperator::AsOf {
+                                match_condition: self.expr_to_sql(&match_condition)?,
+                                constraint: join_constraint,
+                            },
+                            None => self
+                                .join_operator_to_sql(join.join_type, join_constraint)?,
+                        };
                         let ast_join = ast::Join {
                             relation,
                             global: false,
-                            join_operator: self
-                                .join_operator_to_sql(join.join_type, join_constraint)?,
+                            join_operator,
                         };
                         let mut from = select.pop_from().unwrap();
                         from.push_join(ast_join);
@@ -986,6 +1015,17 @@ impl Unparser<'_> {
                 Ok(())
             }
             LogicalPlan::SubqueryAlias(plan_alias) => {
+                if self.dialect.supports_pivot()
+                    && self.try_pivot_relation_to_sql(plan_alias, relation)?
+                {
+                    if !select.already_projected() {
+                        select.projection(vec![ast::SelectItem::Wildcard(
+                            ast::WildcardAdditionalOptions::default(),
+                        )]);
+                    }
+                    return Ok(());
+                }
+
                 let (plan, mut columns) =
                     subquery_alias_inner_query_and_columns(plan_alias);
                 let unparsed_table_scan = self.unparse_table_scan_pushdown(
@@ -1150,6 +1190,19 @@ impl Unparser<'_> {
                     relation,
                 )
             }
+            LogicalPlan::Sample(sample) => {
+                self.select_to_sql_recursively(
+                    sample.input.as_ref(),
+                    query,
+                    select,
+                    relation,
+                )?;
+                // The sample can only be unparsed as a TABLESAMPLE clause of a table
+                if !relation.sample(Some(self.sample_to_sql(sample))) {
+                    return not_impl_err!("Unsupported plan: {plan:?}");
+                }
+                Ok(())
+            }
             LogicalPlan::EmptyRelation(_) => {
                 // An EmptyRelation could be behind an UNNEST node. If the dialect supports UNNEST as a table factor,
                 // a TableRelationBuilder will be created for the UNNEST node first.
@@ -1490,6 +1543,311 @@ impl Unparser<'_> {
         Ok(Some(unnest_relation))
     }
 
+    /// Unparse the input of a `SubqueryAlias` as a PIVOT or UNPIVOT table
+    /// factor if it has the shape produced by the SQL planner for these
+    /// operators.
+    ///
+    /// Returns `Ok(true)` when the relation has been set.
+    fn try_pivot_relation_to_sql(
+        &self,
+        plan_alias: &SubqueryAlias,
+        relation: &mut RelationBuilder,
+    ) -> Result<bool> {
+        let alias =
+            Some(self.new_table_alias(plan_alias.alias.table().to_string(), vec![]));
+        if let Some(mut pivot) = self.try_pivot_to_sql(plan_alias.input.as_ref())? {
+            pivot.alias(alias);
+            relation.pivot(pivot);
+            Ok(true)
+        } else if let Some(mut unpivot) =
+            self.try_unpivot_to_sql(plan_alias.input.as_ref())?
+        {
+            unpivot.alias(alias);
+            relation.unpivot(unpivot);
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    /// Recognize a PIVOT planned as
+    ///
+    /// ```text
+    /// Projection: <all aggregate output columns>
+    ///   Aggregate: groupBy=[[<remaining input columns>]], aggr=[[
+    ///     agg(x) FILTER (WHERE pivot = value1) AS name1,
+    ///     agg(x) FILTER (WHERE pivot = value2) AS name2, ...]]
+    /// ```
+    ///
+    /// Only a single aggregate is supported, so that every value can be
+    /// aliased with the name of its output column regardless of how the
+    /// dialect names pivot columns.
+    fn try_pivot_to_sql(
+        &self,
+        plan: &LogicalPlan,
+    ) -> Result<Option<PivotRelationBuilder>> {
+        let LogicalPlan::Projection(projection) = plan else {
+            return Ok(None);
+        };
+        let LogicalPlan::Aggregate(agg) = projection.input.as_ref() else {
+            return Ok(None);
+        };
+        let output_columns = agg.schema.columns().into_iter().map(Expr::Column);
+        if !projection.expr.iter().cloned().eq(output_columns) {
+            return Ok(None);
+        }
+
+        let mut pivot_column = None;
+        let mut aggregate = None;
+        let mut values = Vec::with_capacity(agg.aggr_expr.len());
+        for expr in &agg.aggr_expr {
+            let Expr::Alias(Alias { expr, name, .. }) = expr else {
+                return Ok(None);
+            };
+            let Expr::AggregateFunction(func) = expr.as_ref() else {
+                return Ok(None);
+            };
+            let Some(Expr::BinaryExpr(BinaryExpr {
+                left,
+                op: Operator::Eq,
+                right,
+            })) = func.params.filter.as_deref()
+            else {
+                return Ok(None);
+            };
+            let mut func = func.clone();
+            func.params.filter = None;
+            let func = Expr::AggregateFunction(func);
+            if *pivot_column.get_or_insert(left) != left
+                || *aggregate.get_or_insert_with(|| func.clone()) != func
+            {
+                return Ok(None);
+            }
+            values.push((right, name));
+        }
+        let (Some(pivot_column), Some(aggregate)) = (pivot_column, aggregate) else {
+            return Ok(None);
+        };
+
+        // PIVOT implicitly groups by all columns that are not referenced
+        let mut referenced = pivot_column.column_refs();
+        referenced.extend(aggregate.column_refs());
+        let group_expr = agg
+            .input
+            .schema()
+            .columns()
+            .into_iter()
+            .filter(|column| !referenced.contains(column))
+            .map(Expr::Column)
+            .collect::<Vec<_>>();
+        if agg.group_expr != group_expr {
+            return Ok(None);
+        }
+
+        let values = values
+            .into_iter()
+            .map(|(value, name)| {
+                Ok(ast::ExprWithAlias {
+                    expr: self.expr_to_sql(value)?,
+                    alias: Some(self.new_ident_quoted_if_needs(name.clone())),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let mut pivot = PivotRelationBuilder::default();
+        pivot
+            .table(self.pivot_input_to_sql(agg.input.as_ref())?)
+            .aggregate_functions(vec![self.unqualified_expr_to_sql(&aggregate)?])
+            .value_column(self.unqualified_expr_to_sql(pivot_column)?)
+            .values(values);
+        Ok(Some(pivot))
+    }
+
+    /// Recognize an UNPIVOT planned as
+    ///
+    /// ```text
+    /// Filter: value IS NOT NULL          (unless INCLUDE NULLS)
+    ///   Union
+    ///     Projection: <remaining input columns>, Utf8("label1") AS name, col1 AS value
+    ///       <input>
+    ///     Projection: <remaining input columns>, Utf8("label2") AS name, col2 AS value
+    ///       <input>
+    /// ```
+    fn try_unpivot_to_sql(
+        &self,
+        plan: &LogicalPlan,
+    ) -> Result<Option<UnpivotRelationBuilder>> {
+        let (union, not_null) = match plan {
+            LogicalPlan::Filter(filter) => {
+                match (&filter.predicate, filter.input.as_ref()) {
+                    (Expr::IsNotNull(value), LogicalPlan::Union(union)) => {
+                        (union, Some(value.as_ref()))
+                    }
+                    _ => return Ok(None),
+                }
+            }
+            LogicalPlan::Union(union) => (union, None),
+            _ => return Ok(None),
+        };
+
+        let mut input = None;
+        let mut kept = None;
+        let mut names = None;
+        let mut columns = Vec::with_capacity(union.inputs.len());
+        for branch in &union.inputs {
+            let LogicalPlan::Projection(projection) = branch.as_ref() else {
+                return Ok(None);
+            };
+            let [kept_exprs @ .., Expr::Alias(label), Expr::Alias(value)] =
+                projection.expr.as_slice()
+            else {
+                return Ok(None);
+            };
+            let (
+                Expr::Literal(ScalarValue::Utf8(Some(label_value)), _),
+                Expr::Column(column),
+            ) = (label.expr.as_ref(), value.expr.as_ref())
+            else {
+                return Ok(None);
+            };
+            if *input.get_or_insert(&projection.input) != &projection.input
+                || *kept.get_or_insert(kept_exprs) != kept_exprs
+                || *names.get_or_insert((&label.name, &value.name))
+                    != (&label.name, &value.name)
+            {
+                return Ok(None);
+            }
+            columns.push((column, label_value));
+        }
+        let (Some(input), Some(kept), Some((name, value_name))) = (input, kept, names)
+        else {
+            return Ok(None);
+        };
+        if let Some(not_null) = not_null
+            && *not_null != Expr::Column(Column::new_unqualified(value_name))
+        {
+            return Ok(None);
+        }
+
+        // UNPIVOT implicitly keeps all columns that are not unpivoted
+        let expected_kept = input
+            .schema()
+            .columns()
+            .into_iter()
+            .filter(|column| !columns.iter().any(|(unpivoted, _)| *unpivoted == column))
+            .map(Expr::Column)
+            .collect::<Vec<_>>();
+        if kept != expected_kept {
+            return Ok(None);
+        }
+
+        let columns = columns
+            .into_iter()
+            .map(|(column, label)| ast::ExprWithAlias {
+                expr: ast::Expr::Identifier(
+                    self.new_ident_quoted_if_needs(column.name.clone()),
+                ),
+                alias: (*label != column.name).then(|| Ident::with_quote('\'', label)),
+            })
+            .collect();
+        let mut unpivot = UnpivotRelationBuilder::default();
+        unpivot
+            .table(self.pivot_input_to_sql(input.as_ref())?)
+            .value(self.new_ident_quoted_if_needs(value_name.clone()))
+            .name(self.new_ident_quoted_if_needs(name.clone()))
+            .columns(columns)
+            .include_nulls(not_null.is_none());
+        Ok(Some(unpivot))
+    }
+
+    /// Unparse the input of a PIVOT or UNPIVOT as a table factor, using a
+    /// derived subquery unless it is a plain (possibly aliased) table scan.
+    fn pivot_input_to_sql(&self, plan: &LogicalPlan) -> Result<ast::TableFactor> {
+        let is_plain_scan = |plan: &LogicalPlan| matches!(plan, LogicalPlan::TableScan(scan) if !Self::is_scan_with_pushdown(scan));
+        let mut relation = RelationBuilder::default();
+        match plan {
+            LogicalPlan::SubqueryAlias(alias) if !is_plain_scan(alias.input.as_ref()) => {
+                self.derive(
+                    alias.input.as_ref(),
+                    &mut relation,
+                    Some(self.new_table_alias(alias.alias.table().to_string(), vec![])),
+                    false,
+                )?;
+            }
+            LogicalPlan::SubqueryAlias(_) => {
+                self.select_to_sql_recursively(
+                    plan,
+                    &mut None,
+                    &mut SelectBuilder::default(),
+                    &mut relation,
+                )?;
+            }
+            _ if is_plain_scan(plan) => {
+                self.select_to_sql_recursively(
+                    plan,
+                    &mut None,
+                    &mut SelectBuilder::default(),
+                    &mut relation,
+                )?;
+            }
+            _ => {
+                self.derive_with_dialect_alias(
+                    "derived_pivot",
+                    plan,
+                    &mut relation,
+                    false,
+                    vec![],
+                )?;
+            }
+        }
+        relation
+            .build()?
+            .ok_or_else(|| internal_datafusion_err!("Missing relation for PIVOT input"))
+    }
+
+    /// Unparse an expression with all column references unqualified, as
+    /// required inside PIVOT.
+    fn unqualified_expr_to_sql(&self, expr: &Expr) -> Result<ast::Expr> {
+        let expr = expr
+            .clone()
+            .transform(|expr| match expr {
+                Expr::Column(column) => Ok(Transformed::yes(Expr::Column(
+                    Column::new_unqualified(column.name),
+                ))),
+                _ => Ok(Transformed::no(expr)),
+            })
+            .data()?;
+        self.expr_to_sql(&expr)
+    }
+
+    /// Converts a [`Sample`] into a `TABLESAMPLE <method> (<percent> PERCENT)
+    /// [REPEATABLE (<seed>)]` clause.
+    fn sample_to_sql(&self, sample: &Sample) -> ast::TableSampleKind {
+        let name = match sample.method {
+            SampleMethod::Bernoulli => ast::TableSampleMethod::Bernoulli,
+            SampleMethod::System => ast::TableSampleMethod::System,
+        };
+        let quantity = ast::TableSampleQuantity {
+            parenthesized: true,
+            value: ast::Expr::value(ast::Value::Number(
+                (sample.fraction * 100.0).to_string(),
+                false,
+            )),
+            unit: Some(ast::TableSampleUnit::Percent),
+        };
+        let seed = sample.seed.map(|seed| ast::TableSampleSeed {
+            modifier: ast::TableSampleSeedModifier::Repeatable,
+            value: ast::Value::Number(seed.to_string(), false),
+        });
+        ast::TableSampleKind::AfterTableAlias(Box::new(ast::TableSample {
+            modifier: ast::TableSampleModifier::TableSample,
+            name: Some(name),
+            quantity: Some(quantity),
+            seed,
+            bucket: None,
+            offset: None,
+        }))
+    }
+
     /// Build a `SELECT alias."VALUE"` item for Snowflake FLATTEN output.
     fn build_flatten_value_select_item(
         &self,
@@ -1773,6 +2131,9 @@ impl Unparser<'_> {
             JoinType::LeftMark | JoinType::RightMark => {
                 unimplemented!("Unparsing of Mark join type")
             }
+            JoinType::LeftAsOf => {
+                return internal_err!("ASOF join requires a match condition");
+            }
         })
     }
 
@@ -126,6 +126,8 @@ fn roundtrip_statement() -> Result<()> {
             "select ta.j1_id from j1 ta where ta.j1_id > 1;",
             "select ta.j1_id, tb.j2_string from j1 ta join j2 tb on (ta.j1_id = tb.j2_id);",
             "select ta.j1_id, tb.j2_string, tc.j3_string from j1 ta join j2 tb on (ta.j1_id = tb.j2_id) join j3 tc on (ta.j1_id = tc.j3_id);",
+            "select ta.j1_id, tb.j2_string from j1 ta asof join j2 tb match_condition (ta.j1_id >= tb.j2_id);",
+            "select ta.j1_id, tb.j2_string from j1 ta asof join j2 tb match_condition (ta.j1_id < tb.j2_id) on ta.j1_string = tb.j2_string;",
             "select * from (select id, first_name from person)",
             "select * from (select id, first_name from (select * from person))",
             "select id, count(*) as cnt from (select id from person) group by id",
@@ -259,6 +261,18 @@ fn roundtrip_statement() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_asof_join_to_sql() {
+    let statement = generate_round_trip_statement(
+        GenericDialect {},
+        "select ta.j1_id, tb.j2_string from j1 ta asof join j2 tb match_condition (ta.j1_id >= tb.j2_id) on ta.j1_string = tb.j2_string",
+    );
+    assert_snapshot!(
+        statement,
+        @"SELECT ta.j1_id, tb.j2_string FROM j1 AS ta ASOF JOIN j2 AS tb MATCH_CONDITION ((ta.j1_id >= tb.j2_id)) ON (ta.j1_string = tb.j2_string)"
+    );
+}
+
 #[test]
 fn roundtrip_crossjoin() -> Result<()> {
     let query = "select j1.j1_id, j2.j2_string from j1, j2";
@@ -3611,3 +3625,80 @@ fn snowflake_flatten_multiple_unnest_cross_join() -> Result<(), DataFusionError>
     );
     Ok(())
 }
+
+#[test]
+fn roundtrip_pivot() -> Result<(), DataFusionError> {
+    let sql =
+        "SELECT * FROM j1 PIVOT (max(j1_id) FOR j1_string IN ('a', 'b' AS bee)) AS p";
+    let snowflake = SnowflakeDialect::new();
+    roundtrip_statement_with_dialect_helper!(
+        sql: sql,
+        parser_dialect: GenericDialect {},
+        unparser_dialect: snowflake,
+        expected: @r#"SELECT "p"."a", "p"."bee" FROM "j1" PIVOT(max("j1_id") FOR "j1_string" IN ('a' AS "a", 'b' AS "bee")) AS "p""#,
+    );
+    // Dialects without PIVOT get the equivalent aggregation
+    roundtrip_statement_with_dialect_helper!(
+        sql: sql,
+        parser_dialect: GenericDialect {},
+        unparser_dialect: UnparserDefaultDialect {},
+        expected: @"SELECT p.a, p.bee FROM (SELECT max(j1.j1_id) FILTER (WHERE (j1.j1_string = 'a')) AS a, max(j1.j1_id) FILTER (WHERE (j1.j1_string = 'b')) AS bee FROM j1) AS p",
+    );
+    Ok(())
+}
+
+#[test]
+fn roundtrip_pivot_with_input_subquery() -> Result<(), DataFusionError> {
+    let unparser = CustomDialectBuilder::default()
+        .with_supports_pivot(true)
+        .build();
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT * FROM (SELECT j1_id, j1_string FROM j1 WHERE j1_id > 1) AS t PIVOT (max(j1_id) FOR j1_string IN ('a')) AS p",
+        parser_dialect: GenericDialect {},
+        unparser_dialect: unparser,
+        expected: @"SELECT p.a FROM (SELECT j1.j1_id, j1.j1_string FROM j1 WHERE (j1.j1_id > 1)) AS t PIVOT(max(j1_id) FOR j1_string IN ('a' AS a)) AS p",
+    );
+    Ok(())
+}
+
+#[test]
+fn roundtrip_unpivot() -> Result<(), DataFusionError> {
+    let sql = "SELECT u.id, u.kind, u.name FROM person UNPIVOT (name FOR kind IN (first_name, last_name AS 'last')) AS u";
+    roundtrip_statement_with_dialect_helper!(
+        sql: sql,
+        parser_dialect: GenericDialect {},
+        unparser_dialect: BigQueryDialect {},
+        expected: @"SELECT `u`.`id`, `u`.`kind`, `u`.`name` FROM `person` UNPIVOT(`name` FOR `kind` IN (`first_name`, `last_name` AS 'last')) AS `u`",
+    );
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT * FROM j2 UNPIVOT INCLUDE NULLS (v FOR k IN (j2_string, j2_string AS 'again')) AS u",
+        parser_dialect: GenericDialect {},
+        unparser_dialect: BigQueryDialect {},
+        expected: @"SELECT `u`.`j2_id`, `u`.`k`, `u`.`v` FROM `j2` UNPIVOT INCLUDE NULLS (`v` FOR `k` IN (`j2_string`, `j2_string` AS 'again')) AS `u`",
+    );
+    // Dialects without UNPIVOT get the equivalent UNION ALL
+    roundtrip_statement_with_dialect_helper!(
+        sql: sql,
+        parser_dialect: GenericDialect {},
+        unparser_dialect: UnparserDefaultDialect {},
+        expected: @r#"SELECT u.id, u.kind, u."name" FROM (SELECT person.id, person.age, person.state, person.salary, person.birth_date, person."😀", 'first_name' AS kind, person.first_name AS "name" FROM person UNION ALL SELECT person.id, person.age, person.state, person.salary, person.birth_date, person."😀", 'last' AS kind, person.last_name AS "name" FROM person) AS u WHERE "name" IS NOT NULL"#,
+    );
+    Ok(())
+}
+
+#[test]
+fn roundtrip_tablesample() -> Result<(), DataFusionError> {
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT j1_id FROM j1 AS t TABLESAMPLE SYSTEM (5 PERCENT) REPEATABLE (42)",
+        parser_dialect: GenericDialect {},
+        unparser_dialect: UnparserDefaultDialect {},
+        expected: @"SELECT t.j1_id FROM j1 AS t TABLESAMPLE SYSTEM (5 PERCENT) REPEATABLE (42)",
+    );
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT * FROM j1 TABLESAMPLE (50) WHERE j1_id > 1",
+        parser_dialect: GenericDialect {},
+        unparser_dialect: UnparserDefaultDialect {},
+        expected: @"SELECT j1.j1_id, j1.j1_string FROM j1 TABLESAMPLE BERNOULLI (50 PERCENT) WHERE (j1.j1_id > 1)",
+    );
+    Ok(())
+}
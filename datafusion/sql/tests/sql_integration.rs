@@ -806,6 +806,43 @@ fn plan_delete_quoted_identifier_case_sensitive() {
     );
 }
 
+#[test]
+fn plan_merge() {
+    let sql = "MERGE INTO person p USING orders o ON p.id = o.customer_id \
+        WHEN MATCHED AND o.qty > 10 THEN UPDATE SET age = o.qty \
+        WHEN MATCHED THEN DELETE \
+        WHEN NOT MATCHED THEN INSERT (id, last_name) VALUES (o.customer_id, o.o_item_id) \
+        WHEN NOT MATCHED BY SOURCE THEN UPDATE SET state = 'gone'";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+    Dml: op=[Merge] table=[person] on=[p.id = o.customer_id] clauses=[WHEN MATCHED AND o.qty > Int64(10) THEN UPDATE SET age = o.qty, WHEN MATCHED THEN DELETE, WHEN NOT MATCHED BY TARGET THEN INSERT VALUES (o.customer_id, CAST(NULL AS Utf8), o.o_item_id, CAST(NULL AS Int32), CAST(NULL AS Utf8), CAST(NULL AS Float64), CAST(NULL AS Timestamp(ns)), CAST(NULL AS Int32)), WHEN NOT MATCHED BY SOURCE THEN UPDATE SET state = Utf8("gone")]
+      SubqueryAlias: o
+        TableScan: orders
+    "#
+    );
+}
+
+#[rstest]
+#[case::update_missing_column(
+    "MERGE INTO person USING orders ON id = customer_id WHEN MATCHED THEN UPDATE SET doesnotexist = 1",
+    "Error during planning: Column 'doesnotexist' does not exist in the MERGE target table"
+)]
+#[case::insert_target_column(
+    "MERGE INTO person p USING orders ON id = customer_id WHEN NOT MATCHED THEN INSERT (id) VALUES (p.id)",
+    "Error during planning: MERGE INSERT values cannot reference the target table, got p.id"
+)]
+#[case::insert_column_count(
+    "MERGE INTO person USING orders ON id = customer_id WHEN NOT MATCHED THEN INSERT (id, age) VALUES (customer_id)",
+    "Error during planning: Column count doesn't match insert query!"
+)]
+#[test]
+fn plan_merge_invalid(#[case] sql: &str, #[case] expected: &str) {
+    let err = logical_plan(sql).expect_err("query should have failed");
+    assert_eq!(err.strip_backtrace(), expected);
+}
+
 #[test]
 fn select_column_does_not_exist() {
     let sql = "SELECT doesnotexist FROM person";
@@ -4921,6 +4958,53 @@ fn plan_create_index() {
     }
 }
 
+#[test]
+fn plan_analyze_table() {
+    let plan = logical_plan("ANALYZE TABLE person").unwrap();
+    assert_snapshot!(plan, @r#"AnalyzeTable: Bare { table: "person" }"#);
+
+    let sql = "ANALYZE TABLE person COMPUTE STATISTICS FOR COLUMNS id, AGE";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"AnalyzeTable: Bare { table: "person" } columns:=[id, age]"#
+    );
+
+    let sql = "ANALYZE TABLE person COMPUTE STATISTICS FOR COLUMNS nope";
+    let err = logical_plan(sql).unwrap_err();
+    assert_snapshot!(
+        err.strip_backtrace(),
+        @"Error during planning: Column nope not found in table person"
+    );
+}
+
+#[test]
+fn plan_materialized_view() {
+    let sql = "CREATE MATERIALIZED VIEW v AS SELECT id FROM person WHERE age > 21";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+    CreateMaterializedView: Bare { table: "v" }
+      Projection: person.id
+        Filter: person.age > Int64(21)
+          TableScan: person
+    "#
+    );
+
+    let plan = logical_plan("REFRESH MATERIALIZED VIEW person").unwrap();
+    assert_snapshot!(plan, @r#"RefreshMaterializedView: Bare { table: "person" }"#);
+
+    let plan = logical_plan("DROP MATERIALIZED VIEW v").unwrap();
+    assert_snapshot!(plan, @r#"DropView: Bare { table: "v" } if not exist:=false"#);
+
+    let err = logical_plan("REFRESH MATERIALIZED VIEW nope").unwrap_err();
+    assert_snapshot!(
+        err.strip_backtrace(),
+        @"Error during planning: No table named: nope found"
+    );
+}
+
 #[test]
 fn test_table_function_with_unsupported_arg_propagates_error() {
     let sql = "SELECT * FROM my_func(('a', 'b', 'c'))";
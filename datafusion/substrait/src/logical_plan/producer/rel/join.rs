@@ -30,7 +30,7 @@ pub fn from_join(
 ) -> datafusion::common::Result<Box<Rel>> {
     let left = producer.handle_plan(join.left.as_ref())?;
     let right = producer.handle_plan(join.right.as_ref())?;
-    let join_type = to_substrait_jointype(join.join_type);
+    let join_type = to_substrait_jointype(join.join_type)?;
     // we only support basic joins so return an error for anything not yet supported
     match join.join_constraint {
         JoinConstraint::On => {}
@@ -105,8 +105,10 @@ fn to_substrait_join_expr(
     Ok(join_expr)
 }
 
-fn to_substrait_jointype(join_type: JoinType) -> join_rel::JoinType {
-    match join_type {
+fn to_substrait_jointype(
+    join_type: JoinType,
+) -> datafusion::common::Result<join_rel::JoinType> {
+    Ok(match join_type {
         JoinType::Inner => join_rel::JoinType::Inner,
         JoinType::Left => join_rel::JoinType::Left,
         JoinType::Right => join_rel::JoinType::Right,
@@ -117,5 +119,6 @@ fn to_substrait_jointype(join_type: JoinType) -> join_rel::JoinType {
         JoinType::RightMark => join_rel::JoinType::RightMark,
         JoinType::RightAnti => join_rel::JoinType::RightAnti,
         JoinType::RightSemi => join_rel::JoinType::RightSemi,
-    }
+        JoinType::LeftAsOf => return not_impl_err!("join type: `LeftAsOf`"),
+    })
 }
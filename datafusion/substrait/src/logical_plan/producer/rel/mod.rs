@@ -71,6 +71,7 @@ pub fn to_substrait_rel(
             not_impl_err!("Unsupported plan type: {plan:?}")?
         }
         LogicalPlan::Unnest(plan) => not_impl_err!("Unsupported plan type: {plan:?}")?,
+        LogicalPlan::Sample(plan) => not_impl_err!("Unsupported plan type: {plan:?}")?,
         LogicalPlan::RecursiveQuery(plan) => {
             not_impl_err!("Unsupported plan type: {plan:?}")?
         }
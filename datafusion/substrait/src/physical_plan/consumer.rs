@@ -18,203 +18,886 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use datafusion::arrow::datatypes::{DataType, Field, Schema};
-use datafusion::common::{not_impl_err, substrait_err};
+use datafusion::arrow::datatypes::{Field, Schema, SchemaRef};
+use datafusion::common::config::CsvOptions;
+use datafusion::common::tree_node::{Transformed, TreeNode};
+use datafusion::common::{
+    DFSchema, JoinSide, JoinType, NullEquality, ScalarValue, not_impl_err,
+    substrait_datafusion_err, substrait_err,
+};
 use datafusion::datasource::listing::PartitionedFile;
 use datafusion::datasource::object_store::ObjectStoreUrl;
 use datafusion::datasource::physical_plan::{
-    FileGroup, FileScanConfigBuilder, ParquetSource,
+    CsvSource, FileGroup, FileScanConfigBuilder, FileSource, JsonSource, ParquetSource,
 };
 use datafusion::error::{DataFusionError, Result};
-use datafusion::physical_plan::ExecutionPlan;
+use datafusion::execution::SessionState;
+use datafusion::logical_expr::Expr;
+use datafusion::physical_expr::expressions::Column;
+use datafusion::physical_expr::projection::ProjectionExprs;
+use datafusion::physical_expr::utils::collect_columns;
+use datafusion::physical_expr::{
+    LexOrdering, PhysicalExpr, create_physical_expr, create_physical_sort_exprs,
+};
+use datafusion::physical_plan::aggregates::{
+    AggregateExec, AggregateMode, PhysicalGroupBy,
+};
+use datafusion::physical_plan::coalesce_partitions::CoalescePartitionsExec;
+use datafusion::physical_plan::filter::FilterExecBuilder;
+use datafusion::physical_plan::joins::utils::{ColumnIndex, JoinFilter};
+use datafusion::physical_plan::joins::{HashJoinExec, PartitionMode};
+use datafusion::physical_plan::projection::{ProjectionExec, ProjectionExpr};
+use datafusion::physical_plan::repartition::RepartitionExec;
+use datafusion::physical_plan::sorts::sort::SortExec;
+use datafusion::physical_plan::union::UnionExec;
+use datafusion::physical_plan::{ExecutionPlan, Partitioning};
+use datafusion::physical_planner::create_aggregate_expr_with_name_and_maybe_filter;
 use datafusion::prelude::SessionContext;
 
-use crate::variation_const::{
-    DEFAULT_CONTAINER_TYPE_VARIATION_REF, LARGE_CONTAINER_TYPE_VARIATION_REF,
-    VIEW_CONTAINER_TYPE_VARIATION_REF,
+use crate::extensions::Extensions;
+use crate::logical_plan::consumer::{
+    DefaultSubstraitConsumer, from_substrait_agg_func, from_substrait_rex,
+    from_substrait_sorts, from_substrait_type,
+};
+use crate::physical_plan::{
+    JSON_READ_OPTIONS_TYPE_URL, JsonReadOptions, PhysicalOptions, positional_df_schema,
 };
 use async_recursion::async_recursion;
 use chrono::DateTime;
 use datafusion::datasource::memory::DataSourceExec;
 use object_store::ObjectMeta;
-use substrait::proto::Type;
-use substrait::proto::read_rel::local_files::file_or_files::PathType;
+use prost::Message;
+use substrait::proto::aggregate_function::AggregationInvocation;
+use substrait::proto::comparison_join_key::{SimpleComparisonType, comparison_type};
+use substrait::proto::exchange_rel::ExchangeKind;
+use substrait::proto::expression::field_reference::ReferenceType;
+use substrait::proto::expression::{FieldReference, reference_segment};
+use substrait::proto::fetch_rel::CountMode;
+use substrait::proto::read_rel::local_files::file_or_files::{FileFormat, PathType};
+use substrait::proto::rel_common::EmitKind;
+use substrait::proto::set_rel::SetOp;
 use substrait::proto::r#type::{Kind, Nullability};
+use substrait::proto::{
+    AggregateRel, ExchangeRel, Expression, FetchRel, HashJoinRel, NamedStruct,
+    ProjectRel, ReadRel, RelCommon, Type, hash_join_rel,
+};
 use substrait::proto::{
     Rel, expression::MaskExpression, read_rel::ReadType, rel::RelType,
 };
 
 /// Convert Substrait Rel to DataFusion ExecutionPlan
-#[async_recursion]
+///
+/// `extensions` maps the anchors of the functions used by the plan to their
+/// names.
 pub async fn from_substrait_rel(
-    _ctx: &SessionContext,
+    ctx: &SessionContext,
     rel: &Rel,
-    _extensions: &HashMap<u32, &String>,
+    extensions: &HashMap<u32, &String>,
 ) -> Result<Arc<dyn ExecutionPlan>> {
-    let mut base_config_builder;
+    let extensions = Extensions {
+        functions: extensions
+            .iter()
+            .map(|(anchor, name)| (*anchor, name.to_string()))
+            .collect(),
+        ..Default::default()
+    };
+    let state = ctx.state();
+    let consumer = PhysicalPlanConsumer {
+        consumer: DefaultSubstraitConsumer::new(&extensions, &state),
+        state: &state,
+    };
+    consumer.consume_rel(rel).await
+}
 
-    match &rel.rel_type {
-        Some(RelType::Read(read)) => {
-            if read.filter.is_some() || read.best_effort_filter.is_some() {
-                return not_impl_err!("Read with filter is not supported");
-            }
+/// Converts Substrait [`Rel`]s and their expressions to [`ExecutionPlan`]s.
+///
+/// The expressions are converted to logical expressions over a schema in
+/// which each field is qualified by its index (see [`positional_df_schema`])
+/// and then planned against the input of the relation.
+struct PhysicalPlanConsumer<'a> {
+    consumer: DefaultSubstraitConsumer<'a>,
+    state: &'a SessionState,
+}
 
-            if read.advanced_extension.is_some() {
-                return not_impl_err!("Read with AdvancedExtension is not supported");
+impl PhysicalPlanConsumer<'_> {
+    #[async_recursion]
+    async fn consume_rel(&self, rel: &Rel) -> Result<Arc<dyn ExecutionPlan>> {
+        match &rel.rel_type {
+            Some(RelType::Read(read)) => self.consume_read_rel(read).await,
+            Some(RelType::Project(project)) => self.consume_project_rel(project).await,
+            Some(RelType::Filter(filter)) => {
+                let input = self.consume_rel(required(&filter.input, "Filter")?).await?;
+                let Some(condition) = filter.condition.as_ref() else {
+                    return substrait_err!("Filter without a condition");
+                };
+                let predicate = self.to_physical_expr(condition, &input.schema()).await?;
+                let projection = emit_mapping(filter.common.as_ref())?;
+                let filter = FilterExecBuilder::new(predicate, input)
+                    .apply_projection(projection)?
+                    .build()?;
+                Ok(Arc::new(filter) as Arc<dyn ExecutionPlan>)
+            }
+            Some(RelType::HashJoin(join)) => self.consume_hash_join_rel(join).await,
+            Some(RelType::Sort(sort)) => {
+                let input = self.consume_rel(required(&sort.input, "Sort")?).await?;
+                let df_schema = positional_df_schema(&input.schema())?;
+                let sorts =
+                    from_substrait_sorts(&self.consumer, &sort.sorts, &df_schema).await?;
+                let sort_exprs = create_physical_sort_exprs(
+                    &sorts,
+                    &df_schema,
+                    self.state.execution_props(),
+                )?;
+                let Some(ordering) = LexOrdering::new(sort_exprs) else {
+                    return substrait_err!("Sort without sort fields");
+                };
+                let options = PhysicalOptions::from_advanced_extension(
+                    sort.advanced_extension.as_ref(),
+                )?;
+                let sort = SortExec::new(ordering, input)
+                    .with_preserve_partitioning(options.preserve_partitioning);
+                Ok(Arc::new(sort) as Arc<dyn ExecutionPlan>)
+            }
+            Some(RelType::Fetch(fetch)) => self.consume_fetch_rel(fetch).await,
+            Some(RelType::Aggregate(aggregate)) => {
+                self.consume_aggregate_rel(aggregate).await
             }
+            Some(RelType::Exchange(exchange)) => {
+                self.consume_exchange_rel(exchange).await
+            }
+            Some(RelType::Set(set)) => match SetOp::try_from(set.op) {
+                Ok(SetOp::UnionAll) => {
+                    let mut inputs = Vec::with_capacity(set.inputs.len());
+                    for input in &set.inputs {
+                        inputs.push(self.consume_rel(input).await?);
+                    }
+                    UnionExec::try_new(inputs)
+                }
+                _ => not_impl_err!(
+                    "Only UNION ALL set operations are supported in Substrait physical plans"
+                ),
+            },
+            _ => not_impl_err!("Unsupported Reltype: {:?}", rel.rel_type),
+        }
+    }
 
-            let Some(schema) = read.base_schema.as_ref() else {
-                return substrait_err!("Missing base schema in the read");
-            };
+    async fn consume_read_rel(&self, read: &ReadRel) -> Result<Arc<dyn ExecutionPlan>> {
+        if read.filter.is_some() {
+            return not_impl_err!("Read with filter is not supported");
+        }
 
-            let Some(r#struct) = schema.r#struct.as_ref() else {
-                return substrait_err!("Missing struct in the schema");
-            };
+        let options =
+            PhysicalOptions::from_advanced_extension(read.advanced_extension.as_ref())?;
 
-            match schema
-                .names
-                .iter()
-                .zip(r#struct.types.iter())
-                .map(|(name, r#type)| to_field(name, r#type))
-                .collect::<Result<Vec<Field>>>()
+        let Some(schema) = read.base_schema.as_ref() else {
+            return substrait_err!("Missing base schema in the read");
+        };
+        let schema = Arc::new(self.consume_named_struct(schema)?);
+
+        let Some(ReadType::LocalFiles(files)) = &read.read_type else {
+            return not_impl_err!(
+                "Only LocalFile reads are supported when parsing physical"
+            );
+        };
+
+        // All files of a read share the same format, a read without files
+        // is read as Parquet
+        let file_format = files
+            .items
+            .first()
+            .and_then(|file| file.file_format.as_ref());
+        let source: Arc<dyn FileSource> = match file_format {
+            Some(FileFormat::Parquet(_)) | None => {
+                let mut source = ParquetSource::new(Arc::clone(&schema));
+                if let Some(predicate) = read.best_effort_filter.as_ref() {
+                    let predicate = self.to_physical_expr(predicate, &schema).await?;
+                    source = source.with_predicate(predicate);
+                }
+                Arc::new(source)
+            }
+            Some(_) if read.best_effort_filter.is_some() => {
+                return not_impl_err!(
+                    "Read with a best effort filter is only supported for Parquet"
+                );
+            }
+            Some(FileFormat::Text(text_options)) => {
+                let csv_options = CsvOptions {
+                    has_header: Some(text_options.header_lines_to_skip > 0),
+                    delimiter: single_byte(&text_options.field_delimiter, "delimiter")?
+                        .unwrap_or(b','),
+                    quote: single_byte(&text_options.quote, "quote")?.unwrap_or(b'"'),
+                    escape: single_byte(&text_options.escape, "escape")?,
+                    ..Default::default()
+                };
+                Arc::new(
+                    CsvSource::new(Arc::clone(&schema)).with_csv_options(csv_options),
+                )
+            }
+            Some(FileFormat::Extension(any))
+                if any.type_url == JSON_READ_OPTIONS_TYPE_URL =>
             {
-                Ok(fields) => {
-                    let schema = Arc::new(Schema::new(fields));
-                    let source = Arc::new(ParquetSource::new(Arc::clone(&schema)));
-                    base_config_builder = FileScanConfigBuilder::new(
-                        ObjectStoreUrl::local_filesystem(),
-                        source,
+                let options =
+                    JsonReadOptions::decode(any.value.as_ref()).map_err(|e| {
+                        substrait_datafusion_err!("Failed to decode JsonReadOptions: {e}")
+                    })?;
+                Arc::new(
+                    JsonSource::new(Arc::clone(&schema))
+                        .with_newline_delimited(options.newline_delimited),
+                )
+            }
+            Some(file_format) => {
+                return not_impl_err!(
+                    "Unsupported file format in Substrait physical plan consumer: {file_format:?}"
+                );
+            }
+        };
+        // Expressions projected by the read replace the mask
+        let source = if options.projection.is_empty() {
+            source
+        } else {
+            let names = output_names(read.common.as_ref());
+            let mut exprs = Vec::with_capacity(options.projection.len());
+            for (i, expression) in options.projection.iter().enumerate() {
+                let expr = self.to_physical_expr(expression, &schema).await?;
+                let alias = match names.get(i) {
+                    Some(name) => name.clone(),
+                    None => expr.to_string(),
+                };
+                exprs.push(ProjectionExpr::new(expr, alias));
+            }
+            match source.try_pushdown_projection(&ProjectionExprs::new(exprs))? {
+                Some(source) => source,
+                None => {
+                    return not_impl_err!(
+                        "Projecting expressions is not supported by the {} source",
+                        source.file_type()
                     );
                 }
-                Err(e) => return Err(e),
+            }
+        };
+        let mut base_config_builder =
+            FileScanConfigBuilder::new(ObjectStoreUrl::local_filesystem(), source);
+
+        let file_sizes =
+            PhysicalOptions::from_advanced_extension(files.advanced_extension.as_ref())?
+                .file_sizes;
+        let mut file_groups = vec![];
+        for (i, file) in files.items.iter().enumerate() {
+            if file.file_format != files.items[0].file_format {
+                return not_impl_err!(
+                    "Reads of files with different formats are not supported"
+                );
+            }
+            let path = if let Some(path_type) = &file.path_type {
+                match path_type {
+                    PathType::UriPath(path) => Ok(path.clone()),
+                    PathType::UriPathGlob(path) => Ok(path.clone()),
+                    PathType::UriFile(path) => Ok(path.clone()),
+                    PathType::UriFolder(path) => Ok(path.clone()),
+                }
+            } else {
+                Err(DataFusionError::Substrait("Missing PathType".to_string()))
+            }?;
+
+            // TODO substrait plans do not have `last_modified` or `size` but `ObjectMeta`
+            // requires them both - perhaps we can change the object-store crate
+            // to make these optional? We cannot guarantee that we have access to the
+            // files to get this information, depending on how this library is being
+            // used
+            let last_modified = DateTime::parse_from_str(
+                "1970 Jan 1 00:00:00.000 +0000",
+                "%Y %b %d %H:%M:%S%.3f %z",
+            )
+            .unwrap();
+            let size = file_sizes
+                .get(i)
+                .copied()
+                .unwrap_or(file.start + file.length);
+
+            let mut partitioned_file = PartitionedFile::new_from_meta(ObjectMeta {
+                last_modified: last_modified.into(),
+                location: path.into(),
+                size,
+                e_tag: None,
+                version: None,
+            });
+            if file.start != 0 || file.length != size {
+                partitioned_file = partitioned_file
+                    .with_range(file.start as i64, (file.start + file.length) as i64);
+            }
+
+            let part_index = file.partition_index as usize;
+            while part_index >= file_groups.len() {
+                file_groups.push(FileGroup::default());
+            }
+            file_groups[part_index].push(partitioned_file)
+        }
+
+        base_config_builder = base_config_builder.with_file_groups(file_groups);
+
+        if let Some(MaskExpression { select, .. }) = &read.projection
+            && let Some(projection) = &select.as_ref()
+        {
+            let column_indices: Vec<usize> = projection
+                .struct_items
+                .iter()
+                .map(|item| item.field as usize)
+                .collect();
+            base_config_builder =
+                base_config_builder.with_projection_indices(Some(column_indices))?;
+        }
+
+        Ok(
+            DataSourceExec::from_data_source(base_config_builder.build())
+                as Arc<dyn ExecutionPlan>,
+        )
+    }
+
+    async fn consume_project_rel(
+        &self,
+        project: &ProjectRel,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let input = self
+            .consume_rel(required(&project.input, "Project")?)
+            .await?;
+        let input_schema = input.schema();
+
+        // Only the expressions may be emitted, which is how the producer
+        // encodes a ProjectionExec
+        let input_len = input_schema.fields().len();
+        if let Some(mapping) = emit_mapping(project.common.as_ref())?
+            && !mapping
+                .iter()
+                .copied()
+                .eq(input_len..input_len + project.expressions.len())
+        {
+            return not_impl_err!(
+                "Project emitting its input fields is not supported in Substrait physical plans"
+            );
+        }
+
+        let names = output_names(project.common.as_ref());
+        let mut exprs = Vec::with_capacity(project.expressions.len());
+        for (i, expression) in project.expressions.iter().enumerate() {
+            let expr = self.to_physical_expr(expression, &input_schema).await?;
+            let alias = match names.get(i) {
+                Some(name) => name.clone(),
+                None => expr.to_string(),
             };
+            exprs.push(ProjectionExpr::new(expr, alias));
+        }
+        Ok(Arc::new(ProjectionExec::try_new(exprs, input)?))
+    }
 
-            match &read.as_ref().read_type {
-                Some(ReadType::LocalFiles(files)) => {
-                    let mut file_groups = vec![];
-
-                    for file in &files.items {
-                        let path = if let Some(path_type) = &file.path_type {
-                            match path_type {
-                                PathType::UriPath(path) => Ok(path.clone()),
-                                PathType::UriPathGlob(path) => Ok(path.clone()),
-                                PathType::UriFile(path) => Ok(path.clone()),
-                                PathType::UriFolder(path) => Ok(path.clone()),
-                            }
-                        } else {
-                            Err(DataFusionError::Substrait(
-                                "Missing PathType".to_string(),
-                            ))
-                        }?;
-
-                        // TODO substrait plans do not have `last_modified` or `size` but `ObjectMeta`
-                        // requires them both - perhaps we can change the object-store crate
-                        // to make these optional? We cannot guarantee that we have access to the
-                        // files to get this information, depending on how this library is being
-                        // used
-                        let last_modified = DateTime::parse_from_str(
-                            "1970 Jan 1 00:00:00.000 +0000",
-                            "%Y %b %d %H:%M:%S%.3f %z",
-                        )
-                        .unwrap();
-                        let size = 0;
-
-                        let partitioned_file =
-                            PartitionedFile::new_from_meta(ObjectMeta {
-                                last_modified: last_modified.into(),
-                                location: path.into(),
-                                size,
-                                e_tag: None,
-                                version: None,
-                            });
-
-                        let part_index = file.partition_index as usize;
-                        while part_index >= file_groups.len() {
-                            file_groups.push(FileGroup::default());
+    async fn consume_hash_join_rel(
+        &self,
+        join: &HashJoinRel,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let left = self.consume_rel(required(&join.left, "HashJoin")?).await?;
+        let right = self.consume_rel(required(&join.right, "HashJoin")?).await?;
+        let left_schema = left.schema();
+        let right_schema = right.schema();
+
+        #[expect(deprecated)]
+        if !join.left_keys.is_empty() || !join.right_keys.is_empty() {
+            return not_impl_err!(
+                "HashJoin with left_keys and right_keys is not supported, use keys instead"
+            );
+        }
+        let mut null_equality = None;
+        let mut on = Vec::with_capacity(join.keys.len());
+        for key in &join.keys {
+            let comparison = match key
+                .comparison
+                .as_ref()
+                .and_then(|comparison| comparison.inner_type.as_ref())
+            {
+                Some(comparison_type::InnerType::Simple(simple)) => {
+                    match SimpleComparisonType::try_from(*simple) {
+                        Ok(SimpleComparisonType::Eq) => NullEquality::NullEqualsNothing,
+                        Ok(SimpleComparisonType::IsNotDistinctFrom) => {
+                            NullEquality::NullEqualsNull
+                        }
+                        _ => {
+                            return not_impl_err!(
+                                "Unsupported join key comparison: {simple}"
+                            );
                         }
-                        file_groups[part_index].push(partitioned_file)
                     }
+                }
+                _ => {
+                    return not_impl_err!(
+                        "Only simple join key comparisons are supported"
+                    );
+                }
+            };
+            if null_equality.is_some_and(|null_equality| null_equality != comparison) {
+                return not_impl_err!(
+                    "Join keys with different comparisons are not supported"
+                );
+            }
+            null_equality = Some(comparison);
 
-                    base_config_builder =
-                        base_config_builder.with_file_groups(file_groups);
-
-                    if let Some(MaskExpression { select, .. }) = &read.projection
-                        && let Some(projection) = &select.as_ref()
-                    {
-                        let column_indices: Vec<usize> = projection
-                            .struct_items
-                            .iter()
-                            .map(|item| item.field as usize)
-                            .collect();
-                        base_config_builder = base_config_builder
-                            .with_projection_indices(Some(column_indices))?;
+            let left_index = field_index(key.left.as_ref())?;
+            let right_index = field_index(key.right.as_ref())?;
+            on.push((
+                Arc::new(Column::new(
+                    left_schema.field(left_index).name(),
+                    left_index,
+                )) as Arc<dyn PhysicalExpr>,
+                Arc::new(Column::new(
+                    right_schema.field(right_index).name(),
+                    right_index,
+                )) as Arc<dyn PhysicalExpr>,
+            ));
+        }
+
+        let filter = match join.post_join_filter.as_ref() {
+            Some(post_join_filter) => Some(
+                self.to_join_filter(post_join_filter, &left_schema, &right_schema)
+                    .await?,
+            ),
+            None => None,
+        };
+
+        let join_type = from_substrait_hash_join_type(join.r#type)?;
+        let options =
+            PhysicalOptions::from_advanced_extension(join.advanced_extension.as_ref())?;
+        let partition_mode = match options.partition_mode.as_deref() {
+            Some("Partitioned") => PartitionMode::Partitioned,
+            Some("CollectLeft") | None => PartitionMode::CollectLeft,
+            Some("Auto") => PartitionMode::Auto,
+            Some(mode) => {
+                return substrait_err!("Invalid hash join partition mode {mode}");
+            }
+        };
+        let projection = emit_mapping(join.common.as_ref())?;
+
+        Ok(Arc::new(HashJoinExec::try_new(
+            left,
+            right,
+            on,
+            filter,
+            &join_type,
+            projection,
+            partition_mode,
+            null_equality.unwrap_or(NullEquality::NullEqualsNothing),
+            options.null_aware,
+        )?))
+    }
+
+    /// Converts a post join filter over the fields of the left input followed
+    /// by the fields of the right input to a [`JoinFilter`], whose schema only
+    /// contains the referenced fields
+    async fn to_join_filter(
+        &self,
+        expression: &Expression,
+        left_schema: &SchemaRef,
+        right_schema: &SchemaRef,
+    ) -> Result<JoinFilter> {
+        let combined_schema = Schema::new(
+            left_schema
+                .fields()
+                .iter()
+                .chain(right_schema.fields().iter())
+                .cloned()
+                .collect::<Vec<_>>(),
+        );
+        let expr = self
+            .to_physical_expr(expression, &Arc::new(combined_schema.clone()))
+            .await?;
+
+        let mut indices = collect_columns(&expr)
+            .iter()
+            .map(|column| column.index())
+            .collect::<Vec<_>>();
+        indices.sort_unstable();
+
+        let left_len = left_schema.fields().len();
+        let column_indices = indices
+            .iter()
+            .map(|&index| {
+                if index < left_len {
+                    ColumnIndex {
+                        index,
+                        side: JoinSide::Left,
                     }
+                } else {
+                    ColumnIndex {
+                        index: index - left_len,
+                        side: JoinSide::Right,
+                    }
+                }
+            })
+            .collect();
+        let intermediate_schema = Schema::new(
+            indices
+                .iter()
+                .map(|&index| Arc::clone(&combined_schema.fields()[index]))
+                .collect::<Vec<_>>(),
+        );
+        let expr = expr
+            .transform(|e| {
+                let Some(column) = e.downcast_ref::<Column>() else {
+                    return Ok(Transformed::no(e));
+                };
+                let position = indices.binary_search(&column.index()).map_err(|_| {
+                    substrait_datafusion_err!("Unknown join filter column {column}")
+                })?;
+                Ok(Transformed::yes(
+                    Arc::new(Column::new(column.name(), position)) as _,
+                ))
+            })?
+            .data;
+
+        Ok(JoinFilter::new(
+            expr,
+            column_indices,
+            Arc::new(intermediate_schema),
+        ))
+    }
 
-                    Ok(
-                        DataSourceExec::from_data_source(base_config_builder.build())
-                            as Arc<dyn ExecutionPlan>,
-                    )
+    async fn consume_fetch_rel(
+        &self,
+        fetch: &FetchRel,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        // Only a sort with a fetch (top-k) is produced as a FetchRel
+        let input = required(&fetch.input, "Fetch")?;
+        let (Some(RelType::Sort(_)), None) = (&input.rel_type, &fetch.offset_mode) else {
+            return not_impl_err!(
+                "Only a Fetch over a Sort is supported in Substrait physical plans"
+            );
+        };
+        let count = match &fetch.count_mode {
+            #[expect(deprecated)]
+            Some(CountMode::Count(count)) => Some(*count),
+            Some(CountMode::CountExpr(expr)) => {
+                let empty_schema = DFSchema::empty();
+                match from_substrait_rex(&self.consumer, expr, &empty_schema).await? {
+                    Expr::Literal(ScalarValue::Int64(count), _) => count,
+                    _ => None,
                 }
-                _ => not_impl_err!(
-                    "Only LocalFile reads are supported when parsing physical"
-                ),
             }
-        }
-        _ => not_impl_err!("Unsupported Reltype: {:?}", rel.rel_type),
+            None => None,
+        };
+        let Some(count) = count.filter(|count| *count >= 0) else {
+            return not_impl_err!("Fetch without a constant count is not supported");
+        };
+        let input = self.consume_rel(input).await?;
+        let Some(sort) = input.downcast_ref::<SortExec>() else {
+            return substrait_err!("Expected the input of the Fetch to be a sort");
+        };
+        Ok(Arc::new(sort.with_fetch(Some(count as usize))))
     }
-}
 
-fn to_field(name: &String, r#type: &Type) -> Result<Field> {
-    let Some(kind) = r#type.kind.as_ref() else {
-        return substrait_err!("Missing kind in the type with name {}", name);
-    };
+    async fn consume_aggregate_rel(
+        &self,
+        aggregate: &AggregateRel,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let input = self
+            .consume_rel(required(&aggregate.input, "Aggregate")?)
+            .await?;
+        let input_schema = input.schema();
+        let options = PhysicalOptions::from_advanced_extension(
+            aggregate.advanced_extension.as_ref(),
+        )?;
+        let mode = match options.aggregate_mode.as_deref() {
+            Some("Partial") => AggregateMode::Partial,
+            Some("PartialReduce") => AggregateMode::PartialReduce,
+            Some("Final") => AggregateMode::Final,
+            Some("FinalPartitioned") => AggregateMode::FinalPartitioned,
+            Some("Single") | None => AggregateMode::Single,
+            Some("SinglePartitioned") => AggregateMode::SinglePartitioned,
+            Some(mode) => return substrait_err!("Invalid aggregate mode {mode}"),
+        };
 
-    let mut nullable = false;
-    let data_type = match kind {
-        Kind::Bool(boolean) => {
-            nullable = is_nullable(boolean.nullability);
-            Ok(DataType::Boolean)
-        }
-        Kind::I64(i64) => {
-            nullable = is_nullable(i64.nullability);
-            Ok(DataType::Int64)
+        let grouping_expressions = match aggregate.groupings.as_slice() {
+            [] => vec![],
+            [grouping] =>
+            {
+                #[expect(deprecated)]
+                if grouping.grouping_expressions.is_empty() {
+                    grouping
+                        .expression_references
+                        .iter()
+                        .map(|i| {
+                            aggregate.grouping_expressions.get(*i as usize).ok_or_else(
+                                || {
+                                    substrait_datafusion_err!(
+                                        "Invalid grouping reference {i}"
+                                    )
+                                },
+                            )
+                        })
+                        .collect::<Result<Vec<_>>>()?
+                } else {
+                    grouping.grouping_expressions.iter().collect()
+                }
+            }
+            _ => {
+                return not_impl_err!(
+                    "Grouping sets are not supported in Substrait physical plans"
+                );
+            }
+        };
+
+        let names = output_names(aggregate.common.as_ref());
+        let mut group_by = Vec::with_capacity(grouping_expressions.len());
+        for (i, expression) in grouping_expressions.into_iter().enumerate() {
+            let expr = self.to_physical_expr(expression, &input_schema).await?;
+            let name = match names.get(i) {
+                Some(name) => name.clone(),
+                None => expr.to_string(),
+            };
+            group_by.push((expr, name));
         }
-        Kind::Fp64(fp64) => {
-            nullable = is_nullable(fp64.nullability);
-            Ok(DataType::Float64)
+
+        // The arguments of the aggregate functions refer to the input of the
+        // first phase of the aggregation
+        let aggregate_input_schema = match options.aggregate_input_schema.as_ref() {
+            Some(schema) => Arc::new(self.consume_named_struct(schema)?),
+            None => Arc::clone(&input_schema),
+        };
+        let df_schema = positional_df_schema(&aggregate_input_schema)?;
+        let mut aggr_exprs = Vec::with_capacity(aggregate.measures.len());
+        let mut filter_exprs = Vec::with_capacity(aggregate.measures.len());
+        for (i, measure) in aggregate.measures.iter().enumerate() {
+            let Some(function) = measure.measure.as_ref() else {
+                return substrait_err!("Aggregate measure without a function");
+            };
+            let filter = match measure.filter.as_ref() {
+                Some(filter) => Some(Box::new(
+                    from_substrait_rex(&self.consumer, filter, &df_schema).await?,
+                )),
+                None => None,
+            };
+            let order_by =
+                from_substrait_sorts(&self.consumer, &function.sorts, &df_schema).await?;
+            let distinct = function.invocation == AggregationInvocation::Distinct as i32;
+            let expr = from_substrait_agg_func(
+                &self.consumer,
+                function,
+                &df_schema,
+                filter,
+                order_by,
+                distinct,
+            )
+            .await?;
+            let (aggr_expr, filter, _) =
+                create_aggregate_expr_with_name_and_maybe_filter(
+                    &expr,
+                    names.get(group_by.len() + i).cloned(),
+                    expr.human_display().to_string(),
+                    &df_schema,
+                    &aggregate_input_schema,
+                    self.state.execution_props(),
+                )?;
+            aggr_exprs.push(aggr_expr);
+            filter_exprs.push(filter);
         }
-        Kind::String(string) => {
-            nullable = is_nullable(string.nullability);
-            match string.type_variation_reference {
-                DEFAULT_CONTAINER_TYPE_VARIATION_REF => Ok(DataType::Utf8),
-                LARGE_CONTAINER_TYPE_VARIATION_REF => Ok(DataType::LargeUtf8),
-                VIEW_CONTAINER_TYPE_VARIATION_REF => Ok(DataType::Utf8View),
-                _ => substrait_err!(
-                    "Invalid type variation found for substrait string type class: {}",
-                    string.type_variation_reference
-                ),
+
+        Ok(Arc::new(AggregateExec::try_new(
+            mode,
+            PhysicalGroupBy::new_single(group_by),
+            aggr_exprs,
+            filter_exprs,
+            input,
+            aggregate_input_schema,
+        )?))
+    }
+
+    async fn consume_exchange_rel(
+        &self,
+        exchange: &ExchangeRel,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let input = self
+            .consume_rel(required(&exchange.input, "Exchange")?)
+            .await?;
+        let partition_count = exchange.partition_count as usize;
+        let partitioning = match &exchange.exchange_kind {
+            Some(ExchangeKind::ScatterByFields(scatter)) => {
+                let input_schema = input.schema();
+                let exprs = scatter
+                    .fields
+                    .iter()
+                    .map(|field| {
+                        let index = field_index(Some(field))?;
+                        Ok(
+                            Arc::new(Column::new(input_schema.field(index).name(), index))
+                                as Arc<dyn PhysicalExpr>,
+                        )
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                Partitioning::Hash(exprs, partition_count)
             }
-        }
-        Kind::Binary(binary) => {
-            nullable = is_nullable(binary.nullability);
-            match binary.type_variation_reference {
-                DEFAULT_CONTAINER_TYPE_VARIATION_REF => Ok(DataType::Binary),
-                LARGE_CONTAINER_TYPE_VARIATION_REF => Ok(DataType::LargeBinary),
-                VIEW_CONTAINER_TYPE_VARIATION_REF => Ok(DataType::BinaryView),
-                _ => substrait_err!(
-                    "Invalid type variation found for substrait binary type class: {}",
-                    binary.type_variation_reference
-                ),
+            Some(ExchangeKind::RoundRobin(_)) => {
+                Partitioning::RoundRobinBatch(partition_count)
+            }
+            // A single target merges all partitions of the input
+            Some(ExchangeKind::SingleTarget(_)) if partition_count == 1 => {
+                return Ok(Arc::new(CoalescePartitionsExec::new(input)));
+            }
+            _ => {
+                return not_impl_err!(
+                    "Unsupported exchange kind in Substrait physical plan consumer: {:?}",
+                    exchange.exchange_kind
+                );
             }
+        };
+        let options = PhysicalOptions::from_advanced_extension(
+            exchange.advanced_extension.as_ref(),
+        )?;
+        let mut repartition = RepartitionExec::try_new(input, partitioning)?;
+        if options.preserve_order {
+            repartition = repartition.with_preserve_order();
         }
-        _ => substrait_err!(
-            "Unsupported kind: {:?} in the type with name {}",
-            kind,
-            name
-        ),
-    }?;
-
-    Ok(Field::new(name, data_type, nullable))
+        Ok(Arc::new(repartition))
+    }
+
+    async fn to_physical_expr(
+        &self,
+        expression: &Expression,
+        schema: &SchemaRef,
+    ) -> Result<Arc<dyn PhysicalExpr>> {
+        let df_schema = positional_df_schema(schema)?;
+        let expr = from_substrait_rex(&self.consumer, expression, &df_schema).await?;
+        create_physical_expr(&expr, &df_schema, self.state.execution_props())
+    }
+
+    /// Converts a [`NamedStruct`] to a [`Schema`], keeping the nullability of
+    /// its fields
+    fn consume_named_struct(&self, named_struct: &NamedStruct) -> Result<Schema> {
+        let Some(r#struct) = named_struct.r#struct.as_ref() else {
+            return substrait_err!("Missing struct in the schema");
+        };
+        let names = &named_struct.names;
+        let mut name_idx = 0;
+        let mut fields = Vec::with_capacity(r#struct.types.len());
+        for r#type in &r#struct.types {
+            let Some(name) = names.get(name_idx) else {
+                return substrait_err!("Missing name for field {}", fields.len());
+            };
+            name_idx += 1;
+            let data_type =
+                from_substrait_type(&self.consumer, r#type, names, &mut name_idx)?;
+            fields.push(Field::new(name, data_type, is_nullable_type(r#type)));
+        }
+        if name_idx != names.len() {
+            return substrait_err!(
+                "Names list must match exactly to nested schema, but found {} uses for {} names",
+                name_idx,
+                names.len()
+            );
+        }
+        Ok(Schema::new(fields))
+    }
+}
+
+fn required<'a>(rel: &'a Option<Box<Rel>>, name: &str) -> Result<&'a Rel> {
+    rel.as_deref()
+        .ok_or_else(|| substrait_datafusion_err!("{name} relation without an input"))
+}
+
+fn emit_mapping(common: Option<&RelCommon>) -> Result<Option<Vec<usize>>> {
+    match common.and_then(|common| common.emit_kind.as_ref()) {
+        Some(EmitKind::Emit(emit)) => emit
+            .output_mapping
+            .iter()
+            .map(|i| {
+                usize::try_from(*i)
+                    .map_err(|_| substrait_datafusion_err!("Invalid output mapping {i}"))
+            })
+            .collect::<Result<Vec<_>>>()
+            .map(Some),
+        Some(EmitKind::Direct(_)) | None => Ok(None),
+    }
+}
+
+fn output_names(common: Option<&RelCommon>) -> &[String] {
+    common
+        .and_then(|common| common.hint.as_ref())
+        .map(|hint| hint.output_names.as_slice())
+        .unwrap_or_default()
+}
+
+fn field_index(field_reference: Option<&FieldReference>) -> Result<usize> {
+    if let Some(FieldReference {
+        reference_type: Some(ReferenceType::DirectReference(segment)),
+        ..
+    }) = field_reference
+        && let Some(reference_segment::ReferenceType::StructField(field)) =
+            segment.reference_type.as_ref()
+        && field.child.is_none()
+    {
+        return Ok(field.field as usize);
+    }
+    not_impl_err!("Only direct references to top-level fields are supported")
+}
+
+fn single_byte(value: &str, name: &str) -> Result<Option<u8>> {
+    match value.as_bytes() {
+        [] => Ok(None),
+        [byte] => Ok(Some(*byte)),
+        _ => not_impl_err!("CSV {name} must be a single byte, got {value}"),
+    }
+}
+
+fn from_substrait_hash_join_type(join_type: i32) -> Result<JoinType> {
+    let Ok(join_type) = hash_join_rel::JoinType::try_from(join_type) else {
+        return substrait_err!("Invalid hash join type {join_type}");
+    };
+    match join_type {
+        hash_join_rel::JoinType::Inner => Ok(JoinType::Inner),
+        hash_join_rel::JoinType::Left => Ok(JoinType::Left),
+        hash_join_rel::JoinType::Right => Ok(JoinType::Right),
+        hash_join_rel::JoinType::Outer => Ok(JoinType::Full),
+        hash_join_rel::JoinType::LeftSemi => Ok(JoinType::LeftSemi),
+        hash_join_rel::JoinType::RightSemi => Ok(JoinType::RightSemi),
+        hash_join_rel::JoinType::LeftAnti => Ok(JoinType::LeftAnti),
+        hash_join_rel::JoinType::RightAnti => Ok(JoinType::RightAnti),
+        hash_join_rel::JoinType::LeftMark => Ok(JoinType::LeftMark),
+        hash_join_rel::JoinType::RightMark => Ok(JoinType::RightMark),
+        _ => not_impl_err!("Unsupported hash join type {}", join_type.as_str_name()),
+    }
+}
+
+fn is_nullable_type(r#type: &Type) -> bool {
+    let nullability = match &r#type.kind {
+        Some(Kind::Bool(t)) => t.nullability,
+        Some(Kind::I8(t)) => t.nullability,
+        Some(Kind::I16(t)) => t.nullability,
+        Some(Kind::I32(t)) => t.nullability,
+        Some(Kind::I64(t)) => t.nullability,
+        Some(Kind::Fp32(t)) => t.nullability,
+        Some(Kind::Fp64(t)) => t.nullability,
+        Some(Kind::String(t)) => t.nullability,
+        Some(Kind::Binary(t)) => t.nullability,
+        #[expect(deprecated)]
+        Some(Kind::Timestamp(t)) => t.nullability,
+        Some(Kind::Date(t)) => t.nullability,
+        #[expect(deprecated)]
+        Some(Kind::Time(t)) => t.nullability,
+        Some(Kind::IntervalYear(t)) => t.nullability,
+        Some(Kind::IntervalDay(t)) => t.nullability,
+        Some(Kind::IntervalCompound(t)) => t.nullability,
+        #[expect(deprecated)]
+        Some(Kind::TimestampTz(t)) => t.nullability,
+        Some(Kind::Uuid(t)) => t.nullability,
+        Some(Kind::FixedChar(t)) => t.nullability,
+        Some(Kind::Varchar(t)) => t.nullability,
+        Some(Kind::FixedBinary(t)) => t.nullability,
+        Some(Kind::Decimal(t)) => t.nullability,
+        Some(Kind::PrecisionTime(t)) => t.nullability,
+        Some(Kind::PrecisionTimestamp(t)) => t.nullability,
+        Some(Kind::PrecisionTimestampTz(t)) => t.nullability,
+        Some(Kind::Struct(t)) => t.nullability,
+        Some(Kind::List(t)) => t.nullability,
+        Some(Kind::Map(t)) => t.nullability,
+        Some(Kind::Func(t)) => t.nullability,
+        Some(Kind::UserDefined(t)) => t.nullability,
+        _ => return true,
+    };
+    is_nullable(nullability)
 }
 
 fn is_nullable(nullability: i32) -> bool {
@@ -15,5 +15,128 @@
 // specific language governing permissions and limitations
 // under the License.
 
+//! Conversion between DataFusion [`ExecutionPlan`]s and Substrait [`Rel`]s.
+//!
+//! Expressions are converted with the same machinery as the logical plans,
+//! referring to the fields of the input by position. Properties of the
+//! operators that Substrait cannot express, such as the partition mode of a
+//! hash join, are carried as a [`PhysicalOptions`] enhancement in the
+//! [`AdvancedExtension`] of the relation. They change the plan, so consumers
+//! must not ignore them.
+//!
+//! [`ExecutionPlan`]: datafusion::physical_plan::ExecutionPlan
+//! [`Rel`]: substrait::proto::Rel
+
+use std::sync::Arc;
+
+use datafusion::arrow::datatypes::Schema;
+use datafusion::common::{
+    Column, DFSchema, Result, TableReference, substrait_datafusion_err, substrait_err,
+};
+use prost::Message;
+use substrait::proto::extensions::AdvancedExtension;
+
 pub mod consumer;
 pub mod producer;
+
+/// Type URL of the [`PhysicalOptions`] enhancement of an [`AdvancedExtension`]
+pub const PHYSICAL_OPTIONS_TYPE_URL: &str = "datafusion.substrait.PhysicalOptions";
+
+/// Type URL of [`JsonReadOptions`] in the extension file format of a read
+pub const JSON_READ_OPTIONS_TYPE_URL: &str = "datafusion.substrait.JsonReadOptions";
+
+/// Properties of DataFusion physical operators that have no equivalent in
+/// Substrait. Only the fields relevant to the encoded operator are set.
+#[derive(Clone, PartialEq, Message)]
+pub struct PhysicalOptions {
+    /// [`PartitionMode`](datafusion::physical_plan::joins::PartitionMode) of a
+    /// hash join
+    #[prost(string, optional, tag = "1")]
+    pub partition_mode: Option<String>,
+    /// [`AggregateMode`](datafusion::physical_plan::aggregates::AggregateMode)
+    /// of an aggregation
+    #[prost(string, optional, tag = "2")]
+    pub aggregate_mode: Option<String>,
+    /// Whether a sort preserves the partitioning of its input
+    #[prost(bool, tag = "3")]
+    pub preserve_partitioning: bool,
+    /// Whether a repartition preserves the order of its input
+    #[prost(bool, tag = "4")]
+    pub preserve_order: bool,
+    /// Schema of the input of the first phase of an aggregation, which the
+    /// arguments of the aggregate functions refer to
+    #[prost(message, optional, tag = "5")]
+    pub aggregate_input_schema: Option<substrait::proto::NamedStruct>,
+    /// Sizes of the files of a read, needed to tell a byte range that starts
+    /// at the beginning of a file from the whole file
+    #[prost(uint64, repeated, tag = "6")]
+    pub file_sizes: Vec<u64>,
+    /// Expressions over the base schema that a read projects, instead of
+    /// only selecting fields. Their names are the output names of the read.
+    #[prost(message, repeated, tag = "7")]
+    pub projection: Vec<substrait::proto::Expression>,
+    /// Whether a hash anti join is null-aware, as needed for `NOT IN`
+    #[prost(bool, tag = "8")]
+    pub null_aware: bool,
+}
+
+impl PhysicalOptions {
+    pub(crate) fn to_advanced_extension(&self) -> AdvancedExtension {
+        AdvancedExtension {
+            optimization: vec![],
+            enhancement: Some(pbjson_types::Any {
+                type_url: PHYSICAL_OPTIONS_TYPE_URL.to_string(),
+                value: self.encode_to_vec().into(),
+            }),
+        }
+    }
+
+    pub(crate) fn from_advanced_extension(
+        extension: Option<&AdvancedExtension>,
+    ) -> Result<Self> {
+        // Optimizations may be ignored
+        match extension.and_then(|extension| extension.enhancement.as_ref()) {
+            Some(any) if any.type_url == PHYSICAL_OPTIONS_TYPE_URL => {
+                Self::decode(any.value.as_ref()).map_err(|e| {
+                    substrait_datafusion_err!("Failed to decode PhysicalOptions: {e}")
+                })
+            }
+            Some(any) => substrait_err!(
+                "Unsupported enhancement in Substrait physical plan: {}",
+                any.type_url
+            ),
+            None => Ok(Self::default()),
+        }
+    }
+}
+
+/// Options of a newline-delimited or array JSON read
+#[derive(Clone, PartialEq, Message)]
+pub struct JsonReadOptions {
+    /// Whether the files contain newline-delimited JSON
+    #[prost(bool, tag = "1")]
+    pub newline_delimited: bool,
+}
+
+/// Returns a [`DFSchema`] for `schema` in which every field is qualified by
+/// its index, so that positional columns can be resolved even when several
+/// fields share the same name.
+pub(crate) fn positional_df_schema(schema: &Schema) -> Result<DFSchema> {
+    let qualified_fields = schema
+        .fields()
+        .iter()
+        .enumerate()
+        .map(|(index, field)| (Some(positional_qualifier(index)), Arc::clone(field)))
+        .collect();
+    DFSchema::new_with_metadata(qualified_fields, schema.metadata().clone())
+}
+
+/// Returns the logical [`Column`] referring to the field at `index` of a
+/// schema created by [`positional_df_schema`]
+pub(crate) fn positional_column(index: usize, name: &str) -> Column {
+    Column::new(Some(positional_qualifier(index)), name)
+}
+
+fn positional_qualifier(index: usize) -> TableReference {
+    TableReference::bare(index.to_string())
+}
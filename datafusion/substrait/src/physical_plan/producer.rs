@@ -16,191 +16,955 @@
 // under the License.
 
 use std::collections::HashMap;
+use std::sync::Arc;
 
-use crate::variation_const::{
-    DEFAULT_CONTAINER_TYPE_VARIATION_REF, LARGE_CONTAINER_TYPE_VARIATION_REF,
-    VIEW_CONTAINER_TYPE_VARIATION_REF,
+use crate::extensions::Extensions;
+use crate::logical_plan::producer::{
+    SubstraitProducer, from_aggregate_function, substrait_sort_field,
+    to_substrait_named_struct, to_substrait_rex,
+};
+use crate::physical_plan::{
+    JSON_READ_OPTIONS_TYPE_URL, JsonReadOptions, PhysicalOptions, positional_column,
+    positional_df_schema,
 };
 
-use datafusion::arrow::datatypes::DataType;
+use datafusion::arrow::datatypes::{Schema, SchemaRef};
+use datafusion::common::tree_node::{Transformed, TreeNode};
+use datafusion::common::{
+    DFSchema, DFSchemaRef, JoinSide, JoinType, NullEquality, not_impl_err,
+};
+use datafusion::datasource::physical_plan::{
+    CsvSource, FileScanConfig, FileSource, JsonSource, ParquetSource,
+};
 use datafusion::datasource::source::DataSourceExec;
 use datafusion::error::{DataFusionError, Result};
-use datafusion::physical_plan::{ExecutionPlan, displayable};
+use datafusion::logical_expr::expr::{self, AggregateFunctionParams};
+use datafusion::logical_expr::{Cast, Expr, Like, SortExpr, TryCast, lit};
+use datafusion::physical_expr::aggregate::AggregateFunctionExpr;
+use datafusion::physical_expr::expressions::{
+    BinaryExpr, CaseExpr, CastExpr, Column, DynamicFilterPhysicalExpr, InListExpr,
+    IsNotNullExpr, IsNullExpr, LikeExpr, Literal, NegativeExpr, NotExpr, TryCastExpr,
+};
+use datafusion::physical_expr::{
+    PhysicalExpr, PhysicalSortExpr, ScalarFunctionExpr, conjunction_opt,
+    split_conjunction,
+};
+use datafusion::physical_plan::aggregates::{AggregateExec, AggregateMode};
+use datafusion::physical_plan::coalesce_partitions::CoalescePartitionsExec;
+use datafusion::physical_plan::filter::FilterExec;
+use datafusion::physical_plan::joins::HashJoinExec;
+use datafusion::physical_plan::projection::ProjectionExec;
+use datafusion::physical_plan::repartition::RepartitionExec;
+use datafusion::physical_plan::sorts::sort::SortExec;
+use datafusion::physical_plan::union::UnionExec;
+use datafusion::physical_plan::{ExecutionPlan, Partitioning, displayable};
 
-use datafusion::datasource::physical_plan::ParquetSource;
-use substrait::proto::ReadRel;
-use substrait::proto::Rel;
-use substrait::proto::expression::MaskExpression;
+use substrait::proto::aggregate_rel::{Grouping, Measure};
+use substrait::proto::comparison_join_key::{
+    ComparisonType, SimpleComparisonType, comparison_type,
+};
+use substrait::proto::exchange_rel::{
+    ExchangeKind, RoundRobin, ScatterFields, SingleBucketExpression,
+};
+use substrait::proto::expression::field_reference::RootReference;
+use substrait::proto::expression::field_reference::{ReferenceType, RootType};
 use substrait::proto::expression::mask_expression::{StructItem, StructSelect};
-use substrait::proto::read_rel::LocalFiles;
-use substrait::proto::read_rel::ReadType;
+use substrait::proto::expression::{
+    FieldReference, MaskExpression, ReferenceSegment, reference_segment,
+};
+use substrait::proto::extensions::SimpleExtensionDeclaration;
+use substrait::proto::extensions::simple_extension_declaration::MappingType;
+use substrait::proto::fetch_rel::CountMode;
 use substrait::proto::read_rel::local_files::FileOrFiles;
-use substrait::proto::read_rel::local_files::file_or_files::ParquetReadOptions;
-use substrait::proto::read_rel::local_files::file_or_files::{FileFormat, PathType};
+use substrait::proto::read_rel::local_files::file_or_files::{
+    DelimiterSeparatedTextReadOptions, FileFormat, ParquetReadOptions, PathType,
+};
+use substrait::proto::read_rel::{LocalFiles, ReadType};
 use substrait::proto::rel::RelType;
-use substrait::proto::r#type::{
-    Binary, Boolean, Fp64, I64, Kind, Nullability, String as SubstraitString, Struct,
+use substrait::proto::rel_common::{Emit, EmitKind, Hint};
+use substrait::proto::set_rel::SetOp;
+use substrait::proto::{
+    AggregateRel, AggregationPhase, ComparisonJoinKey, ExchangeRel, Expression, FetchRel,
+    FilterRel, HashJoinRel, ProjectRel, ReadRel, Rel, RelCommon, SetRel, SortRel,
+    hash_join_rel,
 };
-use substrait::proto::{NamedStruct, Type, extensions};
 
 /// Convert DataFusion ExecutionPlan to Substrait Rel
+///
+/// Functions and types used by the plan are registered in `extension_info`,
+/// the declarations of which must be passed along with the plan.
 pub fn to_substrait_rel(
     plan: &dyn ExecutionPlan,
-    _extension_info: &mut (
-        Vec<extensions::SimpleExtensionDeclaration>,
-        HashMap<String, u32>,
-    ),
+    extension_info: &mut (Vec<SimpleExtensionDeclaration>, HashMap<String, u32>),
 ) -> Result<Box<Rel>> {
-    if let Some(data_source_exec) = plan.downcast_ref::<DataSourceExec>()
-        && let Some((file_config, _)) =
-            data_source_exec.downcast_to_file_source::<ParquetSource>()
-    {
+    let mut producer = PhysicalPlanProducer::try_new(&extension_info.0)?;
+    let rel = producer.handle_plan(plan)?;
+    producer.register_new_extensions(extension_info);
+    Ok(rel)
+}
+
+/// Converts [`ExecutionPlan`]s and their [`PhysicalExpr`]s to Substrait.
+///
+/// The expressions are converted to logical expressions over a schema in
+/// which each field is qualified by its index (see [`positional_df_schema`]),
+/// so that the logical producer resolves them to the same field references.
+struct PhysicalPlanProducer {
+    extensions: Extensions,
+    num_functions: usize,
+    num_types: usize,
+}
+
+impl SubstraitProducer for PhysicalPlanProducer {
+    fn register_function(&mut self, fn_name: String) -> u32 {
+        self.extensions.register_function(&fn_name)
+    }
+
+    fn register_type(&mut self, type_name: String) -> u32 {
+        self.extensions.register_type(&type_name)
+    }
+
+    fn get_extensions(self) -> Extensions {
+        self.extensions
+    }
+}
+
+impl PhysicalPlanProducer {
+    fn try_new(declarations: &Vec<SimpleExtensionDeclaration>) -> Result<Self> {
+        let extensions = Extensions::try_from(declarations)?;
+        Ok(Self {
+            num_functions: extensions.functions.len(),
+            num_types: extensions.types.len(),
+            extensions,
+        })
+    }
+
+    /// Appends the declarations of the functions and types registered while
+    /// producing the plan to `extension_info`
+    fn register_new_extensions(
+        self,
+        extension_info: &mut (Vec<SimpleExtensionDeclaration>, HashMap<String, u32>),
+    ) {
+        let Extensions {
+            functions, types, ..
+        } = self.extensions;
+        let new_extensions = Extensions {
+            functions: functions
+                .into_iter()
+                .filter(|(anchor, _)| *anchor as usize >= self.num_functions)
+                .collect(),
+            types: types
+                .into_iter()
+                .filter(|(anchor, _)| *anchor as usize >= self.num_types)
+                .collect(),
+            ..Default::default()
+        };
+        for declaration in Vec::<SimpleExtensionDeclaration>::from(new_extensions) {
+            if let Some(MappingType::ExtensionFunction(function)) =
+                &declaration.mapping_type
+            {
+                extension_info
+                    .1
+                    .insert(function.name.clone(), function.function_anchor);
+            }
+            extension_info.0.push(declaration);
+        }
+    }
+
+    fn handle_plan(&mut self, plan: &dyn ExecutionPlan) -> Result<Box<Rel>> {
+        if let Some(data_source_exec) = plan.downcast_ref::<DataSourceExec>()
+            && let Some(file_config) = data_source_exec
+                .data_source()
+                .downcast_ref::<FileScanConfig>()
+        {
+            return self.handle_file_scan(file_config);
+        }
+        if let Some(projection) = plan.downcast_ref::<ProjectionExec>() {
+            return self.handle_projection(projection);
+        }
+        if let Some(filter) = plan.downcast_ref::<FilterExec>() {
+            return self.handle_filter(filter);
+        }
+        if let Some(join) = plan.downcast_ref::<HashJoinExec>() {
+            return self.handle_hash_join(join);
+        }
+        if let Some(sort) = plan.downcast_ref::<SortExec>() {
+            return self.handle_sort(sort);
+        }
+        if let Some(aggregate) = plan.downcast_ref::<AggregateExec>() {
+            return self.handle_aggregate(aggregate);
+        }
+        if let Some(repartition) = plan.downcast_ref::<RepartitionExec>() {
+            return self.handle_repartition(repartition);
+        }
+        if let Some(coalesce) = plan.downcast_ref::<CoalescePartitionsExec>() {
+            return self.handle_coalesce_partitions(coalesce);
+        }
+        if let Some(union) = plan.downcast_ref::<UnionExec>() {
+            return self.handle_union(union);
+        }
+        Err(DataFusionError::Substrait(format!(
+            "Unsupported plan in Substrait physical plan producer: {}",
+            displayable(plan).one_line()
+        )))
+    }
+
+    fn handle_file_scan(&mut self, file_config: &FileScanConfig) -> Result<Box<Rel>> {
+        if !file_config.table_partition_cols().is_empty() {
+            return not_impl_err!(
+                "Reads with partition columns are not supported in Substrait physical plans"
+            );
+        }
+        if file_config.limit.is_some() {
+            return not_impl_err!(
+                "Reads with a limit are not supported in Substrait physical plans"
+            );
+        }
+        if !file_config.output_ordering.is_empty() {
+            return not_impl_err!(
+                "Reads with an output ordering are not supported in Substrait physical plans"
+            );
+        }
+        if file_config.file_compression_type.is_compressed() {
+            return not_impl_err!(
+                "Reads of compressed files are not supported in Substrait physical plans"
+            );
+        }
+
+        let file_source = file_config.file_source();
+        let mut best_effort_filter = None;
+        let file_format = if let Some(parquet) =
+            file_source.downcast_ref::<ParquetSource>()
+        {
+            // Dynamic filters are filled in at runtime by the operator that
+            // created them, which is not part of the Substrait plan
+            let predicate = parquet.filter().map(|predicate| {
+                split_conjunction(&predicate)
+                    .into_iter()
+                    .filter(|e| e.downcast_ref::<DynamicFilterPhysicalExpr>().is_none())
+                    .cloned()
+                    .collect::<Vec<_>>()
+            });
+            if let Some(predicate) = predicate.and_then(conjunction_opt) {
+                best_effort_filter = Some(Box::new(
+                    self.handle_rex(&predicate, file_config.file_schema())?,
+                ));
+            }
+            FileFormat::Parquet(ParquetReadOptions {})
+        } else if let Some(csv) = file_source.downcast_ref::<CsvSource>() {
+            if csv.terminator().is_some()
+                || csv.comment().is_some()
+                || csv.newlines_in_values()
+                || csv.truncate_rows()
+            {
+                return not_impl_err!(
+                    "CSV reads with a terminator, comment, newlines in values or truncated rows are not supported in Substrait physical plans"
+                );
+            }
+            FileFormat::Text(DelimiterSeparatedTextReadOptions {
+                field_delimiter: char::from(csv.delimiter()).to_string(),
+                max_line_size: 0,
+                quote: char::from(csv.quote()).to_string(),
+                header_lines_to_skip: csv.has_header() as u64,
+                escape: csv
+                    .escape()
+                    .map(|escape| char::from(escape).to_string())
+                    .unwrap_or_default(),
+                value_treated_as_null: None,
+            })
+        } else if let Some(json) = file_source.downcast_ref::<JsonSource>() {
+            let options = JsonReadOptions {
+                newline_delimited: json.newline_delimited(),
+            };
+            FileFormat::Extension(pbjson_types::Any {
+                type_url: JSON_READ_OPTIONS_TYPE_URL.to_string(),
+                value: prost::Message::encode_to_vec(&options).into(),
+            })
+        } else {
+            return not_impl_err!(
+                "Unsupported file source in Substrait physical plan producer: {}",
+                file_source.file_type()
+            );
+        };
+
         let mut substrait_files = vec![];
+        let mut file_sizes = vec![];
+        let mut has_ranges = false;
         for (partition_index, files) in file_config.file_groups.iter().enumerate() {
             for file in files.iter() {
+                file_sizes.push(file.object_meta.size);
+                has_ranges |= file.range.is_some();
+                let (start, length) = match &file.range {
+                    Some(range) => (range.start as u64, (range.end - range.start) as u64),
+                    None => (0, file.object_meta.size),
+                };
                 substrait_files.push(FileOrFiles {
                     partition_index: partition_index.try_into().unwrap(),
-                    start: 0,
-                    length: file.object_meta.size,
+                    start,
+                    length,
                     path_type: Some(PathType::UriPath(
                         file.object_meta.location.as_ref().to_string(),
                     )),
-                    file_format: Some(FileFormat::Parquet(ParquetReadOptions {})),
+                    file_format: Some(file_format.clone()),
                 });
             }
         }
 
-        let mut names = vec![];
-        let mut types = vec![];
-
-        for field in file_config.file_schema().fields.iter() {
-            match to_substrait_type(field.data_type(), field.is_nullable()) {
-                Ok(t) => {
-                    names.push(field.name().clone());
-                    types.push(t);
-                }
-                Err(e) => return Err(e),
-            }
-        }
-
-        let type_info = Struct {
-            types,
+        let base_schema = Arc::new(positional_df_schema(file_config.file_schema())?);
+        let mut base_schema = to_substrait_named_struct(self, &base_schema)?;
+        if let Some(r#struct) = base_schema.r#struct.as_mut() {
             // FIXME: duckdb doesn't set this field, keep it as default variant 0.
             // https://github.com/duckdb/substrait/blob/b6f56643cb11d52de0e32c24a01dfd5947df62be/src/to_substrait.cpp#L1106-L1127
-            type_variation_reference: 0,
-            nullability: Nullability::Required.into(),
-        };
+            r#struct.type_variation_reference = 0;
+        }
 
+        // A projection of unrenamed columns is a mask, any other projection
+        // is carried as expressions over the base schema
         let mut select_struct = None;
-        if let Some(projection) = file_config.file_source().projection().as_ref() {
-            let struct_items = projection
-                .column_indices()
-                .into_iter()
-                .map(|index| StructItem {
-                    field: index as i32,
-                    // FIXME: duckdb sets this to None, but it's not clear why.
-                    // https://github.com/duckdb/substrait/blob/b6f56643cb11d52de0e32c24a01dfd5947df62be/src/to_substrait.cpp#L1191
-                    child: None,
+        let mut common = None;
+        let mut advanced_extension = None;
+        if let Some(projection) = file_source.projection() {
+            let columns = projection
+                .iter()
+                .map(|projection_expr| {
+                    projection_expr
+                        .expr
+                        .downcast_ref::<Column>()
+                        .filter(|column| column.name() == projection_expr.alias)
                 })
-                .collect();
-
-            select_struct = Some(StructSelect { struct_items });
+                .collect::<Option<Vec<_>>>();
+            match columns {
+                Some(columns) => {
+                    let struct_items = columns
+                        .iter()
+                        .map(|column| StructItem {
+                            field: column.index() as i32,
+                            // FIXME: duckdb sets this to None, but it's not clear why.
+                            // https://github.com/duckdb/substrait/blob/b6f56643cb11d52de0e32c24a01dfd5947df62be/src/to_substrait.cpp#L1191
+                            child: None,
+                        })
+                        .collect();
+                    select_struct = Some(StructSelect { struct_items });
+                }
+                None => {
+                    let options = PhysicalOptions {
+                        projection: projection
+                            .iter()
+                            .map(|e| self.handle_rex(&e.expr, file_config.file_schema()))
+                            .collect::<Result<_>>()?,
+                        ..Default::default()
+                    };
+                    advanced_extension = Some(options.to_advanced_extension());
+                    let output_names = projection.iter().map(|e| e.alias.clone());
+                    common = Some(rel_common(None, output_names.collect()));
+                }
+            }
         }
 
-        return Ok(Box::new(Rel {
+        Ok(Box::new(Rel {
             rel_type: Some(RelType::Read(Box::new(ReadRel {
-                common: None,
-                base_schema: Some(NamedStruct {
-                    names,
-                    r#struct: Some(type_info),
-                }),
+                common,
+                base_schema: Some(base_schema),
                 filter: None,
-                best_effort_filter: None,
+                best_effort_filter,
                 projection: Some(MaskExpression {
                     select: select_struct,
                     // FIXME: duckdb set this to true, but it's not clear why.
                     // https://github.com/duckdb/substrait/blob/b6f56643cb11d52de0e32c24a01dfd5947df62be/src/to_substrait.cpp#L1186.
                     maintain_singular_struct: true,
                 }),
-                advanced_extension: None,
+                advanced_extension,
                 read_type: Some(ReadType::LocalFiles(LocalFiles {
                     items: substrait_files,
-                    advanced_extension: None,
+                    advanced_extension: has_ranges.then(|| {
+                        PhysicalOptions {
+                            file_sizes,
+                            ..Default::default()
+                        }
+                        .to_advanced_extension()
+                    }),
                 })),
             }))),
-        }));
+        }))
+    }
+
+    fn handle_projection(&mut self, projection: &ProjectionExec) -> Result<Box<Rel>> {
+        let input_schema = projection.input().schema();
+        let df_schema = Arc::new(positional_df_schema(&input_schema)?);
+        let expressions = projection
+            .expr()
+            .iter()
+            .map(|e| self.handle_rex_with_df_schema(&e.expr, &df_schema))
+            .collect::<Result<Vec<_>>>()?;
+        let output_names = projection.expr().iter().map(|e| e.alias.clone()).collect();
+
+        // The direct output of a ProjectRel is its input followed by the
+        // expressions, only the latter are emitted
+        let input_len = input_schema.fields().len();
+        let output_mapping = (input_len..input_len + expressions.len())
+            .map(|i| i as i32)
+            .collect();
+
+        Ok(Box::new(Rel {
+            rel_type: Some(RelType::Project(Box::new(ProjectRel {
+                common: Some(rel_common(Some(output_mapping), output_names)),
+                input: Some(self.handle_plan(projection.input().as_ref())?),
+                expressions,
+                advanced_extension: None,
+            }))),
+        }))
+    }
+
+    fn handle_filter(&mut self, filter: &FilterExec) -> Result<Box<Rel>> {
+        if filter.fetch().is_some() {
+            return not_impl_err!(
+                "Filters with a fetch are not supported in Substrait physical plans"
+            );
+        }
+        let condition = self.handle_rex(filter.predicate(), &filter.input().schema())?;
+        let output_mapping = filter
+            .projection()
+            .as_ref()
+            .map(|projection| projection.iter().map(|i| *i as i32).collect::<Vec<_>>());
+
+        Ok(Box::new(Rel {
+            rel_type: Some(RelType::Filter(Box::new(FilterRel {
+                common: Some(rel_common(output_mapping, vec![])),
+                input: Some(self.handle_plan(filter.input().as_ref())?),
+                condition: Some(Box::new(condition)),
+                advanced_extension: None,
+            }))),
+        }))
+    }
+
+    fn handle_hash_join(&mut self, join: &HashJoinExec) -> Result<Box<Rel>> {
+        if join.fetch().is_some() {
+            return not_impl_err!(
+                "Hash joins with a fetch are not supported in Substrait physical plans"
+            );
+        }
+
+        let comparison = match join.null_equality() {
+            NullEquality::NullEqualsNothing => SimpleComparisonType::Eq,
+            NullEquality::NullEqualsNull => SimpleComparisonType::IsNotDistinctFrom,
+        };
+        let keys = join
+            .on()
+            .iter()
+            .map(|(left, right)| {
+                let (Some(left), Some(right)) = (
+                    left.downcast_ref::<Column>(),
+                    right.downcast_ref::<Column>(),
+                ) else {
+                    return not_impl_err!(
+                        "Hash joins on expressions are not supported in Substrait physical plans"
+                    );
+                };
+                Ok(ComparisonJoinKey {
+                    left: Some(field_reference(left.index())),
+                    right: Some(field_reference(right.index())),
+                    comparison: Some(ComparisonType {
+                        inner_type: Some(comparison_type::InnerType::Simple(
+                            comparison as i32,
+                        )),
+                    }),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // The post join filter refers to the fields of the left input followed
+        // by the fields of the right input
+        let left_schema = join.left().schema();
+        let right_schema = join.right().schema();
+        let post_join_filter = match join.filter() {
+            Some(filter) => {
+                let combined_schema = Schema::new(
+                    left_schema
+                        .fields()
+                        .iter()
+                        .chain(right_schema.fields().iter())
+                        .cloned()
+                        .collect::<Vec<_>>(),
+                );
+                let left_len = left_schema.fields().len();
+                let mut expr = to_logical_expr(filter.expression())?;
+                expr = expr
+                    .transform(|e| {
+                        let Expr::Column(column) = &e else {
+                            return Ok(Transformed::no(e));
+                        };
+                        let Some(index) = column
+                            .relation
+                            .as_ref()
+                            .and_then(|r| r.table().parse::<usize>().ok())
+                        else {
+                            return Ok(Transformed::no(e));
+                        };
+                        let column_index = &filter.column_indices()[index];
+                        let index = match column_index.side {
+                            JoinSide::Left => column_index.index,
+                            JoinSide::Right => left_len + column_index.index,
+                            JoinSide::None => {
+                                return not_impl_err!(
+                                    "Join filters on the mark column are not supported in Substrait physical plans"
+                                );
+                            }
+                        };
+                        Ok(Transformed::yes(Expr::Column(positional_column(
+                            index,
+                            combined_schema.field(index).name(),
+                        ))))
+                    })?
+                    .data;
+                let df_schema = Arc::new(positional_df_schema(&combined_schema)?);
+                Some(Box::new(to_substrait_rex(self, &expr, &df_schema)?))
+            }
+            None => None,
+        };
+
+        let output_mapping = join
+            .projection
+            .as_ref()
+            .map(|projection| projection.iter().map(|i| *i as i32).collect::<Vec<_>>());
+        let options = PhysicalOptions {
+            partition_mode: Some(format!("{:?}", join.partition_mode())),
+            null_aware: join.null_aware,
+            ..Default::default()
+        };
+
+        #[expect(deprecated)]
+        Ok(Box::new(Rel {
+            rel_type: Some(RelType::HashJoin(Box::new(HashJoinRel {
+                common: Some(rel_common(output_mapping, vec![])),
+                left: Some(self.handle_plan(join.left().as_ref())?),
+                right: Some(self.handle_plan(join.right().as_ref())?),
+                left_keys: vec![],
+                right_keys: vec![],
+                keys,
+                post_join_filter,
+                r#type: to_substrait_hash_join_type(join.join_type())? as i32,
+                build_input: hash_join_rel::BuildInput::Left as i32,
+                advanced_extension: Some(options.to_advanced_extension()),
+            }))),
+        }))
+    }
+
+    fn handle_sort(&mut self, sort: &SortExec) -> Result<Box<Rel>> {
+        let input_schema = sort.input().schema();
+        let df_schema = Arc::new(positional_df_schema(&input_schema)?);
+        let sorts = sort
+            .expr()
+            .iter()
+            .map(|sort_expr| {
+                let sort_expr = to_logical_sort_expr(sort_expr)?;
+                substrait_sort_field(self, &sort_expr, &df_schema)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let options = PhysicalOptions {
+            preserve_partitioning: sort.preserve_partitioning(),
+            ..Default::default()
+        };
+
+        let sort_rel = Box::new(Rel {
+            rel_type: Some(RelType::Sort(Box::new(SortRel {
+                common: None,
+                input: Some(self.handle_plan(sort.input().as_ref())?),
+                sorts,
+                advanced_extension: Some(options.to_advanced_extension()),
+            }))),
+        });
+
+        // A sort with a fetch (top-k) is a FetchRel directly over the SortRel
+        match sort.fetch() {
+            Some(fetch) => {
+                let empty_schema = Arc::new(DFSchema::empty());
+                let count = to_substrait_rex(self, &lit(fetch as i64), &empty_schema)?;
+                Ok(Box::new(Rel {
+                    rel_type: Some(RelType::Fetch(Box::new(FetchRel {
+                        common: None,
+                        input: Some(sort_rel),
+                        offset_mode: None,
+                        count_mode: Some(CountMode::CountExpr(Box::new(count))),
+                        advanced_extension: None,
+                    }))),
+                }))
+            }
+            None => Ok(sort_rel),
+        }
+    }
+
+    fn handle_aggregate(&mut self, aggregate: &AggregateExec) -> Result<Box<Rel>> {
+        if aggregate.group_expr().has_grouping_set() {
+            return not_impl_err!(
+                "Grouping sets are not supported in Substrait physical plans"
+            );
+        }
+        if aggregate.limit_options().is_some() {
+            return not_impl_err!(
+                "Aggregations with a limit are not supported in Substrait physical plans"
+            );
+        }
+
+        let mode = aggregate.mode();
+        let phase = match mode {
+            AggregateMode::Partial => AggregationPhase::InitialToIntermediate,
+            AggregateMode::PartialReduce => AggregationPhase::IntermediateToIntermediate,
+            AggregateMode::Final | AggregateMode::FinalPartitioned => {
+                AggregationPhase::IntermediateToResult
+            }
+            AggregateMode::Single | AggregateMode::SinglePartitioned => {
+                AggregationPhase::InitialToResult
+            }
+        };
+
+        // The group expressions refer to the input, while the arguments of the
+        // aggregate functions refer to the input of the first phase
+        let input_df_schema =
+            Arc::new(positional_df_schema(&aggregate.input().schema())?);
+        let grouping_expressions = aggregate
+            .group_expr()
+            .expr()
+            .iter()
+            .map(|(expr, _)| self.handle_rex_with_df_schema(expr, &input_df_schema))
+            .collect::<Result<Vec<_>>>()?;
+        let groupings = if grouping_expressions.is_empty() {
+            vec![]
+        } else {
+            vec![Grouping {
+                #[expect(deprecated)]
+                grouping_expressions: vec![],
+                expression_references: (0..grouping_expressions.len() as u32).collect(),
+            }]
+        };
+
+        let aggregate_input_schema = aggregate.input_schema();
+        let aggregate_df_schema =
+            Arc::new(positional_df_schema(&aggregate_input_schema)?);
+        let measures = aggregate
+            .aggr_expr()
+            .iter()
+            .zip(aggregate.filter_expr())
+            .map(|(aggr_expr, filter)| {
+                let mut measure = self.handle_measure(
+                    aggr_expr,
+                    filter.as_ref(),
+                    &aggregate_df_schema,
+                )?;
+                if let Some(function) = measure.measure.as_mut() {
+                    function.phase = phase as i32;
+                }
+                Ok(measure)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // The names of the groups and aggregates, which differ from the output
+        // field names of the intermediate state of a partial aggregation
+        let output_names = aggregate
+            .group_expr()
+            .expr()
+            .iter()
+            .map(|(_, name)| name.clone())
+            .chain(aggregate.aggr_expr().iter().map(|e| e.name().to_string()))
+            .collect();
+        let options = PhysicalOptions {
+            aggregate_mode: Some(format!("{mode:?}")),
+            aggregate_input_schema: Some(to_substrait_named_struct(
+                self,
+                &aggregate_df_schema,
+            )?),
+            ..Default::default()
+        };
+
+        Ok(Box::new(Rel {
+            rel_type: Some(RelType::Aggregate(Box::new(AggregateRel {
+                common: Some(rel_common(None, output_names)),
+                input: Some(self.handle_plan(aggregate.input().as_ref())?),
+                groupings,
+                measures,
+                grouping_expressions,
+                advanced_extension: Some(options.to_advanced_extension()),
+            }))),
+        }))
+    }
+
+    fn handle_repartition(&mut self, repartition: &RepartitionExec) -> Result<Box<Rel>> {
+        let (exchange_kind, partition_count) = match repartition.partitioning() {
+            Partitioning::Hash(exprs, partition_count) => {
+                let fields = exprs
+                    .iter()
+                    .map(|expr| match expr.downcast_ref::<Column>() {
+                        Some(column) => Ok(field_reference(column.index())),
+                        None => not_impl_err!(
+                            "Hash repartitioning on expressions is not supported in Substrait physical plans"
+                        ),
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                (
+                    ExchangeKind::ScatterByFields(ScatterFields { fields }),
+                    *partition_count,
+                )
+            }
+            Partitioning::RoundRobinBatch(partition_count) => (
+                ExchangeKind::RoundRobin(RoundRobin { exact: false }),
+                *partition_count,
+            ),
+            partitioning => {
+                return not_impl_err!(
+                    "Unsupported partitioning in Substrait physical plan producer: {partitioning}"
+                );
+            }
+        };
+        let options = PhysicalOptions {
+            preserve_order: repartition.preserve_order(),
+            ..Default::default()
+        };
+
+        Ok(Box::new(Rel {
+            rel_type: Some(RelType::Exchange(Box::new(ExchangeRel {
+                common: None,
+                input: Some(self.handle_plan(repartition.input().as_ref())?),
+                partition_count: partition_count as i32,
+                targets: vec![],
+                advanced_extension: Some(options.to_advanced_extension()),
+                exchange_kind: Some(exchange_kind),
+            }))),
+        }))
+    }
+
+    fn handle_coalesce_partitions(
+        &mut self,
+        coalesce: &CoalescePartitionsExec,
+    ) -> Result<Box<Rel>> {
+        if coalesce.fetch().is_some() {
+            return not_impl_err!(
+                "Coalescing partitions with a fetch is not supported in Substrait physical plans"
+            );
+        }
+        // All rows are sent to the single output partition 0
+        let target = self.handle_rex(
+            &(Arc::new(Literal::new(0_i32.into())) as Arc<dyn PhysicalExpr>),
+            &coalesce.input().schema(),
+        )?;
+
+        Ok(Box::new(Rel {
+            rel_type: Some(RelType::Exchange(Box::new(ExchangeRel {
+                common: None,
+                input: Some(self.handle_plan(coalesce.input().as_ref())?),
+                partition_count: 1,
+                targets: vec![],
+                advanced_extension: None,
+                exchange_kind: Some(ExchangeKind::SingleTarget(Box::new(
+                    SingleBucketExpression {
+                        expression: Some(Box::new(target)),
+                    },
+                ))),
+            }))),
+        }))
+    }
+
+    fn handle_union(&mut self, union: &UnionExec) -> Result<Box<Rel>> {
+        let inputs = union
+            .inputs()
+            .iter()
+            .map(|input| self.handle_plan(input.as_ref()).map(|rel| *rel))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Box::new(Rel {
+            rel_type: Some(RelType::Set(SetRel {
+                common: None,
+                inputs,
+                op: SetOp::UnionAll as i32,
+                advanced_extension: None,
+            })),
+        }))
+    }
+
+    fn handle_measure(
+        &mut self,
+        aggr_expr: &AggregateFunctionExpr,
+        filter: Option<&Arc<dyn PhysicalExpr>>,
+        df_schema: &DFSchemaRef,
+    ) -> Result<Measure> {
+        if aggr_expr.is_reversed() {
+            return not_impl_err!(
+                "Reversed aggregate functions are not supported in Substrait physical plans"
+            );
+        }
+        let args = aggr_expr
+            .expressions()
+            .iter()
+            .map(to_logical_expr)
+            .collect::<Result<Vec<_>>>()?;
+        let order_by = aggr_expr
+            .order_bys()
+            .iter()
+            .map(to_logical_sort_expr)
+            .collect::<Result<Vec<_>>>()?;
+        let filter = filter.map(to_logical_expr).transpose()?.map(Box::new);
+        let aggregate_function = expr::AggregateFunction {
+            func: Arc::new(aggr_expr.fun().clone()),
+            params: AggregateFunctionParams {
+                args,
+                distinct: aggr_expr.is_distinct(),
+                filter,
+                order_by,
+                null_treatment: None,
+            },
+        };
+        from_aggregate_function(self, &aggregate_function, df_schema)
+    }
+
+    fn handle_rex(
+        &mut self,
+        expr: &Arc<dyn PhysicalExpr>,
+        schema: &SchemaRef,
+    ) -> Result<Expression> {
+        let df_schema = Arc::new(positional_df_schema(schema)?);
+        self.handle_rex_with_df_schema(expr, &df_schema)
+    }
+
+    fn handle_rex_with_df_schema(
+        &mut self,
+        expr: &Arc<dyn PhysicalExpr>,
+        df_schema: &DFSchemaRef,
+    ) -> Result<Expression> {
+        let expr = to_logical_expr(expr)?;
+        to_substrait_rex(self, &expr, df_schema)
     }
-    Err(DataFusionError::Substrait(format!(
-        "Unsupported plan in Substrait physical plan producer: {}",
-        displayable(plan).one_line()
-    )))
 }
 
-// see https://github.com/duckdb/substrait/blob/b6f56643cb11d52de0e32c24a01dfd5947df62be/src/to_substrait.cpp#L954-L1094.
-fn to_substrait_type(data_type: &DataType, nullable: bool) -> Result<Type> {
-    let nullability = if nullable {
-        Nullability::Nullable.into()
+/// Converts a [`PhysicalExpr`] to the logical [`Expr`] it was planned from,
+/// with columns referring to a schema created by [`positional_df_schema`]
+fn to_logical_expr(expr: &Arc<dyn PhysicalExpr>) -> Result<Expr> {
+    let boxed = |expr| to_logical_expr(expr).map(Box::new);
+
+    if let Some(column) = expr.downcast_ref::<Column>() {
+        Ok(Expr::Column(positional_column(
+            column.index(),
+            column.name(),
+        )))
+    } else if let Some(literal) = expr.downcast_ref::<Literal>() {
+        Ok(Expr::Literal(literal.value().clone(), None))
+    } else if let Some(binary) = expr.downcast_ref::<BinaryExpr>() {
+        Ok(Expr::BinaryExpr(expr::BinaryExpr::new(
+            boxed(binary.left())?,
+            *binary.op(),
+            boxed(binary.right())?,
+        )))
+    } else if let Some(function) = expr.downcast_ref::<ScalarFunctionExpr>() {
+        let args = function
+            .args()
+            .iter()
+            .map(to_logical_expr)
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Expr::ScalarFunction(expr::ScalarFunction::new_udf(
+            Arc::new(function.fun().clone()),
+            args,
+        )))
+    } else if let Some(cast) = expr.downcast_ref::<CastExpr>() {
+        Ok(Expr::Cast(Cast::new(
+            boxed(cast.expr())?,
+            cast.cast_type().clone(),
+        )))
+    } else if let Some(cast) = expr.downcast_ref::<TryCastExpr>() {
+        Ok(Expr::TryCast(TryCast::new(
+            boxed(cast.expr())?,
+            cast.cast_type().clone(),
+        )))
+    } else if let Some(is_null) = expr.downcast_ref::<IsNullExpr>() {
+        Ok(Expr::IsNull(boxed(is_null.arg())?))
+    } else if let Some(is_not_null) = expr.downcast_ref::<IsNotNullExpr>() {
+        Ok(Expr::IsNotNull(boxed(is_not_null.arg())?))
+    } else if let Some(not) = expr.downcast_ref::<NotExpr>() {
+        Ok(Expr::Not(boxed(not.arg())?))
+    } else if let Some(negative) = expr.downcast_ref::<NegativeExpr>() {
+        Ok(Expr::Negative(boxed(negative.arg())?))
+    } else if let Some(like) = expr.downcast_ref::<LikeExpr>() {
+        Ok(Expr::Like(Like::new(
+            like.negated(),
+            boxed(like.expr())?,
+            boxed(like.pattern())?,
+            None,
+            like.case_insensitive(),
+        )))
+    } else if let Some(in_list) = expr.downcast_ref::<InListExpr>() {
+        let list = in_list
+            .list()
+            .iter()
+            .map(to_logical_expr)
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Expr::InList(expr::InList::new(
+            boxed(in_list.expr())?,
+            list,
+            in_list.negated(),
+        )))
+    } else if let Some(case) = expr.downcast_ref::<CaseExpr>() {
+        let when_then_expr = case
+            .when_then_expr()
+            .iter()
+            .map(|(when, then)| Ok((boxed(when)?, boxed(then)?)))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Expr::Case(expr::Case::new(
+            case.expr().map(boxed).transpose()?,
+            when_then_expr,
+            case.else_expr().map(boxed).transpose()?,
+        )))
     } else {
-        Nullability::Required.into()
-    };
-
-    match data_type {
-        DataType::Boolean => Ok(Type {
-            kind: Some(Kind::Bool(Boolean {
-                type_variation_reference: 0,
-                nullability,
-            })),
-        }),
-        DataType::Int64 => Ok(Type {
-            kind: Some(Kind::I64(I64 {
-                type_variation_reference: 0,
-                nullability,
-            })),
-        }),
-        DataType::Float64 => Ok(Type {
-            kind: Some(Kind::Fp64(Fp64 {
-                type_variation_reference: 0,
-                nullability,
-            })),
-        }),
-        DataType::Utf8 => Ok(Type {
-            kind: Some(Kind::String(SubstraitString {
-                type_variation_reference: DEFAULT_CONTAINER_TYPE_VARIATION_REF,
-                nullability,
-            })),
-        }),
-        DataType::LargeUtf8 => Ok(Type {
-            kind: Some(Kind::String(SubstraitString {
-                type_variation_reference: LARGE_CONTAINER_TYPE_VARIATION_REF,
-                nullability,
-            })),
-        }),
-        DataType::Utf8View => Ok(Type {
-            kind: Some(Kind::String(SubstraitString {
-                type_variation_reference: VIEW_CONTAINER_TYPE_VARIATION_REF,
-                nullability,
-            })),
-        }),
-        DataType::Binary => Ok(Type {
-            kind: Some(Kind::Binary(Binary {
-                type_variation_reference: DEFAULT_CONTAINER_TYPE_VARIATION_REF,
-                nullability,
-            })),
-        }),
-        DataType::LargeBinary => Ok(Type {
-            kind: Some(Kind::Binary(Binary {
-                type_variation_reference: LARGE_CONTAINER_TYPE_VARIATION_REF,
-                nullability,
-            })),
-        }),
-        DataType::BinaryView => Ok(Type {
-            kind: Some(Kind::Binary(Binary {
-                type_variation_reference: VIEW_CONTAINER_TYPE_VARIATION_REF,
-                nullability,
-            })),
+        not_impl_err!(
+            "Unsupported expression in Substrait physical plan producer: {expr}"
+        )
+    }
+}
+
+fn to_logical_sort_expr(sort_expr: &PhysicalSortExpr) -> Result<SortExpr> {
+    Ok(SortExpr::new(
+        to_logical_expr(&sort_expr.expr)?,
+        !sort_expr.options.descending,
+        sort_expr.options.nulls_first,
+    ))
+}
+
+fn to_substrait_hash_join_type(join_type: &JoinType) -> Result<hash_join_rel::JoinType> {
+    Ok(match join_type {
+        JoinType::Inner => hash_join_rel::JoinType::Inner,
+        JoinType::Left => hash_join_rel::JoinType::Left,
+        JoinType::Right => hash_join_rel::JoinType::Right,
+        JoinType::Full => hash_join_rel::JoinType::Outer,
+        JoinType::LeftSemi => hash_join_rel::JoinType::LeftSemi,
+        JoinType::RightSemi => hash_join_rel::JoinType::RightSemi,
+        JoinType::LeftAnti => hash_join_rel::JoinType::LeftAnti,
+        JoinType::RightAnti => hash_join_rel::JoinType::RightAnti,
+        JoinType::LeftMark => hash_join_rel::JoinType::LeftMark,
+        JoinType::RightMark => hash_join_rel::JoinType::RightMark,
+        JoinType::LeftAsOf => {
+            return not_impl_err!(
+                "ASOF joins are not supported in Substrait physical plans"
+            );
+        }
+    })
+}
+
+fn rel_common(output_mapping: Option<Vec<i32>>, output_names: Vec<String>) -> RelCommon {
+    RelCommon {
+        hint: (!output_names.is_empty()).then(|| Hint {
+            output_names,
+            ..Default::default()
         }),
-        _ => Err(DataFusionError::Substrait(format!(
-            "Logical type {data_type} not implemented as substrait type"
-        ))),
+        advanced_extension: None,
+        emit_kind: output_mapping
+            .map(|output_mapping| EmitKind::Emit(Emit { output_mapping })),
+    }
+}
+
+fn field_reference(index: usize) -> FieldReference {
+    FieldReference {
+        reference_type: Some(ReferenceType::DirectReference(ReferenceSegment {
+            reference_type: Some(reference_segment::ReferenceType::StructField(
+                Box::new(reference_segment::StructField {
+                    field: index as i32,
+                    child: None,
+                }),
+            )),
+        })),
+        root_type: Some(RootType::RootReference(RootReference {})),
     }
 }
@@ -18,16 +18,22 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use datafusion::arrow::datatypes::Schema;
+use datafusion::arrow::datatypes::{DataType, Field, Schema};
 use datafusion::dataframe::DataFrame;
 use datafusion::datasource::listing::PartitionedFile;
 use datafusion::datasource::object_store::ObjectStoreUrl;
 use datafusion::datasource::physical_plan::{
-    FileGroup, FileScanConfigBuilder, ParquetSource,
+    FileGroup, FileScanConfigBuilder, JsonSource, ParquetSource,
 };
 use datafusion::error::Result;
-use datafusion::physical_plan::{ExecutionPlan, displayable};
-use datafusion::prelude::{ParquetReadOptions, SessionContext};
+use datafusion::physical_expr::expressions::Column;
+use datafusion::physical_plan::coalesce_partitions::CoalescePartitionsExec;
+use datafusion::physical_plan::repartition::RepartitionExec;
+use datafusion::physical_plan::union::UnionExec;
+use datafusion::physical_plan::{ExecutionPlan, Partitioning, displayable};
+use datafusion::prelude::{
+    CsvReadOptions, ParquetReadOptions, SessionConfig, SessionContext,
+};
 use datafusion_substrait::physical_plan::{consumer, producer};
 
 use datafusion::datasource::memory::DataSourceExec;
@@ -100,6 +106,136 @@ async fn wildcard_select_alltypes() -> Result<()> {
     roundtrip_alltypes("SELECT * FROM alltypes_plain").await
 }
 
+#[tokio::test]
+async fn json_exec() -> Result<()> {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("a", DataType::Int64, false),
+        Field::new("b", DataType::Utf8, true),
+    ]));
+    let source = Arc::new(JsonSource::new(schema).with_newline_delimited(false));
+
+    let scan_config =
+        FileScanConfigBuilder::new(ObjectStoreUrl::local_filesystem(), source)
+            .with_file_groups(vec![FileGroup::new(vec![PartitionedFile::new(
+                "file://foo/part-0.json".to_string(),
+                123,
+            )])])
+            .with_projection_indices(Some(vec![1, 0]))?
+            .build();
+    let json_exec: Arc<dyn ExecutionPlan> = DataSourceExec::from_data_source(scan_config);
+
+    roundtrip_plan(json_exec, &SessionContext::new()).await
+}
+
+#[tokio::test]
+async fn repartition_and_union_exec() -> Result<()> {
+    let ctx = create_parquet_context().await?;
+    let scan = ctx
+        .sql("SELECT a, b FROM data")
+        .await?
+        .create_physical_plan()
+        .await?;
+
+    let hash = RepartitionExec::try_new(
+        Arc::clone(&scan),
+        Partitioning::Hash(vec![Arc::new(Column::new("b", 1))], 3),
+    )?;
+    let round_robin =
+        RepartitionExec::try_new(Arc::clone(&scan), Partitioning::RoundRobinBatch(3))?;
+    let union = UnionExec::try_new(vec![Arc::new(hash), Arc::new(round_robin)])?;
+    let plan = Arc::new(CoalescePartitionsExec::new(union));
+
+    roundtrip_plan(plan, &ctx).await
+}
+
+#[tokio::test]
+async fn filter() -> Result<()> {
+    roundtrip("SELECT a, b FROM data WHERE a > 1 AND f LIKE 'a%' AND b IS NOT NULL").await
+}
+
+#[tokio::test]
+async fn projection_with_expressions() -> Result<()> {
+    roundtrip(
+        "SELECT a + 1 AS a1, -b, CAST(a AS VARCHAR), \
+         CASE WHEN a > 1 THEN 'x' ELSE 'y' END AS c, a IN (1, 3), NOT d, upper(f) \
+         FROM data",
+    )
+    .await
+}
+
+#[tokio::test]
+async fn sort() -> Result<()> {
+    roundtrip("SELECT a, b FROM data ORDER BY a DESC NULLS LAST, b").await
+}
+
+#[tokio::test]
+async fn sort_with_fetch() -> Result<()> {
+    roundtrip("SELECT a, b FROM data ORDER BY b LIMIT 2").await
+}
+
+#[tokio::test]
+async fn aggregate() -> Result<()> {
+    roundtrip("SELECT a, count(b), sum(b) FILTER (WHERE b > 1) FROM data GROUP BY a")
+        .await
+}
+
+#[tokio::test]
+async fn aggregate_without_group_by() -> Result<()> {
+    roundtrip("SELECT count(DISTINCT a), min(b) FROM data").await
+}
+
+#[tokio::test]
+async fn hash_join() -> Result<()> {
+    roundtrip(
+        "SELECT d1.a, d2.b FROM data d1 JOIN data d2 ON d1.a = d2.a AND d1.b > d2.b",
+    )
+    .await
+}
+
+#[tokio::test]
+async fn left_anti_join() -> Result<()> {
+    roundtrip("SELECT a FROM data WHERE a NOT IN (SELECT a FROM data WHERE b > 2)").await
+}
+
+#[tokio::test]
+async fn union_all() -> Result<()> {
+    roundtrip("SELECT a FROM data UNION ALL SELECT a + 1 FROM data").await
+}
+
+#[tokio::test]
+async fn csv_exec() -> Result<()> {
+    let ctx = create_parquet_context().await?;
+    ctx.register_csv("data_csv", "tests/testdata/data.csv", CsvReadOptions::new())
+        .await?;
+    let plan = ctx
+        .sql("SELECT a, b, f FROM data_csv WHERE a > 1")
+        .await?
+        .create_physical_plan()
+        .await?;
+
+    roundtrip_plan(plan, &ctx).await
+}
+
+#[tokio::test]
+async fn unsupported_plan() -> Result<()> {
+    let ctx = create_parquet_context().await?;
+    let plan = ctx
+        .sql("SELECT a, row_number() OVER (ORDER BY b) FROM data")
+        .await?
+        .create_physical_plan()
+        .await?;
+
+    let mut extension_info = (vec![], HashMap::new());
+    let err = producer::to_substrait_rel(plan.as_ref(), &mut extension_info).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Unsupported plan in Substrait physical plan producer"),
+        "{err}"
+    );
+
+    Ok(())
+}
+
 async fn roundtrip(sql: &str) -> Result<()> {
     let ctx = create_parquet_context().await?;
     let df = ctx.sql(sql).await?;
@@ -120,17 +256,28 @@ async fn roundtrip_alltypes(sql: &str) -> Result<()> {
 
 async fn roundtrip_parquet(df: DataFrame) -> Result<()> {
     let physical_plan = df.create_physical_plan().await?;
+    let ctx = create_parquet_context().await?;
+
+    roundtrip_plan(physical_plan, &ctx).await
+}
 
+async fn roundtrip_plan(
+    physical_plan: Arc<dyn ExecutionPlan>,
+    ctx: &SessionContext,
+) -> Result<()> {
     // Convert the plan into a substrait (protobuf) Rel
     let mut extension_info = (vec![], HashMap::new());
     let substrait_plan =
         producer::to_substrait_rel(physical_plan.as_ref(), &mut extension_info)?;
 
     // Convert the substrait Rel back into a physical plan
-    let ctx = create_parquet_context().await?;
+    let extensions = extension_info
+        .1
+        .iter()
+        .map(|(name, anchor)| (*anchor, name))
+        .collect();
     let physical_plan_roundtrip =
-        consumer::from_substrait_rel(&ctx, substrait_plan.as_ref(), &HashMap::new())
-            .await?;
+        consumer::from_substrait_rel(ctx, substrait_plan.as_ref(), &extensions).await?;
 
     // Compare the original and roundtrip physical plans
     let expected = format!("{}", displayable(physical_plan.as_ref()).indent(true));
@@ -144,7 +291,12 @@ async fn roundtrip_parquet(df: DataFrame) -> Result<()> {
 }
 
 async fn create_parquet_context() -> Result<SessionContext> {
-    let ctx = SessionContext::new();
+    // Use several partitions so that the plans contain repartitions. Dynamic
+    // filters are runtime state and are not part of the serialized plan.
+    let config = SessionConfig::new()
+        .with_target_partitions(4)
+        .set_bool("datafusion.optimizer.enable_dynamic_filter_pushdown", false);
+    let ctx = SessionContext::new_with_config(config);
     let explicit_options = ParquetReadOptions::default();
 
     ctx.register_parquet("data", "tests/testdata/data.parquet", explicit_options)